@@ -0,0 +1,518 @@
+// 结果分析模块
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::PathBuf;
+
+use crate::engine::FileHashes;
+use crate::hash::HashAlgorithm;
+use crate::i18n::{Text, t};
+use crate::ui::{FileItem, FileStatus};
+
+/// 发生碰撞的弱哈希算法
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CollisionKind {
+    Crc32,
+    Xxhash3,
+}
+
+impl CollisionKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            CollisionKind::Crc32 => "CRC32",
+            CollisionKind::Xxhash3 => "XXH3",
+        }
+    }
+}
+
+/// 一组弱哈希相同但强哈希不同的文件
+#[derive(Debug, Clone)]
+pub struct HashCollision {
+    pub kind: CollisionKind,
+    pub hash: String,
+    /// (路径, 最强摘要)
+    pub files: Vec<(PathBuf, String)>,
+}
+
+/// 查找 CRC32/XXH3 相同但强哈希（SHA256/SHA1/MD5）不同的文件组
+///
+/// xxhash3 同时是缓存校验键，真实的 xxhash3 碰撞意味着缓存可能返回错误结果。
+pub fn find_collisions(files: &[FileItem]) -> Vec<HashCollision> {
+    let completed: Vec<&FileItem> = files
+        .iter()
+        .filter(|f| matches!(f.status, FileStatus::Completed) && !f.strong_digest().is_empty())
+        .collect();
+
+    let mut collisions = Vec::new();
+    for kind in [CollisionKind::Crc32, CollisionKind::Xxhash3] {
+        let mut groups: BTreeMap<&str, Vec<&FileItem>> = BTreeMap::new();
+        for file in &completed {
+            let key = match kind {
+                CollisionKind::Crc32 => file.crc32.as_str(),
+                CollisionKind::Xxhash3 => file.xxhash3.as_str(),
+            };
+            if !key.is_empty() {
+                groups.entry(key).or_default().push(file);
+            }
+        }
+
+        for (hash, group) in groups {
+            let distinct_digests: HashSet<&str> = group.iter().map(|f| f.strong_digest()).collect();
+            if distinct_digests.len() > 1 {
+                collisions.push(HashCollision {
+                    kind,
+                    hash: hash.to_string(),
+                    files: group
+                        .iter()
+                        .map(|f| (f.path.clone(), f.strong_digest().to_string()))
+                        .collect(),
+                });
+            }
+        }
+    }
+
+    collisions
+}
+
+/// 文件名相同但内容不同的一组文件
+#[derive(Debug, Clone)]
+pub struct NameConflict {
+    pub name: String,
+    /// 各个版本：(最强摘要, 内容相同的文件)，文件多的版本在前
+    pub variants: Vec<(String, Vec<PathBuf>)>,
+}
+
+impl NameConflict {
+    pub fn file_count(&self) -> usize {
+        self.variants.iter().map(|(_, paths)| paths.len()).sum()
+    }
+}
+
+/// 查找文件名相同但内容不同的文件（如各目录中本应一致的 `config.json`），按文件名排序
+///
+/// 按 XXH3 区分内容（快速模式下也会计算），显示的摘要优先取强哈希。
+pub fn find_name_conflicts(files: &[FileItem]) -> Vec<NameConflict> {
+    let mut groups: BTreeMap<String, BTreeMap<&str, Vec<&FileItem>>> = BTreeMap::new();
+    for file in files {
+        if !matches!(file.status, FileStatus::Completed) || file.xxhash3.is_empty() {
+            continue;
+        }
+        let Some(name) = file.path.file_name() else {
+            continue;
+        };
+        groups
+            .entry(name.to_string_lossy().into_owned())
+            .or_default()
+            .entry(file.xxhash3.as_str())
+            .or_default()
+            .push(file);
+    }
+
+    groups
+        .into_iter()
+        .filter(|(_, variants)| variants.len() > 1)
+        .map(|(name, variants)| {
+            let mut variants: Vec<(String, Vec<PathBuf>)> = variants
+                .into_iter()
+                .map(|(xxhash3, group)| {
+                    let digest = match group[0].strong_digest() {
+                        "" => xxhash3,
+                        strong => strong,
+                    };
+                    let mut paths: Vec<PathBuf> = group.iter().map(|f| f.path.clone()).collect();
+                    paths.sort();
+                    (digest.to_string(), paths)
+                })
+                .collect();
+            variants.sort_by(|a, b| b.1.len().cmp(&a.1.len()).then_with(|| a.1.cmp(&b.1)));
+            NameConflict { name, variants }
+        })
+        .collect()
+}
+
+/// 两个文件的对比结果
+#[derive(Debug, Clone)]
+pub struct FileComparison {
+    pub paths: [PathBuf; 2],
+    pub sizes: [u64; 2],
+    /// 大小不同时一定不同，不计算哈希
+    pub hashes: Option<[FileHashes; 2]>,
+}
+
+impl FileComparison {
+    /// 两侧都有结果的算法（缓存命中时可能包含额外的算法）
+    pub fn common_algorithms(&self) -> Vec<HashAlgorithm> {
+        let Some([a, b]) = &self.hashes else {
+            return Vec::new();
+        };
+        HashAlgorithm::ALL
+            .into_iter()
+            .filter(|alg| !a.hash_value(*alg).is_empty() && !b.hash_value(*alg).is_empty())
+            .collect()
+    }
+
+    pub fn identical(&self) -> bool {
+        let Some([a, b]) = &self.hashes else {
+            return false;
+        };
+        let algorithms = self.common_algorithms();
+        self.sizes[0] == self.sizes[1]
+            && !algorithms.is_empty()
+            && algorithms
+                .iter()
+                .all(|alg| a.hash_value(*alg) == b.hash_value(*alg))
+    }
+}
+
+/// 缓存审计中需要关注的文件
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AuditFinding {
+    /// 大小或修改时间已变化，属于正常修改，缓存只是过期
+    Changed,
+    /// 元数据未变但内容哈希不同，可能是静默损坏（bit rot）
+    Corrupted { cached: String, actual: String },
+    /// 文件存在但无法读取
+    Unreadable(String),
+}
+
+/// 缓存审计报告
+#[derive(Debug, Clone)]
+pub struct AuditReport {
+    /// 文件仍存在、参与核对的条目数
+    pub checked: usize,
+    /// 文件已不存在的条目数
+    pub missing: usize,
+    pub findings: Vec<(PathBuf, AuditFinding)>,
+}
+
+impl AuditReport {
+    /// 可能损坏的文件
+    pub fn suspicious(&self) -> impl Iterator<Item = &(PathBuf, AuditFinding)> {
+        self.findings
+            .iter()
+            .filter(|(_, f)| matches!(f, AuditFinding::Corrupted { .. }))
+    }
+}
+
+/// 按扩展名分组的统计
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExtensionGroup {
+    /// 无扩展名的文件归为 None，显示时再取当前语言的名称
+    pub extension: Option<String>,
+    pub count: usize,
+    pub total_size: u64,
+}
+
+/// 按扩展名统计文件数量与总大小，按总大小降序排列
+pub fn summarize_by_extension(files: &[FileItem]) -> Vec<ExtensionGroup> {
+    let mut groups: HashMap<Option<String>, (usize, u64)> = HashMap::new();
+    for file in files {
+        let group = groups.entry(file.extension()).or_default();
+        group.0 += 1;
+        group.1 += file.size;
+    }
+
+    let mut summary: Vec<ExtensionGroup> = groups
+        .into_iter()
+        .map(|(extension, (count, total_size))| ExtensionGroup {
+            extension,
+            count,
+            total_size,
+        })
+        .collect();
+    summary.sort_by(|a, b| {
+        b.total_size
+            .cmp(&a.total_size)
+            .then_with(|| a.extension.cmp(&b.extension))
+    });
+    summary
+}
+
+/// 已完成的文件中直接采用缓存与重新计算的数量
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SourceSummary {
+    pub cached: usize,
+    pub computed: usize,
+}
+
+impl SourceSummary {
+    pub fn total(&self) -> usize {
+        self.cached + self.computed
+    }
+
+    /// 单个文件的来源由 `before` 变为 `after` 时增量更新，取值同 `SourceFilter::source`
+    pub fn replace(&mut self, before: Option<bool>, after: Option<bool>) {
+        if let Some(count) = self.count_mut(before) {
+            *count = count.saturating_sub(1);
+        }
+        if let Some(count) = self.count_mut(after) {
+            *count += 1;
+        }
+    }
+
+    fn count_mut(&mut self, source: Option<bool>) -> Option<&mut usize> {
+        match source {
+            Some(true) => Some(&mut self.cached),
+            Some(false) => Some(&mut self.computed),
+            None => None,
+        }
+    }
+}
+
+/// 统计已完成文件的结果来源
+pub fn summarize_sources(files: &[FileItem]) -> SourceSummary {
+    let mut summary = SourceSummary::default();
+    for file in files {
+        match SourceFilter::source(file) {
+            Some(true) => summary.cached += 1,
+            Some(false) => summary.computed += 1,
+            None => {}
+        }
+    }
+    summary
+}
+
+/// 按结果来源筛选表格
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SourceFilter {
+    #[default]
+    All,
+    /// 只看直接采用缓存的结果
+    Cached,
+    /// 只看重新读取计算的结果
+    Computed,
+}
+
+impl SourceFilter {
+    pub const ALL: [SourceFilter; 3] = [
+        SourceFilter::All,
+        SourceFilter::Cached,
+        SourceFilter::Computed,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SourceFilter::All => t(Text::SourceAll),
+            SourceFilter::Cached => t(Text::SourceCached),
+            SourceFilter::Computed => t(Text::SourceComputed),
+        }
+    }
+
+    pub fn matches(self, file: &FileItem) -> bool {
+        match self {
+            SourceFilter::All => true,
+            SourceFilter::Cached => Self::source(file) == Some(true),
+            SourceFilter::Computed => Self::source(file) == Some(false),
+        }
+    }
+
+    /// 已完成的文件是否来自缓存，尚未完成时为 None
+    pub fn source(file: &FileItem) -> Option<bool> {
+        matches!(file.status, FileStatus::Completed).then_some(file.from_cache)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn completed_item(path: &str, crc32: &str, sha1: &str, xxhash3: &str) -> FileItem {
+        FileItem::completed(
+            path,
+            1024,
+            &[
+                (HashAlgorithm::Crc32, crc32),
+                (HashAlgorithm::Md5, &"0".repeat(32)),
+                (HashAlgorithm::Sha1, sha1),
+                (HashAlgorithm::Xxh3, xxhash3),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_crc32_collision_detected() {
+        let files = vec![
+            completed_item("/a", "deadbeef", &"1".repeat(40), &"a".repeat(32)),
+            completed_item("/b", "deadbeef", &"2".repeat(40), &"b".repeat(32)),
+            completed_item("/c", "cafebabe", &"3".repeat(40), &"c".repeat(32)),
+        ];
+
+        let collisions = find_collisions(&files);
+        assert_eq!(collisions.len(), 1);
+        assert_eq!(collisions[0].kind, CollisionKind::Crc32);
+        assert_eq!(collisions[0].hash, "deadbeef");
+        assert_eq!(collisions[0].files.len(), 2);
+    }
+
+    #[test]
+    fn test_identical_content_is_not_collision() {
+        let files = vec![
+            completed_item("/a", "deadbeef", &"1".repeat(40), &"a".repeat(32)),
+            completed_item("/b", "deadbeef", &"1".repeat(40), &"a".repeat(32)),
+        ];
+
+        assert!(find_collisions(&files).is_empty());
+    }
+
+    #[test]
+    fn test_pending_items_ignored() {
+        let mut pending = completed_item("/b", "deadbeef", &"2".repeat(40), &"b".repeat(32));
+        pending.status = FileStatus::Pending;
+        let files = vec![
+            completed_item("/a", "deadbeef", &"1".repeat(40), &"a".repeat(32)),
+            pending,
+        ];
+
+        assert!(find_collisions(&files).is_empty());
+    }
+
+    #[test]
+    fn test_same_name_different_content() {
+        use crate::engine::compute_file_hash;
+        use crate::hash::EnabledHashes;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let mut files = Vec::new();
+        for (sub, content) in [
+            ("a", "{\"debug\": false}"),
+            ("b", "{\"debug\": true}"),
+            ("c", "{\"debug\": false}"),
+            ("d", "unique"),
+        ] {
+            let name = if sub == "d" {
+                "other.json"
+            } else {
+                "config.json"
+            };
+            let path = dir.path().join(sub).join(name);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(&path, content).unwrap();
+
+            let hashes = compute_file_hash(
+                &path,
+                None,
+                64 * 1024,
+                4 * 1024 * 1024,
+                None,
+                false,
+                EnabledHashes::default(),
+            )
+            .unwrap();
+            files.push(FileItem::completed(
+                path,
+                content.len() as u64,
+                &[
+                    (HashAlgorithm::Crc32, &hashes.crc32),
+                    (HashAlgorithm::Md5, &hashes.md5),
+                    (HashAlgorithm::Sha1, &hashes.sha1),
+                    (HashAlgorithm::Xxh3, &hashes.xxhash3),
+                ],
+            ));
+        }
+
+        let conflicts = find_name_conflicts(&files);
+        assert_eq!(conflicts.len(), 1);
+        let conflict = &conflicts[0];
+        assert_eq!(conflict.name, "config.json");
+        assert_eq!(conflict.file_count(), 3);
+        // 文件多的版本在前，摘要为 SHA1
+        assert_eq!(
+            conflict.variants[0].1,
+            [files[0].path.clone(), files[2].path.clone()]
+        );
+        assert_eq!(conflict.variants[0].0, files[0].sha1);
+        assert_eq!(conflict.variants[1].1, [files[1].path.clone()]);
+
+        // 内容一致的同名文件不报告
+        files.remove(1);
+        assert!(find_name_conflicts(&files).is_empty());
+    }
+
+    #[test]
+    fn test_summarize_sources() {
+        let mut files = Vec::new();
+        for (idx, (status, from_cache)) in [
+            (FileStatus::Completed, true),
+            (FileStatus::Completed, false),
+            (FileStatus::Completed, true),
+            (FileStatus::Completed, true),
+            (FileStatus::Failed, false),
+            (FileStatus::Pending, false),
+            // 重新计算前的旧结果不计入
+            (FileStatus::Computing, true),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            let mut item = FileItem::new(PathBuf::from(format!("/f{idx}")), 10);
+            item.status = status;
+            item.from_cache = from_cache;
+            files.push(item);
+        }
+
+        let summary = summarize_sources(&files);
+        assert_eq!(
+            summary,
+            SourceSummary {
+                cached: 3,
+                computed: 1
+            }
+        );
+        assert_eq!(summary.total(), 4);
+
+        // 增量更新与重新统计一致
+        let mut incremental = SourceSummary::default();
+        for file in &files {
+            incremental.replace(None, SourceFilter::source(file));
+        }
+        incremental.replace(SourceFilter::source(&files[0]), None);
+        incremental.replace(Some(false), Some(true));
+        assert_eq!(
+            incremental,
+            SourceSummary {
+                cached: 3,
+                computed: 0
+            }
+        );
+
+        let matching = |filter: SourceFilter| -> Vec<usize> {
+            (0..files.len())
+                .filter(|&idx| filter.matches(&files[idx]))
+                .collect()
+        };
+        assert_eq!(matching(SourceFilter::Cached), [0, 2, 3]);
+        assert_eq!(matching(SourceFilter::Computed), [1]);
+        assert_eq!(matching(SourceFilter::All).len(), files.len());
+    }
+
+    #[test]
+    fn test_summarize_by_extension() {
+        let files = vec![
+            FileItem::new(PathBuf::from("/a/disk1.ISO"), 4000),
+            FileItem::new(PathBuf::from("/a/disk2.iso"), 6000),
+            FileItem::new(PathBuf::from("/a/notes.txt"), 10),
+            FileItem::new(PathBuf::from("/a/Makefile"), 20),
+        ];
+
+        let summary = summarize_by_extension(&files);
+        assert_eq!(
+            summary,
+            vec![
+                ExtensionGroup {
+                    extension: Some("iso".to_string()),
+                    count: 2,
+                    total_size: 10000,
+                },
+                ExtensionGroup {
+                    extension: None,
+                    count: 1,
+                    total_size: 20,
+                },
+                ExtensionGroup {
+                    extension: Some("txt".to_string()),
+                    count: 1,
+                    total_size: 10,
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,373 @@
+// 性能测试：测量本机各哈希算法、各读取路径与各性能预设的吞吐量
+
+use std::fs::{self, File};
+use std::hint::black_box;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use md5::{Digest, Md5};
+use ring::digest::{SHA1_FOR_LEGACY_USE_ONLY, SHA256};
+
+use crate::cache::CacheConfig;
+use crate::engine::{
+    IoStrategy, PERFORMANCE_PRESETS, PerformancePreset, compute_file_hash, compute_file_hash_with,
+};
+use crate::error::{HashResult, IoErrorContext};
+use crate::hash::{CRC64, HashAlgorithm};
+
+/// 测试数据规模
+struct Plan {
+    /// 未选择文件时生成的临时文件大小
+    generated_size: u64,
+    /// 单独测量算法时使用的内存数据大小
+    sample_size: usize,
+    /// 小文件路径使用的文件数量与单个大小
+    tiny_files: usize,
+    tiny_file_size: usize,
+}
+
+const DEFAULT_PLAN: Plan = Plan {
+    generated_size: 2 * 1024 * 1024 * 1024,
+    sample_size: 256 * 1024 * 1024,
+    tiny_files: 512,
+    tiny_file_size: 48 * 1024,
+};
+
+/// 生成的临时文件大小，供界面提示
+pub const GENERATED_SIZE: u64 = DEFAULT_PLAN.generated_size;
+
+/// 性能测试结果，吞吐量单位为 MB/s
+#[cfg_attr(test, derive(Debug))]
+pub struct BenchmarkReport {
+    /// 用户选择的测试文件，None 表示使用生成的临时文件
+    pub source: Option<PathBuf>,
+    pub file_size: u64,
+    pub algorithms: Vec<(HashAlgorithm, f64)>,
+    pub strategies: Vec<(IoStrategy, f64)>,
+    pub presets: Vec<(&'static PerformancePreset, f64)>,
+}
+
+impl BenchmarkReport {
+    /// 整体吞吐量最高的预设
+    pub fn fastest_preset(&self) -> Option<&'static PerformancePreset> {
+        self.presets
+            .iter()
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(preset, _)| *preset)
+    }
+}
+
+/// 运行性能测试；`source` 为 None 时生成临时文件，结束后自动删除
+pub fn run(source: Option<PathBuf>, config: &CacheConfig) -> HashResult<BenchmarkReport> {
+    run_with_plan(source, config, &DEFAULT_PLAN, &std::env::temp_dir())
+}
+
+/// 生成的文件放在 `scratch_parent` 下的临时目录中，离开作用域时连同目录一起删除
+fn run_with_plan(
+    source: Option<PathBuf>,
+    config: &CacheConfig,
+    plan: &Plan,
+    scratch_parent: &Path,
+) -> HashResult<BenchmarkReport> {
+    let scratch = ScratchDir::create_in(scratch_parent)?;
+    let enabled = config.enabled_hashes;
+
+    let file = match &source {
+        Some(path) => path.clone(),
+        None => {
+            let path = scratch.path().join("benchmark.bin");
+            write_test_data(&path, plan.generated_size)?;
+            path
+        }
+    };
+    let file_size = fs::metadata(&file).with_path(&file)?.len();
+
+    // 算法本身的速度与磁盘无关，直接在内存中测量
+    let mut sample = vec![0u8; plan.sample_size];
+    fill_test_data(&mut sample, 0);
+    let algorithms = HashAlgorithm::ALL
+        .into_iter()
+        .map(|algorithm| {
+            let elapsed = timed(|| {
+                digest_one(algorithm, &sample);
+                Ok(())
+            })?;
+            Ok((algorithm, throughput(sample.len() as u64, elapsed)))
+        })
+        .collect::<HashResult<Vec<_>>>()?;
+    drop(sample);
+
+    let mut strategies = Vec::with_capacity(IoStrategy::ALL.len());
+    for strategy in IoStrategy::ALL {
+        let targets: Vec<(PathBuf, u64)> = if strategy == IoStrategy::Tiny {
+            // 整体读取只适用于小文件，单独生成一批
+            write_tiny_files(scratch.path(), plan.tiny_files, plan.tiny_file_size)?
+                .into_iter()
+                .map(|path| (path, plan.tiny_file_size as u64))
+                .collect()
+        } else {
+            vec![(file.clone(), file_size)]
+        };
+        for (path, _) in &targets {
+            drop_cached(path);
+        }
+        let elapsed = timed(|| {
+            for (path, size) in &targets {
+                compute_file_hash_with(
+                    path,
+                    *size,
+                    strategy,
+                    None,
+                    config.buffer_size,
+                    config.mmap_chunk_size,
+                    enabled,
+                )?;
+            }
+            Ok(())
+        })?;
+        let bytes = targets.iter().map(|(_, size)| size).sum();
+        strategies.push((strategy, throughput(bytes, elapsed)));
+    }
+
+    // 预设按引擎实际会选择的路径测量
+    let presets = PERFORMANCE_PRESETS
+        .iter()
+        .map(|preset| {
+            drop_cached(&file);
+            let elapsed = timed(|| {
+                compute_file_hash(
+                    &file,
+                    None,
+                    preset.buffer_size,
+                    preset.mmap_chunk_size,
+                    Some(file_size),
+                    false,
+                    enabled,
+                )
+                .map(drop)
+            })?;
+            Ok((preset, throughput(file_size, elapsed)))
+        })
+        .collect::<HashResult<Vec<_>>>()?;
+
+    Ok(BenchmarkReport {
+        source,
+        file_size,
+        algorithms,
+        strategies,
+        presets,
+    })
+}
+
+/// 单独计算一种算法，不附带 `FileHasher` 始终计算的 CRC32 与 XXH3
+fn digest_one(algorithm: HashAlgorithm, data: &[u8]) {
+    match algorithm {
+        HashAlgorithm::Crc32 => {
+            black_box(crc32fast::hash(data));
+        }
+        HashAlgorithm::Crc64 => {
+            black_box(CRC64.checksum(data));
+        }
+        HashAlgorithm::Adler32 => {
+            black_box(adler2::adler32_slice(data));
+        }
+        HashAlgorithm::Md5 => {
+            black_box(Md5::digest(data));
+        }
+        HashAlgorithm::Sha1 => {
+            black_box(ring::digest::digest(&SHA1_FOR_LEGACY_USE_ONLY, data));
+        }
+        HashAlgorithm::Sha256 => {
+            black_box(ring::digest::digest(&SHA256, data));
+        }
+        HashAlgorithm::Xxh3 => {
+            black_box(xxhash_rust::xxh3::xxh3_128(data));
+        }
+    }
+}
+
+fn timed(op: impl FnOnce() -> HashResult<()>) -> HashResult<Duration> {
+    let start = Instant::now();
+    op()?;
+    Ok(start.elapsed())
+}
+
+fn throughput(bytes: u64, elapsed: Duration) -> f64 {
+    bytes as f64 / (1024.0 * 1024.0) / elapsed.as_secs_f64().max(1e-9)
+}
+
+/// 用 xorshift 填充伪随机数据，避免全零数据被文件系统或压缩层特殊处理
+fn fill_test_data(buf: &mut [u8], seed: u64) {
+    let mut state = seed ^ 0x9E37_79B9_7F4A_7C15;
+    for chunk in buf.chunks_mut(8) {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        chunk.copy_from_slice(&state.to_le_bytes()[..chunk.len()]);
+    }
+}
+
+fn write_test_data(path: &Path, size: u64) -> HashResult<()> {
+    const CHUNK: usize = 8 * 1024 * 1024;
+
+    let mut writer = BufWriter::new(File::create(path).with_path(path)?);
+    let mut buf = vec![0u8; CHUNK];
+    let mut written = 0u64;
+    while written < size {
+        let n = (size - written).min(CHUNK as u64) as usize;
+        fill_test_data(&mut buf[..n], written);
+        writer.write_all(&buf[..n]).with_path(path)?;
+        written += n as u64;
+    }
+    writer.flush().with_path(path)
+}
+
+fn write_tiny_files(dir: &Path, count: usize, size: usize) -> HashResult<Vec<PathBuf>> {
+    let mut buf = vec![0u8; size];
+    (0..count)
+        .map(|i| {
+            let path = dir.join(format!("tiny_{i}.bin"));
+            fill_test_data(&mut buf, i as u64);
+            fs::write(&path, &buf).with_path(&path)?;
+            Ok(path)
+        })
+        .collect()
+}
+
+/// 测试期间的临时目录，离开作用域时连同生成的文件一起删除
+struct ScratchDir {
+    path: PathBuf,
+}
+
+impl ScratchDir {
+    /// 名称带进程号与时间；已存在时换一个名称，不会与同时运行的其他测试共用目录
+    fn create_in(parent: &Path) -> HashResult<Self> {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.subsec_nanos());
+        let mut attempt = 0u32;
+        loop {
+            let path = parent.join(format!(
+                "turbohash-benchmark-{}-{}-{}",
+                std::process::id(),
+                nanos,
+                attempt
+            ));
+            match fs::create_dir(&path) {
+                Ok(()) => return Ok(Self { path }),
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists && attempt < 100 => {
+                    attempt += 1;
+                }
+                Err(e) => return Err(e).with_path(&path),
+            }
+        }
+    }
+
+    fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for ScratchDir {
+    fn drop(&mut self) {
+        if let Err(e) = fs::remove_dir_all(&self.path) {
+            log::warn!("删除性能测试临时文件失败: {} ({})", self.path.display(), e);
+        }
+    }
+}
+
+/// 让系统丢弃文件的缓存页，每次测量都从磁盘读取，而不是测量刚写入或上一轮读入的页缓存
+#[cfg(target_os = "linux")]
+fn drop_cached(path: &Path) {
+    use std::os::fd::AsRawFd;
+
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(e) => {
+            log::warn!("无法清除文件缓存: {} - {}", path.display(), e);
+            return;
+        }
+    };
+    // 刚生成的数据还是脏页，写回磁盘后才能丢弃
+    if let Err(e) = file.sync_all() {
+        log::warn!("无法清除文件缓存: {} - {}", path.display(), e);
+        return;
+    }
+    let ret = unsafe { libc::posix_fadvise(file.as_raw_fd(), 0, 0, libc::POSIX_FADV_DONTNEED) };
+    if ret != 0 {
+        log::warn!(
+            "无法清除文件缓存: {} - {}",
+            path.display(),
+            std::io::Error::from_raw_os_error(ret)
+        );
+    }
+}
+
+/// 没有其他句柄时，以无缓冲方式打开文件会使系统写回并丢弃其缓存页
+#[cfg(windows)]
+fn drop_cached(path: &Path) {
+    use std::os::windows::fs::OpenOptionsExt;
+    use windows_sys::Win32::Storage::FileSystem::FILE_FLAG_NO_BUFFERING;
+
+    if let Err(e) = File::options()
+        .read(true)
+        .custom_flags(FILE_FLAG_NO_BUFFERING)
+        .open(path)
+    {
+        log::warn!("无法清除文件缓存: {} - {}", path.display(), e);
+    }
+}
+
+/// 其他平台没有逐个文件清除缓存的方法，测量结果可能包含页缓存
+#[cfg(not(any(target_os = "linux", windows)))]
+fn drop_cached(_path: &Path) {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_benchmark_reports_all_paths_and_cleans_up() {
+        let plan = Plan {
+            generated_size: 3 * 1024 * 1024,
+            sample_size: 256 * 1024,
+            tiny_files: 4,
+            tiny_file_size: 16 * 1024,
+        };
+        let scratch_parent = tempfile::TempDir::new().unwrap();
+        let report =
+            run_with_plan(None, &CacheConfig::default(), &plan, scratch_parent.path()).unwrap();
+
+        assert!(report.source.is_none());
+        assert_eq!(report.file_size, plan.generated_size);
+        assert_eq!(report.algorithms.len(), HashAlgorithm::ALL.len());
+        assert_eq!(report.strategies.len(), IoStrategy::ALL.len());
+        assert_eq!(report.presets.len(), PERFORMANCE_PRESETS.len());
+        assert!(
+            report
+                .algorithms
+                .iter()
+                .map(|(_, v)| v)
+                .chain(report.strategies.iter().map(|(_, v)| v))
+                .chain(report.presets.iter().map(|(_, v)| v))
+                .all(|v| v.is_finite() && *v > 0.0)
+        );
+        assert!(report.fastest_preset().is_some());
+
+        // 临时目录已删除
+        assert_eq!(fs::read_dir(scratch_parent.path()).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn test_scratch_dirs_do_not_collide() {
+        let parent = tempfile::TempDir::new().unwrap();
+        let first = ScratchDir::create_in(parent.path()).unwrap();
+        let second = ScratchDir::create_in(parent.path()).unwrap();
+        assert_ne!(first.path(), second.path());
+
+        drop(first);
+        drop(second);
+        assert_eq!(fs::read_dir(parent.path()).unwrap().count(), 0);
+    }
+}
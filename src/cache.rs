@@ -11,34 +11,803 @@
 
 use r2d2::Pool;
 use r2d2_sqlite::SqliteConnectionManager;
-use rusqlite::{Connection, params};
+use rusqlite::types::ValueRef;
+use rusqlite::{Connection, OptionalExtension, params};
 use std::collections::HashMap;
+use std::fmt;
 use std::fs;
-use std::path::{Path, PathBuf};
+use std::path::{Component, Path, PathBuf};
 use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use dunce;
 
+use crate::columns::{ColumnSet, ColumnWidths, TableColumn};
+use crate::engine::{
+    FileHashes, PERFORMANCE_PRESETS, PerformancePreset, is_rotational_path, with_long_path,
+};
 use crate::error::{CacheOperation, HashError, HashResult, IntoCacheError, IoErrorContext};
+use crate::hash::{EnabledHashes, HashAlgorithm};
+use crate::i18n::{Language, Text, t};
 
 /// 当前缓存版本
-const CURRENT_CACHE_VERSION: u32 = 3;
+const CURRENT_CACHE_VERSION: u32 = 5;
+
+/// 连接等待其他连接释放锁的时长
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// busy_timeout 之后仍然锁定时，批量写入的最大尝试次数
+const SAVE_MAX_ATTEMPTS: u32 = 3;
+
+/// 重试的初始退避时间，每次翻倍
+const SAVE_RETRY_BACKOFF: Duration = Duration::from_millis(50);
+
+/// 缓存主表（v5）：可选算法列允许 NULL，兼容禁用 SHA1 等算法后的新条目
+const HASH_CACHE_TABLE_SQL: &str = "CREATE TABLE IF NOT EXISTS hash_cache (
+    path TEXT NOT NULL PRIMARY KEY,
+    file_size INTEGER NOT NULL CHECK(file_size > 0),
+    modified_time INTEGER NOT NULL CHECK(modified_time >= 0),
+    cached_at INTEGER NOT NULL CHECK(cached_at > 0),
+    xxhash3 TEXT NOT NULL CHECK(length(xxhash3) = 32),
+    crc32 TEXT NOT NULL CHECK(length(crc32) = 8),
+    md5 TEXT CHECK(md5 IS NULL OR length(md5) = 32),
+    sha1 TEXT CHECK(sha1 IS NULL OR length(sha1) = 40),
+    sha256 TEXT CHECK(sha256 IS NULL OR length(sha256) = 64),
+    crc64 TEXT CHECK(crc64 IS NULL OR length(crc64) = 16),
+    adler32 TEXT CHECK(adler32 IS NULL OR length(adler32) = 8),
+    CHECK(xxhash3 GLOB '[0-9a-fA-F][0-9a-fA-F]*'),
+    CHECK(crc32 GLOB '[0-9a-fA-F][0-9a-fA-F]*'),
+    CHECK(md5 IS NULL OR md5 GLOB '[0-9a-fA-F][0-9a-fA-F]*'),
+    CHECK(sha1 IS NULL OR sha1 GLOB '[0-9a-fA-F][0-9a-fA-F]*'),
+    CHECK(sha256 IS NULL OR sha256 GLOB '[0-9a-fA-F][0-9a-fA-F]*'),
+    CHECK(crc64 IS NULL OR crc64 GLOB '[0-9a-fA-F][0-9a-fA-F]*'),
+    CHECK(adler32 IS NULL OR adler32 GLOB '[0-9a-fA-F][0-9a-fA-F]*')
+) WITHOUT ROWID";
 
 /// VACUUM 阈值配置
 const VACUUM_SIZE_THRESHOLD: f64 = 0.3; // 30% free space
+/// 退出时按计划整理的空闲页比例阈值
+const SCHEDULED_VACUUM_THRESHOLD: f64 = 0.1;
+/// 「每周一次」的整理间隔
+const WEEKLY_VACUUM_SECS: u64 = 7 * 86400;
+
+/// 分批清理时每批删除的条目数
+const CLEANUP_CHUNK_SIZE: usize = 10_000;
+
+/// 缓存清理的范围
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CleanupKind {
+    /// 超过保留天数的条目
+    Expired,
+    /// 全部条目
+    All,
+}
+
+/// 分批清理的进度
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CleanupProgress {
+    Deleting { deleted: usize, total: usize },
+    Vacuuming,
+}
+
+/// 分批清理的结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CleanupOutcome {
+    pub kind: CleanupKind,
+    pub deleted: usize,
+    /// 用户中途取消，已删除的条目不会恢复
+    pub cancelled: bool,
+}
+
+/// 计算队列的处理顺序
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ComputeOrder {
+    /// 按添加顺序（由线程池自由分配，不保证严格有序）
+    Added,
+    /// 小文件优先，大量小文件能迅速完成
+    SmallestFirst,
+    /// 大文件优先，减少批次末尾只剩一个大文件在计算的情况
+    LargestFirst,
+}
+
+impl ComputeOrder {
+    pub const ALL: [ComputeOrder; 3] = [
+        ComputeOrder::Added,
+        ComputeOrder::SmallestFirst,
+        ComputeOrder::LargestFirst,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ComputeOrder::Added => t(Text::OrderAdded),
+            ComputeOrder::SmallestFirst => t(Text::OrderSmallestFirst),
+            ComputeOrder::LargestFirst => t(Text::OrderLargestFirst),
+        }
+    }
+
+    fn key(self) -> &'static str {
+        match self {
+            ComputeOrder::Added => "added",
+            ComputeOrder::SmallestFirst => "smallest",
+            ComputeOrder::LargestFirst => "largest",
+        }
+    }
+}
+
+impl fmt::Display for ComputeOrder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.key())
+    }
+}
+
+impl FromStr for ComputeOrder {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|order| order.key() == s)
+            .ok_or_else(|| format!("未知的计算顺序: {}", s))
+    }
+}
+
+/// 机械硬盘上的并发策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiskConcurrency {
+    /// 检测批次公共根目录所在的磁盘，机械硬盘限制并发
+    Auto,
+    /// 始终使用全部核心并发（NVMe/SSD）
+    Full,
+    /// 始终限制并发（检测不准确的机械硬盘阵列、USB 硬盘盒等）
+    Limited,
+}
+
+impl DiskConcurrency {
+    pub const ALL: [DiskConcurrency; 3] = [
+        DiskConcurrency::Auto,
+        DiskConcurrency::Full,
+        DiskConcurrency::Limited,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            DiskConcurrency::Auto => t(Text::DiskAuto),
+            DiskConcurrency::Full => t(Text::DiskFull),
+            DiskConcurrency::Limited => t(Text::DiskLimited),
+        }
+    }
+
+    fn key(self) -> &'static str {
+        match self {
+            DiskConcurrency::Auto => "auto",
+            DiskConcurrency::Full => "full",
+            DiskConcurrency::Limited => "limited",
+        }
+    }
+
+    /// `root` 下的文件是否应限制并发
+    pub fn limits(self, root: &Path) -> bool {
+        match self {
+            DiskConcurrency::Auto => is_rotational_path(root) == Some(true),
+            DiskConcurrency::Full => false,
+            DiskConcurrency::Limited => true,
+        }
+    }
+}
+
+impl fmt::Display for DiskConcurrency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.key())
+    }
+}
+
+impl FromStr for DiskConcurrency {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|mode| mode.key() == s)
+            .ok_or_else(|| format!("未知的磁盘并发策略: {}", s))
+    }
+}
+
+/// 命中缓存前对文件内容的校验程度
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheValidation {
+    /// 只比较大小与修改时间，不读取文件（大量文件的例行复查）
+    Fast,
+    /// 重新计算 XXH3 并与缓存比对
+    Normal,
+    /// 重新计算所有启用的哈希，全部与缓存一致才采用（归档等对完整性要求高的场景）
+    Paranoid,
+}
+
+impl CacheValidation {
+    pub const ALL: [CacheValidation; 3] = [
+        CacheValidation::Fast,
+        CacheValidation::Normal,
+        CacheValidation::Paranoid,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            CacheValidation::Fast => t(Text::ValidationFast),
+            CacheValidation::Normal => t(Text::ValidationNormal),
+            CacheValidation::Paranoid => t(Text::ValidationParanoid),
+        }
+    }
+
+    fn key(self) -> &'static str {
+        match self {
+            CacheValidation::Fast => "fast",
+            CacheValidation::Normal => "normal",
+            CacheValidation::Paranoid => "paranoid",
+        }
+    }
+}
+
+impl fmt::Display for CacheValidation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.key())
+    }
+}
+
+impl FromStr for CacheValidation {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|level| level.key() == s)
+            .ok_or_else(|| format!("未知的缓存校验级别: {}", s))
+    }
+}
+
+/// 设置中选择的性能模式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PerformanceMode {
+    PowerSaving,
+    Balanced,
+    HighPerformance,
+    /// 手动调整过读取缓冲或内存映射块大小
+    Custom,
+}
+
+impl PerformanceMode {
+    pub const ALL: [PerformanceMode; 4] = [
+        PerformanceMode::PowerSaving,
+        PerformanceMode::Balanced,
+        PerformanceMode::HighPerformance,
+        PerformanceMode::Custom,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            PerformanceMode::PowerSaving => t(Text::ModePowerSaving),
+            PerformanceMode::Balanced => t(Text::ModeBalanced),
+            PerformanceMode::HighPerformance => t(Text::ModeHighPerformance),
+            PerformanceMode::Custom => t(Text::ModeCustom),
+        }
+    }
+
+    fn key(self) -> &'static str {
+        match self {
+            PerformanceMode::PowerSaving => "power_saving",
+            PerformanceMode::Balanced => "balanced",
+            PerformanceMode::HighPerformance => "high_performance",
+            PerformanceMode::Custom => "custom",
+        }
+    }
+
+    /// 对应的预设，自定义模式为 None
+    pub fn preset(self) -> Option<&'static PerformancePreset> {
+        PERFORMANCE_PRESETS
+            .iter()
+            .find(|preset| preset.mode == self)
+    }
+
+    /// 按缓冲与内存映射块大小反推模式，不匹配任何预设时为自定义
+    pub fn from_sizes(buffer_size: usize, mmap_chunk_size: usize) -> Self {
+        PERFORMANCE_PRESETS
+            .iter()
+            .find(|preset| {
+                preset.buffer_size == buffer_size && preset.mmap_chunk_size == mmap_chunk_size
+            })
+            .map_or(PerformanceMode::Custom, |preset| preset.mode)
+    }
+}
+
+impl fmt::Display for PerformanceMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.key())
+    }
+}
+
+impl FromStr for PerformanceMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|mode| mode.key() == s)
+            .ok_or_else(|| format!("未知的性能模式: {}", s))
+    }
+}
+
+/// 空闲时自动整理数据库（VACUUM）的计划，在退出程序时检查
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VacuumSchedule {
+    /// 只在清理缓存后空闲页过多时整理
+    Off,
+    /// 退出时空闲页超过阈值则整理
+    OnExit,
+    /// 距上次整理超过一周、且存在空闲页时在退出时整理
+    Weekly,
+}
+
+impl VacuumSchedule {
+    pub const ALL: [VacuumSchedule; 3] = [
+        VacuumSchedule::Off,
+        VacuumSchedule::OnExit,
+        VacuumSchedule::Weekly,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            VacuumSchedule::Off => t(Text::VacuumOff),
+            VacuumSchedule::OnExit => t(Text::VacuumOnExit),
+            VacuumSchedule::Weekly => t(Text::VacuumWeekly),
+        }
+    }
+
+    fn key(self) -> &'static str {
+        match self {
+            VacuumSchedule::Off => "off",
+            VacuumSchedule::OnExit => "on_exit",
+            VacuumSchedule::Weekly => "weekly",
+        }
+    }
+
+    /// 是否到了整理时间；`last_vacuum` 与 `now` 为 Unix 秒
+    pub fn is_due(self, free_ratio: f64, last_vacuum: Option<u64>, now: u64) -> bool {
+        match self {
+            VacuumSchedule::Off => false,
+            VacuumSchedule::OnExit => free_ratio > SCHEDULED_VACUUM_THRESHOLD,
+            // 没有空闲页时整理不会缩小文件
+            VacuumSchedule::Weekly => {
+                free_ratio > 0.0
+                    && last_vacuum.is_none_or(|last| now.saturating_sub(last) >= WEEKLY_VACUUM_SECS)
+            }
+        }
+    }
+}
+
+impl fmt::Display for VacuumSchedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.key())
+    }
+}
+
+impl FromStr for VacuumSchedule {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|schedule| schedule.key() == s)
+            .ok_or_else(|| format!("未知的整理计划: {}", s))
+    }
+}
+
+/// 文件大小的显示单位
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SizeUnit {
+    /// 1024 进制（KiB/MiB），与操作系统一致
+    Binary,
+    /// 1000 进制（KB/MB），与硬盘厂商标注一致
+    Decimal,
+}
+
+impl SizeUnit {
+    pub const ALL: [SizeUnit; 2] = [SizeUnit::Binary, SizeUnit::Decimal];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SizeUnit::Binary => t(Text::SizeBinary),
+            SizeUnit::Decimal => t(Text::SizeDecimal),
+        }
+    }
+
+    fn key(self) -> &'static str {
+        match self {
+            SizeUnit::Binary => "binary",
+            SizeUnit::Decimal => "decimal",
+        }
+    }
+}
+
+impl fmt::Display for SizeUnit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.key())
+    }
+}
+
+impl FromStr for SizeUnit {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|unit| unit.key() == s)
+            .ok_or_else(|| format!("未知的大小单位: {}", s))
+    }
+}
+
+/// 界面语言
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UiLanguage {
+    /// 找到中文字体时使用中文，否则使用英文
+    Auto,
+    Chinese,
+    English,
+}
+
+impl UiLanguage {
+    pub const ALL: [UiLanguage; 3] = [UiLanguage::Auto, UiLanguage::Chinese, UiLanguage::English];
+
+    // 选项同时写出英文，未加载中文字体时仍可辨认
+    pub fn label(self) -> &'static str {
+        match self {
+            UiLanguage::Auto => "自动 (Auto)",
+            UiLanguage::Chinese => "中文 (Chinese)",
+            UiLanguage::English => "English",
+        }
+    }
+
+    fn key(self) -> &'static str {
+        match self {
+            UiLanguage::Auto => "auto",
+            UiLanguage::Chinese => "zh",
+            UiLanguage::English => "en",
+        }
+    }
+
+    /// 实际使用的语言；`chinese_font` 为是否成功加载了中文字体
+    pub fn resolve(self, chinese_font: bool) -> Language {
+        match self {
+            UiLanguage::Auto if chinese_font => Language::Chinese,
+            UiLanguage::Chinese => Language::Chinese,
+            UiLanguage::Auto | UiLanguage::English => Language::English,
+        }
+    }
+}
+
+impl fmt::Display for UiLanguage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.key())
+    }
+}
+
+impl FromStr for UiLanguage {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|language| language.key() == s)
+            .ok_or_else(|| format!("未知的界面语言: {}", s))
+    }
+}
+
+/// 直接添加的指向文件的符号链接如何计算（遍历目录时始终跳过符号链接）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymlinkPolicy {
+    /// 读取链接指向的文件，缓存按解析后的目标路径保存
+    Follow,
+    /// 对链接中保存的目标路径计算哈希，不读写缓存
+    Link,
+}
+
+impl SymlinkPolicy {
+    pub const ALL: [SymlinkPolicy; 2] = [SymlinkPolicy::Follow, SymlinkPolicy::Link];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SymlinkPolicy::Follow => t(Text::SymlinkFollow),
+            SymlinkPolicy::Link => t(Text::SymlinkLink),
+        }
+    }
+
+    fn key(self) -> &'static str {
+        match self {
+            SymlinkPolicy::Follow => "follow",
+            SymlinkPolicy::Link => "link",
+        }
+    }
+}
+
+impl fmt::Display for SymlinkPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.key())
+    }
+}
+
+impl FromStr for SymlinkPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|policy| policy.key() == s)
+            .ok_or_else(|| format!("未知的符号链接处理方式: {}", s))
+    }
+}
+
+/// 点击哈希复制后的提示方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CopyFeedback {
+    /// 单元格文字暂时替换为「已复制到剪贴板」
+    InCell,
+    /// 在单元格上方浮动显示提示，哈希值保持可见
+    Overlay,
+}
+
+impl CopyFeedback {
+    pub const ALL: [CopyFeedback; 2] = [CopyFeedback::InCell, CopyFeedback::Overlay];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            CopyFeedback::InCell => t(Text::CopyFeedbackInCell),
+            CopyFeedback::Overlay => t(Text::CopyFeedbackOverlay),
+        }
+    }
+
+    fn key(self) -> &'static str {
+        match self {
+            CopyFeedback::InCell => "cell",
+            CopyFeedback::Overlay => "overlay",
+        }
+    }
+}
+
+impl fmt::Display for CopyFeedback {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.key())
+    }
+}
+
+impl FromStr for CopyFeedback {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|feedback| feedback.key() == s)
+            .ok_or_else(|| format!("未知的复制提示方式: {}", s))
+    }
+}
+
+/// 界面中显示与复制哈希时使用的编码，缓存中始终保存十六进制
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HashEncoding {
+    Hex,
+    Base64,
+    /// URL 安全的 Base64（`-` `_`），不带填充
+    Base64Url,
+    ZBase32,
+}
+
+impl HashEncoding {
+    pub const ALL: [HashEncoding; 4] = [
+        HashEncoding::Hex,
+        HashEncoding::Base64,
+        HashEncoding::Base64Url,
+        HashEncoding::ZBase32,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            HashEncoding::Hex => t(Text::EncodingHex),
+            HashEncoding::Base64 => "Base64",
+            HashEncoding::Base64Url => t(Text::EncodingBase64Url),
+            HashEncoding::ZBase32 => "z-base-32",
+        }
+    }
+
+    fn key(self) -> &'static str {
+        match self {
+            HashEncoding::Hex => "hex",
+            HashEncoding::Base64 => "base64",
+            HashEncoding::Base64Url => "base64url",
+            HashEncoding::ZBase32 => "zbase32",
+        }
+    }
+}
+
+impl fmt::Display for HashEncoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.key())
+    }
+}
+
+impl FromStr for HashEncoding {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|encoding| encoding.key() == s)
+            .ok_or_else(|| format!("未知的哈希编码: {}", s))
+    }
+}
+
+/// 新加入文件何时自动开始计算
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AutoComputeMode {
+    /// 任何方式加入的文件都在防抖后自动计算
+    Auto,
+    /// 只在手动点击「开始计算」时计算
+    Manual,
+    /// 仅拖放加入的文件自动计算，通过对话框、粘贴、最近路径等加入的保持暂停
+    AutoOnDrop,
+}
+
+impl AutoComputeMode {
+    pub const ALL: [AutoComputeMode; 3] = [
+        AutoComputeMode::Auto,
+        AutoComputeMode::Manual,
+        AutoComputeMode::AutoOnDrop,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            AutoComputeMode::Auto => t(Text::AutoComputeAuto),
+            AutoComputeMode::Manual => t(Text::AutoComputeManual),
+            AutoComputeMode::AutoOnDrop => t(Text::AutoComputeOnDrop),
+        }
+    }
+
+    /// 新加入的文件是否应自动开始计算
+    pub fn auto_starts(self, dropped: bool) -> bool {
+        match self {
+            AutoComputeMode::Auto => true,
+            AutoComputeMode::Manual => false,
+            AutoComputeMode::AutoOnDrop => dropped,
+        }
+    }
+
+    fn key(self) -> &'static str {
+        match self {
+            AutoComputeMode::Auto => "auto",
+            AutoComputeMode::Manual => "manual",
+            AutoComputeMode::AutoOnDrop => "drop",
+        }
+    }
+}
+
+impl fmt::Display for AutoComputeMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.key())
+    }
+}
+
+impl FromStr for AutoComputeMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|mode| mode.key() == s)
+            .ok_or_else(|| format!("未知的自动计算模式: {}", s))
+    }
+}
 
 /// 缓存配置
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct CacheConfig {
     pub min_file_size: u64,
     pub retention_days: u32,
+    /// 性能模式，选择预设时决定读取缓冲与内存映射块大小
+    pub performance_mode: PerformanceMode,
     pub buffer_size: usize,
     pub mmap_chunk_size: usize,
-    pub auto_compute_enabled: bool,
+    /// 新加入文件的自动计算方式
+    pub auto_compute: AutoComputeMode,
     pub uppercase_display: bool,
+    /// 显示与复制哈希时的编码，大写显示只作用于十六进制
+    pub hash_encoding: HashEncoding,
+    /// 网络路径（SMB/NFS）是否允许使用内存映射，默认强制缓冲读取
+    pub network_mmap_enabled: bool,
+    /// 扫描时每批发送的文件数
+    pub scan_batch_size: usize,
+    /// 扫描时批次的最长等待时间（毫秒）
+    pub scan_flush_ms: u64,
+    /// 扫描遍历线程数，1 为串行，0 为按 CPU 核心数自动选择
+    pub scan_threads: usize,
+    /// 启用系统托盘并允许最小化到托盘
+    pub minimize_to_tray: bool,
+    /// 批处理完成时发送系统通知
+    pub notify_on_complete: bool,
+    /// 批处理耗时超过该秒数才发送通知
+    pub notify_threshold_secs: u64,
+    /// 先计算快速哈希预筛，仅对可能重复的文件做完整计算
+    pub quick_prescreen: bool,
+    /// 修改时间比较容差（毫秒），兼容 FAT32/exFAT 的 2 秒精度及丢失纳秒的网络共享
+    pub mtime_tolerance_ms: u64,
+    /// 结果表格中可见的列
+    pub visible_columns: ColumnSet,
+    /// 用户拖动调整过的列宽
+    pub column_widths: ColumnWidths,
+    /// 缓存键中的文件名统一为 NFC，使 macOS（NFD）与其他平台的缓存互通
+    pub normalize_unicode_names: bool,
+    /// "最近" 菜单保留的路径数量
+    pub recent_limit: usize,
+    /// 启用的可选哈希算法（MD5/SHA1/SHA256）
+    pub enabled_hashes: EnabledHashes,
+    /// 快速模式：未命中缓存的文件只计算 XXH3，用于大批量查重
+    pub xxh3_only_mode: bool,
+    /// 进度条中每个文件额外计入的开销（KB），0 表示按字节计算
+    pub progress_file_cost_kb: u64,
+    /// 全局进度按已完成文件数计算（默认按字节）
+    pub progress_by_file_count: bool,
+    /// 超过此大小（字节）的文件扫描时跳过计算，0 表示不限制
+    pub max_file_size: u64,
+    /// 不读写可移动介质上文件的缓存（同一盘符可能对应不同设备）
+    pub skip_removable_cache: bool,
+    /// 日志级别（turbohash.log 与 stderr）
+    pub log_level: log::LevelFilter,
+    /// 仅新增/变更：大小与修改时间未变的文件直接采用缓存结果，不做 XXH3 校验
+    pub sync_mode: bool,
+    /// 允许计算显式添加的块设备（如 `/dev/sdb`，仅 Unix）
+    pub hash_block_devices: bool,
+    /// 计算期间阻止系统休眠
+    pub prevent_sleep: bool,
+    /// 清空队列前弹出确认
+    pub confirm_clear_queue: bool,
+    /// 添加文件时查找同名校验文件并自动校验
+    pub verify_sidecars: bool,
+    /// 计算队列的处理顺序
+    pub compute_order: ComputeOrder,
+    /// 在单个线程中按队列顺序逐个计算，用于性能测试与排查问题
+    pub serial_compute: bool,
+    /// 计算完成后将哈希写入文件扩展属性（需 xattr-export 特性）
+    pub write_xattrs: bool,
+    /// 扫描时将 NTFS 备用数据流作为单独的行加入计算（仅 Windows）
+    pub hash_alternate_streams: bool,
+    /// 机械硬盘上限制扫描与计算的并发
+    pub disk_concurrency: DiskConcurrency,
+    /// 空闲时自动整理数据库的计划
+    pub vacuum_schedule: VacuumSchedule,
+    /// 文件大小的显示单位
+    pub size_unit: SizeUnit,
+    /// 界面语言
+    pub ui_language: UiLanguage,
+    /// 直接添加的符号链接计算目标文件还是链接本身
+    pub symlink_policy: SymlinkPolicy,
+    /// 稀疏文件只读取已分配的区域，空洞按零计算
+    pub sparse_files: bool,
+    /// 复制哈希后的提示方式
+    pub copy_feedback: CopyFeedback,
+    /// 复制提示的显示时长（毫秒）
+    pub copy_toast_ms: u64,
+    /// UI 每帧最多处理的工作线程消息数，0 为按帧时间预算自动调整
+    pub max_messages_per_frame: usize,
+    /// 工作线程发往 UI 的消息通道容量，重启后生效
+    pub ui_channel_capacity: usize,
+    /// 扫描时整个跳过的目录名（不区分大小写）
+    pub excluded_dirs: Vec<String>,
+    /// 启动时检查是否有新版本（需 update-check 特性）
+    pub update_check: bool,
+    /// 版本清单的地址
+    pub update_manifest_url: String,
+    /// 命中缓存前的校验级别
+    pub cache_validation: CacheValidation,
+    /// 统计各哈希算法的耗时（诊断用）
+    pub hash_timing: bool,
 }
 
 impl Default for CacheConfig {
@@ -46,14 +815,93 @@ impl Default for CacheConfig {
         Self {
             min_file_size: 1024 * 1024,
             retention_days: 30,
+            performance_mode: PerformanceMode::Balanced,
             buffer_size: 256 * 1024,
             mmap_chunk_size: 4 * 1024 * 1024,
-            auto_compute_enabled: true,
+            auto_compute: AutoComputeMode::Auto,
             uppercase_display: true,
+            hash_encoding: HashEncoding::Hex,
+            network_mmap_enabled: false,
+            scan_batch_size: 100,
+            scan_flush_ms: 50,
+            scan_threads: 1,
+            minimize_to_tray: false,
+            notify_on_complete: true,
+            notify_threshold_secs: 10,
+            quick_prescreen: false,
+            mtime_tolerance_ms: 2000,
+            visible_columns: ColumnSet::default(),
+            column_widths: ColumnWidths::default(),
+            normalize_unicode_names: false,
+            recent_limit: 10,
+            enabled_hashes: EnabledHashes::default(),
+            xxh3_only_mode: false,
+            progress_file_cost_kb: 0,
+            progress_by_file_count: false,
+            max_file_size: 0,
+            skip_removable_cache: true,
+            log_level: log::LevelFilter::Info,
+            sync_mode: false,
+            hash_block_devices: false,
+            prevent_sleep: true,
+            confirm_clear_queue: true,
+            verify_sidecars: true,
+            compute_order: ComputeOrder::Added,
+            serial_compute: false,
+            write_xattrs: false,
+            hash_alternate_streams: false,
+            disk_concurrency: DiskConcurrency::Auto,
+            vacuum_schedule: VacuumSchedule::Off,
+            size_unit: SizeUnit::Binary,
+            ui_language: UiLanguage::Auto,
+            symlink_policy: SymlinkPolicy::Follow,
+            sparse_files: false,
+            copy_feedback: CopyFeedback::InCell,
+            copy_toast_ms: 2000,
+            max_messages_per_frame: 0,
+            ui_channel_capacity: 1024,
+            excluded_dirs: [
+                "node_modules",
+                ".git",
+                "$RECYCLE.BIN",
+                "System Volume Information",
+            ]
+            .map(String::from)
+            .to_vec(),
+            update_check: false,
+            update_manifest_url: crate::update::DEFAULT_MANIFEST_URL.to_string(),
+            cache_validation: CacheValidation::Normal,
+            hash_timing: false,
         }
     }
 }
 
+/// 解析每行一个的目录名列表，忽略空行与首尾空白
+pub fn parse_dir_names(text: &str) -> Vec<String> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+impl CacheConfig {
+    /// 切换性能模式，预设模式同时套用其缓冲与内存映射块大小
+    pub fn set_performance_mode(&mut self, mode: PerformanceMode) {
+        self.performance_mode = mode;
+        if let Some(preset) = mode.preset() {
+            self.buffer_size = preset.buffer_size;
+            self.mmap_chunk_size = preset.mmap_chunk_size;
+        }
+    }
+
+    /// 缓存条目是否已超过保留期限，即下次清理过期时会被删除
+    pub fn is_past_retention(&self, cached_at: u64, now: u64) -> bool {
+        self.retention_days > 0
+            && cached_at < now.saturating_sub(u64::from(self.retention_days) * 86400)
+    }
+}
+
 /// 缓存条目
 #[derive(Debug, Clone)]
 pub struct CacheEntry {
@@ -65,17 +913,91 @@ pub struct CacheEntry {
     pub crc32: String,
     pub md5: String,
     pub sha1: String,
+    /// 未计算的算法为空字符串（数据库中存为 NULL）
+    pub sha256: String,
+    pub crc64: String,
+    pub adler32: String,
+}
+
+impl CacheEntry {
+    pub fn new(
+        path: PathBuf,
+        file_size: u64,
+        modified_time: u64,
+        cached_at: u64,
+        hashes: FileHashes,
+    ) -> Self {
+        Self {
+            path,
+            file_size,
+            modified_time,
+            cached_at,
+            xxhash3: hashes.xxhash3,
+            crc32: hashes.crc32,
+            md5: hashes.md5,
+            sha1: hashes.sha1,
+            sha256: hashes.sha256,
+            crc64: hashes.crc64,
+            adler32: hashes.adler32,
+        }
+    }
+
+    pub fn hash_value(&self, algorithm: HashAlgorithm) -> &str {
+        match algorithm {
+            HashAlgorithm::Crc32 => &self.crc32,
+            HashAlgorithm::Crc64 => &self.crc64,
+            HashAlgorithm::Adler32 => &self.adler32,
+            HashAlgorithm::Md5 => &self.md5,
+            HashAlgorithm::Sha1 => &self.sha1,
+            HashAlgorithm::Sha256 => &self.sha256,
+            HashAlgorithm::Xxh3 => &self.xxhash3,
+        }
+    }
+}
+
+/// 按 `path, file_size, modified_time, cached_at, xxhash3, crc32, md5, sha1, sha256, crc64, adler32`
+/// 的列顺序读取条目
+fn entry_from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<CacheEntry> {
+    Ok(CacheEntry {
+        path: PathBuf::from(row.get::<_, String>(0)?),
+        file_size: row.get::<_, i64>(1)? as u64,
+        modified_time: row.get::<_, i64>(2)? as u64,
+        cached_at: row.get::<_, i64>(3)? as u64,
+        xxhash3: row.get(4)?,
+        crc32: row.get(5)?,
+        md5: row.get::<_, Option<String>>(6)?.unwrap_or_default(),
+        sha1: row.get::<_, Option<String>>(7)?.unwrap_or_default(),
+        sha256: row.get::<_, Option<String>>(8)?.unwrap_or_default(),
+        crc64: row.get::<_, Option<String>>(9)?.unwrap_or_default(),
+        adler32: row.get::<_, Option<String>>(10)?.unwrap_or_default(),
+    })
+}
+
+/// 空字符串写入数据库时存为 NULL
+fn non_empty(value: &str) -> Option<&str> {
+    (!value.is_empty()).then_some(value)
 }
 
 /// 路径规范化器（带缓存）
 pub struct PathNormalizer {
     cache: Arc<Mutex<HashMap<PathBuf, PathBuf>>>,
+    unicode_nfc: AtomicBool,
 }
 
 impl PathNormalizer {
     pub fn new() -> Self {
         Self {
             cache: Arc::new(Mutex::new(HashMap::new())),
+            unicode_nfc: AtomicBool::new(false),
+        }
+    }
+
+    /// 开关文件名 NFC 规范化，切换时清空已缓存的结果
+    pub fn set_unicode_nfc(&self, enabled: bool) {
+        if self.unicode_nfc.swap(enabled, Ordering::Relaxed) != enabled {
+            if let Ok(mut cache_guard) = self.cache.lock() {
+                cache_guard.clear();
+            }
         }
     }
 
@@ -91,7 +1013,23 @@ impl PathNormalizer {
         }
         drop(cache_guard);
 
-        let normalized = dunce::canonicalize(path).with_path(path)?;
+        // 规范化会丢掉数据流名称，先拆出 `:stream` 再拼回，避免与主文件共用缓存
+        #[cfg(windows)]
+        let (base, stream) = match crate::streams::split_stream(path) {
+            Some((file, stream)) => (file, Some(stream)),
+            None => (path.to_path_buf(), None),
+        };
+        #[cfg(not(windows))]
+        let base = path;
+
+        // 符号链接解析为目标路径，跟随链接计算时链接与目标共用同一缓存条目
+        let normalized = with_long_path(&base, |p| dunce::canonicalize(p)).with_path(path)?;
+
+        #[cfg(windows)]
+        let normalized = match stream {
+            Some(stream) => crate::streams::stream_path(&normalized, &stream),
+            None => normalized,
+        };
 
         #[cfg(windows)]
         let normalized = {
@@ -99,6 +1037,12 @@ impl PathNormalizer {
             PathBuf::from(s)
         };
 
+        let normalized = if self.unicode_nfc.load(Ordering::Relaxed) {
+            nfc_path(&normalized)
+        } else {
+            normalized
+        };
+
         let mut cache_guard = self.cache.lock().map_err(|e| HashError::Cache {
             operation: CacheOperation::PathNormalization,
             kind: crate::error::CacheErrorKind::PoolExhausted,
@@ -110,10 +1054,79 @@ impl PathNormalizer {
     }
 }
 
+/// 缓存条目的键：规范化后的 UTF-8 路径
+///
+/// Linux 上旧编码（如 GBK）的文件名不是有效的 UTF-8，无法作为键。这类文件照常计算，
+/// 只是不读写缓存，也不影响同一批次中其他文件的缓存。
+fn cache_key(normalized: &Path) -> Option<&str> {
+    let key = normalized.to_str();
+    if key.is_none() {
+        log::debug!(
+            "文件名不是有效的 UTF-8，不使用缓存: {}",
+            normalized.display()
+        );
+    }
+    key
+}
+
+/// 批处理队列中保存的路径：原始字节，非 UTF-8 的文件名崩溃后也能恢复
+#[cfg(unix)]
+fn path_to_bytes(path: &Path) -> Vec<u8> {
+    use std::os::unix::ffi::OsStrExt;
+    path.as_os_str().as_bytes().to_vec()
+}
+
+#[cfg(unix)]
+fn path_from_bytes(bytes: &[u8]) -> PathBuf {
+    use std::os::unix::ffi::OsStrExt;
+    PathBuf::from(std::ffi::OsStr::from_bytes(bytes))
+}
+
+/// Windows 上为 UTF-16LE，可以保存未配对的代理项
+#[cfg(windows)]
+fn path_to_bytes(path: &Path) -> Vec<u8> {
+    use std::os::windows::ffi::OsStrExt;
+    path.as_os_str()
+        .encode_wide()
+        .flat_map(u16::to_le_bytes)
+        .collect()
+}
+
+#[cfg(windows)]
+fn path_from_bytes(bytes: &[u8]) -> PathBuf {
+    use std::os::windows::ffi::OsStringExt;
+    let wide: Vec<u16> = bytes
+        .chunks_exact(2)
+        .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+        .collect();
+    PathBuf::from(std::ffi::OsString::from_wide(&wide))
+}
+
+/// 将路径中的名称部分转换为 NFC，盘符/前缀与根保持原样
+///
+/// 非 UTF-8 的名称无法规范化，按原样保留。
+pub fn nfc_path(path: &Path) -> PathBuf {
+    use unicode_normalization::UnicodeNormalization;
+
+    path.components()
+        .map(|component| match component {
+            Component::Normal(name) => match name.to_str() {
+                Some(name) => PathBuf::from(name.nfc().collect::<String>()),
+                None => PathBuf::from(name),
+            },
+            other => PathBuf::from(other.as_os_str()),
+        })
+        .collect()
+}
+
 /// SQLite 连接池管理器
+///
+/// 克隆只复制连接池句柄，长时间的维护操作可以克隆后在锁外执行。
+#[derive(Clone)]
 pub struct HashCachePool {
     read_pool: Pool<SqliteConnectionManager>,
     write_pool: Pool<SqliteConnectionManager>,
+    db_path: PathBuf,
     config: CacheConfig,
     pub path_normalizer: Arc<PathNormalizer>,
 }
@@ -124,6 +1137,7 @@ impl HashCachePool {
 
         let read_manager = SqliteConnectionManager::file(db_path).with_init(|conn| {
             let _ = conn.query_row("PRAGMA journal_mode=WAL", [], |row| row.get::<_, String>(0));
+            let _ = conn.busy_timeout(BUSY_TIMEOUT);
             let _ = conn.execute("PRAGMA synchronous=NORMAL", []);
             let _ = conn.execute("PRAGMA cache_size=-64000", []); // 64MB
             let _ = conn.execute("PRAGMA mmap_size=268435456", []); // 256MB
@@ -133,6 +1147,8 @@ impl HashCachePool {
 
         let write_manager = SqliteConnectionManager::file(db_path).with_init(|conn| {
             let _ = conn.query_row("PRAGMA journal_mode=WAL", [], |row| row.get::<_, String>(0));
+            // 并发写入时先由 SQLite 等待锁释放，而不是立即返回 SQLITE_BUSY
+            let _ = conn.busy_timeout(BUSY_TIMEOUT);
             let _ = conn.execute("PRAGMA synchronous=NORMAL", []);
             let _ = conn.execute("PRAGMA cache_size=-64000", []); // 64MB
             let _ = conn.execute("PRAGMA mmap_size=268435456", []); // 256MB
@@ -164,23 +1180,32 @@ impl HashCachePool {
                 context: "failed to create write pool".to_string(),
             })?;
 
+        let path_normalizer = PathNormalizer::new();
+        path_normalizer.set_unicode_nfc(config.normalize_unicode_names);
+
         Ok(Self {
             read_pool,
             write_pool,
+            db_path: db_path.to_path_buf(),
             config,
-            path_normalizer: Arc::new(PathNormalizer::new()),
+            path_normalizer: Arc::new(path_normalizer),
         })
     }
 
+    /// 数据库文件的路径，内存数据库为 `:memory:`
+    pub fn db_path(&self) -> &Path {
+        &self.db_path
+    }
+
     /// 初始化数据库：创建表、索引、迁移
     fn initialize_database(db_path: &Path) -> HashResult<()> {
         let mut conn = Connection::open(db_path)
             .with_cache_error(CacheOperation::Connection, "failed to open database")?;
 
-        // 读取当前版本
+        // 读取当前版本（metadata.value 为 TEXT 列，需转换为整数）
         let version: u32 = conn
             .query_row(
-                "SELECT COALESCE((SELECT value FROM metadata WHERE key = 'version'), '0')",
+                "SELECT CAST(COALESCE((SELECT value FROM metadata WHERE key = 'version'), '0') AS INTEGER)",
                 [],
                 |row| row.get(0),
             )
@@ -191,8 +1216,8 @@ impl HashCachePool {
             Self::run_migrations(&mut conn, version)?;
         }
 
-        // 创建主表（v3 schema）
-        Self::create_schema_v3(&mut conn)?;
+        // 创建主表（v5 schema）
+        Self::create_schema_v5(&mut conn)?;
 
         // 创建设置表
         conn.execute(
@@ -204,29 +1229,46 @@ impl HashCachePool {
         )
         .with_cache_error(CacheOperation::Migrate, "failed to create settings table")?;
 
-        Ok(())
-    }
+        // 批处理队列：崩溃后恢复未完成的文件
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS batch_queue (
+                path TEXT PRIMARY KEY,
+                done INTEGER NOT NULL DEFAULT 0
+            )",
+            [],
+        )
+        .with_cache_error(
+            CacheOperation::Migrate,
+            "failed to create batch_queue table",
+        )?;
+
+        // 文件备注：按规范化路径保存，重新添加文件时恢复
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS notes (
+                path TEXT PRIMARY KEY,
+                note TEXT NOT NULL
+            )",
+            [],
+        )
+        .with_cache_error(CacheOperation::Migrate, "failed to create notes table")?;
 
-    /// 创建 v3 schema（带 CHECK 约束）
-    fn create_schema_v3(conn: &mut Connection) -> HashResult<()> {
+        // 命名的配置方案，配置以 JSON 保存
         conn.execute(
-            "CREATE TABLE IF NOT EXISTS hash_cache (
-                path TEXT NOT NULL PRIMARY KEY,
-                file_size INTEGER NOT NULL CHECK(file_size > 0),
-                modified_time INTEGER NOT NULL CHECK(modified_time >= 0),
-                cached_at INTEGER NOT NULL CHECK(cached_at > 0),
-                xxhash3 TEXT NOT NULL CHECK(length(xxhash3) = 32),
-                crc32 TEXT NOT NULL CHECK(length(crc32) = 8),
-                md5 TEXT NOT NULL CHECK(length(md5) = 32),
-                sha1 TEXT NOT NULL CHECK(length(sha1) = 40),
-                CHECK(xxhash3 GLOB '[0-9a-fA-F][0-9a-fA-F]*'),
-                CHECK(crc32 GLOB '[0-9a-fA-F][0-9a-fA-F]*'),
-                CHECK(md5 GLOB '[0-9a-fA-F][0-9a-fA-F]*'),
-                CHECK(sha1 GLOB '[0-9a-fA-F][0-9a-fA-F]*')
-            ) WITHOUT ROWID",
+            "CREATE TABLE IF NOT EXISTS profiles (
+                name TEXT PRIMARY KEY,
+                config TEXT NOT NULL
+            )",
             [],
         )
-        .with_cache_error(CacheOperation::Migrate, "failed to create hash_cache table")?;
+        .with_cache_error(CacheOperation::Migrate, "failed to create profiles table")?;
+
+        Ok(())
+    }
+
+    /// 创建 v5 schema（带 CHECK 约束，CRC32 与 XXH3 以外的算法可为空）
+    fn create_schema_v5(conn: &mut Connection) -> HashResult<()> {
+        conn.execute(HASH_CACHE_TABLE_SQL, [])
+            .with_cache_error(CacheOperation::Migrate, "failed to create hash_cache table")?;
 
         // 性能优化索引
         conn.execute(
@@ -247,12 +1289,41 @@ impl HashCachePool {
     }
 
     /// 运行数据库迁移
-    fn run_migrations(conn: &mut Connection, _current_version: u32) -> HashResult<()> {
+    fn run_migrations(conn: &mut Connection, current_version: u32) -> HashResult<()> {
         let tx = conn.unchecked_transaction().with_cache_error(
             CacheOperation::Migrate,
             "failed to begin migration transaction",
         )?;
 
+        // v3 -> v4：放宽 MD5/SHA1 的 NOT NULL 约束并新增 SHA256 列
+        // v4 -> v5：新增 CRC64 与 Adler-32 列
+        // 两者都通过重建主表完成，新增的列保持 NULL
+        let has_cache_table: bool = tx
+            .query_row(
+                "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'hash_cache')",
+                [],
+                |row| row.get(0),
+            )
+            .with_cache_error(CacheOperation::Migrate, "failed to inspect schema")?;
+        if current_version < 5 && has_cache_table {
+            let columns = if current_version < 4 {
+                "path, file_size, modified_time, cached_at, xxhash3, crc32, md5, sha1"
+            } else {
+                "path, file_size, modified_time, cached_at, xxhash3, crc32, md5, sha1, sha256"
+            };
+            tx.execute("ALTER TABLE hash_cache RENAME TO hash_cache_old", [])
+                .with_cache_error(CacheOperation::Migrate, "failed to rename old table")?;
+            tx.execute(HASH_CACHE_TABLE_SQL, [])
+                .with_cache_error(CacheOperation::Migrate, "failed to create v5 table")?;
+            tx.execute(
+                &format!("INSERT INTO hash_cache ({columns}) SELECT {columns} FROM hash_cache_old"),
+                [],
+            )
+            .with_cache_error(CacheOperation::Migrate, "failed to copy old entries")?;
+            tx.execute("DROP TABLE hash_cache_old", [])
+                .with_cache_error(CacheOperation::Migrate, "failed to drop old table")?;
+        }
+
         // 更新版本号到元数据表
         tx.execute(
             "CREATE TABLE IF NOT EXISTS metadata (key TEXT PRIMARY KEY, value TEXT)",
@@ -270,7 +1341,7 @@ impl HashCachePool {
         tx.commit()
             .with_cache_error(CacheOperation::Migrate, "failed to commit migration")?;
 
-        eprintln!("[Cache] 已迁移到 v{}", CURRENT_CACHE_VERSION);
+        log::info!("已迁移到 v{}", CURRENT_CACHE_VERSION);
         Ok(())
     }
 
@@ -304,11 +1375,20 @@ impl HashCachePool {
             result.insert(normalized_paths[i].clone(), None);
         }
 
-        for chunk in normalized_paths.chunks(SQLITE_MAX_VARIABLE_NUMBER) {
-            let placeholders = (0..chunk.len()).map(|_| "?").collect::<Vec<_>>().join(", ");
+        // 没有缓存键的文件保持为 None
+        let keys: Vec<&str> = normalized_paths
+            .iter()
+            .filter_map(|p| cache_key(p))
+            .collect();
+        for path_strs in keys.chunks(SQLITE_MAX_VARIABLE_NUMBER) {
+            let placeholders = (0..path_strs.len())
+                .map(|_| "?")
+                .collect::<Vec<_>>()
+                .join(", ");
 
             let sql = format!(
-                "SELECT path, file_size, modified_time, cached_at, xxhash3, crc32, md5, sha1
+                "SELECT path, file_size, modified_time, cached_at, xxhash3, crc32, md5, sha1, sha256,
+                        crc64, adler32
                  FROM hash_cache WHERE path IN ({})",
                 placeholders
             );
@@ -318,21 +1398,6 @@ impl HashCachePool {
                 .prepare_cached(&sql)
                 .with_cache_error(CacheOperation::BatchRead, "failed to prepare statement")?;
 
-            let path_strs: Vec<String> = chunk
-                .iter()
-                .map(|p| {
-                    p.to_str()
-                        .ok_or_else(|| HashError::Cache {
-                            operation: CacheOperation::PathNormalization,
-                            kind: crate::error::CacheErrorKind::InvalidPath(
-                                "path contains invalid UTF-8".to_string(),
-                            ),
-                            context: format!("path: {}", p.display()),
-                        })
-                        .map(|s| s.to_string())
-                })
-                .collect::<HashResult<Vec<_>>>()?;
-
             let params: Vec<&dyn rusqlite::ToSql> = path_strs
                 .iter()
                 .map(|s| s as &dyn rusqlite::ToSql)
@@ -346,17 +1411,8 @@ impl HashCachePool {
                 .next()
                 .with_cache_error(CacheOperation::BatchRead, "row iteration failed")?
             {
-                let db_path = PathBuf::from(row.get::<_, String>(0)?);
-                let entry = CacheEntry {
-                    path: db_path.clone(),
-                    file_size: row.get::<_, i64>(1)? as u64,
-                    modified_time: row.get::<_, i64>(2)? as u64,
-                    cached_at: row.get::<_, i64>(3)? as u64,
-                    xxhash3: row.get(4)?,
-                    crc32: row.get(5)?,
-                    md5: row.get(6)?,
-                    sha1: row.get(7)?,
-                };
+                let entry = entry_from_row(row)?;
+                let db_path = entry.path.clone();
                 // 同时用规范化路径和原始路径作为键
                 result.insert(db_path.clone(), Some(entry.clone()));
                 // 查找对应的原始路径并也插入
@@ -369,12 +1425,66 @@ impl HashCachePool {
         Ok(result)
     }
 
-    /// 批量保存缓存（使用写连接池 + 路径规范化）
+    /// 查询某个目录（含子目录）下的全部缓存条目
+    pub fn get_entries_under(&self, dir: &Path) -> HashResult<Vec<CacheEntry>> {
+        let conn = self.read_pool.get().map_err(|e| HashError::Cache {
+            operation: CacheOperation::Connection,
+            kind: crate::error::CacheErrorKind::PoolExhausted,
+            context: format!("read pool timeout: {}", e),
+        })?;
+
+        let normalized = self.path_normalizer.normalize(dir)?;
+        // 目录名不是 UTF-8 时其下不会有缓存条目
+        let Some(prefix) = cache_key(&normalized) else {
+            return Ok(Vec::new());
+        };
+        let mut prefix = prefix.to_string();
+        // 补上分隔符，避免 /data/a 匹配到 /data/ab 下的条目
+        if !prefix.ends_with(std::path::MAIN_SEPARATOR) {
+            prefix.push(std::path::MAIN_SEPARATOR);
+        }
+
+        let mut stmt = conn
+            .prepare_cached(
+                "SELECT path, file_size, modified_time, cached_at, xxhash3, crc32, md5, sha1, sha256,
+                        crc64, adler32
+                 FROM hash_cache WHERE substr(path, 1, length(?1)) = ?1 ORDER BY path",
+            )
+            .with_cache_error(CacheOperation::BatchRead, "failed to prepare statement")?;
+        let entries = stmt
+            .query_map(params![prefix], entry_from_row)
+            .with_cache_error(CacheOperation::BatchRead, "query failed")?
+            .collect::<Result<Vec<_>, _>>()
+            .with_cache_error(CacheOperation::BatchRead, "row iteration failed")?;
+        Ok(entries)
+    }
+
+    /// 批量保存缓存（使用写连接池 + 路径规范化），数据库锁定时整批回滚并退避重试
     pub fn save_entries_batch(&self, entries: &[CacheEntry]) -> HashResult<usize> {
         if entries.is_empty() {
             return Ok(0);
         }
 
+        let mut backoff = SAVE_RETRY_BACKOFF;
+        let mut attempt = 1;
+        loop {
+            match self.try_save_entries_batch(entries) {
+                Err(e) if e.is_database_locked() && attempt < SAVE_MAX_ATTEMPTS => {
+                    log::warn!(
+                        "数据库锁定，{}ms 后重试（第 {} 次）",
+                        backoff.as_millis(),
+                        attempt
+                    );
+                    thread::sleep(backoff);
+                    backoff *= 2;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    fn try_save_entries_batch(&self, entries: &[CacheEntry]) -> HashResult<usize> {
         let conn = self
             .write_pool
             .get()
@@ -394,35 +1504,40 @@ impl HashCachePool {
             let mut stmt = tx
                 .prepare_cached(
                     "INSERT OR REPLACE INTO hash_cache
-                 (path, file_size, modified_time, cached_at, xxhash3, crc32, md5, sha1)
-                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                 (path, file_size, modified_time, cached_at, xxhash3, crc32, md5, sha1, sha256,
+                  crc64, adler32)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
                 )
                 .with_cache_error(CacheOperation::BatchWrite, "failed to prepare statement")?;
 
             for entry in entries {
                 // 规范化路径
                 let normalized_path = self.path_normalizer.normalize(&entry.path)?;
-                let path_str = normalized_path.to_str().ok_or_else(|| HashError::Cache {
-                    operation: CacheOperation::PathNormalization,
-                    kind: crate::error::CacheErrorKind::InvalidPath(
-                        "normalized path contains invalid UTF-8".to_string(),
-                    ),
-                    context: format!("path: {}", normalized_path.display()),
-                })?;
-
-                match stmt.execute(params![
-                    path_str,
-                    entry.file_size as i64,
-                    entry.modified_time as i64,
-                    entry.cached_at as i64,
-                    &entry.xxhash3,
-                    &entry.crc32,
-                    &entry.md5,
-                    &entry.sha1,
-                ]) {
+                let Some(path_str) = cache_key(&normalized_path) else {
+                    continue;
+                };
+
+                match stmt
+                    .execute(params![
+                        path_str,
+                        entry.file_size as i64,
+                        entry.modified_time as i64,
+                        entry.cached_at as i64,
+                        &entry.xxhash3,
+                        &entry.crc32,
+                        non_empty(&entry.md5),
+                        non_empty(&entry.sha1),
+                        non_empty(&entry.sha256),
+                        non_empty(&entry.crc64),
+                        non_empty(&entry.adler32),
+                    ])
+                    .with_cache_error(CacheOperation::BatchWrite, path_str)
+                {
                     Ok(_) => saved += 1,
+                    // 锁定错误中止整个事务，交给外层重试
+                    Err(e) if e.is_database_locked() => return Err(e),
                     Err(e) => {
-                        eprintln!("[Cache] 批量保存失败: {} (path: {})", e, path_str);
+                        log::error!("批量保存失败: {} (path: {})", e, path_str);
                     }
                 }
             }
@@ -435,60 +1550,194 @@ impl HashCachePool {
         Ok(saved)
     }
 
-    /// 清理过期缓存
-    pub fn cleanup_expired(&self) -> HashResult<usize> {
-        if self.config.retention_days == 0 {
-            return Ok(0);
+    /// 分批删除缓存条目，每批之间检查取消标志并报告进度
+    ///
+    /// 每批单独获取写连接并提交，删除数百万条时保存缓存等写入仍可在批次之间进行。
+    /// 删除后空闲页过多时在当前线程执行 VACUUM（不可取消）。
+    pub fn cleanup(
+        &self,
+        kind: CleanupKind,
+        cancel: &AtomicBool,
+        mut progress: impl FnMut(CleanupProgress),
+    ) -> HashResult<CleanupOutcome> {
+        let mut outcome = CleanupOutcome {
+            kind,
+            deleted: 0,
+            cancelled: false,
+        };
+        let cutoff_time = match kind {
+            CleanupKind::Expired if self.config.retention_days == 0 => return Ok(outcome),
+            CleanupKind::Expired => {
+                let elapsed = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map_err(|e| HashError::SystemResource(format!("SystemTime error: {}", e)))?;
+                elapsed
+                    .as_secs()
+                    .saturating_sub(self.config.retention_days as u64 * 86400)
+                    as i64
+            }
+            // cached_at 受 CHECK 约束必须大于 0
+            CleanupKind::All => i64::MAX,
+        };
+
+        let total = {
+            let conn = self.read_pool.get().map_err(|e| HashError::Cache {
+                operation: CacheOperation::Connection,
+                kind: crate::error::CacheErrorKind::PoolExhausted,
+                context: format!("read pool timeout: {}", e),
+            })?;
+            conn.query_row(
+                "SELECT COUNT(*) FROM hash_cache WHERE cached_at < ?1",
+                params![cutoff_time],
+                |row| row.get::<_, i64>(0),
+            )
+            .with_cache_error(CacheOperation::Cleanup, "failed to count entries")?
+                as usize
+        };
+        progress(CleanupProgress::Deleting { deleted: 0, total });
+
+        while outcome.deleted < total {
+            if cancel.load(Ordering::Relaxed) {
+                outcome.cancelled = true;
+                break;
+            }
+            let conn = self.write_pool.get().map_err(|e| HashError::Cache {
+                operation: CacheOperation::Connection,
+                kind: crate::error::CacheErrorKind::PoolExhausted,
+                context: format!("write pool timeout: {}", e),
+            })?;
+            let deleted = conn
+                .execute(
+                    "DELETE FROM hash_cache WHERE path IN
+                     (SELECT path FROM hash_cache WHERE cached_at < ?1 LIMIT ?2)",
+                    params![cutoff_time, CLEANUP_CHUNK_SIZE as i64],
+                )
+                .with_cache_error(CacheOperation::Cleanup, "failed to delete entries")?;
+            drop(conn);
+
+            outcome.deleted += deleted;
+            progress(CleanupProgress::Deleting {
+                deleted: outcome.deleted,
+                total: total.max(outcome.deleted),
+            });
+            if deleted < CLEANUP_CHUNK_SIZE {
+                break;
+            }
         }
 
-        let now = SystemTime::now();
-        let elapsed = now
-            .duration_since(UNIX_EPOCH)
-            .map_err(|e| HashError::SystemResource(format!("SystemTime error: {}", e)))?;
-        let cutoff_time = elapsed
-            .as_secs()
-            .saturating_sub(self.config.retention_days as u64 * 86400);
+        if outcome.deleted > 0 {
+            log::info!("清理了 {} 条缓存条目", outcome.deleted);
+            if self.should_vacuum()? {
+                progress(CleanupProgress::Vacuuming);
+                self.vacuum()?;
+            }
+        }
+
+        Ok(outcome)
+    }
 
+    /// 将新批处理的文件加入队列，与尚未完成的队列合并；已完成的文件重新加入时恢复为未完成
+    pub fn enqueue_batch(&self, paths: &[PathBuf]) -> HashResult<()> {
         let conn = self.write_pool.get().map_err(|e| HashError::Cache {
             operation: CacheOperation::Connection,
             kind: crate::error::CacheErrorKind::PoolExhausted,
             context: format!("write pool timeout: {}", e),
         })?;
 
-        let deleted = conn
-            .execute(
-                "DELETE FROM hash_cache WHERE cached_at < ?1",
-                params![cutoff_time as i64],
-            )
-            .with_cache_error(CacheOperation::Cleanup, "failed to delete expired entries")?;
+        let tx = conn
+            .unchecked_transaction()
+            .with_cache_error(CacheOperation::BatchWrite, "failed to begin transaction")?;
+        {
+            let mut stmt = tx
+                .prepare_cached(
+                    "INSERT INTO batch_queue (path, done) VALUES (?1, 0)
+                     ON CONFLICT(path) DO UPDATE SET done = 0",
+                )
+                .with_cache_error(CacheOperation::BatchWrite, "failed to prepare statement")?;
+            for path in paths {
+                stmt.execute(params![path_to_bytes(path)])
+                    .with_cache_error(CacheOperation::BatchWrite, "failed to enqueue path")?;
+            }
+        }
+        tx.commit()
+            .with_cache_error(CacheOperation::BatchWrite, "failed to commit transaction")?;
+
+        Ok(())
+    }
 
-        if deleted > 0 {
-            eprintln!("[Cache] 清理了 {} 条过期条目", deleted);
+    /// 标记批处理中已完成（含失败）的文件
+    pub fn mark_batch_done(&self, paths: &[PathBuf]) -> HashResult<()> {
+        if paths.is_empty() {
+            return Ok(());
+        }
+
+        let conn = self.write_pool.get().map_err(|e| HashError::Cache {
+            operation: CacheOperation::Connection,
+            kind: crate::error::CacheErrorKind::PoolExhausted,
+            context: format!("write pool timeout: {}", e),
+        })?;
 
-            // 检查是否需要 VACUUM
-            self.schedule_vacuum_if_needed()?;
+        let tx = conn
+            .unchecked_transaction()
+            .with_cache_error(CacheOperation::BatchWrite, "failed to begin transaction")?;
+        {
+            let mut stmt = tx
+                .prepare_cached("UPDATE batch_queue SET done = 1 WHERE path = ?1")
+                .with_cache_error(CacheOperation::BatchWrite, "failed to prepare statement")?;
+            for path in paths {
+                stmt.execute(params![path_to_bytes(path)])
+                    .with_cache_error(CacheOperation::BatchWrite, "failed to mark path done")?;
+            }
         }
+        tx.commit()
+            .with_cache_error(CacheOperation::BatchWrite, "failed to commit transaction")?;
+
+        Ok(())
+    }
+
+    /// 读取上次未完成批处理中尚未处理的文件
+    pub fn load_pending_batch(&self) -> HashResult<Vec<PathBuf>> {
+        let conn = self.read_pool.get().map_err(|e| HashError::Cache {
+            operation: CacheOperation::Connection,
+            kind: crate::error::CacheErrorKind::PoolExhausted,
+            context: format!("read pool timeout: {}", e),
+        })?;
+
+        let mut stmt = conn
+            .prepare_cached("SELECT path FROM batch_queue WHERE done = 0 ORDER BY rowid")
+            .with_cache_error(CacheOperation::BatchRead, "failed to prepare statement")?;
+        let paths = stmt
+            .query_map([], |row| {
+                Ok(match row.get_ref(0)? {
+                    // 旧版本以文本保存，其中不含非 UTF-8 的路径
+                    ValueRef::Text(text) => std::str::from_utf8(text).ok().map(PathBuf::from),
+                    ValueRef::Blob(bytes) => Some(path_from_bytes(bytes)),
+                    _ => None,
+                })
+            })
+            .with_cache_error(CacheOperation::BatchRead, "query failed")?
+            .filter_map(|row| row.ok().flatten())
+            .collect();
 
-        Ok(deleted)
+        Ok(paths)
     }
 
-    /// 清空所有缓存
-    pub fn clear_all(&self) -> HashResult<usize> {
+    /// 批处理完成或被放弃时清空队列
+    pub fn clear_batch_queue(&self) -> HashResult<()> {
         let conn = self.write_pool.get().map_err(|e| HashError::Cache {
             operation: CacheOperation::Connection,
             kind: crate::error::CacheErrorKind::PoolExhausted,
             context: format!("write pool timeout: {}", e),
         })?;
 
-        let deleted = conn
-            .execute("DELETE FROM hash_cache", [])
-            .with_cache_error(CacheOperation::Cleanup, "failed to clear all entries")?;
+        conn.execute("DELETE FROM batch_queue", [])
+            .with_cache_error(CacheOperation::Cleanup, "failed to clear batch queue")?;
 
-        Ok(deleted)
+        Ok(())
     }
 
-    /// 使单个缓存条目失效
-    pub fn invalidate_entry(&self, path: &Path) -> HashResult<()> {
+    /// 保存文件备注，空备注删除记录
+    pub fn save_note(&self, path: &Path, note: &str) -> HashResult<()> {
         let normalized_path = self.path_normalizer.normalize(path)?;
         let path_str = normalized_path.to_str().ok_or_else(|| HashError::Cache {
             operation: CacheOperation::PathNormalization,
@@ -504,15 +1753,150 @@ impl HashCachePool {
             context: format!("write pool timeout: {}", e),
         })?;
 
+        let note = note.trim();
+        if note.is_empty() {
+            conn.execute("DELETE FROM notes WHERE path = ?1", params![path_str])
+                .with_cache_error(CacheOperation::BatchWrite, "failed to delete note")?;
+        } else {
+            conn.execute(
+                "INSERT OR REPLACE INTO notes (path, note) VALUES (?1, ?2)",
+                params![path_str, note],
+            )
+            .with_cache_error(CacheOperation::BatchWrite, "failed to save note")?;
+        }
+
+        Ok(())
+    }
+
+    /// 批量读取文件备注，结果以传入的原始路径为键，没有备注的文件不出现在结果中
+    pub fn get_notes(&self, paths: &[&Path]) -> HashResult<HashMap<PathBuf, String>> {
+        let mut result = HashMap::new();
+        if paths.is_empty() {
+            return Ok(result);
+        }
+
+        let conn = self.read_pool.get().map_err(|e| HashError::Cache {
+            operation: CacheOperation::Connection,
+            kind: crate::error::CacheErrorKind::PoolExhausted,
+            context: format!("read pool timeout: {}", e),
+        })?;
+        let mut stmt = conn
+            .prepare_cached("SELECT note FROM notes WHERE path = ?1")
+            .with_cache_error(CacheOperation::BatchRead, "failed to prepare statement")?;
+
+        for path in paths {
+            // 已删除或无法访问的文件没有可恢复的备注
+            let Ok(normalized_path) = self.path_normalizer.normalize(path) else {
+                continue;
+            };
+            let Some(path_str) = normalized_path.to_str() else {
+                continue;
+            };
+            let note: Option<String> = stmt
+                .query_row(params![path_str], |row| row.get(0))
+                .optional()
+                .with_cache_error(CacheOperation::BatchRead, "query failed")?;
+            if let Some(note) = note {
+                result.insert(path.to_path_buf(), note);
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// 使单个缓存条目失效
+    pub fn invalidate_entry(&self, path: &Path) -> HashResult<()> {
+        let normalized_path = self.path_normalizer.normalize(path)?;
+        let Some(path_str) = cache_key(&normalized_path) else {
+            return Ok(());
+        };
+
+        let conn = self.write_pool.get().map_err(|e| HashError::Cache {
+            operation: CacheOperation::Connection,
+            kind: crate::error::CacheErrorKind::PoolExhausted,
+            context: format!("write pool timeout: {}", e),
+        })?;
+
         conn.execute("DELETE FROM hash_cache WHERE path = ?1", params![path_str])
             .with_cache_error(CacheOperation::Cleanup, "failed to invalidate entry")?;
 
         Ok(())
     }
 
+    /// 文件重命名后把缓存条目和备注移到新路径
+    ///
+    /// 原文件已不存在、无法再规范化，`from_normalized` 须在重命名之前取得。
+    pub fn move_entry(&self, from_normalized: &Path, to: &Path) -> HashResult<()> {
+        let to_normalized = self.path_normalizer.normalize(to)?;
+        // 任一侧不是 UTF-8 时原条目不存在或无法移动，留给过期清理
+        let (Some(from_str), Some(to_str)) =
+            (cache_key(from_normalized), cache_key(&to_normalized))
+        else {
+            return Ok(());
+        };
+
+        let mut conn = self.write_pool.get().map_err(|e| HashError::Cache {
+            operation: CacheOperation::Connection,
+            kind: crate::error::CacheErrorKind::PoolExhausted,
+            context: format!("write pool timeout: {}", e),
+        })?;
+
+        let tx = conn
+            .transaction()
+            .with_cache_error(CacheOperation::BatchWrite, "failed to begin transaction")?;
+        for table in ["hash_cache", "notes"] {
+            tx.execute(
+                &format!("UPDATE OR REPLACE {} SET path = ?2 WHERE path = ?1", table),
+                params![from_str, to_str],
+            )
+            .with_cache_error(CacheOperation::BatchWrite, "failed to move entry")?;
+        }
+        tx.commit()
+            .with_cache_error(CacheOperation::BatchWrite, "failed to commit transaction")?;
+
+        Ok(())
+    }
+
+    /// 手动执行 WAL 检查点并把 -wal 文件截断为 0
+    ///
+    /// 自动检查点只会把 WAL 内容写回数据库、复用已有空间，长时间运行后 -wal
+    /// 文件可能保持在峰值大小。WAL 模式下 VACUUM 会把整个数据库写入 WAL，
+    /// 因此后台 VACUUM 结束后也会执行一次截断。
+    /// 返回 false 表示有读取者仍在使用旧快照，只完成了部分检查点。
+    pub fn checkpoint_wal(&self) -> HashResult<bool> {
+        let conn = self.write_pool.get().map_err(|e| HashError::Cache {
+            operation: CacheOperation::Connection,
+            kind: crate::error::CacheErrorKind::PoolExhausted,
+            context: format!("write pool timeout: {}", e),
+        })?;
+
+        checkpoint_truncate(&conn)
+    }
+
+    /// 按计划在空闲时整理数据库，返回是否执行了 VACUUM
+    ///
+    /// 调用方需保证没有进行中的批处理或清理：VACUUM 期间其他写入会被阻塞。
+    pub fn vacuum_if_due(&self, schedule: VacuumSchedule) -> HashResult<bool> {
+        if schedule == VacuumSchedule::Off {
+            return Ok(false);
+        }
+        let last_vacuum = self
+            .get_setting("last_vacuum")?
+            .and_then(|s| s.parse().ok());
+        if !schedule.is_due(self.free_page_ratio()?, last_vacuum, unix_now()) {
+            return Ok(false);
+        }
+        self.vacuum()?;
+        Ok(true)
+    }
+
     /// 检查是否需要 VACUUM
     fn should_vacuum(&self) -> HashResult<bool> {
-        // 检查空闲空间比例
+        Ok(self.free_page_ratio()? > VACUUM_SIZE_THRESHOLD)
+    }
+
+    /// 空闲页占总页数的比例
+    fn free_page_ratio(&self) -> HashResult<f64> {
         let conn = self.read_pool.get().map_err(|e| HashError::Cache {
             operation: CacheOperation::Connection,
             kind: crate::error::CacheErrorKind::PoolExhausted,
@@ -526,44 +1910,55 @@ impl HashCachePool {
             .query_row("PRAGMA freelist_count", [], |r| r.get(0))
             .unwrap_or(0);
 
-        if page_count > 0 && (free_pages as f64 / page_count as f64) > VACUUM_SIZE_THRESHOLD {
-            return Ok(true);
+        if page_count > 0 {
+            Ok(free_pages as f64 / page_count as f64)
+        } else {
+            Ok(0.0)
         }
-
-        Ok(false)
     }
 
-    /// 调度 VACUUM（异步执行）
-    fn schedule_vacuum_if_needed(&self) -> HashResult<()> {
-        if !self.should_vacuum()? {
-            return Ok(());
-        }
-
-        let write_pool = self.write_pool.clone();
+    /// 执行 VACUUM 回收空闲页
+    fn vacuum(&self) -> HashResult<()> {
+        let conn = self.write_pool.get().map_err(|e| HashError::Cache {
+            operation: CacheOperation::Connection,
+            kind: crate::error::CacheErrorKind::PoolExhausted,
+            context: format!("write pool timeout: {}", e),
+        })?;
 
-        thread::spawn(move || {
-            // try_get 返回 Option，需要处理
-            if let Some(conn) = write_pool.try_get() {
-                eprintln!("[Cache] 开始 VACUUM...");
+        log::info!("开始 VACUUM...");
+        conn.execute("VACUUM", [])
+            .with_cache_error(CacheOperation::Cleanup, "VACUUM failed")?;
+        log::info!("VACUUM 完成");
+        conn.execute("ANALYZE", []).ok();
+        // VACUUM 经由 WAL 重写整个数据库，顺带截断 -wal 文件
+        let _ = checkpoint_truncate(&conn);
+        drop(conn);
 
-                match conn.execute("VACUUM", []) {
-                    Ok(_) => {
-                        eprintln!("[Cache] VACUUM 完成");
-                        conn.execute("ANALYZE", []).ok();
-                    }
-                    Err(e) => {
-                        eprintln!("[Cache] VACUUM 失败: {}", e);
-                    }
-                }
-            }
-        });
+        self.save_setting("last_vacuum", &unix_now().to_string())
+    }
 
-        Ok(())
+    /// 直接写入 `count` 条虚构条目（不经过路径规范化），用于测试大量数据
+    #[cfg(test)]
+    pub fn insert_test_entries(&self, count: usize) {
+        let conn = self.write_pool.get().unwrap();
+        conn.execute(
+            "WITH RECURSIVE n(i) AS (SELECT 1 UNION ALL SELECT i + 1 FROM n WHERE i < ?1)
+             INSERT INTO hash_cache (path, file_size, modified_time, cached_at, xxhash3, crc32)
+             SELECT '/test/entry' || i, 1, 1, 1, printf('%032d', i), printf('%08d', i) FROM n",
+            params![count as i64],
+        )
+        .unwrap();
     }
 
-    /// 验证缓存条目与元数据匹配
-    pub fn is_valid_with_metadata(entry: &CacheEntry, file_size: u64, modified_time: u64) -> bool {
-        entry.file_size == file_size && entry.modified_time == modified_time
+    /// 验证缓存条目与元数据匹配，修改时间按容差比较
+    pub fn is_valid_with_metadata(
+        entry: &CacheEntry,
+        file_size: u64,
+        modified_time: u64,
+        mtime_tolerance_ms: u64,
+    ) -> bool {
+        entry.file_size == file_size
+            && modified_time_within(entry.modified_time, modified_time, mtime_tolerance_ms)
     }
 
     /// 验证缓存条目完整性
@@ -572,29 +1967,35 @@ impl HashCachePool {
         computed_xxhash3: &str,
         file_size: u64,
         modified_time: u64,
+        mtime_tolerance_ms: u64,
     ) -> bool {
         if entry.file_size != file_size {
-            eprintln!(
-                "[Cache] 验证失败: 文件大小不匹配 (缓存: {}, 当前: {})",
-                entry.file_size, file_size
+            log::debug!(
+                "验证失败: 文件大小不匹配 (缓存: {}, 当前: {})",
+                entry.file_size,
+                file_size
             );
             return false;
         }
 
-        if entry.modified_time != modified_time {
+        if !modified_time_within(entry.modified_time, modified_time, mtime_tolerance_ms) {
             let (cache_secs, cache_nanos) = parse_modified_time(entry.modified_time);
             let (current_secs, current_nanos) = parse_modified_time(modified_time);
-            eprintln!(
-                "[Cache] 验证失败: 修改时间不匹配 (缓存: {}.{:09}, 当前: {}.{:09})",
-                cache_secs, cache_nanos, current_secs, current_nanos
+            log::debug!(
+                "验证失败: 修改时间不匹配 (缓存: {}.{:09}, 当前: {}.{:09})",
+                cache_secs,
+                cache_nanos,
+                current_secs,
+                current_nanos
             );
             return false;
         }
 
         if entry.xxhash3 != computed_xxhash3 {
-            eprintln!(
-                "[Cache] 验证失败: xxhash3 不匹配 (缓存: {}, 计算: {})",
-                entry.xxhash3, computed_xxhash3
+            log::debug!(
+                "验证失败: xxhash3 不匹配 (缓存: {}, 计算: {})",
+                entry.xxhash3,
+                computed_xxhash3
             );
             return false;
         }
@@ -604,41 +2005,36 @@ impl HashCachePool {
 
     /// 验证哈希格式
     pub fn verify_cached_hashes(&self, entry: &CacheEntry) -> HashResult<bool> {
-        if entry.xxhash3.len() != 32 {
-            return Ok(false);
-        }
-
-        if entry.crc32.len() != 8 {
-            return Ok(false);
-        }
-
-        if entry.md5.len() != 32 {
-            return Ok(false);
-        }
-
-        if entry.sha1.len() != 40 {
-            return Ok(false);
-        }
+        let enabled = self.config.enabled_hashes;
+
+        for algorithm in HashAlgorithm::ALL {
+            let value = entry.hash_value(algorithm);
+            if value.is_empty() {
+                // 当前启用的算法必须存在；旧条目中多余的算法（如已禁用的 SHA1）可以保留
+                if enabled.contains(algorithm) {
+                    return Ok(false);
+                }
+                continue;
+            }
 
-        // 验证十六进制格式
-        if hex::decode(&entry.xxhash3).is_err()
-            || hex::decode(&entry.crc32).is_err()
-            || hex::decode(&entry.md5).is_err()
-            || hex::decode(&entry.sha1).is_err()
-        {
-            return Ok(false);
+            if value.len() != algorithm.hex_len() || hex::decode(value).is_err() {
+                return Ok(false);
+            }
         }
 
         Ok(true)
     }
 
-    /// Getter 方法
-    pub fn get_buffer_size(&self) -> usize {
-        self.config.buffer_size
+    /// 更新运行时配置（供 Worker 读取）
+    pub fn set_config(&mut self, config: CacheConfig) {
+        self.path_normalizer
+            .set_unicode_nfc(config.normalize_unicode_names);
+        self.config = config;
     }
 
-    pub fn get_mmap_chunk_size(&self) -> usize {
-        self.config.mmap_chunk_size
+    /// Getter 方法
+    pub fn config(&self) -> &CacheConfig {
+        &self.config
     }
 
     /// 设置管理
@@ -658,70 +2054,406 @@ impl HashCachePool {
         Ok(())
     }
 
-    pub fn get_setting(&self, key: &str) -> HashResult<Option<String>> {
+    pub fn get_setting(&self, key: &str) -> HashResult<Option<String>> {
+        let conn = self.read_pool.get().map_err(|e| HashError::Cache {
+            operation: CacheOperation::Connection,
+            kind: crate::error::CacheErrorKind::PoolExhausted,
+            context: format!("read pool timeout: {}", e),
+        })?;
+
+        let mut stmt = conn
+            .prepare_cached("SELECT value FROM settings WHERE key = ?1")
+            .with_cache_error(CacheOperation::Connection, "failed to prepare statement")?;
+
+        let result = stmt.query_row(params![key], |row| row.get::<_, String>(0));
+
+        match result {
+            Ok(v) => Ok(Some(v)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(HashError::Cache {
+                operation: CacheOperation::Connection,
+                kind: crate::error::CacheErrorKind::QueryFailed(e.to_string()),
+                context: format!("failed to get setting: {}", key),
+            }),
+        }
+    }
+
+    /// 读取全部设置
+    fn load_settings(&self) -> HashResult<HashMap<String, String>> {
+        let conn = self.read_pool.get().map_err(|e| HashError::Cache {
+            operation: CacheOperation::Connection,
+            kind: crate::error::CacheErrorKind::PoolExhausted,
+            context: format!("read pool timeout: {}", e),
+        })?;
+
+        let mut stmt = conn
+            .prepare_cached("SELECT key, value FROM settings")
+            .with_cache_error(CacheOperation::Connection, "failed to prepare statement")?;
+        let rows = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .with_cache_error(CacheOperation::Connection, "failed to load settings")?;
+        rows.collect::<Result<_, _>>()
+            .with_cache_error(CacheOperation::Connection, "failed to load settings")
+    }
+
+    pub fn save_cache_config(&self, config: &CacheConfig) -> HashResult<()> {
+        for (key, value) in config_settings(config) {
+            self.save_setting(key, &value)?;
+        }
+        Ok(())
+    }
+
+    pub fn load_cache_config(&self) -> HashResult<CacheConfig> {
+        Ok(config_from_settings(&self.load_settings()?))
+    }
+
+    /// 已保存的配置方案名称，按名称排序
+    pub fn profile_names(&self) -> HashResult<Vec<String>> {
+        let conn = self.read_pool.get().map_err(|e| HashError::Cache {
+            operation: CacheOperation::Connection,
+            kind: crate::error::CacheErrorKind::PoolExhausted,
+            context: format!("read pool timeout: {}", e),
+        })?;
+
+        let mut stmt = conn
+            .prepare_cached("SELECT name FROM profiles ORDER BY name")
+            .with_cache_error(CacheOperation::Connection, "failed to prepare statement")?;
+        let rows = stmt
+            .query_map([], |row| row.get(0))
+            .with_cache_error(CacheOperation::Connection, "failed to list profiles")?;
+        rows.collect::<Result<_, _>>()
+            .with_cache_error(CacheOperation::Connection, "failed to list profiles")
+    }
+
+    /// 以 JSON 保存配置方案，同名时覆盖
+    pub fn save_profile(&self, name: &str, config: &CacheConfig) -> HashResult<()> {
+        let conn = self.write_pool.get().map_err(|e| HashError::Cache {
+            operation: CacheOperation::Connection,
+            kind: crate::error::CacheErrorKind::PoolExhausted,
+            context: format!("write pool timeout: {}", e),
+        })?;
+
+        conn.execute(
+            "INSERT OR REPLACE INTO profiles (name, config) VALUES (?1, ?2)",
+            params![name, crate::profiles::to_json(None, config)],
+        )
+        .with_cache_error(CacheOperation::Connection, "failed to save profile")?;
+        Ok(())
+    }
+
+    /// 读取配置方案，不存在或无法解析时返回 None
+    pub fn load_profile(&self, name: &str) -> HashResult<Option<CacheConfig>> {
         let conn = self.read_pool.get().map_err(|e| HashError::Cache {
             operation: CacheOperation::Connection,
             kind: crate::error::CacheErrorKind::PoolExhausted,
             context: format!("read pool timeout: {}", e),
         })?;
 
-        let mut stmt = conn
-            .prepare_cached("SELECT value FROM settings WHERE key = ?1")
-            .with_cache_error(CacheOperation::Connection, "failed to prepare statement")?;
+        let json: Option<String> = conn
+            .query_row(
+                "SELECT config FROM profiles WHERE name = ?1",
+                params![name],
+                |row| row.get(0),
+            )
+            .optional()
+            .with_cache_error(CacheOperation::Connection, "failed to load profile")?;
+        Ok(json
+            .and_then(|json| crate::profiles::from_json(&json))
+            .map(|profile| profile.config))
+    }
 
-        let result = stmt.query_row(params![key], |row| row.get::<_, String>(0));
+    pub fn delete_profile(&self, name: &str) -> HashResult<()> {
+        let conn = self.write_pool.get().map_err(|e| HashError::Cache {
+            operation: CacheOperation::Connection,
+            kind: crate::error::CacheErrorKind::PoolExhausted,
+            context: format!("write pool timeout: {}", e),
+        })?;
 
-        match result {
-            Ok(v) => Ok(Some(v)),
-            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
-            Err(e) => Err(HashError::Cache {
-                operation: CacheOperation::Connection,
-                kind: crate::error::CacheErrorKind::QueryFailed(e.to_string()),
-                context: format!("failed to get setting: {}", key),
-            }),
-        }
+        conn.execute("DELETE FROM profiles WHERE name = ?1", params![name])
+            .with_cache_error(CacheOperation::Connection, "failed to delete profile")?;
+        Ok(())
     }
 
-    fn get_setting_or_default<T: FromStr + Copy>(&self, key: &str, default: T) -> T {
-        self.get_setting(key)
-            .ok()
-            .flatten()
-            .and_then(|s| s.parse().ok())
-            .unwrap_or(default)
+    /// 读取最近添加的路径（最新的在前）
+    pub fn load_recent_paths(&self) -> HashResult<Vec<PathBuf>> {
+        Ok(self
+            .get_setting("recent_paths")?
+            .map(|value| {
+                value
+                    .lines()
+                    .filter(|line| !line.is_empty())
+                    .map(PathBuf::from)
+                    .collect()
+            })
+            .unwrap_or_default())
     }
 
-    pub fn save_cache_config(&self, config: &CacheConfig) -> HashResult<()> {
-        self.save_setting("min_file_size", &config.min_file_size.to_string())?;
-        self.save_setting("retention_days", &config.retention_days.to_string())?;
-        self.save_setting("buffer_size", &config.buffer_size.to_string())?;
-        self.save_setting("mmap_chunk_size", &config.mmap_chunk_size.to_string())?;
-        self.save_setting(
-            "auto_compute_enabled",
-            &config.auto_compute_enabled.to_string(),
-        )?;
-        self.save_setting("uppercase_display", &config.uppercase_display.to_string())?;
-        Ok(())
+    /// 保存最近添加的路径，每行一个
+    pub fn save_recent_paths(&self, paths: &[PathBuf]) -> HashResult<()> {
+        let value = paths
+            .iter()
+            .filter_map(|p| p.to_str())
+            .collect::<Vec<_>>()
+            .join("\n");
+        self.save_setting("recent_paths", &value)
     }
+}
 
-    pub fn load_cache_config(&self) -> HashResult<CacheConfig> {
-        let default = CacheConfig::default();
-
-        Ok(CacheConfig {
-            min_file_size: self.get_setting_or_default("min_file_size", default.min_file_size),
-            retention_days: self.get_setting_or_default("retention_days", default.retention_days),
-            buffer_size: self.get_setting_or_default("buffer_size", default.buffer_size),
-            mmap_chunk_size: self
-                .get_setting_or_default("mmap_chunk_size", default.mmap_chunk_size),
-            auto_compute_enabled: self
-                .get_setting_or_default("auto_compute_enabled", default.auto_compute_enabled),
-            uppercase_display: self
-                .get_setting_or_default("uppercase_display", default.uppercase_display),
-        })
+/// 配置中各项的设置键与保存的值，`settings` 表与配置方案共用
+pub(crate) fn config_settings(config: &CacheConfig) -> Vec<(&'static str, String)> {
+    vec![
+        ("min_file_size", config.min_file_size.to_string()),
+        ("retention_days", config.retention_days.to_string()),
+        ("performance_mode", config.performance_mode.to_string()),
+        ("buffer_size", config.buffer_size.to_string()),
+        ("mmap_chunk_size", config.mmap_chunk_size.to_string()),
+        ("auto_compute_mode", config.auto_compute.to_string()),
+        ("uppercase_display", config.uppercase_display.to_string()),
+        ("hash_encoding", config.hash_encoding.to_string()),
+        (
+            "network_mmap_enabled",
+            config.network_mmap_enabled.to_string(),
+        ),
+        ("scan_batch_size", config.scan_batch_size.to_string()),
+        ("scan_flush_ms", config.scan_flush_ms.to_string()),
+        ("scan_threads", config.scan_threads.to_string()),
+        ("minimize_to_tray", config.minimize_to_tray.to_string()),
+        ("notify_on_complete", config.notify_on_complete.to_string()),
+        (
+            "notify_threshold_secs",
+            config.notify_threshold_secs.to_string(),
+        ),
+        ("quick_prescreen", config.quick_prescreen.to_string()),
+        ("mtime_tolerance_ms", config.mtime_tolerance_ms.to_string()),
+        ("visible_columns", config.visible_columns.to_string()),
+        ("column_widths", config.column_widths.to_string()),
+        (
+            "normalize_unicode_names",
+            config.normalize_unicode_names.to_string(),
+        ),
+        ("recent_limit", config.recent_limit.to_string()),
+        ("enabled_hashes", config.enabled_hashes.to_string()),
+        ("xxh3_only_mode", config.xxh3_only_mode.to_string()),
+        (
+            "progress_file_cost_kb",
+            config.progress_file_cost_kb.to_string(),
+        ),
+        (
+            "progress_by_file_count",
+            config.progress_by_file_count.to_string(),
+        ),
+        ("max_file_size", config.max_file_size.to_string()),
+        (
+            "skip_removable_cache",
+            config.skip_removable_cache.to_string(),
+        ),
+        ("log_level", config.log_level.to_string()),
+        ("sync_mode", config.sync_mode.to_string()),
+        ("hash_block_devices", config.hash_block_devices.to_string()),
+        ("prevent_sleep", config.prevent_sleep.to_string()),
+        (
+            "confirm_clear_queue",
+            config.confirm_clear_queue.to_string(),
+        ),
+        ("verify_sidecars", config.verify_sidecars.to_string()),
+        ("compute_order", config.compute_order.to_string()),
+        ("serial_compute", config.serial_compute.to_string()),
+        ("write_xattrs", config.write_xattrs.to_string()),
+        (
+            "hash_alternate_streams",
+            config.hash_alternate_streams.to_string(),
+        ),
+        ("disk_concurrency", config.disk_concurrency.to_string()),
+        ("vacuum_schedule", config.vacuum_schedule.to_string()),
+        ("size_unit", config.size_unit.to_string()),
+        ("ui_language", config.ui_language.to_string()),
+        ("symlink_policy", config.symlink_policy.to_string()),
+        ("sparse_files", config.sparse_files.to_string()),
+        ("copy_feedback", config.copy_feedback.to_string()),
+        ("copy_toast_ms", config.copy_toast_ms.to_string()),
+        (
+            "max_messages_per_frame",
+            config.max_messages_per_frame.to_string(),
+        ),
+        (
+            "ui_channel_capacity",
+            config.ui_channel_capacity.to_string(),
+        ),
+        ("excluded_dirs", config.excluded_dirs.join("\n")),
+        ("update_check", config.update_check.to_string()),
+        ("update_manifest_url", config.update_manifest_url.clone()),
+        ("cache_validation", config.cache_validation.to_string()),
+        ("hash_timing", config.hash_timing.to_string()),
+    ]
+}
+
+/// 从设置键值中读取配置，缺少或无法解析的项使用默认值
+pub(crate) fn config_from_settings(settings: &HashMap<String, String>) -> CacheConfig {
+    let default = CacheConfig::default();
+    // 旧版本只有自动计算的开关
+    let legacy_auto_compute = if setting_or(settings, "auto_compute_enabled", true) {
+        default.auto_compute
+    } else {
+        AutoComputeMode::Manual
+    };
+    // 旧版本只有修改时间列的开关
+    let mut legacy_columns = default.visible_columns;
+    legacy_columns.set(
+        TableColumn::Modified,
+        setting_or(settings, "show_modified_column", false),
+    );
+
+    let buffer_size = setting_or(settings, "buffer_size", default.buffer_size);
+    let mmap_chunk_size = setting_or(settings, "mmap_chunk_size", default.mmap_chunk_size);
+    // 旧版本未保存模式，按大小反推
+    let performance_mode = setting_or(
+        settings,
+        "performance_mode",
+        PerformanceMode::from_sizes(buffer_size, mmap_chunk_size),
+    );
+
+    let mut config = CacheConfig {
+        min_file_size: setting_or(settings, "min_file_size", default.min_file_size),
+        retention_days: setting_or(settings, "retention_days", default.retention_days),
+        performance_mode,
+        buffer_size,
+        mmap_chunk_size,
+        auto_compute: setting_or(settings, "auto_compute_mode", legacy_auto_compute),
+        uppercase_display: setting_or(settings, "uppercase_display", default.uppercase_display),
+        hash_encoding: setting_or(settings, "hash_encoding", default.hash_encoding),
+        network_mmap_enabled: setting_or(
+            settings,
+            "network_mmap_enabled",
+            default.network_mmap_enabled,
+        ),
+        scan_batch_size: setting_or(settings, "scan_batch_size", default.scan_batch_size),
+        scan_flush_ms: setting_or(settings, "scan_flush_ms", default.scan_flush_ms),
+        scan_threads: setting_or(settings, "scan_threads", default.scan_threads),
+        minimize_to_tray: setting_or(settings, "minimize_to_tray", default.minimize_to_tray),
+        notify_on_complete: setting_or(settings, "notify_on_complete", default.notify_on_complete),
+        notify_threshold_secs: setting_or(
+            settings,
+            "notify_threshold_secs",
+            default.notify_threshold_secs,
+        ),
+        quick_prescreen: setting_or(settings, "quick_prescreen", default.quick_prescreen),
+        mtime_tolerance_ms: setting_or(settings, "mtime_tolerance_ms", default.mtime_tolerance_ms),
+        visible_columns: setting_or(settings, "visible_columns", legacy_columns),
+        column_widths: setting_or(settings, "column_widths", default.column_widths),
+        normalize_unicode_names: setting_or(
+            settings,
+            "normalize_unicode_names",
+            default.normalize_unicode_names,
+        ),
+        recent_limit: setting_or(settings, "recent_limit", default.recent_limit),
+        enabled_hashes: setting_or(settings, "enabled_hashes", default.enabled_hashes),
+        xxh3_only_mode: setting_or(settings, "xxh3_only_mode", default.xxh3_only_mode),
+        progress_file_cost_kb: setting_or(
+            settings,
+            "progress_file_cost_kb",
+            default.progress_file_cost_kb,
+        ),
+        progress_by_file_count: setting_or(
+            settings,
+            "progress_by_file_count",
+            default.progress_by_file_count,
+        ),
+        max_file_size: setting_or(settings, "max_file_size", default.max_file_size),
+        skip_removable_cache: setting_or(
+            settings,
+            "skip_removable_cache",
+            default.skip_removable_cache,
+        ),
+        log_level: setting_or(settings, "log_level", default.log_level),
+        sync_mode: setting_or(settings, "sync_mode", default.sync_mode),
+        hash_block_devices: setting_or(settings, "hash_block_devices", default.hash_block_devices),
+        prevent_sleep: setting_or(settings, "prevent_sleep", default.prevent_sleep),
+        confirm_clear_queue: setting_or(
+            settings,
+            "confirm_clear_queue",
+            default.confirm_clear_queue,
+        ),
+        verify_sidecars: setting_or(settings, "verify_sidecars", default.verify_sidecars),
+        compute_order: setting_or(settings, "compute_order", default.compute_order),
+        serial_compute: setting_or(settings, "serial_compute", default.serial_compute),
+        write_xattrs: setting_or(settings, "write_xattrs", default.write_xattrs),
+        hash_alternate_streams: setting_or(
+            settings,
+            "hash_alternate_streams",
+            default.hash_alternate_streams,
+        ),
+        disk_concurrency: setting_or(settings, "disk_concurrency", default.disk_concurrency),
+        vacuum_schedule: setting_or(settings, "vacuum_schedule", default.vacuum_schedule),
+        size_unit: setting_or(settings, "size_unit", default.size_unit),
+        ui_language: setting_or(settings, "ui_language", default.ui_language),
+        symlink_policy: setting_or(settings, "symlink_policy", default.symlink_policy),
+        sparse_files: setting_or(settings, "sparse_files", default.sparse_files),
+        copy_feedback: setting_or(settings, "copy_feedback", default.copy_feedback),
+        copy_toast_ms: setting_or(settings, "copy_toast_ms", default.copy_toast_ms),
+        max_messages_per_frame: setting_or(
+            settings,
+            "max_messages_per_frame",
+            default.max_messages_per_frame,
+        ),
+        ui_channel_capacity: setting_or(
+            settings,
+            "ui_channel_capacity",
+            default.ui_channel_capacity,
+        ),
+        // 保存为空表示用户清空了列表，只有从未保存时才使用默认值
+        excluded_dirs: settings
+            .get("excluded_dirs")
+            .map_or(default.excluded_dirs, |value| parse_dir_names(value)),
+        update_check: setting_or(settings, "update_check", default.update_check),
+        update_manifest_url: settings
+            .get("update_manifest_url")
+            .filter(|url| !url.trim().is_empty())
+            .map_or(default.update_manifest_url, String::clone),
+        cache_validation: setting_or(settings, "cache_validation", default.cache_validation),
+        hash_timing: setting_or(settings, "hash_timing", default.hash_timing),
+    };
+    config.set_performance_mode(performance_mode);
+    config
+}
+
+fn setting_or<T: FromStr>(settings: &HashMap<String, String>, key: &str, default: T) -> T {
+    settings
+        .get(key)
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(default)
+}
+
+/// 将新添加的路径移到最近列表最前面，去重并截断到 limit
+pub fn push_recent_paths(recent: &mut Vec<PathBuf>, added: &[PathBuf], limit: usize) {
+    for path in added.iter().rev() {
+        recent.retain(|p| p != path);
+        recent.insert(0, path.clone());
     }
+    recent.truncate(limit);
+}
+
+/// 执行 `wal_checkpoint(TRUNCATE)`，返回检查点是否完整完成
+fn checkpoint_truncate(conn: &Connection) -> HashResult<bool> {
+    let busy: i64 = conn
+        .query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |row| row.get(0))
+        .with_cache_error(CacheOperation::Cleanup, "failed to checkpoint WAL")?;
+    Ok(busy == 0)
+}
+
+/// 当前 Unix 时间（秒），系统时间早于 1970 年时为 0
+pub fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
 }
 
 pub fn get_file_modified_time(path: &Path) -> HashResult<u64> {
-    let metadata = fs::metadata(path).with_path(path)?;
+    let metadata = with_long_path(path, |p| fs::metadata(p)).with_path(path)?;
+    metadata_modified_time(&metadata, path)
+}
+
+/// 从已读取的元数据中取修改时间，格式同 [`get_file_modified_time`]
+pub fn metadata_modified_time(metadata: &fs::Metadata, path: &Path) -> HashResult<u64> {
     let time = metadata.modified().with_path(path)?;
     let duration = time.duration_since(UNIX_EPOCH).map_err(|_| {
         HashError::Io(
@@ -742,6 +2474,19 @@ pub fn parse_modified_time(combined: u64) -> (u64, u32) {
     (secs, nanos)
 }
 
+/// 判断两个修改时间是否在容差范围内，容差为 0 时要求完全一致
+pub fn modified_time_within(a: u64, b: u64, tolerance_ms: u64) -> bool {
+    if tolerance_ms == 0 {
+        return a == b;
+    }
+
+    let to_nanos = |combined: u64| {
+        let (secs, nanos) = parse_modified_time(combined);
+        u128::from(secs) * 1_000_000_000 + u128::from(nanos)
+    };
+    to_nanos(a).abs_diff(to_nanos(b)) <= u128::from(tolerance_ms) * 1_000_000
+}
+
 // 为了保持向后兼容，保留旧的别名
 pub use HashCachePool as HashCache;
 
@@ -782,6 +2527,28 @@ mod tests {
         let _ = normalizer.normalize(test_path).unwrap();
     }
 
+    #[test]
+    fn test_nfc_path_normalization() {
+        let nfd = Path::new("/data/cafe\u{301}/re\u{301}sume\u{301}.txt");
+        let nfc = Path::new("/data/caf\u{e9}/r\u{e9}sum\u{e9}.txt");
+        assert_ne!(nfd, nfc);
+        assert_eq!(nfc_path(nfd), nfc);
+        assert_eq!(nfc_path(nfc), nfc);
+
+        // 开启后，NFD 文件名的真实文件得到与 NFC 相同的缓存键
+        let temp = TempDir::new().unwrap();
+        let nfd_file = temp.path().join("cafe\u{301}.txt");
+        std::fs::write(&nfd_file, b"test").unwrap();
+
+        let normalizer = PathNormalizer::new();
+        let raw = normalizer.normalize(&nfd_file).unwrap();
+        normalizer.set_unicode_nfc(true);
+        let key = normalizer.normalize(&nfd_file).unwrap();
+
+        assert_eq!(key.file_name(), Some(std::ffi::OsStr::new("caf\u{e9}.txt")));
+        assert_eq!(key.parent(), raw.parent().map(nfc_path).as_deref());
+    }
+
     #[test]
     fn test_batch_save_and_query() {
         let (pool, temp) = create_test_pool().unwrap();
@@ -806,6 +2573,9 @@ mod tests {
                     crc32: format!("{:08x}", i),   // 8字符十六进制
                     md5: format!("{:032}", i),     // 32字符十六进制
                     sha1: format!("{:040}", i),    // 40字符十六进制
+                    sha256: String::new(),
+                    crc64: String::new(),
+                    adler32: String::new(),
                 }
             })
             .collect();
@@ -844,6 +2614,9 @@ mod tests {
             crc32: "01234567".to_string(),
             md5: "0123456789abcdef0123456789abcdef".to_string(),
             sha1: "0123456789abcdef0123456789abcdef01234567".to_string(),
+            sha256: String::new(),
+            crc64: String::new(),
+            adler32: String::new(),
         };
 
         let saved = pool.save_entries_batch(&[invalid_entry]).unwrap();
@@ -878,12 +2651,24 @@ mod tests {
             crc32: format!("{:08x}", 1),
             md5: format!("{:032}", 1),
             sha1: format!("{:040}", 1),
+            sha256: String::new(),
+            crc64: String::new(),
+            adler32: String::new(),
         };
 
         pool.save_entries_batch(&[old_entry]).unwrap();
 
-        let deleted = pool.cleanup_expired().unwrap();
-        assert!(deleted > 0);
+        let outcome = pool
+            .cleanup(CleanupKind::Expired, &AtomicBool::new(false), |_| {})
+            .unwrap();
+        assert!(outcome.deleted > 0);
+
+        // 界面中的过期标记与清理使用相同的界限
+        let mut config = pool.config.clone();
+        assert!(config.is_past_retention(now - 40 * 86400, now));
+        assert!(!config.is_past_retention(now - 20 * 86400, now));
+        config.retention_days = 0;
+        assert!(!config.is_past_retention(1, now));
     }
 
     #[test]
@@ -896,6 +2681,9 @@ mod tests {
             crc32: "01234567".to_string(),
             md5: "0123456789abcdef0123456789abcdef".to_string(),
             sha1: "0123456789abcdef0123456789abcdef01234567".to_string(),
+            sha256: String::new(),
+            crc64: String::new(),
+            adler32: String::new(),
             cached_at: 1_234_567_890,
         };
 
@@ -904,7 +2692,8 @@ mod tests {
             &entry,
             "0123456789abcdef0123456789abcdef",
             2048,
-            12345
+            12345,
+            0
         ));
 
         // 测试完全匹配
@@ -912,10 +2701,469 @@ mod tests {
             &entry,
             "0123456789abcdef0123456789abcdef",
             1024,
-            12345
+            12345,
+            0
+        ));
+    }
+
+    #[test]
+    fn test_mtime_tolerance_copied_file() {
+        let original_mtime = (1_700_000_000u64 << 32) | 500;
+        let copied_mtime = (1_700_000_000u64 << 32) | 501;
+        let entry = CacheEntry {
+            path: PathBuf::from("/test/file"),
+            file_size: 1024,
+            modified_time: original_mtime,
+            xxhash3: "0123456789abcdef0123456789abcdef".to_string(),
+            crc32: "01234567".to_string(),
+            md5: "0123456789abcdef0123456789abcdef".to_string(),
+            sha1: "0123456789abcdef0123456789abcdef01234567".to_string(),
+            sha256: String::new(),
+            crc64: String::new(),
+            adler32: String::new(),
+            cached_at: 1_234_567_890,
+        };
+
+        // 严格模式下 1ns 差异即视为失效
+        assert!(!HashCachePool::is_valid_with_metadata(
+            &entry,
+            1024,
+            copied_mtime,
+            0
+        ));
+
+        // 容差模式下复制后的文件仍命中缓存
+        assert!(HashCachePool::is_valid_with_metadata(
+            &entry,
+            1024,
+            copied_mtime,
+            2000
+        ));
+        assert!(HashCachePool::validate_cache_integrity(
+            &entry,
+            "0123456789abcdef0123456789abcdef",
+            1024,
+            copied_mtime,
+            2000
+        ));
+
+        // FAT32 的 2 秒精度在容差内，超出容差则失效
+        let fat_mtime = 1_700_000_002u64 << 32;
+        assert!(HashCachePool::is_valid_with_metadata(
+            &entry, 1024, fat_mtime, 2000
+        ));
+        let stale_mtime = 1_700_000_003u64 << 32;
+        assert!(!HashCachePool::is_valid_with_metadata(
+            &entry,
+            1024,
+            stale_mtime,
+            2000
         ));
     }
 
+    #[test]
+    fn test_recent_paths_roundtrip() {
+        let (pool, _temp) = create_test_pool().unwrap();
+        assert!(pool.load_recent_paths().unwrap().is_empty());
+
+        let mut recent = Vec::new();
+        push_recent_paths(
+            &mut recent,
+            &[PathBuf::from("/downloads"), PathBuf::from("/iso/a.iso")],
+            3,
+        );
+        push_recent_paths(
+            &mut recent,
+            &[PathBuf::from("/music"), PathBuf::from("/downloads")],
+            3,
+        );
+        assert_eq!(
+            recent,
+            vec![
+                PathBuf::from("/music"),
+                PathBuf::from("/downloads"),
+                PathBuf::from("/iso/a.iso"),
+            ]
+        );
+
+        push_recent_paths(&mut recent, &[PathBuf::from("/new")], 3);
+        assert_eq!(recent.len(), 3);
+        assert_eq!(recent[0], PathBuf::from("/new"));
+
+        pool.save_recent_paths(&recent).unwrap();
+        assert_eq!(pool.load_recent_paths().unwrap(), recent);
+    }
+
+    #[test]
+    fn test_cached_hashes_tolerate_disabled_sha1() {
+        let (mut pool, _temp) = create_test_pool().unwrap();
+        let legacy = CacheEntry {
+            path: PathBuf::from("/test/file"),
+            file_size: 1024,
+            modified_time: 12345,
+            xxhash3: "0123456789abcdef0123456789abcdef".to_string(),
+            crc32: "01234567".to_string(),
+            md5: "0123456789abcdef0123456789abcdef".to_string(),
+            sha1: "0123456789abcdef0123456789abcdef01234567".to_string(),
+            sha256: String::new(),
+            crc64: String::new(),
+            adler32: String::new(),
+            cached_at: 1_234_567_890,
+        };
+        assert!(pool.verify_cached_hashes(&legacy).unwrap());
+
+        // 禁用 SHA1 后旧条目仍然有效
+        let mut config = pool.config().clone();
+        config.enabled_hashes.sha1 = false;
+        pool.set_config(config.clone());
+        assert!(pool.verify_cached_hashes(&legacy).unwrap());
+
+        // 启用 SHA256 后缺少 SHA256 的旧条目需要重新计算
+        config.enabled_hashes.sha256 = true;
+        pool.set_config(config);
+        assert!(!pool.verify_cached_hashes(&legacy).unwrap());
+    }
+
+    #[test]
+    fn test_save_entry_without_sha1() {
+        let (pool, temp) = create_test_pool().unwrap();
+        let path = temp.path().join("no_sha1.bin");
+        std::fs::write(&path, b"content").unwrap();
+
+        let entry = CacheEntry {
+            path: path.clone(),
+            file_size: 7,
+            modified_time: 1,
+            xxhash3: format!("{:032}", 1),
+            crc32: format!("{:08x}", 1),
+            md5: format!("{:032}", 1),
+            sha1: String::new(),
+            sha256: format!("{:064}", 1),
+            crc64: String::new(),
+            adler32: String::new(),
+            cached_at: 1,
+        };
+        assert_eq!(pool.save_entries_batch(&[entry]).unwrap(), 1);
+
+        let loaded = pool.get_by_paths_batch(&[path.as_path()]).unwrap();
+        let loaded = loaded.get(&path).cloned().flatten().unwrap();
+        assert!(loaded.sha1.is_empty());
+        assert_eq!(loaded.sha256, format!("{:064}", 1));
+    }
+
+    #[test]
+    fn test_crc64_adler32_persistence() {
+        let (pool, temp) = create_test_pool().unwrap();
+        let path = temp.path().join("archive.gz");
+        std::fs::write(&path, b"content").unwrap();
+
+        let mut entry = CacheEntry {
+            path: path.clone(),
+            file_size: 7,
+            modified_time: 1,
+            xxhash3: format!("{:032}", 1),
+            crc32: format!("{:08x}", 1),
+            md5: String::new(),
+            sha1: String::new(),
+            sha256: String::new(),
+            crc64: "995dc9bbdf1939fa".to_string(),
+            adler32: "091e01de".to_string(),
+            cached_at: 1,
+        };
+        assert_eq!(pool.save_entries_batch(&[entry.clone()]).unwrap(), 1);
+
+        let loaded = pool.get_by_paths_batch(&[path.as_path()]).unwrap();
+        let loaded = loaded.get(&path).cloned().flatten().unwrap();
+        assert_eq!(loaded.crc64, "995dc9bbdf1939fa");
+        assert_eq!(loaded.adler32, "091e01de");
+
+        // CHECK 约束拒绝长度不符的值
+        entry.crc64 = "091e01de".to_string();
+        assert_eq!(pool.save_entries_batch(&[entry.clone()]).unwrap(), 0);
+        entry.crc64 = String::new();
+        entry.adler32 = "995dc9bbdf1939fa".to_string();
+        assert_eq!(pool.save_entries_batch(&[entry]).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_migrate_v3_schema() {
+        let temp = TempDir::new().unwrap();
+        let db_path = temp.path().join("v3.db");
+        {
+            let conn = Connection::open(&db_path).unwrap();
+            conn.execute_batch(
+                "CREATE TABLE metadata (key TEXT PRIMARY KEY, value TEXT);
+                 INSERT INTO metadata (key, value) VALUES ('version', '3');
+                 CREATE TABLE hash_cache (
+                    path TEXT NOT NULL PRIMARY KEY,
+                    file_size INTEGER NOT NULL CHECK(file_size > 0),
+                    modified_time INTEGER NOT NULL CHECK(modified_time >= 0),
+                    cached_at INTEGER NOT NULL CHECK(cached_at > 0),
+                    xxhash3 TEXT NOT NULL CHECK(length(xxhash3) = 32),
+                    crc32 TEXT NOT NULL CHECK(length(crc32) = 8),
+                    md5 TEXT NOT NULL CHECK(length(md5) = 32),
+                    sha1 TEXT NOT NULL CHECK(length(sha1) = 40)
+                 ) WITHOUT ROWID;",
+            )
+            .unwrap();
+            conn.execute(
+                "INSERT INTO hash_cache VALUES (?1, 7, 1, 1, ?2, ?3, ?4, ?5)",
+                params![
+                    "/legacy",
+                    format!("{:032}", 1),
+                    format!("{:08x}", 1),
+                    format!("{:032}", 2),
+                    format!("{:040}", 3)
+                ],
+            )
+            .unwrap();
+        }
+
+        let pool = HashCachePool::new(&db_path, CacheConfig::default()).unwrap();
+        let conn = pool.read_pool.get().unwrap();
+        let (sha1, sha256): (String, Option<String>) = conn
+            .query_row(
+                "SELECT sha1, sha256 FROM hash_cache WHERE path = '/legacy'",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(sha1, format!("{:040}", 3));
+        assert_eq!(sha256, None);
+    }
+
+    #[test]
+    fn test_migrate_v4_schema() {
+        let temp = TempDir::new().unwrap();
+        let db_path = temp.path().join("v4.db");
+        {
+            let conn = Connection::open(&db_path).unwrap();
+            conn.execute_batch(
+                "CREATE TABLE metadata (key TEXT PRIMARY KEY, value TEXT);
+                 INSERT INTO metadata (key, value) VALUES ('version', '4');
+                 CREATE TABLE hash_cache (
+                    path TEXT NOT NULL PRIMARY KEY,
+                    file_size INTEGER NOT NULL CHECK(file_size > 0),
+                    modified_time INTEGER NOT NULL CHECK(modified_time >= 0),
+                    cached_at INTEGER NOT NULL CHECK(cached_at > 0),
+                    xxhash3 TEXT NOT NULL CHECK(length(xxhash3) = 32),
+                    crc32 TEXT NOT NULL CHECK(length(crc32) = 8),
+                    md5 TEXT CHECK(md5 IS NULL OR length(md5) = 32),
+                    sha1 TEXT CHECK(sha1 IS NULL OR length(sha1) = 40),
+                    sha256 TEXT CHECK(sha256 IS NULL OR length(sha256) = 64)
+                 ) WITHOUT ROWID;",
+            )
+            .unwrap();
+            conn.execute(
+                "INSERT INTO hash_cache VALUES ('/v4', 7, 1, 1, ?1, ?2, NULL, NULL, ?3)",
+                params![
+                    format!("{:032}", 1),
+                    format!("{:08x}", 1),
+                    format!("{:064}", 4)
+                ],
+            )
+            .unwrap();
+        }
+
+        let pool = HashCachePool::new(&db_path, CacheConfig::default()).unwrap();
+        let conn = pool.read_pool.get().unwrap();
+        let (sha256, crc64, adler32): (String, Option<String>, Option<String>) = conn
+            .query_row(
+                "SELECT sha256, crc64, adler32 FROM hash_cache WHERE path = '/v4'",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .unwrap();
+        assert_eq!(sha256, format!("{:064}", 4));
+        assert_eq!((crc64, adler32), (None, None));
+    }
+
+    #[test]
+    fn test_batch_queue_resume() {
+        let (pool, _temp) = create_test_pool().unwrap();
+        let paths: Vec<PathBuf> = (0..5)
+            .map(|i| PathBuf::from(format!("/batch/file{}.bin", i)))
+            .collect();
+
+        // 模拟批处理进行到一半时崩溃
+        pool.enqueue_batch(&paths).unwrap();
+        pool.mark_batch_done(&paths[..2]).unwrap();
+
+        // 重启后只恢复未完成的文件，且保持原顺序
+        let pending = pool.load_pending_batch().unwrap();
+        assert_eq!(pending, paths[2..].to_vec());
+
+        // 新批处理与未完成的队列合并，已完成的文件重新加入时恢复为未完成
+        let extra = PathBuf::from("/batch/extra.bin");
+        pool.enqueue_batch(&[paths[0].clone(), extra.clone()])
+            .unwrap();
+        let mut expected = vec![paths[0].clone()];
+        expected.extend_from_slice(&paths[2..]);
+        expected.push(extra);
+        assert_eq!(pool.load_pending_batch().unwrap(), expected);
+
+        pool.clear_batch_queue().unwrap();
+        assert!(pool.load_pending_batch().unwrap().is_empty());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_batch_queue_keeps_non_utf8_paths() {
+        use std::os::unix::ffi::OsStrExt;
+
+        let (pool, _temp) = create_test_pool().unwrap();
+        // Latin-1 编码的 café.bin
+        let legacy = PathBuf::from(std::ffi::OsStr::from_bytes(b"/batch/caf\xe9.bin"));
+        let utf8 = PathBuf::from("/batch/café.bin");
+        pool.enqueue_batch(&[legacy.clone(), utf8.clone()]).unwrap();
+        assert_eq!(pool.load_pending_batch().unwrap(), [legacy.clone(), utf8]);
+
+        pool.mark_batch_done(std::slice::from_ref(&legacy)).unwrap();
+        assert_eq!(pool.load_pending_batch().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_notes_round_trip() {
+        let (pool, temp) = create_test_pool().unwrap();
+        let file = temp.path().join("note.txt");
+        fs::write(&file, b"note").unwrap();
+        // 同一文件的另一种写法，按规范化路径匹配
+        let alias = temp.path().join(".").join("note.txt");
+        let missing = temp.path().join("missing.txt");
+
+        pool.save_note(&file, "  发布版本  ").unwrap();
+        let notes = pool.get_notes(&[&alias, &missing]).unwrap();
+        assert_eq!(notes.len(), 1);
+        assert_eq!(notes[&alias], "发布版本");
+
+        // 清空备注即删除
+        pool.save_note(&alias, "").unwrap();
+        assert!(pool.get_notes(&[&file]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_move_entry_follows_rename() {
+        let (pool, temp) = create_test_pool().unwrap();
+        let from = temp.path().join("before.txt");
+        let to = temp.path().join("after.txt");
+        fs::write(&from, b"move").unwrap();
+        pool.save_entries_batch(&[CacheEntry {
+            path: from.clone(),
+            file_size: 4,
+            modified_time: 12345,
+            cached_at: 67890,
+            xxhash3: format!("{:032}", 1),
+            crc32: format!("{:08x}", 1),
+            md5: format!("{:032}", 1),
+            sha1: format!("{:040}", 1),
+            sha256: String::new(),
+            crc64: String::new(),
+            adler32: String::new(),
+        }])
+        .unwrap();
+        pool.save_note(&from, "保留").unwrap();
+
+        let from_normalized = pool.path_normalizer.normalize(&from).unwrap();
+        fs::rename(&from, &to).unwrap();
+        pool.move_entry(&from_normalized, &to).unwrap();
+
+        let entries = pool.get_by_paths_batch(&[to.as_path()]).unwrap();
+        assert!(entries[&to].is_some());
+        assert_eq!(pool.get_notes(&[&to]).unwrap()[&to], "保留");
+    }
+
+    #[test]
+    fn test_concurrent_saves_lose_no_entries() {
+        const THREADS: usize = 8;
+        const BATCHES: usize = 10;
+        const PER_BATCH: usize = 20;
+
+        let temp = TempDir::new().unwrap();
+        let db_path = temp.path().join("test.db");
+        // 两个独立的连接池，模拟多个写入方争用同一数据库
+        let pools: Vec<Arc<HashCachePool>> = (0..2)
+            .map(|_| Arc::new(HashCachePool::new(&db_path, CacheConfig::default()).unwrap()))
+            .collect();
+
+        // 路径规范化需要文件真实存在
+        let file_path =
+            |t: usize, b: usize, i: usize| temp.path().join(format!("t{}_b{}_{}.bin", t, b, i));
+        for t in 0..THREADS {
+            for b in 0..BATCHES {
+                for i in 0..PER_BATCH {
+                    fs::write(file_path(t, b, i), b"x").unwrap();
+                }
+            }
+        }
+
+        let handles: Vec<_> = (0..THREADS)
+            .map(|t| {
+                let pool = pools[t % pools.len()].clone();
+                let paths: Vec<Vec<PathBuf>> = (0..BATCHES)
+                    .map(|b| (0..PER_BATCH).map(|i| file_path(t, b, i)).collect())
+                    .collect();
+                thread::spawn(move || {
+                    for batch in paths {
+                        let entries: Vec<CacheEntry> = batch
+                            .into_iter()
+                            .enumerate()
+                            .map(|(i, path)| CacheEntry {
+                                path,
+                                file_size: 1,
+                                modified_time: 1,
+                                cached_at: 1,
+                                xxhash3: format!("{:032x}", i),
+                                crc32: format!("{:08x}", i),
+                                md5: format!("{:032x}", i),
+                                sha1: format!("{:040x}", i),
+                                sha256: String::new(),
+                                crc64: String::new(),
+                                adler32: String::new(),
+                            })
+                            .collect();
+                        assert_eq!(pool.save_entries_batch(&entries).unwrap(), PER_BATCH);
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        let conn = Connection::open(&db_path).unwrap();
+        let count: i64 = conn
+            .query_row("SELECT COUNT(*) FROM hash_cache", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(count as usize, THREADS * BATCHES * PER_BATCH);
+    }
+
+    #[test]
+    fn test_checkpoint_truncates_wal() {
+        let (pool, temp) = create_test_pool().unwrap();
+        let path = temp.path().join("file.bin");
+        fs::write(&path, b"data").unwrap();
+        pool.save_entries_batch(&[CacheEntry {
+            path,
+            file_size: 4,
+            modified_time: 1,
+            cached_at: 1,
+            xxhash3: format!("{:032}", 1),
+            crc32: format!("{:08}", 1),
+            md5: format!("{:032}", 1),
+            sha1: format!("{:040}", 1),
+            sha256: String::new(),
+            crc64: String::new(),
+            adler32: String::new(),
+        }])
+        .unwrap();
+
+        let wal_path = temp.path().join("test.db-wal");
+        assert!(fs::metadata(&wal_path).unwrap().len() > 0);
+
+        assert!(pool.checkpoint_wal().unwrap());
+        assert_eq!(fs::metadata(&wal_path).unwrap().len(), 0);
+    }
+
     #[test]
     fn test_settings() {
         let (pool, _temp) = create_test_pool().unwrap();
@@ -939,4 +3187,209 @@ mod tests {
         assert_eq!(loaded.min_file_size, 2048 * 1024);
         assert_eq!(loaded.retention_days, 60);
     }
+
+    #[test]
+    fn test_performance_mode_round_trips() {
+        let (pool, _temp) = create_test_pool().unwrap();
+
+        let mut config = CacheConfig::default();
+        config.set_performance_mode(PerformanceMode::HighPerformance);
+        pool.save_cache_config(&config).unwrap();
+        let loaded = pool.load_cache_config().unwrap();
+        assert_eq!(loaded.performance_mode, PerformanceMode::HighPerformance);
+        assert_eq!(loaded.buffer_size, 1024 * 1024);
+        assert_eq!(loaded.mmap_chunk_size, 16 * 1024 * 1024);
+
+        // 手动调整的大小原样保留
+        config.set_performance_mode(PerformanceMode::Custom);
+        config.buffer_size = 2 * 1024 * 1024;
+        pool.save_cache_config(&config).unwrap();
+        let loaded = pool.load_cache_config().unwrap();
+        assert_eq!(loaded.performance_mode, PerformanceMode::Custom);
+        assert_eq!(loaded.buffer_size, 2 * 1024 * 1024);
+        assert_eq!(loaded.mmap_chunk_size, 16 * 1024 * 1024);
+
+        // 旧版本只保存了大小
+        pool.save_setting("performance_mode", "").unwrap();
+        pool.save_setting("buffer_size", "65536").unwrap();
+        pool.save_setting("mmap_chunk_size", "1048576").unwrap();
+        let loaded = pool.load_cache_config().unwrap();
+        assert_eq!(loaded.performance_mode, PerformanceMode::PowerSaving);
+    }
+
+    #[test]
+    fn test_vacuum_schedule_decision() {
+        let now = 100 * 86400;
+        let day = 86400;
+
+        assert!(!VacuumSchedule::Off.is_due(0.9, None, now));
+
+        assert!(VacuumSchedule::OnExit.is_due(0.2, Some(now), now));
+        assert!(!VacuumSchedule::OnExit.is_due(0.05, None, now));
+
+        assert!(VacuumSchedule::Weekly.is_due(0.01, None, now));
+        assert!(VacuumSchedule::Weekly.is_due(0.01, Some(now - 7 * day), now));
+        assert!(!VacuumSchedule::Weekly.is_due(0.01, Some(now - 6 * day), now));
+        assert!(!VacuumSchedule::Weekly.is_due(0.0, None, now));
+        // 系统时间回拨时不整理
+        assert!(!VacuumSchedule::Weekly.is_due(0.5, Some(now + day), now));
+    }
+
+    #[test]
+    fn test_vacuum_if_due_records_last_vacuum() {
+        let (pool, _temp) = create_test_pool().unwrap();
+        assert!(!pool.vacuum_if_due(VacuumSchedule::Weekly).unwrap());
+
+        pool.insert_test_entries(5000);
+        pool.write_pool
+            .get()
+            .unwrap()
+            .execute("DELETE FROM hash_cache", [])
+            .unwrap();
+
+        assert!(!pool.vacuum_if_due(VacuumSchedule::Off).unwrap());
+        assert!(pool.vacuum_if_due(VacuumSchedule::Weekly).unwrap());
+        assert!(pool.get_setting("last_vacuum").unwrap().is_some());
+        assert_eq!(pool.free_page_ratio().unwrap(), 0.0);
+
+        // 一周内不再整理
+        pool.insert_test_entries(5000);
+        pool.write_pool
+            .get()
+            .unwrap()
+            .execute("DELETE FROM hash_cache", [])
+            .unwrap();
+        assert!(!pool.vacuum_if_due(VacuumSchedule::Weekly).unwrap());
+        assert!(pool.vacuum_if_due(VacuumSchedule::OnExit).unwrap());
+    }
+
+    #[test]
+    fn test_visible_columns_persistence() {
+        let (pool, _temp) = create_test_pool().unwrap();
+
+        // 旧版本的修改时间开关迁移到列集合
+        pool.save_setting("show_modified_column", "true").unwrap();
+        let loaded = pool.load_cache_config().unwrap();
+        assert!(loaded.visible_columns.contains(TableColumn::Modified));
+
+        let mut config = loaded;
+        config.visible_columns.set(TableColumn::Modified, false);
+        config.visible_columns.set(TableColumn::Throughput, true);
+        config.column_widths.set(TableColumn::Filename, 320.0);
+        config.enabled_hashes.sha256 = true;
+        config.enabled_hashes.sha1 = false;
+        config.recent_limit = 5;
+        config.size_unit = SizeUnit::Decimal;
+        config.ui_language = UiLanguage::English;
+        config.symlink_policy = SymlinkPolicy::Link;
+        config.sparse_files = true;
+        config.copy_feedback = CopyFeedback::Overlay;
+        config.copy_toast_ms = 3500;
+        config.max_messages_per_frame = 500;
+        config.ui_channel_capacity = 4096;
+        config.hash_encoding = HashEncoding::Base64Url;
+        config.excluded_dirs = vec!["target".to_string(), "$RECYCLE.BIN".to_string()];
+        config.update_check = true;
+        config.update_manifest_url = "https://example.com/version.json".to_string();
+        config.cache_validation = CacheValidation::Paranoid;
+        config.hash_timing = true;
+        config.serial_compute = true;
+        config.normalize_unicode_names = true;
+        pool.save_cache_config(&config).unwrap();
+
+        let loaded = pool.load_cache_config().unwrap();
+        assert_eq!(loaded.visible_columns, config.visible_columns);
+        assert_eq!(loaded.column_widths.get(TableColumn::Filename), Some(320.0));
+        assert_eq!(loaded.enabled_hashes, config.enabled_hashes);
+        assert_eq!(loaded.recent_limit, 5);
+        assert_eq!(loaded.size_unit, SizeUnit::Decimal);
+        assert_eq!(loaded.ui_language, UiLanguage::English);
+        assert_eq!(loaded.symlink_policy, SymlinkPolicy::Link);
+        assert!(loaded.sparse_files);
+        assert_eq!(loaded.copy_feedback, CopyFeedback::Overlay);
+        assert_eq!(loaded.copy_toast_ms, 3500);
+        assert_eq!(loaded.max_messages_per_frame, 500);
+        assert_eq!(loaded.ui_channel_capacity, 4096);
+        assert_eq!(loaded.hash_encoding, HashEncoding::Base64Url);
+        assert_eq!(loaded.excluded_dirs, config.excluded_dirs);
+        assert!(loaded.update_check);
+        assert_eq!(
+            loaded.update_manifest_url,
+            "https://example.com/version.json"
+        );
+        assert_eq!(loaded.cache_validation, CacheValidation::Paranoid);
+        assert!(loaded.hash_timing);
+        assert!(loaded.serial_compute);
+        assert!(loaded.normalize_unicode_names);
+
+        config.excluded_dirs.clear();
+        pool.save_cache_config(&config).unwrap();
+        assert!(pool.load_cache_config().unwrap().excluded_dirs.is_empty());
+
+        // 修改时间列的开关随列集合一起保存
+        config.visible_columns.set(TableColumn::Modified, true);
+        pool.save_cache_config(&config).unwrap();
+        let loaded = pool.load_cache_config().unwrap();
+        assert!(loaded.visible_columns.contains(TableColumn::Modified));
+        assert_eq!(
+            pool.get_setting("visible_columns").unwrap().as_deref(),
+            Some(config.visible_columns.to_string().as_str())
+        );
+    }
+
+    #[test]
+    fn test_profile_roundtrip() {
+        let (pool, _temp) = create_test_pool().unwrap();
+        assert!(pool.profile_names().unwrap().is_empty());
+
+        let mut archival = CacheConfig {
+            cache_validation: CacheValidation::Paranoid,
+            ..CacheConfig::default()
+        };
+        archival.enabled_hashes.sha256 = true;
+        archival.enabled_hashes.crc64 = true;
+        archival.set_performance_mode(PerformanceMode::PowerSaving);
+        let mut dedup = CacheConfig {
+            xxh3_only_mode: true,
+            compute_order: ComputeOrder::SmallestFirst,
+            ..CacheConfig::default()
+        };
+        pool.save_profile("完整归档", &archival).unwrap();
+        pool.save_profile("快速去重", &dedup).unwrap();
+
+        assert_eq!(pool.profile_names().unwrap(), ["完整归档", "快速去重"]);
+        assert_eq!(pool.load_profile("完整归档").unwrap(), Some(archival));
+        assert_eq!(pool.load_profile("快速去重").unwrap(), Some(dedup.clone()));
+        assert_eq!(pool.load_profile("不存在").unwrap(), None);
+        // 方案与当前配置分开保存
+        assert_eq!(pool.load_cache_config().unwrap(), CacheConfig::default());
+
+        dedup.min_file_size = 1024;
+        pool.save_profile("快速去重", &dedup).unwrap();
+        assert_eq!(pool.load_profile("快速去重").unwrap(), Some(dedup));
+        pool.delete_profile("完整归档").unwrap();
+        assert_eq!(pool.profile_names().unwrap(), ["快速去重"]);
+    }
+
+    #[test]
+    fn test_ui_language_falls_back_without_chinese_font() {
+        assert_eq!(UiLanguage::Auto.resolve(true), Language::Chinese);
+        assert_eq!(UiLanguage::Auto.resolve(false), Language::English);
+        assert_eq!(UiLanguage::Chinese.resolve(false), Language::Chinese);
+        assert_eq!(UiLanguage::English.resolve(true), Language::English);
+    }
+
+    #[test]
+    fn test_auto_compute_mode_migrates_legacy_switch() {
+        let (pool, _temp) = create_test_pool().unwrap();
+
+        pool.save_setting("auto_compute_enabled", "false").unwrap();
+        let mut config = pool.load_cache_config().unwrap();
+        assert_eq!(config.auto_compute, AutoComputeMode::Manual);
+
+        config.auto_compute = AutoComputeMode::AutoOnDrop;
+        pool.save_cache_config(&config).unwrap();
+        let loaded = pool.load_cache_config().unwrap();
+        assert_eq!(loaded.auto_compute, AutoComputeMode::AutoOnDrop);
+    }
 }
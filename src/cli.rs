@@ -0,0 +1,173 @@
+// 命令行模式：不启动界面，直接计算标准输入的哈希
+
+use std::io::{self, Read, Write};
+
+use crate::engine::{FileHashes, format_hash_results};
+use crate::error::{HashResult, IoErrorContext};
+use crate::hash::{EnabledHashes, FileHasher, HashAlgorithm};
+
+/// 标准输入在输出与错误信息中的名称，与 coreutils 一致
+const STDIN_NAME: &str = "-";
+const STDIN_BUFFER_SIZE: usize = 256 * 1024;
+
+/// 命令行参数中是否请求了标准输入模式
+pub fn wants_stdin(args: &[String]) -> bool {
+    args.iter().any(|arg| arg == "--stdin")
+}
+
+/// 解析 `--algo md5,sha256`（或 `--algo=md5,sha256`），未指定时输出 SHA256
+fn selected_algorithms(args: &[String]) -> Result<Vec<HashAlgorithm>, String> {
+    let mut value = None;
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if let Some(v) = arg.strip_prefix("--algo=") {
+            value = Some(v.to_string());
+        } else if arg == "--algo" {
+            value = Some(iter.next().ok_or("--algo 需要指定算法")?.clone());
+        }
+    }
+
+    match value {
+        Some(v) => parse_algorithms(&v),
+        None => Ok(vec![HashAlgorithm::Sha256]),
+    }
+}
+
+/// 解析逗号分隔的算法列表，保持输入顺序并去重
+fn parse_algorithms(s: &str) -> Result<Vec<HashAlgorithm>, String> {
+    let mut algorithms = Vec::new();
+    for key in s.split(',').map(str::trim).filter(|k| !k.is_empty()) {
+        let algorithm = HashAlgorithm::ALL
+            .into_iter()
+            .find(|a| a.key() == key.to_lowercase())
+            .ok_or_else(|| format!("未知的哈希算法: {}", key))?;
+        if !algorithms.contains(&algorithm) {
+            algorithms.push(algorithm);
+        }
+    }
+    if algorithms.is_empty() {
+        return Err("未指定哈希算法".to_string());
+    }
+    Ok(algorithms)
+}
+
+/// 分块读取直到 EOF，内存占用与输入长度无关
+fn hash_reader(mut reader: impl Read, enabled: EnabledHashes) -> HashResult<FileHashes> {
+    let mut hasher = FileHasher::with_algorithms(enabled);
+    let mut buffer = vec![0u8; STDIN_BUFFER_SIZE];
+    loop {
+        let n = match reader.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(n) => n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e).with_path(STDIN_NAME),
+        };
+        hasher.update(&buffer[..n]);
+    }
+    Ok(format_hash_results(&hasher.finalize()?))
+}
+
+/// 单个算法时输出 `hash  -`（可直接替代 sha256sum），多个时每行 `ALG (-) = hash`
+fn format_output(hashes: &FileHashes, algorithms: &[HashAlgorithm]) -> String {
+    use std::fmt::Write;
+
+    if let [algorithm] = algorithms {
+        return format!("{}  {}\n", hashes.hash_value(*algorithm), STDIN_NAME);
+    }
+    let mut output = String::new();
+    for a in algorithms {
+        let _ = writeln!(
+            output,
+            "{} ({}) = {}",
+            a.label(),
+            STDIN_NAME,
+            hashes.hash_value(*a)
+        );
+    }
+    output
+}
+
+/// 运行 `--stdin` 模式，返回进程退出码
+pub fn run_stdin(args: &[String]) -> i32 {
+    let algorithms = match selected_algorithms(args) {
+        Ok(algorithms) => algorithms,
+        Err(e) => {
+            eprintln!("错误: {e}");
+            return 2;
+        }
+    };
+
+    let mut enabled = EnabledHashes::NONE;
+    for algorithm in &algorithms {
+        enabled.set(*algorithm, true);
+    }
+
+    match hash_reader(io::stdin().lock(), enabled) {
+        Ok(hashes) => {
+            let mut stdout = io::stdout().lock();
+            if stdout
+                .write_all(format_output(&hashes, &algorithms).as_bytes())
+                .is_err()
+            {
+                return 1;
+            }
+            0
+        }
+        Err(e) => {
+            eprintln!("错误: {e}");
+            1
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 每次最多返回 7 字节的读取器，确保按块增量计算
+    struct Trickle<'a>(&'a [u8]);
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let n = buf.len().min(7).min(self.0.len());
+            buf[..n].copy_from_slice(&self.0[..n]);
+            self.0 = &self.0[n..];
+            Ok(n)
+        }
+    }
+
+    #[test]
+    fn test_hash_reader_matches_known_digests() {
+        let algorithms = parse_algorithms("md5,SHA256,md5").unwrap();
+        assert_eq!(algorithms, [HashAlgorithm::Md5, HashAlgorithm::Sha256]);
+
+        let enabled = EnabledHashes {
+            md5: true,
+            sha256: true,
+            ..EnabledHashes::NONE
+        };
+        let data = b"The quick brown fox jumps over the lazy dog";
+        let hashes = hash_reader(Trickle(data), enabled).unwrap();
+        assert_eq!(hashes.md5, "9e107d9d372bb6826bd81d3542a419d6");
+        assert_eq!(
+            format_output(&hashes, &[HashAlgorithm::Sha256]),
+            "d7a8fbb307d7809469ca9abcb0082e4f8d5651e46d3cdb762d02d0bf37c9e592  -\n"
+        );
+        assert!(hashes.sha1.is_empty());
+    }
+
+    #[test]
+    fn test_selected_algorithms() {
+        let args = |s: &[&str]| s.iter().map(ToString::to_string).collect::<Vec<_>>();
+        assert_eq!(
+            selected_algorithms(&args(&["--stdin"])).unwrap(),
+            [HashAlgorithm::Sha256]
+        );
+        assert_eq!(
+            selected_algorithms(&args(&["--stdin", "--algo=crc32,xxh3"])).unwrap(),
+            [HashAlgorithm::Crc32, HashAlgorithm::Xxh3]
+        );
+        assert!(selected_algorithms(&args(&["--stdin", "--algo"])).is_err());
+        assert!(selected_algorithms(&args(&["--algo", "blake3"])).is_err());
+    }
+}
@@ -0,0 +1,319 @@
+// 结果表格的列定义与可见性配置
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::hash::{EnabledHashes, HashAlgorithm};
+use crate::i18n::{Text, t};
+
+/// 结果表格中的列
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TableColumn {
+    Status,
+    Filename,
+    Extension,
+    Size,
+    Modified,
+    /// 哈希的计算时间，命中缓存时为写入缓存的时间
+    ComputedAt,
+    Duration,
+    Throughput,
+    Progress,
+    /// XXH3 前 8 位，便于跨机器快速目测比对
+    ShortId,
+    Hash(HashAlgorithm),
+    Note,
+}
+
+impl TableColumn {
+    /// 全部列，按表格中的显示顺序排列
+    pub const ALL: [TableColumn; 18] = [
+        TableColumn::Status,
+        TableColumn::Filename,
+        TableColumn::Extension,
+        TableColumn::Size,
+        TableColumn::Modified,
+        TableColumn::ComputedAt,
+        TableColumn::Duration,
+        TableColumn::Throughput,
+        TableColumn::Progress,
+        TableColumn::ShortId,
+        TableColumn::Hash(HashAlgorithm::Crc32),
+        TableColumn::Hash(HashAlgorithm::Crc64),
+        TableColumn::Hash(HashAlgorithm::Adler32),
+        TableColumn::Hash(HashAlgorithm::Md5),
+        TableColumn::Hash(HashAlgorithm::Sha1),
+        TableColumn::Hash(HashAlgorithm::Sha256),
+        TableColumn::Hash(HashAlgorithm::Xxh3),
+        TableColumn::Note,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            TableColumn::Status => t(Text::ColumnStatus),
+            TableColumn::Filename => t(Text::ColumnFilename),
+            TableColumn::Extension => t(Text::ColumnExtension),
+            TableColumn::Size => t(Text::ColumnSize),
+            TableColumn::Modified => t(Text::ColumnModified),
+            TableColumn::ComputedAt => t(Text::ColumnComputedAt),
+            TableColumn::Duration => t(Text::ColumnDuration),
+            TableColumn::Throughput => t(Text::ColumnThroughput),
+            TableColumn::Progress => t(Text::ColumnProgress),
+            TableColumn::ShortId => t(Text::ColumnShortId),
+            TableColumn::Hash(algorithm) => algorithm.label(),
+            TableColumn::Note => t(Text::ColumnNote),
+        }
+    }
+
+    /// 配置中使用的小写标识
+    pub fn key(self) -> &'static str {
+        match self {
+            TableColumn::Status => "status",
+            TableColumn::Filename => "filename",
+            TableColumn::Extension => "ext",
+            TableColumn::Size => "size",
+            TableColumn::Modified => "mtime",
+            TableColumn::ComputedAt => "computed_at",
+            TableColumn::Duration => "duration",
+            TableColumn::Throughput => "throughput",
+            TableColumn::Progress => "progress",
+            TableColumn::ShortId => "shortid",
+            TableColumn::Hash(algorithm) => algorithm.key(),
+            TableColumn::Note => "note",
+        }
+    }
+
+    /// 文件名列始终显示，不可隐藏
+    pub fn is_toggleable(self) -> bool {
+        self != TableColumn::Filename
+    }
+
+    /// 横向滚动时固定在左侧的列，均位于显示顺序的最前面
+    pub fn is_frozen(self) -> bool {
+        matches!(self, TableColumn::Status | TableColumn::Filename)
+    }
+
+    fn index(self) -> usize {
+        Self::ALL.iter().position(|c| *c == self).unwrap_or(0)
+    }
+
+    fn bit(self) -> u32 {
+        1 << self.index()
+    }
+}
+
+/// 可见列集合
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ColumnSet(u32);
+
+impl ColumnSet {
+    const EMPTY: ColumnSet = ColumnSet(0);
+
+    pub fn contains(self, column: TableColumn) -> bool {
+        !column.is_toggleable() || self.0 & column.bit() != 0
+    }
+
+    pub fn set(&mut self, column: TableColumn, visible: bool) {
+        if !column.is_toggleable() {
+            return;
+        }
+        if visible {
+            self.0 |= column.bit();
+        } else {
+            self.0 &= !column.bit();
+        }
+    }
+
+    /// 实际显示的列：可见且（对哈希列而言）对应算法已启用
+    pub fn visible(self, enabled: EnabledHashes) -> Vec<TableColumn> {
+        TableColumn::ALL
+            .into_iter()
+            .filter(|c| self.contains(*c))
+            .filter(|c| match c {
+                TableColumn::Hash(algorithm) => enabled.contains(*algorithm),
+                _ => true,
+            })
+            .collect()
+    }
+}
+
+impl Default for ColumnSet {
+    // 默认与旧版本一致：不显示类型、修改时间、计算时间、吞吐量、短ID、XXH3 和备注
+    fn default() -> Self {
+        let mut set = Self::EMPTY;
+        for column in TableColumn::ALL {
+            let hidden = matches!(
+                column,
+                TableColumn::Extension
+                    | TableColumn::Modified
+                    | TableColumn::ComputedAt
+                    | TableColumn::Throughput
+                    | TableColumn::ShortId
+                    | TableColumn::Hash(HashAlgorithm::Xxh3)
+                    | TableColumn::Note
+            );
+            set.set(column, !hidden);
+        }
+        set
+    }
+}
+
+impl fmt::Display for ColumnSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let keys: Vec<&str> = TableColumn::ALL
+            .into_iter()
+            .filter(|c| c.is_toggleable() && self.contains(*c))
+            .map(TableColumn::key)
+            .collect();
+        write!(f, "{}", keys.join(","))
+    }
+}
+
+impl FromStr for ColumnSet {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut set = Self::EMPTY;
+        for key in s.split(',').map(str::trim).filter(|k| !k.is_empty()) {
+            let column = TableColumn::ALL
+                .into_iter()
+                .find(|c| c.key() == key)
+                .ok_or_else(|| format!("未知的列: {}", key))?;
+            set.set(column, true);
+        }
+        Ok(set)
+    }
+}
+
+/// 用户拖动调整过的列宽（整数像素），未调整过的列使用默认宽度
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ColumnWidths([u16; TableColumn::ALL.len()]);
+
+impl ColumnWidths {
+    pub fn get(self, column: TableColumn) -> Option<f32> {
+        match self.0[column.index()] {
+            0 => None,
+            width => Some(f32::from(width)),
+        }
+    }
+
+    /// 记录列宽，与已保存的宽度相同时返回 false
+    pub fn set(&mut self, column: TableColumn, width: f32) -> bool {
+        let width = width.round().clamp(1.0, f32::from(u16::MAX)) as u16;
+        let slot = &mut self.0[column.index()];
+        if *slot == width {
+            return false;
+        }
+        *slot = width;
+        true
+    }
+}
+
+impl fmt::Display for ColumnWidths {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let entries: Vec<String> = TableColumn::ALL
+            .into_iter()
+            .filter_map(|c| self.get(c).map(|width| format!("{}={}", c.key(), width)))
+            .collect();
+        write!(f, "{}", entries.join(","))
+    }
+}
+
+impl FromStr for ColumnWidths {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut widths = Self::default();
+        for entry in s.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            let (key, width) = entry
+                .split_once('=')
+                .ok_or_else(|| format!("无效的列宽: {}", entry))?;
+            // 旧版本中不存在的列（如已移除的算法）直接忽略
+            let Some(column) = TableColumn::ALL.into_iter().find(|c| c.key() == key) else {
+                continue;
+            };
+            let width: u16 = width
+                .parse()
+                .map_err(|_| format!("无效的列宽: {}", entry))?;
+            widths.set(column, f32::from(width));
+        }
+        Ok(widths)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_column_set_roundtrip() {
+        let mut set = ColumnSet::default();
+        set.set(TableColumn::Throughput, true);
+        set.set(TableColumn::ShortId, true);
+        set.set(TableColumn::ComputedAt, true);
+        set.set(TableColumn::Hash(HashAlgorithm::Md5), false);
+
+        let parsed: ColumnSet = set.to_string().parse().unwrap();
+        assert_eq!(parsed, set);
+        assert!(parsed.contains(TableColumn::Throughput));
+        assert!(parsed.contains(TableColumn::ShortId));
+        assert!(parsed.contains(TableColumn::ComputedAt));
+        assert!(!ColumnSet::default().contains(TableColumn::ShortId));
+        assert!(!ColumnSet::default().contains(TableColumn::ComputedAt));
+        assert!(!parsed.contains(TableColumn::Hash(HashAlgorithm::Md5)));
+        assert!("status,bogus".parse::<ColumnSet>().is_err());
+    }
+
+    #[test]
+    fn test_visible_columns_respect_enabled_hashes() {
+        let mut set = ColumnSet::default();
+        // 即使清空，文件名列仍然显示
+        for column in TableColumn::ALL {
+            set.set(column, false);
+        }
+        assert_eq!(
+            set.visible(EnabledHashes::default()),
+            [TableColumn::Filename]
+        );
+
+        // 未启用的算法即使勾选了也不显示
+        set.set(TableColumn::Hash(HashAlgorithm::Sha256), true);
+        set.set(TableColumn::Hash(HashAlgorithm::Crc32), true);
+        assert_eq!(
+            set.visible(EnabledHashes::default()),
+            [
+                TableColumn::Filename,
+                TableColumn::Hash(HashAlgorithm::Crc32)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_column_widths_roundtrip() {
+        let mut widths = ColumnWidths::default();
+        assert_eq!(widths.get(TableColumn::Filename), None);
+        assert!(widths.set(TableColumn::Filename, 312.4));
+        assert!(!widths.set(TableColumn::Filename, 311.6));
+        assert!(widths.set(TableColumn::Hash(HashAlgorithm::Sha256), 480.0));
+
+        let text = widths.to_string();
+        assert_eq!(text, "filename=312,sha256=480");
+        let parsed: ColumnWidths = text.parse().unwrap();
+        assert_eq!(parsed, widths);
+        assert_eq!(parsed.get(TableColumn::Filename), Some(312.0));
+        assert_eq!(parsed.get(TableColumn::Note), None);
+
+        // 未知的列忽略，格式错误报错
+        let parsed: ColumnWidths = "blake9=100,note=200".parse().unwrap();
+        assert_eq!(parsed.get(TableColumn::Note), Some(200.0));
+        assert!("filename".parse::<ColumnWidths>().is_err());
+        assert!("filename=wide".parse::<ColumnWidths>().is_err());
+    }
+
+    #[test]
+    fn test_frozen_columns_lead() {
+        let frozen = TableColumn::ALL.iter().filter(|c| c.is_frozen()).count();
+        assert_eq!(frozen, 2);
+        assert!(TableColumn::ALL[..frozen].iter().all(|c| c.is_frozen()));
+    }
+}
@@ -1,14 +1,53 @@
 // 自适应IO引擎模块
 
 use crossbeam_channel::Sender;
+use std::collections::HashMap;
 use std::fs::File;
-use std::io::{BufReader, Read};
-use std::path::Path;
-use std::time::Instant;
-
-use crate::cache::CacheConfig;
+use std::io::{self, BufReader, Read, Seek, SeekFrom};
+use std::ops::ControlFlow;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+use walkdir::WalkDir;
+
+use crate::cache::{CacheConfig, PerformanceMode};
 use crate::error::{HashError, HashResult, IoErrorContext};
-use crate::hash::FileHasher;
+use crate::hash::{Digests, EnabledHashes, FileHasher, HashAlgorithm};
+use crate::i18n::{Text, t};
+use crate::sparse;
+
+/// 单个文件的哈希结果（小写十六进制），未启用的算法为空字符串
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FileHashes {
+    pub crc32: String,
+    pub crc64: String,
+    pub adler32: String,
+    pub md5: String,
+    pub sha1: String,
+    pub sha256: String,
+    pub xxhash3: String,
+}
+
+impl FileHashes {
+    pub fn hash_value(&self, algorithm: HashAlgorithm) -> &str {
+        match algorithm {
+            HashAlgorithm::Crc32 => &self.crc32,
+            HashAlgorithm::Crc64 => &self.crc64,
+            HashAlgorithm::Adler32 => &self.adler32,
+            HashAlgorithm::Md5 => &self.md5,
+            HashAlgorithm::Sha1 => &self.sha1,
+            HashAlgorithm::Sha256 => &self.sha256,
+            HashAlgorithm::Xxh3 => &self.xxhash3,
+        }
+    }
+
+    /// 快速模式的结果只有 XXH3。缓存表要求 crc32 非空，这类结果不写入缓存，
+    /// 以免半成品条目在之后的完整计算中被当作命中
+    pub fn is_xxh3_only(&self) -> bool {
+        self.crc32.is_empty()
+    }
+}
 
 /// 进度更新消息
 #[derive(Debug, Clone)]
@@ -70,35 +109,621 @@ impl SystemInfo {
     }
 }
 
+/// 设置中的性能模式预设
+#[derive(Debug)]
+pub struct PerformancePreset {
+    pub mode: PerformanceMode,
+    pub icon: &'static str,
+    pub buffer_size: usize,
+    pub mmap_chunk_size: usize,
+}
+
+pub const PERFORMANCE_PRESETS: [PerformancePreset; 3] = [
+    PerformancePreset {
+        mode: PerformanceMode::PowerSaving,
+        icon: "🍃",
+        buffer_size: 64 * 1024,
+        mmap_chunk_size: 1024 * 1024,
+    },
+    PerformancePreset {
+        mode: PerformanceMode::Balanced,
+        icon: "⚖️",
+        buffer_size: 256 * 1024,
+        mmap_chunk_size: 4 * 1024 * 1024,
+    },
+    PerformancePreset {
+        mode: PerformanceMode::HighPerformance,
+        icon: "⚡",
+        buffer_size: 1024 * 1024,
+        mmap_chunk_size: 16 * 1024 * 1024,
+    },
+];
+
+impl PerformancePreset {
+    pub fn matches(&self, config: &CacheConfig) -> bool {
+        config.performance_mode == self.mode
+    }
+}
+
+/// 按本机可用内存选择预设：推荐缓冲大小以内最大的一档
 pub fn detect_optimal_config() -> CacheConfig {
     let sys_info = SystemInfo::detect();
-    let (buffer_size, mmap_chunk_size) = sys_info.recommend_buffer_sizes();
-
-    CacheConfig {
+    let (buffer_size, _) = sys_info.recommend_buffer_sizes();
+    let mode = PERFORMANCE_PRESETS
+        .iter()
+        .rev()
+        .find(|preset| preset.buffer_size <= buffer_size)
+        .map_or(PerformanceMode::PowerSaving, |preset| preset.mode);
+
+    let mut config = CacheConfig {
         min_file_size: 1024 * 1024,
         retention_days: 30,
-        buffer_size,
-        mmap_chunk_size,
-        auto_compute_enabled: CacheConfig::default().auto_compute_enabled,
-        uppercase_display: CacheConfig::default().uppercase_display,
+        ..CacheConfig::default()
+    };
+    config.set_performance_mode(mode);
+    config
+}
+
+/// 判断路径是否位于网络文件系统（SMB/NFS 等）
+///
+/// Windows 上识别 UNC 路径和映射的网络驱动器，Unix 上通过 `statfs` 检查文件系统类型。
+pub fn is_network_path(path: &Path) -> bool {
+    #[cfg(windows)]
+    {
+        is_unc_path(&path.to_string_lossy()) || windows_drive_is_remote(path)
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        use std::os::unix::ffi::OsStrExt;
+
+        let Ok(c_path) = std::ffi::CString::new(path.as_os_str().as_bytes()) else {
+            return false;
+        };
+        let mut stat: libc::statfs = unsafe { std::mem::zeroed() };
+        if unsafe { libc::statfs(c_path.as_ptr(), &raw mut stat) } != 0 {
+            return false;
+        }
+        #[allow(clippy::unnecessary_cast)]
+        is_network_fs_magic(stat.f_type as u64)
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        use std::os::unix::ffi::OsStrExt;
+
+        let Ok(c_path) = std::ffi::CString::new(path.as_os_str().as_bytes()) else {
+            return false;
+        };
+        let mut stat: libc::statfs = unsafe { std::mem::zeroed() };
+        if unsafe { libc::statfs(c_path.as_ptr(), &raw mut stat) } != 0 {
+            return false;
+        }
+        let name = unsafe { std::ffi::CStr::from_ptr(stat.f_fstypename.as_ptr()) };
+        is_network_fs_name(&name.to_string_lossy())
+    }
+
+    #[cfg(not(any(windows, target_os = "linux", target_os = "macos")))]
+    {
+        let _ = path;
+        false
+    }
+}
+
+/// 判断路径是否为块设备（如 `/dev/sdb`），非 Unix 平台始终为 false
+pub fn is_block_device(path: &Path) -> bool {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::FileTypeExt;
+        std::fs::metadata(path).is_ok_and(|m| m.file_type().is_block_device())
+    }
+
+    #[cfg(not(unix))]
+    {
+        let _ = path;
+        false
+    }
+}
+
+/// 判断文件类型是否为 FIFO、套接字或字符设备，非 Unix 平台始终为 false
+///
+/// 读取这类文件会一直阻塞等待写入方（FIFO、套接字）或读出无穷的数据（`/dev/zero`），
+/// 无法得到哈希，扫描与计算时都应跳过。
+pub fn is_special_file(file_type: std::fs::FileType) -> bool {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::FileTypeExt;
+        file_type.is_fifo() || file_type.is_socket() || file_type.is_char_device()
+    }
+
+    #[cfg(not(unix))]
+    {
+        let _ = file_type;
+        false
+    }
+}
+
+/// 读取单个文件之前检查其类型，特殊文件返回错误而不是打开后一直阻塞
+pub fn ensure_not_special(path: &Path) -> HashResult<()> {
+    let metadata = with_long_path(path, |p| std::fs::metadata(p)).with_path(path)?;
+    if is_special_file(metadata.file_type()) {
+        return Err(HashError::Io(
+            io::Error::new(io::ErrorKind::Unsupported, "不支持的文件类型"),
+            path.to_path_buf(),
+        ));
+    }
+    Ok(())
+}
+
+/// 文件内容长度
+///
+/// 块设备的元数据长度为 0，改为定位到末尾得到设备容量，
+/// 使读取路径选择与进度计算按实际大小进行。
+pub fn content_len(path: &Path) -> io::Result<u64> {
+    let metadata = with_long_path(path, |p| std::fs::metadata(p))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::FileTypeExt;
+        if metadata.file_type().is_block_device() {
+            return seek_len(path);
+        }
+    }
+    Ok(metadata.len())
+}
+
+/// 定位到末尾得到可读取的字节数
+fn seek_len(path: &Path) -> io::Result<u64> {
+    File::open(path)?.seek(SeekFrom::End(0))
+}
+
+/// 判断路径是否位于可移动介质（U 盘、存储卡、光盘等）
+///
+/// 同一盘符或挂载点可能先后挂载不同的物理设备，这类路径上的缓存命中并不可靠。
+/// Windows 上通过 `GetDriveTypeW` 判断，Linux 上读取块设备在 sysfs 中的 `removable` 标志，
+/// macOS 上将挂载在 `/Volumes` 下的卷视为外部卷。
+pub fn is_removable_path(path: &Path) -> bool {
+    #[cfg(windows)]
+    {
+        use windows_sys::Win32::System::WindowsProgramming::{DRIVE_CDROM, DRIVE_REMOVABLE};
+        matches!(
+            windows_drive_type(path),
+            Some(DRIVE_REMOVABLE | DRIVE_CDROM)
+        )
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        use std::os::unix::fs::MetadataExt;
+
+        let Ok(metadata) = std::fs::metadata(path) else {
+            return false;
+        };
+        let dev = metadata.dev();
+        let device_dir = PathBuf::from(format!(
+            "/sys/dev/block/{}:{}",
+            libc::major(dev),
+            libc::minor(dev)
+        ));
+        sysfs_device_is_removable(&device_dir)
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        use std::os::unix::ffi::OsStrExt;
+
+        let Ok(c_path) = std::ffi::CString::new(path.as_os_str().as_bytes()) else {
+            return false;
+        };
+        let mut stat: libc::statfs = unsafe { std::mem::zeroed() };
+        if unsafe { libc::statfs(c_path.as_ptr(), &raw mut stat) } != 0 {
+            return false;
+        }
+        let mount_point = unsafe { std::ffi::CStr::from_ptr(stat.f_mntonname.as_ptr()) };
+        is_external_volume_mount(&mount_point.to_string_lossy())
+    }
+
+    #[cfg(not(any(windows, target_os = "linux", target_os = "macos")))]
+    {
+        let _ = path;
+        false
     }
 }
 
+/// 读取 sysfs 块设备目录的 `removable` 标志
+///
+/// 分区（如 `sdb1`）自身没有该文件，需要查看所属磁盘，即上一级目录。
+#[cfg(any(target_os = "linux", test))]
+fn sysfs_device_is_removable(device_dir: &Path) -> bool {
+    let read_flag = |dir: &Path| {
+        std::fs::read_to_string(dir.join("removable"))
+            .ok()
+            .map(|flag| flag.trim() == "1")
+    };
+    let Ok(dir) = device_dir.canonicalize() else {
+        return false;
+    };
+    read_flag(&dir)
+        .or_else(|| dir.parent().and_then(read_flag))
+        .unwrap_or(false)
+}
+
+/// 机械硬盘上计算使用的线程数
+pub const ROTATIONAL_THREADS: usize = 2;
+
+/// 判断路径是否位于机械硬盘，无法判断时返回 None
+///
+/// 机械硬盘上多线程同时读取不同文件会让磁头来回寻道，串行或两路并发反而更快。
+/// Windows 上通过 `DeviceIoControl` 查询卷的寻道开销（`IncursSeekPenalty`），
+/// Linux 上读取块设备在 sysfs 中的 `queue/rotational` 标志。
+pub fn is_rotational_path(path: &Path) -> Option<bool> {
+    #[cfg(windows)]
+    {
+        windows_incurs_seek_penalty(path)
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        use std::os::unix::fs::MetadataExt;
+
+        let dev = std::fs::metadata(path).ok()?.dev();
+        let device_dir = PathBuf::from(format!(
+            "/sys/dev/block/{}:{}",
+            libc::major(dev),
+            libc::minor(dev)
+        ));
+        sysfs_device_is_rotational(&device_dir)
+    }
+
+    #[cfg(not(any(windows, target_os = "linux")))]
+    {
+        let _ = path;
+        None
+    }
+}
+
+/// 读取 sysfs 块设备目录的 `queue/rotational` 标志
+///
+/// 与 `removable` 相同，分区没有 `queue` 目录，需要查看所属磁盘。
+#[cfg(any(target_os = "linux", test))]
+fn sysfs_device_is_rotational(device_dir: &Path) -> Option<bool> {
+    let read_flag = |dir: &Path| {
+        std::fs::read_to_string(dir.join("queue").join("rotational"))
+            .ok()
+            .map(|flag| flag.trim() == "1")
+    };
+    let dir = device_dir.canonicalize().ok()?;
+    read_flag(&dir).or_else(|| dir.parent().and_then(read_flag))
+}
+
+/// 判断两个已存在的路径是否位于同一卷，任一路径无法查询时返回 None
+///
+/// Unix 上比较 `st_dev`，Windows 上比较 `GetVolumePathNameW` 得到的卷挂载点。
+pub fn same_volume(a: &Path, b: &Path) -> Option<bool> {
+    Some(volume_id(a)? == volume_id(b)?)
+}
+
+#[cfg(unix)]
+pub type VolumeId = u64;
+#[cfg(windows)]
+pub type VolumeId = String;
+#[cfg(not(any(unix, windows)))]
+pub type VolumeId = ();
+
+/// 路径所在卷的标识，Unix 上为 `st_dev`，Windows 上为小写的卷挂载点
+pub fn volume_id(path: &Path) -> Option<VolumeId> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+
+        std::fs::metadata(path).ok().map(|m| m.dev())
+    }
+
+    #[cfg(windows)]
+    {
+        windows_volume_path(path).map(|volume| volume.to_ascii_lowercase())
+    }
+
+    #[cfg(not(any(unix, windows)))]
+    {
+        let _ = path;
+        None
+    }
+}
+
+/// 按卷记住 `is_removable_path` 的结果
+///
+/// 一个批次的文件大多位于少数几个卷上，每个卷只需读取一次 sysfs 或调用一次
+/// `GetDriveTypeW`。换盘后同一卷标识可能对应另一设备，因此只在一个批次内复用。
+#[derive(Debug, Default)]
+pub struct RemovableVolumes {
+    known: Mutex<HashMap<VolumeId, bool>>,
+}
+
+impl RemovableVolumes {
+    pub fn contains(&self, path: &Path) -> bool {
+        let Some(volume) = volume_id(path) else {
+            return is_removable_path(path);
+        };
+        let known = self.known.lock().ok();
+        if let Some(&removable) = known.as_ref().and_then(|known| known.get(&volume)) {
+            return removable;
+        }
+        drop(known);
+        // 查询期间不持有锁，并发的线程可能重复查询同一个卷
+        let removable = is_removable_path(path);
+        if let Ok(mut known) = self.known.lock() {
+            known.insert(volume, removable);
+        }
+        removable
+    }
+}
+
+/// 路径所在卷的挂载点（如 `C:\` 或挂载到文件夹的卷 `D:\mnt\disk\`）
+#[cfg(windows)]
+fn windows_volume_path(path: &Path) -> Option<String> {
+    use std::os::windows::ffi::OsStrExt;
+    use windows_sys::Win32::Storage::FileSystem::GetVolumePathNameW;
+
+    let wide: Vec<u16> = path
+        .as_os_str()
+        .encode_wide()
+        .chain(std::iter::once(0))
+        .collect();
+    let mut buffer = [0u16; 1024];
+    let ok = unsafe { GetVolumePathNameW(wide.as_ptr(), buffer.as_mut_ptr(), buffer.len() as u32) };
+    if ok == 0 {
+        return None;
+    }
+    let len = buffer.iter().position(|&c| c == 0).unwrap_or(buffer.len());
+    Some(String::from_utf16_lossy(&buffer[..len]))
+}
+
+/// 查询盘符所在卷是否有寻道开销，非盘符路径或查询失败时返回 None
+#[cfg(windows)]
+fn windows_incurs_seek_penalty(path: &Path) -> Option<bool> {
+    use std::os::windows::fs::OpenOptionsExt;
+    use std::os::windows::io::AsRawHandle;
+    use std::path::{Component, Prefix};
+    use windows_sys::Win32::Storage::FileSystem::{FILE_SHARE_READ, FILE_SHARE_WRITE};
+    use windows_sys::Win32::System::IO::DeviceIoControl;
+    use windows_sys::Win32::System::Ioctl::{
+        DEVICE_SEEK_PENALTY_DESCRIPTOR, IOCTL_STORAGE_QUERY_PROPERTY, PropertyStandardQuery,
+        STORAGE_PROPERTY_QUERY, StorageDeviceSeekPenaltyProperty,
+    };
+
+    let Some(Component::Prefix(prefix)) = path.components().next() else {
+        return None;
+    };
+    let letter = match prefix.kind() {
+        Prefix::Disk(letter) | Prefix::VerbatimDisk(letter) => letter,
+        _ => return None,
+    };
+    // 只查询属性，不需要读写权限（也就不需要管理员权限）
+    let volume = std::fs::OpenOptions::new()
+        .access_mode(0)
+        .share_mode(FILE_SHARE_READ | FILE_SHARE_WRITE)
+        .open(format!(r"\\.\{}:", letter as char))
+        .ok()?;
+
+    let query = STORAGE_PROPERTY_QUERY {
+        PropertyId: StorageDeviceSeekPenaltyProperty,
+        QueryType: PropertyStandardQuery,
+        AdditionalParameters: [0],
+    };
+    let mut descriptor = DEVICE_SEEK_PENALTY_DESCRIPTOR::default();
+    let mut returned = 0u32;
+    let ok = unsafe {
+        DeviceIoControl(
+            volume.as_raw_handle(),
+            IOCTL_STORAGE_QUERY_PROPERTY,
+            (&raw const query).cast(),
+            std::mem::size_of::<STORAGE_PROPERTY_QUERY>() as u32,
+            (&raw mut descriptor).cast(),
+            std::mem::size_of::<DEVICE_SEEK_PENALTY_DESCRIPTOR>() as u32,
+            &mut returned,
+            std::ptr::null_mut(),
+        )
+    };
+    (ok != 0).then_some(descriptor.IncursSeekPenalty)
+}
+
+/// macOS 上启动卷挂载在 `/`，外接磁盘和光盘挂载在 `/Volumes/<名称>`
+#[cfg(any(target_os = "macos", test))]
+fn is_external_volume_mount(mount_point: &str) -> bool {
+    mount_point
+        .strip_prefix("/Volumes/")
+        .is_some_and(|name| !name.is_empty())
+}
+
+/// 目录路径超过该长度（MAX_PATH - 12）时，Windows 未开启长路径支持就可能访问失败
+#[cfg(windows)]
+const WINDOWS_PATH_LIMIT: usize = 248;
+
+/// 执行文件操作；Windows 上因路径过长失败时改用 `\\?\` 前缀重试
+///
+/// 带前缀的路径只用于这一次访问，显示和缓存仍使用原路径（`dunce` 规范化后的形式）。
+pub fn with_long_path<T>(
+    path: &Path,
+    op: impl Fn(&Path) -> std::io::Result<T>,
+) -> std::io::Result<T> {
+    #[cfg(windows)]
+    {
+        match op(path) {
+            Err(e) if is_path_length_error(&e, path) => {
+                let verbatim = std::path::absolute(path)
+                    .ok()
+                    .and_then(|abs| verbatim_path(&abs.to_string_lossy()));
+                match verbatim {
+                    Some(verbatim) => op(Path::new(&verbatim)),
+                    None => Err(e),
+                }
+            }
+            result => result,
+        }
+    }
+
+    #[cfg(not(windows))]
+    {
+        op(path)
+    }
+}
+
+/// ERROR_FILENAME_EXCED_RANGE，或长路径上的 ERROR_PATH_NOT_FOUND
+#[cfg(windows)]
+fn is_path_length_error(error: &std::io::Error, path: &Path) -> bool {
+    const ERROR_PATH_NOT_FOUND: i32 = 3;
+    const ERROR_FILENAME_EXCED_RANGE: i32 = 206;
+
+    match error.raw_os_error() {
+        Some(ERROR_FILENAME_EXCED_RANGE) => true,
+        Some(ERROR_PATH_NOT_FOUND) => path.as_os_str().len() >= WINDOWS_PATH_LIMIT,
+        _ => false,
+    }
+}
+
+/// 绝对路径转为 verbatim 形式：`C:\a` → `\\?\C:\a`，`\\server\share` → `\\?\UNC\server\share`
+///
+/// 已是 verbatim/设备路径或不是绝对路径时返回 None。
+#[cfg(any(windows, test))]
+fn verbatim_path(path: &str) -> Option<String> {
+    let path = path.replace('/', "\\");
+    if path.starts_with(r"\\?\") || path.starts_with(r"\\.\") {
+        return None;
+    }
+    if let Some(unc) = path.strip_prefix(r"\\") {
+        return Some(format!(r"\\?\UNC\{}", unc));
+    }
+    let bytes = path.as_bytes();
+    if bytes.len() >= 3 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':' && bytes[2] == b'\\' {
+        return Some(format!(r"\\?\{}", path));
+    }
+    None
+}
+
+/// UNC 路径：`\\server\share`、`//server/share` 或 `\\?\UNC\server\share`
+#[cfg(any(windows, test))]
+fn is_unc_path(path: &str) -> bool {
+    if let Some(rest) = path.strip_prefix(r"\\?\") {
+        return rest
+            .get(..4)
+            .is_some_and(|p| p.eq_ignore_ascii_case(r"UNC\"));
+    }
+    path.starts_with(r"\\") || path.starts_with("//")
+}
+
+#[cfg(windows)]
+fn windows_drive_is_remote(path: &Path) -> bool {
+    use windows_sys::Win32::System::WindowsProgramming::DRIVE_REMOTE;
+
+    windows_drive_type(path) == Some(DRIVE_REMOTE)
+}
+
+/// 盘符路径所在驱动器的 `GetDriveTypeW` 类型，非盘符路径返回 None
+#[cfg(windows)]
+fn windows_drive_type(path: &Path) -> Option<u32> {
+    use std::path::{Component, Prefix};
+    use windows_sys::Win32::Storage::FileSystem::GetDriveTypeW;
+
+    let Some(Component::Prefix(prefix)) = path.components().next() else {
+        return None;
+    };
+    let letter = match prefix.kind() {
+        Prefix::Disk(letter) | Prefix::VerbatimDisk(letter) => letter,
+        _ => return None,
+    };
+    let root: Vec<u16> = format!("{}:\\", letter as char)
+        .encode_utf16()
+        .chain(std::iter::once(0))
+        .collect();
+    Some(unsafe { GetDriveTypeW(root.as_ptr()) })
+}
+
+/// Linux `statfs.f_type` 中的网络文件系统魔数
+#[cfg(any(target_os = "linux", test))]
+fn is_network_fs_magic(magic: u64) -> bool {
+    const NFS_SUPER_MAGIC: u64 = 0x6969;
+    const SMB_SUPER_MAGIC: u64 = 0x517B;
+    const CIFS_MAGIC_NUMBER: u64 = 0xFF53_4D42;
+    const SMB2_MAGIC_NUMBER: u64 = 0xFE53_4D42;
+    const CODA_SUPER_MAGIC: u64 = 0x7375_7245;
+    const AFS_SUPER_MAGIC: u64 = 0x5346_414F;
+    const CEPH_SUPER_MAGIC: u64 = 0x00C3_6400;
+    const V9FS_MAGIC: u64 = 0x0102_1997;
+
+    matches!(
+        magic,
+        NFS_SUPER_MAGIC
+            | SMB_SUPER_MAGIC
+            | CIFS_MAGIC_NUMBER
+            | SMB2_MAGIC_NUMBER
+            | CODA_SUPER_MAGIC
+            | AFS_SUPER_MAGIC
+            | CEPH_SUPER_MAGIC
+            | V9FS_MAGIC
+    )
+}
+
+/// macOS `statfs.f_fstypename` 中的网络文件系统名称
+#[cfg(any(target_os = "macos", test))]
+fn is_network_fs_name(name: &str) -> bool {
+    matches!(name, "nfs" | "smbfs" | "afpfs" | "webdav" | "cifs")
+}
+
 const TINY_FILE_THRESHOLD: u64 = 64 * 1024;
 const MEDIUM_FILE_THRESHOLD: u64 = 512 * 1024 * 1024;
 
-fn format_hash_results(
-    crc32: u32,
-    md5: &[u8],
-    sha1: &[u8],
-    xxh3: &[u8],
-) -> (String, String, String, String) {
-    (
-        format!("{:08x}", crc32),
-        hex::encode(md5),
-        hex::encode(sha1),
-        hex::encode(xxh3),
-    )
+/// 按字节阈值发送进度的最小时间间隔，避免快速读取时刷屏
+const MIN_PROGRESS_GAP: Duration = Duration::from_millis(16);
+
+/// 快速哈希读取的首尾采样大小
+const QUICK_HASH_SAMPLE: u64 = 64 * 1024;
+
+/// 快速哈希：对文件大小 + 首尾各 64KB 计算 xxhash3
+///
+/// 仅用于去重预筛，中间内容不同的文件会得到相同结果，不能作为权威校验值。
+pub fn quick_hash(path: &Path) -> HashResult<String> {
+    use xxhash_rust::xxh3::Xxh3;
+
+    let mut file = with_long_path(path, |p| File::open(p)).with_path(path)?;
+    let file_size = file.metadata().with_path(path)?.len();
+
+    let mut hasher = Xxh3::new();
+    hasher.update(&file_size.to_le_bytes());
+
+    if file_size <= QUICK_HASH_SAMPLE * 2 {
+        let mut data = Vec::with_capacity(file_size as usize);
+        file.read_to_end(&mut data).with_path(path)?;
+        hasher.update(&data);
+    } else {
+        let mut buffer = vec![0u8; QUICK_HASH_SAMPLE as usize];
+        file.read_exact(&mut buffer).with_path(path)?;
+        hasher.update(&buffer);
+
+        file.seek(SeekFrom::End(-(QUICK_HASH_SAMPLE as i64)))
+            .with_path(path)?;
+        file.read_exact(&mut buffer).with_path(path)?;
+        hasher.update(&buffer);
+    }
+
+    Ok(hex::encode(hasher.digest128().to_be_bytes()))
+}
+
+pub fn format_hash_results(digests: &Digests) -> FileHashes {
+    FileHashes {
+        crc32: format!("{:08x}", digests.crc32),
+        crc64: digests
+            .crc64
+            .map(|crc| format!("{:016x}", crc))
+            .unwrap_or_default(),
+        adler32: digests
+            .adler32
+            .map(|adler| format!("{:08x}", adler))
+            .unwrap_or_default(),
+        md5: digests.md5.map(hex::encode).unwrap_or_default(),
+        sha1: digests.sha1.map(hex::encode).unwrap_or_default(),
+        sha256: digests.sha256.map(hex::encode).unwrap_or_default(),
+        xxhash3: hex::encode(digests.xxh3),
+    }
 }
 
 #[cfg(target_pointer_width = "32")]
@@ -113,31 +738,120 @@ fn check_chunk_size_fits(chunk_size: u64, path: &Path) -> HashResult<()> {
     Ok(())
 }
 
+/// 引擎的读取路径
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IoStrategy {
+    /// 一次性读入内存
+    Tiny,
+    /// 缓冲读取
+    Buffered,
+    /// 分块内存映射
+    Mmap,
+}
+
+impl IoStrategy {
+    pub const ALL: [IoStrategy; 3] = [IoStrategy::Tiny, IoStrategy::Buffered, IoStrategy::Mmap];
+
+    /// 按文件大小选择读取路径，网络路径等场景可强制缓冲读取
+    pub fn for_size(file_size: u64, force_buffered: bool) -> Self {
+        if file_size < TINY_FILE_THRESHOLD {
+            IoStrategy::Tiny
+        } else if file_size < MEDIUM_FILE_THRESHOLD || force_buffered {
+            IoStrategy::Buffered
+        } else {
+            IoStrategy::Mmap
+        }
+    }
+
+    /// 同 `for_size`，块设备不做内存映射
+    pub fn for_file(path: &Path, file_size: u64, force_buffered: bool) -> Self {
+        Self::for_size(file_size, force_buffered || is_mmap_unsafe(path, file_size))
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            IoStrategy::Tiny => t(Text::StrategyTiny),
+            IoStrategy::Buffered => t(Text::StrategyBuffered),
+            IoStrategy::Mmap => t(Text::StrategyMmap),
+        }
+    }
+}
+
 pub fn compute_file_hash(
     path: &Path,
     progress_sender: Option<&Sender<ProgressUpdate>>,
     buffer_size: usize,
     mmap_chunk_size: usize,
     file_size_hint: Option<u64>,
-) -> HashResult<(String, String, String, String)> {
+    force_buffered: bool,
+    enabled: EnabledHashes,
+) -> HashResult<FileHashes> {
     let file_size = if let Some(size) = file_size_hint {
         size
     } else {
-        std::fs::metadata(path).with_path(path)?.len()
+        content_len(path).with_path(path)?
     };
 
-    let optimized_buffer_size = optimize_buffer_size(file_size, buffer_size);
-    let optimized_chunk_size = optimize_chunk_size(file_size, mmap_chunk_size);
+    compute_file_hash_with(
+        path,
+        file_size,
+        IoStrategy::for_file(path, file_size, force_buffered),
+        progress_sender,
+        buffer_size,
+        mmap_chunk_size,
+        enabled,
+    )
+}
 
-    if file_size < TINY_FILE_THRESHOLD {
-        compute_hash_tiny(path, file_size)
-    } else if file_size < MEDIUM_FILE_THRESHOLD {
-        compute_hash_medium(path, file_size, progress_sender, optimized_buffer_size)
-    } else {
-        compute_hash_large(path, file_size, progress_sender, optimized_chunk_size)
+/// 使用指定的读取路径计算，不按文件大小自动选择（性能测试使用）
+pub fn compute_file_hash_with(
+    path: &Path,
+    file_size: u64,
+    strategy: IoStrategy,
+    progress_sender: Option<&Sender<ProgressUpdate>>,
+    buffer_size: usize,
+    mmap_chunk_size: usize,
+    enabled: EnabledHashes,
+) -> HashResult<FileHashes> {
+    compute_file_hash_exact(
+        path,
+        file_size,
+        strategy,
+        progress_sender,
+        optimize_buffer_size(file_size, buffer_size),
+        optimize_chunk_size(file_size, mmap_chunk_size),
+        enabled,
+    )
+}
+
+/// 与 `compute_file_hash_with` 相同，但原样使用给定的缓冲区与映射块大小，不按文件大小调整
+///
+/// 供「高级重算」排查读取问题：窗口中显示的参数就是实际使用的参数。
+pub fn compute_file_hash_exact(
+    path: &Path,
+    file_size: u64,
+    strategy: IoStrategy,
+    progress_sender: Option<&Sender<ProgressUpdate>>,
+    buffer_size: usize,
+    mmap_chunk_size: usize,
+    enabled: EnabledHashes,
+) -> HashResult<FileHashes> {
+    match strategy {
+        IoStrategy::Tiny => compute_hash_tiny(path, file_size, enabled),
+        IoStrategy::Buffered => {
+            compute_hash_medium(path, file_size, progress_sender, buffer_size, enabled)
+        }
+        IoStrategy::Mmap => {
+            compute_hash_large(path, file_size, progress_sender, mmap_chunk_size, enabled)
+        }
     }
 }
 
+/// 块设备按流式读取，不做内存映射；只有会走内存映射的大小才需要检查
+fn is_mmap_unsafe(path: &Path, file_size: u64) -> bool {
+    file_size >= MEDIUM_FILE_THRESHOLD && is_block_device(path)
+}
+
 fn optimize_buffer_size(file_size: u64, default_buffer_size: usize) -> usize {
     let optimal_size = if file_size < 10 * 1024 * 1024 {
         (file_size / 4).max(64 * 1024).min(512 * 1024) as usize
@@ -149,13 +863,20 @@ fn optimize_buffer_size(file_size: u64, default_buffer_size: usize) -> usize {
     optimal_size.next_multiple_of(65536)
 }
 
+/// 根据文件大小调整映射块大小，只会放大、不会小于配置值
 fn optimize_chunk_size(file_size: u64, default_chunk_size: usize) -> usize {
+    // 设置中可选的最大映射块
+    const HUGE_FILE_MAX_CHUNK: usize = 64 * 1024 * 1024;
+    const LARGE_FILE_MIN_CHUNK: usize = 8 * 1024 * 1024;
+
     let optimal_size = if file_size > 10u64 * 1024 * 1024 * 1024 {
-        // 超大文件（>10GB）：使用更大块减少映射次数
-        (default_chunk_size * 4).min(16 * 1024 * 1024)
+        // 超大文件（>10GB）：放大到 4 倍减少映射次数，最多放大到 64MB
+        (default_chunk_size * 4)
+            .min(HUGE_FILE_MAX_CHUNK)
+            .max(default_chunk_size)
     } else if file_size > 1024 * 1024 * 1024 {
-        // 大文件（>1GB）：使用 8MB 块
-        8 * 1024 * 1024
+        // 大文件（>1GB）：至少使用 8MB 块
+        default_chunk_size.max(LARGE_FILE_MIN_CHUNK)
     } else {
         default_chunk_size
     };
@@ -164,17 +885,21 @@ fn optimize_chunk_size(file_size: u64, default_chunk_size: usize) -> usize {
     optimal_size.next_multiple_of(2 * 1024 * 1024)
 }
 
-fn compute_hash_tiny(path: &Path, _file_size: u64) -> HashResult<(String, String, String, String)> {
-    let data = std::fs::read(path).with_path(path)?;
+fn compute_hash_tiny(
+    path: &Path,
+    _file_size: u64,
+    enabled: EnabledHashes,
+) -> HashResult<FileHashes> {
+    let data = with_long_path(path, |p| std::fs::read(p)).with_path(path)?;
 
-    let mut hasher = FileHasher::new();
+    let mut hasher = FileHasher::with_algorithms(enabled);
     hasher.update(&data);
-    let (crc32, md5, sha1, xxh3) = hasher.finalize().map_err(|e| {
-        eprintln!("[Engine] 哈希计算失败: {}", e);
+    let digests = hasher.finalize().map_err(|e| {
+        log::warn!("哈希计算失败: {}", e);
         e
     })?;
 
-    Ok(format_hash_results(crc32, &md5, &sha1, &xxh3))
+    Ok(format_hash_results(&digests))
 }
 
 fn compute_hash_medium(
@@ -182,16 +907,18 @@ fn compute_hash_medium(
     file_size: u64,
     progress_sender: Option<&Sender<ProgressUpdate>>,
     buffer_size: usize,
-) -> HashResult<(String, String, String, String)> {
-    let file = File::open(path).with_path(path)?;
+    enabled: EnabledHashes,
+) -> HashResult<FileHashes> {
+    let file = with_long_path(path, |p| File::open(p)).with_path(path)?;
     let mut reader = BufReader::with_capacity(buffer_size, file);
-    let mut hasher = FileHasher::new();
+    let mut hasher = FileHasher::with_algorithms(enabled);
 
     let mut buffer = vec![0u8; buffer_size];
     let mut processed = 0u64;
 
     let progress_interval = (file_size / 100).max(1024 * 1024); // 至少1MB间隔
     let mut next_progress_threshold = progress_interval;
+    let mut last_update = Instant::now();
 
     loop {
         let n = reader.read(&mut buffer).with_path(path)?;
@@ -204,22 +931,35 @@ fn compute_hash_medium(
         processed += n as u64;
 
         if let Some(sender) = progress_sender {
-            if processed >= next_progress_threshold {
+            // 字节阈值与时间阈值结合：慢速读取按时间更新，快速读取限制频率
+            let due_by_bytes =
+                processed >= next_progress_threshold && last_update.elapsed() >= MIN_PROGRESS_GAP;
+            if due_by_bytes || should_send_progress(&mut last_update, processed, file_size) {
+                if due_by_bytes {
+                    last_update = Instant::now();
+                    next_progress_threshold = processed + progress_interval;
+                }
                 let update = ProgressUpdate {
                     processed,
                     total: file_size,
                 };
                 let _ = sender.try_send(update);
-                next_progress_threshold += progress_interval;
             }
         }
     }
 
-    let (crc32, md5, sha1, xxh3) = hasher.finalize().map_err(|e| {
-        eprintln!("[Engine] 哈希计算失败: {}", e);
+    if let Some(sender) = progress_sender {
+        let _ = sender.try_send(ProgressUpdate {
+            processed,
+            total: file_size,
+        });
+    }
+
+    let digests = hasher.finalize().map_err(|e| {
+        log::warn!("哈希计算失败: {}", e);
         e
     })?;
-    Ok(format_hash_results(crc32, &md5, &sha1, &xxh3))
+    Ok(format_hash_results(&digests))
 }
 
 fn compute_hash_large(
@@ -227,10 +967,11 @@ fn compute_hash_large(
     file_size: u64,
     progress_sender: Option<&Sender<ProgressUpdate>>,
     mmap_chunk_size: usize,
-) -> HashResult<(String, String, String, String)> {
+    enabled: EnabledHashes,
+) -> HashResult<FileHashes> {
     // 统一使用串行 mmap 处理，确保正确性
     // MD5/SHA1/CRC32 不支持并行状态合并，必须串行计算
-    compute_hash_large_serial(path, file_size, progress_sender, mmap_chunk_size)
+    compute_hash_large_serial(path, file_size, progress_sender, mmap_chunk_size, enabled)
 }
 
 fn compute_hash_large_serial(
@@ -238,16 +979,38 @@ fn compute_hash_large_serial(
     file_size: u64,
     progress_sender: Option<&Sender<ProgressUpdate>>,
     mmap_chunk_size: usize,
-) -> HashResult<(String, String, String, String)> {
+    enabled: EnabledHashes,
+) -> HashResult<FileHashes> {
+    let mut hasher = FileHasher::with_algorithms(enabled);
+    for_each_mmap_chunk(path, file_size, progress_sender, mmap_chunk_size, |chunk| {
+        hasher.update(chunk);
+        ControlFlow::Continue(())
+    })?;
+
+    let digests = hasher.finalize().map_err(|e| {
+        log::warn!("哈希计算失败: {}", e);
+        e
+    })?;
+    Ok(format_hash_results(&digests))
+}
+
+/// 按 `mmap_chunk_size` 依次映射文件的各段交给 `f`，每段只映射一次，处理完即解除映射；
+/// `f` 返回 `Break` 时不再映射后续各段
+fn for_each_mmap_chunk(
+    path: &Path,
+    file_size: u64,
+    progress_sender: Option<&Sender<ProgressUpdate>>,
+    mmap_chunk_size: usize,
+    mut f: impl FnMut(&[u8]) -> ControlFlow<()>,
+) -> HashResult<()> {
     use memmap2::MmapOptions;
 
-    let file = File::open(path).with_path(path)?;
+    let file = with_long_path(path, |p| File::open(p)).with_path(path)?;
     let file_len = file.metadata().with_path(path)?.len();
 
     #[cfg(target_pointer_width = "32")]
     check_chunk_size_fits(mmap_chunk_size as u64, path)?;
 
-    let mut hasher = FileHasher::new();
     let mut processed = 0u64;
 
     let progress_interval = (file_size / 50).max(16 * 1024 * 1024); // 至少16MB间隔
@@ -265,7 +1028,9 @@ fn compute_hash_large_serial(
                 .map_err(|e| HashError::Io(e, path.to_path_buf()))?
         };
 
-        hasher.update(&mmap);
+        if f(&mmap).is_break() {
+            return Ok(());
+        }
         processed += chunk_size as u64;
         offset += chunk_size as u64;
 
@@ -280,12 +1045,103 @@ fn compute_hash_large_serial(
             }
         }
     }
+    Ok(())
+}
 
-    let (crc32, md5, sha1, xxh3) = hasher.finalize().map_err(|e| {
-        eprintln!("[Engine] 哈希计算失败: {}", e);
-        e
-    })?;
-    Ok(format_hash_results(crc32, &md5, &sha1, &xxh3))
+/// 分块哈希的默认块大小
+pub const DEFAULT_HASH_CHUNK: u64 = 4 * 1024 * 1024;
+
+/// 分块哈希中的一块（小写十六进制）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChunkHash {
+    pub offset: u64,
+    pub len: u64,
+    pub xxhash3: String,
+    pub sha256: String,
+}
+
+/// 单个文件按固定大小分块的哈希列表
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChunkedHashes {
+    pub chunk_size: u64,
+    pub file_size: u64,
+    pub chunks: Vec<ChunkHash>,
+    /// 见 `chunk_root`
+    pub root: String,
+}
+
+/// 把文件切成 `chunk_size` 大小的块（最后一块可能较短），分别计算 XXH3 与 SHA256
+///
+/// 用于虚拟机镜像、数据集等大文件的局部去重与分段传输校验：只需重传哈希不一致的块。
+/// 每块之前检查 `cancel`，设置后不再读取剩余部分，返回 `Interrupted` 错误。
+pub fn compute_chunk_hashes(
+    path: &Path,
+    chunk_size: u64,
+    progress_sender: Option<&Sender<ProgressUpdate>>,
+    cancel: &AtomicBool,
+) -> HashResult<ChunkedHashes> {
+    use ring::digest::{SHA256, digest};
+    use xxhash_rust::xxh3::xxh3_128;
+
+    ensure_not_special(path)?;
+    // 块设备的元数据长度为 0，映射不到任何内容
+    if is_block_device(path) {
+        return Err(HashError::Io(
+            io::Error::new(io::ErrorKind::Unsupported, "块设备不支持分块哈希"),
+            path.to_path_buf(),
+        ));
+    }
+    let file_size = with_long_path(path, |p| std::fs::metadata(p))
+        .with_path(path)?
+        .len();
+    let chunk_size = chunk_size.max(1);
+    let mut chunks = Vec::new();
+    let mut offset = 0u64;
+    for_each_mmap_chunk(
+        path,
+        file_size,
+        progress_sender,
+        chunk_size as usize,
+        |data| {
+            if cancel.load(Ordering::Relaxed) {
+                return ControlFlow::Break(());
+            }
+            chunks.push(ChunkHash {
+                offset,
+                len: data.len() as u64,
+                xxhash3: hex::encode(xxh3_128(data).to_be_bytes()),
+                sha256: hex::encode(digest(&SHA256, data)),
+            });
+            offset += data.len() as u64;
+            ControlFlow::Continue(())
+        },
+    )?;
+    if cancel.load(Ordering::Relaxed) {
+        return Err(HashError::Io(
+            io::Error::new(io::ErrorKind::Interrupted, "分块哈希已取消"),
+            path.to_path_buf(),
+        ));
+    }
+
+    Ok(ChunkedHashes {
+        chunk_size,
+        file_size: offset,
+        root: chunk_root(&chunks),
+        chunks,
+    })
+}
+
+/// 根哈希：各块 SHA256 的原始字节按顺序拼接后再计算 SHA256
+///
+/// 只依赖块哈希列表，接收方拿到列表后可以独立验证，不需要读取整个文件。
+pub fn chunk_root(chunks: &[ChunkHash]) -> String {
+    let mut context = ring::digest::Context::new(&ring::digest::SHA256);
+    for chunk in chunks {
+        if let Ok(bytes) = hex::decode(&chunk.sha256) {
+            context.update(&bytes);
+        }
+    }
+    hex::encode(context.finish())
 }
 
 fn should_send_progress(last_update: &mut Instant, processed: u64, total: u64) -> bool {
@@ -314,12 +1170,14 @@ pub fn compute_xxhash3_only(
     progress_sender: Option<&Sender<ProgressUpdate>>,
     buffer_size: usize,
     mmap_chunk_size: usize,
+    force_buffered: bool,
 ) -> HashResult<(String, u64)> {
-    let file_size = std::fs::metadata(path).with_path(path)?.len();
+    let file_size = content_len(path).with_path(path)?;
 
     let xxhash3 = if file_size < TINY_FILE_THRESHOLD {
         compute_xxhash3_tiny(path)?
-    } else if file_size < MEDIUM_FILE_THRESHOLD {
+    } else if file_size < MEDIUM_FILE_THRESHOLD || force_buffered || is_mmap_unsafe(path, file_size)
+    {
         compute_xxhash3_medium(path, file_size, progress_sender, buffer_size)?
     } else {
         compute_xxhash3_large(path, file_size, progress_sender, mmap_chunk_size)?
@@ -328,29 +1186,244 @@ pub fn compute_xxhash3_only(
     Ok((xxhash3, file_size))
 }
 
+/// 稀疏文件只读取已分配的区域，空洞按零字节送入哈希器，结果与普通读取一致
+///
+/// 文件没有空洞或不支持查询时返回 None，由调用方按普通方式计算。
+pub fn compute_sparse_hash(
+    path: &Path,
+    progress_sender: Option<&Sender<ProgressUpdate>>,
+    buffer_size: usize,
+    enabled: EnabledHashes,
+) -> HashResult<Option<(FileHashes, u64)>> {
+    let mut hasher = FileHasher::with_algorithms(enabled);
+    let Some(file_size) = hash_allocated_ranges(path, progress_sender, buffer_size, |data| {
+        hasher.update(data);
+    })?
+    else {
+        return Ok(None);
+    };
+    let digests = hasher.finalize().map_err(|e| {
+        log::warn!("哈希计算失败: {}", e);
+        e
+    })?;
+    Ok(Some((format_hash_results(&digests), file_size)))
+}
+
+/// 稀疏文件的 XXH3（快速模式与缓存校验使用），规则同 [`compute_sparse_hash`]
+pub fn compute_xxhash3_sparse(
+    path: &Path,
+    progress_sender: Option<&Sender<ProgressUpdate>>,
+    buffer_size: usize,
+) -> HashResult<Option<(String, u64)>> {
+    use xxhash_rust::xxh3::Xxh3;
+
+    let mut hasher = Xxh3::new();
+    let file_size = hash_allocated_ranges(path, progress_sender, buffer_size, |data| {
+        hasher.update(data);
+    })?;
+    Ok(file_size.map(|size| (hex::encode(hasher.digest128().to_be_bytes()), size)))
+}
+
+/// 依次把已分配区域的内容与区域之间的零字节交给 `update`，返回文件大小；
+/// 文件不是稀疏文件时不读取任何内容并返回 None
+fn hash_allocated_ranges(
+    path: &Path,
+    progress_sender: Option<&Sender<ProgressUpdate>>,
+    buffer_size: usize,
+    mut update: impl FnMut(&[u8]),
+) -> HashResult<Option<u64>> {
+    let mut file = with_long_path(path, |p| File::open(p)).with_path(path)?;
+    let metadata = file.metadata().with_path(path)?;
+    let Some(ranges) = sparse::allocated_ranges(&file, &metadata).with_path(path)? else {
+        return Ok(None);
+    };
+    let file_size = metadata.len();
+    log::debug!(
+        "稀疏文件 {}：{} 个已分配区域，共 {} 字节",
+        path.display(),
+        ranges.len(),
+        ranges.iter().map(|r| r.end - r.start).sum::<u64>()
+    );
+
+    let mut buffer = vec![0u8; buffer_size.max(64 * 1024)];
+    let zeros = vec![0u8; buffer.len()];
+    let mut processed = 0u64;
+    let mut last_update = Instant::now();
+
+    // 末尾追加一个空区域，统一处理最后一个区域之后的空洞
+    for range in ranges
+        .iter()
+        .chain(std::iter::once(&(file_size..file_size)))
+    {
+        let mut hole = range.start.saturating_sub(processed);
+        while hole > 0 {
+            let n = hole.min(zeros.len() as u64) as usize;
+            update(&zeros[..n]);
+            hole -= n as u64;
+        }
+        processed = processed.max(range.start);
+
+        file.seek(SeekFrom::Start(processed)).with_path(path)?;
+        let mut remaining = range.end.saturating_sub(processed);
+        while remaining > 0 {
+            let want = remaining.min(buffer.len() as u64) as usize;
+            let n = file.read(&mut buffer[..want]).with_path(path)?;
+            if n == 0 {
+                return Err(HashError::Io(
+                    io::Error::new(io::ErrorKind::UnexpectedEof, "文件在计算过程中被截断"),
+                    path.to_path_buf(),
+                ));
+            }
+            update(&buffer[..n]);
+            processed += n as u64;
+            remaining -= n as u64;
+
+            if let Some(sender) = progress_sender {
+                if should_send_progress(&mut last_update, processed, file_size) {
+                    let _ = sender.try_send(ProgressUpdate {
+                        processed,
+                        total: file_size,
+                    });
+                }
+            }
+        }
+    }
+
+    if let Some(sender) = progress_sender {
+        let _ = sender.try_send(ProgressUpdate {
+            processed,
+            total: file_size,
+        });
+    }
+    Ok(Some(file_size))
+}
+
+/// 目录合并哈希：按相对路径排序后，把所有文件的原始内容送入同一个哈希器
+///
+/// 每个文件前写入分隔头：相对路径字节数（u64 LE）+ 相对路径（UTF-8，以 `/` 分隔）
+/// + 文件长度（u64 LE），避免 "ab"+"c" 与 "a"+"bc" 这类边界歧义。
+/// 与扫描一致，跳过以 `.` 开头的条目且不跟随符号链接。
+pub fn compute_directory_hash(
+    root: &Path,
+    buffer_size: usize,
+    enabled: EnabledHashes,
+) -> HashResult<FileHashes> {
+    let mut files: Vec<(String, PathBuf)> = Vec::new();
+    let walker = WalkDir::new(root)
+        .follow_links(false)
+        .into_iter()
+        .filter_entry(|e| e.depth() == 0 || !e.file_name().to_string_lossy().starts_with('.'));
+    for entry in walker {
+        let entry = entry?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let relative = entry
+            .path()
+            .strip_prefix(root)
+            .unwrap_or(entry.path())
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        files.push((relative, entry.into_path()));
+    }
+    files.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let mut hasher = FileHasher::with_algorithms(enabled);
+    let mut buffer = vec![0u8; buffer_size.max(64 * 1024)];
+    for (relative, path) in &files {
+        let file = with_long_path(path, |p| File::open(p)).with_path(path)?;
+        let file_len = file.metadata().with_path(path)?.len();
+
+        hasher.update(&(relative.len() as u64).to_le_bytes());
+        hasher.update(relative.as_bytes());
+        hasher.update(&file_len.to_le_bytes());
+
+        // 只读取分隔头中声明的长度，保证头部与内容一致
+        let mut reader = file.take(file_len);
+        let mut remaining = file_len;
+        loop {
+            let bytes_read = reader.read(&mut buffer).with_path(path)?;
+            if bytes_read == 0 {
+                break;
+            }
+            hasher.update(&buffer[..bytes_read]);
+            remaining -= bytes_read as u64;
+        }
+        if remaining > 0 {
+            return Err(HashError::Io(
+                std::io::Error::new(std::io::ErrorKind::UnexpectedEof, "文件在计算过程中被截断"),
+                path.clone(),
+            ));
+        }
+    }
+
+    Ok(format_hash_results(&hasher.finalize()?))
+}
+
+/// 判断路径本身是否为符号链接（不跟随链接）
+pub fn is_symlink(path: &Path) -> bool {
+    std::fs::symlink_metadata(path).is_ok_and(|m| m.file_type().is_symlink())
+}
+
+/// 符号链接中保存的目标路径
+///
+/// Unix 上为原始字节；Windows 上目标路径为 UTF-16，转为 UTF-8 后返回。
+pub fn link_target_bytes(path: &Path) -> io::Result<Vec<u8>> {
+    let target = std::fs::read_link(path)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::ffi::OsStrExt;
+        Ok(target.as_os_str().as_bytes().to_vec())
+    }
+
+    #[cfg(not(unix))]
+    {
+        Ok(target.to_string_lossy().into_owned().into_bytes())
+    }
+}
+
+/// 符号链接本身的哈希：对链接中保存的目标路径计算，不读取目标文件
+///
+/// 返回哈希与目标路径的字节数（作为链接的大小）。
+pub fn compute_link_hash(path: &Path, enabled: EnabledHashes) -> HashResult<(FileHashes, u64)> {
+    let target = link_target_bytes(path).with_path(path)?;
+    let mut hasher = FileHasher::with_algorithms(enabled);
+    hasher.update(&target);
+    Ok((
+        format_hash_results(&hasher.finalize()?),
+        target.len() as u64,
+    ))
+}
+
 pub fn compute_all_hashes_cached(
     path: &Path,
     progress_sender: Option<&Sender<ProgressUpdate>>,
     buffer_size: usize,
     mmap_chunk_size: usize,
-) -> HashResult<(String, String, String, String, u64)> {
-    let file_size = std::fs::metadata(path).with_path(path)?.len();
+    force_buffered: bool,
+    enabled: EnabledHashes,
+) -> HashResult<(FileHashes, u64)> {
+    let file_size = content_len(path).with_path(path)?;
 
-    let (crc32, md5, sha1, xxhash3) = compute_file_hash(
+    let hashes = compute_file_hash(
         path,
         progress_sender,
         buffer_size,
         mmap_chunk_size,
         Some(file_size),
+        force_buffered,
+        enabled,
     )?;
 
-    Ok((crc32, md5, sha1, xxhash3, file_size))
+    Ok((hashes, file_size))
 }
 
 fn compute_xxhash3_tiny(path: &Path) -> HashResult<String> {
     use xxhash_rust::xxh3::Xxh3;
 
-    let data = std::fs::read(path).with_path(path)?;
+    let data = with_long_path(path, |p| std::fs::read(p)).with_path(path)?;
     let mut hasher = Xxh3::new();
     hasher.update(&data);
     let xxh3 = hasher.digest128();
@@ -366,7 +1439,7 @@ fn compute_xxhash3_medium(
 ) -> HashResult<String> {
     use xxhash_rust::xxh3::Xxh3;
 
-    let file = File::open(path).with_path(path)?;
+    let file = with_long_path(path, |p| File::open(p)).with_path(path)?;
     let mut reader = BufReader::with_capacity(buffer_size, file);
     let mut hasher = Xxh3::new();
 
@@ -418,7 +1491,7 @@ fn compute_xxhash3_large_serial(
     use memmap2::MmapOptions;
     use xxhash_rust::xxh3::Xxh3;
 
-    let file = File::open(path).with_path(path)?;
+    let file = with_long_path(path, |p| File::open(p)).with_path(path)?;
     let file_len = file.metadata().with_path(path)?.len();
 
     #[cfg(target_pointer_width = "32")]
@@ -472,14 +1545,28 @@ mod tests {
             .write_all(b"Hello, World!")
             .expect("Failed to write test data");
 
-        let result = compute_file_hash(temp_file.path(), None, 64 * 1024, 1024 * 1024, None);
+        let result = compute_file_hash(
+            temp_file.path(),
+            None,
+            64 * 1024,
+            1024 * 1024,
+            None,
+            false,
+            EnabledHashes::default(),
+        );
         assert!(
             result.is_ok(),
             "compute_file_hash failed: {:?}",
             result.err()
         );
 
-        let (crc32, md5, sha1, xxh3) = result.unwrap();
+        let FileHashes {
+            crc32,
+            md5,
+            sha1,
+            xxhash3: xxh3,
+            ..
+        } = result.unwrap();
         assert!(!crc32.is_empty());
         assert!(!md5.is_empty());
         assert!(!sha1.is_empty());
@@ -499,6 +1586,53 @@ mod tests {
         assert!(should_send_progress(&mut last_update, 100, 10000));
     }
 
+    #[test]
+    fn test_optimize_chunk_size_keeps_configured_chunk() {
+        const MB: usize = 1024 * 1024;
+        const GB: u64 = 1024 * 1024 * 1024;
+
+        // 默认 4MB 配置保持原有行为
+        assert_eq!(optimize_chunk_size(100 * GB, 4 * MB), 16 * MB);
+        assert_eq!(optimize_chunk_size(2 * GB, 4 * MB), 8 * MB);
+        assert_eq!(optimize_chunk_size(GB / 2, 4 * MB), 4 * MB);
+
+        // 高性能预设的 16MB 块在超大文件上继续放大
+        assert_eq!(optimize_chunk_size(100 * GB, 16 * MB), 64 * MB);
+        assert_eq!(optimize_chunk_size(2 * GB, 16 * MB), 16 * MB);
+
+        // 配置的 64MB 块不会被压回 16MB 或 8MB
+        assert_eq!(optimize_chunk_size(100 * GB, 64 * MB), 64 * MB);
+        assert_eq!(optimize_chunk_size(2 * GB, 64 * MB), 64 * MB);
+    }
+
+    #[test]
+    fn test_medium_file_reports_progress() {
+        let mut temp_file = NamedTempFile::new().expect("Failed to create temp file");
+        let test_data = vec![0x3C_u8; 4 * 1024 * 1024];
+        temp_file
+            .write_all(&test_data)
+            .expect("Failed to write test data");
+        temp_file.flush().expect("Failed to flush");
+
+        let (tx, rx) = crossbeam_channel::bounded(1024);
+        compute_file_hash(
+            temp_file.path(),
+            Some(&tx),
+            256 * 1024,
+            4 * 1024 * 1024,
+            Some(test_data.len() as u64),
+            false,
+            EnabledHashes::default(),
+        )
+        .unwrap();
+
+        let updates: Vec<ProgressUpdate> = rx.try_iter().collect();
+        assert!(!updates.is_empty(), "多 MB 文件至少应有一次进度更新");
+        let last = updates.last().unwrap();
+        assert_eq!(last.processed, test_data.len() as u64);
+        assert_eq!(last.total, test_data.len() as u64);
+    }
+
     #[test]
     fn test_xxh3_correctness() {
         let mut temp_file = NamedTempFile::new().expect("Failed to create temp file");
@@ -543,6 +1677,8 @@ mod tests {
             256 * 1024,
             4 * 1024 * 1024,
             Some(file_size),
+            false,
+            EnabledHashes::default(),
         );
 
         let result2 = compute_file_hash(
@@ -551,13 +1687,27 @@ mod tests {
             512 * 1024,
             8 * 1024 * 1024,
             Some(file_size),
+            false,
+            EnabledHashes::default(),
         );
 
         assert!(result1.is_ok(), "First hash computation failed");
         assert!(result2.is_ok(), "Second hash computation failed");
 
-        let (crc32_1, md5_1, sha1_1, xxh3_1) = result1.unwrap();
-        let (crc32_2, md5_2, sha1_2, xxh3_2) = result2.unwrap();
+        let FileHashes {
+            crc32: crc32_1,
+            md5: md5_1,
+            sha1: sha1_1,
+            xxhash3: xxh3_1,
+            ..
+        } = result1.unwrap();
+        let FileHashes {
+            crc32: crc32_2,
+            md5: md5_2,
+            sha1: sha1_2,
+            xxhash3: xxh3_2,
+            ..
+        } = result2.unwrap();
 
         // 验证相同的文件产生相同的哈希值
         assert_eq!(crc32_1, crc32_2, "CRC32 mismatch");
@@ -589,6 +1739,8 @@ mod tests {
             256 * 1024,
             4 * 1024 * 1024,
             Some(file_size),
+            false,
+            EnabledHashes::default(),
         );
 
         let result2 = compute_file_hash(
@@ -597,13 +1749,27 @@ mod tests {
             256 * 1024,
             4 * 1024 * 1024,
             Some(file_size),
+            false,
+            EnabledHashes::default(),
         );
 
         assert!(result1.is_ok(), "First hash computation failed");
         assert!(result2.is_ok(), "Second hash computation failed");
 
-        let (crc32_1, md5_1, sha1_1, xxh3_1) = result1.unwrap();
-        let (crc32_2, md5_2, sha1_2, xxh3_2) = result2.unwrap();
+        let FileHashes {
+            crc32: crc32_1,
+            md5: md5_1,
+            sha1: sha1_1,
+            xxhash3: xxh3_1,
+            ..
+        } = result1.unwrap();
+        let FileHashes {
+            crc32: crc32_2,
+            md5: md5_2,
+            sha1: sha1_2,
+            xxhash3: xxh3_2,
+            ..
+        } = result2.unwrap();
 
         assert_eq!(crc32_1, crc32_2, "CRC32 should be consistent");
         assert_eq!(md5_1, md5_2, "MD5 should be consistent");
@@ -621,8 +1787,10 @@ mod tests {
         temp_file.flush().expect("Failed to flush");
 
         // 多次计算 xxHash3 应该得到相同结果
-        let result1 = compute_xxhash3_only(temp_file.path(), None, 256 * 1024, 4 * 1024 * 1024);
-        let result2 = compute_xxhash3_only(temp_file.path(), None, 512 * 1024, 8 * 1024 * 1024);
+        let result1 =
+            compute_xxhash3_only(temp_file.path(), None, 256 * 1024, 4 * 1024 * 1024, false);
+        let result2 =
+            compute_xxhash3_only(temp_file.path(), None, 512 * 1024, 8 * 1024 * 1024, false);
 
         assert!(result1.is_ok(), "First xxHash3 computation failed");
         assert!(result2.is_ok(), "Second xxHash3 computation failed");
@@ -633,4 +1801,433 @@ mod tests {
         assert_eq!(size1, size2, "File sizes should match");
         assert_eq!(xxh3_1, xxh3_2, "xxHash3 should be consistent");
     }
+
+    #[test]
+    fn test_network_path_classification() {
+        assert!(is_unc_path(r"\\server\share\file.iso"));
+        assert!(is_unc_path(r"\\?\UNC\server\share\file.iso"));
+        assert!(is_unc_path("//server/share/file.iso"));
+        assert!(!is_unc_path(r"\\?\C:\data\file.iso"));
+        assert!(!is_unc_path(r"C:\data\file.iso"));
+        assert!(!is_unc_path("/home/user/file.iso"));
+
+        assert!(is_network_fs_magic(0x6969)); // NFS
+        assert!(is_network_fs_magic(0xFF53_4D42)); // CIFS
+        assert!(!is_network_fs_magic(0xEF53)); // ext4
+        assert!(!is_network_fs_magic(0x0102_1994)); // tmpfs
+
+        assert!(is_network_fs_name("smbfs"));
+        assert!(!is_network_fs_name("apfs"));
+    }
+
+    #[test]
+    fn test_removable_media_classification() {
+        // 模拟 sysfs：/sys/dev/block/8:17 -> devices/.../block/sdb/sdb1
+        let temp = tempfile::TempDir::new().unwrap();
+        let usb = temp.path().join("sdb");
+        let usb_part = usb.join("sdb1");
+        std::fs::create_dir_all(&usb_part).unwrap();
+        std::fs::write(usb.join("removable"), "1\n").unwrap();
+        let ssd = temp.path().join("nvme0n1");
+        std::fs::create_dir_all(&ssd).unwrap();
+        std::fs::write(ssd.join("removable"), "0\n").unwrap();
+
+        assert!(sysfs_device_is_removable(&usb_part));
+        assert!(sysfs_device_is_removable(&usb));
+        assert!(!sysfs_device_is_removable(&ssd));
+        assert!(!sysfs_device_is_removable(&temp.path().join("missing")));
+
+        assert!(is_external_volume_mount("/Volumes/USB DISK"));
+        assert!(!is_external_volume_mount("/"));
+        assert!(!is_external_volume_mount("/Volumes/"));
+    }
+
+    #[test]
+    fn test_rotational_detection() {
+        // 模拟 sysfs：机械硬盘 sda 的分区 sda1，以及 NVMe 固态硬盘
+        let temp = tempfile::TempDir::new().unwrap();
+        let hdd = temp.path().join("sda");
+        let hdd_part = hdd.join("sda1");
+        std::fs::create_dir_all(&hdd_part).unwrap();
+        std::fs::create_dir_all(hdd.join("queue")).unwrap();
+        std::fs::write(hdd.join("queue").join("rotational"), "1\n").unwrap();
+        let ssd = temp.path().join("nvme0n1");
+        std::fs::create_dir_all(ssd.join("queue")).unwrap();
+        std::fs::write(ssd.join("queue").join("rotational"), "0\n").unwrap();
+        let unknown = temp.path().join("loop0");
+        std::fs::create_dir_all(&unknown).unwrap();
+
+        assert_eq!(sysfs_device_is_rotational(&hdd), Some(true));
+        assert_eq!(sysfs_device_is_rotational(&hdd_part), Some(true));
+        assert_eq!(sysfs_device_is_rotational(&ssd), Some(false));
+        assert_eq!(sysfs_device_is_rotational(&unknown), None);
+        assert_eq!(
+            sysfs_device_is_rotational(&temp.path().join("missing")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_same_volume() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let db = temp.path().join("hash_cache.db");
+        let nested = temp.path().join("photos").join("2024");
+        std::fs::create_dir_all(&nested).unwrap();
+        std::fs::write(&db, b"").unwrap();
+
+        assert_eq!(same_volume(&db, &nested), Some(true));
+        assert_eq!(same_volume(&db, temp.path()), Some(true));
+        assert_eq!(same_volume(&db, &temp.path().join("missing")), None);
+        // procfs 是独立的文件系统
+        #[cfg(target_os = "linux")]
+        assert_eq!(same_volume(&db, Path::new("/proc")), Some(false));
+
+        // 同一卷上的文件只查询一次
+        let removable = RemovableVolumes::default();
+        assert_eq!(removable.contains(&db), is_removable_path(&db));
+        assert_eq!(removable.contains(&nested), is_removable_path(&nested));
+        assert_eq!(removable.known.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_content_len() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(&vec![1u8; 12345]).unwrap();
+        temp_file.flush().unwrap();
+
+        assert!(!is_block_device(temp_file.path()));
+        assert_eq!(content_len(temp_file.path()).unwrap(), 12345);
+        assert_eq!(seek_len(temp_file.path()).unwrap(), 12345);
+
+        // 有可读的块设备时（通常需要 root），容量应与 sysfs 中的扇区数一致
+        #[cfg(target_os = "linux")]
+        if let Ok(entries) = std::fs::read_dir("/sys/class/block") {
+            for entry in entries.filter_map(Result::ok) {
+                let device = Path::new("/dev").join(entry.file_name());
+                let Ok(sectors) = std::fs::read_to_string(entry.path().join("size")) else {
+                    continue;
+                };
+                if !is_block_device(&device) || File::open(&device).is_err() {
+                    continue;
+                }
+                let expected = sectors.trim().parse::<u64>().unwrap() * 512;
+                assert_eq!(content_len(&device).unwrap(), expected);
+                break;
+            }
+        }
+    }
+
+    #[test]
+    fn test_force_buffered_matches_mmap() {
+        let mut temp_file = NamedTempFile::new().expect("Failed to create temp file");
+        let test_data = vec![0x3C_u8; 2 * 1024 * 1024];
+        temp_file
+            .write_all(&test_data)
+            .expect("Failed to write test data");
+        temp_file.flush().expect("Failed to flush");
+
+        let file_size = std::fs::metadata(temp_file.path()).unwrap().len();
+        let enabled = EnabledHashes {
+            md5: true,
+            sha1: true,
+            sha256: true,
+            crc64: true,
+            adler32: true,
+        };
+        let buffered =
+            compute_hash_medium(temp_file.path(), file_size, None, 256 * 1024, enabled).unwrap();
+        let mapped =
+            compute_hash_large_serial(temp_file.path(), file_size, None, 1024 * 1024, enabled)
+                .unwrap();
+
+        assert_eq!(buffered, mapped, "buffered and mmap paths must agree");
+    }
+
+    #[test]
+    fn test_sparse_file_matches_dense_equivalent() {
+        use std::io::{Seek, SeekFrom};
+
+        // 8MB 文件：开头、中间与末尾各有一段数据，其余为空洞
+        let len = 8 * 1024 * 1024;
+        let segments: [(u64, u8); 3] =
+            [(0, 0x11), (3 * 1024 * 1024 + 17, 0x22), (len - 4096, 0x33)];
+        let mut dense = vec![0u8; len as usize];
+        let mut sparse = NamedTempFile::new().expect("Failed to create temp file");
+        sparse.as_file().set_len(len).unwrap();
+        for (offset, byte) in segments {
+            let start = offset as usize;
+            dense[start..start + 4096].fill(byte);
+            sparse.seek(SeekFrom::Start(offset)).unwrap();
+            sparse.write_all(&[byte; 4096]).unwrap();
+        }
+        sparse.flush().unwrap();
+
+        let enabled = EnabledHashes {
+            md5: true,
+            sha1: true,
+            sha256: true,
+            crc64: true,
+            adler32: true,
+        };
+        let mut expected = FileHasher::with_algorithms(enabled);
+        expected.update(&dense);
+        let expected = format_hash_results(&expected.finalize().unwrap());
+        let plain = compute_file_hash(
+            sparse.path(),
+            None,
+            256 * 1024,
+            4 * 1024 * 1024,
+            None,
+            false,
+            enabled,
+        )
+        .unwrap();
+        assert_eq!(plain, expected);
+
+        // 文件系统不支持空洞时文件按普通文件处理
+        match compute_sparse_hash(sparse.path(), None, 256 * 1024, enabled).unwrap() {
+            Some((hashes, size)) => {
+                assert_eq!(size, len);
+                assert_eq!(hashes, expected, "holes must hash as zeros");
+                let (xxhash3, _) = compute_xxhash3_sparse(sparse.path(), None, 256 * 1024)
+                    .unwrap()
+                    .unwrap();
+                assert_eq!(xxhash3, expected.xxhash3);
+            }
+            None => log::warn!("临时目录所在文件系统不支持稀疏文件"),
+        }
+
+        // 完全写满的文件没有空洞
+        let mut full = NamedTempFile::new().expect("Failed to create temp file");
+        full.write_all(&dense).unwrap();
+        full.flush().unwrap();
+        assert!(
+            compute_sparse_hash(full.path(), None, 256 * 1024, enabled)
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_quick_hash_identical_files_match() {
+        let data: Vec<u8> = (0..300 * 1024).map(|i| (i % 251) as u8).collect();
+        let mut file1 = NamedTempFile::new().expect("Failed to create temp file");
+        let mut file2 = NamedTempFile::new().expect("Failed to create temp file");
+        file1.write_all(&data).expect("Failed to write test data");
+        file2.write_all(&data).expect("Failed to write test data");
+        file1.flush().expect("Failed to flush");
+        file2.flush().expect("Failed to flush");
+
+        let hash1 = quick_hash(file1.path()).unwrap();
+        let hash2 = quick_hash(file2.path()).unwrap();
+        assert_eq!(hash1, hash2);
+        assert_eq!(hash1.len(), 32);
+    }
+
+    #[test]
+    fn test_quick_hash_different_tails_diverge() {
+        let data: Vec<u8> = (0..300 * 1024).map(|i| (i % 251) as u8).collect();
+        let mut tail_changed = data.clone();
+        let last = tail_changed.len() - 1;
+        tail_changed[last] ^= 0xFF;
+
+        let mut file1 = NamedTempFile::new().expect("Failed to create temp file");
+        let mut file2 = NamedTempFile::new().expect("Failed to create temp file");
+        file1.write_all(&data).expect("Failed to write test data");
+        file2
+            .write_all(&tail_changed)
+            .expect("Failed to write test data");
+        file1.flush().expect("Failed to flush");
+        file2.flush().expect("Failed to flush");
+
+        assert_ne!(
+            quick_hash(file1.path()).unwrap(),
+            quick_hash(file2.path()).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_directory_hash_of_two_file_tree() {
+        let temp = tempfile::TempDir::new().unwrap();
+        std::fs::create_dir(temp.path().join("sub")).unwrap();
+        std::fs::write(temp.path().join("sub").join("b.txt"), b"world").unwrap();
+        std::fs::write(temp.path().join("a.txt"), b"hello").unwrap();
+        std::fs::write(temp.path().join(".hidden"), b"ignored").unwrap();
+
+        let enabled = EnabledHashes::default();
+        let hashes = compute_directory_hash(temp.path(), 64 * 1024, enabled).unwrap();
+
+        // 按相对路径排序，每个文件前是 路径长度 + 路径 + 文件长度
+        let mut expected = FileHasher::with_algorithms(enabled);
+        for (relative, content) in [("a.txt", &b"hello"[..]), ("sub/b.txt", &b"world"[..])] {
+            expected.update(&(relative.len() as u64).to_le_bytes());
+            expected.update(relative.as_bytes());
+            expected.update(&(content.len() as u64).to_le_bytes());
+            expected.update(content);
+        }
+        assert_eq!(hashes, format_hash_results(&expected.finalize().unwrap()));
+
+        // 内容拼接相同但文件边界不同，结果必须不同
+        std::fs::write(temp.path().join("a.txt"), b"hellow").unwrap();
+        std::fs::write(temp.path().join("sub").join("b.txt"), b"orld").unwrap();
+        let shifted = compute_directory_hash(temp.path(), 64 * 1024, enabled).unwrap();
+        assert_ne!(hashes, shifted);
+    }
+
+    #[test]
+    fn test_exact_sizes_match_tuned_sizes() {
+        let data: Vec<u8> = (0..=250).cycle().take(3 * 1024 * 1024 + 123).collect();
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(&data).unwrap();
+        temp_file.flush().unwrap();
+        let path = temp_file.path();
+        let size = data.len() as u64;
+        let enabled = EnabledHashes::default();
+
+        let tuned = compute_file_hash_with(
+            path,
+            size,
+            IoStrategy::Mmap,
+            None,
+            64 * 1024,
+            1024 * 1024,
+            enabled,
+        )
+        .unwrap();
+        // 未对齐到 2MB 的映射块与很小的缓冲区原样使用，结果不变
+        for (strategy, buffer_size, chunk_size) in [
+            (IoStrategy::Mmap, 64 * 1024, 1024 * 1024),
+            (IoStrategy::Mmap, 64 * 1024, 192 * 1024),
+            (IoStrategy::Buffered, 4096, 1024 * 1024),
+        ] {
+            let exact = compute_file_hash_exact(
+                path,
+                size,
+                strategy,
+                None,
+                buffer_size,
+                chunk_size,
+                enabled,
+            )
+            .unwrap();
+            assert_eq!(exact, tuned, "{strategy:?} {buffer_size} {chunk_size}");
+        }
+    }
+
+    #[test]
+    fn test_chunk_hashes_and_stable_root() {
+        use ring::digest::{SHA256, digest};
+
+        const CHUNK: u64 = 64 * 1024;
+        let mut data: Vec<u8> = (0..CHUNK * 2 + 1000).map(|i| (i % 251) as u8).collect();
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(&data).unwrap();
+        temp_file.flush().unwrap();
+
+        let cancel = AtomicBool::new(false);
+        let chunked = compute_chunk_hashes(temp_file.path(), CHUNK, None, &cancel).unwrap();
+        assert_eq!(chunked.file_size, data.len() as u64);
+        let spans: Vec<(u64, u64)> = chunked.chunks.iter().map(|c| (c.offset, c.len)).collect();
+        assert_eq!(spans, [(0, CHUNK), (CHUNK, CHUNK), (CHUNK * 2, 1000)]);
+        let last = &data[(CHUNK * 2) as usize..];
+        assert_eq!(chunked.chunks[2].sha256, hex::encode(digest(&SHA256, last)));
+        assert_eq!(
+            chunked.chunks[2].xxhash3,
+            hex::encode(xxhash_rust::xxh3::xxh3_128(last).to_be_bytes())
+        );
+
+        // 根哈希为各块 SHA256 原始字节拼接后的 SHA256，重复计算结果不变
+        let concatenated: Vec<u8> = chunked
+            .chunks
+            .iter()
+            .flat_map(|c| hex::decode(&c.sha256).unwrap())
+            .collect();
+        assert_eq!(chunked.root, hex::encode(digest(&SHA256, &concatenated)));
+        assert_eq!(chunk_root(&chunked.chunks), chunked.root);
+        assert_eq!(
+            compute_chunk_hashes(temp_file.path(), CHUNK, None, &cancel).unwrap(),
+            chunked
+        );
+
+        // 只修改第二块：其余块不变，根哈希改变
+        data[CHUNK as usize + 10] ^= 0xFF;
+        std::fs::write(temp_file.path(), &data).unwrap();
+        let modified = compute_chunk_hashes(temp_file.path(), CHUNK, None, &cancel).unwrap();
+        assert_eq!(modified.chunks[0], chunked.chunks[0]);
+        assert_ne!(modified.chunks[1], chunked.chunks[1]);
+        assert_eq!(modified.chunks[2], chunked.chunks[2]);
+        assert_ne!(modified.root, chunked.root);
+    }
+
+    #[test]
+    fn test_chunk_hashes_progress_and_cancel() {
+        const CHUNK: u64 = 1024 * 1024;
+        let temp_file = NamedTempFile::new().unwrap();
+        temp_file.as_file().set_len(CHUNK * 40).unwrap();
+
+        // 进度间隔至少 16MB
+        let (progress_tx, progress_rx) = crossbeam_channel::unbounded();
+        let cancel = AtomicBool::new(false);
+        compute_chunk_hashes(temp_file.path(), CHUNK, Some(&progress_tx), &cancel).unwrap();
+        let updates: Vec<u64> = progress_rx.try_iter().map(|u| u.processed).collect();
+        assert_eq!(updates, [16 * CHUNK, 32 * CHUNK]);
+
+        cancel.store(true, Ordering::Relaxed);
+        let error = compute_chunk_hashes(temp_file.path(), CHUNK, None, &cancel).unwrap_err();
+        assert!(
+            matches!(&error, HashError::Io(e, _) if e.kind() == io::ErrorKind::Interrupted),
+            "{error}"
+        );
+    }
+
+    #[test]
+    fn test_verbatim_path() {
+        assert_eq!(
+            verbatim_path(r"C:\data\file.bin").as_deref(),
+            Some(r"\\?\C:\data\file.bin")
+        );
+        assert_eq!(
+            verbatim_path("D:/data/file.bin").as_deref(),
+            Some(r"\\?\D:\data\file.bin")
+        );
+        assert_eq!(
+            verbatim_path(r"\\server\share\file.bin").as_deref(),
+            Some(r"\\?\UNC\server\share\file.bin")
+        );
+        assert_eq!(verbatim_path(r"\\?\C:\data\file.bin"), None);
+        assert_eq!(verbatim_path(r"relative\file.bin"), None);
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn test_long_path_is_readable() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let mut dir = temp.path().to_path_buf();
+        while dir.as_os_str().len() < 280 {
+            dir.push("a_fairly_long_directory_name_for_testing");
+        }
+        let path = dir.join("data.bin");
+        let data = vec![42u8; 100 * 1024];
+
+        // 用 verbatim 前缀创建，确保不依赖系统的长路径设置
+        let verbatim = PathBuf::from(verbatim_path(&path.to_string_lossy()).unwrap());
+        std::fs::create_dir_all(verbatim.parent().unwrap()).unwrap();
+        std::fs::write(&verbatim, &data).unwrap();
+
+        let mut expected = FileHasher::new();
+        expected.update(&data);
+        let expected = format_hash_results(&expected.finalize().unwrap());
+
+        let hashes = compute_file_hash(
+            &path,
+            None,
+            64 * 1024,
+            4 * 1024 * 1024,
+            None,
+            false,
+            EnabledHashes::default(),
+        )
+        .unwrap();
+        assert_eq!(hashes, expected);
+    }
 }
@@ -65,6 +65,8 @@ pub enum HashError {
     SystemResource(String),
     #[cfg(target_pointer_width = "32")]
     FileTooLarge(PathBuf),
+    #[cfg(any(feature = "remote-verify", feature = "update-check"))]
+    Network(String),
 }
 
 impl fmt::Display for HashError {
@@ -90,12 +92,29 @@ impl fmt::Display for HashError {
             HashError::FileTooLarge(path) => {
                 write!(f, "文件过大（超过32位系统限制）: {}", path.display())
             }
+            #[cfg(any(feature = "remote-verify", feature = "update-check"))]
+            HashError::Network(msg) => {
+                write!(f, "网络错误: {}", msg)
+            }
         }
     }
 }
 
 impl std::error::Error for HashError {}
 
+impl HashError {
+    /// 数据库被其他连接占用（SQLITE_BUSY/SQLITE_LOCKED），稍后重试可能成功
+    pub fn is_database_locked(&self) -> bool {
+        matches!(
+            self,
+            HashError::Cache {
+                kind: CacheErrorKind::DatabaseLocked,
+                ..
+            }
+        )
+    }
+}
+
 impl From<io::Error> for HashError {
     fn from(err: io::Error) -> Self {
         HashError::Io(err, PathBuf::from("unknown"))
@@ -0,0 +1,507 @@
+// 校验文件导出模块
+
+use std::fmt::Write;
+use std::fs::File;
+use std::io::{self, BufWriter, Write as _};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use crate::engine::ChunkedHashes;
+use crate::hash::HashAlgorithm;
+use crate::ui::{FileItem, FileStatus};
+
+/// 十六进制摘要的大小写
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HexCase {
+    Lower,
+    Upper,
+}
+
+impl HexCase {
+    pub fn apply(self, hex: &str) -> String {
+        match self {
+            HexCase::Lower => hex.to_lowercase(),
+            HexCase::Upper => hex.to_uppercase(),
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            HexCase::Lower => "小写",
+            HexCase::Upper => "大写",
+        }
+    }
+}
+
+/// 校验文件格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChecksumFormat {
+    Md5Sum,
+    Sha1Sum,
+    Sha256Sum,
+    Sfv,
+}
+
+impl ChecksumFormat {
+    pub const ALL: [ChecksumFormat; 4] = [
+        ChecksumFormat::Md5Sum,
+        ChecksumFormat::Sha1Sum,
+        ChecksumFormat::Sha256Sum,
+        ChecksumFormat::Sfv,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ChecksumFormat::Md5Sum => "MD5 (md5sum)",
+            ChecksumFormat::Sha1Sum => "SHA1 (sha1sum)",
+            ChecksumFormat::Sha256Sum => "SHA256 (sha256sum)",
+            ChecksumFormat::Sfv => "SFV (CRC32)",
+        }
+    }
+
+    pub fn algorithm(self) -> HashAlgorithm {
+        match self {
+            ChecksumFormat::Md5Sum => HashAlgorithm::Md5,
+            ChecksumFormat::Sha1Sum => HashAlgorithm::Sha1,
+            ChecksumFormat::Sha256Sum => HashAlgorithm::Sha256,
+            ChecksumFormat::Sfv => HashAlgorithm::Crc32,
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            ChecksumFormat::Md5Sum => "md5",
+            ChecksumFormat::Sha1Sum => "sha1",
+            ChecksumFormat::Sha256Sum => "sha256",
+            ChecksumFormat::Sfv => "sfv",
+        }
+    }
+
+    /// 各格式惯用的大小写：coreutils 输出小写，QuickSFV 输出大写
+    pub fn default_case(self) -> HexCase {
+        match self {
+            ChecksumFormat::Md5Sum | ChecksumFormat::Sha1Sum | ChecksumFormat::Sha256Sum => {
+                HexCase::Lower
+            }
+            ChecksumFormat::Sfv => HexCase::Upper,
+        }
+    }
+}
+
+/// 生成校验文件内容
+///
+/// 位于 `base_dir` 下的文件写为以 `/` 分隔的相对路径（校验文件约定相对于自身所在目录），
+/// 其余写绝对路径。未完成或缺少对应哈希的文件被跳过，返回 (内容, 写入数, 跳过数)。
+pub fn format_checksum_file(
+    files: &[FileItem],
+    format: ChecksumFormat,
+    case: HexCase,
+    base_dir: &Path,
+) -> (String, usize, usize) {
+    let algorithm = format.algorithm();
+    let mut content = String::new();
+    if format == ChecksumFormat::Sfv {
+        content.push_str("; Generated by TurboHash\n");
+    }
+
+    let (mut written, mut skipped) = (0, 0);
+    for file in files {
+        let hash = file.hash_value(algorithm);
+        if !matches!(file.status, FileStatus::Completed) || hash.is_empty() {
+            skipped += 1;
+            continue;
+        }
+
+        let path = display_path(&file.path, base_dir);
+        let hash = case.apply(hash);
+        let _ = match format {
+            // 两个空格表示文本模式，与 md5sum/sha1sum/sha256sum 输出一致
+            ChecksumFormat::Md5Sum | ChecksumFormat::Sha1Sum | ChecksumFormat::Sha256Sum => {
+                writeln!(content, "{}  {}", hash, path)
+            }
+            ChecksumFormat::Sfv => writeln!(content, "{} {}", path, hash),
+        };
+        written += 1;
+    }
+
+    (content, written, skipped)
+}
+
+/// 生成分块哈希列表：`#` 开头的头部记录文件名、大小、块大小与根哈希，
+/// 之后每行为制表符分隔的 `序号 偏移 长度 XXH3 SHA256`
+pub fn format_chunk_list(file_name: &str, hashes: &ChunkedHashes, case: HexCase) -> String {
+    let mut content = String::new();
+    let _ = writeln!(content, "# file: {}", file_name);
+    let _ = writeln!(content, "# size: {}", hashes.file_size);
+    let _ = writeln!(content, "# chunk_size: {}", hashes.chunk_size);
+    let _ = writeln!(content, "# root_sha256: {}", case.apply(&hashes.root));
+    for (index, chunk) in hashes.chunks.iter().enumerate() {
+        let _ = writeln!(
+            content,
+            "{}\t{}\t{}\t{}\t{}",
+            index,
+            chunk.offset,
+            chunk.len,
+            case.apply(&chunk.xxhash3),
+            case.apply(&chunk.sha256)
+        );
+    }
+    content
+}
+
+/// 流式导出的格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StreamFormat {
+    Csv,
+    Jsonl,
+}
+
+impl StreamFormat {
+    pub const ALL: [StreamFormat; 2] = [StreamFormat::Csv, StreamFormat::Jsonl];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            StreamFormat::Csv => "CSV",
+            StreamFormat::Jsonl => "JSON Lines",
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            StreamFormat::Csv => "csv",
+            StreamFormat::Jsonl => "jsonl",
+        }
+    }
+}
+
+/// 流式导出的刷新间隔：崩溃或强制结束时最多丢失这段时间内完成的结果
+const STREAM_FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// 流式导出：批次开始时创建文件，每个文件完成后追加一行
+///
+/// 结果不在内存中累积，百万级文件的批次也只占用写缓冲；批次被取消时文件中保留已完成的部分。
+pub struct StreamExport {
+    path: PathBuf,
+    format: StreamFormat,
+    writer: BufWriter<File>,
+    written: usize,
+    last_flush: Instant,
+}
+
+impl StreamExport {
+    /// 创建（覆盖）导出文件，CSV 先写入表头
+    pub fn create(path: &Path, format: StreamFormat) -> io::Result<Self> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        if format == StreamFormat::Csv {
+            let mut header = String::from("path,size");
+            for algorithm in HashAlgorithm::ALL {
+                header.push(',');
+                header.push_str(algorithm.key());
+            }
+            header.push_str(",from_cache,note");
+            writeln!(writer, "{}", header)?;
+        }
+        Ok(Self {
+            path: path.to_path_buf(),
+            format,
+            writer,
+            written: 0,
+            last_flush: Instant::now(),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// 追加一个已完成的文件，距上次刷新超过间隔时写入磁盘
+    pub fn append(&mut self, file: &FileItem) -> io::Result<()> {
+        let line = match self.format {
+            StreamFormat::Csv => csv_line(file),
+            StreamFormat::Jsonl => json_line(file),
+        };
+        writeln!(self.writer, "{}", line)?;
+        self.written += 1;
+        if self.last_flush.elapsed() >= STREAM_FLUSH_INTERVAL {
+            self.writer.flush()?;
+            self.last_flush = Instant::now();
+        }
+        Ok(())
+    }
+
+    /// 批次结束或取消时关闭文件，返回写入的行数
+    pub fn finish(mut self) -> io::Result<usize> {
+        self.writer.flush()?;
+        Ok(self.written)
+    }
+}
+
+fn csv_line(file: &FileItem) -> String {
+    let mut line = format!("{},{}", csv_field(&file.path.to_string_lossy()), file.size);
+    for algorithm in HashAlgorithm::ALL {
+        line.push(',');
+        line.push_str(&file.hash_value(algorithm).to_lowercase());
+    }
+    line.push_str(if file.from_cache { ",true" } else { ",false" });
+    line.push(',');
+    line.push_str(&csv_field(&file.note));
+    line
+}
+
+/// 含逗号、引号或换行的字段加引号，引号写作两个引号
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+/// 只写出已计算的算法与非空的备注
+fn json_line(file: &FileItem) -> String {
+    let mut line = format!(
+        "{{\"path\":{},\"size\":{}",
+        json_string(&file.path.to_string_lossy()),
+        file.size
+    );
+    for algorithm in HashAlgorithm::ALL {
+        let hash = file.hash_value(algorithm);
+        if !hash.is_empty() {
+            let _ = write!(line, ",\"{}\":\"{}\"", algorithm.key(), hash.to_lowercase());
+        }
+    }
+    let _ = write!(line, ",\"from_cache\":{}", file.from_cache);
+    if !file.note.is_empty() {
+        let _ = write!(line, ",\"note\":{}", json_string(&file.note));
+    }
+    line.push('}');
+    line
+}
+
+/// 转义为带引号的 JSON 字符串
+pub fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if u32::from(c) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", u32::from(c));
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// 「复制校验命令」的目标 shell
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VerifyShell {
+    PowerShell,
+    Bash,
+}
+
+impl VerifyShell {
+    pub const ALL: [VerifyShell; 2] = [VerifyShell::PowerShell, VerifyShell::Bash];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            VerifyShell::PowerShell => "PowerShell",
+            VerifyShell::Bash => "bash (coreutils)",
+        }
+    }
+}
+
+/// 生成可直接运行的单行校验命令，需在文件所在目录执行
+///
+/// 选用已计算的最强算法；CRC32 与 XXH3 没有系统自带的校验工具，只有这两种结果时返回 None。
+pub fn verify_command(item: &FileItem, shell: VerifyShell) -> Option<String> {
+    let algorithm = [
+        HashAlgorithm::Sha256,
+        HashAlgorithm::Sha1,
+        HashAlgorithm::Md5,
+    ]
+    .into_iter()
+    .find(|algorithm| !item.hash_value(*algorithm).is_empty())?;
+    let hash = item.hash_value(algorithm);
+    let name = item.filename();
+
+    Some(match shell {
+        // Get-FileHash 输出大写，-eq 比较不区分大小写
+        VerifyShell::PowerShell => format!(
+            "(Get-FileHash -LiteralPath {} -Algorithm {}).Hash -eq {}",
+            powershell_quote(&name),
+            algorithm.label(),
+            powershell_quote(&hash.to_uppercase())
+        ),
+        VerifyShell::Bash => format!(
+            "echo {} | {}sum -c",
+            shell_quote(&format!("{}  {}", hash.to_lowercase(), name)),
+            algorithm.key()
+        ),
+    })
+}
+
+/// POSIX shell 单引号转义：`'` → `'\''`
+fn shell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', r"'\''"))
+}
+
+/// PowerShell 单引号字符串中 `'` 写作 `''`
+fn powershell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "''"))
+}
+
+fn display_path(path: &Path, base_dir: &Path) -> String {
+    let path = dunce::simplified(path);
+    match path.strip_prefix(dunce::simplified(base_dir)) {
+        Ok(relative) => relative
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/"),
+        Err(_) => path.display().to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn completed_item(path: &Path) -> FileItem {
+        FileItem::completed(
+            path,
+            3,
+            &[
+                (HashAlgorithm::Crc32, "352441c2"),
+                (HashAlgorithm::Md5, "900150983cd24fb0d6963f7d28e17f72"),
+                (
+                    HashAlgorithm::Sha1,
+                    "A9993E364706816ABA3E25717850C26C9CD0D89D",
+                ),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_default_case_matches_format_convention() {
+        let base = PathBuf::from("/data");
+        let files = vec![completed_item(&base.join("sub").join("abc.txt"))];
+
+        let (md5sum, written, skipped) = format_checksum_file(
+            &files,
+            ChecksumFormat::Md5Sum,
+            ChecksumFormat::Md5Sum.default_case(),
+            &base,
+        );
+        assert_eq!(md5sum, "900150983cd24fb0d6963f7d28e17f72  sub/abc.txt\n");
+        assert_eq!((written, skipped), (1, 0));
+
+        let (sha1sum, _, _) = format_checksum_file(
+            &files,
+            ChecksumFormat::Sha1Sum,
+            ChecksumFormat::Sha1Sum.default_case(),
+            &base,
+        );
+        assert_eq!(
+            sha1sum,
+            "a9993e364706816aba3e25717850c26c9cd0d89d  sub/abc.txt\n"
+        );
+
+        let (sfv, _, _) = format_checksum_file(
+            &files,
+            ChecksumFormat::Sfv,
+            ChecksumFormat::Sfv.default_case(),
+            &base,
+        );
+        assert!(sfv.ends_with("sub/abc.txt 352441C2\n"));
+    }
+
+    #[test]
+    fn test_case_override_and_skipped_files() {
+        let base = PathBuf::from("/data");
+        let mut pending = FileItem::new(base.join("pending.bin"), 1);
+        pending.status = FileStatus::Pending;
+        let files = vec![completed_item(&base.join("abc.txt")), pending];
+
+        let (md5sum, written, skipped) =
+            format_checksum_file(&files, ChecksumFormat::Md5Sum, HexCase::Upper, &base);
+        assert_eq!(md5sum, "900150983CD24FB0D6963F7D28E17F72  abc.txt\n");
+        assert_eq!((written, skipped), (1, 1));
+
+        // 未启用 SHA256 时没有可导出的行
+        let (_, written, skipped) =
+            format_checksum_file(&files, ChecksumFormat::Sha256Sum, HexCase::Lower, &base);
+        assert_eq!((written, skipped), (0, 2));
+    }
+
+    #[test]
+    fn test_cancelled_stream_export_keeps_completed_subset() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let mut files: Vec<FileItem> = ["a.bin", "b,\"quoted\".bin", "c.bin", "d.bin"]
+            .iter()
+            .map(|name| completed_item(&dir.path().join(name)))
+            .collect();
+        files[1].note = "原始版本, \"勿删\"".to_string();
+
+        for format in StreamFormat::ALL {
+            let target = dir.path().join(format!("stream.{}", format.extension()));
+            let mut export = StreamExport::create(&target, format).unwrap();
+            // 前两个文件完成后批次被取消，其余文件不会再追加
+            for file in &files[..2] {
+                export.append(file).unwrap();
+            }
+            assert_eq!(export.finish().unwrap(), 2);
+
+            let content = std::fs::read_to_string(&target).unwrap();
+            let lines: Vec<&str> = content.lines().collect();
+            match format {
+                StreamFormat::Csv => {
+                    assert_eq!(lines.len(), 3);
+                    assert!(lines[0].starts_with("path,size,crc32,"));
+                    assert!(lines[0].ends_with(",from_cache,note"));
+                    assert!(lines[1].contains(",3,352441c2,"));
+                    assert!(lines[1].ends_with(",false,"));
+                    assert!(lines[2].contains("b,\"\"quoted\"\".bin\","));
+                    assert!(lines[2].contains("a9993e364706816aba3e25717850c26c9cd0d89d"));
+                    assert!(lines[2].ends_with(",false,\"原始版本, \"\"勿删\"\"\""));
+                }
+                StreamFormat::Jsonl => {
+                    assert_eq!(lines.len(), 2);
+                    assert!(lines[1].contains(r#"b,\"quoted\".bin""#));
+                    assert!(lines[1].contains(r#""md5":"900150983cd24fb0d6963f7d28e17f72""#));
+                    assert!(!lines[1].contains("sha256"));
+                    assert!(lines[0].ends_with(r#","from_cache":false}"#));
+                    assert!(
+                        lines[1].ends_with(r#","from_cache":false,"note":"原始版本, \"勿删\""}"#)
+                    );
+                }
+            }
+            assert!(!content.contains("c.bin") && !content.contains("d.bin"));
+        }
+    }
+
+    #[test]
+    fn test_verify_command_uses_strongest_hash_and_quotes_names() {
+        let item = completed_item(Path::new("/data/it's here.txt"));
+        assert_eq!(
+            verify_command(&item, VerifyShell::Bash).unwrap(),
+            "echo 'a9993e364706816aba3e25717850c26c9cd0d89d  it'\\''s here.txt' | sha1sum -c"
+        );
+        assert_eq!(
+            verify_command(&item, VerifyShell::PowerShell).unwrap(),
+            "(Get-FileHash -LiteralPath 'it''s here.txt' -Algorithm SHA1).Hash -eq 'A9993E364706816ABA3E25717850C26C9CD0D89D'"
+        );
+
+        // 只有 CRC32/XXH3 时没有系统工具可用
+        let mut weak = FileItem::new(PathBuf::from("/data/a.bin"), 1);
+        weak.crc32 = "352441c2".to_string();
+        assert!(verify_command(&weak, VerifyShell::Bash).is_none());
+    }
+}
@@ -0,0 +1,278 @@
+// 按文件夹分组模块
+//
+// 把队列中的文件按所在目录组织为树，供表格之外的分组视图渲染。根节点为所有文件的
+// 公共上级目录；只含一个子文件夹、本身没有文件的中间层合并显示（如 `src/bin`）。
+
+use std::collections::{BTreeMap, HashMap};
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+use crate::ui::{FileItem, FileStatus};
+
+/// 文件夹节点
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FolderNode {
+    /// 文件夹的完整路径；文件不在同一磁盘上时根节点为空路径，只作为各磁盘的容器
+    pub path: PathBuf,
+    /// 相对于上级节点的名称，根节点为完整路径
+    pub name: String,
+    pub children: Vec<FolderNode>,
+    /// 直接位于该文件夹下的文件（`files` 中的下标），按文件名排序
+    pub files: Vec<usize>,
+}
+
+/// 文件夹（含子文件夹）的汇总
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct FolderSummary {
+    pub file_count: usize,
+    pub completed: usize,
+    pub total_size: u64,
+    /// 按文件大小加权的进度（0.0 - 1.0），不含过大与已删除的文件
+    pub progress: f64,
+}
+
+/// 按文件夹分组视图中的一行
+#[derive(Debug, Clone, Copy)]
+pub enum FolderRow<'a> {
+    Folder { node: &'a FolderNode, depth: usize },
+    File { idx: usize, depth: usize },
+}
+
+impl FolderNode {
+    /// 一次遍历汇总每个文件夹（含子文件夹）的数量、完成数、总大小与进度，按文件夹路径索引
+    pub fn summarize_all(&self, files: &[FileItem]) -> HashMap<PathBuf, FolderSummary> {
+        let mut summaries = HashMap::new();
+        self.collect_summaries(files, &mut |node, summary| {
+            summaries.insert(node.path.clone(), summary);
+        });
+        summaries
+    }
+
+    /// 先汇总子文件夹再累加到上级，每个文件只访问一次
+    fn collect_summaries(
+        &self,
+        files: &[FileItem],
+        visit: &mut impl FnMut(&FolderNode, FolderSummary),
+    ) -> (FolderSummary, ProgressTotals) {
+        let mut summary = FolderSummary::default();
+        let mut totals = ProgressTotals::default();
+        for file in self.files.iter().filter_map(|&idx| files.get(idx)) {
+            summary.file_count += 1;
+            summary.total_size += file.size;
+            if matches!(file.status, FileStatus::Completed) {
+                summary.completed += 1;
+            }
+            // 与全局进度一致：未加入计算队列与已删除的文件不参与
+            if !matches!(file.status, FileStatus::Oversized | FileStatus::Deleted) {
+                totals.processed += file.progress * file.size as f64;
+                totals.size += file.size;
+                totals.count += 1;
+                totals.progress_sum += file.progress;
+            }
+        }
+        for child in &self.children {
+            let (child_summary, child_totals) = child.collect_summaries(files, visit);
+            summary.file_count += child_summary.file_count;
+            summary.completed += child_summary.completed;
+            summary.total_size += child_summary.total_size;
+            totals.processed += child_totals.processed;
+            totals.size += child_totals.size;
+            totals.count += child_totals.count;
+            totals.progress_sum += child_totals.progress_sum;
+        }
+
+        summary.progress = if totals.size > 0 {
+            totals.processed / totals.size as f64
+        } else if totals.count > 0 {
+            // 全部为空文件时按文件数计算
+            totals.progress_sum / totals.count as f64
+        } else {
+            0.0
+        };
+        visit(self, summary);
+        (summary, totals)
+    }
+
+    /// 展开的部分按显示顺序排为行：文件夹标题，其后为子文件夹与直接包含的文件。
+    /// 根节点为空路径（文件不在同一磁盘上）时各磁盘作为顶层；`is_open(节点, 深度)`
+    /// 决定文件夹是否展开
+    pub fn visible_rows(
+        &self,
+        mut is_open: impl FnMut(&FolderNode, usize) -> bool,
+    ) -> Vec<FolderRow<'_>> {
+        let mut rows = Vec::new();
+        if self.path.as_os_str().is_empty() {
+            for child in &self.children {
+                child.push_rows(0, &mut is_open, &mut rows);
+            }
+        } else {
+            self.push_rows(0, &mut is_open, &mut rows);
+        }
+        rows
+    }
+
+    fn push_rows<'a>(
+        &'a self,
+        depth: usize,
+        is_open: &mut impl FnMut(&FolderNode, usize) -> bool,
+        rows: &mut Vec<FolderRow<'a>>,
+    ) {
+        rows.push(FolderRow::Folder { node: self, depth });
+        if !is_open(self, depth) {
+            return;
+        }
+        for child in &self.children {
+            child.push_rows(depth + 1, is_open, rows);
+        }
+        rows.extend(self.files.iter().map(|&idx| FolderRow::File {
+            idx,
+            depth: depth + 1,
+        }));
+    }
+}
+
+/// 参与进度计算的文件的累计值
+#[derive(Default)]
+struct ProgressTotals {
+    /// 已处理的字节数
+    processed: f64,
+    size: u64,
+    count: usize,
+    progress_sum: f64,
+}
+
+/// 构建过程中的节点，子文件夹按名称排序
+#[derive(Default)]
+struct Builder {
+    children: BTreeMap<OsString, Builder>,
+    files: Vec<usize>,
+}
+
+impl Builder {
+    /// 转为节点，沿途合并只有一个子文件夹且没有文件的中间层
+    fn into_node(mut self, mut path: PathBuf, base: &Path, files: &[FileItem]) -> FolderNode {
+        while self.files.is_empty() && self.children.len() == 1 {
+            let Some((name, child)) = self.children.pop_first() else {
+                break;
+            };
+            path.push(name);
+            self = child;
+        }
+
+        let name = path
+            .strip_prefix(base)
+            .ok()
+            .filter(|relative| !relative.as_os_str().is_empty())
+            .map_or_else(
+                || dunce::simplified(&path).display().to_string(),
+                |relative| relative.display().to_string(),
+            );
+        let children = self
+            .children
+            .into_iter()
+            .map(|(name, child)| child.into_node(path.join(name), &path, files))
+            .collect();
+        self.files
+            .sort_by(|&a, &b| files[a].path.file_name().cmp(&files[b].path.file_name()));
+
+        FolderNode {
+            path,
+            name,
+            children,
+            files: self.files,
+        }
+    }
+}
+
+/// 按所在目录把 `indices` 指向的文件组织为树
+pub fn build_folder_tree(
+    files: &[FileItem],
+    indices: impl IntoIterator<Item = usize>,
+) -> FolderNode {
+    let mut root = Builder::default();
+    for idx in indices {
+        let Some(parent) = files.get(idx).and_then(|file| file.path.parent()) else {
+            continue;
+        };
+        let mut node = &mut root;
+        for component in parent.components() {
+            node = node
+                .children
+                .entry(component.as_os_str().to_os_string())
+                .or_default();
+        }
+        node.files.push(idx);
+    }
+    root.into_node(PathBuf::new(), Path::new(""), files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(path: &str, size: u64, status: FileStatus, progress: f64) -> FileItem {
+        let mut item = FileItem::new(PathBuf::from(path), size);
+        item.status = status;
+        item.progress = progress;
+        item
+    }
+
+    #[test]
+    fn test_folder_tree_and_progress() {
+        let files = vec![
+            item("/scan/b.bin", 100, FileStatus::Completed, 1.0),
+            item("/scan/sub/deep/x.bin", 300, FileStatus::Computing, 0.5),
+            item("/scan/a.bin", 100, FileStatus::Pending, 0.0),
+            item("/scan/sub/deep/y.bin", 100, FileStatus::Completed, 1.0),
+            item("/scan/other/big.iso", 1000, FileStatus::Oversized, 0.0),
+        ];
+
+        let root = build_folder_tree(&files, 0..files.len());
+        assert_eq!(root.path, Path::new("/scan"));
+        assert_eq!(root.name, "/scan");
+        // 同一文件夹下按文件名排序
+        assert_eq!(root.files, [2, 0]);
+        let names: Vec<&str> = root.children.iter().map(|c| c.name.as_str()).collect();
+        // 只有一个子文件夹的中间层合并显示
+        assert_eq!(
+            names,
+            ["other", &format!("sub{}deep", std::path::MAIN_SEPARATOR)]
+        );
+        assert_eq!(root.children[1].path, Path::new("/scan/sub/deep"));
+        assert_eq!(root.children[1].files, [1, 3]);
+
+        let summaries = root.summarize_all(&files);
+        assert_eq!(summaries.len(), 3);
+        let summary = summaries[&root.path];
+        assert_eq!(summary.file_count, 5);
+        assert_eq!(summary.completed, 2);
+        assert_eq!(summary.total_size, 1600);
+        // 过大的文件不参与进度：(100 + 150 + 0 + 100) / 600
+        assert!((summary.progress - 350.0 / 600.0).abs() < 1e-9);
+
+        let deep = summaries[&root.children[1].path];
+        assert!((deep.progress - 250.0 / 400.0).abs() < 1e-9);
+        assert_eq!(deep.file_count, 2);
+        assert_eq!(summaries[&root.children[0].path].progress, 0.0);
+
+        // 只展开根节点时子文件夹折叠，文件排在子文件夹之后
+        let rows = root.visible_rows(|_, depth| depth == 0);
+        let shape: Vec<(Option<usize>, usize)> = rows
+            .iter()
+            .map(|row| match *row {
+                FolderRow::Folder { depth, .. } => (None, depth),
+                FolderRow::File { idx, depth } => (Some(idx), depth),
+            })
+            .collect();
+        assert_eq!(
+            shape,
+            [(None, 0), (None, 1), (None, 1), (Some(2), 1), (Some(0), 1)]
+        );
+        assert_eq!(root.visible_rows(|_, _| true).len(), 3 + files.len());
+
+        // 只取部分文件（如「只看问题」）
+        let subset = build_folder_tree(&files, [1]);
+        assert_eq!(subset.path, Path::new("/scan/sub/deep"));
+        assert!(subset.children.is_empty());
+    }
+}
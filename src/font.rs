@@ -11,7 +11,8 @@ use dirs::home_dir;
 #[cfg(target_os = "linux")]
 use dirs::home_dir;
 
-pub fn load_chinese_font(fonts: &mut FontDefinitions) -> HashResult<()> {
+/// 按顺序尝试系统中文字体，返回成功加载的字体路径
+pub fn load_chinese_font(fonts: &mut FontDefinitions) -> HashResult<PathBuf> {
     let font_paths = get_system_chinese_fonts();
 
     for font_path in font_paths {
@@ -25,7 +26,7 @@ pub fn load_chinese_font(fonts: &mut FontDefinitions) -> HashResult<()> {
                 font_ids.insert(0, "chinese".to_owned());
             }
 
-            return Ok(());
+            return Ok(font_path);
         }
     }
 
@@ -1,49 +1,314 @@
 use crate::error::{HashError, HashResult};
+use crate::hash_timing::{self, HasherTiming};
+use adler2::Adler32;
+use crc::{CRC_64_XZ, Crc, Table};
 use crc32fast::Hasher as Crc32Hasher;
 use md5::{Digest, Md5};
-use ring::digest::{Context, SHA1_FOR_LEGACY_USE_ONLY};
+use ring::digest::{Context, SHA1_FOR_LEGACY_USE_ONLY, SHA256};
+use std::fmt;
+use std::str::FromStr;
+use std::time::Instant;
 use xxhash_rust::xxh3::Xxh3;
 
+/// CRC64：ECMA-182 多项式，参数与 xz、7-Zip 输出的 CRC64 一致（CRC-64/XZ）
+pub static CRC64: Crc<u64, Table<16>> = Crc::<u64, Table<16>>::new(&CRC_64_XZ);
+
+/// 支持的哈希算法
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HashAlgorithm {
+    Crc32,
+    Crc64,
+    Adler32,
+    Md5,
+    Sha1,
+    Sha256,
+    Xxh3,
+}
+
+impl HashAlgorithm {
+    pub const ALL: [HashAlgorithm; 7] = [
+        HashAlgorithm::Crc32,
+        HashAlgorithm::Crc64,
+        HashAlgorithm::Adler32,
+        HashAlgorithm::Md5,
+        HashAlgorithm::Sha1,
+        HashAlgorithm::Sha256,
+        HashAlgorithm::Xxh3,
+    ];
+
+    /// 可由用户开关的算法（CRC32 与 XXH3 始终计算，XXH3 是缓存校验键）
+    pub const OPTIONAL: [HashAlgorithm; 5] = [
+        HashAlgorithm::Md5,
+        HashAlgorithm::Sha1,
+        HashAlgorithm::Sha256,
+        HashAlgorithm::Crc64,
+        HashAlgorithm::Adler32,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            HashAlgorithm::Crc32 => "CRC32",
+            HashAlgorithm::Crc64 => "CRC64",
+            HashAlgorithm::Adler32 => "Adler32",
+            HashAlgorithm::Md5 => "MD5",
+            HashAlgorithm::Sha1 => "SHA1",
+            HashAlgorithm::Sha256 => "SHA256",
+            HashAlgorithm::Xxh3 => "XXH3",
+        }
+    }
+
+    /// 配置中使用的小写标识
+    pub fn key(self) -> &'static str {
+        match self {
+            HashAlgorithm::Crc32 => "crc32",
+            HashAlgorithm::Crc64 => "crc64",
+            HashAlgorithm::Adler32 => "adler32",
+            HashAlgorithm::Md5 => "md5",
+            HashAlgorithm::Sha1 => "sha1",
+            HashAlgorithm::Sha256 => "sha256",
+            HashAlgorithm::Xxh3 => "xxh3",
+        }
+    }
+
+    /// 十六进制输出长度
+    pub fn hex_len(self) -> usize {
+        match self {
+            HashAlgorithm::Crc32 | HashAlgorithm::Adler32 => 8,
+            HashAlgorithm::Crc64 => 16,
+            HashAlgorithm::Md5 | HashAlgorithm::Xxh3 => 32,
+            HashAlgorithm::Sha1 => 40,
+            HashAlgorithm::Sha256 => 64,
+        }
+    }
+}
+
+/// 启用的可选哈希算法
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EnabledHashes {
+    pub md5: bool,
+    pub sha1: bool,
+    pub sha256: bool,
+    pub crc64: bool,
+    pub adler32: bool,
+}
+
+impl EnabledHashes {
+    /// 只计算始终启用的 CRC32 与 XXH3
+    pub const NONE: EnabledHashes = EnabledHashes {
+        md5: false,
+        sha1: false,
+        sha256: false,
+        crc64: false,
+        adler32: false,
+    };
+
+    pub fn contains(self, algorithm: HashAlgorithm) -> bool {
+        match algorithm {
+            HashAlgorithm::Crc32 | HashAlgorithm::Xxh3 => true,
+            HashAlgorithm::Crc64 => self.crc64,
+            HashAlgorithm::Adler32 => self.adler32,
+            HashAlgorithm::Md5 => self.md5,
+            HashAlgorithm::Sha1 => self.sha1,
+            HashAlgorithm::Sha256 => self.sha256,
+        }
+    }
+
+    pub fn set(&mut self, algorithm: HashAlgorithm, enabled: bool) {
+        match algorithm {
+            HashAlgorithm::Crc32 | HashAlgorithm::Xxh3 => {}
+            HashAlgorithm::Crc64 => self.crc64 = enabled,
+            HashAlgorithm::Adler32 => self.adler32 = enabled,
+            HashAlgorithm::Md5 => self.md5 = enabled,
+            HashAlgorithm::Sha1 => self.sha1 = enabled,
+            HashAlgorithm::Sha256 => self.sha256 = enabled,
+        }
+    }
+
+    /// 未启用 SHA-256 时，已启用且已不抗碰撞的 MD5 与 SHA1，需要在设置中提示
+    pub fn insecure_without_sha256(self) -> Vec<HashAlgorithm> {
+        if self.sha256 {
+            return Vec::new();
+        }
+        [HashAlgorithm::Md5, HashAlgorithm::Sha1]
+            .into_iter()
+            .filter(|algorithm| self.contains(*algorithm))
+            .collect()
+    }
+}
+
+impl Default for EnabledHashes {
+    // 默认与旧版本一致：MD5 + SHA1
+    fn default() -> Self {
+        Self {
+            md5: true,
+            sha1: true,
+            ..Self::NONE
+        }
+    }
+}
+
+impl fmt::Display for EnabledHashes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let keys: Vec<&str> = HashAlgorithm::OPTIONAL
+            .into_iter()
+            .filter(|a| self.contains(*a))
+            .map(HashAlgorithm::key)
+            .collect();
+        write!(f, "{}", keys.join(","))
+    }
+}
+
+impl FromStr for EnabledHashes {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut enabled = Self::NONE;
+        for key in s.split(',').map(str::trim).filter(|k| !k.is_empty()) {
+            let algorithm = HashAlgorithm::OPTIONAL
+                .into_iter()
+                .find(|a| a.key() == key)
+                .ok_or_else(|| format!("未知的哈希算法: {}", key))?;
+            enabled.set(algorithm, true);
+        }
+        Ok(enabled)
+    }
+}
+
+/// 单次遍历得到的原始摘要，未启用的算法为 None
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Digests {
+    pub crc32: u32,
+    pub crc64: Option<u64>,
+    pub adler32: Option<u32>,
+    pub md5: Option<[u8; 16]>,
+    pub sha1: Option<[u8; 20]>,
+    pub sha256: Option<[u8; 32]>,
+    pub xxh3: [u8; 16],
+}
+
 pub struct FileHasher {
     crc32_hasher: Crc32Hasher,
-    md5_hasher: Md5,
-    sha1_context: Context,
+    crc64_digest: Option<crc::Digest<'static, u64, Table<16>>>,
+    adler32_hasher: Option<Adler32>,
+    md5_hasher: Option<Md5>,
+    sha1_context: Option<Context>,
+    sha256_context: Option<Context>,
     xxh3_hasher: Xxh3,
+    /// 开启诊断时各算法的耗时
+    timing: Option<HasherTiming>,
 }
 
 impl FileHasher {
     pub fn new() -> Self {
+        Self::with_algorithms(EnabledHashes::default())
+    }
+
+    pub fn with_algorithms(enabled: EnabledHashes) -> Self {
         Self {
             crc32_hasher: Crc32Hasher::new(),
-            md5_hasher: Md5::new(),
-            sha1_context: Context::new(&SHA1_FOR_LEGACY_USE_ONLY),
+            crc64_digest: enabled.crc64.then(|| CRC64.digest()),
+            adler32_hasher: enabled.adler32.then(Adler32::new),
+            md5_hasher: enabled.md5.then(Md5::new),
+            sha1_context: enabled
+                .sha1
+                .then(|| Context::new(&SHA1_FOR_LEGACY_USE_ONLY)),
+            sha256_context: enabled.sha256.then(|| Context::new(&SHA256)),
             xxh3_hasher: Xxh3::new(),
+            timing: hash_timing::is_enabled().then(HasherTiming::default),
         }
     }
 
     pub fn update(&mut self, data: &[u8]) {
-        self.crc32_hasher.update(data);
-        self.md5_hasher.update(data);
-        self.sha1_context.update(data);
-        self.xxh3_hasher.update(data);
+        let Some(mut timing) = self.timing.take() else {
+            for algorithm in HashAlgorithm::ALL {
+                self.update_one(algorithm, data);
+            }
+            return;
+        };
+        for algorithm in HashAlgorithm::ALL {
+            let start = Instant::now();
+            if self.update_one(algorithm, data) {
+                timing.record(algorithm, data.len(), start.elapsed());
+            }
+        }
+        self.timing = Some(timing);
+    }
+
+    /// 更新单个算法，未启用时返回 false
+    fn update_one(&mut self, algorithm: HashAlgorithm, data: &[u8]) -> bool {
+        match algorithm {
+            HashAlgorithm::Crc32 => self.crc32_hasher.update(data),
+            HashAlgorithm::Crc64 => match &mut self.crc64_digest {
+                Some(crc64) => crc64.update(data),
+                None => return false,
+            },
+            HashAlgorithm::Adler32 => match &mut self.adler32_hasher {
+                Some(adler32) => adler32.write_slice(data),
+                None => return false,
+            },
+            HashAlgorithm::Md5 => match &mut self.md5_hasher {
+                Some(md5) => md5.update(data),
+                None => return false,
+            },
+            HashAlgorithm::Sha1 => match &mut self.sha1_context {
+                Some(sha1) => sha1.update(data),
+                None => return false,
+            },
+            HashAlgorithm::Sha256 => match &mut self.sha256_context {
+                Some(sha256) => sha256.update(data),
+                None => return false,
+            },
+            HashAlgorithm::Xxh3 => self.xxh3_hasher.update(data),
+        }
+        true
     }
 
-    pub fn finalize(self) -> HashResult<(u32, [u8; 16], [u8; 20], [u8; 16])> {
+    pub fn finalize(self) -> HashResult<Digests> {
+        if let Some(timing) = &self.timing {
+            timing.flush_into(hash_timing::totals());
+        }
         let crc32 = self.crc32_hasher.finalize();
+        let crc64 = self.crc64_digest.map(|digest| digest.finalize());
+        let adler32 = self.adler32_hasher.map(|hasher| hasher.checksum());
+
+        let md5 = self
+            .md5_hasher
+            .map(|hasher| {
+                hasher.finalize().as_slice().try_into().map_err(|_| {
+                    HashError::SystemResource("MD5 哈希输出大小不匹配: 预期 16 字节".to_string())
+                })
+            })
+            .transpose()?;
 
-        let md5_digest = self.md5_hasher.finalize();
-        let md5: [u8; 16] = md5_digest.as_slice().try_into().map_err(|_| {
-            HashError::SystemResource("MD5 哈希输出大小不匹配: 预期 16 字节".to_string())
-        })?;
+        let sha1 = self
+            .sha1_context
+            .map(|context| {
+                context.finish().as_ref().try_into().map_err(|_| {
+                    HashError::SystemResource("SHA1 哈希输出大小不匹配: 预期 20 字节".to_string())
+                })
+            })
+            .transpose()?;
 
-        let sha1_digest = self.sha1_context.finish();
-        let sha1: [u8; 20] = sha1_digest.as_ref().try_into().map_err(|_| {
-            HashError::SystemResource("SHA1 哈希输出大小不匹配: 预期 20 字节".to_string())
-        })?;
+        let sha256 = self
+            .sha256_context
+            .map(|context| {
+                context.finish().as_ref().try_into().map_err(|_| {
+                    HashError::SystemResource("SHA256 哈希输出大小不匹配: 预期 32 字节".to_string())
+                })
+            })
+            .transpose()?;
 
-        let xxh3_arr: [u8; 16] = self.xxh3_hasher.digest128().to_be_bytes();
+        let xxh3: [u8; 16] = self.xxh3_hasher.digest128().to_be_bytes();
 
-        Ok((crc32, md5, sha1, xxh3_arr))
+        Ok(Digests {
+            crc32,
+            crc64,
+            adler32,
+            md5,
+            sha1,
+            sha256,
+            xxh3,
+        })
     }
 }
 
@@ -65,12 +330,60 @@ mod tests {
         let result = hasher.finalize();
         assert!(result.is_ok(), "finalize should succeed");
 
-        let (crc32, md5, sha1, xxh3) = result.unwrap();
+        let digests = result.unwrap();
+
+        assert_ne!(digests.crc32, 0);
+        assert_ne!(digests.md5, Some([0u8; 16]));
+        assert_ne!(digests.sha1, Some([0u8; 20]));
+        assert_ne!(digests.xxh3, [0u8; 16]);
+    }
+
+    #[test]
+    fn test_timing_only_collected_when_enabled() {
+        use crate::hash_timing::AlgorithmTimings;
+
+        let enabled = EnabledHashes {
+            sha256: true,
+            ..EnabledHashes::NONE
+        };
+        let data = [0x5au8; 4096];
+
+        hash_timing::set_enabled(false);
+        let mut hasher = FileHasher::with_algorithms(enabled);
+        hasher.update(&data);
+        assert!(hasher.timing.is_none());
 
-        assert_ne!(crc32, 0);
-        assert_ne!(md5, [0u8; 16]);
-        assert_ne!(sha1, [0u8; 20]);
-        assert_ne!(xxh3, [0u8; 16]);
+        hash_timing::set_enabled(true);
+        let mut hasher = FileHasher::with_algorithms(enabled);
+        hash_timing::set_enabled(false);
+        hasher.update(&data);
+        hasher.update(&data);
+        let totals = AlgorithmTimings::new();
+        hasher.timing.as_ref().unwrap().flush_into(&totals);
+
+        // 只有启用的算法有数据
+        let mut timed: Vec<(HashAlgorithm, u64)> = totals
+            .snapshot()
+            .iter()
+            .map(|timing| (timing.algorithm, timing.bytes))
+            .collect();
+        timed.sort_by_key(|&(algorithm, _)| algorithm.key());
+        assert_eq!(
+            timed,
+            [
+                (HashAlgorithm::Crc32, 8192),
+                (HashAlgorithm::Sha256, 8192),
+                (HashAlgorithm::Xxh3, 8192),
+            ]
+        );
+        // 计时不影响结果
+        let mut plain = FileHasher::with_algorithms(enabled);
+        plain.update(&data);
+        plain.update(&data);
+        assert_eq!(hasher.finalize().unwrap(), plain.finalize().unwrap());
+
+        totals.reset();
+        assert!(totals.snapshot().is_empty());
     }
 
     #[test]
@@ -79,14 +392,96 @@ mod tests {
         let result = hasher.finalize();
         assert!(result.is_ok(), "finalize should succeed");
 
-        let (crc32, md5, sha1, xxh3) = result.unwrap();
+        let digests = result.unwrap();
 
-        assert_eq!(crc32, 0);
-        assert_eq!(hex::encode(md5), "d41d8cd98f00b204e9800998ecf8427e");
+        assert_eq!(digests.crc32, 0);
+        assert_eq!(
+            hex::encode(digests.md5.unwrap()),
+            "d41d8cd98f00b204e9800998ecf8427e"
+        );
         assert_eq!(
-            hex::encode(sha1),
+            hex::encode(digests.sha1.unwrap()),
             "da39a3ee5e6b4b0d3255bfef95601890afd80709"
         );
-        assert_eq!(hex::encode(xxh3), "99aa06d3014798d86001c324468d497f");
+        assert_eq!(
+            hex::encode(digests.xxh3),
+            "99aa06d3014798d86001c324468d497f"
+        );
+        assert_eq!(digests.sha256, None);
+        assert_eq!(digests.crc64, None);
+        assert_eq!(digests.adler32, None);
+    }
+
+    #[test]
+    fn test_crc64_and_adler32_known_answers() {
+        let enabled = EnabledHashes {
+            crc64: true,
+            adler32: true,
+            ..EnabledHashes::NONE
+        };
+        let digests = FileHasher::with_algorithms(enabled).finalize().unwrap();
+        assert_eq!(digests.crc64, Some(0));
+        assert_eq!(digests.adler32, Some(1));
+        assert_eq!(digests.md5, None);
+
+        // 分块更新与整体计算一致
+        let mut hasher = FileHasher::with_algorithms(enabled);
+        hasher.update(b"12345");
+        hasher.update(b"6789");
+        let digests = hasher.finalize().unwrap();
+        assert_eq!(digests.crc64, Some(0x995d_c9bb_df19_39fa));
+        assert_eq!(digests.adler32, Some(0x091e_01de));
+
+        let mut hasher = FileHasher::with_algorithms(enabled);
+        hasher.update(b"Wikipedia");
+        assert_eq!(hasher.finalize().unwrap().adler32, Some(0x11e6_0398));
+    }
+
+    #[test]
+    fn test_sha256_only_selection() {
+        let enabled = EnabledHashes {
+            md5: true,
+            sha256: true,
+            ..EnabledHashes::NONE
+        };
+        let mut hasher = FileHasher::with_algorithms(enabled);
+        hasher.update(b"abc");
+        let digests = hasher.finalize().unwrap();
+
+        assert_eq!(digests.sha1, None);
+        assert_eq!(
+            hex::encode(digests.md5.unwrap()),
+            "900150983cd24fb0d6963f7d28e17f72"
+        );
+        assert_eq!(
+            hex::encode(digests.sha256.unwrap()),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    #[test]
+    fn test_enabled_hashes_roundtrip() {
+        assert_eq!(EnabledHashes::default().to_string(), "md5,sha1");
+        assert_eq!(
+            EnabledHashes::default().insecure_without_sha256(),
+            [HashAlgorithm::Md5, HashAlgorithm::Sha1]
+        );
+
+        let parsed: EnabledHashes = "md5, sha256".parse().unwrap();
+        assert!(!parsed.contains(HashAlgorithm::Sha1));
+        assert!(parsed.contains(HashAlgorithm::Sha256));
+        assert!(parsed.contains(HashAlgorithm::Crc32));
+        assert!(parsed.insecure_without_sha256().is_empty());
+        let md5_only: EnabledHashes = "md5".parse().unwrap();
+        assert_eq!(md5_only.insecure_without_sha256(), [HashAlgorithm::Md5]);
+        assert_eq!(parsed.to_string().parse::<EnabledHashes>().unwrap(), parsed);
+
+        let parsed: EnabledHashes = "crc64,adler32".parse().unwrap();
+        assert!(parsed.contains(HashAlgorithm::Crc64));
+        assert!(parsed.contains(HashAlgorithm::Adler32));
+        assert!(!parsed.contains(HashAlgorithm::Md5));
+        assert_eq!(parsed.to_string(), "crc64,adler32");
+
+        assert!("md4".parse::<EnabledHashes>().is_err());
     }
 }
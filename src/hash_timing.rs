@@ -0,0 +1,117 @@
+// 各哈希算法的耗时统计（诊断）
+//
+// 同时启用 SHA256、SHA1 等多个算法时，用于判断哪个算法占了主要的计算时间。开启后每个
+// `FileHasher` 分别计时各算法的 `update`，完成时一次性累加到全局统计，批次结束后在
+// 耗时分解窗口中按算法显示耗时与吞吐量。默认关闭，关闭时不读取时钟，没有额外开销。
+
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::Duration;
+
+use crate::hash::HashAlgorithm;
+
+const ALGORITHM_COUNT: usize = HashAlgorithm::ALL.len();
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static TOTALS: AlgorithmTimings = AlgorithmTimings::new();
+
+/// 运行时开关计时，只影响之后创建的哈希器
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// 所有哈希器累加到的全局统计
+pub fn totals() -> &'static AlgorithmTimings {
+    &TOTALS
+}
+
+/// 单个算法的累计耗时
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AlgorithmTiming {
+    pub algorithm: HashAlgorithm,
+    pub bytes: u64,
+    /// 各线程的耗时之和，可能超过批次的实际耗时
+    pub elapsed: Duration,
+}
+
+impl AlgorithmTiming {
+    /// 单线程吞吐量（MB/s），与性能测试一致
+    pub fn throughput(&self) -> f64 {
+        if self.elapsed.is_zero() {
+            0.0
+        } else {
+            self.bytes as f64 / (1024.0 * 1024.0) / self.elapsed.as_secs_f64()
+        }
+    }
+}
+
+/// 可在多个线程间共享的累计统计
+pub struct AlgorithmTimings {
+    nanos: [AtomicU64; ALGORITHM_COUNT],
+    bytes: [AtomicU64; ALGORITHM_COUNT],
+}
+
+impl AlgorithmTimings {
+    pub const fn new() -> Self {
+        Self {
+            nanos: [const { AtomicU64::new(0) }; ALGORITHM_COUNT],
+            bytes: [const { AtomicU64::new(0) }; ALGORITHM_COUNT],
+        }
+    }
+
+    pub fn reset(&self) {
+        for counter in self.nanos.iter().chain(&self.bytes) {
+            counter.store(0, Ordering::Relaxed);
+        }
+    }
+
+    /// 有数据的算法，按耗时从多到少排列
+    pub fn snapshot(&self) -> Vec<AlgorithmTiming> {
+        let mut timings: Vec<AlgorithmTiming> = HashAlgorithm::ALL
+            .into_iter()
+            .enumerate()
+            .map(|(i, algorithm)| AlgorithmTiming {
+                algorithm,
+                bytes: self.bytes[i].load(Ordering::Relaxed),
+                elapsed: Duration::from_nanos(self.nanos[i].load(Ordering::Relaxed)),
+            })
+            .filter(|timing| timing.bytes > 0)
+            .collect();
+        timings.sort_by_key(|timing| std::cmp::Reverse(timing.elapsed));
+        timings
+    }
+}
+
+impl Default for AlgorithmTimings {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 单个哈希器的计时，完成时再累加到共享统计，避免每块数据都写原子变量
+#[derive(Debug, Clone, Default)]
+pub struct HasherTiming {
+    nanos: [u64; ALGORITHM_COUNT],
+    bytes: [u64; ALGORITHM_COUNT],
+}
+
+impl HasherTiming {
+    pub fn record(&mut self, algorithm: HashAlgorithm, bytes: usize, elapsed: Duration) {
+        // 枚举的声明顺序与 `HashAlgorithm::ALL` 一致
+        let i = algorithm as usize;
+        self.nanos[i] += elapsed.as_nanos() as u64;
+        self.bytes[i] += bytes as u64;
+    }
+
+    pub fn flush_into(&self, totals: &AlgorithmTimings) {
+        for i in 0..ALGORITHM_COUNT {
+            if self.bytes[i] > 0 {
+                totals.nanos[i].fetch_add(self.nanos[i], Ordering::Relaxed);
+                totals.bytes[i].fetch_add(self.bytes[i], Ordering::Relaxed);
+            }
+        }
+    }
+}
@@ -0,0 +1,1050 @@
+// 界面语言
+//
+// egui 内置字体不含汉字，系统中找不到中文字体时中文标签会显示为方框，
+// 此时（或用户手动选择时）切换为英文界面。界面文本通过 `Text` 键查表，
+// 每个键按 `Language::ALL` 的顺序给出各语言的文本；增加语言时在 `Language`
+// 中加一项并为每个键补上译文，数组长度不符会直接编译失败。
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// 已翻译的界面语言
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Language {
+    Chinese,
+    English,
+}
+
+impl Language {
+    pub const ALL: [Language; 2] = [Language::Chinese, Language::English];
+    const COUNT: usize = Self::ALL.len();
+}
+
+static CURRENT: AtomicUsize = AtomicUsize::new(Language::Chinese as usize);
+
+/// 切换界面语言，下一帧生效
+pub fn set_language(language: Language) {
+    CURRENT.store(language as usize, Ordering::Relaxed);
+}
+
+pub fn language() -> Language {
+    Language::ALL[CURRENT.load(Ordering::Relaxed)]
+}
+
+/// 按当前界面语言取文本
+pub fn t(key: Text) -> &'static str {
+    key.get(language())
+}
+
+/// 按当前界面语言取文本，并依次用 `args` 替换其中的 `{}`
+///
+/// 参数中的 `{}`（如文件名）原样保留，不会被后面的参数替换。
+pub fn tf(key: Text, args: &[&dyn std::fmt::Display]) -> String {
+    fill(t(key), args)
+}
+
+fn fill(template: &str, args: &[&dyn std::fmt::Display]) -> String {
+    use std::fmt::Write;
+
+    let mut parts = template.split("{}");
+    let mut text = parts.next().unwrap_or_default().to_string();
+    for (i, part) in parts.enumerate() {
+        match args.get(i) {
+            Some(arg) => {
+                let _ = write!(text, "{}", arg);
+            }
+            None => text.push_str("{}"),
+        }
+        text.push_str(part);
+    }
+    text
+}
+
+macro_rules! texts {
+    ($($key:ident => [$($text:literal),+ $(,)?],)*) => {
+        /// 界面文本的键
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        pub enum Text {
+            $($key,)*
+        }
+
+        impl Text {
+            #[cfg(test)]
+            const ALL: &'static [Text] = &[$(Text::$key,)*];
+
+            fn translations(self) -> [&'static str; Language::COUNT] {
+                match self {
+                    $(Text::$key => [$($text),+],)*
+                }
+            }
+        }
+    };
+}
+
+impl Text {
+    pub fn get(self, language: Language) -> &'static str {
+        self.translations()[language as usize]
+    }
+}
+
+texts! {
+    // 状态徽标
+    StatusPending => ["等待", "Pending"],
+    StatusComputing => ["计算", "Hashing"],
+    StatusCompleted => ["完成", "Done"],
+    StatusCached => ["缓存", "Cached"],
+    StatusUnchanged => ["未变更", "Unchanged"],
+    StatusFailed => ["失败", "Failed"],
+    StatusDeleted => ["已删除", "Deleted"],
+    StatusCancelled => ["取消", "Cancelled"],
+    StatusQuickHashed => ["预筛", "Prescreened"],
+    StatusOversized => ["跳过-过大", "Skipped: too large"],
+    StatusPendingHint => ["排队等待计算", "Waiting in the queue"],
+    StatusComputingHint => ["正在计算", "Being hashed"],
+    StatusCompletedHint => ["已计算完成", "Hashed"],
+    StatusCachedHint => ["命中缓存，未重新计算", "Taken from the cache without rehashing"],
+    StatusUnchangedHint => [
+        "大小与修改时间未变，直接采用缓存结果（仅新增/变更模式）",
+        "Size and modified time unchanged, cached result reused (new/changed only mode)",
+    ],
+    StatusFailedHint => ["读取或计算失败", "Reading or hashing failed"],
+    StatusDeletedHint => [
+        "添加后文件已被删除或移走，未计算",
+        "Deleted or moved after being added, not hashed",
+    ],
+    StatusCancelledHint => ["计算被取消", "Hashing was cancelled"],
+    StatusQuickHashedHint => [
+        "仅完成快速预筛，结果非权威",
+        "Only the quick prescreen ran, result is not authoritative",
+    ],
+    StatusOversizedHint => [
+        "超过最大文件大小，未加入计算队列",
+        "Larger than the maximum file size, not queued",
+    ],
+    ClipboardVerifyLegend => [
+        "与剪贴板中的哈希一致 / 不一致",
+        "Matches / differs from the hash in the clipboard",
+    ],
+
+    // 表格列
+    ColumnStatus => ["状态", "Status"],
+    ColumnFilename => ["文件名", "Name"],
+    ColumnExtension => ["类型", "Type"],
+    ColumnSize => ["大小", "Size"],
+    ColumnModified => ["修改时间", "Modified"],
+    ColumnComputedAt => ["计算时间", "Computed"],
+    ColumnDuration => ["耗时", "Time"],
+    ColumnThroughput => ["吞吐量", "Throughput"],
+    ColumnProgress => ["进度", "Progress"],
+    ColumnShortId => ["短ID", "Short ID"],
+    ColumnNote => ["备注", "Note"],
+
+    // 工具栏
+    AddFiles => ["添加文件", "Add files"],
+    AddFolder => ["添加文件夹", "Add folder"],
+    AddFolderHint => [
+        "也可直接拖放文件夹递归扫描；按住 Shift 拖放则将文件夹作为整体计算合并哈希",
+        "You can also drop folders to scan them recursively; hold Shift while dropping to hash a folder as a whole",
+    ],
+    PastePaths => ["粘贴路径", "Paste paths"],
+    PastePathsHint => [
+        "从剪贴板添加路径，每行一个 (Ctrl+Shift+V)",
+        "Add paths from the clipboard, one per line (Ctrl+Shift+V)",
+    ],
+    Recent => ["最近", "Recent"],
+    Profiles => ["配置方案", "Profile"],
+    ProfilesHint => [
+        "保存完整的配置（含启用的哈希算法）为命名方案并一键切换，例如「快速去重」与「完整归档」；方案可导出为文件分享给他人。名称后的 * 表示当前配置已修改",
+        "Save the whole configuration (including enabled hashes) as a named profile and switch with one click, e.g. \"quick dedup\" and \"full archival\"; profiles can be exported to a file and shared. A * after the name means the settings were changed after applying it",
+    ],
+    NoProfile => ["未选择", "None"],
+    ProfileName => ["方案名称", "Profile name"],
+    SaveProfile => ["保存当前配置", "Save current settings"],
+    ImportProfile => ["导入方案…", "Import profile…"],
+    ExportProfile => ["导出方案…", "Export profile…"],
+    DeleteProfile => ["删除方案", "Delete profile"],
+    ProfileSaved => ["已保存配置方案「{}」", "Saved profile \"{}\""],
+    ProfileSaveFailed => ["保存配置方案失败: {}", "Failed to save profile: {}"],
+    ProfileSwitched => ["已切换到配置方案「{}」", "Switched to profile \"{}\""],
+    ProfileMissing => ["配置方案「{}」不存在或已损坏", "Profile \"{}\" is missing or damaged"],
+    ProfileLoadFailed => ["读取配置方案失败: {}", "Failed to read profile: {}"],
+    ImportProfileTitle => ["导入配置方案", "Import profile"],
+    ProfileImported => ["已导入配置方案「{}」", "Imported profile \"{}\""],
+    ProfileImportedAs => [
+        "已有同名的配置方案，导入为「{}」",
+        "A profile with that name already exists; imported as \"{}\"",
+    ],
+    ImportFailed => ["导入失败: {}", "Import failed: {}"],
+    ExportProfileTitle => ["导出配置方案", "Export profile"],
+    ProfileExported => ["已导出配置方案「{}」", "Exported profile \"{}\""],
+    ClearQueue => ["清空队列", "Clear queue"],
+    RefreshMetadata => ["刷新元数据", "Refresh metadata"],
+    RefreshMetadataHint => [
+        "重新读取队列中文件的大小与修改时间，已变更的文件改回等待计算",
+        "Re-read file sizes and modification times; changed files go back to pending",
+    ],
+    OpenContainingFolder => ["打开所在文件夹", "Open containing folder"],
+    OpenContainingFolderHint => [
+        "在文件管理器中显示选中的文件（未选中时为第一个已完成的文件）",
+        "Show the selected file in the file manager (or the first completed file when nothing is selected)",
+    ],
+    CopyAll => ["复制全部", "Copy all"],
+    CopyAllHint => ["复制所有已完成行（制表符分隔）", "Copy all completed rows (tab-separated)"],
+    ExportChecksums => ["导出校验文件", "Export checksums"],
+    ExportChecksumsHint => [
+        "导出 md5sum/sha1sum/sha256sum/SFV 格式的校验文件",
+        "Export md5sum/sha1sum/sha256sum/SFV checksum files",
+    ],
+    RemoteVerify => ["从 URL 校验", "Verify from URL"],
+    RemoteVerifyHint => [
+        "下载发布页上的 SHA256SUMS 等校验清单，按文件名与列表中的文件比对",
+        "Download a checksum list such as SHA256SUMS and match it against the files by name",
+    ],
+    RemoteVerifyUnavailable => [
+        "当前构建未启用（需 --features remote-verify）",
+        "Not enabled in this build (requires --features remote-verify)",
+    ],
+    VerifyManifest => ["按清单校验", "Verify manifest"],
+    VerifyManifestHint => [
+        "选择本地的 SHA256SUMS 等校验清单，加入其所在目录并按文件名比对；同时列出目录中存在但清单未列出的文件，以及清单中列出但已不存在的文件",
+        "Pick a local checksum list such as SHA256SUMS, add its folder and match files by name; also reports files in the folder that the list does not mention and listed files that are missing",
+    ],
+    Untracked => ["未列出", "Unlisted"],
+    UntrackedHint => [
+        "所在目录的校验清单中没有这个文件，可能是后来加入或被替换的文件",
+        "Not mentioned in the folder's checksum list; it may have been added or replaced later",
+    ],
+    WriteXattrs => ["写入扩展属性", "Write xattrs"],
+    WriteXattrsHint => [
+        "将已完成文件的哈希写入扩展属性 user.sha256 等，供其他工具读取；已有不同值的文件保留原值，不支持扩展属性的文件系统会跳过",
+        "Write hashes of completed files to extended attributes such as user.sha256; files that already have different values keep them, and file systems without xattr support are skipped",
+    ],
+    NoCompletedFiles => ["没有已完成的文件", "No completed files"],
+    XattrsWritten => ["已写入扩展属性：{} 个文件", "Wrote xattrs for {} files"],
+    XattrsUnchanged => ["，{} 个文件已有相同的值", ", {} already up to date"],
+    XattrsConflicts => [
+        "，{} 个文件已有不同的值（未覆盖）",
+        ", {} already had different values (not overwritten)",
+    ],
+    XattrsUnsupported => [
+        "，{} 个文件所在文件系统不支持（已跳过）",
+        ", {} on file systems without xattr support (skipped)",
+    ],
+    XattrsFailed => ["，{} 个失败", ", {} failed"],
+    XattrUnavailable => [
+        "当前构建未启用（需 Linux/macOS 且 --features xattr-export）",
+        "Not enabled in this build (requires Linux/macOS and --features xattr-export)",
+    ],
+    HashList => ["清单筛查", "Hash list"],
+    HashListHint => [
+        "粘贴已知哈希清单，标记哈希出现在清单中的文件（如筛查已知恶意文件）",
+        "Paste a list of known hashes and flag files whose hash appears in it (e.g. known malware)",
+    ],
+    HashListMatched => ["⚠ 命中", "⚠ Listed"],
+    HashListMatchedHint => [
+        "哈希出现在粘贴清单中的文件数，点击查看清单",
+        "Files whose hash appears in the pasted list; click to open the list",
+    ],
+    Collisions => ["碰撞检测", "Collisions"],
+    CollisionsHint => [
+        "查找 CRC32/XXH3 相同但强哈希（SHA256/SHA1/MD5）不同的文件",
+        "Find files with equal CRC32/XXH3 but different strong hashes (SHA256/SHA1/MD5)",
+    ],
+    NameConflicts => ["同名不同内容", "Name conflicts"],
+    NameConflictsHint => [
+        "查找文件名相同但内容不同的已完成文件（如各目录中本应一致的 config.json），用于发现配置漂移",
+        "Find completed files that share a file name but differ in content (e.g. config.json copies that should match), to catch configuration drift",
+    ],
+    OnlyProblems => ["只看问题", "Problems only"],
+    OnlyProblemsHint => [
+        "只显示失败、已删除、已取消、因过大跳过、校验不一致或无法校验、哈希与缓存不同、校验清单未列出以及命中哈希清单的行",
+        "Only show failed, deleted, cancelled, oversized, mismatched or unverifiable, cache-changed, unlisted and hash-list rows",
+    ],
+    Problems => ["个问题", "problems"],
+    TypeSummary => ["类型统计", "File types"],
+    TypeSummaryHint => ["按扩展名统计文件数量与总大小", "Count files and total size by extension"],
+    SourceAll => ["全部结果", "All results"],
+    SourceCached => ["仅缓存命中", "Cache hits only"],
+    SourceComputed => ["仅新计算", "Computed only"],
+    SourceFilterHint => [
+        "按结果来源筛选表格：直接采用缓存的结果，或重新读取文件计算的结果；可与「只看问题」同时使用",
+        "Filter the table by where results came from: taken from the cache, or computed by reading the file; combines with Problems only",
+    ],
+    CacheHits => ["缓存命中", "Cache hits"],
+    FreshlyComputed => ["计算", "Computed"],
+    SourceSummaryHint => [
+        "队列中已完成的文件里直接采用缓存与重新读取计算的数量。命中少于预期时，检查缓存阈值、保留天数、缓存校验级别与可移动磁盘设置",
+        "Completed files in the queue taken from the cache versus computed by reading the file. If there are fewer hits than expected, check the cache threshold, retention, cache validation and removable media settings",
+    ],
+    GroupByFolder => ["按文件夹分组", "Group by folder"],
+    GroupByFolderHint => [
+        "把文件按所在文件夹组织为可折叠的树，每个文件夹显示文件数、总大小与进度；按哈希查找定位时切换回表格",
+        "Nest files under collapsible folders showing each folder's file count, total size and progress; locating a hash switches back to the table",
+    ],
+    RenameByHash => ["按哈希重命名", "Rename by hash"],
+    RenameByHashHint => [
+        "将已完成的文件重命名为「SHA256+原扩展名」，执行前先试运行预览",
+        "Rename completed files to \"SHA256 + original extension\" after a dry-run preview",
+    ],
+    CompareFiles => ["对比两个文件", "Compare files"],
+    CompareFilesHint => [
+        "选择两个文件并判断内容是否相同",
+        "Pick two files and check whether their contents match",
+    ],
+    DirectoryHash => ["目录哈希", "Directory hash"],
+    DirectoryHashHint => [
+        "将整个文件夹的内容按相对路径顺序合并计算为一个哈希；也可按住 Shift 拖放文件夹",
+        "Hash a whole folder as one value in relative-path order; you can also Shift+drop a folder",
+    ],
+    MinimizeToTray => ["最小化到托盘", "Minimize to tray"],
+    Settings => ["缓存设置", "Settings"],
+    Uppercase => ["大写显示", "Uppercase"],
+    Columns => ["列", "Columns"],
+    QuickPrescreen => ["快速预筛", "Quick prescreen"],
+    QuickPrescreenHint => [
+        "先对文件首尾各 64KB 计算快速哈希，仅对可能重复的文件做完整计算（预筛结果非权威）",
+        "Hash the first and last 64KB first and fully hash only likely duplicates (prescreen results are not authoritative)",
+    ],
+    Xxh3Only => ["快速模式（仅 XXH3）", "Fast mode (XXH3 only)"],
+    Xxh3OnlyHint => [
+        "未命中缓存的文件只计算 XXH3，适合大批量查重；其余哈希留空且结果不写入缓存",
+        "Files not in the cache get only XXH3, for large duplicate scans; other hashes stay empty and results are not cached",
+    ],
+    SyncMode => ["仅新增/变更", "New/changed only"],
+    SyncModeHint => [
+        "大小与修改时间与缓存一致的文件直接采用缓存结果并标记为「未变更」，不再读取内容校验；适合定期重新扫描同一文件夹",
+        "Files whose size and modified time match the cache reuse the cached result and are marked \"unchanged\" without rereading; useful for rescanning the same folder",
+    ],
+    AutoComputeAuto => ["自动计算", "Auto start"],
+    AutoComputeManual => ["手动计算", "Manual start"],
+    AutoComputeOnDrop => ["仅拖放自动计算", "Auto start on drop"],
+    AutoComputeHint => [
+        "自动计算：新加入的文件稍后自动开始\n手动计算：加入后保持暂停，点击「开始计算」才计算\n仅拖放自动计算：只有拖放加入的文件自动开始",
+        "Auto start: newly added files start shortly after\nManual start: added files wait until you click Start\nAuto start on drop: only dropped files start automatically",
+    ],
+    Elapsed => ["已用时", "Elapsed"],
+    Stop => ["停止", "Stop"],
+    Start => ["开始计算", "Start"],
+    ComputeSelected => ["计算选中项", "Compute selected"],
+    ComputeSelectedHint => [
+        "只计算表格中选中的等待计算的文件，其余文件保持等待。单击选中一行，Ctrl 单击增减，Shift 单击选中一段（只看问题时按显示顺序）",
+        "Compute only the selected pending files and leave the rest waiting. Click selects a row, Ctrl+click adds or removes, Shift+click selects a range (in display order when showing problems only)",
+    ],
+    LastRun => ["上次耗时", "Last run"],
+    TimeBreakdownHint => [
+        "点击查看扫描、计算与缓存写入各占多少时间",
+        "Click to see how the time split between scanning, hashing and cache writes",
+    ],
+    Files => ["文件", "Files"],
+
+    // 表格行
+    JustNow => ["刚刚", "just now"],
+    MinutesAgo => ["{}分钟前", "{} min ago"],
+    HoursAgo => ["{}小时前", "{} h ago"],
+    DaysAgo => ["{}天前", "{} days ago"],
+    // {} 为保留天数
+    PastRetentionHint => [
+        "已超过保留期限（{} 天），下次清理过期时将从缓存中删除，可考虑重新计算",
+        "Older than the retention period ({} days) and removed by the next cleanup; consider recomputing",
+    ],
+    QuickHashHint => [
+        "快速哈希（仅首尾各 64KB，非权威）",
+        "Quick hash (first and last 64KB only, not authoritative)",
+    ],
+    InHashList => ["出现在粘贴的哈希清单中", "appears in the pasted hash list"],
+    AddNote => ["添加备注", "Add note"],
+    HashAnyway => ["仍然计算", "Hash anyway"],
+    HashAnywayHint => [
+        "忽略最大文件大小限制计算此文件",
+        "Ignore the maximum file size and hash this file",
+    ],
+    VerifyClipboard => ["粘贴校验", "Verify from clipboard"],
+    VerifyClipboardHint => ["与剪贴板中的哈希值比对", "Compare with the hash in the clipboard"],
+    CopyVerifyCommand => ["复制校验命令", "Copy verify command"],
+    CopyVerifyCommandHint => [
+        "复制可在文件所在目录直接运行的单行校验命令，无需安装 TurboHash",
+        "Copy a one-line verify command to run in the file's folder without TurboHash",
+    ],
+    AdvancedRehash => ["高级重算...", "Advanced rehash..."],
+    ForceRecompute => ["强制重新计算", "Force recompute"],
+    ForceRecomputeHint => [
+        "不采用缓存，重新读取并计算选中的文件；结果与原有缓存不同时弹出「哈希已变更」提示并列出新旧值，用于检查静默损坏",
+        "Re-read and hash the selected files without using the cache; if a result differs from the cached value, a \"hash changed\" warning lists the old and new values, to catch silent corruption",
+    ],
+    HashChanges => ["⚠ 哈希已变更", "⚠ Hash changed"],
+    HashChangesHint => [
+        "强制重新计算的结果与原有缓存不同，点击查看新旧值",
+        "Forced recompute results differ from the cache; click to see old and new values",
+    ],
+    HashChangesTitle => ["哈希已变更！", "Hash changed!"],
+    HashChangesNote => [
+        "强制重新计算或严格校验的结果与原有缓存不同。大小与修改时间未变而内容不同，可能是静默损坏",
+        "A forced recompute or paranoid check differs from the cache. If size and modification time are unchanged, this may be silent corruption",
+    ],
+    HashChangeModified => [
+        "已修改（大小或修改时间已变化）",
+        "Modified (size or modification time changed)",
+    ],
+    HashChangeCorrupted => [
+        "可能损坏（大小与修改时间未变）",
+        "Possibly corrupted (size and modification time unchanged)",
+    ],
+    CachedValue => ["缓存中的值", "Cached value"],
+    RecomputedValue => ["重新计算", "Recomputed"],
+    Dismiss => ["清除", "Dismiss"],
+    AdvancedRehashHint => [
+        "用指定的缓冲区与 MMAP 块大小单独计算此文件，用于排查读取问题，结果不写入缓存",
+        "Hash this file alone with explicit buffer and mmap chunk sizes to troubleshoot reads; results are not cached",
+    ],
+    ChunkHashes => ["分块哈希…", "Chunk hashes…"],
+    ChunkHashesHint => [
+        "把文件切成固定大小的块分别计算 XXH3 与 SHA256，并给出根哈希，用于大文件的局部去重与分段传输校验",
+        "Split the file into fixed-size chunks, hash each with XXH3 and SHA256 and show a root hash, for partial deduplication and transfer verification of large files",
+    ],
+    CopySri => ["复制为 SRI", "Copy as SRI"],
+    CopySriHint => [
+        "复制 Subresource Integrity 格式的 SHA256（sha256-<base64>），需要启用 SHA256",
+        "Copy the SHA256 in Subresource Integrity form (sha256-<base64>); requires SHA256",
+    ],
+
+    // 状态栏
+    GlobalProgress => ["全局进度:", "Progress:"],
+    FilesDone => ["已完成文件", "Files done"],
+    ProcessedTotal => ["已处理 / 总计", "Processed / total"],
+    FilesCleared => ["已清空文件", "Files cleared"],
+    Undo => ["撤销", "Undo"],
+    UndoWhileComputing => ["计算进行中，无法撤销", "Cannot undo while hashing"],
+
+    // 设置：性能模式
+    PerformanceMode => ["🚀 性能模式", "🚀 Performance"],
+    PerformanceModeHint => ["调整 I/O 策略", "Tune the I/O strategy"],
+    ModePowerSaving => ["节能", "Power saving"],
+    ModeBalanced => ["均衡", "Balanced"],
+    ModeHighPerformance => ["高性能", "High performance"],
+    ModeCustom => ["自定义", "Custom"],
+    Benchmark => ["📊 性能测试", "📊 Benchmark"],
+    BenchmarkHint => [
+        "测量本机各哈希算法与各预设的吞吐量，帮助选择性能模式",
+        "Measure the throughput of each algorithm and preset on this machine to help pick a mode",
+    ],
+
+    // 设置：详细设置
+    BufferSize => ["读取缓冲", "Read buffer"],
+    MmapChunk => ["内存映射", "Memory map"],
+    MinFileSize => ["缓存阈值", "Cache threshold"],
+    Retention => ["保留期限", "Retention"],
+    DaysSuffix => [" 天", " days"],
+    Forever => ["(永久)", "(forever)"],
+    ScanBatch => ["扫描批量", "Scan batches"],
+    FilesSuffix => [" 个", " files"],
+    ScanBatchSizeHint => ["每批发送到界面的文件数", "Files sent to the UI per batch"],
+    ScanFlushHint => ["未满批次的最长等待时间", "Longest wait before sending a partial batch"],
+    ScanThreads => ["扫描线程", "Scan threads"],
+    ThreadsAuto => ["自动", "Auto"],
+    ThreadsSerial => ["串行", "Serial"],
+    Threads => ["线程", "threads"],
+    ScanThreadsHint => [
+        "多线程并行遍历子目录，适合 NVMe 上层级很深的目录；机械硬盘和网络路径建议串行",
+        "Walk subdirectories in parallel, useful for deep trees on NVMe; use serial for hard disks and network paths",
+    ],
+    SystemTray => ["系统托盘", "System tray"],
+    MinimizeToTrayHint => [
+        "在托盘显示批次进度，点击托盘图标恢复窗口；窗口未聚焦时每个批次结束都发送系统通知",
+        "Show batch progress in the tray and restore the window by clicking the icon; a notification is sent when each batch ends while the window is not focused",
+    ],
+    TrayUnavailable => [
+        "当前构建未启用托盘支持（需 --features tray，仅 Windows/macOS）",
+        "Tray support is not enabled in this build (requires --features tray, Windows/macOS only)",
+    ],
+    CompletionNotice => ["完成通知", "Notification"],
+    Enable => ["启用", "Enable"],
+    LongerThan => ["耗时超过", "when longer than"],
+    SecondsSuffix => [" 秒", " s"],
+    NetworkPaths => ["网络路径", "Network paths"],
+    AllowNetworkMmap => ["允许内存映射", "Allow memory mapping"],
+    AllowNetworkMmapHint => [
+        "默认对 SMB/NFS 路径使用缓冲读取，避免远程缺页风暴",
+        "SMB/NFS paths use buffered reads by default to avoid remote page-fault storms",
+    ],
+    RemovableMedia => ["可移动磁盘", "Removable media"],
+    SkipRemovableCache => ["不缓存可移动磁盘", "Do not cache removable media"],
+    SkipRemovableCacheHint => [
+        "同一盘符可能先后插入不同的 U 盘，缓存可能命中另一设备上同路径的文件",
+        "Different USB drives can share a drive letter, so the cache may match a file at the same path on another device",
+    ],
+    MtimeTolerance => ["时间容差", "Time tolerance"],
+    MtimeToleranceHint => [
+        "修改时间差异在此范围内视为未修改（FAT32/exFAT 精度为 2 秒），0 表示严格比较",
+        "Modified times within this range count as unchanged (FAT32/exFAT store 2-second precision); 0 compares exactly",
+    ],
+    RecentLimit => ["最近记录", "Recent items"],
+    EntriesSuffix => [" 条", " entries"],
+    FilenameNormalization => ["文件名规范化", "Name normalization"],
+    NormalizeNfc => ["统一为 NFC", "Normalize to NFC"],
+    NormalizeNfcHint => [
+        "macOS 文件名为 NFD 形式，开启后可与其他平台导入的缓存匹配",
+        "macOS file names are NFD; enable to match caches imported from other platforms",
+    ],
+    HashAlgorithms => ["哈希算法", "Hash algorithms"],
+    InsecureHashWarning => [
+        "{} 已不安全，建议启用 SHA-256",
+        "{} is no longer secure, consider enabling SHA-256",
+    ],
+    MaxFileSize => ["最大文件", "Max file size"],
+    Unlimited => ["不限制", "Unlimited"],
+    MaxFileSizeHint => [
+        "超过此大小的文件仍会列出，但标记为「跳过-过大」且不计算；右键可「仍然计算」",
+        "Larger files are still listed but marked \"Skipped: too large\" and not hashed; right-click to hash anyway",
+    ],
+    ProgressModel => ["进度模型", "Progress model"],
+    ByFileCount => ["按文件数", "By file count"],
+    ByFileCountHint => [
+        "按已完成文件数 / 总文件数显示全局进度，不考虑文件大小",
+        "Show overall progress as completed files / total files, ignoring file sizes",
+    ],
+    PerFilePrefix => ["每文件 +", "Per file +"],
+    PerFileCostHint => [
+        "每个文件额外计入的等效字节数，体现打开和查询缓存的开销；0 表示按字节计算，大量小文件时可设为 256",
+        "Extra bytes counted per file for the cost of opening it and querying the cache; 0 counts bytes only, try 256 for many small files",
+    ],
+    UpdateCheck => ["检查更新", "Updates"],
+    CheckUpdateOnStartup => ["启动时检查更新", "Check on startup"],
+    CheckUpdateHint => [
+        "启动时从下面的地址下载版本清单，有新版本时在工具栏下方提示；失败时不做任何提示",
+        "Downloads the version manifest from the address below on startup and shows a notice under the toolbar when a newer version exists; failures are ignored",
+    ],
+    UpdateUnsupportedHint => [
+        "当前构建未启用 update-check 特性，不包含任何网络代码",
+        "This build was compiled without the update-check feature and contains no network code",
+    ],
+    CheckUpdateNow => ["立即检查", "Check now"],
+    UpToDate => ["已是最新版本", "Up to date"],
+    UpdateCheckFailed => ["检查更新失败", "Update check failed"],
+    UpdateAvailable => ["有新版本 {}（当前 {}）", "New version {} available (current {})"],
+    UpdateDownload => ["前往下载", "Download"],
+    Logging => ["日志", "Logging"],
+    LogError => ["错误", "Error"],
+    LogWarn => ["警告", "Warning"],
+    LogInfo => ["信息", "Info"],
+    LogDebug => ["调试", "Debug"],
+    LogOff => ["关闭", "Off"],
+    ViewLog => ["查看日志", "View log"],
+    OpenLogDir => ["打开日志目录", "Open log folder"],
+    OpenLogFailed => ["无法打开日志", "Cannot open the log"],
+    OpenDirFailed => ["无法打开目录", "Cannot open the folder"],
+    BlockDevices => ["块设备", "Block devices"],
+    AllowBlockDevices => ["允许计算块设备", "Allow hashing block devices"],
+    AllowBlockDevicesHint => [
+        "显式添加 /dev/sdb 等设备路径时按设备容量流式读取整个设备（通常需要 root 权限）；结果不写入缓存。仅 Unix",
+        "Explicitly added device paths such as /dev/sdb are read in full up to the device size (usually needs root); results are not cached. Unix only",
+    ],
+    Power => ["电源", "Power"],
+    PreventSleep => ["计算时阻止系统休眠", "Prevent sleep while hashing"],
+    PreventSleepHint => [
+        "计算期间阻止系统自动休眠，批次完成或停止后恢复（Windows 使用系统 API，macOS 使用 caffeinate，Linux 使用 systemd-inhibit）",
+        "Keep the system awake while hashing and restore afterwards (system API on Windows, caffeinate on macOS, systemd-inhibit on Linux)",
+    ],
+    Sidecars => ["校验文件", "Checksum files"],
+    VerifySidecars => ["自动校验同名校验文件", "Verify matching checksum files"],
+    VerifySidecarsHint => [
+        "添加 movie.iso 时若同目录存在 movie.iso.sha256 / movie.iso.md5sum 等校验文件，计算完成后自动比对并在状态列显示 ✓/✗",
+        "When movie.iso sits next to movie.iso.sha256 / movie.iso.md5sum etc., compare after hashing and show ✓/✗ in the status column",
+    ],
+    ConfirmClear => ["清空前确认", "Confirm before clearing"],
+    ConfirmClearHint => [
+        "关闭后点击「清空队列」立即清空，仍可在状态栏撤销",
+        "When off, Clear queue clears immediately; you can still undo from the status bar",
+    ],
+    ComputeOrder => ["计算顺序", "Hashing order"],
+    OrderAdded => ["添加顺序", "Order added"],
+    OrderSmallestFirst => ["小文件优先", "Smallest first"],
+    OrderLargestFirst => ["大文件优先", "Largest first"],
+    ComputeOrderHint => [
+        "小文件优先：混合批次中大量小文件迅速完成，不会被大文件占满线程；大文件优先：避免批次末尾只剩一个大文件在计算。下一批次生效",
+        "Smallest first: many small files finish quickly instead of waiting behind large ones; largest first: avoids one large file running alone at the end. Applies to the next batch",
+    ],
+    CacheValidation => ["缓存校验级别", "Cache validation"],
+    ValidationFast => ["快速", "Fast"],
+    ValidationNormal => ["标准", "Normal"],
+    ValidationParanoid => ["严格", "Paranoid"],
+    CacheValidationHint => [
+        "快速：大小与修改时间一致即采用缓存，不读取文件，适合大量文件的例行复查；标准：重新计算 XXH3 并与缓存比对；严格：重新计算所有启用的哈希，全部一致才采用缓存，适合归档校验。下一批次生效",
+        "Fast: trust the cache when size and modification time match, without reading the file, for routine re-scans of many files; Normal: recompute XXH3 and compare it with the cache; Paranoid: recompute every enabled hash and trust the cache only if all match, for archival checks. Applies to the next batch",
+    ],
+    Diagnostics => ["诊断", "Diagnostics"],
+    HashTiming => ["统计各算法耗时", "Time each hash algorithm"],
+    HashTimingHint => [
+        "计算时分别记录每个哈希算法的耗时，批次结束后在耗时分解窗口中查看各算法的吞吐量，找出拖慢计算的算法。会略微增加开销，下一批次生效",
+        "Record the time spent in each hash algorithm while computing; after the batch, the time breakdown window shows per-algorithm throughput so you can find the one slowing things down. Adds slight overhead. Applies to the next batch",
+    ],
+    SerialCompute => ["串行模式", "Serial mode"],
+    SerialComputeHint => [
+        "在单个线程中按队列顺序逐个计算，耗时可复现，便于性能测试与排查缓存、进度问题；结果与并行计算完全一致，但大批次会明显变慢。下一批次生效",
+        "Hash files one at a time in queue order on a single thread, so timing is reproducible for benchmarking and diagnosing cache or progress issues; results are identical to parallel mode but large batches are much slower. Applies to the next batch",
+    ],
+    CacheContention => ["⚠ 缓存与文件在同一磁盘", "⚠ Cache on the same disk"],
+    // 依次为批次文件所在的目录、每次写入缓存的文件数
+    CacheContentionHint => [
+        "缓存数据库与 {} 位于同一块机械硬盘或可移动介质，读取文件与写入缓存会相互争用。本批次的缓存已改为每 {} 个文件写入一次；把 TurboHash 放到其他磁盘上运行可以避免争用",
+        "The cache database is on the same hard disk or removable drive as {}, so reading files and writing the cache compete. This batch now writes the cache every {} files; run TurboHash from another disk to avoid the contention",
+    ],
+    Xattrs => ["扩展属性", "Extended attributes"],
+    WriteXattrsOnComplete => ["计算完成后写入扩展属性", "Write xattrs after hashing"],
+    WriteXattrsOnCompleteHint => [
+        "每批次完成后将哈希写入 user.crc32 / user.md5 / user.sha1 / user.sha256 / user.xxh3 扩展属性；FAT 等不支持的文件系统自动跳过",
+        "After each batch write hashes to the user.crc32 / user.md5 / user.sha1 / user.sha256 / user.xxh3 attributes; unsupported file systems such as FAT are skipped",
+    ],
+    AlternateStreams => ["备用数据流", "Alternate streams"],
+    HashAlternateStreams => ["计算 NTFS 备用数据流", "Hash NTFS alternate data streams"],
+    HashAlternateStreamsHint => [
+        "扫描时枚举每个文件的备用数据流（ADS），每个流作为单独的一行 file.txt:stream 计算哈希，用于取证检查。下次添加文件时生效",
+        "List each file's alternate data streams (ADS) while scanning and hash each as its own file.txt:stream row, for forensics. Applies to files added afterwards",
+    ],
+    WindowsOnly => ["仅 Windows（NTFS）", "Windows (NTFS) only"],
+    DiskConcurrency => ["磁盘并发", "Disk concurrency"],
+    DiskAuto => ["自动检测", "Detect"],
+    DiskFull => ["始终全部并发", "Always fully parallel"],
+    DiskLimited => ["始终限制并发", "Always limited"],
+    // {} 为机械硬盘上的计算线程数
+    DiskConcurrencyHint => [
+        "机械硬盘上多线程同时读取会导致磁头来回寻道，反而更慢。自动检测：批次所在磁盘为机械硬盘时计算限制为 {} 线程、扫描改为串行（Windows 查询寻道开销，Linux 读取 sysfs rotational 标志）；检测不准确时可手动指定。下一批次生效",
+        "Parallel reads on a hard disk make the heads seek back and forth and are slower. Detect: on a hard disk hashing is limited to {} threads and scanning is serial (seek penalty query on Windows, sysfs rotational flag on Linux); override if detection is wrong. Applies to the next batch",
+    ],
+    VacuumSchedule => ["自动整理", "Auto vacuum"],
+    VacuumOff => ["仅清理后", "Only after cleanup"],
+    VacuumOnExit => ["退出时（碎片过多）", "On exit (when fragmented)"],
+    VacuumWeekly => ["每周一次", "Weekly"],
+    VacuumScheduleHint => [
+        "退出程序时按计划执行 VACUUM 压缩缓存数据库：「退出时」在空闲页超过 10% 时整理，「每周一次」距上次整理满 7 天且有空闲页时整理。计算或清理进行中退出时跳过；数据库较大时退出会稍慢",
+        "VACUUM the cache database on exit: \"On exit\" when over 10% of pages are free, \"Weekly\" when 7 days have passed and there are free pages. Skipped while hashing or cleaning up; exit takes longer with a large database",
+    ],
+    SizeUnit => ["大小单位", "Size unit"],
+    SizeBinary => ["二进制（KiB/MiB）", "Binary (KiB/MiB)"],
+    SizeDecimal => ["十进制（KB/MB）", "Decimal (KB/MB)"],
+    SizeUnitHint => [
+        "表格、状态栏与设置中的文件大小按 1024（KiB/MiB，与操作系统一致）或 1000（KB/MB，与硬盘厂商标注一致）换算",
+        "Sizes in the table, status bar and settings use 1024 (KiB/MiB, like the operating system) or 1000 (KB/MB, like drive vendors)",
+    ],
+    SymlinkPolicy => ["符号链接", "Symlinks"],
+    SymlinkFollow => ["计算目标文件", "Hash the target"],
+    SymlinkLink => ["计算链接本身", "Hash the link itself"],
+    SymlinkPolicyHint => [
+        "直接添加的指向文件的符号链接：「计算目标文件」读取链接指向的文件，缓存按解析后的目标路径保存；「计算链接本身」对链接中保存的目标路径计算哈希，不读写缓存。遍历目录时始终跳过符号链接",
+        "Symlinks to files added directly: \"Hash the target\" reads the file the link points to and caches it under the resolved target path; \"Hash the link itself\" hashes the target path stored in the link and bypasses the cache. Symlinks inside folders are always skipped",
+    ],
+    CopyFeedback => ["复制提示", "Copy feedback"],
+    CopyFeedbackInCell => ["替换单元格文字", "Replace cell text"],
+    CopyFeedbackOverlay => ["浮动提示", "Floating overlay"],
+    CopyFeedbackHint => [
+        "点击哈希复制后，「替换单元格文字」暂时把哈希值换成「已复制到剪贴板」；「浮动提示」在单元格上方显示提示，哈希值保持可见",
+        "After clicking a hash to copy it, \"Replace cell text\" briefly swaps the value for a confirmation; \"Floating overlay\" shows the confirmation above the cell and keeps the value visible",
+    ],
+    CopyToastDurationHint => ["复制提示的显示时长", "How long the copy confirmation stays visible"],
+    MessagesPerFrame => ["每帧消息数", "Messages per frame"],
+    MessagesPerFrameAuto => ["自动（按帧时间）", "Auto (frame time)"],
+    MessagesPerFrameUnit => ["条", "messages"],
+    MessagesPerFrameHint => [
+        "界面每帧最多处理的工作线程消息数。「自动」先处理至少 64 条消息，再在约 8ms 内尽量多处理，大批量快速计算时界面不落后、工作线程也不会因通道已满而等待；固定上限过小会限制计算吞吐",
+        "Maximum worker messages the UI handles per frame. \"Auto\" first takes at least 64 and then keeps going for about 8 ms, so the UI keeps up with large fast batches and the worker is not blocked on a full channel; a small fixed limit caps throughput",
+    ],
+    ChannelCapacity => ["消息通道容量", "Message channel capacity"],
+    ChannelCapacityHint => [
+        "工作线程发往界面的消息最多积压的条数，重启后生效。通道满时计算线程等待界面处理（进度等可合并的消息直接丢弃），大量小文件时容量过小会使计算速度受限于界面刷新",
+        "How many worker messages may queue up for the UI; takes effect after restart. When full, hashing threads wait for the UI (coalescable messages such as progress are dropped), so a small capacity throttles large batches of tiny files to the UI's pace",
+    ],
+    HashEncoding => ["哈希编码", "Hash encoding"],
+    HashEncodingHint => [
+        "表格、复制与「复制全部」中哈希的显示编码；缓存与导出的校验文件始终使用十六进制",
+        "Encoding for hashes in the table and when copying; the cache and exported checksum files always use hex",
+    ],
+    EncodingHex => ["十六进制", "Hex"],
+    EncodingBase64Url => ["Base64（URL 安全）", "Base64 (URL-safe)"],
+    ExcludedDirs => ["排除的目录", "Excluded folders"],
+    ExcludedDirsHint => [
+        "扫描文件夹时整个跳过这些名称的子目录（每行一个，不区分大小写）；直接添加的文件夹本身不受影响",
+        "Subfolders with these names are skipped entirely when scanning (one per line, case-insensitive); folders you add directly are still scanned",
+    ],
+    SparseFiles => ["稀疏文件", "Sparse files"],
+    SkipSparseHoles => ["只读取已分配的区域", "Read only allocated ranges"],
+    SkipSparseHolesHint => [
+        "虚拟机磁盘等稀疏文件的空洞不读盘、不映射内存，直接按零计算，哈希与普通读取一致。避免内存映射巨大的稀疏文件时提交内存暴涨",
+        "Holes in sparse files such as VM disks are hashed as zeros without reading or memory-mapping them; the hashes are identical. Avoids committed memory ballooning when mapping huge sparse files",
+    ],
+
+    // 设置：维护操作
+    CleanExpired => ["🧹 清理过期", "🧹 Remove expired"],
+    CheckpointWal => ["检查点/压缩 WAL", "Checkpoint WAL"],
+    CheckpointWalHint => [
+        "将 WAL 日志写回数据库并把 -wal 文件截断为 0",
+        "Write the WAL back into the database and truncate the -wal file to 0",
+    ],
+    WalCheckpointed => ["WAL 已压缩", "WAL checkpointed"],
+    WalPartial => [
+        "部分完成：有读取仍在进行，请稍后重试",
+        "Partly done: reads are still in progress, try again later",
+    ],
+    OperationFailed => ["失败", "Failed"],
+    AuditCache => ["🔍 审计缓存", "🔍 Audit cache"],
+    AuditCacheHint => [
+        "选择文件夹，重新核对其中缓存条目的内容是否仍与磁盘一致",
+        "Pick a folder and recheck that its cached entries still match the files on disk",
+    ],
+    ClearAll => ["🗑️ 清空所有", "🗑️ Clear all"],
+    Cleaning => ["清理中…", "Cleaning…"],
+    Vacuuming => ["压缩数据库中…", "Compacting database…"],
+    Cancel => ["取消", "Cancel"],
+    VacuumNotCancellable => ["压缩数据库无法中途取消", "Compacting cannot be cancelled"],
+    // 校验文件与路径
+    SidecarMatch => ["与校验文件 {} 一致", "Matches checksum file {}"],
+    SidecarMismatch => [
+        "与校验文件 {} 不一致\n期望 {}: {}",
+        "Differs from checksum file {}\nExpected {}: {}",
+    ],
+    SidecarNotComputed => [
+        "未计算 {}，无法与校验文件 {} 比对（可在缓存设置中启用该算法）",
+        "{} was not computed, so checksum file {} cannot be checked (enable the algorithm in Settings)",
+    ],
+    SidecarPending => ["计算完成后将与校验文件 {} 比对", "Will be checked against checksum file {} once hashed"],
+    InvalidFileName => ["无效文件名", "Invalid file name"],
+    PathsAdded => ["已添加 {} 个路径，跳过 {} 个", "Added {} paths, skipped {}"],
+    ClipboardUnreadable => ["无法读取剪贴板", "Cannot read the clipboard"],
+    NoRecentPaths => ["暂无最近记录", "No recent items"],
+    ClearRecentPaths => ["清除最近记录", "Clear recent items"],
+    PickFiles => ["选择要计算哈希的文件", "Choose files to hash"],
+    PickFolder => ["选择要计算哈希的文件夹", "Choose a folder to hash"],
+    RevealedParent => ["文件已不存在，已打开 {}", "The file no longer exists, opened {}"],
+    MetadataRefreshed => [
+        "元数据已刷新：{} 个文件已变更，{} 个文件已删除",
+        "Metadata refreshed: {} files changed, {} files deleted",
+    ],
+    SidecarSummary => ["校验文件: {} 个一致，{} 个不一致", "Checksum files: {} match, {} differ"],
+
+    // 清空队列与恢复
+    FilesRestored => ["已恢复 {} 个文件", "Restored {} files"],
+    ConfirmClearQueue => ["确定清空队列中的 {} 个文件？", "Clear the {} files in the queue?"],
+    ClearUndoWindow => ["清空后 {} 秒内可撤销", "You can undo within {} seconds"],
+    DontAskAgain => ["不再询问", "Don't ask again"],
+    DontAskAgainHint => ["可在缓存设置中重新开启", "Can be turned back on in Settings"],
+    Clear => ["清空", "Clear"],
+    UnfinishedBatch => ["未完成的批处理", "Unfinished batch"],
+    ResumeBatchPrompt => [
+        "上次批处理还有 {} 个文件未完成，是否继续？",
+        "{} files from the last batch were not finished. Continue?",
+    ],
+    ResumeBatch => ["继续上次批处理", "Resume last batch"],
+    Discard => ["放弃", "Discard"],
+
+    // 导出
+    StreamExportFailed => ["流式导出失败: {}", "Streaming export failed: {}"],
+    StreamExportWritten => ["流式导出已写入 {} 行: {}", "Streaming export wrote {} lines: {}"],
+    Format => ["格式", "Format"],
+    LetterCase => ["大小写", "Letter case"],
+    CaseConventional => ["{}（惯例）", "{} (conventional)"],
+    ExportEllipsis => ["导出...", "Export..."],
+    StreamExport => ["流式导出", "Streaming export"],
+    StreamExportWriting => ["正在写入: {}", "Writing: {}"],
+    StopStreamExport => ["停止流式导出", "Stop streaming export"],
+    StreamExportArmed => ["下次开始计算时写入 {}: {}", "Will write {} when hashing next starts: {}"],
+    ChooseFile => ["选择文件...", "Choose file..."],
+    NothingToExport => ["没有可导出的 {} 结果", "No {} results to export"],
+    ExportedSkipped => ["已导出 {} 个文件，跳过 {} 个", "Exported {} files, skipped {}"],
+    Exported => ["已导出 {} 个文件", "Exported {} files"],
+    ExportFailed => ["导出失败: {}", "Export failed: {}"],
+    CopyAllHeader => ["文件名\t大小", "Name\tSize"],
+    CopyAllNoteHeader => ["\t备注", "\tNote"],
+    VerifyCommandCopied => ["已复制 {} 校验命令: {}", "Copied {} verify command: {}"],
+    NoVerifyCommandHash => [
+        "没有可用于校验命令的哈希（需要 MD5/SHA1/SHA256）",
+        "No hash usable for a verify command (needs MD5/SHA1/SHA256)",
+    ],
+    NoCompletedResults => ["没有已完成的结果", "No completed results"],
+    RowsCopied => ["已复制 {} 行", "Copied {} rows"],
+    CopiedToClipboard => ["已复制到剪贴板", "Copied to clipboard"],
+    ClickToCopy => ["点击复制", "Click to copy"],
+    SriCopied => ["已复制 SRI: {}", "Copied SRI: {}"],
+    Copy => ["复制", "Copy"],
+
+    // 批次耗时与完成
+    TimeBreakdown => ["批次耗时分解", "Batch time breakdown"],
+    TotalTime => [
+        "总耗时 {}（从开始扫描或开始计算到最后一个文件完成）",
+        "Total {} (from the start of scanning or hashing to the last file finishing)",
+    ],
+    PhaseScanning => ["扫描", "Scanning"],
+    PhaseWaiting => ["等待开始计算", "Waiting to start"],
+    PhaseHashing => ["计算", "Hashing"],
+    PhaseCacheWrites => ["缓存写入", "Cache writes"],
+    CacheWritesHint => [
+        "后台进行，与计算重叠，不计入总耗时",
+        "Runs in the background alongside hashing and is not part of the total",
+    ],
+    AlgorithmTimes => ["各算法耗时", "Time per algorithm"],
+    AlgorithmTimesNote => [
+        "各线程耗时之和，吞吐量按单线程计算",
+        "Summed across threads; throughput is per thread",
+    ],
+    CleanupCancelled => ["已取消，已删除 {} 条", "Cancelled, {} entries deleted"],
+    CleanupExpiredDone => ["已清理 {} 条", "Removed {} entries"],
+    CleanupAllDone => ["已清空 {} 条", "Cleared {} entries"],
+    FailedWith => ["失败: {}", "Failed: {}"],
+    ManifestUnreadable => ["无法读取校验清单: {}", "Cannot read the checksum list: {}"],
+    TrayCompleted => ["TurboHash - 已完成 {} 个文件", "TurboHash - {} files done"],
+    TrayComputing => ["TurboHash - 计算中 {}%", "TurboHash - hashing {}%"],
+    BatchCompletedNotice => ["完成 {} 个文件，耗时 {}", "Hashed {} files in {}"],
+    AlgorithmDisabled => ["该算法未在缓存设置中启用", "This algorithm is not enabled in Settings"],
+
+    // 分析窗口
+    CollisionReport => ["碰撞检测报告", "Collision report"],
+    NoCollisions => [
+        "未发现 CRC32/XXH3 相同但强哈希不同的文件",
+        "No files share a CRC32/XXH3 while differing in a strong hash",
+    ],
+    CollisionsFound => ["发现 {} 组碰撞", "{} collision groups found"],
+    CollisionEntry => ["  {}  摘要: {}", "  {}  digest: {}"],
+    NameConflictReport => ["同名不同内容报告", "Name conflict report"],
+    NoNameConflicts => [
+        "未发现文件名相同但内容不同的已完成文件",
+        "No completed files share a name with different contents",
+    ],
+    NameConflictsFound => ["发现 {} 个文件名存在不同内容", "{} file names have differing contents"],
+    NameConflictEntry => ["{}：{} 个版本，{} 个文件", "{}: {} versions, {} files"],
+    DigestEntry => ["  摘要: {}", "  digest: {}"],
+    NoFiles => ["暂无文件", "No files"],
+    Count => ["数量", "Count"],
+    TotalSize => ["总大小", "Total size"],
+    NoExtension => ["其他", "Other"],
+    FileComparison => ["文件对比", "File comparison"],
+    Computing => ["计算中...", "Hashing..."],
+    CompareFailed => ["对比失败: {}", "Comparison failed: {}"],
+    Identical => ["✓ 相同", "✓ Identical"],
+    Different => ["✗ 不同", "✗ Different"],
+    SizesDiffer => ["大小不同：{} / {}（未计算哈希）", "Sizes differ: {} / {} (not hashed)"],
+    Algorithm => ["算法", "Algorithm"],
+
+    // 目录哈希与缓存审计
+    PickDirectoryHashFolder => ["选择要计算合并哈希的文件夹", "Choose a folder to hash as a whole"],
+    OneDirectoryHash => [
+        "一次只能计算一个文件夹的合并哈希，其余文件夹已按常规加入",
+        "Only one folder can be hashed as a whole at a time; the others were added normally",
+    ],
+    DropDirectoryHash => ["松开：计算文件夹的合并哈希", "Release to hash the folder as a whole"],
+    DropToAdd => [
+        "松开：添加并扫描（按住 Shift 则计算文件夹的合并哈希）",
+        "Release to add and scan (hold Shift to hash the folder as a whole)",
+    ],
+    DirectoryHashTitle => ["目录合并哈希", "Directory hash"],
+    DirectoryLabel => ["目录: {}", "Folder: {}"],
+    DirectoryHashNote => [
+        "按相对路径排序合并所有文件内容，忽略以 . 开头的条目",
+        "Combines all file contents in relative-path order, ignoring entries that start with .",
+    ],
+    HashFailed => ["计算失败: {}", "Hashing failed: {}"],
+    PickAuditFolder => ["选择要审计缓存的文件夹", "Choose a folder to audit"],
+    CacheAudit => ["缓存审计", "Cache audit"],
+    CacheAuditNote => [
+        "重新计算 XXH3 与缓存比对：修改时间或大小变化属于正常修改，两者未变而内容不同则可能是静默损坏",
+        "Rehashes XXH3 and compares it with the cache: a changed size or modified time is a normal edit, while different contents with both unchanged may be silent corruption",
+    ],
+    Auditing => ["核对中...", "Checking..."],
+    AuditFailed => ["审计失败: {}", "Audit failed: {}"],
+    AuditSummary => [
+        "已核对 {} 个文件（{} 个已不存在），可能损坏 {} 个，其余异常 {} 个",
+        "Checked {} files ({} no longer exist): {} possibly corrupted, {} other problems",
+    ],
+    AuditClean => ["✓ 所有文件与缓存一致", "✓ All files match the cache"],
+    PossiblyCorrupted => ["可能损坏", "Possibly corrupted"],
+    AuditXxh3 => ["缓存 XXH3: {}\n当前 XXH3: {}", "Cached XXH3: {}\nCurrent XXH3: {}"],
+    Unreadable => ["无法读取", "Unreadable"],
+    Modified => ["已修改", "Modified"],
+    ModifiedHint => ["大小或修改时间与缓存不同", "Size or modified time differs from the cache"],
+
+    // 高级重算与性能测试
+    AdvancedRehashTitle => ["高级重算", "Advanced rehash"],
+    FileLabel => ["文件: {}", "File: {}"],
+    AdvancedRehashNote => [
+        "使用指定的参数单独计算此文件，用于排查读取问题；结果不写入缓存，也不更新表格",
+        "Hashes this file alone with the given parameters to troubleshoot reads; the result is neither cached nor shown in the table",
+    ],
+    BufferSizeLabel => ["缓冲区大小", "Buffer size"],
+    MmapChunkLabel => ["MMAP 块大小", "MMAP chunk size"],
+    ReadMethod => ["读取方式", "Read method"],
+    ForceBuffered => ["强制缓冲读取（不使用内存映射）", "Force buffered reads (no memory mapping)"],
+    RehashExactSizes => [
+        "按所选大小原样读取，不做批量计算时的自动调整；小文件整体读入内存，不使用这两项",
+        "Reads with exactly these sizes, without the automatic tuning used in batches; small files are read whole and use neither",
+    ],
+    StartRehash => ["开始重算", "Rehash"],
+    RehashFailed => ["重算失败: {}", "Rehash failed: {}"],
+    RehashParamsUsed => ["参数: 缓冲区 {} / MMAP 块 {}{}", "Parameters: buffer {} / MMAP chunk {}{}"],
+    RehashReadPath => ["读取路径: {}", "Read path: {}"],
+    StrategyTiny => ["小文件（整体读取）", "Small file (read whole)"],
+    StrategyBuffered => ["中等文件（缓冲读取）", "Medium file (buffered reads)"],
+    StrategyMmap => ["大文件（内存映射）", "Large file (memory mapped)"],
+    ForceBufferedSuffix => [" / 强制缓冲读取", " / forced buffered reads"],
+    RehashTiming => ["耗时: {}（{}，{} MB/s）", "Time: {} ({}, {} MB/s)"],
+    BenchmarkTitle => ["性能测试", "Benchmark"],
+    BenchmarkNote => [
+        "测量本机各哈希算法、各读取路径与各性能预设的吞吐量；测试期间请勿进行其他磁盘密集操作",
+        "Measures the throughput of each hash algorithm, read path and performance preset on this machine; avoid other disk-heavy work while it runs",
+    ],
+    BenchmarkRunning => ["测试中，可能需要数十秒...", "Running, this can take tens of seconds..."],
+    BenchmarkGenerated => ["使用生成数据（{}）", "Use generated data ({})"],
+    BenchmarkGeneratedHint => [
+        "在临时目录生成测试文件，结束后自动删除",
+        "Creates a test file in the temp folder and deletes it afterwards",
+    ],
+    PickBenchmarkFile => ["选择用于性能测试的文件", "Choose a file to benchmark"],
+    BenchmarkFailed => ["测试失败: {}", "Benchmark failed: {}"],
+    RunAgain => ["重新测试", "Run again"],
+    GeneratedData => ["生成数据", "Generated data"],
+    BenchmarkFile => ["测试文件: {}（{}）", "Test file: {} ({})"],
+    BenchmarkAlgorithms => ["哈希算法（内存）", "Hash algorithms (in memory)"],
+    BenchmarkReadPaths => ["读取路径（当前设置）", "Read paths (current settings)"],
+    BenchmarkPresets => ["性能预设", "Performance presets"],
+    PresetSizes => ["缓冲 {} / MMAP 块 {}", "Buffer {} / MMAP chunk {}"],
+    Throughput => ["{} MB/s", "{} MB/s"],
+    FastestThroughput => ["{} MB/s ★", "{} MB/s ★"],
+    ApplyPreset => ["应用「{}」", "Apply \"{}\""],
+    ApplyPresetHint => [
+        "将性能模式切换为本次测试中最快的预设",
+        "Switch the performance mode to the fastest preset in this run",
+    ],
+
+    // 分块哈希
+    ChunkHashTitle => ["分块哈希", "Chunk hashes"],
+    ChunkSizeLabel => ["块大小", "Chunk size"],
+    ChunkRootNote => [
+        "根哈希为各块 SHA256 按顺序拼接后的 SHA256；只需重传哈希不一致的块",
+        "The root hash is the SHA256 of all chunk SHA256s in order; only chunks whose hashes differ need to be resent",
+    ],
+    ChunkHashRead => ["已读取 {} / {}", "Read {} of {}"],
+    RootHash => ["根哈希", "Root hash"],
+    ChunkCount => ["{} 块，共 {}", "{} chunks, {} in total"],
+    ExportChunkList => ["导出列表…", "Export list…"],
+    ExportChunkListTitle => ["导出分块哈希列表", "Export chunk hash list"],
+    TxtFiles => ["文本文件", "Text files"],
+    ChunkListExported => ["已导出 {} 块的哈希列表", "Exported the hashes of {} chunks"],
+
+    // 按哈希重命名
+    RenameNote => [
+        "将文件重命名为「SHA256+原扩展名」；目标已存在且内容相同时跳过，内容不同时提示冲突，不会覆盖任何文件",
+        "Renames files to \"SHA256 + original extension\"; skips when an identical target exists and reports a conflict when it differs. Nothing is ever overwritten",
+    ],
+    NothingToRename => [
+        "没有可重命名的文件：需要已完成计算且启用了 SHA256 的文件",
+        "Nothing to rename: files must be hashed with SHA256 enabled",
+    ],
+    Renaming => ["正在重命名...", "Renaming..."],
+    PlanningRename => ["正在生成预览...", "Building preview..."],
+    SelectAll => ["全选", "Select all"],
+    SelectNone => ["全不选", "Select none"],
+    PreviewRename => ["预览 {} 个文件（试运行）", "Preview {} files (dry run)"],
+    RenamePlanSummary => ["将重命名 {} 个，跳过 {} 个，冲突 {} 个", "{} to rename, {} skipped, {} conflicts"],
+    RenameConflictNote => [
+        "冲突的文件不会被改动，请手动检查同名目标",
+        "Conflicting files are left alone; check the existing targets by hand",
+    ],
+    RenameWaitForBatch => [
+        "正在计算，完成后才能执行重命名",
+        "Hashing is in progress; renaming is available once it finishes",
+    ],
+    ConfirmRename => [
+        "我确认重命名以上 {} 个文件（不可撤销）",
+        "I confirm renaming the {} files above (cannot be undone)",
+    ],
+    ApplyRename => ["执行重命名", "Rename"],
+    BackToSelection => ["返回选择", "Back to selection"],
+    Renamed => ["已重命名 {} 个文件", "Renamed {} files"],
+    RenamedWithFailures => [
+        "已重命名 {} 个文件，{} 个失败（详见日志）",
+        "Renamed {} files, {} failed (see the log)",
+    ],
+
+    // 按哈希查找与清单筛查
+    HashSearchHint => ["粘贴哈希值查找", "Paste a hash to find"],
+    HashSearchTooltip => [
+        "在所有哈希列中查找，按长度自动识别算法；匹配多个文件（如重复文件）时列出全部",
+        "Searches every hash column, detecting the algorithm by length; lists all files when several match (such as duplicates)",
+    ],
+    InvalidHash => ["不是有效的哈希值", "Not a valid hash"],
+    UnknownHashLength => ["无法识别 {} 位的哈希", "Unrecognized {}-digit hash"],
+    NotFound => ["未找到", "Not found"],
+    FoundMatches => ["找到 {} 个（{}）", "Found {} ({})"],
+    ListMatchesHint => ["点击列出所有匹配的文件", "Click to list all matching files"],
+    HashSearchResults => ["哈希查找结果", "Hash search results"],
+    SameHashFiles => ["{} 个文件的哈希值相同", "{} files share this hash"],
+    Locate => ["定位", "Locate"],
+    HashListNote => [
+        "粘贴已知哈希清单（每行一个，MD5/SHA1/SHA256/CRC32/XXH3 均可，也可直接粘贴校验文件内容），已完成的文件任一哈希出现在清单中即标记为命中",
+        "Paste a list of known hashes (one per line, MD5/SHA1/SHA256/CRC32/XXH3, or the contents of a checksum file); completed files with any hash in the list are flagged",
+    ],
+    HashListPlaceholder => [
+        "每行一个哈希值，# 开头的行为注释",
+        "One hash per line, lines starting with # are comments",
+    ],
+    Screen => ["筛查", "Screen"],
+    ClearList => ["清除清单", "Clear list"],
+    HashListSize => ["清单共 {} 条", "{} entries in the list"],
+    HashListSkipped => ["（{} 行无法识别，已跳过）", " ({} unrecognized lines skipped)"],
+    HashListHits => ["⚠ 命中 {} 个文件", "⚠ {} files listed"],
+    HashListNoHits => ["未命中任何已完成的文件", "No completed files are listed"],
+    HashListToast => ["清单筛查：命中 {} 个文件", "Hash list: {} files listed"],
+
+    // 校验清单
+    RemoteVerifyNote => [
+        "粘贴校验清单（如 SHA256SUMS）的链接，下载后按文件名与列表中的文件比对，结果显示在状态列",
+        "Paste the link to a checksum list (such as SHA256SUMS); after downloading it files are matched by name and the result shows in the status column",
+    ],
+    Fetch => ["获取", "Fetch"],
+    Downloading => ["下载中...", "Downloading..."],
+    NoChecksumsDownloaded => ["未在下载的内容中找到校验记录", "No checksum records found in the download"],
+    ChecksumListApplied => [
+        "校验清单 {} 条记录，匹配 {} 个文件：{} 个一致，{} 个不一致",
+        "Checksum list has {} records, matched {} files: {} match, {} differ",
+    ],
+    PickManifest => ["选择校验清单（如 SHA256SUMS）", "Choose a checksum list (such as SHA256SUMS)"],
+    NoChecksumsIn => ["未在 {} 中找到校验记录", "No checksum records found in {}"],
+    ManifestReport => ["校验清单报告", "Checksum list report"],
+    ManifestLabel => ["清单: {}", "List: {}"],
+    ManifestMatched => ["✓ 一致 {}", "✓ Match {}"],
+    ManifestMismatched => ["✗ 不一致 {}", "✗ Differ {}"],
+    ManifestPending => ["待计算 {}", "To hash {}"],
+    ManifestUntracked => ["{}（目录中存在但清单中没有）: {}", "{} (in the folder but not in the list): {}"],
+    ManifestMissing => ["缺失（清单中列出但找不到）: {}", "Missing (listed but not found): {}"],
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_placeholders_filled_in_order() {
+        let text = Text::UpdateAvailable.get(Language::Chinese);
+        assert_eq!(fill(text, &[&"2.0", &"{}"]), "有新版本 2.0（当前 {}）");
+        // 参数不足时保留占位符
+        assert_eq!(fill(text, &[&"2.0"]), "有新版本 2.0（当前 {}）");
+    }
+
+    #[test]
+    fn test_every_key_has_all_translations() {
+        for &key in Text::ALL {
+            for language in Language::ALL {
+                assert!(
+                    !key.get(language).is_empty(),
+                    "{:?} 缺少 {:?} 文本",
+                    key,
+                    language
+                );
+            }
+            // 英文文本在没有中文字体时也要能显示
+            let english = key.get(Language::English);
+            assert!(
+                !english
+                    .chars()
+                    .any(|c| ('\u{4e00}'..='\u{9fff}').contains(&c)),
+                "{:?} 的英文文本包含汉字: {}",
+                key,
+                english
+            );
+        }
+    }
+}
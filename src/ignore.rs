@@ -0,0 +1,340 @@
+// `.turbohashignore` 忽略文件
+//
+// 与 `.gitignore` 类似，在扫描的文件夹中放一个 `.turbohashignore`，每行一个 glob 模式，
+// 匹配的文件与目录不加入列表。比设置中的排除目录更直观，也可以随文件夹一起分享。
+// 支持的语法与 gitignore 一致：`#` 注释、`!` 重新包含、末尾 `/` 只匹配目录、含 `/`
+// 的模式相对忽略文件所在目录、`*`/`?`/`[...]` 不跨越目录、`**` 匹配任意层目录。
+// 每层目录都可以有自己的忽略文件，深层的规则优先，同一文件中靠后的规则优先；被忽略的
+// 目录整个跳过，其中的文件不能再被重新包含。
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// 忽略文件名
+pub const IGNORE_FILE_NAME: &str = ".turbohashignore";
+
+/// 一条忽略规则
+#[derive(Debug)]
+struct Rule {
+    pattern: Vec<Token>,
+    /// `!` 开头，匹配时重新包含
+    negated: bool,
+    /// 末尾为 `/`，只匹配目录
+    dir_only: bool,
+    /// 含 `/` 的模式匹配相对路径，否则只匹配名称
+    anchored: bool,
+}
+
+impl Rule {
+    /// 解析一行，空行与注释返回 None
+    fn parse(line: &str) -> Option<Rule> {
+        let line = line.trim_end();
+        if line.is_empty() || line.starts_with('#') {
+            return None;
+        }
+        let (negated, line) = match line.strip_prefix('!') {
+            Some(rest) => (true, rest),
+            None => (false, line),
+        };
+        let (dir_only, line) = match line.strip_suffix('/') {
+            Some(rest) => (true, rest),
+            None => (false, line),
+        };
+        let anchored = line.contains('/');
+        let line = line.strip_prefix('/').unwrap_or(line);
+        if line.is_empty() {
+            return None;
+        }
+        Some(Rule {
+            pattern: compile(line),
+            negated,
+            dir_only,
+            anchored,
+        })
+    }
+
+    /// `relative` 为相对忽略文件所在目录、以 `/` 分隔的路径
+    fn matches(&self, relative: &str, is_dir: bool) -> bool {
+        if self.dir_only && !is_dir {
+            return false;
+        }
+        let text = if self.anchored {
+            relative
+        } else {
+            relative.rsplit('/').next().unwrap_or(relative)
+        };
+        let text: Vec<char> = text.chars().collect();
+        glob_match(&self.pattern, &text)
+    }
+}
+
+/// 一个忽略文件中的规则
+#[derive(Debug)]
+struct IgnoreFile {
+    /// 忽略文件所在的目录
+    base: PathBuf,
+    rules: Vec<Rule>,
+}
+
+impl IgnoreFile {
+    fn parse(base: &Path, content: &str) -> IgnoreFile {
+        IgnoreFile {
+            base: base.to_path_buf(),
+            rules: content.lines().filter_map(Rule::parse).collect(),
+        }
+    }
+
+    /// 最后一条匹配的规则决定结果，没有匹配时返回 None
+    fn decide(&self, path: &Path, is_dir: bool) -> Option<bool> {
+        let relative = path
+            .strip_prefix(&self.base)
+            .ok()?
+            .components()
+            .map(|component| component.as_os_str().to_str())
+            .collect::<Option<Vec<&str>>>()?
+            .join("/");
+        self.rules
+            .iter()
+            .rev()
+            .find(|rule| rule.matches(&relative, is_dir))
+            .map(|rule| !rule.negated)
+    }
+}
+
+/// 从扫描根目录到当前目录逐层生效的忽略文件，子目录共享上层已读取的规则
+#[derive(Debug, Clone, Default)]
+pub struct IgnoreStack {
+    files: Vec<Arc<IgnoreFile>>,
+}
+
+impl IgnoreStack {
+    /// 进入目录时读取其中的忽略文件，返回适用于该目录内条目的规则
+    pub fn enter(&self, dir: &Path) -> IgnoreStack {
+        let path = dir.join(IGNORE_FILE_NAME);
+        let content = match std::fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return self.clone(),
+            Err(e) => {
+                log::warn!("无法读取忽略文件: {} - {}", path.display(), e);
+                return self.clone();
+            }
+        };
+        let file = IgnoreFile::parse(dir, &content);
+        if file.rules.is_empty() {
+            return self.clone();
+        }
+        let mut stack = self.clone();
+        stack.files.push(Arc::new(file));
+        stack
+    }
+
+    /// 深层忽略文件中的规则优先
+    pub fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        self.files
+            .iter()
+            .rev()
+            .find_map(|file| file.decide(path, is_dir))
+            .unwrap_or(false)
+    }
+}
+
+/// Windows 上文件名不区分大小写，与排除目录一致按 ASCII 忽略大小写比较
+fn chars_equal(a: char, b: char) -> bool {
+    if cfg!(windows) {
+        a.eq_ignore_ascii_case(&b)
+    } else {
+        a == b
+    }
+}
+
+/// 模式中的一个元素
+#[derive(Debug)]
+enum Token {
+    Char(char),
+    /// `?`
+    Any,
+    /// `[...]`，不含括号
+    Class {
+        items: Vec<char>,
+        negated: bool,
+    },
+    /// `*`，不跨越目录
+    Star,
+    /// `**`，可跨越目录
+    DoubleStar,
+    /// `**/`，匹配零层或多层目录
+    AnyDirs,
+}
+
+impl Token {
+    fn matches(&self, c: char) -> bool {
+        match self {
+            Token::Char(expected) => chars_equal(*expected, c),
+            Token::Any => c != '/',
+            Token::Class { items, negated } => c != '/' && class_contains(items, c) != *negated,
+            Token::Star | Token::DoubleStar | Token::AnyDirs => false,
+        }
+    }
+}
+
+fn compile(pattern: &str) -> Vec<Token> {
+    let chars: Vec<char> = pattern.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let (token, len) = match chars[i..] {
+            ['*', '*', '/', ..] => (Token::AnyDirs, 3),
+            ['*', '*', ..] => (Token::DoubleStar, 2),
+            ['*', ..] => (Token::Star, 1),
+            ['?', ..] => (Token::Any, 1),
+            // 没有闭合的 `[` 按普通字符处理
+            ['[', ..] => parse_class(&chars[i + 1..])
+                .map_or((Token::Char('['), 1), |(token, len)| (token, len + 1)),
+            ['\\', c, ..] => (Token::Char(c), 2),
+            [c, ..] => (Token::Char(c), 1),
+            [] => unreachable!(),
+        };
+        tokens.push(token);
+        i += len;
+    }
+    tokens
+}
+
+/// 解析 `[` 之后的字符类，返回字符类与其长度（含 `]`）；没有闭合时返回 None
+fn parse_class(class: &[char]) -> Option<(Token, usize)> {
+    let (negated, start) = match class.first() {
+        Some('!' | '^') => (true, 1),
+        _ => (false, 0),
+    };
+    // 紧跟在开头的 `]` 是普通字符
+    let end = start
+        + 1
+        + class
+            .get(start + 1..)?
+            .iter()
+            .position(|&item| item == ']')?;
+    let token = Token::Class {
+        items: class[start..end].to_vec(),
+        negated,
+    };
+    Some((token, end + 1))
+}
+
+fn class_contains(items: &[char], c: char) -> bool {
+    let mut i = 0;
+    while i < items.len() {
+        if i + 2 < items.len() && items[i + 1] == '-' {
+            let in_range = |c: char| (items[i]..=items[i + 2]).contains(&c);
+            if in_range(c)
+                || (cfg!(windows)
+                    && (in_range(c.to_ascii_lowercase()) || in_range(c.to_ascii_uppercase())))
+            {
+                return true;
+            }
+            i += 3;
+        } else {
+            if chars_equal(items[i], c) {
+                return true;
+            }
+            i += 1;
+        }
+    }
+    false
+}
+
+/// 从模式末尾向前逐个元素计算「模式的剩余部分能否匹配文本的每个后缀」，
+/// 耗时与模式长度 × 文本长度成正比，多个 `*` 也不会回溯出指数级的组合
+fn glob_match(tokens: &[Token], text: &[char]) -> bool {
+    let n = text.len();
+    // next[t]：其后的元素能否匹配 text[t..]
+    let mut next = vec![false; n + 1];
+    next[n] = true;
+    let mut current = vec![false; n + 1];
+    for token in tokens.iter().rev() {
+        // `**/` 之后的部分能否从某个 `/` 之后开始匹配
+        let mut after_slash = false;
+        for t in (0..=n).rev() {
+            current[t] = match token {
+                Token::Star => next[t] || (t < n && text[t] != '/' && current[t + 1]),
+                Token::DoubleStar => next[t] || (t < n && current[t + 1]),
+                Token::AnyDirs => {
+                    after_slash |= t < n && text[t] == '/' && next[t + 1];
+                    next[t] || after_slash
+                }
+                _ => t < n && token.matches(text[t]) && next[t + 1],
+            };
+        }
+        std::mem::swap(&mut next, &mut current);
+    }
+    next[0]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ignore_rules() {
+        let base = Path::new("/data");
+        let content = "\
+# 注释与空行
+
+*.tmp
+build/
+/docs/*.pdf
+cache/**/index
+!keep.tmp
+data[0-9].bin
+";
+        let stack = IgnoreStack {
+            files: vec![Arc::new(IgnoreFile::parse(base, content))],
+        };
+        let ignored = |path: &str, is_dir| stack.is_ignored(&base.join(path), is_dir);
+
+        // 不含 `/` 的模式匹配任意层的名称
+        assert!(ignored("a.tmp", false));
+        assert!(ignored("sub/deep/b.tmp", false));
+        assert!(!ignored("a.tmp.txt", false));
+        // 靠后的 `!` 规则重新包含
+        assert!(!ignored("sub/keep.tmp", false));
+        // 末尾 `/` 只匹配目录
+        assert!(ignored("src/build", true));
+        assert!(!ignored("src/build", false));
+        // 含 `/` 的模式相对忽略文件所在目录，`*` 不跨越目录
+        assert!(ignored("docs/manual.pdf", false));
+        assert!(!ignored("docs/old/manual.pdf", false));
+        assert!(!ignored("sub/docs/manual.pdf", false));
+        // `**/` 匹配零层或多层目录
+        assert!(ignored("cache/index", false));
+        assert!(ignored("cache/a/b/index", false));
+        assert!(!ignored("cache/a/index2", false));
+        // 字符类
+        assert!(ignored("data7.bin", false));
+        assert!(!ignored("datax.bin", false));
+        // 不在忽略文件所在目录下
+        assert!(!stack.is_ignored(Path::new("/other/a.tmp"), false));
+    }
+
+    #[test]
+    fn test_many_stars_match_in_linear_time() {
+        let matches = |pattern: &str, text: &str| {
+            glob_match(&compile(pattern), &text.chars().collect::<Vec<char>>())
+        };
+        // 逐个回溯时需要尝试约 C(60, 8) 种组合
+        let name = "a".repeat(60);
+        let start = std::time::Instant::now();
+        assert!(!matches("*a*a*a*a*a*a*a*a*b", &name));
+        assert!(!matches("**/*a*a*a*a*a*a*a*a*b", &format!("x/{name}")));
+        assert!(matches("*a*a*a*a*a*a*a*a*", &name));
+        assert!(start.elapsed() < std::time::Duration::from_secs(1));
+
+        // 转义与未闭合的字符类按普通字符匹配
+        assert!(matches("a\\*b", "a*b"));
+        assert!(!matches("a\\*b", "axb"));
+        assert!(matches("[ab", "[ab"));
+        assert!(matches("[!0-9]x", "ax"));
+        assert!(!matches("[!0-9]x", "5x"));
+        assert!(matches("a/**", "a/b/c"));
+        assert!(!matches("a*", "ab/c"));
+    }
+}
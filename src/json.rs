@@ -0,0 +1,158 @@
+// 扁平 JSON 对象的解析
+//
+// 配置方案文件与版本清单都只是一层键值，不引入完整的 JSON 解析库。值为字符串、数字或
+// 布尔，后两者保留原文；嵌套的对象与数组视为无法解析。
+
+use std::iter::Peekable;
+use std::str::Chars;
+
+/// 解析扁平的 JSON 对象，按出现顺序返回各字段
+pub fn parse_flat_object(json: &str) -> Option<Vec<(String, String)>> {
+    let mut chars = json.trim().strip_prefix('{')?.chars().peekable();
+    let mut fields = Vec::new();
+    skip_whitespace(&mut chars);
+    if chars.peek() == Some(&'}') {
+        chars.next();
+    } else {
+        loop {
+            skip_whitespace(&mut chars);
+            let key = parse_string(&mut chars)?;
+            skip_whitespace(&mut chars);
+            if chars.next()? != ':' {
+                return None;
+            }
+            skip_whitespace(&mut chars);
+            let value = if chars.peek() == Some(&'"') {
+                parse_string(&mut chars)?
+            } else {
+                let mut literal = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c == ',' || c == '}' || c.is_whitespace() {
+                        break;
+                    }
+                    literal.push(c);
+                    chars.next();
+                }
+                if literal.is_empty() || literal.starts_with(['{', '[']) {
+                    return None;
+                }
+                literal
+            };
+            fields.push((key, value));
+            skip_whitespace(&mut chars);
+            match chars.next()? {
+                ',' => {}
+                '}' => break,
+                _ => return None,
+            }
+        }
+    }
+    chars.all(char::is_whitespace).then_some(fields)
+}
+
+/// 取出扁平 JSON 对象中的一个字段，重复的键以最后一个为准
+pub fn string_field(json: &str, key: &str) -> Option<String> {
+    parse_flat_object(json)?
+        .into_iter()
+        .rev()
+        .find(|(name, _)| name == key)
+        .map(|(_, value)| value)
+}
+
+fn skip_whitespace(chars: &mut Peekable<Chars<'_>>) {
+    while chars.next_if(|c| c.is_whitespace()).is_some() {}
+}
+
+fn parse_string(chars: &mut Peekable<Chars<'_>>) -> Option<String> {
+    if chars.next()? != '"' {
+        return None;
+    }
+    let mut value = String::new();
+    loop {
+        match chars.next()? {
+            '"' => return Some(value),
+            '\\' => value.push(match chars.next()? {
+                'n' => '\n',
+                'r' => '\r',
+                't' => '\t',
+                'b' => '\u{8}',
+                'f' => '\u{c}',
+                'u' => parse_unicode_escape(chars)?,
+                // `\"`、`\\`、`\/`
+                c => c,
+            }),
+            c => value.push(c),
+        }
+    }
+}
+
+/// `\u` 之后的四位十六进制；基本平面以外的字符由一对代理项表示，如 `\ud83d\ude00`
+fn parse_unicode_escape(chars: &mut Peekable<Chars<'_>>) -> Option<char> {
+    let high = parse_hex4(chars)?;
+    if !(0xD800..0xDC00).contains(&high) {
+        // 单独出现的低代理项不是有效字符，`from_u32` 返回 None
+        return char::from_u32(high);
+    }
+    if chars.next()? != '\\' || chars.next()? != 'u' {
+        return None;
+    }
+    let low = parse_hex4(chars)?;
+    if !(0xDC00..0xE000).contains(&low) {
+        return None;
+    }
+    char::from_u32(0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00))
+}
+
+fn parse_hex4(chars: &mut Peekable<Chars<'_>>) -> Option<u32> {
+    let hex: String = chars.by_ref().take(4).collect();
+    if hex.len() != 4 {
+        return None;
+    }
+    u32::from_str_radix(&hex, 16).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_flat_object() {
+        let fields = parse_flat_object(
+            r#" { "a": "x\"y\\z\/", "n": 4096, "b": true, "e": "\u00e9\u4E2D\ud83d\ude00" } "#,
+        )
+        .unwrap();
+        let expected = [
+            ("a", "x\"y\\z/"),
+            ("n", "4096"),
+            ("b", "true"),
+            ("e", "é中😀"),
+        ];
+        assert_eq!(fields.len(), expected.len());
+        for ((key, value), (expected_key, expected_value)) in fields.iter().zip(expected) {
+            assert_eq!(
+                (key.as_str(), value.as_str()),
+                (expected_key, expected_value)
+            );
+        }
+        assert_eq!(parse_flat_object("{}"), Some(Vec::new()));
+        assert_eq!(
+            string_field(r#"{"v":"1","v":"2"}"#, "v").as_deref(),
+            Some("2")
+        );
+        assert_eq!(string_field(r#"{"v":"1"}"#, "url"), None);
+
+        // 不完整的代理项对、嵌套结构与多余内容
+        for json in [
+            r#"{"e": "\ud83d"}"#,
+            r#"{"e": "\ud83dA"}"#,
+            r#"{"e": "\ude00"}"#,
+            r#"{"e": "\u12"}"#,
+            r#"{"a": {"b": "c"}}"#,
+            r#"{"a": [1]}"#,
+            r#"{"a": "b"} x"#,
+            r#"{"a" "b"}"#,
+        ] {
+            assert_eq!(parse_flat_object(json), None, "{json}");
+        }
+    }
+}
@@ -0,0 +1,179 @@
+// 日志模块：同时输出到 stderr 与可执行文件旁的滚动日志文件
+//
+// 窗口版（windows_subsystem = "windows"）没有控制台，stderr 不可见，现场排查只能依赖日志文件。
+
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+use log::{LevelFilter, Log, Metadata, Record};
+
+use crate::i18n::{Text, t};
+
+/// 日志文件名，与 `hash_cache.db` 位于同一目录
+const LOG_FILE_NAME: &str = "turbohash.log";
+/// 单个日志文件的大小上限，超过后滚动
+const MAX_LOG_SIZE: u64 = 5 * 1024 * 1024;
+/// 保留的历史日志数量（turbohash.log.1 ~ turbohash.log.3）
+const MAX_ROTATED_FILES: usize = 3;
+
+static LOGGER: OnceLock<FileLogger> = OnceLock::new();
+
+/// 安装全局日志器；重复调用只调整级别
+pub fn init(log_dir: &Path, level: LevelFilter) {
+    let logger = LOGGER.get_or_init(|| FileLogger::open(log_dir.join(LOG_FILE_NAME), MAX_LOG_SIZE));
+    let _ = log::set_logger(logger);
+    log::set_max_level(level);
+}
+
+/// 运行时调整日志级别
+pub fn set_level(level: LevelFilter) {
+    log::set_max_level(level);
+}
+
+/// 当前日志文件路径，未初始化时为 None
+pub fn log_file() -> Option<&'static Path> {
+    LOGGER.get().map(|logger| logger.path.as_path())
+}
+
+/// 设置界面中可选的日志级别
+pub const LEVELS: [(LevelFilter, Text); 4] = [
+    (LevelFilter::Error, Text::LogError),
+    (LevelFilter::Warn, Text::LogWarn),
+    (LevelFilter::Info, Text::LogInfo),
+    (LevelFilter::Debug, Text::LogDebug),
+];
+
+pub fn level_label(level: LevelFilter) -> &'static str {
+    t(LEVELS
+        .iter()
+        .find(|(l, _)| *l == level)
+        .map_or(Text::LogOff, |&(_, label)| label))
+}
+
+struct FileLogger {
+    path: PathBuf,
+    max_size: u64,
+    // (文件, 当前大小)；无法打开日志文件时只输出到 stderr
+    file: Mutex<Option<(File, u64)>>,
+}
+
+impl FileLogger {
+    fn open(path: PathBuf, max_size: u64) -> Self {
+        let file = open_append(&path);
+        Self {
+            path,
+            max_size,
+            file: Mutex::new(file),
+        }
+    }
+
+    fn write_line(&self, line: &str) {
+        let Ok(mut guard) = self.file.lock() else {
+            return;
+        };
+        if let Some((_, size)) = guard.as_ref() {
+            if *size + line.len() as u64 > self.max_size {
+                // 先关闭再改名，Windows 上不能重命名已打开的文件
+                *guard = None;
+                rotate(&self.path, MAX_ROTATED_FILES);
+                *guard = open_append(&self.path);
+            }
+        }
+        if let Some((file, size)) = guard.as_mut() {
+            if file.write_all(line.as_bytes()).is_ok() {
+                *size += line.len() as u64;
+            }
+        }
+    }
+}
+
+impl Log for FileLogger {
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    // 级别过滤已由 log 宏按 max_level 完成
+    fn log(&self, record: &Record<'_>) {
+        let line = format!(
+            "{} {:<5} {}: {}\n",
+            chrono::Local::now().format("%Y-%m-%d %H:%M:%S%.3f"),
+            record.level(),
+            short_target(record.target()),
+            record.args()
+        );
+        eprint!("{line}");
+        self.write_line(&line);
+    }
+
+    fn flush(&self) {
+        if let Ok(mut guard) = self.file.lock() {
+            if let Some((file, _)) = guard.as_mut() {
+                let _ = file.flush();
+            }
+        }
+    }
+}
+
+fn open_append(path: &Path) -> Option<(File, u64)> {
+    let file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .ok()?;
+    let size = file.metadata().map_or(0, |m| m.len());
+    Some((file, size))
+}
+
+/// `TurboHash::cache` → `cache`
+fn short_target(target: &str) -> &str {
+    target.rsplit("::").next().unwrap_or(target)
+}
+
+/// 滚动日志：`log.2` → `log.3`，`log.1` → `log.2`，`log` → `log.1`，最旧的一份被删除
+fn rotate(path: &Path, keep: usize) {
+    let numbered = |n: usize| {
+        let mut name = path.as_os_str().to_owned();
+        name.push(format!(".{n}"));
+        PathBuf::from(name)
+    };
+    let _ = fs::remove_file(numbered(keep));
+    for n in (1..keep).rev() {
+        let _ = fs::rename(numbered(n), numbered(n + 1));
+    }
+    let _ = fs::rename(path, numbered(1));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use log::Level;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_log_file_rotates_when_full() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join(LOG_FILE_NAME);
+        let logger = FileLogger::open(path.clone(), 200);
+
+        for i in 0..20 {
+            logger.log(
+                &Record::builder()
+                    .args(format_args!("第 {} 条日志", i))
+                    .level(Level::Warn)
+                    .target("TurboHash::cache")
+                    .build(),
+            );
+        }
+        logger.flush();
+
+        let current = fs::read_to_string(&path).unwrap();
+        assert!(current.len() <= 200);
+        assert!(current.contains("WARN  cache: 第 19 条日志"));
+        // 只保留固定数量的历史文件
+        assert!(temp.path().join("turbohash.log.1").exists());
+        assert!(temp.path().join("turbohash.log.3").exists());
+        assert!(!temp.path().join("turbohash.log.4").exists());
+    }
+}
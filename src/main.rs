@@ -1,34 +1,61 @@
 #![cfg_attr(windows, windows_subsystem = "windows")]
 #![warn(clippy::all, clippy::pedantic)]
 
+mod analysis;
+mod benchmark;
 mod cache;
+mod cli;
+mod columns;
 mod engine;
 mod error;
+mod export;
+mod folders;
 mod font;
 mod hash;
+mod hash_timing;
+mod i18n;
+mod ignore;
+mod json;
+mod logging;
+mod notification;
+mod power;
+mod profiles;
 mod progress;
+mod remote;
+mod rename;
 mod scanner; // 新增模块
+mod sparse;
+#[cfg(windows)]
+mod streams;
+mod timeline;
+mod tray;
 mod ui;
+mod update;
 mod utils;
+mod verify;
 mod worker;
+mod xattrs;
 
 use eframe::egui;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 fn main() -> eframe::Result<()> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    // `--stdin`：计算标准输入的哈希后直接退出，不启动界面
+    if cli::wants_stdin(&args) {
+        std::process::exit(cli::run_stdin(&args));
+    }
+
+    // 日志写在可执行文件旁（与 hash_cache.db 同目录），级别在加载配置后调整
+    let exe_dir = std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(Path::to_path_buf))
+        .unwrap_or_else(|| PathBuf::from("."));
+    logging::init(&exe_dir, log::LevelFilter::Info);
+
     // 解析命令行参数，仅检查存在性，不展开文件夹
-    let initial_paths: Vec<PathBuf> = std::env::args()
-        .skip(1)
-        .filter_map(|arg| {
-            let path = PathBuf::from(&arg);
-            if path.exists() {
-                Some(path)
-            } else {
-                eprintln!("警告: 路径不存在，跳过: {arg}");
-                None
-            }
-        })
-        .collect();
+    let initial_paths: Vec<PathBuf> = args.into_iter().flat_map(expand_arg).collect();
 
     let options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default()
@@ -38,7 +65,7 @@ fn main() -> eframe::Result<()> {
         ..Default::default()
     };
 
-    eframe::run_native(
+    let result = eframe::run_native(
         "TurboHash",
         options,
         Box::new(|cc| {
@@ -46,5 +73,34 @@ fn main() -> eframe::Result<()> {
             // 直接传递路径，UI 初始化后会调用 Scanner 异步扫描
             Ok(Box::new(ui::TurboHashApp::new(cc, initial_paths)?))
         }),
-    )
-}
\ No newline at end of file
+    );
+    // 窗口已关闭，等待退出时的数据库整理完成
+    worker::WorkerThread::wait_for_exit_vacuum();
+    result
+}
+
+/// 展开单个命令行参数：`@list.txt` 读取响应文件中的路径（每行一个），其余按路径处理
+fn expand_arg(arg: String) -> Vec<PathBuf> {
+    if let Some(list_file) = arg.strip_prefix('@') {
+        return match utils::read_path_list_file(Path::new(list_file)) {
+            Ok((paths, skipped)) => {
+                if skipped > 0 {
+                    log::warn!("响应文件 {list_file} 中有 {skipped} 个路径不存在，已跳过");
+                }
+                paths
+            }
+            Err(e) => {
+                log::warn!("无法读取响应文件 {list_file}: {e}");
+                Vec::new()
+            }
+        };
+    }
+
+    let path = PathBuf::from(&arg);
+    if path.exists() {
+        vec![path]
+    } else {
+        log::warn!("路径不存在，跳过: {arg}");
+        Vec::new()
+    }
+}
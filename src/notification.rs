@@ -0,0 +1,17 @@
+// 系统通知模块
+
+use std::thread;
+
+/// 在后台线程发送桌面通知，避免 D-Bus 等调用阻塞界面
+pub fn send_notification(body: String) {
+    thread::spawn(move || {
+        if let Err(e) = notify_rust::Notification::new()
+            .summary("TurboHash")
+            .body(&body)
+            .appname("TurboHash")
+            .show()
+        {
+            log::warn!("发送系统通知失败: {}", e);
+        }
+    });
+}
@@ -0,0 +1,132 @@
+// 电源管理：计算期间阻止系统休眠
+//
+// Windows 使用 SetThreadExecutionState；macOS 与 Linux 没有可直接调用的稳定 API，
+// 分别借助系统自带的 caffeinate 与 systemd-inhibit 子进程持有休眠锁。
+
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+use std::process::{Child, Command, Stdio};
+
+/// 持有期间阻止系统自动休眠，离开作用域时释放
+///
+/// Windows 的执行状态按线程记录，必须在同一线程（UI 线程）中创建和释放。
+pub struct SleepInhibitor {
+    #[cfg(any(target_os = "macos", target_os = "linux"))]
+    child: Child,
+}
+
+impl SleepInhibitor {
+    /// 申请休眠锁；平台不支持或调用失败时返回 None，只记录日志
+    pub fn acquire() -> Option<Self> {
+        let inhibitor = Self::acquire_platform();
+        if inhibitor.is_some() {
+            log::debug!("已阻止系统休眠");
+        }
+        inhibitor
+    }
+
+    #[cfg(windows)]
+    fn acquire_platform() -> Option<Self> {
+        use windows_sys::Win32::System::Power::{
+            ES_CONTINUOUS, ES_SYSTEM_REQUIRED, SetThreadExecutionState,
+        };
+
+        // SAFETY: 仅设置当前线程的执行状态标志
+        let previous = unsafe { SetThreadExecutionState(ES_CONTINUOUS | ES_SYSTEM_REQUIRED) };
+        if previous == 0 {
+            log::warn!("阻止系统休眠失败: SetThreadExecutionState 返回 0");
+            return None;
+        }
+        Some(Self {})
+    }
+
+    #[cfg(target_os = "macos")]
+    fn acquire_platform() -> Option<Self> {
+        // -w：TurboHash 异常退出时 caffeinate 随之结束，不会残留休眠锁
+        let child = Command::new("caffeinate")
+            .args(["-i", "-w", &std::process::id().to_string()])
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn();
+        Self::from_child(child, "caffeinate")
+    }
+
+    #[cfg(target_os = "linux")]
+    fn acquire_platform() -> Option<Self> {
+        // 子命令 cat 读取管道，TurboHash 释放锁或异常退出时管道关闭，cat 与 systemd-inhibit 随之退出
+        let child = Command::new("systemd-inhibit")
+            .args([
+                "--what=sleep:idle",
+                "--who=TurboHash",
+                "--why=正在计算文件哈希",
+                "--mode=block",
+                "cat",
+            ])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn();
+        Self::from_child(child, "systemd-inhibit")
+    }
+
+    #[cfg(any(target_os = "macos", target_os = "linux"))]
+    fn from_child(child: std::io::Result<Child>, program: &str) -> Option<Self> {
+        match child {
+            Ok(child) => Some(Self { child }),
+            Err(e) => {
+                log::warn!("阻止系统休眠失败: 无法启动 {} ({})", program, e);
+                None
+            }
+        }
+    }
+
+    #[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
+    fn acquire_platform() -> Option<Self> {
+        None
+    }
+}
+
+impl Drop for SleepInhibitor {
+    #[cfg(windows)]
+    fn drop(&mut self) {
+        use windows_sys::Win32::System::Power::{ES_CONTINUOUS, SetThreadExecutionState};
+
+        // SAFETY: 清除当前线程的执行状态标志，恢复默认休眠行为
+        unsafe {
+            SetThreadExecutionState(ES_CONTINUOUS);
+        }
+        log::debug!("已恢复系统休眠");
+    }
+
+    #[cfg(any(target_os = "macos", target_os = "linux"))]
+    fn drop(&mut self) {
+        // 先关闭管道让子进程自行退出，再确保结束并回收
+        drop(self.child.stdin.take());
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+        log::debug!("已恢复系统休眠");
+    }
+
+    #[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
+    fn drop(&mut self) {}
+}
+
+#[cfg(all(test, windows))]
+mod tests {
+    use super::*;
+    use windows_sys::Win32::System::Power::{
+        ES_CONTINUOUS, ES_SYSTEM_REQUIRED, SetThreadExecutionState,
+    };
+
+    #[test]
+    fn test_inhibitor_sets_and_clears_execution_state() {
+        let inhibitor = SleepInhibitor::acquire().expect("SetThreadExecutionState 调用失败");
+        // 重新设置相同的标志不改变状态，返回值即为当前线程原有的执行状态
+        let state = unsafe { SetThreadExecutionState(ES_CONTINUOUS | ES_SYSTEM_REQUIRED) };
+        assert_ne!(state & ES_SYSTEM_REQUIRED, 0);
+
+        drop(inhibitor);
+        let state = unsafe { SetThreadExecutionState(ES_CONTINUOUS) };
+        assert_eq!(state & ES_SYSTEM_REQUIRED, 0);
+    }
+}
@@ -0,0 +1,147 @@
+// 配置方案
+//
+// 把完整的配置（含启用的哈希算法）保存为命名的方案，在工具栏中一键切换，例如只算 XXH3
+// 的「快速去重」与启用全部加密哈希的「完整归档」。方案以扁平的 JSON 对象保存，键与
+// `settings` 表相同、值均为字符串；导出的文件另用 `profile` 字段记录方案名，便于在团队
+// 中分享。缺少或无法解析的项使用默认值，其他版本导出的方案也能导入。
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::cache::{CacheConfig, config_from_settings, config_settings};
+use crate::error::{HashError, HashResult, IoErrorContext};
+use crate::export::json_string;
+use crate::json::parse_flat_object;
+
+/// 导出文件中记录方案名的字段
+const NAME_KEY: &str = "profile";
+
+/// 解析出的配置方案
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Profile {
+    /// 导出文件中记录的方案名，数据库中保存的方案没有
+    pub name: Option<String>,
+    pub config: CacheConfig,
+}
+
+/// 转为 JSON，每项一行便于比较与手动修改
+pub fn to_json(name: Option<&str>, config: &CacheConfig) -> String {
+    let fields: Vec<String> = name
+        .map(|name| (NAME_KEY, name.to_string()))
+        .into_iter()
+        .chain(config_settings(config))
+        .map(|(key, value)| format!("  {}: {}", json_string(key), json_string(&value)))
+        .collect();
+    format!("{{\n{}\n}}\n", fields.join(",\n"))
+}
+
+/// 解析配置方案，不是 JSON 对象或不含任何配置项时返回 None
+pub fn from_json(json: &str) -> Option<Profile> {
+    let mut settings: HashMap<String, String> = parse_flat_object(json)?.into_iter().collect();
+    let name = settings
+        .remove(NAME_KEY)
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty());
+    let known = config_settings(&CacheConfig::default())
+        .iter()
+        .any(|(key, _)| settings.contains_key(*key));
+    known.then(|| Profile {
+        name,
+        config: config_from_settings(&settings),
+    })
+}
+
+/// 导出配置方案文件
+pub fn export_profile(path: &Path, name: &str, config: &CacheConfig) -> HashResult<()> {
+    std::fs::write(path, to_json(Some(name), config)).with_path(path)
+}
+
+/// 导入配置方案文件，文件中没有方案名时使用文件名
+pub fn import_profile(path: &Path) -> HashResult<(String, CacheConfig)> {
+    let json = std::fs::read_to_string(path).with_path(path)?;
+    let profile = from_json(&json).ok_or_else(|| {
+        HashError::Io(
+            std::io::Error::new(std::io::ErrorKind::InvalidData, "不是有效的配置方案文件"),
+            path.to_path_buf(),
+        )
+    })?;
+    let name = profile.name.unwrap_or_else(|| {
+        path.file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    });
+    Ok((name, profile.config))
+}
+
+/// 导入时避免覆盖同名方案：名称已存在时依次尝试「名称 (2)」「名称 (3)」…
+pub fn unused_name(name: &str, existing: &[String]) -> String {
+    let taken = |candidate: &str| existing.iter().any(|name| name == candidate);
+    if !taken(name) {
+        return name.to_string();
+    }
+    // 已有 n 个方案时，n + 1 个候选中必有一个未被占用
+    (2..=existing.len() + 2)
+        .map(|n| format!("{} ({})", name, n))
+        .find(|candidate| !taken(candidate))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::{CacheValidation, ComputeOrder};
+
+    #[test]
+    fn test_profile_file_roundtrip() {
+        let mut config = CacheConfig {
+            compute_order: ComputeOrder::LargestFirst,
+            cache_validation: CacheValidation::Paranoid,
+            excluded_dirs: vec!["node_modules".to_string(), "a\"b\\c".to_string()],
+            ..CacheConfig::default()
+        };
+        config.enabled_hashes.sha256 = true;
+        config.enabled_hashes.md5 = false;
+
+        let temp = tempfile::TempDir::new().unwrap();
+        let path = temp.path().join("archive.json");
+        export_profile(&path, "完整 \"归档\"", &config).unwrap();
+        let (name, imported) = import_profile(&path).unwrap();
+        assert_eq!(name, "完整 \"归档\"");
+        assert_eq!(imported, config);
+
+        // 手写的方案：没有方案名时使用文件名，数字与布尔可以不加引号，缺少的项使用默认值
+        let path = temp.path().join("快速去重.json");
+        std::fs::write(
+            &path,
+            "{ \"xxh3_only_mode\": true, \"min_file_size\": 4096, \"unknown\": \"x\" }",
+        )
+        .unwrap();
+        let (name, imported) = import_profile(&path).unwrap();
+        assert_eq!(name, "快速去重");
+        assert!(imported.xxh3_only_mode);
+        assert_eq!(imported.min_file_size, 4096);
+        assert_eq!(
+            imported.enabled_hashes,
+            CacheConfig::default().enabled_hashes
+        );
+
+        // 不是配置方案的文件
+        for json in [
+            "",
+            "[]",
+            "{}",
+            "{\"name\": \"x\"}",
+            "{\"min_file_size\": \"1\"",
+        ] {
+            assert_eq!(from_json(json), None, "{json}");
+        }
+    }
+
+    #[test]
+    fn test_unused_name() {
+        let existing = ["归档".to_string(), "归档 (2)".to_string()];
+        assert_eq!(unused_name("去重", &existing), "去重");
+        assert_eq!(unused_name("归档", &existing), "归档 (3)");
+        assert_eq!(unused_name("归档 (2)", &existing), "归档 (2) (2)");
+    }
+}
@@ -24,6 +24,8 @@ pub struct FileProgress {
 /// 1. **实时进度累加**：将处理中文件的已处理字节计入全局进度
 /// 2. **线程安全**：使用AtomicU64和RwLock确保并发安全
 /// 3. **自动清理**：文件完成时自动从处理中列表移除
+/// 4. **成本模型**：可为每个文件额外计入固定开销，使大量小文件时进度更贴近实际耗时
+/// 5. **文件数模式**：可改为按已完成文件数 / 总文件数计算，不考虑文件大小
 ///
 /// # 使用示例
 ///
@@ -50,29 +52,69 @@ pub struct ProgressTracker {
     total_bytes: Arc<AtomicU64>,
     // 处理中的文件进度（读写锁，支持高并发读）
     in_progress: Arc<RwLock<HashMap<PathBuf, FileProgress>>>,
+    // 批次中的文件数（成本模型与文件数模式使用）
+    total_files: Arc<AtomicU64>,
+    // 已完成的文件数
+    completed_files: Arc<AtomicU64>,
+    // 每个文件的固定开销（等效字节数），0 表示纯字节模型
+    per_file_cost: u64,
+    // 按文件数而非字节数计算全局进度
+    count_based: bool,
 }
 
 impl ProgressTracker {
-    /// 创建新的进度跟踪器
+    /// 创建新的进度跟踪器（纯字节模型）
     pub fn new() -> Self {
+        Self::with_file_cost(0)
+    }
+
+    /// 创建带成本模型的进度跟踪器
+    ///
+    /// 每个文件按 `文件大小 + per_file_cost` 计入进度，用来体现打开、
+    /// 读取元数据、查询缓存等与大小无关的开销。
+    pub fn with_file_cost(per_file_cost: u64) -> Self {
         Self {
             processed_bytes: Arc::new(AtomicU64::new(0)),
             total_bytes: Arc::new(AtomicU64::new(0)),
             in_progress: Arc::new(RwLock::new(HashMap::new())),
+            total_files: Arc::new(AtomicU64::new(0)),
+            completed_files: Arc::new(AtomicU64::new(0)),
+            per_file_cost,
+            count_based: false,
+        }
+    }
+
+    /// 创建按文件数计算的进度跟踪器
+    ///
+    /// 全局进度为 已完成文件数 / 总文件数，处理中文件的部分进度不计入。
+    pub fn by_file_count() -> Self {
+        Self {
+            count_based: true,
+            ..Self::with_file_cost(0)
         }
     }
 
+    pub fn is_count_based(&self) -> bool {
+        self.count_based
+    }
 
     pub fn set_total(&self, total: u64) {
         self.total_bytes.store(total, Ordering::Relaxed);
     }
 
+    pub fn set_total_files(&self, count: u64) {
+        self.total_files.store(count, Ordering::Relaxed);
+    }
+
     pub fn start_file(&self, path: PathBuf, total: u64) {
         if let Ok(mut guard) = self.in_progress.write() {
-            guard.insert(path, FileProgress {
-                processed: 0,
-                total,
-            });
+            guard.insert(
+                path,
+                FileProgress {
+                    processed: 0,
+                    total,
+                },
+            );
         }
         // 如果锁被毒化，忽略错误（此时应用程序可能已经处于不可恢复状态）
     }
@@ -85,18 +127,46 @@ impl ProgressTracker {
         }
     }
 
-
     pub fn complete_file(&self, path: &Path) {
         if let Ok(mut guard) = self.in_progress.write() {
             if let Some(progress) = guard.remove(path) {
-                // 将文件的总字节数计入已完成字节
-                self.processed_bytes.fetch_add(progress.total, Ordering::Relaxed);
+                // 将文件的总字节数（及固定开销）计入已完成字节
+                self.processed_bytes
+                    .fetch_add(progress.total + self.per_file_cost, Ordering::Relaxed);
+                self.completed_files.fetch_add(1, Ordering::Relaxed);
             }
         }
     }
 
+    /// 将文件移出本批次（如计算前已被删除），使全局进度仍能到达 100%
+    pub fn remove_file(&self, path: &Path, size: u64) {
+        if let Ok(mut guard) = self.in_progress.write() {
+            guard.remove(path);
+        }
+        let _ = self
+            .total_bytes
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |total| {
+                Some(total.saturating_sub(size))
+            });
+        let _ = self
+            .total_files
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |count| {
+                Some(count.saturating_sub(1))
+            });
+    }
+
     pub fn get_global_progress(&self) -> f64 {
-        let total = self.total_bytes.load(Ordering::Relaxed);
+        if self.count_based {
+            let total = self.total_files.load(Ordering::Relaxed);
+            if total == 0 {
+                return 0.0;
+            }
+            let completed = self.completed_files.load(Ordering::Relaxed);
+            return (completed as f64 / total as f64).min(1.0);
+        }
+
+        let total = self.total_bytes.load(Ordering::Relaxed)
+            + self.total_files.load(Ordering::Relaxed) * self.per_file_cost;
         if total == 0 {
             return 0.0;
         }
@@ -111,12 +181,22 @@ impl ProgressTracker {
             .unwrap_or(0);
 
         let total_processed = processed + in_progress_bytes;
-        total_processed as f64 / total as f64
+        (total_processed as f64 / total as f64).min(1.0)
+    }
+
+    pub fn completed_files(&self) -> u64 {
+        self.completed_files.load(Ordering::Relaxed)
+    }
+
+    pub fn total_files(&self) -> u64 {
+        self.total_files.load(Ordering::Relaxed)
     }
 
     pub fn reset(&self) {
         self.processed_bytes.store(0, Ordering::Relaxed);
         self.total_bytes.store(0, Ordering::Relaxed);
+        self.total_files.store(0, Ordering::Relaxed);
+        self.completed_files.store(0, Ordering::Relaxed);
         if let Ok(mut guard) = self.in_progress.write() {
             guard.clear();
         }
@@ -137,7 +217,10 @@ impl ProgressTracker {
     /// 获取处理中的文件数量（主要用于测试）
     #[cfg(test)]
     pub fn get_in_progress_count(&self) -> usize {
-        self.in_progress.read().map(|guard| guard.len()).unwrap_or(0)
+        self.in_progress
+            .read()
+            .map(|guard| guard.len())
+            .unwrap_or(0)
     }
 }
 
@@ -203,6 +286,93 @@ mod tests {
         assert_eq!(tracker.get_in_progress_count(), 0);
     }
 
+    /// 模拟 1 个 100MB 大文件 + 1000 个 1KB 小文件，先处理小文件
+    fn tiny_heavy_curve(tracker: &ProgressTracker) -> Vec<f64> {
+        let tiny: Vec<PathBuf> = (0..1000)
+            .map(|i| PathBuf::from(format!("/test/tiny{}.txt", i)))
+            .collect();
+        let big = PathBuf::from("/test/big.iso");
+        tracker.set_total(100 * 1024 * 1024 + 1000 * 1024);
+        tracker.set_total_files(1001);
+
+        let mut curve = Vec::new();
+        for chunk in tiny.chunks(250) {
+            for path in chunk {
+                tracker.start_file(path.clone(), 1024);
+                tracker.complete_file(path);
+            }
+            curve.push(tracker.get_global_progress());
+        }
+        tracker.start_file(big.clone(), 100 * 1024 * 1024);
+        tracker.complete_file(&big);
+        curve.push(tracker.get_global_progress());
+        curve
+    }
+
+    #[test]
+    fn test_file_cost_model_curve() {
+        let bytes = tiny_heavy_curve(&ProgressTracker::new());
+        let weighted = tiny_heavy_curve(&ProgressTracker::with_file_cost(256 * 1024));
+
+        // 纯字节模型下 1000 个小文件几乎不推动进度条
+        assert!(bytes[3] < 0.01);
+        // 成本模型下小文件占据了相当比例
+        assert!(weighted[3] > 0.5);
+        // 两种模型都单调递增并在结束时到达 100%
+        for curve in [&bytes, &weighted] {
+            assert!(curve.windows(2).all(|w| w[0] <= w[1]));
+            assert_eq!(*curve.last().unwrap(), 1.0);
+        }
+    }
+
+    #[test]
+    fn test_count_based_progress() {
+        let tracker = ProgressTracker::by_file_count();
+        let big = PathBuf::from("/test/big.iso");
+        let small = PathBuf::from("/test/small.txt");
+        tracker.set_total(1000 + 10);
+        tracker.set_total_files(4);
+
+        // 处理中的部分进度不计入
+        tracker.start_file(big.clone(), 1000);
+        tracker.update_progress(&big, 900);
+        assert_eq!(tracker.get_global_progress(), 0.0);
+
+        // 小文件与大文件同等计数
+        tracker.start_file(small.clone(), 10);
+        tracker.complete_file(&small);
+        assert_eq!(tracker.get_global_progress(), 0.25);
+        tracker.complete_file(&big);
+        assert_eq!(tracker.get_global_progress(), 0.5);
+        assert_eq!((tracker.completed_files(), tracker.total_files()), (2, 4));
+
+        // 重复完成同一文件不会重复计数
+        tracker.complete_file(&big);
+        assert_eq!(tracker.completed_files(), 2);
+
+        tracker.reset();
+        assert_eq!(tracker.get_global_progress(), 0.0);
+        assert_eq!(tracker.completed_files(), 0);
+    }
+
+    #[test]
+    fn test_removed_file_leaves_progress_reachable() {
+        let tracker = ProgressTracker::with_file_cost(100);
+        let kept = PathBuf::from("/test/kept.bin");
+        let gone = PathBuf::from("/test/gone.bin");
+        tracker.set_total(1000 + 500);
+        tracker.set_total_files(2);
+
+        tracker.start_file(gone.clone(), 500);
+        tracker.remove_file(&gone, 500);
+        assert_eq!(tracker.get_in_progress_count(), 0);
+        assert_eq!(tracker.get_total_bytes(), 1000);
+
+        tracker.start_file(kept.clone(), 1000);
+        tracker.complete_file(&kept);
+        assert_eq!(tracker.get_global_progress(), 1.0);
+    }
+
     #[test]
     fn test_progress_tracker_zero_total() {
         let tracker = ProgressTracker::new();
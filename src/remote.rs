@@ -0,0 +1,140 @@
+// 从 URL 获取校验清单（可选特性 remote-verify）与版本清单（可选特性 update-check）
+//
+// 默认构建不包含任何网络代码；启用特性后在工作线程中通过 ureq 阻塞下载，
+// 支持 HTTPS（rustls）与重定向，发布页的下载链接通常会先重定向到 CDN。
+
+#[cfg(any(feature = "remote-verify", feature = "update-check"))]
+mod imp {
+    use std::time::Duration;
+
+    use crate::cache::SizeUnit;
+    use crate::error::{HashError, HashResult};
+    use crate::utils::format_size;
+
+    /// 连接、重定向与读取的总超时
+    const FETCH_TIMEOUT: Duration = Duration::from_secs(30);
+    const MAX_REDIRECTS: u32 = 5;
+    /// 校验清单的大小上限，避免误填大文件链接时读入整个文件
+    const MAX_BODY_BYTES: u64 = 4 * 1024 * 1024;
+
+    /// 只启用 update-check 时也会编译下载代码，但不提供从 URL 校验
+    pub const SUPPORTED: bool = cfg!(feature = "remote-verify");
+
+    /// 下载 `url` 的文本内容，`unit` 用于错误信息中的大小
+    pub fn fetch_text(url: &str, unit: SizeUnit) -> HashResult<String> {
+        let url = url.trim();
+        if !(url.starts_with("http://") || url.starts_with("https://")) {
+            return Err(HashError::Network(
+                "仅支持 http:// 或 https:// 链接".to_string(),
+            ));
+        }
+
+        let agent: ureq::Agent = ureq::Agent::config_builder()
+            .timeout_global(Some(FETCH_TIMEOUT))
+            .max_redirects(MAX_REDIRECTS)
+            .user_agent(concat!("TurboHash/", env!("CARGO_PKG_VERSION")))
+            .build()
+            .into();
+
+        let mut response = agent.get(url).call().map_err(|e| network_error(e, unit))?;
+        response
+            .body_mut()
+            .with_config()
+            .limit(MAX_BODY_BYTES)
+            .read_to_string()
+            .map_err(|e| network_error(e, unit))
+    }
+
+    fn network_error(e: ureq::Error, unit: SizeUnit) -> HashError {
+        HashError::Network(match e {
+            ureq::Error::StatusCode(code) => format!("服务器返回 HTTP {}", code),
+            ureq::Error::Timeout(_) => format!("请求超时（{} 秒）", FETCH_TIMEOUT.as_secs()),
+            ureq::Error::TooManyRedirects => "重定向次数过多".to_string(),
+            ureq::Error::BodyExceedsLimit(_) => format!(
+                "内容超过 {}，不像是校验清单",
+                format_size(MAX_BODY_BYTES, unit)
+            ),
+            e => e.to_string(),
+        })
+    }
+}
+
+#[cfg(not(any(feature = "remote-verify", feature = "update-check")))]
+mod imp {
+    use crate::cache::SizeUnit;
+    use crate::error::{HashError, HashResult};
+
+    pub const SUPPORTED: bool = false;
+
+    /// 未启用联网特性时的占位实现
+    pub fn fetch_text(_url: &str, _unit: SizeUnit) -> HashResult<String> {
+        Err(HashError::SystemResource(
+            "当前构建不支持网络访问".to_string(),
+        ))
+    }
+}
+
+pub use imp::{SUPPORTED, fetch_text};
+
+#[cfg(all(test, any(feature = "remote-verify", feature = "update-check")))]
+mod tests {
+    use super::*;
+    use crate::cache::SizeUnit;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::thread;
+
+    /// 依次应答 `responses.len()` 个连接的本地 HTTP 服务，返回根地址
+    fn mock_server(responses: Vec<String>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        thread::spawn(move || {
+            for response in responses {
+                let (stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream);
+                let mut line = String::new();
+                // 读完请求头
+                while reader.read_line(&mut line).unwrap() > 0 && line != "\r\n" {
+                    line.clear();
+                }
+                let mut stream = reader.into_inner();
+                stream.write_all(response.as_bytes()).unwrap();
+            }
+        });
+        format!("http://{}", addr)
+    }
+
+    fn response(status: &str, headers: &str, body: &str) -> String {
+        format!(
+            "HTTP/1.1 {}\r\n{}Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+            status,
+            headers,
+            body.len(),
+            body
+        )
+    }
+
+    #[test]
+    fn test_fetch_follows_redirect() {
+        let body = format!("{}  app.tar.gz\n", "ab".repeat(32));
+        let base = mock_server(vec![
+            response("302 Found", "Location: /SHA256SUMS\r\n", ""),
+            response("200 OK", "", &body),
+        ]);
+
+        assert_eq!(
+            fetch_text(&format!("{}/latest", base), SizeUnit::Binary).unwrap(),
+            body
+        );
+    }
+
+    #[test]
+    fn test_fetch_reports_http_errors() {
+        let base = mock_server(vec![response("404 Not Found", "", "")]);
+        let err = fetch_text(&format!("{}/SHA256SUMS", base), SizeUnit::Binary).unwrap_err();
+        assert!(err.to_string().contains("404"), "{}", err);
+
+        let err = fetch_text("ftp://example.com/SHA256SUMS", SizeUnit::Binary).unwrap_err();
+        assert!(err.to_string().contains("http"), "{}", err);
+    }
+}
@@ -0,0 +1,325 @@
+// 按哈希重命名模块
+//
+// 把文件重命名为 `<sha256><原扩展名>`。先生成只读的试运行计划供用户确认，
+// 确认后才真正执行；目标已存在时按内容决定跳过或提示冲突，从不覆盖文件。
+
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::error::HashResult;
+
+/// 单个文件的重命名处理方式
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RenameAction {
+    /// 执行重命名
+    Rename,
+    /// 文件名已是哈希值
+    AlreadyNamed,
+    /// 目标已存在且内容相同，保留原文件不动
+    SkipSameHash,
+    /// 目标已存在但内容不同，需要用户自行处理
+    Conflict { existing_sha256: String },
+    /// 读取已存在的目标失败
+    TargetUnreadable(String),
+}
+
+impl RenameAction {
+    pub fn label(&self) -> String {
+        match self {
+            RenameAction::Rename => "重命名".to_string(),
+            RenameAction::AlreadyNamed => "已是哈希文件名".to_string(),
+            RenameAction::SkipSameHash => "跳过：目标已存在且内容相同".to_string(),
+            RenameAction::Conflict { existing_sha256 } => {
+                format!("冲突：目标已存在但内容不同 ({})", existing_sha256)
+            }
+            RenameAction::TargetUnreadable(e) => format!("跳过：无法读取已存在的目标 ({})", e),
+        }
+    }
+
+    pub fn is_rename(&self) -> bool {
+        matches!(self, RenameAction::Rename)
+    }
+}
+
+/// 试运行计划中的一项
+#[derive(Debug, Clone)]
+pub struct RenamePlanEntry {
+    pub source: PathBuf,
+    pub target: PathBuf,
+    pub action: RenameAction,
+}
+
+/// 执行结果，Err 为失败原因
+#[derive(Debug, Clone)]
+pub struct RenameOutcome {
+    pub source: PathBuf,
+    pub target: PathBuf,
+    pub result: Result<(), String>,
+}
+
+/// 目标路径：同目录下的 `<sha256 小写><原扩展名>`
+pub fn target_path(source: &Path, sha256: &str) -> PathBuf {
+    let mut name = sha256.to_lowercase();
+    if let Some(ext) = source.extension() {
+        name.push('.');
+        name.push_str(&ext.to_string_lossy());
+    }
+    source.with_file_name(name)
+}
+
+/// 生成试运行计划，不修改任何文件
+///
+/// `files` 为 (路径, SHA256)。`hash_existing` 用于计算已存在目标的 SHA256；
+/// 同一批中内容相同的多个文件只重命名第一个，其余视为目标已存在。
+pub fn plan_renames(
+    files: &[(PathBuf, String)],
+    hash_existing: impl Fn(&Path) -> HashResult<String>,
+) -> Vec<RenamePlanEntry> {
+    let mut claimed: HashSet<PathBuf> = HashSet::new();
+
+    files
+        .iter()
+        .map(|(source, sha256)| {
+            let sha256 = sha256.to_lowercase();
+            let target = target_path(source, &sha256);
+            let action = if is_same_file(source, &target) {
+                RenameAction::AlreadyNamed
+            } else if claimed.contains(&target) {
+                RenameAction::SkipSameHash
+            } else if target.exists() {
+                match hash_existing(&target) {
+                    Ok(existing) if existing.eq_ignore_ascii_case(&sha256) => {
+                        RenameAction::SkipSameHash
+                    }
+                    Ok(existing) => RenameAction::Conflict {
+                        existing_sha256: existing.to_lowercase(),
+                    },
+                    Err(e) => RenameAction::TargetUnreadable(e.to_string()),
+                }
+            } else {
+                RenameAction::Rename
+            };
+            if action.is_rename() {
+                claimed.insert(target.clone());
+            }
+            RenamePlanEntry {
+                source: source.clone(),
+                target,
+                action,
+            }
+        })
+        .collect()
+}
+
+/// 执行计划中标记为重命名的项
+///
+/// 试运行之后新出现的同名文件不会被覆盖，该项失败。
+pub fn apply_plan(plan: &[RenamePlanEntry]) -> Vec<RenameOutcome> {
+    plan.iter()
+        .filter(|entry| entry.action.is_rename())
+        .map(|entry| {
+            let result = match rename_no_replace(&entry.source, &entry.target) {
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {
+                    Err("目标文件已存在".to_string())
+                }
+                result => result.map_err(|e| e.to_string()),
+            };
+            RenameOutcome {
+                source: entry.source.clone(),
+                target: entry.target.clone(),
+                result,
+            }
+        })
+        .collect()
+}
+
+/// 重命名但不覆盖已存在的目标，目标存在时返回 `AlreadyExists`
+///
+/// 优先使用系统的不覆盖重命名，否则先建硬链接再删除源文件，目标存在时建链接即失败。
+/// 文件系统连硬链接也不支持时（如 FAT）退回先检查再重命名，两步之间新出现的同名
+/// 文件仍可能被覆盖。
+fn rename_no_replace(source: &Path, target: &Path) -> io::Result<()> {
+    if let Some(result) = rename_exclusive(source, target) {
+        return result;
+    }
+    match fs::hard_link(source, target) {
+        Ok(()) => {
+            return fs::remove_file(source).inspect_err(|_| {
+                let _ = fs::remove_file(target);
+            });
+        }
+        Err(e) if e.kind() == io::ErrorKind::AlreadyExists => return Err(e),
+        Err(_) => {}
+    }
+    if target.exists() {
+        return Err(io::ErrorKind::AlreadyExists.into());
+    }
+    fs::rename(source, target)
+}
+
+/// `renameat2(RENAME_NOREPLACE)`，文件系统或内核不支持时返回 None
+#[cfg(target_os = "linux")]
+fn rename_exclusive(source: &Path, target: &Path) -> Option<io::Result<()>> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let (Ok(from), Ok(to)) = (
+        CString::new(source.as_os_str().as_bytes()),
+        CString::new(target.as_os_str().as_bytes()),
+    ) else {
+        return Some(Err(io::ErrorKind::InvalidInput.into()));
+    };
+    let ret = unsafe {
+        libc::renameat2(
+            libc::AT_FDCWD,
+            from.as_ptr(),
+            libc::AT_FDCWD,
+            to.as_ptr(),
+            libc::RENAME_NOREPLACE,
+        )
+    };
+    if ret == 0 {
+        return Some(Ok(()));
+    }
+    let e = io::Error::last_os_error();
+    match e.raw_os_error() {
+        Some(libc::EINVAL | libc::ENOSYS | libc::ENOTSUP) => None,
+        _ => Some(Err(e)),
+    }
+}
+
+/// 不带 `MOVEFILE_REPLACE_EXISTING` 的 `MoveFileExW` 本身不覆盖目标
+#[cfg(windows)]
+fn rename_exclusive(source: &Path, target: &Path) -> Option<io::Result<()>> {
+    use std::os::windows::ffi::OsStrExt;
+    use windows_sys::Win32::Storage::FileSystem::MoveFileExW;
+
+    let wide = |path: &Path| -> Vec<u16> {
+        path.as_os_str()
+            .encode_wide()
+            .chain(std::iter::once(0))
+            .collect()
+    };
+    let (from, to) = (wide(source), wide(target));
+    if unsafe { MoveFileExW(from.as_ptr(), to.as_ptr(), 0) } != 0 {
+        Some(Ok(()))
+    } else {
+        Some(Err(io::Error::last_os_error()))
+    }
+}
+
+#[cfg(not(any(target_os = "linux", windows)))]
+fn rename_exclusive(_source: &Path, _target: &Path) -> Option<io::Result<()>> {
+    None
+}
+
+/// 路径相同，或在大小写不敏感的文件系统上指向同一文件
+fn is_same_file(a: &Path, b: &Path) -> bool {
+    if a == b {
+        return true;
+    }
+    match (fs::canonicalize(a), fs::canonicalize(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::HashError;
+    use tempfile::TempDir;
+
+    fn fake_sha(content: &[u8]) -> String {
+        format!("{:064x}", crc32fast::hash(content))
+    }
+
+    fn hash_file(path: &Path) -> HashResult<String> {
+        fs::read(path)
+            .map(|content| fake_sha(&content))
+            .map_err(|e| HashError::SystemResource(e.to_string()))
+    }
+
+    #[test]
+    fn test_plan_is_dry_run_and_handles_collisions() {
+        let dir = TempDir::new().unwrap();
+        let write = |name: &str, content: &[u8]| {
+            let path = dir.path().join(name);
+            fs::write(&path, content).unwrap();
+            path
+        };
+
+        let fresh = write("fresh.txt", b"fresh");
+        let dup_a = write("dup_a.bin", b"dup");
+        let dup_b = write("dup_b.bin", b"dup");
+        let same = write("same.dat", b"same");
+        write(&format!("{}.dat", fake_sha(b"same")), b"same");
+        let conflict = write("conflict.log", b"conflict");
+        write(&format!("{}.log", fake_sha(b"conflict")), b"other");
+        let named = write(&fake_sha(b"named"), b"named");
+
+        let files: Vec<(PathBuf, String)> = [&fresh, &dup_a, &dup_b, &same, &conflict, &named]
+            .iter()
+            .map(|path| ((*path).clone(), hash_file(path).unwrap().to_uppercase()))
+            .collect();
+        let plan = plan_renames(&files, hash_file);
+
+        let actions: Vec<&RenameAction> = plan.iter().map(|entry| &entry.action).collect();
+        assert_eq!(actions[0], &RenameAction::Rename);
+        assert_eq!(
+            plan[0].target,
+            dir.path().join(format!("{}.txt", fake_sha(b"fresh")))
+        );
+        assert_eq!(actions[1], &RenameAction::Rename);
+        assert_eq!(actions[2], &RenameAction::SkipSameHash);
+        assert_eq!(actions[3], &RenameAction::SkipSameHash);
+        assert_eq!(
+            actions[4],
+            &RenameAction::Conflict {
+                existing_sha256: fake_sha(b"other")
+            }
+        );
+        assert_eq!(actions[5], &RenameAction::AlreadyNamed);
+
+        // 试运行不改动任何文件
+        for path in [&fresh, &dup_a, &dup_b, &same, &conflict, &named] {
+            assert!(path.exists());
+        }
+        assert!(!plan[0].target.exists());
+
+        let outcomes = apply_plan(&plan);
+        assert_eq!(outcomes.len(), 2);
+        assert!(outcomes.iter().all(|outcome| outcome.result.is_ok()));
+        assert!(!fresh.exists() && plan[0].target.exists());
+        assert!(dup_b.exists() && same.exists() && conflict.exists());
+    }
+
+    #[test]
+    fn test_apply_never_overwrites_new_target() {
+        let dir = TempDir::new().unwrap();
+        let source = dir.path().join("a.txt");
+        fs::write(&source, b"a").unwrap();
+        let files = vec![(source.clone(), hash_file(&source).unwrap())];
+        let plan = plan_renames(&files, hash_file);
+        assert_eq!(plan[0].action, RenameAction::Rename);
+
+        // 试运行之后出现的同名文件
+        fs::write(&plan[0].target, b"other").unwrap();
+        let outcomes = apply_plan(&plan);
+        assert!(outcomes[0].result.is_err());
+        assert_eq!(fs::read(&plan[0].target).unwrap(), b"other");
+        assert!(source.exists());
+
+        let other = dir.path().join("b.txt");
+        fs::write(&other, b"b").unwrap();
+        assert_eq!(
+            rename_no_replace(&other, &source).unwrap_err().kind(),
+            io::ErrorKind::AlreadyExists
+        );
+        let moved = dir.path().join("c.txt");
+        rename_no_replace(&other, &moved).unwrap();
+        assert!(!other.exists() && fs::read(&moved).unwrap() == b"b");
+    }
+}
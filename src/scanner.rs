@@ -1,16 +1,128 @@
 use crossbeam_channel::{Receiver, Sender, bounded};
+use std::ffi::OsStr;
 use std::fs;
 use std::mem;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 use std::thread;
 use std::time::{Duration, Instant};
 use walkdir::WalkDir;
 
+use crate::cache::{CacheConfig, DiskConcurrency, SymlinkPolicy};
+use crate::engine::{content_len, is_block_device, is_special_file, is_symlink, link_target_bytes};
+use crate::ignore::IgnoreStack;
+use crate::verify::{ExpectedHash, SidecarIndex};
 use crate::worker::UiMessage;
 
+/// 批次大小与刷新间隔的下限，避免过小批次带来的通道开销
+const MIN_SCAN_BATCH_SIZE: usize = 10;
+const MIN_SCAN_FLUSH_MS: u64 = 10;
+
+/// 扫描参数（每次扫描时从配置读取）
+#[derive(Debug, Clone)]
+pub struct ScanOptions {
+    pub batch_size: usize,
+    pub flush_interval: Duration,
+    /// 超过此大小的文件只列出不计算，0 表示不限制
+    pub max_file_size: u64,
+    /// 遍历线程数，1 为串行遍历
+    pub threads: usize,
+    /// 允许显式添加的块设备
+    pub block_devices: bool,
+    /// 查找同名校验文件（如 `movie.iso.sha256`）并在计算后自动校验
+    pub sidecars: bool,
+    /// 将 NTFS 备用数据流作为单独的文件加入（仅 Windows）
+    pub alternate_streams: bool,
+    /// 机械硬盘上退回串行遍历
+    pub disk_concurrency: DiskConcurrency,
+    /// 直接添加的符号链接计算目标文件还是链接本身
+    pub symlinks: SymlinkPolicy,
+    /// 整个跳过的目录名（不区分大小写）
+    pub excluded_dirs: Vec<String>,
+}
+
+impl ScanOptions {
+    pub fn from_config(config: &CacheConfig) -> Self {
+        Self {
+            batch_size: config.scan_batch_size.max(MIN_SCAN_BATCH_SIZE),
+            flush_interval: Duration::from_millis(config.scan_flush_ms.max(MIN_SCAN_FLUSH_MS)),
+            max_file_size: config.max_file_size,
+            threads: match config.scan_threads {
+                0 => num_cpus::get(),
+                n => n,
+            },
+            block_devices: config.hash_block_devices,
+            sidecars: config.verify_sidecars,
+            alternate_streams: config.hash_alternate_streams,
+            disk_concurrency: config.disk_concurrency,
+            symlinks: config.symlink_policy,
+            excluded_dirs: config.excluded_dirs.clone(),
+        }
+    }
+
+    /// 目录名是否在排除列表中；Windows 上目录名不区分大小写，统一按 ASCII 忽略大小写比较
+    fn is_excluded_dir(&self, name: &OsStr) -> bool {
+        name.to_str().is_some_and(|name| {
+            self.excluded_dirs
+                .iter()
+                .any(|excluded| excluded.eq_ignore_ascii_case(name))
+        })
+    }
+
+    fn discovered(&self, path: &Path, size: u64) -> DiscoveredFile {
+        DiscoveredFile {
+            path: path.to_path_buf(),
+            size,
+            oversized: self.max_file_size > 0 && size > self.max_file_size,
+            expected: None,
+        }
+    }
+
+    /// 追加文件本身，开启备用数据流时在其后追加每个 `file:stream`
+    fn push_discovered(&self, files: &mut Vec<DiscoveredFile>, path: &Path, size: u64) {
+        files.push(self.discovered(path, size));
+        if self.alternate_streams {
+            self.push_streams(files, path);
+        }
+    }
+
+    #[cfg(windows)]
+    fn push_streams(&self, files: &mut Vec<DiscoveredFile>, path: &Path) {
+        match crate::streams::alternate_streams(path) {
+            Ok(streams) => files.extend(
+                streams
+                    .iter()
+                    .map(|stream| self.discovered(&stream.path, stream.size)),
+            ),
+            Err(e) => log::warn!("无法枚举备用数据流: {} - {}", path.display(), e),
+        }
+    }
+
+    /// 其他平台没有备用数据流
+    #[cfg(not(windows))]
+    fn push_streams(&self, _files: &mut Vec<DiscoveredFile>, _path: &Path) {}
+}
+
+impl Default for ScanOptions {
+    fn default() -> Self {
+        Self::from_config(&CacheConfig::default())
+    }
+}
+
+/// 扫描发现的文件
+#[derive(Debug, Clone)]
+pub struct DiscoveredFile {
+    pub path: PathBuf,
+    pub size: u64,
+    /// 超过最大文件大小，列出但不加入计算队列
+    pub oversized: bool,
+    /// 同名校验文件中的期望哈希
+    pub expected: Option<ExpectedHash>,
+}
+
 #[cfg_attr(test, derive(Debug))]
 pub enum ScannerMessage {
-    Scan(Vec<PathBuf>),
+    Scan(Vec<PathBuf>, ScanOptions),
 }
 
 pub struct FileScanner {
@@ -28,86 +140,643 @@ impl FileScanner {
         Self { tx }
     }
 
-    pub fn scan(&self, paths: Vec<PathBuf>) {
-        let _ = self.tx.send(ScannerMessage::Scan(paths));
+    pub fn scan(&self, paths: Vec<PathBuf>, options: ScanOptions) {
+        let _ = self.tx.send(ScannerMessage::Scan(paths, options));
     }
 
     fn run(rx: Receiver<ScannerMessage>, ui_tx: Sender<UiMessage>) {
+        // 线程池在各次扫描间复用，线程数设置变化时才重建
+        let mut cached: Option<(usize, Option<rayon::ThreadPool>)> = None;
         while let Ok(msg) = rx.recv() {
             match msg {
-                ScannerMessage::Scan(paths) => {
+                ScannerMessage::Scan(paths, options) => {
+                    if cached
+                        .as_ref()
+                        .is_none_or(|(threads, _)| *threads != options.threads)
+                    {
+                        cached = Some((options.threads, Self::build_pool(options.threads)));
+                    }
+                    let pool = cached.as_ref().and_then(|(_, pool)| pool.as_ref());
                     for path in paths {
-                        Self::scan_path(&path, &ui_tx);
+                        // 机械硬盘上并行遍历同样会导致磁头来回寻道
+                        let pool = pool.filter(|_| !options.disk_concurrency.limits(&path));
+                        Self::scan_path(&path, &ui_tx, &options, pool);
                     }
+                    let _ = ui_tx.send(UiMessage::ScanFinished);
                 }
             }
         }
     }
 
-    fn scan_path(root: &PathBuf, ui_tx: &Sender<UiMessage>) {
+    /// 并行扫描使用独立线程池，避免目录 I/O 占用计算哈希的全局线程池
+    fn build_pool(threads: usize) -> Option<rayon::ThreadPool> {
+        if threads <= 1 {
+            return None;
+        }
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .thread_name(|i| format!("scanner-{i}"))
+            .build()
+            .map_err(|e| log::warn!("创建扫描线程池失败，改用串行遍历: {}", e))
+            .ok()
+    }
+
+    fn scan_path(
+        root: &PathBuf,
+        ui_tx: &Sender<UiMessage>,
+        options: &ScanOptions,
+        pool: Option<&rayon::ThreadPool>,
+    ) {
+        // 指向目录的符号链接照常遍历；直接添加的指向文件（或悬空）的链接按设置处理，
+        // 跟随时与普通文件走同一流程，大小为目标文件的大小
+        if is_symlink(root) && !root.is_dir() {
+            match options.symlinks {
+                SymlinkPolicy::Link => {
+                    match link_target_bytes(root) {
+                        Ok(target) => {
+                            let _ = ui_tx.send(UiMessage::FilesDiscovered(vec![
+                                options.discovered(root, target.len() as u64),
+                            ]));
+                        }
+                        Err(e) => log::warn!("无法读取符号链接: {} - {}", root.display(), e),
+                    }
+                    return;
+                }
+                SymlinkPolicy::Follow if !root.exists() => {
+                    log::warn!("跳过目标不存在的符号链接: {}", root.display());
+                    return;
+                }
+                SymlinkPolicy::Follow => {}
+            }
+        }
         if root.is_file() {
             if let Ok(metadata) = fs::metadata(root) {
-                let _ = ui_tx.send(UiMessage::FilesDiscovered(vec![(
-                    root.clone(),
-                    metadata.len(),
-                )]));
+                let mut files = Vec::new();
+                options.push_discovered(&mut files, root, metadata.len());
+                if options.sidecars {
+                    let index = SidecarIndex::default();
+                    for file in &mut files {
+                        file.expected = index.lookup(&file.path);
+                    }
+                }
+                let _ = ui_tx.send(UiMessage::FilesDiscovered(files));
             }
             return;
         }
+        // 块设备只在作为根路径显式添加时处理，遍历目录时不会进入 /dev
+        if is_block_device(root) {
+            if !options.block_devices {
+                log::warn!("跳过块设备（未在设置中启用）: {}", root.display());
+                return;
+            }
+            match content_len(root) {
+                Ok(size) => {
+                    let _ = ui_tx.send(UiMessage::FilesDiscovered(vec![
+                        options.discovered(root, size),
+                    ]));
+                }
+                Err(e) => log::warn!("无法读取块设备: {} - {}", root.display(), e),
+            }
+            return;
+        }
+        if fs::metadata(root).is_ok_and(|metadata| is_special_file(metadata.file_type())) {
+            log::warn!("不支持的文件类型，跳过: {}", root.display());
+            return;
+        }
+
+        let batcher = Batcher::new(ui_tx, options);
+        match pool {
+            // 与 WalkDir 的 filter_entry 一致，根目录本身也参与隐藏条目过滤
+            Some(pool) if root.file_name().is_none_or(is_visible) => {
+                pool.scope(|scope| {
+                    Self::walk_parallel(root, &IgnoreStack::default(), &batcher, scope);
+                });
+            }
+            Some(_) => {}
+            None => Self::walk_serial(root, &batcher),
+        }
+        batcher.finish();
+    }
 
+    fn walk_serial(root: &Path, batcher: &Batcher<'_>) {
+        // 第 n 项为深度 n 的条目适用的忽略规则；深度优先遍历，进入目录时截断到当前深度
+        let mut ignores = vec![IgnoreStack::default()];
         let walker = WalkDir::new(root)
             .follow_links(false)
             .into_iter()
-            .filter_entry(|e| {
-                e.file_name()
-                    .to_str()
-                    .map(|s| !s.starts_with('.'))
-                    .unwrap_or(false)
+            // 显式添加的根目录即使在排除列表或忽略文件中也照常扫描
+            .filter_entry(move |e| {
+                let is_dir = e.file_type().is_dir();
+                let visible = is_visible(e.file_name())
+                    && !(e.depth() > 0
+                        && ((is_dir && batcher.options.is_excluded_dir(e.file_name()))
+                            || ignores[e.depth()].is_ignored(e.path(), is_dir)));
+                if visible && is_dir {
+                    ignores.truncate(e.depth() + 1);
+                    let inner = ignores[e.depth()].enter(e.path());
+                    ignores.push(inner);
+                }
+                visible
             });
 
-        let mut batch = Vec::with_capacity(100);
-        let mut last_send = Instant::now();
-
+        // 与并行遍历一样按目录交给 batcher，单个目录的文件过多时按批次大小分段
+        let mut files = Vec::new();
         for entry in walker {
             match entry {
+                Ok(entry) if entry.file_type().is_dir() => {
+                    batcher.push(mem::take(&mut files));
+                }
                 Ok(entry) if entry.file_type().is_file() => {
-                    let path = entry.path().to_path_buf();
+                    let path = entry.path();
 
                     match entry.metadata() {
                         Ok(metadata) => {
-                            batch.push((path, metadata.len()));
+                            batcher
+                                .options
+                                .push_discovered(&mut files, path, metadata.len());
+                            if files.len() >= batcher.options.batch_size {
+                                batcher.push(mem::take(&mut files));
+                            }
                         }
                         Err(e) => {
-                            eprintln!(
-                                "[Scanner] 跳过文件（无法读取元数据）: {} - {}",
-                                path.display(),
-                                e
-                            );
+                            log::warn!("跳过文件（无法读取元数据）: {} - {}", path.display(), e);
                         }
                     }
-
-                    if batch.len() >= 100 || last_send.elapsed() >= Duration::from_millis(50) {
-                        let _ = ui_tx.send(UiMessage::FilesDiscovered(mem::take(&mut batch)));
-                        last_send = Instant::now();
-
-                        thread::yield_now();
-                    }
+                }
+                Ok(entry) if is_special_file(entry.file_type()) => {
+                    log::debug!("不支持的文件类型，跳过: {}", entry.path().display());
                 }
                 Err(e) => {
                     let path_str = e
                         .path()
                         .map(|p| p.display().to_string())
                         .unwrap_or_else(|| "未知路径".to_string());
-                    eprintln!("[Scanner] 遍历错误: {} - {}", path_str, e);
+                    log::warn!("遍历错误: {} - {}", path_str, e);
                 }
                 _ => {
-                    // 不是文件（目录、符号链接等），跳过
+                    // 不是文件（符号链接等），跳过
+                }
+            }
+        }
+        batcher.push(files);
+    }
+
+    /// 每个目录一个任务，子目录作为新任务交给线程池；不跟随符号链接
+    fn walk_parallel<'s>(
+        dir: &Path,
+        ignores: &IgnoreStack,
+        batcher: &'s Batcher<'s>,
+        scope: &rayon::Scope<'s>,
+    ) {
+        let ignores = ignores.enter(dir);
+        let entries = match fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(e) => {
+                log::warn!("遍历错误: {} - {}", dir.display(), e);
+                return;
+            }
+        };
+
+        let mut files = Vec::new();
+        for entry in entries {
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(e) => {
+                    log::warn!("遍历错误: {} - {}", dir.display(), e);
+                    continue;
+                }
+            };
+            if !is_visible(&entry.file_name()) {
+                continue;
+            }
+
+            let path = entry.path();
+            let file_type = entry.file_type();
+            let is_dir = file_type.as_ref().is_ok_and(std::fs::FileType::is_dir);
+            if ignores.is_ignored(&path, is_dir) {
+                continue;
+            }
+            match file_type {
+                Ok(file_type) if file_type.is_dir() => {
+                    if batcher.options.is_excluded_dir(&entry.file_name()) {
+                        continue;
+                    }
+                    let ignores = ignores.clone();
+                    scope.spawn(move |scope| Self::walk_parallel(&path, &ignores, batcher, scope));
+                }
+                Ok(file_type) if file_type.is_file() => match entry.metadata() {
+                    Ok(metadata) => {
+                        batcher
+                            .options
+                            .push_discovered(&mut files, &path, metadata.len());
+                        if files.len() >= batcher.options.batch_size {
+                            batcher.push(mem::take(&mut files));
+                        }
+                    }
+                    Err(e) => {
+                        log::warn!("跳过文件（无法读取元数据）: {} - {}", path.display(), e);
+                    }
+                },
+                Ok(file_type) if is_special_file(file_type) => {
+                    log::debug!("不支持的文件类型，跳过: {}", path.display());
+                }
+                Ok(_) => {}
+                Err(e) => log::warn!("遍历错误: {} - {}", path.display(), e),
+            }
+        }
+        batcher.push(files);
+    }
+}
+
+/// 隐藏条目（以 `.` 开头）不参与扫描；按原始字节判断，非 UTF-8 名称照常列出
+fn is_visible(name: &OsStr) -> bool {
+    name.as_encoded_bytes().first() != Some(&b'.')
+}
+
+/// 按批次大小与刷新间隔把发现的文件发送给 UI，串行与并行遍历共用
+struct Batcher<'a> {
+    ui_tx: &'a Sender<UiMessage>,
+    options: &'a ScanOptions,
+    sidecars: Option<SidecarIndex>,
+    // (待发送的文件, 上次发送时间)
+    state: Mutex<(Vec<DiscoveredFile>, Instant)>,
+}
+
+impl<'a> Batcher<'a> {
+    fn new(ui_tx: &'a Sender<UiMessage>, options: &'a ScanOptions) -> Self {
+        Self {
+            ui_tx,
+            options,
+            sidecars: options.sidecars.then(SidecarIndex::default),
+            state: Mutex::new((Vec::with_capacity(options.batch_size), Instant::now())),
+        }
+    }
+
+    fn push(&self, mut files: Vec<DiscoveredFile>) {
+        if files.is_empty() {
+            return;
+        }
+        // 在加锁前查找校验文件，并行遍历时各线程互不等待
+        if let Some(index) = &self.sidecars {
+            for file in &mut files {
+                file.expected = index.lookup(&file.path);
+            }
+        }
+        let Ok(mut state) = self.state.lock() else {
+            return;
+        };
+        let (batch, last_send) = &mut *state;
+        batch.extend(files);
+
+        let mut sent = false;
+        // 每批不超过 batch_size，并行遍历一次推入整个目录时拆分发送
+        while batch.len() >= self.options.batch_size {
+            let rest = batch.split_off(self.options.batch_size);
+            let _ = self
+                .ui_tx
+                .send(UiMessage::FilesDiscovered(mem::replace(batch, rest)));
+            sent = true;
+        }
+        if !batch.is_empty() && last_send.elapsed() >= self.options.flush_interval {
+            let _ = self
+                .ui_tx
+                .send(UiMessage::FilesDiscovered(mem::take(batch)));
+            sent = true;
+        }
+        if sent {
+            *last_send = Instant::now();
+            drop(state);
+            thread::yield_now();
+        }
+    }
+
+    fn finish(self) {
+        if let Ok((batch, _)) = self.state.into_inner() {
+            if !batch.is_empty() {
+                let _ = self.ui_tx.send(UiMessage::FilesDiscovered(batch));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossbeam_channel::unbounded;
+    use tempfile::TempDir;
+
+    /// 返回每批的文件（保持发送顺序）
+    fn scan_batches(
+        root: &PathBuf,
+        options: &ScanOptions,
+        pool: Option<&rayon::ThreadPool>,
+    ) -> Vec<Vec<DiscoveredFile>> {
+        let (tx, rx) = unbounded();
+        FileScanner::scan_path(root, &tx, options, pool);
+        drop(tx);
+        rx.iter()
+            .filter_map(|msg| match msg {
+                UiMessage::FilesDiscovered(batch) => Some(batch),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn scan_dir(root: &PathBuf, options: &ScanOptions) -> Vec<DiscoveredFile> {
+        let mut files: Vec<DiscoveredFile> = scan_batches(root, options, None)
+            .into_iter()
+            .flatten()
+            .collect();
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+        files
+    }
+
+    #[test]
+    fn test_parallel_scan_matches_serial() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path().join("tree");
+        // 64 个顶层目录，每个下有 4 个子目录，每层 3 个文件
+        for i in 0..64 {
+            let top = root.join(format!("d{i}"));
+            for j in 0..4 {
+                let sub = top.join(format!("s{j}"));
+                std::fs::create_dir_all(&sub).unwrap();
+                for k in 0u8..3 {
+                    std::fs::write(sub.join(format!("f{k}.bin")), [k; 16]).unwrap();
                 }
             }
+            for k in 0..3 {
+                std::fs::write(top.join(format!("f{k}.bin")), b"top").unwrap();
+            }
         }
+        std::fs::create_dir_all(root.join(".hidden")).unwrap();
+        std::fs::write(root.join(".hidden").join("skip.bin"), b"x").unwrap();
+        std::fs::write(root.join(".dotfile"), b"x").unwrap();
+
+        // 刷新间隔足够长时，除最后一批外每批都应恰好为 batch_size
+        let options = ScanOptions {
+            batch_size: 50,
+            flush_interval: Duration::from_secs(3600),
+            ..ScanOptions::default()
+        };
+        let pool = FileScanner::build_pool(4).unwrap();
 
-        if !batch.is_empty() {
-            let _ = ui_tx.send(UiMessage::FilesDiscovered(batch));
+        let serial = scan_batches(&root, &options, None);
+        let parallel = scan_batches(&root, &options, Some(&pool));
+
+        for batches in [&serial, &parallel] {
+            let (last, full) = batches.split_last().unwrap();
+            assert!(full.iter().all(|batch| batch.len() == options.batch_size));
+            assert!(!last.is_empty() && last.len() <= options.batch_size);
         }
+
+        let sorted = |batches: Vec<Vec<DiscoveredFile>>| {
+            let mut paths: Vec<PathBuf> = batches.into_iter().flatten().map(|f| f.path).collect();
+            paths.sort();
+            paths
+        };
+        let serial = sorted(serial);
+        assert_eq!(serial.len(), 64 * (4 * 3 + 3));
+        assert!(serial.iter().all(|p| {
+            p.strip_prefix(&root)
+                .unwrap()
+                .components()
+                .all(|c| !c.as_os_str().to_string_lossy().starts_with('.'))
+        }));
+        assert_eq!(serial, sorted(parallel));
+    }
+
+    #[test]
+    fn test_oversized_file_is_listed_but_flagged() {
+        let temp = TempDir::new().unwrap();
+        // 临时目录名以 `.` 开头，会被隐藏条目过滤掉
+        let root = temp.path().join("scan");
+        std::fs::create_dir(&root).unwrap();
+        std::fs::write(root.join("big.img"), vec![0u8; 4096]).unwrap();
+        std::fs::write(root.join("small.txt"), b"hello").unwrap();
+
+        let options = ScanOptions {
+            max_file_size: 1024,
+            ..ScanOptions::default()
+        };
+        let files = scan_dir(&root, &options);
+        assert_eq!(files.len(), 2);
+        assert!(files[0].path.ends_with("big.img") && files[0].oversized);
+        assert!(files[1].path.ends_with("small.txt") && !files[1].oversized);
+
+        // 默认不限制大小
+        let files = scan_dir(&root, &ScanOptions::default());
+        assert!(files.iter().all(|f| !f.oversized));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_excluded_dirs_are_pruned() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path().join("node_modules");
+        for dir in ["src", "node_modules/pkg", "src/Node_Modules", "build/cache"] {
+            std::fs::create_dir_all(root.join(dir)).unwrap();
+        }
+        for file in [
+            "keep.txt",
+            "src/main.rs",
+            "node_modules/pkg/index.js",
+            "src/Node_Modules/dep.js",
+            "build/cache/blob",
+        ] {
+            std::fs::write(root.join(file), b"x").unwrap();
+        }
+
+        let options = ScanOptions {
+            excluded_dirs: vec!["node_modules".to_string(), "cache".to_string()],
+            ..ScanOptions::default()
+        };
+        let pool = FileScanner::build_pool(2).unwrap();
+        let expected = [root.join("keep.txt"), root.join("src").join("main.rs")];
+        // 显式添加的根目录不受排除列表影响，其下同名目录整个跳过
+        for pool in [None, Some(&pool)] {
+            let mut paths: Vec<PathBuf> = scan_batches(&root, &options, pool)
+                .into_iter()
+                .flatten()
+                .map(|f| f.path)
+                .collect();
+            paths.sort();
+            assert_eq!(paths, expected);
+        }
+    }
+
+    #[test]
+    fn test_ignore_file_excludes_subtree() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path().join("scan");
+        for dir in ["vendor/lib", "assets/raw", "assets/final"] {
+            std::fs::create_dir_all(root.join(dir)).unwrap();
+        }
+        for file in [
+            "main.rs",
+            "notes.tmp",
+            "vendor/lib/dep.rs",
+            "assets/raw/a.psd",
+            "assets/raw/keep.tmp",
+            "assets/final/a.png",
+        ] {
+            std::fs::write(root.join(file), b"x").unwrap();
+        }
+        std::fs::write(
+            root.join(crate::ignore::IGNORE_FILE_NAME),
+            "# 第三方代码\n/vendor/\n*.tmp\n",
+        )
+        .unwrap();
+        // 深层的忽略文件可以重新包含上层忽略的文件
+        std::fs::write(
+            root.join("assets/raw")
+                .join(crate::ignore::IGNORE_FILE_NAME),
+            "*.psd\n!keep.tmp\n",
+        )
+        .unwrap();
+
+        let pool = FileScanner::build_pool(2).unwrap();
+        let mut expected = vec![
+            root.join("main.rs"),
+            root.join("assets/raw/keep.tmp"),
+            root.join("assets/final/a.png"),
+        ];
+        expected.sort();
+        for pool in [None, Some(&pool)] {
+            let mut paths: Vec<PathBuf> = scan_batches(&root, &ScanOptions::default(), pool)
+                .into_iter()
+                .flatten()
+                .map(|f| f.path)
+                .collect();
+            paths.sort();
+            assert_eq!(paths, expected);
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_fifo_is_skipped() {
+        use std::os::unix::ffi::OsStrExt;
+
+        let temp = TempDir::new().unwrap();
+        let root = temp.path().join("scan");
+        std::fs::create_dir(&root).unwrap();
+        std::fs::write(root.join("data.bin"), b"x").unwrap();
+        let fifo = root.join("pipe");
+        let c_path = std::ffi::CString::new(fifo.as_os_str().as_bytes()).unwrap();
+        assert_eq!(unsafe { libc::mkfifo(c_path.as_ptr(), 0o600) }, 0);
+
+        let options = ScanOptions::default();
+        let pool = FileScanner::build_pool(2).unwrap();
+        for pool in [None, Some(&pool)] {
+            let paths: Vec<PathBuf> = scan_batches(&root, &options, pool)
+                .into_iter()
+                .flatten()
+                .map(|f| f.path)
+                .collect();
+            assert_eq!(paths, [root.join("data.bin")]);
+        }
+        // 直接添加的 FIFO 不会打开读取，也就不会阻塞
+        assert!(scan_batches(&fifo, &options, None).is_empty());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_non_utf8_names_are_listed() {
+        use std::os::unix::ffi::OsStrExt;
+
+        let temp = TempDir::new().unwrap();
+        let root = temp.path().join("scan");
+        // Latin-1 编码的 café 目录与 été.bin
+        let dir = root.join(OsStr::from_bytes(b"caf\xe9"));
+        std::fs::create_dir_all(&dir).unwrap();
+        let legacy = dir.join(OsStr::from_bytes(b"\xe9t\xe9.bin"));
+        std::fs::write(&legacy, b"x").unwrap();
+        std::fs::write(root.join(OsStr::from_bytes(b".\xe9")), b"x").unwrap();
+
+        let pool = FileScanner::build_pool(2).unwrap();
+        for pool in [None, Some(&pool)] {
+            let paths: Vec<PathBuf> = scan_batches(&root, &ScanOptions::default(), pool)
+                .into_iter()
+                .flatten()
+                .map(|f| f.path)
+                .collect();
+            assert_eq!(paths, std::slice::from_ref(&legacy));
+        }
+    }
+
+    #[test]
+    fn test_symlink_added_directly() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path().join("scan");
+        std::fs::create_dir(&root).unwrap();
+        let target = root.join("target.bin");
+        std::fs::write(&target, vec![1u8; 4096]).unwrap();
+        let link = root.join("link.bin");
+        std::os::unix::fs::symlink(&target, &link).unwrap();
+        let dangling = root.join("dangling.bin");
+        std::os::unix::fs::symlink(root.join("missing.bin"), &dangling).unwrap();
+
+        // 跟随：按目标文件的大小列出，悬空链接跳过
+        assert_eq!(scan_dir(&link, &ScanOptions::default())[0].size, 4096);
+        assert!(scan_dir(&dangling, &ScanOptions::default()).is_empty());
+        // 遍历目录时不论设置如何都跳过符号链接
+        assert_eq!(scan_dir(&root, &ScanOptions::default()).len(), 1);
+
+        let options = ScanOptions {
+            symlinks: SymlinkPolicy::Link,
+            ..ScanOptions::default()
+        };
+        let target_len = target.as_os_str().len() as u64;
+        assert_eq!(scan_dir(&link, &options)[0].size, target_len);
+        assert_eq!(scan_dir(&dangling, &options).len(), 1);
+        assert_eq!(scan_dir(&root, &options).len(), 1);
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn test_alternate_streams_listed_as_rows() {
+        use crate::engine::compute_file_hash;
+        use crate::hash::EnabledHashes;
+        use crate::streams::stream_path;
+
+        let temp = TempDir::new().unwrap();
+        let root = temp.path().join("scan");
+        std::fs::create_dir(&root).unwrap();
+        let file = root.join("file.txt");
+        std::fs::write(&file, b"main").unwrap();
+        std::fs::write(stream_path(&file, "extra"), b"hidden data").unwrap();
+
+        // 默认关闭
+        let files = scan_dir(&root, &ScanOptions::default());
+        assert_eq!(files.len(), 1);
+
+        let options = ScanOptions {
+            alternate_streams: true,
+            ..ScanOptions::default()
+        };
+        let files = scan_dir(&root, &options);
+        let rows: Vec<(&Path, u64)> = files.iter().map(|f| (f.path.as_path(), f.size)).collect();
+        let stream = stream_path(&file, "extra");
+        assert_eq!(rows, [(file.as_path(), 4), (stream.as_path(), 11)]);
+
+        // 数据流按 `path:stream` 打开，走普通的计算流程
+        let hash = |path: &Path| {
+            compute_file_hash(
+                path,
+                None,
+                4096,
+                4096,
+                None,
+                false,
+                EnabledHashes::default(),
+            )
+            .unwrap()
+            .xxhash3
+        };
+        let copy = temp.path().join("copy.bin");
+        std::fs::write(&copy, b"hidden data").unwrap();
+        assert_eq!(hash(&stream), hash(&copy));
+        assert_ne!(hash(&stream), hash(&file));
     }
 }
@@ -0,0 +1,166 @@
+// 稀疏文件的已分配区域查询
+//
+// 虚拟机磁盘等稀疏文件大部分是未分配的空洞，读取时全为零。开启后计算前查询文件的
+// 已分配区域（Unix `SEEK_DATA/SEEK_HOLE`，Windows `FSCTL_QUERY_ALLOCATED_RANGES`），
+// 只读取这些区域，空洞直接按零字节送入哈希器，不读盘也不映射内存，
+// 避免内存映射巨大的稀疏文件时提交内存暴涨。结果与按普通方式读取完全一致。
+
+use std::fs::{File, Metadata};
+use std::io;
+use std::ops::Range;
+
+/// 查询文件中已分配（可能含数据）的区域，按偏移排序且互不重叠
+///
+/// 文件没有空洞、所在文件系统或平台不支持查询时返回 None，调用方按普通方式读取。
+pub fn allocated_ranges(file: &File, metadata: &Metadata) -> io::Result<Option<Vec<Range<u64>>>> {
+    let len = metadata.len();
+    if !metadata.is_file() || len == 0 || !imp::may_have_holes(metadata) {
+        return Ok(None);
+    }
+    let ranges = imp::query_ranges(file, len)?;
+    // 已分配区域覆盖整个文件时不算稀疏
+    Ok(ranges.filter(|ranges| !(ranges.len() == 1 && ranges[0] == (0..len))))
+}
+
+#[cfg(any(
+    target_os = "linux",
+    target_os = "android",
+    target_os = "freebsd",
+    target_os = "macos"
+))]
+mod imp {
+    use std::fs::{File, Metadata};
+    use std::io;
+    use std::ops::Range;
+    use std::os::unix::fs::MetadataExt;
+    use std::os::unix::io::AsRawFd;
+
+    /// 占用的块少于文件长度时才可能有空洞，避免对普通文件逐个查询
+    pub fn may_have_holes(metadata: &Metadata) -> bool {
+        metadata.blocks().saturating_mul(512) < metadata.len()
+    }
+
+    /// 文件系统不支持查询时返回 None
+    pub fn query_ranges(file: &File, len: u64) -> io::Result<Option<Vec<Range<u64>>>> {
+        let fd = file.as_raw_fd();
+        let mut ranges = Vec::new();
+        let mut offset = 0u64;
+        while offset < len {
+            let data = unsafe { libc::lseek(fd, offset as libc::off_t, libc::SEEK_DATA) };
+            if data < 0 {
+                let e = io::Error::last_os_error();
+                return match e.raw_os_error() {
+                    // 之后只剩空洞
+                    Some(libc::ENXIO) => Ok(Some(ranges)),
+                    // 文件系统不支持查询
+                    Some(libc::EINVAL) if ranges.is_empty() => Ok(None),
+                    _ => Err(e),
+                };
+            }
+            let hole = unsafe { libc::lseek(fd, data, libc::SEEK_HOLE) };
+            if hole < 0 {
+                return Err(io::Error::last_os_error());
+            }
+            let (start, end) = (data as u64, (hole as u64).min(len));
+            if end <= start {
+                break;
+            }
+            ranges.push(start..end);
+            offset = end;
+        }
+        Ok(Some(ranges))
+    }
+}
+
+#[cfg(windows)]
+mod imp {
+    use std::fs::{File, Metadata};
+    use std::io;
+    use std::ops::Range;
+    use std::os::windows::fs::MetadataExt;
+    use std::os::windows::io::AsRawHandle;
+    use windows_sys::Win32::Foundation::ERROR_MORE_DATA;
+    use windows_sys::Win32::Storage::FileSystem::FILE_ATTRIBUTE_SPARSE_FILE;
+    use windows_sys::Win32::System::IO::DeviceIoControl;
+    use windows_sys::Win32::System::Ioctl::{
+        FILE_ALLOCATED_RANGE_BUFFER, FSCTL_QUERY_ALLOCATED_RANGES,
+    };
+
+    /// 只有标记为稀疏的文件才可能有空洞
+    pub fn may_have_holes(metadata: &Metadata) -> bool {
+        metadata.file_attributes() & FILE_ATTRIBUTE_SPARSE_FILE != 0
+    }
+
+    pub fn query_ranges(file: &File, len: u64) -> io::Result<Option<Vec<Range<u64>>>> {
+        const BATCH: usize = 64;
+        let mut ranges = Vec::new();
+        let mut offset = 0u64;
+        loop {
+            let query = FILE_ALLOCATED_RANGE_BUFFER {
+                FileOffset: offset as i64,
+                Length: (len - offset) as i64,
+            };
+            let mut out = [FILE_ALLOCATED_RANGE_BUFFER {
+                FileOffset: 0,
+                Length: 0,
+            }; BATCH];
+            let mut returned = 0u32;
+            let ok = unsafe {
+                DeviceIoControl(
+                    file.as_raw_handle(),
+                    FSCTL_QUERY_ALLOCATED_RANGES,
+                    (&raw const query).cast(),
+                    size_of::<FILE_ALLOCATED_RANGE_BUFFER>() as u32,
+                    out.as_mut_ptr().cast(),
+                    size_of_val(&out) as u32,
+                    &mut returned,
+                    std::ptr::null_mut(),
+                )
+            };
+            let more = if ok == 0 {
+                let e = io::Error::last_os_error();
+                if e.raw_os_error() != Some(ERROR_MORE_DATA as i32) {
+                    return Err(e);
+                }
+                true
+            } else {
+                false
+            };
+
+            let count = returned as usize / size_of::<FILE_ALLOCATED_RANGE_BUFFER>();
+            for range in &out[..count] {
+                let start = range.FileOffset as u64;
+                let end = (start + range.Length as u64).min(len);
+                if end > start {
+                    ranges.push(start..end);
+                }
+            }
+            match ranges.last() {
+                // 输出缓冲区已满，从最后一个区域之后继续查询
+                Some(last) if more && last.end < len => offset = last.end,
+                _ => return Ok(Some(ranges)),
+            }
+        }
+    }
+}
+
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "android",
+    target_os = "freebsd",
+    target_os = "macos",
+    windows
+)))]
+mod imp {
+    use std::fs::{File, Metadata};
+    use std::io;
+    use std::ops::Range;
+
+    pub fn may_have_holes(_metadata: &Metadata) -> bool {
+        false
+    }
+
+    pub fn query_ranges(_file: &File, _len: u64) -> io::Result<Option<Vec<Range<u64>>>> {
+        Ok(None)
+    }
+}
@@ -0,0 +1,129 @@
+// NTFS 备用数据流（ADS）
+//
+// 取证场景中文件可能附带 `file.txt:stream` 形式的备用数据流。开启后扫描时通过
+// `FindFirstStreamW` 枚举每个文件的数据流，每个流作为一行 `file.txt:stream` 加入队列，
+// 按 `path:stream` 名称打开即可走普通的计算流程。仅 Windows（模块只在 Windows 上编译）。
+
+use std::ffi::OsString;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// 文件的一个备用数据流
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AlternateStream {
+    /// `file.txt:stream`
+    pub path: PathBuf,
+    pub size: u64,
+}
+
+/// 拼接数据流路径 `file.txt:stream`
+pub fn stream_path(file: &Path, stream: &str) -> PathBuf {
+    let mut path = OsString::from(file);
+    path.push(":");
+    path.push(stream);
+    PathBuf::from(path)
+}
+
+/// 拆分出数据流名称：`C:\dir\file.txt:stream` → (`C:\dir\file.txt`, `stream`)
+///
+/// 只检查最后一个路径分量，盘符中的冒号不受影响。不含数据流时返回 None。
+pub fn split_stream(path: &Path) -> Option<(PathBuf, String)> {
+    let name = path.file_name()?.to_str()?;
+    let (file, stream) = name.split_once(':')?;
+    if file.is_empty() || stream.is_empty() {
+        return None;
+    }
+    Some((path.with_file_name(file), stream.to_string()))
+}
+
+/// 枚举文件的备用数据流（不含默认的 `::$DATA` 主流）
+pub fn alternate_streams(file: &Path) -> io::Result<Vec<AlternateStream>> {
+    use std::os::windows::ffi::OsStrExt;
+    use windows_sys::Win32::Foundation::{ERROR_HANDLE_EOF, INVALID_HANDLE_VALUE};
+    use windows_sys::Win32::Storage::FileSystem::{
+        FindClose, FindFirstStreamW, FindNextStreamW, FindStreamInfoStandard,
+        WIN32_FIND_STREAM_DATA,
+    };
+
+    let wide: Vec<u16> = file
+        .as_os_str()
+        .encode_wide()
+        .chain(std::iter::once(0))
+        .collect();
+    let mut data = WIN32_FIND_STREAM_DATA::default();
+    let handle = unsafe {
+        FindFirstStreamW(
+            wide.as_ptr(),
+            FindStreamInfoStandard,
+            (&raw mut data).cast(),
+            0,
+        )
+    };
+    if handle == INVALID_HANDLE_VALUE {
+        let e = io::Error::last_os_error();
+        // 没有任何数据流（如 FAT 卷上的文件）
+        return if e.raw_os_error() == Some(ERROR_HANDLE_EOF as i32) {
+            Ok(Vec::new())
+        } else {
+            Err(e)
+        };
+    }
+
+    let mut streams = Vec::new();
+    loop {
+        let len = data
+            .cStreamName
+            .iter()
+            .position(|&c| c == 0)
+            .unwrap_or(data.cStreamName.len());
+        let name = String::from_utf16_lossy(&data.cStreamName[..len]);
+        // 名称格式为 `:stream:$DATA`，主流为 `::$DATA`
+        if let Some(stream) = name
+            .strip_prefix(':')
+            .and_then(|rest| rest.strip_suffix(":$DATA"))
+            .filter(|stream| !stream.is_empty())
+        {
+            streams.push(AlternateStream {
+                path: stream_path(file, stream),
+                size: u64::try_from(data.StreamSize).unwrap_or(0),
+            });
+        }
+        if unsafe { FindNextStreamW(handle, (&raw mut data).cast()) } == 0 {
+            break;
+        }
+    }
+    unsafe { FindClose(handle) };
+    Ok(streams)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_enumerate_and_split_streams() {
+        let dir = TempDir::new().unwrap();
+        let file = dir.path().join("file.txt");
+        fs::write(&file, b"main").unwrap();
+        fs::write(stream_path(&file, "extra"), b"hidden data").unwrap();
+
+        let streams = alternate_streams(&file).unwrap();
+        assert_eq!(
+            streams,
+            [AlternateStream {
+                path: stream_path(&file, "extra"),
+                size: 11,
+            }]
+        );
+        assert_eq!(fs::read(&streams[0].path).unwrap(), b"hidden data");
+
+        assert_eq!(
+            split_stream(&streams[0].path),
+            Some((file.clone(), "extra".to_string()))
+        );
+        assert_eq!(split_stream(&file), None);
+        assert_eq!(split_stream(Path::new(r"C:\")), None);
+    }
+}
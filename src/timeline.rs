@@ -0,0 +1,193 @@
+// 批次耗时分解
+//
+// 记录一次批处理中扫描开始/结束、首个文件开始计算、最后一个文件完成的时刻，以及后台
+// 写入缓存的累计耗时。批次结束后据此显示各阶段所占的时间，帮助判断瓶颈在扫描大量
+// 小文件、计算本身还是缓存写入。
+
+use std::time::{Duration, Instant};
+
+/// 一次批处理中各阶段的时间标记
+#[derive(Debug, Clone, Default)]
+pub struct BatchTimeline {
+    scan_started: Option<Instant>,
+    scan_finished: Option<Instant>,
+    /// 尚未结束的扫描请求数，全部结束时才记录扫描结束
+    pending_scans: usize,
+    /// 开始计算后才发起的扫描属于下一批次
+    next_scan_started: Option<Instant>,
+    next_scan_finished: Option<Instant>,
+    compute_started: Option<Instant>,
+    last_completed: Option<Instant>,
+    /// 后台写入缓存的累计耗时，与计算并行进行
+    cache_save: Duration,
+}
+
+/// 各阶段的耗时
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeBreakdown {
+    pub scanning: Duration,
+    /// 扫描结束到开始计算之间（自动计算的防抖等待或手动点击前的空闲）
+    pub waiting: Duration,
+    pub hashing: Duration,
+    /// 不计入总耗时
+    pub cache_save: Duration,
+    /// 从最早的标记到最后一个文件完成
+    pub total: Duration,
+}
+
+impl TimeBreakdown {
+    /// 某一阶段占总耗时的比例（0.0 - 1.0）
+    pub fn share(&self, part: Duration) -> f64 {
+        if self.total.is_zero() {
+            0.0
+        } else {
+            (part.as_secs_f64() / self.total.as_secs_f64()).min(1.0)
+        }
+    }
+}
+
+impl BatchTimeline {
+    pub fn scan_started(&mut self, now: Instant) {
+        if self.compute_started.is_some() {
+            self.next_scan_started.get_or_insert(now);
+        } else {
+            self.scan_started.get_or_insert(now);
+        }
+        self.pending_scans += 1;
+    }
+
+    pub fn scan_finished(&mut self, now: Instant) {
+        self.pending_scans = self.pending_scans.saturating_sub(1);
+        if self.pending_scans > 0 {
+            return;
+        }
+        if self.next_scan_started.is_some() {
+            self.next_scan_finished = Some(now);
+        } else if self.scan_started.is_some() {
+            self.scan_finished = Some(now);
+        }
+    }
+
+    pub fn file_started(&mut self, now: Instant) {
+        self.compute_started.get_or_insert(now);
+    }
+
+    /// 文件完成、失败或被发现已删除
+    pub fn file_finished(&mut self, now: Instant) {
+        self.last_completed = Some(now);
+    }
+
+    pub fn cache_saved(&mut self, duration: Duration) {
+        self.cache_save += duration;
+    }
+
+    /// 是否已经开始计算
+    pub fn has_computed(&self) -> bool {
+        self.compute_started.is_some()
+    }
+
+    /// 批次结束时取出已记录的标记；计算期间发起的扫描留给下一批次
+    pub fn take_finished(&mut self) -> BatchTimeline {
+        let next = BatchTimeline {
+            scan_started: self.next_scan_started,
+            scan_finished: self.next_scan_finished,
+            pending_scans: self.pending_scans,
+            ..BatchTimeline::default()
+        };
+        std::mem::replace(self, next)
+    }
+
+    /// 按时间顺序排列的已发生的标记
+    pub fn markers(&self) -> Vec<(&'static str, Instant)> {
+        let mut markers: Vec<(&'static str, Instant)> = [
+            ("scan_started", self.scan_started),
+            ("scan_finished", self.scan_finished),
+            ("compute_started", self.compute_started),
+            ("last_completed", self.last_completed),
+        ]
+        .into_iter()
+        .filter_map(|(name, time)| time.map(|time| (name, time)))
+        .collect();
+        // 标记相同时保持上面的先后顺序
+        markers.sort_by_key(|&(_, time)| time);
+        markers
+    }
+
+    /// 还没有文件完成时返回 None
+    pub fn breakdown(&self) -> Option<TimeBreakdown> {
+        let compute_started = self.compute_started?;
+        let last_completed = self.last_completed?;
+        let since = |end: Instant, start: Option<Instant>| {
+            start.map_or(Duration::ZERO, |start| end.saturating_duration_since(start))
+        };
+
+        let scanning = self
+            .scan_finished
+            .map_or(Duration::ZERO, |end| since(end, self.scan_started));
+        let waiting = since(compute_started, self.scan_finished);
+        let hashing = last_completed.saturating_duration_since(compute_started);
+        let (_, first) = self.markers()[0];
+        Some(TimeBreakdown {
+            scanning,
+            waiting,
+            hashing,
+            cache_save: self.cache_save,
+            total: last_completed.saturating_duration_since(first),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_markers_are_ordered() {
+        let base = Instant::now();
+        let at = |ms| base + Duration::from_millis(ms);
+
+        let mut timeline = BatchTimeline::default();
+        // 两次拖放：第二次扫描结束时才算扫描结束
+        timeline.scan_started(at(0));
+        timeline.scan_started(at(100));
+        timeline.scan_finished(at(400));
+        timeline.scan_finished(at(900));
+        timeline.file_started(at(1000));
+        timeline.file_started(at(1010));
+        timeline.cache_saved(Duration::from_millis(30));
+        timeline.file_finished(at(1500));
+        timeline.cache_saved(Duration::from_millis(20));
+        timeline.file_finished(at(1900));
+
+        let names: Vec<&str> = timeline.markers().iter().map(|&(name, _)| name).collect();
+        assert_eq!(
+            names,
+            [
+                "scan_started",
+                "scan_finished",
+                "compute_started",
+                "last_completed"
+            ]
+        );
+        assert!(timeline.markers().windows(2).all(|w| w[0].1 <= w[1].1));
+
+        let breakdown = timeline.breakdown().unwrap();
+        assert_eq!(breakdown.scanning, Duration::from_millis(900));
+        assert_eq!(breakdown.waiting, Duration::from_millis(100));
+        assert_eq!(breakdown.hashing, Duration::from_millis(900));
+        assert_eq!(breakdown.cache_save, Duration::from_millis(50));
+        assert_eq!(breakdown.total, Duration::from_millis(1900));
+        assert!((breakdown.share(breakdown.scanning) - 900.0 / 1900.0).abs() < 1e-9);
+
+        // 批次结束时仍在进行的扫描留给下一批次
+        timeline.scan_started(at(2000));
+        let finished = timeline.take_finished();
+        assert_eq!(
+            finished.breakdown().unwrap().total,
+            Duration::from_millis(1900)
+        );
+        assert!(!timeline.has_computed());
+        timeline.scan_finished(at(2500));
+        assert_eq!(timeline.markers().len(), 2);
+    }
+}
@@ -0,0 +1,118 @@
+// 系统托盘模块（可选特性 `tray`，仅 Windows/macOS）
+
+use eframe::egui;
+
+use crate::error::HashResult;
+
+#[cfg(all(feature = "tray", any(windows, target_os = "macos")))]
+mod imp {
+    use eframe::egui;
+    use tray_icon::{
+        Icon, MouseButton, MouseButtonState, TrayIcon, TrayIconBuilder, TrayIconEvent,
+    };
+
+    use crate::error::{HashError, HashResult};
+
+    const ICON_SIZE: u32 = 16;
+
+    pub struct TrayHandle {
+        icon: TrayIcon,
+    }
+
+    impl TrayHandle {
+        pub const SUPPORTED: bool = true;
+
+        pub fn create(ctx: &egui::Context) -> HashResult<Self> {
+            let icon = Icon::from_rgba(icon_rgba(), ICON_SIZE, ICON_SIZE)
+                .map_err(|e| HashError::SystemResource(format!("托盘图标无效: {}", e)))?;
+            let tray = TrayIconBuilder::new()
+                .with_icon(icon)
+                .with_tooltip("TurboHash")
+                .build()
+                .map_err(|e| HashError::SystemResource(format!("创建托盘图标失败: {}", e)))?;
+
+            // 窗口隐藏时 update() 不一定会被调用，因此直接在事件回调中恢复窗口
+            let ctx = ctx.clone();
+            TrayIconEvent::set_event_handler(Some(move |event| {
+                let restore = matches!(
+                    event,
+                    TrayIconEvent::Click {
+                        button: MouseButton::Left,
+                        button_state: MouseButtonState::Up,
+                        ..
+                    } | TrayIconEvent::DoubleClick { .. }
+                );
+                if restore {
+                    ctx.send_viewport_cmd(egui::ViewportCommand::Visible(true));
+                    ctx.send_viewport_cmd(egui::ViewportCommand::Focus);
+                    ctx.request_repaint();
+                }
+            }));
+
+            Ok(Self { icon: tray })
+        }
+
+        pub fn set_tooltip(&self, text: &str) {
+            let _ = self.icon.set_tooltip(Some(text));
+        }
+    }
+
+    impl Drop for TrayHandle {
+        fn drop(&mut self) {
+            TrayIconEvent::set_event_handler(None::<fn(TrayIconEvent)>);
+        }
+    }
+
+    /// 16x16 蓝色方块图标，带浅色描边
+    fn icon_rgba() -> Vec<u8> {
+        let mut rgba = Vec::with_capacity((ICON_SIZE * ICON_SIZE * 4) as usize);
+        for y in 0..ICON_SIZE {
+            for x in 0..ICON_SIZE {
+                let border = x == 0 || y == 0 || x == ICON_SIZE - 1 || y == ICON_SIZE - 1;
+                let pixel = if border {
+                    [0x9C, 0xC8, 0xFF, 0xFF]
+                } else {
+                    [0x1E, 0x6F, 0xD9, 0xFF]
+                };
+                rgba.extend_from_slice(&pixel);
+            }
+        }
+        rgba
+    }
+}
+
+#[cfg(not(all(feature = "tray", any(windows, target_os = "macos"))))]
+mod imp {
+    use eframe::egui;
+
+    use crate::error::{HashError, HashResult};
+
+    /// 未启用 `tray` 特性或平台不支持时的占位实现
+    pub struct TrayHandle;
+
+    impl TrayHandle {
+        pub const SUPPORTED: bool = false;
+
+        pub fn create(_ctx: &egui::Context) -> HashResult<Self> {
+            Err(HashError::SystemResource(
+                "当前构建不支持系统托盘".to_string(),
+            ))
+        }
+
+        pub fn set_tooltip(&self, _text: &str) {}
+    }
+}
+
+pub use imp::TrayHandle;
+
+/// 创建托盘图标，失败时记录日志并降级为无托盘
+pub fn try_create_tray(ctx: &egui::Context) -> Option<TrayHandle> {
+    let result: HashResult<TrayHandle> = TrayHandle::create(ctx);
+    match result {
+        Ok(handle) => Some(handle),
+        Err(e) => {
+            log::warn!("{}", e);
+            None
+        }
+    }
+}
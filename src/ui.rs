@@ -1,29 +1,401 @@
 // GUI主逻辑模块
 
 use crossbeam_channel::{Receiver, Sender};
-use std::collections::HashMap;
-use std::path::PathBuf;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 
 use dunce;
+use egui::scroll_area::ScrollBarVisibility;
 use egui::{self, CentralPanel, ScrollArea, TopBottomPanel, Widget};
 use egui_extras::{Column, TableBuilder};
 
-use crate::cache::{CacheConfig, CacheEntry, HashCache};
+use crate::analysis::{
+    AuditFinding, AuditReport, ExtensionGroup, FileComparison, HashCollision, NameConflict,
+    SourceFilter, SourceSummary, find_collisions, find_name_conflicts, summarize_by_extension,
+    summarize_sources,
+};
+use crate::benchmark::{BenchmarkReport, GENERATED_SIZE};
+use crate::cache::{
+    AutoComputeMode, CacheConfig, CacheEntry, CacheValidation, CleanupKind, CleanupOutcome,
+    CleanupProgress, ComputeOrder, CopyFeedback, DiskConcurrency, HashCache, HashEncoding,
+    PerformanceMode, SizeUnit, SymlinkPolicy, UiLanguage, VacuumSchedule, modified_time_within,
+    parse_dir_names, push_recent_paths, unix_now,
+};
+use crate::columns::{ColumnWidths, TableColumn};
+use crate::engine::{
+    ChunkedHashes, DEFAULT_HASH_CHUNK, FileHashes, PERFORMANCE_PRESETS, PerformancePreset,
+    ROTATIONAL_THREADS, RemovableVolumes,
+};
 use crate::error::{HashError, HashResult};
+use crate::export::{
+    ChecksumFormat, HexCase, StreamExport, StreamFormat, VerifyShell, format_checksum_file,
+    format_chunk_list, verify_command,
+};
+use crate::folders::{FolderNode, FolderRow, FolderSummary, build_folder_tree};
 use crate::font::load_chinese_font;
+use crate::hash::HashAlgorithm;
+use crate::hash_timing::AlgorithmTiming;
+use crate::i18n::{self, Text, t, tf};
+use crate::notification::send_notification;
+use crate::power::SleepInhibitor;
 use crate::progress::ProgressTracker;
-use crate::utils::format_duration;
-use crate::worker::{UiMessage, WorkerMessage, WorkerThread};
+use crate::remote;
+use crate::rename::{RenameAction, RenameOutcome, RenamePlanEntry};
+use crate::scanner::DiscoveredFile;
+use crate::timeline::BatchTimeline;
+use crate::tray::{TrayHandle, try_create_tray};
+use crate::update::{self, UpdateInfo};
+use crate::utils::{
+    format_age, format_count, format_digest, format_duration, format_modified_time, format_size,
+    format_timestamp, open_with_system, parse_path_list, read_clipboard_text,
+    reveal_in_file_manager, sri_digest,
+};
+use crate::verify::{
+    ChecksumSource, ExpectedHash, HashList, HashSearch, ManifestReport, manifest_key,
+    search_by_hash, verify_against,
+};
+use crate::worker::{
+    FileStat, MIN_UI_CHANNEL_CAPACITY, RehashParams, RehashReport, UiMessage, WorkerMessage,
+    WorkerThread,
+};
+use crate::xattrs::{self, XattrReport, XattrTarget};
 
 /// 文件状态
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum FileStatus {
     Pending,
     Computing,
     Completed,
     Failed,
+    /// 扫描后、计算前文件已被删除或移走
+    Deleted,
+    Cancelled,
+    /// 仅完成快速预筛（非权威结果）
+    QuickHashed,
+    /// 超过最大文件大小，未加入计算队列
+    Oversized,
+}
+
+/// 状态列的显示样式：符号 + 颜色 + 文字，文字保留以免只靠颜色区分
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatusBadge {
+    Pending,
+    Computing,
+    Completed,
+    Cached,
+    Unchanged,
+    Failed,
+    Deleted,
     Cancelled,
+    QuickHashed,
+    Oversized,
+}
+
+impl StatusBadge {
+    /// 图例中的显示顺序
+    pub const ALL: [StatusBadge; 10] = [
+        StatusBadge::Pending,
+        StatusBadge::Computing,
+        StatusBadge::Completed,
+        StatusBadge::Cached,
+        StatusBadge::Unchanged,
+        StatusBadge::Failed,
+        StatusBadge::Deleted,
+        StatusBadge::Cancelled,
+        StatusBadge::QuickHashed,
+        StatusBadge::Oversized,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            StatusBadge::Pending => t(Text::StatusPending),
+            StatusBadge::Computing => t(Text::StatusComputing),
+            StatusBadge::Completed => t(Text::StatusCompleted),
+            StatusBadge::Cached => t(Text::StatusCached),
+            StatusBadge::Unchanged => t(Text::StatusUnchanged),
+            StatusBadge::Failed => t(Text::StatusFailed),
+            StatusBadge::Deleted => t(Text::StatusDeleted),
+            StatusBadge::Cancelled => t(Text::StatusCancelled),
+            StatusBadge::QuickHashed => t(Text::StatusQuickHashed),
+            StatusBadge::Oversized => t(Text::StatusOversized),
+        }
+    }
+
+    /// 符号均取自 egui 自带的 emoji 字体，不依赖系统字体
+    pub fn glyph(self) -> &'static str {
+        match self {
+            StatusBadge::Pending => "⏳",
+            StatusBadge::Computing => "🔄",
+            StatusBadge::Completed => "✔",
+            StatusBadge::Cached => "⚡",
+            StatusBadge::Unchanged => "⏸",
+            StatusBadge::Failed => "✖",
+            StatusBadge::Deleted => "🗑",
+            StatusBadge::Cancelled => "⏹",
+            StatusBadge::QuickHashed => "🔍",
+            StatusBadge::Oversized => "⏭",
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            StatusBadge::Pending => t(Text::StatusPendingHint),
+            StatusBadge::Computing => t(Text::StatusComputingHint),
+            StatusBadge::Completed => t(Text::StatusCompletedHint),
+            StatusBadge::Cached => t(Text::StatusCachedHint),
+            StatusBadge::Unchanged => t(Text::StatusUnchangedHint),
+            StatusBadge::Failed => t(Text::StatusFailedHint),
+            StatusBadge::Deleted => t(Text::StatusDeletedHint),
+            StatusBadge::Cancelled => t(Text::StatusCancelledHint),
+            StatusBadge::QuickHashed => t(Text::StatusQuickHashedHint),
+            StatusBadge::Oversized => t(Text::StatusOversizedHint),
+        }
+    }
+
+    /// 按当前主题取色，深色主题使用更亮的色调以保证对比度
+    pub fn color(self, visuals: &egui::Visuals) -> egui::Color32 {
+        use egui::Color32;
+        let dark = visuals.dark_mode;
+        match self {
+            StatusBadge::Pending | StatusBadge::Cancelled | StatusBadge::Deleted => {
+                visuals.weak_text_color()
+            }
+            StatusBadge::Computing | StatusBadge::QuickHashed => {
+                if dark {
+                    Color32::from_rgb(110, 170, 255)
+                } else {
+                    Color32::from_rgb(20, 100, 200)
+                }
+            }
+            StatusBadge::Completed => {
+                if dark {
+                    Color32::from_rgb(110, 210, 110)
+                } else {
+                    Color32::from_rgb(30, 130, 50)
+                }
+            }
+            StatusBadge::Cached => {
+                if dark {
+                    Color32::from_rgb(235, 195, 70)
+                } else {
+                    Color32::from_rgb(165, 115, 0)
+                }
+            }
+            StatusBadge::Unchanged => {
+                if dark {
+                    Color32::from_rgb(120, 190, 190)
+                } else {
+                    Color32::from_rgb(30, 120, 120)
+                }
+            }
+            StatusBadge::Failed => visuals.error_fg_color,
+            StatusBadge::Oversized => visuals.warn_fg_color,
+        }
+    }
+
+    pub fn rich_text(self, visuals: &egui::Visuals) -> egui::RichText {
+        egui::RichText::new(format!("{} {}", self.glyph(), self.label())).color(self.color(visuals))
+    }
+}
+
+/// 等待计算的文件路径与总大小；`rows` 为 None 时取整个队列，否则只取其中的行
+fn pending_batch(files: &[FileItem], rows: Option<&HashSet<usize>>) -> (Vec<(PathBuf, u64)>, u64) {
+    let pending: Vec<&FileItem> = files
+        .iter()
+        .enumerate()
+        .filter(|(idx, _)| rows.is_none_or(|rows| rows.contains(idx)))
+        .map(|(_, file)| file)
+        .filter(|f| matches!(f.status, FileStatus::Pending))
+        .collect();
+    (
+        pending.iter().map(|f| (f.path.clone(), f.size)).collect(),
+        pending.iter().map(|f| f.size).sum(),
+    )
+}
+
+/// 可拖动调整宽度的列的默认宽度与范围：文件名、备注与哈希列
+fn resizable_width(column: TableColumn) -> Option<(f32, f32, f32)> {
+    match column {
+        TableColumn::Filename => Some((200.0, 100.0, 400.0)),
+        TableColumn::Note => Some((160.0, 80.0, 400.0)),
+        TableColumn::Hash(algorithm) => Some(match algorithm {
+            HashAlgorithm::Crc32 | HashAlgorithm::Adler32 => (100.0, 80.0, f32::INFINITY),
+            HashAlgorithm::Crc64 => (170.0, 130.0, 190.0),
+            HashAlgorithm::Md5 | HashAlgorithm::Xxh3 => (290.0, 180.0, 300.0),
+            HashAlgorithm::Sha1 => (330.0, 230.0, 360.0),
+            HashAlgorithm::Sha256 => (520.0, 300.0, 540.0),
+        }),
+        _ => None,
+    }
+}
+
+/// 可调整的列本次的初始宽度：保存过的宽度，否则为默认宽度
+fn initial_width(column: TableColumn, widths: ColumnWidths) -> Option<f32> {
+    let (default, min, max) = resizable_width(column)?;
+    Some(widths.get(column).unwrap_or(default).clamp(min, max))
+}
+
+/// 各列的宽度设置，最后一列占满剩余空间且不可调整
+fn table_column(column: TableColumn, is_last: bool, widths: ColumnWidths) -> Column {
+    if let Some((_, min, max)) = resizable_width(column) {
+        return if is_last {
+            Column::remainder().at_least(min).clip(true)
+        } else {
+            Column::initial(initial_width(column, widths).unwrap_or(min))
+                .range(min..=max)
+                .resizable(true)
+                .clip(true)
+        };
+    }
+    if is_last {
+        Column::remainder().clip(true)
+    } else {
+        Column::exact(cell_width(column, widths))
+    }
+}
+
+/// 按文件夹分组时的键：(文件列表的变化计数, 只看问题, 来源筛选)
+type FolderTreeKey = (u64, bool, SourceFilter);
+
+/// 按文件夹分组视图中每层缩进一格
+fn indent_row(ui: &mut egui::Ui, depth: usize) {
+    let indent = ui.spacing().indent;
+    for _ in 0..depth {
+        ui.add_space(indent);
+    }
+}
+
+/// 不使用表格时（按文件夹分组）各列的固定宽度，与表格中的初始宽度一致
+fn cell_width(column: TableColumn, widths: ColumnWidths) -> f32 {
+    if let Some(width) = initial_width(column, widths) {
+        return width;
+    }
+    match column {
+        TableColumn::Status | TableColumn::ShortId => 90.0,
+        TableColumn::Extension => 70.0,
+        TableColumn::Modified | TableColumn::Progress => 150.0,
+        _ => 100.0,
+    }
+}
+
+/// 「打开所在文件夹」的目标：下标最小的选中行，未选中时为第一个已完成的文件
+fn reveal_target(files: &[FileItem], selected: &HashSet<usize>) -> Option<usize> {
+    selected
+        .iter()
+        .copied()
+        .filter(|&idx| idx < files.len())
+        .min()
+        .or_else(|| {
+            files
+                .iter()
+                .position(|f| matches!(f.status, FileStatus::Completed))
+        })
+}
+
+/// 状态列中同名校验文件的比对结果
+fn render_sidecar_mark(ui: &mut egui::Ui, expected: &ExpectedHash, file: &FileItem) {
+    let sidecar = expected.sidecar.name();
+    let (text, color, hover) = match expected.check(file) {
+        Some(true) => (
+            "✓",
+            egui::Color32::GREEN,
+            tf(Text::SidecarMatch, &[&sidecar]),
+        ),
+        Some(false) => (
+            "✗",
+            egui::Color32::RED,
+            tf(
+                Text::SidecarMismatch,
+                &[&sidecar, &expected.algorithm.label(), &expected.hex],
+            ),
+        ),
+        None if matches!(file.status, FileStatus::Completed) => (
+            "?",
+            ui.visuals().weak_text_color(),
+            tf(
+                Text::SidecarNotComputed,
+                &[&expected.algorithm.label(), &sidecar],
+            ),
+        ),
+        None => (
+            "🔗",
+            ui.visuals().weak_text_color(),
+            tf(Text::SidecarPending, &[&sidecar]),
+        ),
+    };
+    ui.label(egui::RichText::new(text).color(color).strong())
+        .on_hover_text(hover);
+}
+
+/// 各哈希算法的累计耗时，按耗时从多到少排列
+fn render_hash_timings(ui: &mut egui::Ui, timings: &[AlgorithmTiming], unit: SizeUnit) {
+    let total: f64 = timings
+        .iter()
+        .map(|timing| timing.elapsed.as_secs_f64())
+        .sum();
+    ui.label(egui::RichText::new(t(Text::AlgorithmTimes)).strong());
+    egui::Grid::new("hash_timing_grid")
+        .num_columns(4)
+        .spacing([12.0, 6.0])
+        .show(ui, |ui| {
+            for timing in timings {
+                ui.label(timing.algorithm.label());
+                ui.label(format_duration(timing.elapsed.as_millis() as u64))
+                    .on_hover_text(format_size(timing.bytes, unit));
+                ui.label(tf(
+                    Text::Throughput,
+                    &[&format!("{:.0}", timing.throughput())],
+                ));
+                let share = if total > 0.0 {
+                    timing.elapsed.as_secs_f64() / total
+                } else {
+                    0.0
+                };
+                ui.add(
+                    egui::ProgressBar::new(share as f32)
+                        .desired_width(160.0)
+                        .show_percentage(),
+                );
+                ui.end_row();
+            }
+        });
+    ui.label(
+        egui::RichText::new(t(Text::AlgorithmTimesNote))
+            .small()
+            .weak(),
+    );
+}
+
+/// 批次结束时是否发送系统通知：窗口聚焦时不通知；启用托盘时窗口多半已收进托盘，
+/// 每个批次都通知，否则按设置中的耗时阈值
+fn should_notify_completion(
+    config: &CacheConfig,
+    tray: bool,
+    duration_ms: u64,
+    focused: bool,
+) -> bool {
+    !focused
+        && (tray
+            || (config.notify_on_complete && duration_ms >= config.notify_threshold_secs * 1000))
+}
+
+/// 状态列表头的悬停图例
+fn render_status_legend(ui: &mut egui::Ui) {
+    egui::Grid::new("status_legend")
+        .num_columns(2)
+        .spacing([12.0, 4.0])
+        .show(ui, |ui| {
+            for badge in StatusBadge::ALL {
+                ui.label(badge.rich_text(ui.visuals()));
+                ui.label(badge.description());
+                ui.end_row();
+            }
+            ui.label(egui::RichText::new("✓ / ✗").strong());
+            ui.label(t(Text::ClipboardVerifyLegend));
+            ui.end_row();
+        });
 }
 
 /// 文件项
@@ -31,36 +403,183 @@ pub enum FileStatus {
 pub struct FileItem {
     pub path: PathBuf,
     pub size: u64,
-    pub size_str: String,
     pub status: FileStatus,
     pub crc32: String,
     pub md5: String,
     pub sha1: String,
+    pub sha256: String,
+    pub crc64: String,
+    pub adler32: String,
     pub xxhash3: String,
     pub progress: f64,
     pub from_cache: bool,
+    /// 仅新增/变更模式下按元数据判定未变更
+    pub unchanged: bool,
+    pub quick_hash: Option<String>,
+    pub modified_time: Option<u64>,
+    /// 计算时间（Unix 秒），命中缓存时为写入缓存的时间
+    pub computed_at: Option<u64>,
+    /// 用户备注，按规范化路径保存在数据库中
+    pub note: String,
+    /// 同名校验文件中的期望哈希，计算完成后自动比对
+    pub expected: Option<ExpectedHash>,
+    /// 最近一次计算失败的原因
+    pub error: Option<String>,
     computation_start_time: Option<std::time::Instant>,
     computation_duration_ms: Option<u64>,
+    clipboard_verify: Option<(bool, std::time::Instant)>,
+}
+
+/// 刷新单个文件元数据的结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MetadataRefresh {
+    Unchanged,
+    /// 大小或修改时间变化（或已删除的文件重新出现），已改回等待计算
+    Changed,
+    /// 文件已被删除或移走
+    Missing,
 }
 
 impl FileItem {
     // 现在接收 size，不再进行 IO 操作
     pub fn new(path: PathBuf, size: u64) -> Self {
-        let size_str = humansize::format_size(size, humansize::BINARY);
-
         Self {
             path,
             size,
-            size_str,
             status: FileStatus::Pending,
             crc32: String::new(),
             md5: String::new(),
             sha1: String::new(),
+            sha256: String::new(),
+            crc64: String::new(),
+            adler32: String::new(),
             xxhash3: String::new(),
             progress: 0.0,
             from_cache: false,
+            unchanged: false,
+            quick_hash: None,
+            modified_time: None,
+            computed_at: None,
+            note: String::new(),
+            expected: None,
+            error: None,
             computation_start_time: None,
             computation_duration_ms: None,
+            clipboard_verify: None,
+        }
+    }
+
+    /// 按重新读取的大小与修改时间更新，发生变化时清空结果并改回等待计算
+    fn refresh_metadata(
+        &mut self,
+        stat: Option<FileStat>,
+        mtime_tolerance_ms: u64,
+    ) -> MetadataRefresh {
+        let Some(FileStat {
+            size,
+            modified_time,
+        }) = stat
+        else {
+            if matches!(self.status, FileStatus::Deleted) {
+                return MetadataRefresh::Unchanged;
+            }
+            self.status = FileStatus::Deleted;
+            return MetadataRefresh::Missing;
+        };
+        let mtime_changed = match (self.modified_time, modified_time) {
+            (Some(old), Some(new)) => !modified_time_within(old, new, mtime_tolerance_ms),
+            (Some(_), None) => true,
+            (None, _) => false,
+        };
+        let reappeared = matches!(self.status, FileStatus::Deleted);
+        if size == self.size && !mtime_changed && !reappeared {
+            return MetadataRefresh::Unchanged;
+        }
+
+        let path = std::mem::take(&mut self.path);
+        let previous = std::mem::replace(self, Self::new(path, size));
+        self.modified_time = modified_time;
+        self.note = previous.note;
+        self.expected = previous.expected;
+        // 超过大小上限的文件仍需手动「仍然计算」
+        if matches!(previous.status, FileStatus::Oversized) {
+            self.status = FileStatus::Oversized;
+        }
+        MetadataRefresh::Changed
+    }
+
+    /// 清空结果并改回等待计算，保留备注与期望哈希
+    fn reset_results(&mut self) {
+        let path = std::mem::take(&mut self.path);
+        let size = self.size;
+        let previous = std::mem::replace(self, Self::new(path, size));
+        self.modified_time = previous.modified_time;
+        self.note = previous.note;
+        self.expected = previous.expected;
+    }
+
+    /// 已完成并带有给定哈希的文件项，各模块的测试共用
+    #[cfg(test)]
+    pub fn completed(
+        path: impl Into<PathBuf>,
+        size: u64,
+        hashes: &[(HashAlgorithm, &str)],
+    ) -> Self {
+        let mut item = Self::new(path.into(), size);
+        item.status = FileStatus::Completed;
+        for &(algorithm, value) in hashes {
+            let field = match algorithm {
+                HashAlgorithm::Crc32 => &mut item.crc32,
+                HashAlgorithm::Crc64 => &mut item.crc64,
+                HashAlgorithm::Adler32 => &mut item.adler32,
+                HashAlgorithm::Md5 => &mut item.md5,
+                HashAlgorithm::Sha1 => &mut item.sha1,
+                HashAlgorithm::Sha256 => &mut item.sha256,
+                HashAlgorithm::Xxh3 => &mut item.xxhash3,
+            };
+            *field = value.to_string();
+        }
+        item
+    }
+
+    pub fn hash_value(&self, algorithm: HashAlgorithm) -> &str {
+        match algorithm {
+            HashAlgorithm::Crc32 => &self.crc32,
+            HashAlgorithm::Crc64 => &self.crc64,
+            HashAlgorithm::Adler32 => &self.adler32,
+            HashAlgorithm::Md5 => &self.md5,
+            HashAlgorithm::Sha1 => &self.sha1,
+            HashAlgorithm::Sha256 => &self.sha256,
+            HashAlgorithm::Xxh3 => &self.xxhash3,
+        }
+    }
+
+    /// 可用的最强摘要：SHA256 > SHA1 > MD5
+    pub fn strong_digest(&self) -> &str {
+        [&self.sha256, &self.sha1, &self.md5]
+            .into_iter()
+            .find(|h| !h.is_empty())
+            .map_or("", String::as_str)
+    }
+
+    /// XXH3 的前 8 位十六进制，尚未计算时为空
+    pub fn short_id(&self) -> &str {
+        self.xxhash3.get(..8).unwrap_or_default()
+    }
+
+    /// 写入扩展属性的内容，属性名为 `user.<算法>`
+    fn xattr_target(&self) -> XattrTarget {
+        XattrTarget {
+            path: self.path.clone(),
+            hashes: vec![
+                ("crc32", self.crc32.clone()),
+                ("crc64", self.crc64.clone()),
+                ("adler32", self.adler32.clone()),
+                ("md5", self.md5.clone()),
+                ("sha1", self.sha1.clone()),
+                ("sha256", self.sha256.clone()),
+                ("xxh3", self.xxhash3.clone()),
+            ],
         }
     }
 
@@ -68,18 +587,43 @@ impl FileItem {
         self.path
             .file_name()
             .and_then(|n| n.to_str())
-            .unwrap_or("无效文件名")
+            .unwrap_or(t(Text::InvalidFileName))
             .to_string()
     }
 
-    pub fn status_icon(&self) -> &str {
+    /// 小写扩展名，无扩展名时为 None
+    pub fn extension(&self) -> Option<String> {
+        self.path
+            .extension()
+            .map(|ext| ext.to_string_lossy().to_lowercase())
+    }
+
+    /// 批次结束后需要关注的行：失败、已删除、已取消、因过大跳过，或与期望哈希不一致
+    pub fn has_problem(&self) -> bool {
+        matches!(
+            self.status,
+            FileStatus::Failed
+                | FileStatus::Deleted
+                | FileStatus::Cancelled
+                | FileStatus::Oversized
+        ) || self.expected.as_ref().is_some_and(|expected| {
+            // 不一致，或已完成但没有计算校验文件所用的算法，无法校验
+            matches!(self.status, FileStatus::Completed) && expected.check(self) != Some(true)
+        }) || self.clipboard_verify.is_some_and(|(ok, _)| !ok)
+    }
+
+    pub fn status_badge(&self) -> StatusBadge {
         match &self.status {
-            FileStatus::Pending => "等待",
-            FileStatus::Computing => "计算",
-            FileStatus::Completed if self.from_cache => "缓存",
-            FileStatus::Completed => "完成",
-            FileStatus::Failed => "失败",
-            FileStatus::Cancelled => "取消",
+            FileStatus::Pending => StatusBadge::Pending,
+            FileStatus::Computing => StatusBadge::Computing,
+            FileStatus::Completed if self.unchanged => StatusBadge::Unchanged,
+            FileStatus::Completed if self.from_cache => StatusBadge::Cached,
+            FileStatus::Completed => StatusBadge::Completed,
+            FileStatus::Failed => StatusBadge::Failed,
+            FileStatus::Deleted => StatusBadge::Deleted,
+            FileStatus::Cancelled => StatusBadge::Cancelled,
+            FileStatus::QuickHashed => StatusBadge::QuickHashed,
+            FileStatus::Oversized => StatusBadge::Oversized,
         }
     }
 
@@ -89,6 +633,337 @@ impl FileItem {
             None => String::from("-"),
         }
     }
+
+    /// 实际计算的吞吐量，缓存命中时没有意义
+    pub fn throughput_str(&self, unit: SizeUnit) -> String {
+        match self.computation_duration_ms {
+            Some(ms) if ms > 0 && !self.from_cache => {
+                format!(
+                    "{}/s",
+                    format_size(self.size.saturating_mul(1000) / ms, unit)
+                )
+            }
+            _ => String::from("-"),
+        }
+    }
+}
+
+/// 目录合并哈希窗口的状态
+struct DirectoryHashView {
+    root: PathBuf,
+    // None 表示仍在计算
+    result: Option<Result<FileHashes, String>>,
+}
+
+/// 导出校验文件窗口的状态
+struct ExportDialog {
+    open: bool,
+    format: ChecksumFormat,
+    // 每种格式单独记住大小写，与界面的大写显示设置无关
+    cases: [HexCase; 4],
+    stream_format: StreamFormat,
+}
+
+impl ExportDialog {
+    /// 当前格式在 `cases` 中的下标
+    fn case_index(&self) -> usize {
+        ChecksumFormat::ALL
+            .iter()
+            .position(|f| *f == self.format)
+            .unwrap_or(0)
+    }
+}
+
+impl Default for ExportDialog {
+    fn default() -> Self {
+        Self {
+            open: false,
+            format: ChecksumFormat::Md5Sum,
+            cases: ChecksumFormat::ALL.map(ChecksumFormat::default_case),
+            stream_format: StreamFormat::Csv,
+        }
+    }
+}
+
+/// 文件对比窗口的状态
+struct ComparisonView {
+    paths: [PathBuf; 2],
+    // None 表示仍在计算
+    result: Option<Result<FileComparison, String>>,
+}
+
+/// 后台缓存清理的状态
+struct CleanupView {
+    cancel: Arc<AtomicBool>,
+    progress: Option<CleanupProgress>,
+}
+
+/// 缓存审计窗口的状态
+struct AuditView {
+    root: PathBuf,
+    // None 表示仍在核对
+    result: Option<Result<AuditReport, String>>,
+}
+
+/// 强制重新计算时与原有缓存不同的结果
+struct HashChange {
+    path: PathBuf,
+    old: FileHashes,
+    new: FileHashes,
+    /// 大小或修改时间也已变化，多半是正常修改
+    metadata_changed: bool,
+}
+
+/// 性能测试窗口的状态
+#[derive(Default)]
+struct BenchmarkView {
+    running: bool,
+    // None 表示尚未开始或仍在测试
+    result: Option<Result<BenchmarkReport, String>>,
+}
+
+/// 设置窗口中的操作，窗口绘制完成后再执行
+#[derive(Default)]
+struct SettingsActions {
+    audit: bool,
+    update_check: bool,
+    benchmark: bool,
+    cleanup: Option<CleanupKind>,
+}
+
+/// 「高级重算」窗口的状态
+struct RehashView {
+    params: RehashParams,
+    running: bool,
+    // 最近一次重算的结果
+    result: Option<Result<RehashReport, String>>,
+}
+
+/// 分块哈希窗口的状态
+struct ChunkHashView {
+    path: PathBuf,
+    chunk_size: u64,
+    // 换块大小或关闭窗口时设置，停止读取旧请求的文件
+    cancel: Arc<AtomicBool>,
+    // (已读取, 总大小)
+    progress: Option<(u64, u64)>,
+    // None 表示仍在计算
+    result: Option<Result<ChunkedHashes, String>>,
+}
+
+/// 按哈希重命名窗口的状态
+struct RenameView {
+    // (路径, SHA256, 是否选中)
+    candidates: Vec<(PathBuf, String, bool)>,
+    planning: bool,
+    // None 表示尚未生成试运行计划
+    plan: Option<Vec<RenamePlanEntry>>,
+    confirmed: bool,
+    applying: bool,
+}
+
+/// 从 URL 校验窗口的状态
+#[derive(Default)]
+struct RemoteVerifyView {
+    url: String,
+    fetching: bool,
+    error: Option<String>,
+}
+
+/// 「按清单校验」的状态：清单在窗口关闭前持续应用到扫描出的文件
+struct ManifestView {
+    list: HashMap<String, ExpectedHash>,
+    report: ManifestReport,
+    untracked: HashSet<PathBuf>,
+}
+
+impl ManifestView {
+    fn new(list: HashMap<String, ExpectedHash>, report: ManifestReport) -> Self {
+        let untracked = report.untracked.iter().cloned().collect();
+        Self {
+            list,
+            report,
+            untracked,
+        }
+    }
+
+    /// 清单所在目录下的文件按相对路径取期望哈希
+    fn expected_for(&self, path: &Path) -> Option<ExpectedHash> {
+        let dir = self.report.manifest.parent()?;
+        self.list.get(&manifest_key(dir, path)?).cloned()
+    }
+}
+
+/// 工具栏「按哈希查找」的状态
+#[derive(Default)]
+struct HashSearchView {
+    query: String,
+    result: Option<HashSearch>,
+    // 有文件完成或队列变化，需要重新查找
+    dirty: bool,
+    // 匹配多个文件时显示列表窗口
+    list_open: bool,
+    // 下一帧滚动到的行
+    scroll_to: Option<usize>,
+}
+
+/// 文件表格本帧的行信息，冻结列与滚动列两个表格按同一行号渲染
+struct TableView<'a> {
+    // 只看问题时为显示的文件下标
+    rows: Option<&'a [usize]>,
+    highlighted: &'a HashSet<usize>,
+    // 要滚动到的行号与对齐方式，None 对齐表示只滚动到刚好可见
+    scroll_to_row: Option<(usize, Option<egui::Align>)>,
+    scroll_y: f32,
+    // 上一帧鼠标所在行的文件，两个表格同时高亮
+    hovered_row: Option<usize>,
+    now: u64,
+}
+
+/// 文件表格的一侧：固定在左侧的列或横向滚动的其余列
+struct TablePart<'a> {
+    id_salt: &'static str,
+    columns: &'a [TableColumn],
+    // 最左侧的表格，焦点边框在这一侧画左边线
+    leading: bool,
+    // 最右侧的表格：最后一列占满剩余宽度，并显示垂直滚动条
+    fill: bool,
+}
+
+/// 键盘在表格中移动焦点的方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FocusMove {
+    Up,
+    Down,
+    PageUp,
+    PageDown,
+    Home,
+    End,
+}
+
+/// PageUp/PageDown 一次移动的行数
+const FOCUS_PAGE_ROWS: usize = 20;
+
+/// 焦点文件在当前显示顺序中的位置；被「只看问题」筛掉时落到其后最近的行，
+/// 其后没有行或下标越界时落到最后一行。没有可显示的行时返回 None
+fn focus_position(rows: Option<&[usize]>, file_count: usize, focused: usize) -> Option<usize> {
+    let row_count = rows.map_or(file_count, <[usize]>::len);
+    let last = row_count.checked_sub(1)?;
+    Some(match rows {
+        Some(rows) => rows.iter().position(|&idx| idx >= focused).unwrap_or(last),
+        None => focused.min(last),
+    })
+}
+
+/// 按显示顺序移动焦点，返回新的焦点文件下标；尚无焦点时从第一行（End 为最后一行）开始
+fn move_focus(
+    rows: Option<&[usize]>,
+    file_count: usize,
+    focused: Option<usize>,
+    step: FocusMove,
+) -> Option<usize> {
+    let last = rows.map_or(file_count, <[usize]>::len).checked_sub(1)?;
+    let position = match focused.and_then(|idx| focus_position(rows, file_count, idx)) {
+        None if step == FocusMove::End => last,
+        None => 0,
+        Some(position) => match step {
+            FocusMove::Up => position.saturating_sub(1),
+            FocusMove::Down => (position + 1).min(last),
+            FocusMove::PageUp => position.saturating_sub(FOCUS_PAGE_ROWS),
+            FocusMove::PageDown => (position + FOCUS_PAGE_ROWS).min(last),
+            FocusMove::Home => 0,
+            FocusMove::End => last,
+        },
+    };
+    Some(rows.map_or(position, |rows| rows[position]))
+}
+
+/// 焦点行的边框；冻结列与滚动列分属两个表格，相接处不画竖线
+fn paint_focus_ring(
+    painter: &egui::Painter,
+    rect: egui::Rect,
+    stroke: egui::Stroke,
+    part: &TablePart,
+) {
+    let rect = rect.shrink(stroke.width / 2.0);
+    painter.line_segment([rect.left_top(), rect.right_top()], stroke);
+    painter.line_segment([rect.left_bottom(), rect.right_bottom()], stroke);
+    if part.leading {
+        painter.line_segment([rect.left_top(), rect.left_bottom()], stroke);
+    }
+    if part.fill {
+        painter.line_segment([rect.right_top(), rect.right_bottom()], stroke);
+    }
+}
+
+/// 「清单筛查」的状态：窗口关闭后清单仍然生效，命中的行显示红色标记
+#[derive(Default)]
+struct HashListView {
+    open: bool,
+    text: String,
+    list: Option<HashList>,
+    /// 命中的文件数
+    matched: usize,
+    // 有文件完成或队列变化，需要重新统计
+    dirty: bool,
+}
+
+/// 「清空队列」后可撤销的时间
+const UNDO_CLEAR_SECS: u64 = 8;
+
+/// 攒够这么多条结果再提交一次缓存写入
+const CACHE_SAVE_BATCH: usize = 50;
+
+/// 缓存数据库与计算的文件在同一块慢速磁盘上时减少写入次数，每次提交更多条目
+const CONTENDED_SAVE_BATCH: usize = 1000;
+
+/// 自动模式下每帧处理消息的时间预算（约为 60fps 一帧的一半，留出绘制时间）
+const MESSAGE_FRAME_BUDGET: std::time::Duration = std::time::Duration::from_millis(8);
+
+/// 一帧内从工作线程通道取消息的预算
+///
+/// 自动模式（`limit` 为 0）先取走最小通道容量的消息，保证已阻塞的发送方每帧都能继续，
+/// 之后在时间预算内尽量多取；固定上限时最多取 `limit` 条。剩余消息留在通道中下一帧继续。
+struct MessageDrain {
+    started: std::time::Instant,
+    processed: usize,
+    limit: usize,
+}
+
+impl MessageDrain {
+    fn new(limit: usize) -> Self {
+        Self {
+            started: std::time::Instant::now(),
+            processed: 0,
+            limit,
+        }
+    }
+
+    /// 预算用完或通道为空时返回 None
+    fn next<T>(&mut self, rx: &Receiver<T>) -> Option<T> {
+        if self.exhausted() {
+            return None;
+        }
+        let msg = rx.try_recv().ok()?;
+        self.processed += 1;
+        Some(msg)
+    }
+
+    fn exhausted(&self) -> bool {
+        if self.limit > 0 {
+            self.processed >= self.limit
+        } else {
+            self.processed >= MIN_UI_CHANNEL_CAPACITY
+                && self.started.elapsed() >= MESSAGE_FRAME_BUDGET
+        }
+    }
+}
+
+/// 清空前的队列快照，用于撤销
+struct ClearedQueue {
+    files: Vec<FileItem>,
+    file_index: HashMap<PathBuf, usize>,
+    cleared_at: std::time::Instant,
 }
 
 /// TurboHash主应用
@@ -102,25 +977,121 @@ pub struct TurboHashApp {
     total_size: u64,
     processed_size: u64,
     is_computing: bool,
-    auto_compute_enabled: bool,
+    // 当前计算批次的编号，停止计算时递增以丢弃旧批次的迟到消息
+    batch_id: u64,
+    // 计算期间持有的休眠锁，批次结束或停止时释放
+    sleep_inhibitor: Option<SleepInhibitor>,
+    auto_compute: AutoComputeMode,
+    // 拖放加入的路径，扫描出的文件位于其下时视为拖放加入；扫描全部结束后清空
+    dropped_roots: Vec<PathBuf>,
+    // 已发出、尚未收到 ScanFinished 的扫描数
+    pending_scans: usize,
     last_file_add_time: Option<std::time::Instant>,
     debounce_duration_ms: u64,
     auto_compute_scheduled: bool,
     cache: Arc<Mutex<HashCache>>, // 仅用于配置读取，主要操作移至 worker
     cache_config: CacheConfig,
     show_cache_settings: bool,
+    // 设置中排除目录列表的编辑内容，失去焦点时写回配置
+    excluded_dirs_text: String,
     batch_start_time: Option<std::time::Instant>,
     batch_total_duration_ms: u64,
+    // 当前批次的扫描、计算与缓存写入时间标记
+    batch_timeline: BatchTimeline,
+    // 上一批次的时间标记，批次结束后迟到的缓存写入耗时仍计入其中
+    last_timeline: Option<BatchTimeline>,
+    show_time_breakdown: bool,
+    /// 强制重新计算时哈希已变更的文件
+    hash_changes: Vec<HashChange>,
+    show_hash_changes: bool,
     cache_operation_message: Option<String>,
     uppercase_display: bool,
     clipboard_toast: Option<(String, std::time::Instant)>,
+    status_toast: Option<(String, std::time::Instant)>,
     pending_cache_entries: Vec<CacheEntry>,
+    collision_report: Option<Vec<HashCollision>>,
+    name_conflicts: Option<Vec<NameConflict>>,
+    directory_hash: Option<DirectoryHashView>,
+    comparison: Option<ComparisonView>,
+    audit: Option<AuditView>,
+    cleanup: Option<CleanupView>,
+    benchmark: Option<BenchmarkView>,
+    rehash: Option<RehashView>,
+    chunk_hash: Option<ChunkHashView>,
+    rename: Option<RenameView>,
+    remote_verify: Option<RemoteVerifyView>,
+    manifest: Option<ManifestView>,
+    // 开启「计算完成后写入扩展属性」时本批次已完成、待写入的文件
+    xattr_pending: Vec<XattrTarget>,
+    xattr_writing: bool,
+    hash_search: HashSearchView,
+    hash_list: HashListView,
+    export_dialog: ExportDialog,
+    // 流式导出：已选择目标、等待下一批次开始时创建的文件
+    stream_export_target: Option<(PathBuf, StreamFormat)>,
+    // 当前批次正在写入的流式导出
+    stream_export: Option<StreamExport>,
+    // 检查更新发现的新版本，关闭提示后清空
+    update_available: Option<UpdateInfo>,
+    show_type_summary: bool,
+    // 表格只显示有问题的行
+    only_problems: bool,
+    // 按结果来源（缓存或重新计算）筛选表格
+    source_filter: SourceFilter,
+    // (文件数, 总大小) 未变化时复用上次的统计结果
+    type_summary: ((usize, u64), Vec<ExtensionGroup>),
+    // 表格中选中的行（文件下标），单击选中，Ctrl/Shift 单击多选
+    selected_rows: HashSet<usize>,
+    // Shift 单击时范围选择的起点
+    selection_anchor: Option<usize>,
+    // 当前批次使用的缓存校验级别（设置修改后下一批次才生效）
+    batch_validation: CacheValidation,
+    // 当前批次与缓存数据库位于同一块慢速磁盘时为批次的公共上级目录，缓存改为攒批写入
+    cache_contention: Option<PathBuf>,
+    // 键盘操作的焦点行（文件下标），显示为行的边框
+    focused_row: Option<usize>,
+    // 键盘移动焦点后需要滚动到可见的行
+    focus_scroll: Option<usize>,
+    // 按 Enter 后待打开右键菜单的行
+    open_row_menu: Option<usize>,
+    // 按文件夹分组显示，默认为平铺的表格
+    group_by_folder: bool,
+    // 分组的键未变化时复用上次的分组
+    folder_tree: (FolderTreeKey, FolderNode),
+    // (分组的键, 总进度) 未变化时复用各文件夹的汇总
+    folder_summaries: ((FolderTreeKey, u64), HashMap<PathBuf, FolderSummary>),
+    // 文件加入、改名或状态变化时递增，作为分组等由文件列表派生的数据的缓存键
+    rows_generation: u64,
+    // (文件列表的变化计数, 有问题的行)
+    problem_rows: (u64, Vec<usize>),
+    // 已完成文件的结果来源统计，随计算结果增量更新
+    sources: SourceSummary,
+    tray: Option<TrayHandle>,
+    tray_tooltip: String,
+    recent_paths: Vec<PathBuf>,
+    // 「最近」菜单已打开，打开时在后台检查一次路径是否仍然存在
+    recent_menu_open: bool,
+    // 已保存的配置方案名称
+    profile_names: Vec<String>,
+    // 最近切换到的配置方案及切换时的配置，当前配置与之不同时显示为已修改
+    active_profile: Option<(String, CacheConfig)>,
+    // 「保存当前配置」输入的方案名称
+    new_profile_name: String,
+    batch_done: Vec<PathBuf>,
+    resume_batch: Option<Vec<PathBuf>>,
+    clear_confirm_open: bool,
+    cleared_queue: Option<ClearedQueue>,
+    // 启动时是否成功加载了中文字体，决定「自动」语言的结果
+    chinese_font: bool,
+    // 文件表格的垂直滚动偏移，固定列与滚动列两个表格共用
+    table_scroll_y: f32,
+    table_hovered_row: Option<usize>,
 }
 
 impl TurboHashApp {
     pub fn new(cc: &eframe::CreationContext<'_>, initial_files: Vec<PathBuf>) -> HashResult<Self> {
         let mut fonts = egui::FontDefinitions::default();
-        load_chinese_font(&mut fonts).ok();
+        let font_result = load_chinese_font(&mut fonts);
         cc.egui_ctx.set_fonts(fonts);
 
         let cache_config = CacheConfig::default();
@@ -133,34 +1104,84 @@ impl TurboHashApp {
 
         // 初始化缓存和 Worker
         let (cache, cache_config) = match HashCache::new(&cache_path, cache_config.clone()) {
-            Ok(c) => {
-                let saved_config = c.load_cache_config();
-                match saved_config {
-                    Ok(config) => (Arc::new(Mutex::new(c)), config),
-                    Err(_) => {
-                        let auto_config = crate::engine::detect_optimal_config();
-                        (Arc::new(Mutex::new(c)), auto_config)
-                    }
-                }
+            Ok(mut c) => {
+                let config = c
+                    .load_cache_config()
+                    .unwrap_or_else(|_| crate::engine::detect_optimal_config());
+                c.set_config(config.clone());
+                (Arc::new(Mutex::new(c)), config)
             }
             Err(e) => {
-                eprintln!("[UI] 缓存初始化失败: {}", e);
+                log::error!("缓存初始化失败: {}", e);
                 // 降级到内存缓存
                 match HashCache::new(std::path::Path::new(":memory:"), cache_config.clone()) {
                     Ok(mem_cache) => (Arc::new(Mutex::new(mem_cache)), cache_config.clone()),
                     Err(mem_err) => {
-                        eprintln!("[UI] 内存缓存初始化也失败: {}, 应用程序无法继续", mem_err);
+                        log::error!("内存缓存初始化也失败: {}, 应用程序无法继续", mem_err);
                         std::process::exit(1);
                     }
                 }
             }
         };
 
+        crate::logging::set_level(cache_config.log_level);
+        crate::hash_timing::set_enabled(cache_config.hash_timing);
+
+        // 没有中文字体时汉字显示为方框，自动语言下改用英文界面
+        let chinese_font = match &font_result {
+            Ok(path) => {
+                log::info!("已加载中文字体: {}", path.display());
+                true
+            }
+            Err(e) => {
+                log::warn!("{}，使用内置字体", e);
+                false
+            }
+        };
+        let language = cache_config.ui_language.resolve(chinese_font);
+        i18n::set_language(language);
+        log::info!("界面语言: {:?}", language);
+
+        let tray = if cache_config.minimize_to_tray {
+            try_create_tray(&cc.egui_ctx)
+        } else {
+            None
+        };
         let (_worker, worker_tx, ui_rx) = WorkerThread::spawn(cache.clone());
-        let uppercase_display = cache_config.uppercase_display;
-        let auto_compute_enabled = cache_config.auto_compute_enabled;
+        let mut app = Self::with_worker(cache, cache_config, worker_tx, ui_rx, tray, chinese_font);
+
+        if !initial_files.is_empty() {
+            app.add_files(initial_files);
+        }
+        if update::SUPPORTED && app.cache_config.update_check {
+            app.check_for_update(false);
+        }
+
+        Ok(app)
+    }
+
+    /// 使用已打开的缓存与 Worker 通道构建应用状态
+    fn with_worker(
+        cache: Arc<Mutex<HashCache>>,
+        cache_config: CacheConfig,
+        worker_tx: Sender<WorkerMessage>,
+        ui_rx: Receiver<UiMessage>,
+        tray: Option<TrayHandle>,
+        chinese_font: bool,
+    ) -> Self {
+        let (recent_paths, profile_names, resume_batch) = match cache.lock() {
+            Ok(guard) => (
+                guard.load_recent_paths().unwrap_or_default(),
+                guard.profile_names().unwrap_or_default(),
+                guard
+                    .load_pending_batch()
+                    .ok()
+                    .filter(|paths| !paths.is_empty()),
+            ),
+            Err(_) => Default::default(),
+        };
 
-        let mut app = Self {
+        Self {
             files: Vec::new(),
             file_index: HashMap::new(),
             ui_rx,
@@ -170,7 +1191,13 @@ impl TurboHashApp {
             total_size: 0,
             processed_size: 0,
             is_computing: false,
-            auto_compute_enabled,
+            batch_id: 0,
+            sleep_inhibitor: None,
+            auto_compute: cache_config.auto_compute,
+            uppercase_display: cache_config.uppercase_display,
+            excluded_dirs_text: cache_config.excluded_dirs.join("\n"),
+            dropped_roots: Vec::new(),
+            pending_scans: 0,
             last_file_add_time: None,
             debounce_duration_ms: 500,
             auto_compute_scheduled: false,
@@ -179,966 +1206,7032 @@ impl TurboHashApp {
             show_cache_settings: false,
             batch_start_time: None,
             batch_total_duration_ms: 0,
+            batch_timeline: BatchTimeline::default(),
+            last_timeline: None,
+            show_time_breakdown: false,
+            hash_changes: Vec::new(),
+            show_hash_changes: false,
             cache_operation_message: None,
-            uppercase_display,
             clipboard_toast: None,
+            status_toast: None,
             pending_cache_entries: Vec::new(),
-        };
-
-        if !initial_files.is_empty() {
-            app.add_files(initial_files);
+            collision_report: None,
+            name_conflicts: None,
+            directory_hash: None,
+            audit: None,
+            cleanup: None,
+            benchmark: None,
+            rehash: None,
+            chunk_hash: None,
+            rename: None,
+            remote_verify: None,
+            manifest: None,
+            xattr_pending: Vec::new(),
+            xattr_writing: false,
+            hash_search: HashSearchView::default(),
+            hash_list: HashListView::default(),
+            comparison: None,
+            export_dialog: ExportDialog::default(),
+            stream_export_target: None,
+            stream_export: None,
+            show_type_summary: false,
+            only_problems: false,
+            source_filter: SourceFilter::All,
+            type_summary: ((0, 0), Vec::new()),
+            selected_rows: HashSet::new(),
+            selection_anchor: None,
+            batch_validation: CacheValidation::Normal,
+            cache_contention: None,
+            focused_row: None,
+            focus_scroll: None,
+            open_row_menu: None,
+            group_by_folder: false,
+            folder_tree: ((0, false, SourceFilter::All), FolderNode::default()),
+            folder_summaries: (((0, false, SourceFilter::All), 0), HashMap::new()),
+            rows_generation: 0,
+            problem_rows: (0, Vec::new()),
+            sources: SourceSummary::default(),
+            tray,
+            tray_tooltip: String::new(),
+            recent_paths,
+            recent_menu_open: false,
+            profile_names,
+            active_profile: None,
+            new_profile_name: String::new(),
+            batch_done: Vec::new(),
+            resume_batch,
+            clear_confirm_open: false,
+            cleared_queue: None,
+            chinese_font,
+            table_scroll_y: 0.0,
+            table_hovered_row: None,
+            update_available: None,
         }
-
-        Ok(app)
     }
 
     pub fn add_files(&mut self, paths: Vec<PathBuf>) {
+        push_recent_paths(
+            &mut self.recent_paths,
+            &paths,
+            self.cache_config.recent_limit,
+        );
+        self.save_recent_paths();
+
         // 仅仅是将路径发送给 Scanner，完全非阻塞
+        self.batch_timeline.scan_started(std::time::Instant::now());
+        self.pending_scans += 1;
         let _ = self.worker_tx.send(WorkerMessage::Scan(paths));
     }
 
-    fn open_file_dialog(&mut self) {
-        use rfd::FileDialog;
-        // 注意：FileDialog 可能会阻塞，通常在主线程调用是可以接受的，因为它就是模态对话框
-        if let Some(paths) = FileDialog::new()
-            .set_title("选择要计算哈希的文件")
-            .pick_files()
-        {
-            let path_bufs: Vec<PathBuf> = paths.into_iter().map(|p| p.into()).collect();
-            self.add_files(path_bufs);
+    /// 从文本（每行一个路径）批量添加文件
+    fn add_paths_from_text(&mut self, text: &str) {
+        let (paths, skipped) = parse_path_list(text);
+        let added = paths.len();
+        if !paths.is_empty() {
+            self.add_files(paths);
         }
+        self.status_toast = Some((
+            tf(Text::PathsAdded, &[&added, &skipped]),
+            std::time::Instant::now(),
+        ));
     }
 
-    fn open_folder_dialog(&mut self) {
-        use rfd::FileDialog;
-        if let Some(folder_path) = FileDialog::new()
-            .set_title("选择要计算哈希的文件夹")
-            .pick_folder()
-        {
-            self.add_files(vec![folder_path]);
+    fn paste_paths_from_clipboard(&mut self) {
+        match read_clipboard_text() {
+            Some(text) => self.add_paths_from_text(&text),
+            None => {
+                self.status_toast = Some((
+                    t(Text::ClipboardUnreadable).to_string(),
+                    std::time::Instant::now(),
+                ));
+            }
         }
     }
 
-    pub fn clear_files(&mut self) {
-        self.files.clear();
-        self.file_index.clear();
-        self.total_size = 0;
-        self.processed_size = 0;
-        self.global_progress = 0.0;
-        self.batch_start_time = None;
-        self.batch_total_duration_ms = 0;
-        self.last_file_add_time = None;
-        self.auto_compute_scheduled = false;
-        self.clipboard_toast = None;
-        if let Some(tracker) = &self.progress_tracker {
-            tracker.reset();
+    /// Ctrl+Shift+V：egui 会把它转换为粘贴事件，直接使用事件中的文本
+    fn handle_paste_shortcut(&mut self, ctx: &egui::Context) {
+        if ctx.wants_keyboard_input() {
+            return;
         }
-        self.progress_tracker = None;
-        self.pending_cache_entries.clear();
-    }
 
-    fn finalize_batch(&mut self) {
-        if let Some(start_time) = self.batch_start_time {
-            self.batch_total_duration_ms = start_time.elapsed().as_millis() as u64;
-            self.batch_start_time = None;
+        let pasted = ctx.input(|i| {
+            if !(i.modifiers.command && i.modifiers.shift) {
+                return None;
+            }
+            i.events.iter().find_map(|event| match event {
+                egui::Event::Paste(text) => Some(text.clone()),
+                _ => None,
+            })
+        });
+
+        if let Some(text) = pasted {
+            self.add_paths_from_text(&text);
         }
     }
 
-    pub fn start_computing(&mut self) {
-        if self.files.is_empty() {
-            return;
+    fn save_recent_paths(&self) {
+        if let Ok(guard) = self.cache.lock() {
+            if let Err(e) = guard.save_recent_paths(&self.recent_paths) {
+                log::warn!("保存最近记录失败: {}", e);
+            }
         }
+    }
 
-        if self.batch_start_time.is_none() {
-            self.batch_start_time = Some(std::time::Instant::now());
+    fn render_recent_menu(&mut self, ui: &mut egui::Ui) {
+        // 打开菜单时清理已不存在的路径，结果到达前先显示原有记录
+        if !self.recent_menu_open {
+            self.recent_menu_open = true;
+            let _ = self
+                .worker_tx
+                .send(WorkerMessage::CheckRecentPaths(self.recent_paths.clone()));
         }
 
-        // 重新计算未完成文件的总大小
-        let pending_files: Vec<_> = self
-            .files
-            .iter()
-            .filter(|f| matches!(f.status, FileStatus::Pending))
-            .collect();
-
-        let pending_paths: Vec<_> = pending_files.iter().map(|f| f.path.clone()).collect();
-        let pending_size: u64 = pending_files.iter().map(|f| f.size).sum();
-
-        if pending_paths.is_empty() {
+        if self.recent_paths.is_empty() {
+            ui.label(t(Text::NoRecentPaths));
             return;
         }
 
-        self.progress_tracker = Some(ProgressTracker::new());
-        if let Some(tracker) = &self.progress_tracker {
-            tracker.set_total(pending_size);
+        let mut selected = None;
+        for path in &self.recent_paths {
+            if ui
+                .button(dunce::simplified(path).display().to_string())
+                .clicked()
+            {
+                selected = Some(path.clone());
+            }
         }
 
-        self.processed_size = 0; // 批次内已处理
+        ui.separator();
+        if ui.button(t(Text::ClearRecentPaths)).clicked() {
+            self.recent_paths.clear();
+            self.save_recent_paths();
+            ui.close();
+        }
 
-        self.is_computing = true;
-        let _ = self.worker_tx.send(WorkerMessage::Compute(pending_paths));
+        if let Some(path) = selected {
+            self.add_files(vec![path]);
+            ui.close();
+        }
     }
 
-    pub fn stop_computing(&mut self) {
-        let _ = self.worker_tx.send(WorkerMessage::Cancel);
-        self.is_computing = false;
-
-        for file in &mut self.files {
-            if matches!(file.status, FileStatus::Computing) {
-                file.status = FileStatus::Cancelled;
+    fn render_profiles_menu(&mut self, ui: &mut egui::Ui) {
+        let active = self.active_profile.as_ref().map(|(name, _)| name.clone());
+        let mut selected = None;
+        for name in &self.profile_names {
+            if ui
+                .selectable_label(active.as_ref() == Some(name), name)
+                .clicked()
+            {
+                selected = Some(name.clone());
             }
         }
+        if !self.profile_names.is_empty() {
+            ui.separator();
+        }
 
-        if let Some(tracker) = &self.progress_tracker {
-            tracker.reset();
+        ui.horizontal(|ui| {
+            ui.add(
+                egui::TextEdit::singleline(&mut self.new_profile_name)
+                    .hint_text(t(Text::ProfileName))
+                    .desired_width(140.0),
+            );
+            let name = self.new_profile_name.trim().to_string();
+            if ui
+                .add_enabled(!name.is_empty(), egui::Button::new(t(Text::SaveProfile)))
+                .clicked()
+            {
+                self.save_profile(&name);
+                self.new_profile_name.clear();
+                ui.close();
+            }
+        });
+
+        if ui.button(t(Text::ImportProfile)).clicked() {
+            self.import_profile();
+            ui.close();
+        }
+        if ui
+            .add_enabled(active.is_some(), egui::Button::new(t(Text::ExportProfile)))
+            .clicked()
+        {
+            self.export_profile();
+            ui.close();
+        }
+        if ui
+            .add_enabled(active.is_some(), egui::Button::new(t(Text::DeleteProfile)))
+            .clicked()
+        {
+            self.delete_active_profile();
+            ui.close();
         }
-        self.progress_tracker = None;
 
-        self.finalize_batch();
-        self.last_file_add_time = None;
-        self.auto_compute_scheduled = false;
+        if let Some(name) = selected {
+            self.switch_profile(&ui.ctx().clone(), &name);
+            ui.close();
+        }
     }
 
-    fn process_messages(&mut self, ctx: &egui::Context) {
-        const MAX_MESSAGES_PER_FRAME: usize = 100; // 增加每帧处理量
-        let mut should_finalize_batch = false;
-        let mut processed_count = 0;
-        let mut new_files_added = false;
+    fn refresh_profile_names(&mut self) {
+        if let Ok(guard) = self.cache.lock() {
+            match guard.profile_names() {
+                Ok(names) => self.profile_names = names,
+                Err(e) => log::error!("读取配置方案失败: {}", e),
+            }
+        }
+    }
 
-        while let Ok(msg) = self.ui_rx.try_recv() {
-            if processed_count >= MAX_MESSAGES_PER_FRAME {
-                ctx.request_repaint(); // 还有消息，下一帧继续
-                break;
+    /// 将当前配置保存为方案，同名时覆盖
+    fn save_profile(&mut self, name: &str) {
+        let result = match self.cache.lock() {
+            Ok(guard) => guard.save_profile(name, &self.cache_config),
+            Err(_) => return,
+        };
+        let message = match result {
+            Ok(()) => {
+                self.active_profile = Some((name.to_string(), self.cache_config.clone()));
+                tf(Text::ProfileSaved, &[&name])
             }
-            processed_count += 1;
+            Err(e) => tf(Text::ProfileSaveFailed, &[&e]),
+        };
+        self.refresh_profile_names();
+        self.status_toast = Some((message, std::time::Instant::now()));
+    }
 
-            match msg {
-                UiMessage::FilesDiscovered(batch) => {
-                    for (path, size) in batch {
-                        if !self.file_index.contains_key(&path) {
-                            let item = FileItem::new(path.clone(), size);
-                            let idx = self.files.len();
-                            self.file_index.insert(path, idx);
-                            self.files.push(item);
-                            self.total_size += size;
-                            new_files_added = true;
-                        }
-                    }
-                }
-                UiMessage::FileStarted { path } => {
-                    if let Some(&idx) = self.file_index.get(&path) {
-                        let file = &mut self.files[idx];
-                        file.status = FileStatus::Computing;
-                        file.computation_start_time = Some(std::time::Instant::now());
-                        file.progress = 0.0;
+    fn switch_profile(&mut self, ctx: &egui::Context, name: &str) {
+        let loaded = match self.cache.lock() {
+            Ok(guard) => guard.load_profile(name),
+            Err(_) => return,
+        };
+        let message = match loaded {
+            Ok(Some(config)) => {
+                self.apply_config(ctx, config.clone());
+                self.active_profile = Some((name.to_string(), config));
+                tf(Text::ProfileSwitched, &[&name])
+            }
+            Ok(None) => tf(Text::ProfileMissing, &[&name]),
+            Err(e) => tf(Text::ProfileLoadFailed, &[&e]),
+        };
+        self.refresh_profile_names();
+        self.status_toast = Some((message, std::time::Instant::now()));
+    }
 
-                        if let Some(tracker) = &self.progress_tracker {
-                            tracker.start_file(path.clone(), file.size);
-                        }
-                    }
-                }
-                UiMessage::Xxhash3Computed { path, xxhash3 } => {
-                    if let Some(&idx) = self.file_index.get(&path) {
-                        let file = &mut self.files[idx];
-                        file.xxhash3 = xxhash3;
-                    }
-                }
-                UiMessage::FileCompleted {
-                    path,
-                    crc32,
-                    md5,
-                    sha1,
-                    xxhash3,
-                    duration_ms,
-                    modified_time,
-                    file_size,
-                    from_cache,
-                } => {
-                    if let Some(&idx) = self.file_index.get(&path) {
-                        let file = &mut self.files[idx];
-
-                        file.status = FileStatus::Completed;
-                        file.crc32 = crc32.clone();
-                        file.md5 = md5.clone();
-                        file.sha1 = sha1.clone();
-                        file.xxhash3 = xxhash3.clone(); // 确保更新
-                        file.progress = 1.0;
-                        file.computation_duration_ms = Some(duration_ms);
-                        file.computation_start_time = None;
-                        file.from_cache = from_cache;
-
-                        self.processed_size += file.size;
-
-                        if let Some(tracker) = &self.progress_tracker {
-                            tracker.complete_file(&path);
-                            self.global_progress = tracker.get_global_progress();
-                        }
+    fn delete_active_profile(&mut self) {
+        let Some((name, _)) = self.active_profile.take() else {
+            return;
+        };
+        let result = match self.cache.lock() {
+            Ok(guard) => guard.delete_profile(&name),
+            Err(_) => return,
+        };
+        if let Err(e) = result {
+            log::error!("删除配置方案失败: {}", e);
+        }
+        self.refresh_profile_names();
+    }
 
-                        // 如果不是来自缓存，加入待保存队列
-                        if !from_cache {
-                            use std::time::{SystemTime, UNIX_EPOCH};
-                            let entry = CacheEntry {
-                                path: path.clone(),
-                                file_size,
-                                modified_time,
-                                cached_at: SystemTime::now()
-                                    .duration_since(UNIX_EPOCH)
-                                    .unwrap_or(std::time::Duration::ZERO)
-                                    .as_secs(),
-                                xxhash3: xxhash3.clone(),
-                                crc32,
-                                md5,
-                                sha1,
-                            };
-                            self.pending_cache_entries.push(entry);
-                        }
-                    }
-                }
-                UiMessage::FileFailed { path } => {
-                    if let Some(&idx) = self.file_index.get(&path) {
-                        let file = &mut self.files[idx];
-                        file.status = FileStatus::Failed;
-                        file.computation_start_time = None;
-                    }
-                }
-                UiMessage::Progress {
-                    path,
-                    processed,
-                    total,
-                } => {
-                    if let Some(&idx) = self.file_index.get(&path) {
-                        let file = &mut self.files[idx];
-                        if matches!(file.status, FileStatus::Completed) {
-                            continue;
-                        }
-                        if total > 0 {
-                            file.progress = processed as f64 / total as f64;
-                        }
-                        if let Some(tracker) = &self.progress_tracker {
-                            tracker.update_progress(&path, processed);
-                            self.global_progress = tracker.get_global_progress();
-                        }
-                    }
-                }
-                UiMessage::CacheSaved => {
-                    // 可以在这里显示保存成功的提示
-                }
-                UiMessage::AllCompleted => {
-                    self.is_computing = false;
-                    self.global_progress = 1.0;
-                    self.auto_compute_scheduled = false;
-                    should_finalize_batch = true;
-                    if let Some(tracker) = &self.progress_tracker {
-                        tracker.reset();
-                    }
-                    self.progress_tracker = None;
+    /// 导入的方案加入列表，不立即切换；与已有方案同名时换一个名称，不覆盖
+    fn import_profile(&mut self) {
+        use rfd::FileDialog;
+        let Some(path) = FileDialog::new()
+            .set_title(t(Text::ImportProfileTitle))
+            .add_filter(t(Text::Profiles), &["json"])
+            .pick_file()
+        else {
+            return;
+        };
 
-                    if !self.pending_cache_entries.is_empty() {
-                        let _ = self.worker_tx.send(WorkerMessage::SaveCache(std::mem::take(
-                            &mut self.pending_cache_entries,
-                        )));
-                    }
+        let message = match crate::profiles::import_profile(&path) {
+            Ok((name, config)) => {
+                let saved = match self.cache.lock() {
+                    Ok(guard) => guard.profile_names().and_then(|existing| {
+                        let unused = crate::profiles::unused_name(&name, &existing);
+                        guard.save_profile(&unused, &config).map(|()| unused)
+                    }),
+                    Err(_) => return,
+                };
+                match saved {
+                    Ok(unused) if unused == name => tf(Text::ProfileImported, &[&name]),
+                    Ok(unused) => tf(Text::ProfileImportedAs, &[&unused]),
+                    Err(e) => tf(Text::ProfileSaveFailed, &[&e]),
                 }
             }
+            Err(e) => tf(Text::ImportFailed, &[&e]),
+        };
+        self.refresh_profile_names();
+        self.status_toast = Some((message, std::time::Instant::now()));
+    }
+
+    fn export_profile(&mut self) {
+        use rfd::FileDialog;
+        let Some((name, config)) = &self.active_profile else {
+            return;
+        };
+        let Some(target) = FileDialog::new()
+            .set_title(t(Text::ExportProfileTitle))
+            .set_file_name(format!("{}.json", name))
+            .add_filter(t(Text::Profiles), &["json"])
+            .save_file()
+        else {
+            return;
+        };
+
+        let message = match crate::profiles::export_profile(&target, name, config) {
+            Ok(()) => tf(Text::ProfileExported, &[name]),
+            Err(e) => tf(Text::ExportFailed, &[&e]),
+        };
+        self.status_toast = Some((message, std::time::Instant::now()));
+    }
+
+    /// 整体替换配置（切换配置方案），同步由配置派生的界面状态
+    fn apply_config(&mut self, ctx: &egui::Context, config: CacheConfig) {
+        if config.minimize_to_tray != self.cache_config.minimize_to_tray {
+            self.tray = if config.minimize_to_tray {
+                try_create_tray(ctx)
+            } else {
+                None
+            };
+            self.tray_tooltip.clear();
         }
+        crate::logging::set_level(config.log_level);
+        crate::hash_timing::set_enabled(config.hash_timing);
+        i18n::set_language(config.ui_language.resolve(self.chinese_font));
+        self.uppercase_display = config.uppercase_display;
+        self.excluded_dirs_text = config.excluded_dirs.join("\n");
+        self.auto_compute = config.auto_compute;
+        if self.auto_compute == AutoComputeMode::Manual {
+            self.last_file_add_time = None;
+            self.auto_compute_scheduled = false;
+        }
+        self.cache_config = config;
+        self.persist_config();
+    }
 
-        if !self.pending_cache_entries.is_empty() {
-            let should_flush = self.pending_cache_entries.len() >= 50;
-            if should_flush {
-                let _ = self.worker_tx.send(WorkerMessage::SaveCache(std::mem::take(
-                    &mut self.pending_cache_entries,
-                )));
-            }
+    fn open_file_dialog(&mut self) {
+        use rfd::FileDialog;
+        // 注意：FileDialog 可能会阻塞，通常在主线程调用是可以接受的，因为它就是模态对话框
+        if let Some(paths) = FileDialog::new().set_title(t(Text::PickFiles)).pick_files() {
+            let path_bufs: Vec<PathBuf> = paths.into_iter().map(|p| p.into()).collect();
+            self.add_files(path_bufs);
         }
+    }
 
-        if new_files_added && self.auto_compute_enabled {
-            self.schedule_auto_compute();
+    fn open_folder_dialog(&mut self) {
+        use rfd::FileDialog;
+        if let Some(folder_path) = FileDialog::new()
+            .set_title(t(Text::PickFolder))
+            .pick_folder()
+        {
+            self.add_files(vec![folder_path]);
         }
+    }
 
-        if should_finalize_batch {
-            self.finalize_batch();
+    pub fn clear_files(&mut self) {
+        self.files.clear();
+        self.file_index.clear();
+        self.total_size = 0;
+        self.processed_size = 0;
+        self.global_progress = 0.0;
+        self.batch_start_time = None;
+        self.batch_total_duration_ms = 0;
+        self.batch_timeline = BatchTimeline::default();
+        self.last_timeline = None;
+        self.show_time_breakdown = false;
+        self.hash_changes.clear();
+        self.show_hash_changes = false;
+        self.last_file_add_time = None;
+        self.auto_compute_scheduled = false;
+        self.dropped_roots.clear();
+        self.pending_scans = 0;
+        self.xattr_pending.clear();
+        self.rows_changed();
+        self.clipboard_toast = None;
+        self.clear_selection();
+        // 清空前的批次消息不再计入新队列
+        self.batch_id += 1;
+        if let Some(tracker) = &self.progress_tracker {
+            tracker.reset();
         }
+        self.progress_tracker = None;
+        self.pending_cache_entries.clear();
+        self.batch_done.clear();
+        let _ = self.worker_tx.send(WorkerMessage::ClearBatchQueue);
     }
 
-    fn schedule_auto_compute(&mut self) {
-        self.last_file_add_time = Some(std::time::Instant::now());
-        self.auto_compute_scheduled = true;
+    /// 文件列表整体变化（清空、恢复、批量修改状态等）后使由其派生的缓存失效
+    fn rows_changed(&mut self) {
+        self.rows_generation += 1;
+        self.sources = summarize_sources(&self.files);
+        self.hash_search.dirty = true;
+        self.hash_list.dirty = true;
     }
 
-    fn check_and_execute_auto_compute(&mut self) {
-        if !self.auto_compute_scheduled {
+    /// 在文件管理器中显示选中的文件；文件已不存在时打开仍存在的最近一级上级目录
+    fn reveal_selected_file(&mut self) {
+        let Some(idx) = reveal_target(&self.files, &self.selected_rows) else {
             return;
+        };
+        let path = self.files[idx].path.clone();
+        let result = if path.exists() {
+            reveal_in_file_manager(&path)
+        } else {
+            match path.ancestors().skip(1).find(|dir| dir.is_dir()) {
+                Some(dir) => {
+                    self.status_toast = Some((
+                        tf(Text::RevealedParent, &[&dir.display()]),
+                        std::time::Instant::now(),
+                    ));
+                    open_with_system(dir)
+                }
+                None => Err(std::io::Error::from(std::io::ErrorKind::NotFound)),
+            }
+        };
+        if let Err(e) = result {
+            log::warn!("无法打开所在文件夹: {} - {}", path.display(), e);
+            self.status_toast = Some((
+                format!("{}: {}", t(Text::OpenDirFailed), e),
+                std::time::Instant::now(),
+            ));
         }
+    }
 
-        if let Some(last_add_time) = self.last_file_add_time {
-            let elapsed = last_add_time.elapsed().as_millis() as u64;
+    /// 请求工作线程重新读取队列中所有文件的元数据，结果由 `apply_metadata` 处理
+    fn refresh_metadata(&mut self) {
+        if self.is_computing {
+            return;
+        }
+        let paths = self.files.iter().map(|f| f.path.clone()).collect();
+        let _ = self.worker_tx.send(WorkerMessage::ReadMetadata(paths));
+    }
 
-            if elapsed >= self.debounce_duration_ms {
-                self.start_computing();
-                self.last_file_add_time = None;
-                self.auto_compute_scheduled = false;
+    /// 已变更的文件改回等待计算并使其缓存失效
+    fn apply_metadata(&mut self, stats: Vec<(PathBuf, Option<FileStat>)>) {
+        // 读取期间已开始计算时，以计算结果为准
+        if self.is_computing {
+            return;
+        }
+        let tolerance = self.cache_config.mtime_tolerance_ms;
+        let mut changed = Vec::new();
+        let mut missing = 0;
+        for (path, stat) in stats {
+            // 读取期间已移出列表的文件
+            let Some(&idx) = self.file_index.get(&path) else {
+                continue;
+            };
+            let file = &mut self.files[idx];
+            match file.refresh_metadata(stat, tolerance) {
+                MetadataRefresh::Unchanged => {}
+                MetadataRefresh::Changed => changed.push(path),
+                MetadataRefresh::Missing => missing += 1,
+            }
+        }
+        self.recompute_total_size();
+        self.status_toast = Some((
+            tf(
+                Text::MetadataRefreshed,
+                &[&format_count(changed.len()), &format_count(missing)],
+            ),
+            std::time::Instant::now(),
+        ));
+        if changed.is_empty() && missing == 0 {
+            return;
+        }
+
+        self.rows_changed();
+        if !changed.is_empty() {
+            let _ = self.worker_tx.send(WorkerMessage::InvalidateCache(changed));
+            if self.auto_compute.auto_starts(false) {
+                self.schedule_auto_compute();
             }
         }
     }
 
-    fn show_hash_cell(
-        &mut self,
-        ui: &mut egui::Ui,
-        ctx: &egui::Context,
-        hash_value: &str,
-        unique_id: &str,
-    ) -> egui::Response {
-        if hash_value.is_empty() {
-            ui.label(egui::RichText::new("-").weak().italics())
-        } else {
-            let display_value = if self.uppercase_display {
-                hash_value.to_uppercase()
-            } else {
-                hash_value.to_string()
-            };
+    /// 在后台分批清理缓存，进度与结果通过 UiMessage 返回
+    fn start_cache_cleanup(&mut self, kind: CleanupKind) {
+        let cancel = Arc::new(AtomicBool::new(false));
+        self.cache_operation_message = None;
+        self.cleanup = Some(CleanupView {
+            cancel: Arc::clone(&cancel),
+            progress: None,
+        });
+        let _ = self
+            .worker_tx
+            .send(WorkerMessage::CleanupCache(kind, cancel));
+    }
 
-            let show_toast = self
-                .clipboard_toast
-                .as_ref()
-                .map_or(false, |(id, _)| id == unique_id);
-            let label_text = if show_toast {
-                egui::RichText::new("已复制到剪贴板").color(egui::Color32::GREEN)
-            } else {
-                egui::RichText::new(&display_value).monospace()
+    /// 批次完成后汇总同名校验文件的比对结果
+    fn report_sidecar_results(&mut self) {
+        let (mut matched, mut mismatched) = (0, 0);
+        for file in &self.files {
+            let Some(expected) = &file.expected else {
+                continue;
             };
+            match expected.check(file) {
+                Some(true) => matched += 1,
+                Some(false) => {
+                    mismatched += 1;
+                    log::warn!(
+                        "与校验文件不一致: {} ({})",
+                        file.path.display(),
+                        expected.sidecar
+                    );
+                }
+                None => {}
+            }
+        }
+        if matched + mismatched > 0 {
+            self.status_toast = Some((
+                tf(Text::SidecarSummary, &[&matched, &mismatched]),
+                std::time::Instant::now(),
+            ));
+        }
+    }
 
-            let response = ui.label(label_text).on_hover_text("点击复制");
+    /// 清空队列并保留快照，短时间内可撤销
+    fn clear_files_with_undo(&mut self) {
+        if self.files.is_empty() {
+            return;
+        }
+        self.cleared_queue = Some(ClearedQueue {
+            files: std::mem::take(&mut self.files),
+            file_index: std::mem::take(&mut self.file_index),
+            cleared_at: std::time::Instant::now(),
+        });
+        self.clear_files();
+    }
 
-            if response.hovered() {
-                ui.painter().rect_filled(
-                    response.rect,
-                    egui::CornerRadius::same(4),
-                    egui::Color32::from_rgba_premultiplied(60, 60, 60, 50),
-                );
-            }
+    /// 与 `FileMissing` 一致：已删除的文件不计入总大小
+    fn recompute_total_size(&mut self) {
+        self.total_size = self
+            .files
+            .iter()
+            .filter(|f| !matches!(f.status, FileStatus::Deleted))
+            .map(|f| f.size)
+            .sum();
+    }
 
-            if response.clicked() {
-                ctx.copy_text(display_value.clone());
-                self.clipboard_toast = Some((unique_id.to_string(), std::time::Instant::now()));
+    /// 恢复清空前的队列，清空后新添加的文件排在后面
+    fn undo_clear_files(&mut self) {
+        let Some(snapshot) = self.cleared_queue.take() else {
+            return;
+        };
+        let added = std::mem::replace(&mut self.files, snapshot.files);
+        self.file_index = snapshot.file_index;
+        // 恢复后下标改变
+        self.clear_selection();
+        for file in added {
+            if !self.file_index.contains_key(&file.path) {
+                self.file_index.insert(file.path.clone(), self.files.len());
+                self.files.push(file);
             }
-
-            response
+        }
+        self.rows_changed();
+        self.recompute_total_size();
+        self.status_toast = Some((
+            tf(Text::FilesRestored, &[&format_count(self.files.len())]),
+            std::time::Instant::now(),
+        ));
+        // 未完成的文件重新排队；批处理队列会在开始计算时重新记录
+        if self.auto_compute.auto_starts(false)
+            && self
+                .files
+                .iter()
+                .any(|f| matches!(f.status, FileStatus::Pending))
+        {
+            self.schedule_auto_compute();
         }
     }
 
-    fn render_settings_window(&mut self, ctx: &egui::Context) {
-        // --- 点击外部关闭 (遮罩层) ---
-        egui::Area::new("settings_backdrop".into())
-            .fixed_pos(egui::pos2(0.0, 0.0))
-            .order(egui::Order::Middle) // 位于窗口之下
+    fn render_clear_confirm(&mut self, ctx: &egui::Context) {
+        let mut confirm = false;
+        let mut cancel = false;
+        egui::Window::new(t(Text::ClearQueue))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
             .show(ctx, |ui| {
-                let screen_rect = ctx.viewport_rect();
-                // 绘制半透明遮罩
-                ui.painter().rect_filled(
+                ui.label(tf(
+                    Text::ConfirmClearQueue,
+                    &[&format_count(self.files.len())],
+                ));
+                ui.label(tf(Text::ClearUndoWindow, &[&UNDO_CLEAR_SECS]));
+                ui.add_space(8.0);
+                let mut dont_ask = !self.cache_config.confirm_clear_queue;
+                if ui
+                    .checkbox(&mut dont_ask, t(Text::DontAskAgain))
+                    .on_hover_text(t(Text::DontAskAgainHint))
+                    .changed()
+                {
+                    self.cache_config.confirm_clear_queue = !dont_ask;
+                    self.persist_config();
+                }
+                ui.add_space(8.0);
+                ui.horizontal(|ui| {
+                    confirm = ui.button(t(Text::Clear)).clicked();
+                    cancel = ui.button(t(Text::Cancel)).clicked();
+                });
+            });
+
+        if confirm {
+            self.clear_confirm_open = false;
+            self.clear_files_with_undo();
+        } else if cancel {
+            self.clear_confirm_open = false;
+        }
+    }
+
+    fn finalize_batch(&mut self) {
+        if let Some(start_time) = self.batch_start_time {
+            self.batch_total_duration_ms = start_time.elapsed().as_millis() as u64;
+            self.batch_start_time = None;
+        }
+        self.last_timeline = Some(self.batch_timeline.take_finished());
+        self.close_stream_export();
+    }
+
+    /// 在后台下载版本清单；`manual` 为用户手动检查，结果显示在设置窗口中
+    fn check_for_update(&mut self, manual: bool) {
+        let _ = self.worker_tx.send(WorkerMessage::CheckUpdate {
+            url: self.cache_config.update_manifest_url.clone(),
+            manual,
+        });
+    }
+
+    fn handle_update_checked(&mut self, manual: bool, result: Result<Option<UpdateInfo>, String>) {
+        match result {
+            Ok(Some(info)) => {
+                log::info!("发现新版本 {}", info.version);
+                self.update_available = Some(info);
+            }
+            Ok(None) if manual => {
+                self.cache_operation_message = Some(format!(
+                    "{} ({})",
+                    t(Text::UpToDate),
+                    update::CURRENT_VERSION
+                ));
+            }
+            Ok(None) => {}
+            // 启动时的检查失败（离线等）只记录日志
+            Err(e) => {
+                log::info!("检查更新失败: {}", e);
+                if manual {
+                    self.cache_operation_message =
+                        Some(format!("{}: {}", t(Text::UpdateCheckFailed), e));
+                }
+            }
+        }
+    }
+
+    /// 有新版本时在工具栏下方显示一行提示
+    fn show_update_banner(&mut self, ctx: &egui::Context) {
+        let Some(info) = &self.update_available else {
+            return;
+        };
+        let mut dismissed = false;
+        TopBottomPanel::top("update_banner").show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.label(tf(
+                    Text::UpdateAvailable,
+                    &[&info.version, &update::CURRENT_VERSION],
+                ));
+                ui.hyperlink_to(t(Text::UpdateDownload), &info.url);
+                dismissed = ui.small_button("✕").clicked();
+            });
+        });
+        if dismissed {
+            self.update_available = None;
+        }
+    }
+
+    /// 批次开始时创建已选择的流式导出文件
+    fn open_stream_export(&mut self) {
+        let Some((path, format)) = self.stream_export_target.take() else {
+            return;
+        };
+        match StreamExport::create(&path, format) {
+            Ok(export) => self.stream_export = Some(export),
+            Err(e) => {
+                log::error!("创建流式导出文件失败: {} - {}", path.display(), e);
+                self.status_toast = Some((
+                    tf(Text::StreamExportFailed, &[&e]),
+                    std::time::Instant::now(),
+                ));
+            }
+        }
+    }
+
+    /// 批次结束或取消时关闭流式导出，文件中保留已完成的结果
+    fn close_stream_export(&mut self) {
+        let Some(export) = self.stream_export.take() else {
+            return;
+        };
+        let path = export.path().to_path_buf();
+        let message = match export.finish() {
+            Ok(written) => tf(Text::StreamExportWritten, &[&written, &path.display()]),
+            Err(e) => {
+                log::error!("写入流式导出文件失败: {} - {}", path.display(), e);
+                tf(Text::StreamExportFailed, &[&e])
+            }
+        };
+        self.status_toast = Some((message, std::time::Instant::now()));
+    }
+
+    /// 追加一个已完成的文件；写入失败时停止本批次的流式导出
+    fn append_stream_export(&mut self, idx: usize) {
+        let Some(export) = &mut self.stream_export else {
+            return;
+        };
+        if let Err(e) = export.append(&self.files[idx]) {
+            log::error!("写入流式导出文件失败: {} - {}", export.path().display(), e);
+            self.stream_export = None;
+            self.status_toast = Some((
+                tf(Text::StreamExportFailed, &[&e]),
+                std::time::Instant::now(),
+            ));
+        }
+    }
+
+    /// 批次耗时超过通知阈值时自动显示各阶段耗时
+    fn show_slow_batch_breakdown(&mut self) {
+        let threshold = std::time::Duration::from_secs(self.cache_config.notify_threshold_secs);
+        if self
+            .last_timeline
+            .as_ref()
+            .and_then(BatchTimeline::breakdown)
+            .is_some_and(|breakdown| breakdown.total >= threshold)
+        {
+            self.show_time_breakdown = true;
+        }
+    }
+
+    fn render_time_breakdown_window(&mut self, ctx: &egui::Context) {
+        let Some(breakdown) = self
+            .last_timeline
+            .as_ref()
+            .and_then(BatchTimeline::breakdown)
+        else {
+            self.show_time_breakdown = false;
+            return;
+        };
+        let mut open = true;
+        egui::Window::new(t(Text::TimeBreakdown))
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label(tf(
+                    Text::TotalTime,
+                    &[&format_duration(breakdown.total.as_millis() as u64)],
+                ));
+                ui.separator();
+                egui::Grid::new("time_breakdown_grid")
+                    .num_columns(3)
+                    .spacing([12.0, 6.0])
+                    .show(ui, |ui| {
+                        for (label, part) in [
+                            (t(Text::PhaseScanning), breakdown.scanning),
+                            (t(Text::PhaseWaiting), breakdown.waiting),
+                            (t(Text::PhaseHashing), breakdown.hashing),
+                        ] {
+                            ui.label(label);
+                            ui.label(format_duration(part.as_millis() as u64));
+                            ui.add(
+                                egui::ProgressBar::new(breakdown.share(part) as f32)
+                                    .desired_width(200.0)
+                                    .show_percentage(),
+                            );
+                            ui.end_row();
+                        }
+                        ui.label(t(Text::PhaseCacheWrites));
+                        ui.label(format_duration(breakdown.cache_save.as_millis() as u64));
+                        ui.label(egui::RichText::new(t(Text::CacheWritesHint)).small().weak());
+                        ui.end_row();
+                    });
+
+                let timings = crate::hash_timing::totals().snapshot();
+                if !timings.is_empty() {
+                    ui.separator();
+                    render_hash_timings(ui, &timings, self.cache_config.size_unit);
+                }
+            });
+        if !open {
+            self.show_time_breakdown = false;
+        }
+    }
+
+    pub fn start_computing(&mut self) {
+        self.start_computing_rows(None, false);
+    }
+
+    /// 只计算选中行中等待计算的文件，其余文件保持等待
+    fn compute_selected(&mut self) {
+        let rows = self.selected_rows.clone();
+        self.start_computing_rows(Some(&rows), false);
+        self.last_file_add_time = None;
+        self.auto_compute_scheduled = false;
+    }
+
+    /// 「仍然计算」：忽略最大文件大小，立即只计算这一行，手动计算模式下同样生效
+    fn hash_anyway(&mut self, idx: usize) {
+        self.files[idx].status = FileStatus::Pending;
+        self.rows_generation += 1;
+        self.start_computing_rows(Some(&HashSet::from([idx])), false);
+    }
+
+    /// 选中行中可以强制重新计算的文件（已有结果或已失败）
+    fn selected_recomputable(&self) -> Vec<usize> {
+        self.selected_rows
+            .iter()
+            .copied()
+            .filter(|&idx| {
+                self.files.get(idx).is_some_and(|f| {
+                    matches!(
+                        f.status,
+                        FileStatus::Completed
+                            | FileStatus::Failed
+                            | FileStatus::Cancelled
+                            | FileStatus::QuickHashed
+                    )
+                })
+            })
+            .collect()
+    }
+
+    /// 不采用缓存重新计算选中的文件，结果与原有缓存不同时提示
+    fn force_recompute_selected(&mut self) {
+        if self.is_computing {
+            return;
+        }
+        let rows: HashSet<usize> = self.selected_recomputable().into_iter().collect();
+        for &idx in &rows {
+            self.files[idx].reset_results();
+        }
+        self.rows_changed();
+        self.start_computing_rows(Some(&rows), true);
+    }
+
+    /// 将等待计算的文件加入批次；`rows` 为 None 时计算整个队列，`force` 时不采用缓存。
+    /// 已有批次在运行时不开始新批次：新批次会使旧批次的消息全部过期，其中计算中的文件
+    /// 再也收不到结果
+    fn start_computing_rows(&mut self, rows: Option<&HashSet<usize>>, force: bool) {
+        if self.files.is_empty() || self.is_computing {
+            return;
+        }
+
+        // 重新计算未完成文件的总大小
+        let (pending_paths, pending_size) = pending_batch(&self.files, rows);
+        if pending_paths.is_empty() {
+            return;
+        }
+
+        if self.batch_start_time.is_none() {
+            self.batch_start_time = Some(std::time::Instant::now());
+            crate::hash_timing::totals().reset();
+        }
+
+        self.progress_tracker = Some(if self.cache_config.progress_by_file_count {
+            ProgressTracker::by_file_count()
+        } else {
+            ProgressTracker::with_file_cost(self.cache_config.progress_file_cost_kb * 1024)
+        });
+        if let Some(tracker) = &self.progress_tracker {
+            tracker.set_total(pending_size);
+            tracker.set_total_files(pending_paths.len() as u64);
+        }
+
+        self.processed_size = 0; // 批次内已处理
+
+        self.is_computing = true;
+        self.batch_validation = self.cache_config.cache_validation;
+        self.cache_contention = None;
+        if self.stream_export.is_none() {
+            self.open_stream_export();
+        }
+        if self.cache_config.prevent_sleep && self.sleep_inhibitor.is_none() {
+            self.sleep_inhibitor = SleepInhibitor::acquire();
+        }
+        self.batch_id += 1;
+        let _ = self.worker_tx.send(WorkerMessage::Compute {
+            batch_id: self.batch_id,
+            files: pending_paths,
+            force,
+        });
+    }
+
+    pub fn stop_computing(&mut self) {
+        let _ = self.worker_tx.send(WorkerMessage::Cancel);
+        // 仍在运行的任务稍后送达的进度与结果一律丢弃，不再回写已重置的进度
+        self.batch_id += 1;
+        self.is_computing = false;
+        self.sleep_inhibitor = None;
+
+        for file in &mut self.files {
+            if matches!(file.status, FileStatus::Computing) {
+                file.status = FileStatus::Cancelled;
+            }
+        }
+        self.rows_generation += 1;
+
+        if let Some(tracker) = &self.progress_tracker {
+            tracker.reset();
+        }
+        self.progress_tracker = None;
+
+        self.finalize_batch();
+        self.flush_xattr_pending();
+        self.last_file_add_time = None;
+        self.auto_compute_scheduled = false;
+    }
+
+    /// 将本批次已完成文件的哈希写入扩展属性（已停止的批次也写入已完成的部分），
+    /// 刚算出的结果覆盖已有的值
+    fn flush_xattr_pending(&mut self) {
+        if self.xattr_pending.is_empty() {
+            return;
+        }
+        self.xattr_writing = true;
+        let _ = self.worker_tx.send(WorkerMessage::WriteXattrs {
+            targets: std::mem::take(&mut self.xattr_pending),
+            overwrite: true,
+        });
+    }
+
+    /// 手动将所有已完成文件的哈希写入扩展属性，不覆盖已有的不同值
+    fn write_xattrs_for_completed(&mut self) {
+        let targets = self
+            .files
+            .iter()
+            .filter(|f| matches!(f.status, FileStatus::Completed))
+            .map(FileItem::xattr_target)
+            .collect();
+        self.xattr_writing = true;
+        let _ = self.worker_tx.send(WorkerMessage::WriteXattrs {
+            targets,
+            overwrite: false,
+        });
+    }
+
+    fn finish_xattrs(&mut self, report: &XattrReport) {
+        self.xattr_writing = false;
+        for path in &report.unsupported {
+            log::info!("文件系统不支持扩展属性，已跳过: {}", path.display());
+        }
+        self.status_toast = Some((report.summary(), std::time::Instant::now()));
+    }
+
+    /// 退出时同步保存未落盘的结果，并等待工作线程完成已排队的保存
+    fn flush_on_exit(&mut self) {
+        self.close_stream_export();
+        // 已计算完成但尚未被 UI 处理的结果
+        while let Ok(msg) = self.ui_rx.try_recv() {
+            match msg {
+                UiMessage::FileCompleted {
+                    path,
+                    crc32,
+                    md5,
+                    sha1,
+                    sha256,
+                    crc64,
+                    adler32,
+                    xxhash3,
+                    modified_time,
+                    file_size,
+                    from_cache,
+                    ..
+                } => {
+                    let hashes = FileHashes {
+                        crc32,
+                        md5,
+                        sha1,
+                        sha256,
+                        crc64,
+                        adler32,
+                        xxhash3,
+                    };
+                    if !from_cache && !hashes.is_xxh3_only() {
+                        self.pending_cache_entries.push(CacheEntry::new(
+                            path.clone(),
+                            file_size,
+                            modified_time,
+                            unix_now(),
+                            hashes,
+                        ));
+                    }
+                    self.batch_done.push(path);
+                }
+                UiMessage::FileFailed { path, .. }
+                | UiMessage::FileMissing { path, .. }
+                | UiMessage::QuickHashed { path, .. } => {
+                    self.batch_done.push(path);
+                }
+                _ => {}
+            }
+        }
+
+        if !self.pending_cache_entries.is_empty() {
+            let mut entries = std::mem::take(&mut self.pending_cache_entries);
+            let removable = RemovableVolumes::default();
+            entries.retain(|entry| {
+                WorkerThread::cacheable(&entry.path, &self.cache_config, &removable)
+            });
+            match self.cache.lock() {
+                Ok(cache_guard) => match cache_guard.save_entries_batch(&entries) {
+                    Ok(count) => log::info!("退出前保存 {} 条缓存", count),
+                    Err(e) => log::error!("退出前保存缓存失败: {}", e),
+                },
+                Err(e) => log::error!("退出前保存缓存失败: {}", e),
+            }
+        }
+
+        if !self.batch_done.is_empty() {
+            if let Ok(cache_guard) = self.cache.lock() {
+                if let Err(e) = cache_guard.mark_batch_done(&self.batch_done) {
+                    log::error!("退出前更新批处理队列失败: {}", e);
+                }
+            }
+            self.batch_done.clear();
+        }
+
+        // 计算或清理中途退出时不整理，VACUUM 会阻塞其写入
+        let vacuum = if self.is_computing || self.cleanup.is_some() {
+            VacuumSchedule::Off
+        } else {
+            self.cache_config.vacuum_schedule
+        };
+        if !WorkerThread::shutdown(&self.worker_tx, std::time::Duration::from_secs(5), vacuum) {
+            log::warn!("等待缓存保存超时，部分结果可能未保存");
+        }
+    }
+
+    fn process_messages(&mut self, ctx: &egui::Context) {
+        let mut should_finalize_batch = false;
+        let mut drain = MessageDrain::new(self.cache_config.max_messages_per_frame);
+        // 新加入且按自动计算模式应自动开始的文件
+        let mut new_files_added = false;
+
+        while let Some(msg) = drain.next(&self.ui_rx) {
+            if msg.is_stale(self.batch_id) {
+                continue;
+            }
+
+            match msg {
+                UiMessage::FilesDiscovered(batch) => {
+                    new_files_added |= self.handle_files_discovered(batch);
+                }
+                UiMessage::BatchResumed(found) => self.resume_pending_batch(found),
+                UiMessage::MetadataRead(stats) => self.apply_metadata(stats),
+                UiMessage::RecentPathsMissing(missing) => {
+                    self.handle_recent_paths_missing(&missing);
+                }
+                UiMessage::FileStarted { path, .. } => self.handle_file_started(path),
+                UiMessage::Xxhash3Computed { path, xxhash3, .. } => {
+                    self.handle_xxhash3_computed(&path, xxhash3);
+                }
+                UiMessage::FileCompleted { .. } => self.handle_file_completed(msg),
+                UiMessage::FileFailed { path, error, .. } => self.handle_file_failed(path, error),
+                UiMessage::HashChanged {
+                    path,
+                    old,
+                    new,
+                    metadata_changed,
+                    ..
+                } => {
+                    self.record_hash_change(HashChange {
+                        path,
+                        old,
+                        new,
+                        metadata_changed,
+                    });
+                }
+                UiMessage::FileMissing { path, .. } => self.handle_file_missing(path),
+                UiMessage::QuickHashed {
+                    path, quick_hash, ..
+                } => self.handle_quick_hashed(path, quick_hash),
+                UiMessage::Progress {
+                    path,
+                    processed,
+                    total,
+                    ..
+                } => self.handle_progress(&path, processed, total),
+                UiMessage::DirectoryHashed { root, result } => {
+                    self.handle_directory_hashed(&root, result);
+                }
+                UiMessage::ChunkHashProgress {
+                    path,
+                    chunk_size,
+                    processed,
+                    total,
+                } => self.handle_chunk_hash_progress(&path, chunk_size, (processed, total)),
+                UiMessage::ChunksHashed { path, result } => {
+                    self.handle_chunks_hashed(&path, result);
+                }
+                UiMessage::CacheAudited { root, result } => {
+                    self.handle_cache_audited(&root, result);
+                }
+                UiMessage::CleanupProgress(progress) => self.handle_cleanup_progress(progress),
+                UiMessage::CleanupFinished(result) => self.handle_cleanup_finished(result),
+                UiMessage::NotesLoaded(notes) => self.handle_notes_loaded(notes),
+                UiMessage::RenamePlanned(plan) => self.handle_rename_planned(plan),
+                UiMessage::RenameApplied(outcomes) => self.finish_rename(outcomes),
+                UiMessage::XattrsWritten(report) => self.finish_xattrs(&report),
+                UiMessage::UpdateChecked { manual, result } => {
+                    self.handle_update_checked(manual, result);
+                }
+                UiMessage::ChecksumsFetched(result) => self.handle_checksums_fetched(result),
+                UiMessage::ManifestChecked(result) => self.handle_manifest_checked(result),
+                UiMessage::BenchmarkFinished(result) => self.handle_benchmark_finished(result),
+                UiMessage::Rehashed(result) => self.handle_rehashed(result),
+                UiMessage::FilesCompared(result) => self.handle_files_compared(result),
+                UiMessage::CacheSaved { duration_ms } => self.handle_cache_saved(duration_ms),
+                UiMessage::CacheContention { root, .. } => {
+                    self.cache_contention = Some(root);
+                }
+                UiMessage::ScanFinished => self.handle_scan_finished(),
+                UiMessage::AllCompleted { .. } => {
+                    self.handle_all_completed();
+                    should_finalize_batch = true;
+                }
+            }
+        }
+
+        if !self.ui_rx.is_empty() {
+            ctx.request_repaint(); // 还有消息，下一帧继续
+        }
+
+        self.submit_pending_saves();
+
+        if new_files_added {
+            self.schedule_auto_compute();
+        }
+
+        if should_finalize_batch {
+            self.finalize_batch();
+            self.show_slow_batch_breakdown();
+            self.notify_batch_completed(ctx);
+        }
+    }
+
+    /// 已完成的文件与待保存的缓存攒够一批后交给 Worker 写入
+    fn submit_pending_saves(&mut self) {
+        let save_batch = if self.cache_contention.is_some() {
+            CONTENDED_SAVE_BATCH
+        } else {
+            CACHE_SAVE_BATCH
+        };
+        if self.batch_done.len() >= save_batch {
+            let _ = self
+                .worker_tx
+                .send(WorkerMessage::MarkBatchDone(std::mem::take(
+                    &mut self.batch_done,
+                )));
+        }
+
+        if !self.pending_cache_entries.is_empty() {
+            let should_flush = self.pending_cache_entries.len() >= save_batch;
+            if should_flush {
+                let _ = self.worker_tx.send(WorkerMessage::SaveCache(std::mem::take(
+                    &mut self.pending_cache_entries,
+                )));
+            }
+        }
+    }
+
+    /// 扫描发现的文件加入列表，返回其中是否有按自动计算模式应自动开始的文件
+    fn handle_files_discovered(&mut self, batch: Vec<DiscoveredFile>) -> bool {
+        let mut auto_start = false;
+        let mut added = Vec::new();
+        for found in batch {
+            if self.file_index.contains_key(&found.path) {
+                continue;
+            }
+            let mut item = FileItem::new(found.path.clone(), found.size);
+            if found.oversized {
+                item.status = FileStatus::Oversized;
+            }
+            item.expected = found.expected;
+            // 正在按清单校验时，清单中的记录优先于同名校验文件
+            if let Some(expected) = self
+                .manifest
+                .as_ref()
+                .and_then(|manifest| manifest.expected_for(&found.path))
+            {
+                item.expected = Some(expected);
+            }
+            let dropped = self
+                .dropped_roots
+                .iter()
+                .any(|root| found.path.starts_with(root));
+            auto_start |= self.auto_compute.auto_starts(dropped);
+            let idx = self.files.len();
+            added.push(found.path.clone());
+            self.file_index.insert(found.path, idx);
+            self.files.push(item);
+            self.total_size += found.size;
+        }
+        if !added.is_empty() {
+            self.rows_generation += 1;
+            let _ = self.worker_tx.send(WorkerMessage::LoadNotes(added));
+        }
+        auto_start
+    }
+
+    fn handle_recent_paths_missing(&mut self, missing: &[PathBuf]) {
+        let before = self.recent_paths.len();
+        self.recent_paths.retain(|p| !missing.contains(p));
+        if self.recent_paths.len() != before {
+            self.save_recent_paths();
+        }
+    }
+
+    fn handle_xxhash3_computed(&mut self, path: &Path, xxhash3: String) {
+        if let Some(&idx) = self.file_index.get(path) {
+            self.files[idx].xxhash3 = xxhash3;
+        }
+    }
+
+    fn handle_file_started(&mut self, path: PathBuf) {
+        self.batch_timeline.file_started(std::time::Instant::now());
+        if let Some(&idx) = self.file_index.get(&path) {
+            let file = &mut self.files[idx];
+            self.sources.replace(SourceFilter::source(file), None);
+            self.rows_generation += 1;
+            file.status = FileStatus::Computing;
+            file.computation_start_time = Some(std::time::Instant::now());
+            file.progress = 0.0;
+            file.error = None;
+
+            if let Some(tracker) = &self.progress_tracker {
+                tracker.start_file(path, file.size);
+            }
+        }
+    }
+
+    /// 文件计算完成：更新表格行与进度，新算出的结果加入待保存队列
+    fn handle_file_completed(&mut self, msg: UiMessage) {
+        let UiMessage::FileCompleted {
+            path,
+            crc32,
+            md5,
+            sha1,
+            sha256,
+            crc64,
+            adler32,
+            xxhash3,
+            duration_ms,
+            modified_time,
+            file_size,
+            from_cache,
+            unchanged,
+            computed_at,
+            ..
+        } = msg
+        else {
+            return;
+        };
+        self.batch_timeline.file_finished(std::time::Instant::now());
+        if let Some(&idx) = self.file_index.get(&path) {
+            let file = &mut self.files[idx];
+            self.sources
+                .replace(SourceFilter::source(file), Some(from_cache));
+            self.rows_generation += 1;
+
+            file.status = FileStatus::Completed;
+            file.crc32 = crc32.clone();
+            file.md5 = md5.clone();
+            file.sha1 = sha1.clone();
+            file.sha256 = sha256.clone();
+            file.crc64 = crc64.clone();
+            file.adler32 = adler32.clone();
+            file.xxhash3 = xxhash3.clone(); // 确保更新
+            file.progress = 1.0;
+            file.computation_duration_ms = Some(duration_ms);
+            file.computation_start_time = None;
+            file.from_cache = from_cache;
+            file.unchanged = unchanged;
+            file.modified_time = Some(modified_time).filter(|&t| t != 0);
+            file.computed_at = Some(computed_at).filter(|&t| t != 0);
+
+            self.processed_size += file.size;
+            self.hash_search.dirty = true;
+            self.hash_list.dirty = true;
+            if xattrs::SUPPORTED && self.cache_config.write_xattrs {
+                self.xattr_pending.push(file.xattr_target());
+            }
+
+            if let Some(tracker) = &self.progress_tracker {
+                tracker.complete_file(&path);
+                self.global_progress = tracker.get_global_progress();
+            }
+            self.append_stream_export(idx);
+
+            // 如果不是来自缓存，加入待保存队列（快速模式的结果不完整，不缓存）
+            let hashes = FileHashes {
+                crc32,
+                md5,
+                sha1,
+                sha256,
+                crc64,
+                adler32,
+                xxhash3,
+            };
+            if !from_cache && !hashes.is_xxh3_only() {
+                self.pending_cache_entries.push(CacheEntry::new(
+                    path.clone(),
+                    file_size,
+                    modified_time,
+                    unix_now(),
+                    hashes,
+                ));
+            }
+        }
+        self.batch_done.push(path);
+    }
+
+    fn handle_file_failed(&mut self, path: PathBuf, error: String) {
+        self.batch_timeline.file_finished(std::time::Instant::now());
+        if let Some(&idx) = self.file_index.get(&path) {
+            let file = &mut self.files[idx];
+            self.sources.replace(SourceFilter::source(file), None);
+            self.rows_generation += 1;
+            file.status = FileStatus::Failed;
+            file.error = Some(error).filter(|e| !e.is_empty());
+            file.computation_start_time = None;
+        }
+        if let Some(tracker) = &self.progress_tracker {
+            tracker.complete_file(&path);
+            self.global_progress = tracker.get_global_progress();
+        }
+        self.batch_done.push(path);
+    }
+
+    /// 强制重新计算的结果与缓存不同，同一文件只保留最近一次变更
+    fn record_hash_change(&mut self, change: HashChange) {
+        self.hash_changes.retain(|c| c.path != change.path);
+        self.rows_generation += 1;
+        self.hash_changes.push(change);
+        self.show_hash_changes = true;
+    }
+
+    fn handle_file_missing(&mut self, path: PathBuf) {
+        self.batch_timeline.file_finished(std::time::Instant::now());
+        let mut size = 0;
+        if let Some(&idx) = self.file_index.get(&path) {
+            let file = &mut self.files[idx];
+            self.sources.replace(SourceFilter::source(file), None);
+            self.rows_generation += 1;
+            file.status = FileStatus::Deleted;
+            file.computation_start_time = None;
+            size = file.size;
+            self.total_size = self.total_size.saturating_sub(size);
+        }
+        if let Some(tracker) = &self.progress_tracker {
+            tracker.remove_file(&path, size);
+            self.global_progress = tracker.get_global_progress();
+        }
+        self.batch_done.push(path);
+    }
+
+    fn handle_quick_hashed(&mut self, path: PathBuf, quick_hash: String) {
+        if let Some(&idx) = self.file_index.get(&path) {
+            let file = &mut self.files[idx];
+            self.sources.replace(SourceFilter::source(file), None);
+            self.rows_generation += 1;
+            file.status = FileStatus::QuickHashed;
+            file.quick_hash = Some(quick_hash);
+            file.progress = 1.0;
+            self.processed_size += file.size;
+
+            if let Some(tracker) = &self.progress_tracker {
+                tracker.start_file(path.clone(), file.size);
+                tracker.complete_file(&path);
+                self.global_progress = tracker.get_global_progress();
+            }
+        }
+        self.batch_done.push(path);
+    }
+
+    fn handle_progress(&mut self, path: &Path, processed: u64, total: u64) {
+        let Some(&idx) = self.file_index.get(path) else {
+            return;
+        };
+        let file = &mut self.files[idx];
+        if matches!(file.status, FileStatus::Completed) {
+            return;
+        }
+        if total > 0 {
+            file.progress = processed as f64 / total as f64;
+        }
+        if let Some(tracker) = &self.progress_tracker {
+            tracker.update_progress(path, processed);
+            self.global_progress = tracker.get_global_progress();
+        }
+    }
+
+    fn handle_directory_hashed(&mut self, root: &Path, result: Result<FileHashes, String>) {
+        if let Some(view) = &mut self.directory_hash {
+            // 窗口已切换到其他目录时丢弃旧结果
+            if view.root == root {
+                view.result = Some(result);
+            }
+        }
+    }
+
+    fn handle_chunk_hash_progress(&mut self, path: &Path, chunk_size: u64, progress: (u64, u64)) {
+        let current = self
+            .chunk_hash
+            .as_mut()
+            .filter(|view| view.path == path && view.chunk_size == chunk_size);
+        if let Some(view) = current {
+            view.progress = Some(progress);
+        }
+    }
+
+    fn handle_chunks_hashed(&mut self, path: &Path, result: Result<ChunkedHashes, String>) {
+        if let Some(view) = &mut self.chunk_hash {
+            // 计算期间换了文件或块大小时丢弃旧结果
+            let current = match &result {
+                Ok(hashes) => hashes.chunk_size == view.chunk_size,
+                Err(_) => true,
+            };
+            if view.path == path && current {
+                view.result = Some(result);
+            }
+        }
+    }
+
+    fn handle_cache_audited(&mut self, root: &Path, result: Result<AuditReport, String>) {
+        if let Some(view) = &mut self.audit {
+            if view.root == root {
+                view.result = Some(result);
+            }
+        }
+    }
+
+    fn handle_cleanup_progress(&mut self, progress: CleanupProgress) {
+        if let Some(view) = &mut self.cleanup {
+            view.progress = Some(progress);
+        }
+    }
+
+    fn handle_cleanup_finished(&mut self, result: Result<CleanupOutcome, String>) {
+        self.cleanup = None;
+        self.cache_operation_message = Some(match result {
+            Ok(outcome) if outcome.cancelled => {
+                tf(Text::CleanupCancelled, &[&format_count(outcome.deleted)])
+            }
+            Ok(outcome) => match outcome.kind {
+                CleanupKind::Expired => {
+                    tf(Text::CleanupExpiredDone, &[&format_count(outcome.deleted)])
+                }
+                CleanupKind::All => tf(Text::CleanupAllDone, &[&format_count(outcome.deleted)]),
+            },
+            Err(e) => tf(Text::FailedWith, &[&e]),
+        });
+    }
+
+    fn handle_notes_loaded(&mut self, notes: HashMap<PathBuf, String>) {
+        for (path, note) in notes {
+            if let Some(&idx) = self.file_index.get(&path) {
+                // 读取期间用户已经输入的备注优先
+                if self.files[idx].note.is_empty() {
+                    self.files[idx].note = note;
+                }
+            }
+        }
+    }
+
+    fn handle_rename_planned(&mut self, plan: Vec<RenamePlanEntry>) {
+        if let Some(view) = &mut self.rename {
+            view.planning = false;
+            view.confirmed = false;
+            view.plan = Some(plan);
+        }
+    }
+
+    fn handle_checksums_fetched(&mut self, result: Result<HashMap<String, ExpectedHash>, String>) {
+        match result {
+            Ok(list) => self.apply_remote_checksums(&list),
+            Err(e) => {
+                if let Some(view) = &mut self.remote_verify {
+                    view.fetching = false;
+                    view.error = Some(e);
+                }
+            }
+        }
+    }
+
+    fn handle_manifest_checked(
+        &mut self,
+        result: Result<(HashMap<String, ExpectedHash>, ManifestReport), String>,
+    ) {
+        match result {
+            Ok((list, report)) => self.start_manifest_verify(list, report),
+            Err(e) => {
+                log::warn!("无法读取校验清单: {}", e);
+                self.status_toast = Some((
+                    tf(Text::ManifestUnreadable, &[&e]),
+                    std::time::Instant::now(),
+                ));
+            }
+        }
+    }
+
+    fn handle_benchmark_finished(&mut self, result: Result<BenchmarkReport, String>) {
+        if let Some(view) = &mut self.benchmark {
+            view.running = false;
+            view.result = Some(result);
+        }
+    }
+
+    fn handle_rehashed(&mut self, result: Result<RehashReport, String>) {
+        if let Some(view) = &mut self.rehash {
+            let matches_view = match &result {
+                Ok(report) => report.params.path == view.params.path,
+                Err(_) => true,
+            };
+            if matches_view {
+                view.running = false;
+                view.result = Some(result);
+            }
+        }
+    }
+
+    fn handle_files_compared(&mut self, result: Result<FileComparison, String>) {
+        if let Some(view) = &mut self.comparison {
+            let matches_view = match &result {
+                Ok(comparison) => comparison.paths == view.paths,
+                Err(_) => true,
+            };
+            if matches_view {
+                view.result = Some(result);
+            }
+        }
+    }
+
+    fn handle_cache_saved(&mut self, duration_ms: u64) {
+        let duration = std::time::Duration::from_millis(duration_ms);
+        // 批次结束时提交的最后一批缓存在结束后才写完，计入上一批次
+        match &mut self.last_timeline {
+            Some(last) if !self.batch_timeline.has_computed() => {
+                last.cache_saved(duration);
+            }
+            _ => self.batch_timeline.cache_saved(duration),
+        }
+    }
+
+    fn handle_scan_finished(&mut self) {
+        self.batch_timeline.scan_finished(std::time::Instant::now());
+        // 扫描出的文件已按拖放路径归类，之后加入的文件与这些路径无关
+        self.pending_scans = self.pending_scans.saturating_sub(1);
+        if self.pending_scans == 0 {
+            self.dropped_roots.clear();
+        }
+    }
+
+    /// 批次结束：提交剩余的缓存与扩展属性，收尾工作由 `finalize_batch` 完成
+    fn handle_all_completed(&mut self) {
+        self.is_computing = false;
+        self.sleep_inhibitor = None;
+        self.global_progress = 1.0;
+        if let Some(tracker) = &self.progress_tracker {
+            tracker.reset();
+        }
+        self.progress_tracker = None;
+
+        if !self.pending_cache_entries.is_empty() {
+            let _ = self.worker_tx.send(WorkerMessage::SaveCache(std::mem::take(
+                &mut self.pending_cache_entries,
+            )));
+        }
+        self.batch_done.clear();
+        let _ = self.worker_tx.send(WorkerMessage::ClearBatchQueue);
+        self.flush_xattr_pending();
+
+        if self.tray.is_some() {
+            let completed = self
+                .files
+                .iter()
+                .filter(|f| matches!(f.status, FileStatus::Completed))
+                .count();
+            self.set_tray_tooltip(tf(Text::TrayCompleted, &[&completed]));
+        }
+        self.report_sidecar_results();
+    }
+
+    /// 批处理结束且窗口未聚焦时发送系统通知
+    fn notify_batch_completed(&self, ctx: &egui::Context) {
+        let focused = ctx.input(|i| i.viewport().focused).unwrap_or(false);
+        if !should_notify_completion(
+            &self.cache_config,
+            self.tray.is_some(),
+            self.batch_total_duration_ms,
+            focused,
+        ) {
+            return;
+        }
+
+        let completed = self
+            .files
+            .iter()
+            .filter(|f| matches!(f.status, FileStatus::Completed))
+            .count();
+        send_notification(tf(
+            Text::BatchCompletedNotice,
+            &[
+                &format_count(completed),
+                &format_duration(self.batch_total_duration_ms),
+            ],
+        ));
+    }
+
+    fn schedule_auto_compute(&mut self) {
+        self.last_file_add_time = Some(std::time::Instant::now());
+        self.auto_compute_scheduled = true;
+    }
+
+    fn check_and_execute_auto_compute(&mut self) {
+        // 计算期间加入的文件等当前批次结束后再计算，保持已安排的状态
+        if !self.auto_compute_scheduled || self.is_computing {
+            return;
+        }
+
+        if let Some(last_add_time) = self.last_file_add_time {
+            let elapsed = last_add_time.elapsed().as_millis() as u64;
+
+            if elapsed >= self.debounce_duration_ms {
+                self.start_computing();
+                self.last_file_add_time = None;
+                self.auto_compute_scheduled = false;
+            }
+        }
+    }
+
+    /// 保存配置并同步到 Worker 使用的运行时配置
+    fn persist_config(&self) {
+        if let Ok(mut guard) = self.cache.lock() {
+            guard.set_config(self.cache_config.clone());
+            if let Err(e) = guard.save_cache_config(&self.cache_config) {
+                log::error!("保存配置失败: {}", e);
+            }
+        }
+    }
+
+    fn set_tray_tooltip(&mut self, text: String) {
+        if let Some(tray) = &self.tray {
+            if text != self.tray_tooltip {
+                tray.set_tooltip(&text);
+                self.tray_tooltip = text;
+            }
+        }
+    }
+
+    fn format_hash(&self, hash_value: &str) -> String {
+        format_digest(
+            hash_value,
+            self.cache_config.hash_encoding,
+            self.uppercase_display,
+        )
+    }
+
+    /// 「列」菜单：勾选表格中显示的列并保存到配置，未启用算法的哈希列不可勾选
+    fn render_columns_menu(&mut self, ui: &mut egui::Ui) {
+        let mut changed = false;
+        for column in TableColumn::ALL.into_iter().filter(|c| c.is_toggleable()) {
+            let enabled = match column {
+                TableColumn::Hash(algorithm) => {
+                    self.cache_config.enabled_hashes.contains(algorithm)
+                }
+                _ => true,
+            };
+            let mut visible = self.cache_config.visible_columns.contains(column);
+            if ui
+                .add_enabled(enabled, egui::Checkbox::new(&mut visible, column.label()))
+                .on_disabled_hover_text(t(Text::AlgorithmDisabled))
+                .changed()
+            {
+                self.cache_config.visible_columns.set(column, visible);
+                changed = true;
+            }
+        }
+        if changed {
+            self.persist_config();
+        }
+    }
+
+    /// 将所有已完成的行格式化为制表符分隔的文本块（含表头）
+    fn format_completed_rows(&self) -> (String, usize) {
+        let algorithms: Vec<HashAlgorithm> = HashAlgorithm::ALL
+            .into_iter()
+            .filter(|a| self.cache_config.enabled_hashes.contains(*a))
+            .collect();
+
+        // 只有存在备注时才附加备注列，保持原有格式
+        let with_notes = self
+            .files
+            .iter()
+            .any(|f| matches!(f.status, FileStatus::Completed) && !f.note.is_empty());
+
+        let mut text = String::from(t(Text::CopyAllHeader));
+        for algorithm in &algorithms {
+            text.push('\t');
+            text.push_str(algorithm.label());
+        }
+        if with_notes {
+            text.push_str(t(Text::CopyAllNoteHeader));
+        }
+        text.push('\n');
+
+        let mut count = 0;
+        for file in &self.files {
+            if !matches!(file.status, FileStatus::Completed) {
+                continue;
+            }
+            text.push_str(&format!("{}\t{}", file.filename(), file.size));
+            for algorithm in &algorithms {
+                text.push('\t');
+                text.push_str(&self.format_hash(file.hash_value(*algorithm)));
+            }
+            if with_notes {
+                // 备注中的制表符与换行会破坏表格结构
+                text.push('\t');
+                text.push_str(&file.note.replace(['\t', '\n', '\r'], " "));
+            }
+            text.push('\n');
+            count += 1;
+        }
+
+        (text, count)
+    }
+
+    /// 将剪贴板中的哈希与指定行的计算结果比对
+    fn verify_row_against_clipboard(&mut self, idx: usize) {
+        let Some(text) = read_clipboard_text() else {
+            self.status_toast = Some((
+                t(Text::ClipboardUnreadable).to_string(),
+                std::time::Instant::now(),
+            ));
+            return;
+        };
+
+        let Some(file) = self.files.get_mut(idx) else {
+            return;
+        };
+        let outcome = verify_against(&text, file);
+        file.clipboard_verify = Some((outcome.is_match(), std::time::Instant::now()));
+        self.rows_generation += 1;
+        self.status_toast = Some((
+            format!("{}: {}", file.filename(), outcome.message()),
+            std::time::Instant::now(),
+        ));
+    }
+
+    /// 复制指定行的单行校验命令（PowerShell / bash）
+    fn copy_verify_command(&mut self, ctx: &egui::Context, idx: usize, shell: VerifyShell) {
+        let Some(file) = self.files.get(idx) else {
+            return;
+        };
+        let message = match verify_command(file, shell) {
+            Some(command) => {
+                ctx.copy_text(command);
+                tf(
+                    Text::VerifyCommandCopied,
+                    &[&shell.label(), &file.filename()],
+                )
+            }
+            None => String::from(t(Text::NoVerifyCommandHash)),
+        };
+        self.status_toast = Some((message, std::time::Instant::now()));
+    }
+
+    fn copy_all_results(&mut self, ctx: &egui::Context) {
+        let (text, count) = self.format_completed_rows();
+        if count == 0 {
+            self.status_toast = Some((
+                t(Text::NoCompletedResults).to_string(),
+                std::time::Instant::now(),
+            ));
+            return;
+        }
+        ctx.copy_text(text);
+        self.status_toast = Some((tf(Text::RowsCopied, &[&count]), std::time::Instant::now()));
+    }
+
+    fn render_export_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.export_dialog.open;
+        let mut export_clicked = false;
+        let (mut stream_start, mut stream_stop) = (false, false);
+        egui::Window::new(t(Text::ExportChecksums))
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                let dialog = &mut self.export_dialog;
+                egui::Grid::new("export_grid")
+                    .num_columns(2)
+                    .spacing([12.0, 8.0])
+                    .show(ui, |ui| {
+                        ui.label(t(Text::Format));
+                        egui::ComboBox::from_id_salt("export_format")
+                            .selected_text(dialog.format.label())
+                            .show_ui(ui, |ui| {
+                                for format in ChecksumFormat::ALL {
+                                    ui.selectable_value(&mut dialog.format, format, format.label());
+                                }
+                            });
+                        ui.end_row();
+
+                        ui.label(t(Text::LetterCase));
+                        let index = dialog.case_index();
+                        ui.horizontal(|ui| {
+                            for case in [HexCase::Lower, HexCase::Upper] {
+                                let label = if case == dialog.format.default_case() {
+                                    tf(Text::CaseConventional, &[&case.label()])
+                                } else {
+                                    case.label().to_string()
+                                };
+                                ui.radio_value(&mut dialog.cases[index], case, label);
+                            }
+                        });
+                        ui.end_row();
+                    });
+
+                ui.add_space(8.0);
+                if ui.button(t(Text::ExportEllipsis)).clicked() {
+                    export_clicked = true;
+                }
+
+                ui.add_space(8.0);
+                ui.separator();
+                ui.strong(t(Text::StreamExport));
+                ui.label(
+                    egui::RichText::new(
+                        "计算开始时创建文件，每个文件完成后立即追加一行，结果不在内存中累积；\
+                         批次被停止时文件中保留已完成的部分",
+                    )
+                    .small()
+                    .weak(),
+                );
+                if let Some(export) = &self.stream_export {
+                    ui.label(tf(Text::StreamExportWriting, &[&export.path().display()]));
+                    if ui.button(t(Text::StopStreamExport)).clicked() {
+                        stream_stop = true;
+                    }
+                } else if let Some((path, format)) = &self.stream_export_target {
+                    ui.label(tf(
+                        Text::StreamExportArmed,
+                        &[&format.label(), &path.display()],
+                    ));
+                    if ui.button(t(Text::Cancel)).clicked() {
+                        stream_stop = true;
+                    }
+                } else {
+                    ui.horizontal(|ui| {
+                        let dialog = &mut self.export_dialog;
+                        egui::ComboBox::from_id_salt("stream_export_format")
+                            .selected_text(dialog.stream_format.label())
+                            .show_ui(ui, |ui| {
+                                for format in StreamFormat::ALL {
+                                    ui.selectable_value(
+                                        &mut dialog.stream_format,
+                                        format,
+                                        format.label(),
+                                    );
+                                }
+                            });
+                        if ui.button(t(Text::ChooseFile)).clicked() {
+                            stream_start = true;
+                        }
+                    });
+                }
+            });
+        self.export_dialog.open = open;
+
+        if export_clicked {
+            self.export_checksum_file();
+        }
+        if stream_start {
+            self.choose_stream_export();
+        }
+        if stream_stop {
+            self.stream_export_target = None;
+            self.close_stream_export();
+        }
+    }
+
+    /// 选择流式导出的目标文件；正在计算时立即开始写入，否则等到下一批次开始
+    fn choose_stream_export(&mut self) {
+        use rfd::FileDialog;
+        let format = self.export_dialog.stream_format;
+        let Some(target) = FileDialog::new()
+            .set_title(t(Text::StreamExport))
+            .set_file_name(format!("results.{}", format.extension()))
+            .add_filter(format.label(), &[format.extension()])
+            .save_file()
+        else {
+            return;
+        };
+        self.stream_export_target = Some((target, format));
+        if self.is_computing {
+            self.open_stream_export();
+        }
+    }
+
+    fn export_checksum_file(&mut self) {
+        use rfd::FileDialog;
+        let format = self.export_dialog.format;
+        let case = self.export_dialog.cases[self.export_dialog.case_index()];
+
+        let Some(target) = FileDialog::new()
+            .set_title(t(Text::ExportChecksums))
+            .set_file_name(format!("checksums.{}", format.extension()))
+            .add_filter(format.label(), &[format.extension()])
+            .save_file()
+        else {
+            return;
+        };
+
+        let base_dir = target.parent().map(PathBuf::from).unwrap_or_default();
+        let (content, written, skipped) =
+            format_checksum_file(&self.files, format, case, &base_dir);
+        let message = if written == 0 {
+            tf(Text::NothingToExport, &[&format.algorithm().label()])
+        } else {
+            match std::fs::write(&target, content) {
+                Ok(()) if skipped > 0 => tf(Text::ExportedSkipped, &[&written, &skipped]),
+                Ok(()) => tf(Text::Exported, &[&written]),
+                Err(e) => tf(Text::ExportFailed, &[&e]),
+            }
+        };
+        self.status_toast = Some((message, std::time::Instant::now()));
+    }
+
+    fn show_hash_cell(
+        &mut self,
+        ui: &mut egui::Ui,
+        ctx: &egui::Context,
+        hash_value: &str,
+        unique_id: &str,
+    ) -> egui::Response {
+        if hash_value.is_empty() {
+            ui.label(egui::RichText::new("-").weak().italics())
+        } else {
+            let display_value = self.format_hash(hash_value);
+
+            let show_toast = self
+                .clipboard_toast
+                .as_ref()
+                .map_or(false, |(id, _)| id == unique_id);
+            let overlay = self.cache_config.copy_feedback == CopyFeedback::Overlay;
+            let label_text = if show_toast && !overlay {
+                egui::RichText::new(t(Text::CopiedToClipboard)).color(egui::Color32::GREEN)
+            } else {
+                egui::RichText::new(&display_value).monospace()
+            };
+
+            let response = ui.label(label_text).on_hover_text(t(Text::ClickToCopy));
+
+            if response.hovered() {
+                ui.painter().rect_filled(
+                    response.rect,
+                    egui::CornerRadius::same(4),
+                    egui::Color32::from_rgba_premultiplied(60, 60, 60, 50),
+                );
+            }
+
+            // 浮动提示显示在单元格上方，哈希值保持可见
+            if show_toast && overlay {
+                egui::Area::new(egui::Id::new("clipboard_toast"))
+                    .order(egui::Order::Tooltip)
+                    .interactable(false)
+                    .fixed_pos(response.rect.left_top() - egui::vec2(0.0, 26.0))
+                    .show(ctx, |ui| {
+                        egui::Frame::popup(ui.style()).show(ui, |ui| {
+                            ui.label(
+                                egui::RichText::new(t(Text::CopiedToClipboard))
+                                    .color(egui::Color32::GREEN),
+                            );
+                        });
+                    });
+            }
+
+            if response.clicked() {
+                ctx.copy_text(display_value.clone());
+                self.clipboard_toast = Some((unique_id.to_string(), std::time::Instant::now()));
+            }
+
+            response
+        }
+    }
+
+    /// 计算时间列：显示距今时长，悬停显示具体时间；超过保留期限的缓存结果标为橙色
+    fn show_computed_at_cell(&self, ui: &mut egui::Ui, computed_at: Option<u64>, now: u64) {
+        let Some(computed_at) = computed_at else {
+            ui.label("-");
+            return;
+        };
+        let age = format_age(now.saturating_sub(computed_at));
+        let time = format_timestamp(computed_at);
+        if self.cache_config.is_past_retention(computed_at, now) {
+            ui.label(
+                egui::RichText::new(format!("⚠ {}", age))
+                    .color(egui::Color32::from_rgb(230, 150, 30)),
+            )
+            .on_hover_text(format!(
+                "{}\n{}",
+                time,
+                t(Text::PastRetentionHint)
+                    .replace("{}", &self.cache_config.retention_days.to_string())
+            ));
+        } else {
+            ui.label(age).on_hover_text(time);
+        }
+    }
+
+    /// 按文件夹分组的视图；`rows` 为只看问题时显示的文件
+    fn show_folder_tree(
+        &mut self,
+        ui: &mut egui::Ui,
+        ctx: &egui::Context,
+        columns: &[TableColumn],
+        rows: Option<&[usize]>,
+    ) {
+        // 扫描过程中文件不断增加，分组随之刷新
+        let key = (self.rows_generation, self.only_problems, self.source_filter);
+        if self.folder_tree.0 != key {
+            let tree = match rows {
+                Some(rows) => build_folder_tree(&self.files, rows.iter().copied()),
+                None => build_folder_tree(&self.files, 0..self.files.len()),
+            };
+            self.folder_tree = (key, tree);
+        }
+
+        // 汇总在文件列表或进度变化时一次遍历算出，不在每个文件夹标题中重复统计
+        let summary_key = (key, self.global_progress.to_bits());
+        if self.folder_summaries.0 != summary_key {
+            let summaries = self.folder_tree.1.summarize_all(&self.files);
+            self.folder_summaries = (summary_key, summaries);
+        }
+
+        let tree = std::mem::take(&mut self.folder_tree.1);
+        let now = unix_now();
+        // 只有顶层文件夹默认展开
+        let folder_state = |node: &FolderNode, depth: usize| {
+            let id = egui::Id::new(("folder", &node.path));
+            egui::collapsing_header::CollapsingState::load_with_default_open(ctx, id, depth == 0)
+        };
+        let visible = tree.visible_rows(|node, depth| folder_state(node, depth).is_open());
+        let row_height = ui.spacing().interact_size.y.max(24.0);
+        // 只渲染可见的行，文件很多时不必每帧布局整棵树
+        ScrollArea::both()
+            .id_salt("folder_tree")
+            .auto_shrink([false; 2])
+            .max_height((ui.clip_rect().bottom() - ui.cursor().top()).max(row_height))
+            .show_rows(ui, row_height, visible.len(), |ui, range| {
+                for row in &visible[range] {
+                    ui.horizontal(|ui| {
+                        ui.set_height(row_height);
+                        match *row {
+                            FolderRow::Folder { node, depth } => {
+                                indent_row(ui, depth);
+                                let mut state = folder_state(node, depth);
+                                state.show_toggle_button(
+                                    ui,
+                                    egui::collapsing_header::paint_default_icon,
+                                );
+                                state.store(ctx);
+                                self.show_folder_header(ui, node);
+                            }
+                            FolderRow::File { idx, depth } => {
+                                indent_row(ui, depth);
+                                self.show_folder_file(ui, ctx, idx, columns, now);
+                            }
+                        }
+                    });
+                }
+            });
+        self.folder_tree.1 = tree;
+    }
+
+    /// 文件夹标题：名称、完成数、总大小与进度
+    fn show_folder_header(&self, ui: &mut egui::Ui, node: &FolderNode) {
+        let summary = self
+            .folder_summaries
+            .1
+            .get(&node.path)
+            .copied()
+            .unwrap_or_default();
+        ui.strong(format!("📁 {}", node.name));
+        ui.label(format!(
+            "{}: {} / {}",
+            t(Text::FilesDone),
+            summary.completed,
+            summary.file_count
+        ));
+        ui.label(format_size(summary.total_size, self.cache_config.size_unit));
+        ui.add(
+            egui::ProgressBar::new(summary.progress as f32)
+                .desired_width(120.0)
+                .show_percentage(),
+        );
+    }
+
+    /// 文件夹下的一个文件，各列按表格中的宽度排列
+    fn show_folder_file(
+        &mut self,
+        ui: &mut egui::Ui,
+        ctx: &egui::Context,
+        idx: usize,
+        columns: &[TableColumn],
+        now: u64,
+    ) {
+        // 解决借用冲突：提前克隆需要的数据
+        let file = self.files[idx].clone();
+        let widths = self.cache_config.column_widths;
+        for column in columns {
+            let size = egui::vec2(cell_width(*column, widths), ui.available_height());
+            let (rect, _) = ui.allocate_exact_size(size, egui::Sense::hover());
+            // 一个单元格可能包含多个控件，超出列宽的部分裁掉
+            let mut cell = ui.new_child(
+                egui::UiBuilder::new()
+                    .max_rect(rect)
+                    .layout(egui::Layout::left_to_right(egui::Align::Center)),
+            );
+            cell.set_clip_rect(rect.intersect(ui.clip_rect()));
+            self.show_file_cell(&mut cell, ctx, *column, idx, &file, now);
+        }
+    }
+
+    /// 渲染文件表格的一侧，返回本帧的垂直滚动偏移
+    fn show_file_table(
+        &mut self,
+        ui: &mut egui::Ui,
+        ctx: &egui::Context,
+        part: &TablePart,
+        view: &TableView,
+    ) -> f32 {
+        let scroll_bar = if part.fill {
+            ScrollBarVisibility::VisibleWhenNeeded
+        } else {
+            ScrollBarVisibility::AlwaysHidden
+        };
+        let painter = ui.painter().clone();
+        let focus_stroke = ui.visuals().selection.stroke;
+        // 列集合变化（如启用了新的算法）时使用新的表格状态，各列重新按保存的宽度排列
+        let mut table = TableBuilder::new(ui)
+            .id_salt((part.id_salt, part.columns))
+            .striped(true)
+            .sense(egui::Sense::click())
+            .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
+            .vertical_scroll_offset(view.scroll_y)
+            .scroll_bar_visibility(scroll_bar);
+        let is_last = |i: usize| part.fill && i + 1 == part.columns.len();
+        let saved_widths = self.cache_config.column_widths;
+        for (i, column) in part.columns.iter().enumerate() {
+            table = table.column(table_column(*column, is_last(i), saved_widths));
+        }
+        if let Some((row, align)) = view.scroll_to_row {
+            table = table.scroll_to_row(row, align);
+        }
+        let row_count = view.rows.map_or(self.files.len(), <[usize]>::len);
+        let mut header_widths = Vec::with_capacity(part.columns.len());
+        let output = table
+            .header(30.0, |mut header| {
+                for column in part.columns {
+                    header.col(|ui| {
+                        header_widths.push(ui.max_rect().width());
+                        let response = ui.strong(column.label());
+                        if *column == TableColumn::Status {
+                            response.on_hover_ui(render_status_legend);
+                        }
+                    });
+                }
+            })
+            .body(|body| {
+                body.rows(30.0, row_count, |mut row| {
+                    let idx = view.rows.map_or(row.index(), |rows| rows[row.index()]);
+                    row.set_selected(
+                        view.highlighted.contains(&idx) || self.selected_rows.contains(&idx),
+                    );
+                    if view.hovered_row == Some(idx) {
+                        row.set_hovered(true);
+                    }
+                    if idx < self.files.len() {
+                        // 解决借用冲突：提前克隆需要的数据
+                        let file = self.files[idx].clone();
+                        for column in part.columns {
+                            row.col(|ui| {
+                                self.show_file_cell(ui, ctx, *column, idx, &file, view.now);
+                            });
+                        }
+
+                        let response = row.response();
+                        if response.hovered() {
+                            self.table_hovered_row = Some(idx);
+                        }
+                        if response.clicked() {
+                            let modifiers = ctx.input(|i| i.modifiers);
+                            self.select_row(idx, modifiers, view.rows);
+                            self.focused_row = Some(idx);
+                        }
+                        if self.focused_row == Some(idx) {
+                            paint_focus_ring(&painter, response.rect, focus_stroke, part);
+                            // Enter 打开的菜单显示在行的左下角，而不是鼠标所在位置。右键菜单
+                            // 之后每帧都从记忆中读取打开时的位置，只能用这个接口写入
+                            if self.open_row_menu == Some(idx) {
+                                self.open_row_menu = None;
+                                let popup_id = egui::Popup::default_response_id(&response);
+                                let position = response.rect.left_bottom();
+                                #[allow(deprecated)]
+                                ctx.memory_mut(|mem| mem.open_popup_at(popup_id, Some(position)));
+                            }
+                        }
+                        response.context_menu(|ui| self.show_row_menu(ui, idx));
+                    }
+                });
+            });
+
+        // 拖动结束后保存与初始宽度不同的列宽，下次启动时恢复
+        if !ctx.input(|i| i.pointer.any_down()) {
+            let mut changed = false;
+            for (i, (column, width)) in part.columns.iter().zip(header_widths).enumerate() {
+                if let Some(initial) = initial_width(*column, saved_widths)
+                    && !is_last(i)
+                    && (width - initial).abs() >= 1.0
+                {
+                    changed |= self.cache_config.column_widths.set(*column, width);
+                }
+            }
+            if changed {
+                self.persist_config();
+            }
+        }
+        output.state.offset.y
+    }
+
+    /// 表格中的一个单元格；`file` 为克隆的数据，不再持有 self.files 的借用
+    fn show_file_cell(
+        &mut self,
+        ui: &mut egui::Ui,
+        ctx: &egui::Context,
+        column: TableColumn,
+        idx: usize,
+        file: &FileItem,
+        now: u64,
+    ) {
+        match column {
+            TableColumn::Status => match file.clipboard_verify.map(|(ok, _)| ok) {
+                Some(true) => {
+                    ui.label(
+                        egui::RichText::new("✓")
+                            .color(egui::Color32::GREEN)
+                            .strong(),
+                    );
+                }
+                Some(false) => {
+                    ui.label(egui::RichText::new("✗").color(egui::Color32::RED).strong());
+                }
+                None => {
+                    let response = ui.label(file.status_badge().rich_text(ui.visuals()));
+                    if let (FileStatus::Failed, Some(error)) = (&file.status, &file.error) {
+                        response.on_hover_text(error);
+                    } else if let Some(quick_hash) = &file.quick_hash {
+                        response.on_hover_text(format!(
+                            "{}: {}",
+                            t(Text::QuickHashHint),
+                            quick_hash
+                        ));
+                    }
+                    if let Some(expected) = &file.expected {
+                        render_sidecar_mark(ui, expected, file);
+                    }
+                    if self
+                        .manifest
+                        .as_ref()
+                        .is_some_and(|manifest| manifest.untracked.contains(&file.path))
+                    {
+                        ui.label(
+                            egui::RichText::new(t(Text::Untracked))
+                                .color(egui::Color32::from_rgb(230, 150, 30))
+                                .strong(),
+                        )
+                        .on_hover_text(t(Text::UntrackedHint));
+                    }
+                    if let Some(algorithm) = self
+                        .hash_list
+                        .list
+                        .as_ref()
+                        .and_then(|list| list.find(file))
+                    {
+                        ui.label(
+                            egui::RichText::new(t(Text::HashListMatched))
+                                .color(egui::Color32::RED)
+                                .strong(),
+                        )
+                        .on_hover_text(format!(
+                            "{} {}",
+                            algorithm.label(),
+                            t(Text::InHashList)
+                        ));
+                    }
+                }
+            },
+            TableColumn::Filename => {
+                ui.label(file.filename());
+            }
+            TableColumn::Extension => {
+                ui.label(file.extension().unwrap_or_else(|| String::from("-")));
+            }
+            TableColumn::Size => {
+                ui.label(format_size(file.size, self.cache_config.size_unit));
+            }
+            TableColumn::Modified => {
+                ui.label(
+                    file.modified_time
+                        .map_or_else(|| String::from("-"), format_modified_time),
+                );
+            }
+            TableColumn::ComputedAt => {
+                self.show_computed_at_cell(ui, file.computed_at, now);
+            }
+            TableColumn::Duration => {
+                ui.label(file.duration_str());
+            }
+            TableColumn::Throughput => {
+                ui.label(file.throughput_str(self.cache_config.size_unit));
+            }
+            TableColumn::Progress => {
+                egui::ProgressBar::new(file.progress as f32)
+                    .show_percentage()
+                    .ui(ui);
+            }
+            TableColumn::ShortId => {
+                let path_str = dunce::simplified(&file.path).display().to_string();
+                self.show_hash_cell(ui, ctx, file.short_id(), &format!("{}_shortid", path_str));
+            }
+            TableColumn::Note => {
+                let response = ui.add(
+                    egui::TextEdit::singleline(&mut self.files[idx].note)
+                        .hint_text(t(Text::AddNote))
+                        .desired_width(f32::INFINITY),
+                );
+                // 编辑结束时保存，避免每次按键都写数据库
+                if response.lost_focus() {
+                    let file = &self.files[idx];
+                    let _ = self.worker_tx.send(WorkerMessage::SaveNote(
+                        file.path.clone(),
+                        file.note.clone(),
+                    ));
+                }
+            }
+            TableColumn::Hash(algorithm) => {
+                let path_str = dunce::simplified(&file.path).display().to_string();
+                self.show_hash_cell(
+                    ui,
+                    ctx,
+                    file.hash_value(algorithm),
+                    &format!("{}_{}", path_str, algorithm.key()),
+                );
+            }
+        }
+    }
+
+    /// 行的右键菜单
+    /// 单击选中一行（再次单击取消），Ctrl/⌘ 单击增减，Shift 单击按显示顺序选中到上次单击的行
+    fn select_row(&mut self, idx: usize, modifiers: egui::Modifiers, rows: Option<&[usize]>) {
+        if modifiers.shift {
+            let position =
+                |idx: usize| rows.map_or(Some(idx), |rows| rows.iter().position(|&r| r == idx));
+            if let Some((from, to)) = self.selection_anchor.and_then(position).zip(position(idx)) {
+                if !modifiers.command {
+                    self.selected_rows.clear();
+                }
+                self.selected_rows.extend(
+                    (from.min(to)..=from.max(to)).map(|pos| rows.map_or(pos, |rows| rows[pos])),
+                );
+                return;
+            }
+        }
+
+        if modifiers.command {
+            if !self.selected_rows.remove(&idx) {
+                self.selected_rows.insert(idx);
+            }
+        } else if self.selected_rows.len() == 1 && self.selected_rows.contains(&idx) {
+            self.selected_rows.clear();
+        } else {
+            self.selected_rows.clear();
+            self.selected_rows.insert(idx);
+        }
+        self.selection_anchor = Some(idx);
+    }
+
+    /// 表格的键盘操作：方向键、PageUp/PageDown、Home/End 移动焦点行，空格切换选中，
+    /// Enter 打开该行的右键菜单，Esc 取消焦点。有输入框获得焦点或弹出菜单打开时不处理
+    fn handle_table_keys(&mut self, ctx: &egui::Context, rows: Option<&[usize]>) {
+        // 筛选或清空队列后焦点落到仍然显示的行上
+        self.focused_row = self
+            .focused_row
+            .and_then(|idx| focus_position(rows, self.files.len(), idx))
+            .map(|position| rows.map_or(position, |rows| rows[position]));
+
+        if ctx.memory(|mem| mem.focused().is_some()) || egui::Popup::is_any_open(ctx) {
+            return;
+        }
+        let (step, toggle, open_menu, escape) = ctx.input_mut(|i| {
+            let step = [
+                (egui::Key::ArrowUp, FocusMove::Up),
+                (egui::Key::ArrowDown, FocusMove::Down),
+                (egui::Key::PageUp, FocusMove::PageUp),
+                (egui::Key::PageDown, FocusMove::PageDown),
+                (egui::Key::Home, FocusMove::Home),
+                (egui::Key::End, FocusMove::End),
+            ]
+            .into_iter()
+            .find(|&(key, _)| i.consume_key(egui::Modifiers::NONE, key))
+            .map(|(_, step)| step);
+            (
+                step,
+                i.consume_key(egui::Modifiers::NONE, egui::Key::Space),
+                i.consume_key(egui::Modifiers::NONE, egui::Key::Enter),
+                i.consume_key(egui::Modifiers::NONE, egui::Key::Escape),
+            )
+        });
+
+        if let Some(step) = step {
+            self.focused_row = move_focus(rows, self.files.len(), self.focused_row, step);
+            self.focus_scroll = self.focused_row;
+        }
+        let Some(idx) = self.focused_row else {
+            return;
+        };
+        if toggle {
+            self.select_row(idx, egui::Modifiers::COMMAND, rows);
+        }
+        if open_menu {
+            self.open_row_menu = Some(idx);
+            self.focus_scroll = Some(idx);
+        }
+        if escape {
+            self.focused_row = None;
+        }
+    }
+
+    fn clear_selection(&mut self) {
+        self.selected_rows.clear();
+        self.selection_anchor = None;
+    }
+
+    /// 选中行中等待计算的文件数
+    fn selected_pending(&self) -> usize {
+        self.selected_rows
+            .iter()
+            .filter_map(|&idx| self.files.get(idx))
+            .filter(|f| matches!(f.status, FileStatus::Pending))
+            .count()
+    }
+
+    fn show_row_menu(&mut self, ui: &mut egui::Ui, idx: usize) {
+        if !self.is_computing && self.selected_rows.contains(&idx) {
+            let pending = self.selected_pending();
+            if ui
+                .add_enabled(
+                    pending > 0,
+                    egui::Button::new(format!("{} ({})", t(Text::ComputeSelected), pending)),
+                )
+                .on_hover_text(t(Text::ComputeSelectedHint))
+                .clicked()
+            {
+                self.compute_selected();
+                ui.close();
+            }
+        }
+        if !self.is_computing && self.selected_rows.contains(&idx) {
+            let recomputable = self.selected_recomputable().len();
+            if ui
+                .add_enabled(
+                    recomputable > 0,
+                    egui::Button::new(format!("{} ({})", t(Text::ForceRecompute), recomputable)),
+                )
+                .on_hover_text(t(Text::ForceRecomputeHint))
+                .clicked()
+            {
+                self.force_recompute_selected();
+                ui.close();
+            }
+        }
+        if matches!(self.files[idx].status, FileStatus::Oversized)
+            && ui
+                .add_enabled(!self.is_computing, egui::Button::new(t(Text::HashAnyway)))
+                .on_hover_text(t(Text::HashAnywayHint))
+                .clicked()
+        {
+            self.hash_anyway(idx);
+            ui.close();
+        }
+        if ui
+            .button(t(Text::VerifyClipboard))
+            .on_hover_text(t(Text::VerifyClipboardHint))
+            .clicked()
+        {
+            self.verify_row_against_clipboard(idx);
+            ui.close();
+        }
+        if ui
+            .add_enabled(
+                !matches!(self.files[idx].status, FileStatus::Deleted),
+                egui::Button::new(t(Text::AdvancedRehash)),
+            )
+            .on_hover_text(t(Text::AdvancedRehashHint))
+            .clicked()
+        {
+            self.open_rehash_window(idx);
+            ui.close();
+        }
+        if ui
+            .add_enabled(
+                !matches!(self.files[idx].status, FileStatus::Deleted),
+                egui::Button::new(t(Text::ChunkHashes)),
+            )
+            .on_hover_text(t(Text::ChunkHashesHint))
+            .clicked()
+        {
+            self.start_chunk_hash(self.files[idx].path.clone(), DEFAULT_HASH_CHUNK);
+            ui.close();
+        }
+        let sri = sri_digest(HashAlgorithm::Sha256, &self.files[idx].sha256);
+        if ui
+            .add_enabled(sri.is_some(), egui::Button::new(t(Text::CopySri)))
+            .on_hover_text(t(Text::CopySriHint))
+            .clicked()
+        {
+            if let Some(sri) = sri {
+                ui.ctx().copy_text(sri);
+                self.status_toast = Some((
+                    tf(Text::SriCopied, &[&self.files[idx].filename()]),
+                    std::time::Instant::now(),
+                ));
+            }
+            ui.close();
+        }
+        ui.menu_button(t(Text::CopyVerifyCommand), |ui| {
+            for shell in VerifyShell::ALL {
+                if ui.button(shell.label()).clicked() {
+                    self.copy_verify_command(ui.ctx(), idx, shell);
+                    ui.close();
+                }
+            }
+        })
+        .response
+        .on_hover_text(t(Text::CopyVerifyCommandHint));
+    }
+
+    /// 恢复上次未完成的批处理：由 Worker 读取文件大小后直接加入并开始计算，无需重新扫描
+    fn resume_pending_batch(&mut self, found: Vec<DiscoveredFile>) {
+        let mut added = Vec::new();
+        for found in found {
+            if self.file_index.contains_key(&found.path) {
+                continue;
+            }
+            self.file_index.insert(found.path.clone(), self.files.len());
+            added.push(found.path.clone());
+            self.files.push(FileItem::new(found.path, found.size));
+            self.total_size += found.size;
+        }
+        if !added.is_empty() {
+            self.rows_generation += 1;
+            let _ = self.worker_tx.send(WorkerMessage::LoadNotes(added));
+        }
+        self.start_computing();
+    }
+
+    fn render_resume_prompt(&mut self, ctx: &egui::Context) {
+        let Some(paths) = &self.resume_batch else {
+            return;
+        };
+        let count = paths.len();
+
+        let mut resume = false;
+        let mut discard = false;
+        egui::Window::new(t(Text::UnfinishedBatch))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                ui.label(tf(Text::ResumeBatchPrompt, &[&format_count(count)]));
+                ui.add_space(8.0);
+                ui.horizontal(|ui| {
+                    resume = ui.button(t(Text::ResumeBatch)).clicked();
+                    discard = ui.button(t(Text::Discard)).clicked();
+                });
+            });
+
+        if resume {
+            if let Some(paths) = self.resume_batch.take() {
+                let _ = self.worker_tx.send(WorkerMessage::ResumeBatch(paths));
+            }
+        } else if discard {
+            self.resume_batch = None;
+            let _ = self.worker_tx.send(WorkerMessage::ClearBatchQueue);
+        }
+    }
+
+    fn render_collision_window(&mut self, ctx: &egui::Context) {
+        let mut open = true;
+        egui::Window::new(t(Text::CollisionReport))
+            .open(&mut open)
+            .collapsible(false)
+            .default_width(600.0)
+            .show(ctx, |ui| {
+                let Some(report) = &self.collision_report else {
+                    return;
+                };
+
+                if report.is_empty() {
+                    ui.label(t(Text::NoCollisions));
+                    return;
+                }
+
+                ui.label(
+                    egui::RichText::new(tf(Text::CollisionsFound, &[&report.len()]))
+                        .color(egui::Color32::from_rgb(255, 100, 100))
+                        .strong(),
+                );
+                ScrollArea::vertical().max_height(400.0).show(ui, |ui| {
+                    for collision in report {
+                        ui.separator();
+                        ui.label(
+                            egui::RichText::new(format!(
+                                "{} = {}",
+                                collision.kind.label(),
+                                self.format_hash(&collision.hash)
+                            ))
+                            .monospace()
+                            .strong(),
+                        );
+                        for (path, digest) in &collision.files {
+                            ui.label(tf(
+                                Text::CollisionEntry,
+                                &[
+                                    &dunce::simplified(path).display(),
+                                    &self.format_hash(digest),
+                                ],
+                            ));
+                        }
+                    }
+                });
+            });
+        if !open {
+            self.collision_report = None;
+        }
+    }
+
+    fn render_name_conflict_window(&mut self, ctx: &egui::Context) {
+        let mut open = true;
+        egui::Window::new(t(Text::NameConflictReport))
+            .open(&mut open)
+            .collapsible(false)
+            .default_width(600.0)
+            .show(ctx, |ui| {
+                let Some(report) = &self.name_conflicts else {
+                    return;
+                };
+
+                if report.is_empty() {
+                    ui.label(t(Text::NoNameConflicts));
+                    return;
+                }
+
+                ui.label(
+                    egui::RichText::new(tf(Text::NameConflictsFound, &[&report.len()]))
+                        .color(egui::Color32::from_rgb(230, 150, 30))
+                        .strong(),
+                );
+                ScrollArea::vertical().max_height(400.0).show(ui, |ui| {
+                    for conflict in report {
+                        ui.separator();
+                        ui.label(
+                            egui::RichText::new(tf(
+                                Text::NameConflictEntry,
+                                &[
+                                    &conflict.name,
+                                    &conflict.variants.len(),
+                                    &conflict.file_count(),
+                                ],
+                            ))
+                            .strong(),
+                        );
+                        for (digest, paths) in &conflict.variants {
+                            ui.label(
+                                egui::RichText::new(tf(
+                                    Text::DigestEntry,
+                                    &[&self.format_hash(digest)],
+                                ))
+                                .monospace(),
+                            );
+                            for path in paths {
+                                ui.label(format!("    {}", dunce::simplified(path).display()));
+                            }
+                        }
+                    }
+                });
+            });
+        if !open {
+            self.name_conflicts = None;
+        }
+    }
+
+    fn render_type_summary(&mut self, ctx: &egui::Context) {
+        // 扫描过程中文件不断增加，统计随之刷新
+        let key = (self.files.len(), self.total_size);
+        if self.type_summary.0 != key {
+            self.type_summary = (key, summarize_by_extension(&self.files));
+        }
+
+        egui::SidePanel::right("type_summary")
+            .resizable(true)
+            .default_width(260.0)
+            .show(ctx, |ui| {
+                ui.heading(t(Text::TypeSummary));
+                ui.separator();
+
+                let summary = &self.type_summary.1;
+                if summary.is_empty() {
+                    ui.label(t(Text::NoFiles));
+                    return;
+                }
+
+                ScrollArea::vertical().show(ui, |ui| {
+                    egui::Grid::new("type_summary_grid")
+                        .num_columns(3)
+                        .striped(true)
+                        .spacing([16.0, 4.0])
+                        .show(ui, |ui| {
+                            ui.strong(t(Text::ColumnExtension));
+                            ui.strong(t(Text::Count));
+                            ui.strong(t(Text::TotalSize));
+                            ui.end_row();
+
+                            for group in summary {
+                                ui.label(
+                                    group.extension.as_deref().unwrap_or(t(Text::NoExtension)),
+                                );
+                                ui.label(group.count.to_string());
+                                ui.label(format_size(
+                                    group.total_size,
+                                    self.cache_config.size_unit,
+                                ));
+                                ui.end_row();
+                            }
+                        });
+                });
+            });
+    }
+
+    fn open_compare_dialog(&mut self) {
+        use rfd::FileDialog;
+        let Some(first) = FileDialog::new().set_title("选择第一个文件").pick_file() else {
+            return;
+        };
+        let Some(second) = FileDialog::new().set_title("选择第二个文件").pick_file() else {
+            return;
+        };
+        let paths = [first, second];
+        self.comparison = Some(ComparisonView {
+            paths: paths.clone(),
+            result: None,
+        });
+        let _ = self.worker_tx.send(WorkerMessage::CompareFiles(paths));
+    }
+
+    fn render_comparison_window(&mut self, ctx: &egui::Context) {
+        let mut open = true;
+        egui::Window::new(t(Text::FileComparison))
+            .open(&mut open)
+            .collapsible(false)
+            .default_width(720.0)
+            .show(ctx, |ui| {
+                let Some(view) = &self.comparison else {
+                    return;
+                };
+
+                for (label, path) in ["A", "B"].iter().zip(&view.paths) {
+                    ui.label(format!("{}: {}", label, dunce::simplified(path).display()));
+                }
+                ui.separator();
+
+                let comparison = match &view.result {
+                    None => {
+                        ui.horizontal(|ui| {
+                            ui.spinner();
+                            ui.label(t(Text::Computing));
+                        });
+                        return;
+                    }
+                    Some(Err(e)) => {
+                        ui.label(
+                            egui::RichText::new(tf(Text::CompareFailed, &[&e]))
+                                .color(egui::Color32::RED),
+                        );
+                        return;
+                    }
+                    Some(Ok(comparison)) => comparison,
+                };
+
+                let (verdict, color) = if comparison.identical() {
+                    (t(Text::Identical), egui::Color32::GREEN)
+                } else {
+                    (t(Text::Different), egui::Color32::RED)
+                };
+                ui.label(
+                    egui::RichText::new(verdict)
+                        .size(32.0)
+                        .strong()
+                        .color(color),
+                );
+
+                let Some([a, b]) = &comparison.hashes else {
+                    ui.label(tf(
+                        Text::SizesDiffer,
+                        &[
+                            &format_size(comparison.sizes[0], self.cache_config.size_unit),
+                            &format_size(comparison.sizes[1], self.cache_config.size_unit),
+                        ],
+                    ));
+                    return;
+                };
+
+                egui::Grid::new("comparison_grid")
+                    .num_columns(3)
+                    .spacing([12.0, 6.0])
+                    .show(ui, |ui| {
+                        ui.strong(t(Text::Algorithm));
+                        ui.strong("A");
+                        ui.strong("B");
+                        ui.end_row();
+                        for algorithm in comparison.common_algorithms() {
+                            let (value_a, value_b) =
+                                (a.hash_value(algorithm), b.hash_value(algorithm));
+                            let color = if value_a == value_b {
+                                egui::Color32::GREEN
+                            } else {
+                                egui::Color32::RED
+                            };
+                            ui.label(algorithm.label());
+                            for value in [value_a, value_b] {
+                                ui.label(
+                                    egui::RichText::new(self.format_hash(value))
+                                        .monospace()
+                                        .color(color),
+                                );
+                            }
+                            ui.end_row();
+                        }
+                    });
+            });
+        if !open {
+            self.comparison = None;
+        }
+    }
+
+    fn open_directory_hash_dialog(&mut self) {
+        use rfd::FileDialog;
+        if let Some(root) = FileDialog::new()
+            .set_title(t(Text::PickDirectoryHashFolder))
+            .pick_folder()
+        {
+            self.start_directory_hash(root);
+        }
+    }
+
+    fn start_directory_hash(&mut self, root: PathBuf) {
+        self.directory_hash = Some(DirectoryHashView {
+            root: root.clone(),
+            result: None,
+        });
+        let _ = self.worker_tx.send(WorkerMessage::DirectoryHash(root));
+    }
+
+    /// 拖放加入文件；按住 Shift 拖放文件夹时改为计算该文件夹的合并哈希
+    fn handle_dropped_files(&mut self, ctx: &egui::Context) {
+        let (dropped_files, shift) =
+            ctx.input(|i| (i.raw.dropped_files.clone(), i.modifiers.shift));
+        let mut paths: Vec<PathBuf> = dropped_files.into_iter().filter_map(|f| f.path).collect();
+        if paths.is_empty() {
+            return;
+        }
+
+        if shift {
+            // 目录哈希窗口一次只显示一个结果，其余文件夹按常规扫描
+            if let Some(pos) = paths.iter().position(|p| p.is_dir()) {
+                let root = paths.remove(pos);
+                if paths.iter().any(|p| p.is_dir()) {
+                    self.status_toast = Some((
+                        t(Text::OneDirectoryHash).to_string(),
+                        std::time::Instant::now(),
+                    ));
+                }
+                self.start_directory_hash(root);
+            }
+        }
+
+        if !paths.is_empty() {
+            self.dropped_roots.extend(paths.iter().cloned());
+            self.add_files(paths);
+        }
+    }
+
+    /// 拖动经过窗口时提示松开后的行为
+    fn render_drop_hint(&self, ctx: &egui::Context) {
+        let (hovering, shift) = ctx.input(|i| (!i.raw.hovered_files.is_empty(), i.modifiers.shift));
+        if !hovering {
+            return;
+        }
+        let text = if shift {
+            t(Text::DropDirectoryHash)
+        } else {
+            t(Text::DropToAdd)
+        };
+        egui::Area::new("drop_hint".into())
+            .order(egui::Order::Foreground)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .interactable(false)
+            .show(ctx, |ui| {
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    ui.label(egui::RichText::new(text).heading());
+                });
+            });
+    }
+
+    fn render_directory_hash_window(&mut self, ctx: &egui::Context) {
+        let mut open = true;
+        egui::Window::new(t(Text::DirectoryHashTitle))
+            .open(&mut open)
+            .collapsible(false)
+            .default_width(640.0)
+            .show(ctx, |ui| {
+                let Some(view) = &self.directory_hash else {
+                    return;
+                };
+
+                ui.label(tf(
+                    Text::DirectoryLabel,
+                    &[&dunce::simplified(&view.root).display()],
+                ));
+                ui.label(
+                    egui::RichText::new(t(Text::DirectoryHashNote))
+                        .small()
+                        .weak(),
+                );
+                ui.separator();
+
+                match &view.result {
+                    None => {
+                        ui.horizontal(|ui| {
+                            ui.spinner();
+                            ui.label(t(Text::Computing));
+                        });
+                    }
+                    Some(Err(e)) => {
+                        ui.label(
+                            egui::RichText::new(tf(Text::HashFailed, &[&e]))
+                                .color(egui::Color32::RED),
+                        );
+                    }
+                    Some(Ok(hashes)) => {
+                        egui::Grid::new("directory_hash_grid")
+                            .num_columns(3)
+                            .spacing([12.0, 8.0])
+                            .show(ui, |ui| {
+                                for algorithm in HashAlgorithm::ALL {
+                                    let value = hashes.hash_value(algorithm);
+                                    if value.is_empty() {
+                                        continue;
+                                    }
+                                    let display_value = self.format_hash(value);
+                                    ui.strong(algorithm.label());
+                                    ui.label(
+                                        egui::RichText::new(&display_value).monospace().size(16.0),
+                                    );
+                                    if ui.button(t(Text::Copy)).clicked() {
+                                        ctx.copy_text(display_value);
+                                    }
+                                    ui.end_row();
+                                }
+                            });
+                    }
+                }
+            });
+        if !open {
+            self.directory_hash = None;
+        }
+    }
+
+    fn open_cache_audit_dialog(&mut self) {
+        use rfd::FileDialog;
+        if let Some(root) = FileDialog::new()
+            .set_title(t(Text::PickAuditFolder))
+            .pick_folder()
+        {
+            self.audit = Some(AuditView {
+                root: root.clone(),
+                result: None,
+            });
+            let _ = self.worker_tx.send(WorkerMessage::AuditCache(root));
+        }
+    }
+
+    fn render_audit_window(&mut self, ctx: &egui::Context) {
+        let mut open = true;
+        egui::Window::new(t(Text::CacheAudit))
+            .open(&mut open)
+            .collapsible(false)
+            .default_width(720.0)
+            .show(ctx, |ui| {
+                let Some(view) = &self.audit else {
+                    return;
+                };
+
+                ui.label(tf(
+                    Text::DirectoryLabel,
+                    &[&dunce::simplified(&view.root).display()],
+                ));
+                ui.label(egui::RichText::new(t(Text::CacheAuditNote)).small().weak());
+                ui.separator();
+
+                match &view.result {
+                    None => {
+                        ui.horizontal(|ui| {
+                            ui.spinner();
+                            ui.label(t(Text::Auditing));
+                        });
+                    }
+                    Some(Err(e)) => {
+                        ui.label(
+                            egui::RichText::new(tf(Text::AuditFailed, &[&e]))
+                                .color(egui::Color32::RED),
+                        );
+                    }
+                    Some(Ok(report)) => {
+                        let suspicious = report.suspicious().count();
+                        ui.label(tf(
+                            Text::AuditSummary,
+                            &[
+                                &report.checked,
+                                &report.missing,
+                                &suspicious,
+                                &(report.findings.len() - suspicious),
+                            ],
+                        ));
+                        if report.findings.is_empty() {
+                            ui.label(
+                                egui::RichText::new(t(Text::AuditClean))
+                                    .color(egui::Color32::GREEN),
+                            );
+                            return;
+                        }
+
+                        // 可能损坏的文件排在最前
+                        let mut findings: Vec<_> = report.findings.iter().collect();
+                        findings.sort_by_key(|(_, finding)| match finding {
+                            AuditFinding::Corrupted { .. } => 0,
+                            AuditFinding::Unreadable(_) => 1,
+                            AuditFinding::Changed => 2,
+                        });
+
+                        ui.add_space(4.0);
+                        egui::ScrollArea::vertical()
+                            .max_height(360.0)
+                            .show(ui, |ui| {
+                                egui::Grid::new("audit_grid")
+                                    .num_columns(2)
+                                    .striped(true)
+                                    .spacing([12.0, 4.0])
+                                    .show(ui, |ui| {
+                                        for (path, finding) in findings {
+                                            let (label, color, detail) = match finding {
+                                                AuditFinding::Corrupted { cached, actual } => (
+                                                    t(Text::PossiblyCorrupted),
+                                                    egui::Color32::RED,
+                                                    tf(
+                                                        Text::AuditXxh3,
+                                                        &[
+                                                            &self.format_hash(cached),
+                                                            &self.format_hash(actual),
+                                                        ],
+                                                    ),
+                                                ),
+                                                AuditFinding::Unreadable(e) => (
+                                                    t(Text::Unreadable),
+                                                    egui::Color32::from_rgb(230, 160, 40),
+                                                    e.clone(),
+                                                ),
+                                                AuditFinding::Changed => (
+                                                    t(Text::Modified),
+                                                    egui::Color32::GRAY,
+                                                    t(Text::ModifiedHint).to_string(),
+                                                ),
+                                            };
+                                            ui.label(egui::RichText::new(label).color(color));
+                                            ui.label(dunce::simplified(path).display().to_string())
+                                                .on_hover_text(detail);
+                                            ui.end_row();
+                                        }
+                                    });
+                            });
+                    }
+                }
+            });
+        if !open {
+            self.audit = None;
+        }
+    }
+
+    fn render_hash_changes_window(&mut self, ctx: &egui::Context) {
+        let mut open = true;
+        let mut clear = false;
+        egui::Window::new(t(Text::HashChangesTitle))
+            .open(&mut open)
+            .collapsible(false)
+            .default_width(720.0)
+            .show(ctx, |ui| {
+                ui.label(egui::RichText::new(t(Text::HashChangesNote)).small().weak());
+                ui.separator();
+
+                egui::ScrollArea::vertical()
+                    .max_height(420.0)
+                    .show(ui, |ui| {
+                        for (i, change) in self.hash_changes.iter().enumerate() {
+                            let (label, color) = if change.metadata_changed {
+                                (t(Text::HashChangeModified), egui::Color32::GRAY)
+                            } else {
+                                (t(Text::HashChangeCorrupted), egui::Color32::RED)
+                            };
+                            ui.horizontal(|ui| {
+                                ui.label(egui::RichText::new(label).color(color));
+                                ui.label(
+                                    egui::RichText::new(
+                                        dunce::simplified(&change.path).display().to_string(),
+                                    )
+                                    .strong(),
+                                );
+                            });
+                            egui::Grid::new(("hash_change_grid", i))
+                                .num_columns(3)
+                                .striped(true)
+                                .spacing([12.0, 4.0])
+                                .show(ui, |ui| {
+                                    ui.label("");
+                                    ui.label(egui::RichText::new(t(Text::CachedValue)).strong());
+                                    ui.label(
+                                        egui::RichText::new(t(Text::RecomputedValue)).strong(),
+                                    );
+                                    ui.end_row();
+                                    for algorithm in HashAlgorithm::ALL {
+                                        let old = change.old.hash_value(algorithm);
+                                        let new = change.new.hash_value(algorithm);
+                                        if old.is_empty() || new.is_empty() {
+                                            continue;
+                                        }
+                                        let new_text =
+                                            egui::RichText::new(self.format_hash(new)).monospace();
+                                        ui.label(algorithm.label());
+                                        ui.label(
+                                            egui::RichText::new(self.format_hash(old)).monospace(),
+                                        );
+                                        if old.eq_ignore_ascii_case(new) {
+                                            ui.label(new_text.weak());
+                                        } else {
+                                            ui.label(new_text.color(egui::Color32::RED).strong());
+                                        }
+                                        ui.end_row();
+                                    }
+                                });
+                            ui.add_space(8.0);
+                        }
+                    });
+
+                ui.separator();
+                if ui.button(t(Text::Dismiss)).clicked() {
+                    clear = true;
+                }
+            });
+        if clear {
+            self.hash_changes.clear();
+            self.rows_generation += 1;
+        }
+        if !open || clear {
+            self.show_hash_changes = false;
+        }
+    }
+
+    /// 打开「高级重算」窗口，参数默认取当前设置
+    fn open_rehash_window(&mut self, idx: usize) {
+        self.rehash = Some(RehashView {
+            params: RehashParams {
+                path: self.files[idx].path.clone(),
+                buffer_size: self.cache_config.buffer_size,
+                mmap_chunk_size: self.cache_config.mmap_chunk_size,
+                force_buffered: false,
+            },
+            running: false,
+            result: None,
+        });
+    }
+
+    fn render_rehash_window(&mut self, ctx: &egui::Context) {
+        const BUFFER_SIZES: [usize; 5] = [
+            64 * 1024,
+            256 * 1024,
+            1024 * 1024,
+            2 * 1024 * 1024,
+            4 * 1024 * 1024,
+        ];
+        const CHUNK_SIZES: [usize; 4] = [
+            1024 * 1024,
+            4 * 1024 * 1024,
+            16 * 1024 * 1024,
+            64 * 1024 * 1024,
+        ];
+
+        let mut open = true;
+        let mut start = false;
+        let unit = self.cache_config.size_unit;
+        let (encoding, uppercase) = (self.cache_config.hash_encoding, self.uppercase_display);
+
+        egui::Window::new(t(Text::AdvancedRehashTitle))
+            .open(&mut open)
+            .collapsible(false)
+            .default_width(640.0)
+            .show(ctx, |ui| {
+                let Some(view) = &mut self.rehash else {
+                    return;
+                };
+
+                ui.label(tf(
+                    Text::FileLabel,
+                    &[&dunce::simplified(&view.params.path).display()],
+                ));
+                ui.label(
+                    egui::RichText::new(t(Text::AdvancedRehashNote))
+                        .small()
+                        .weak(),
+                );
+                ui.separator();
+
+                ui.add_enabled_ui(!view.running, |ui| {
+                    egui::Grid::new("rehash_params")
+                        .num_columns(2)
+                        .spacing([12.0, 6.0])
+                        .show(ui, |ui| {
+                            ui.label(t(Text::BufferSizeLabel));
+                            egui::ComboBox::from_id_salt("rehash_buffer_size")
+                                .selected_text(format_size(view.params.buffer_size, unit))
+                                .show_ui(ui, |ui| {
+                                    for size in BUFFER_SIZES {
+                                        ui.selectable_value(
+                                            &mut view.params.buffer_size,
+                                            size,
+                                            format_size(size, unit),
+                                        );
+                                    }
+                                });
+                            ui.end_row();
+
+                            ui.label(t(Text::MmapChunkLabel));
+                            egui::ComboBox::from_id_salt("rehash_chunk_size")
+                                .selected_text(format_size(view.params.mmap_chunk_size, unit))
+                                .show_ui(ui, |ui| {
+                                    for size in CHUNK_SIZES {
+                                        ui.selectable_value(
+                                            &mut view.params.mmap_chunk_size,
+                                            size,
+                                            format_size(size, unit),
+                                        );
+                                    }
+                                });
+                            ui.end_row();
+
+                            ui.label(t(Text::ReadMethod));
+                            ui.checkbox(&mut view.params.force_buffered, t(Text::ForceBuffered));
+                            ui.end_row();
+                        });
+                    ui.label(
+                        egui::RichText::new(t(Text::RehashExactSizes))
+                            .small()
+                            .weak(),
+                    );
+                    ui.add_space(4.0);
+                    start = ui.button(t(Text::StartRehash)).clicked();
+                });
+
+                if view.running {
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        ui.label(t(Text::Computing));
+                    });
+                    return;
+                }
+
+                match &view.result {
+                    None => {}
+                    Some(Err(e)) => {
+                        ui.separator();
+                        ui.label(
+                            egui::RichText::new(tf(Text::RehashFailed, &[&e]))
+                                .color(egui::Color32::RED),
+                        );
+                    }
+                    Some(Ok(report)) => {
+                        ui.separator();
+                        let params = &report.params;
+                        ui.label(tf(
+                            Text::RehashParamsUsed,
+                            &[
+                                &format_size(params.buffer_size, unit),
+                                &format_size(params.mmap_chunk_size, unit),
+                                &if params.force_buffered {
+                                    t(Text::ForceBufferedSuffix)
+                                } else {
+                                    ""
+                                },
+                            ],
+                        ));
+                        ui.label(tf(Text::RehashReadPath, &[&report.strategy.label()]));
+                        let seconds = report.duration_ms.max(1) as f64 / 1000.0;
+                        ui.label(tf(
+                            Text::RehashTiming,
+                            &[
+                                &format_duration(report.duration_ms),
+                                &format_size(report.file_size, unit),
+                                &format!("{:.0}", report.file_size as f64 / 1_000_000.0 / seconds),
+                            ],
+                        ));
+                        egui::Grid::new("rehash_result")
+                            .num_columns(2)
+                            .spacing([12.0, 4.0])
+                            .show(ui, |ui| {
+                                for algorithm in HashAlgorithm::ALL {
+                                    let value = report.hashes.hash_value(algorithm);
+                                    if value.is_empty() {
+                                        continue;
+                                    }
+                                    ui.strong(algorithm.label());
+                                    ui.label(
+                                        egui::RichText::new(format_digest(
+                                            value, encoding, uppercase,
+                                        ))
+                                        .monospace(),
+                                    );
+                                    ui.end_row();
+                                }
+                            });
+                    }
+                }
+            });
+
+        if start {
+            if let Some(view) = &mut self.rehash {
+                view.running = true;
+                let _ = self
+                    .worker_tx
+                    .send(WorkerMessage::Rehash(view.params.clone()));
+            }
+        }
+        if !open {
+            self.rehash = None;
+        }
+    }
+
+    /// 开始计算分块哈希，同时取消仍在进行的上一次请求
+    fn start_chunk_hash(&mut self, path: PathBuf, chunk_size: u64) {
+        self.cancel_chunk_hash();
+        let cancel = Arc::new(AtomicBool::new(false));
+        self.chunk_hash = Some(ChunkHashView {
+            path: path.clone(),
+            chunk_size,
+            cancel: cancel.clone(),
+            progress: None,
+            result: None,
+        });
+        let _ = self.worker_tx.send(WorkerMessage::ChunkHash {
+            path,
+            chunk_size,
+            cancel,
+        });
+    }
+
+    fn cancel_chunk_hash(&mut self) {
+        if let Some(view) = self.chunk_hash.take() {
+            view.cancel.store(true, Ordering::Relaxed);
+        }
+    }
+
+    fn render_chunk_hash_window(&mut self, ctx: &egui::Context) {
+        const CHUNK_SIZES: [u64; 4] = [
+            1024 * 1024,
+            DEFAULT_HASH_CHUNK,
+            16 * 1024 * 1024,
+            64 * 1024 * 1024,
+        ];
+
+        let mut open = true;
+        let mut restart: Option<u64> = None;
+        let mut export = false;
+        let unit = self.cache_config.size_unit;
+
+        egui::Window::new(t(Text::ChunkHashTitle))
+            .open(&mut open)
+            .collapsible(false)
+            .default_width(720.0)
+            .default_height(480.0)
+            .show(ctx, |ui| {
+                let Some(view) = &self.chunk_hash else {
+                    return;
+                };
+
+                ui.label(tf(
+                    Text::FileLabel,
+                    &[&dunce::simplified(&view.path).display()],
+                ));
+                ui.horizontal(|ui| {
+                    ui.label(t(Text::ChunkSizeLabel));
+                    egui::ComboBox::from_id_salt("chunk_hash_size")
+                        .selected_text(format_size(view.chunk_size, unit))
+                        .show_ui(ui, |ui| {
+                            for size in CHUNK_SIZES {
+                                let selected = view.chunk_size == size;
+                                if ui
+                                    .selectable_label(selected, format_size(size, unit))
+                                    .clicked()
+                                    && !selected
+                                {
+                                    restart = Some(size);
+                                }
+                            }
+                        });
+                });
+                ui.label(egui::RichText::new(t(Text::ChunkRootNote)).small().weak());
+                ui.separator();
+
+                match &view.result {
+                    None => match view.progress {
+                        Some((processed, total)) if total > 0 => {
+                            // 按千分比换算，避免大文件的字节数直接转为 f32 丢失精度
+                            let permille = u16::try_from(processed * 1000 / total).unwrap_or(1000);
+                            ui.add(
+                                egui::ProgressBar::new(f32::from(permille) / 1000.0).text(tf(
+                                    Text::ChunkHashRead,
+                                    &[&format_size(processed, unit), &format_size(total, unit)],
+                                )),
+                            );
+                        }
+                        _ => {
+                            ui.horizontal(|ui| {
+                                ui.spinner();
+                                ui.label(t(Text::Computing));
+                            });
+                        }
+                    },
+                    Some(Err(e)) => {
+                        ui.label(
+                            egui::RichText::new(tf(Text::HashFailed, &[e]))
+                                .color(egui::Color32::RED),
+                        );
+                    }
+                    Some(Ok(hashes)) => {
+                        let root = self.format_hash(&hashes.root);
+                        ui.horizontal(|ui| {
+                            ui.strong(t(Text::RootHash));
+                            ui.label(egui::RichText::new(&root).monospace());
+                            if ui.button(t(Text::Copy)).clicked() {
+                                ctx.copy_text(root.clone());
+                            }
+                        });
+                        ui.horizontal(|ui| {
+                            ui.label(tf(
+                                Text::ChunkCount,
+                                &[
+                                    &format_count(hashes.chunks.len()),
+                                    &format_size(hashes.file_size, unit),
+                                ],
+                            ));
+                            export = ui.button(t(Text::ExportChunkList)).clicked();
+                        });
+                        ui.separator();
+
+                        let row_height = ui.text_style_height(&egui::TextStyle::Monospace);
+                        ScrollArea::both().auto_shrink([false, false]).show_rows(
+                            ui,
+                            row_height,
+                            hashes.chunks.len(),
+                            |ui, range| {
+                                for index in range {
+                                    let chunk = &hashes.chunks[index];
+                                    ui.label(
+                                        egui::RichText::new(format!(
+                                            "{:>6}  {:>14}  {}  {}",
+                                            index,
+                                            chunk.offset,
+                                            self.format_hash(&chunk.xxhash3),
+                                            self.format_hash(&chunk.sha256)
+                                        ))
+                                        .monospace(),
+                                    );
+                                }
+                            },
+                        );
+                    }
+                }
+            });
+
+        if let (Some(chunk_size), Some(view)) = (restart, &self.chunk_hash) {
+            let path = view.path.clone();
+            self.start_chunk_hash(path, chunk_size);
+        }
+        if export {
+            self.export_chunk_list();
+        }
+        if !open {
+            self.cancel_chunk_hash();
+        }
+    }
+
+    /// 把分块哈希列表保存为文本文件
+    fn export_chunk_list(&mut self) {
+        use rfd::FileDialog;
+        let Some(view) = &self.chunk_hash else {
+            return;
+        };
+        let Some(Ok(hashes)) = &view.result else {
+            return;
+        };
+        let file_name = view
+            .path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let Some(target) = FileDialog::new()
+            .set_title(t(Text::ExportChunkListTitle))
+            .set_file_name(format!("{}.chunks.txt", file_name))
+            .add_filter(t(Text::TxtFiles), &["txt"])
+            .save_file()
+        else {
+            return;
+        };
+
+        let case = if self.uppercase_display {
+            HexCase::Upper
+        } else {
+            HexCase::Lower
+        };
+        let content = format_chunk_list(&file_name, hashes, case);
+        let message = match std::fs::write(&target, content) {
+            Ok(()) => tf(
+                Text::ChunkListExported,
+                &[&format_count(hashes.chunks.len())],
+            ),
+            Err(e) => tf(Text::ExportFailed, &[&e]),
+        };
+        self.status_toast = Some((message, std::time::Instant::now()));
+    }
+
+    fn render_benchmark_window(&mut self, ctx: &egui::Context) {
+        let mut open = true;
+        // 窗口内的操作，在闭包外执行
+        let mut start: Option<Option<PathBuf>> = None;
+        let mut apply: Option<&'static PerformancePreset> = None;
+        let mut restart = false;
+
+        egui::Window::new(t(Text::BenchmarkTitle))
+            .open(&mut open)
+            .collapsible(false)
+            .default_width(480.0)
+            .show(ctx, |ui| {
+                let Some(view) = &self.benchmark else {
+                    return;
+                };
+
+                ui.label(egui::RichText::new(t(Text::BenchmarkNote)).small().weak());
+                ui.separator();
+
+                if view.running {
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        ui.label(t(Text::BenchmarkRunning));
+                    });
+                    return;
+                }
+
+                match &view.result {
+                    None => {
+                        ui.horizontal(|ui| {
+                            if ui
+                                .button(tf(
+                                    Text::BenchmarkGenerated,
+                                    &[&format_size(GENERATED_SIZE, self.cache_config.size_unit)],
+                                ))
+                                .on_hover_text(t(Text::BenchmarkGeneratedHint))
+                                .clicked()
+                            {
+                                start = Some(None);
+                            }
+                            if ui.button(t(Text::ChooseFile)).clicked() {
+                                if let Some(path) = rfd::FileDialog::new()
+                                    .set_title(t(Text::PickBenchmarkFile))
+                                    .pick_file()
+                                {
+                                    start = Some(Some(path));
+                                }
+                            }
+                        });
+                    }
+                    Some(Err(e)) => {
+                        ui.label(
+                            egui::RichText::new(tf(Text::BenchmarkFailed, &[&e]))
+                                .color(egui::Color32::RED),
+                        );
+                        restart = ui.button(t(Text::RunAgain)).clicked();
+                    }
+                    Some(Ok(report)) => {
+                        let source = match &report.source {
+                            Some(path) => dunce::simplified(path).display().to_string(),
+                            None => String::from(t(Text::GeneratedData)),
+                        };
+                        ui.label(tf(
+                            Text::BenchmarkFile,
+                            &[
+                                &source,
+                                &format_size(report.file_size, self.cache_config.size_unit),
+                            ],
+                        ));
+                        ui.add_space(4.0);
+
+                        let fastest = report.fastest_preset();
+                        egui::Grid::new("benchmark_grid")
+                            .num_columns(2)
+                            .striped(true)
+                            .spacing([24.0, 4.0])
+                            .show(ui, |ui| {
+                                ui.label(
+                                    egui::RichText::new(t(Text::BenchmarkAlgorithms)).strong(),
+                                );
+                                ui.end_row();
+                                for (algorithm, speed) in &report.algorithms {
+                                    ui.label(algorithm.label());
+                                    ui.label(tf(Text::Throughput, &[&format!("{speed:.0}")]));
+                                    ui.end_row();
+                                }
+
+                                ui.label(egui::RichText::new(t(Text::BenchmarkReadPaths)).strong());
+                                ui.end_row();
+                                for (strategy, speed) in &report.strategies {
+                                    ui.label(strategy.label());
+                                    ui.label(tf(Text::Throughput, &[&format!("{speed:.0}")]));
+                                    ui.end_row();
+                                }
+
+                                ui.label(egui::RichText::new(t(Text::BenchmarkPresets)).strong());
+                                ui.end_row();
+                                for (preset, speed) in &report.presets {
+                                    ui.label(format!("{} {}", preset.icon, preset.mode.label()))
+                                        .on_hover_text(tf(
+                                            Text::PresetSizes,
+                                            &[
+                                                &format_size(
+                                                    preset.buffer_size,
+                                                    self.cache_config.size_unit,
+                                                ),
+                                                &format_size(
+                                                    preset.mmap_chunk_size,
+                                                    self.cache_config.size_unit,
+                                                ),
+                                            ],
+                                        ));
+                                    let speed = format!("{speed:.0}");
+                                    if fastest.is_some_and(|f| std::ptr::eq(f, *preset)) {
+                                        ui.label(
+                                            egui::RichText::new(tf(
+                                                Text::FastestThroughput,
+                                                &[&speed],
+                                            ))
+                                            .color(egui::Color32::GREEN),
+                                        );
+                                    } else {
+                                        ui.label(tf(Text::Throughput, &[&speed]));
+                                    }
+                                    ui.end_row();
+                                }
+                            });
+
+                        ui.add_space(8.0);
+                        ui.horizontal(|ui| {
+                            if let Some(preset) = fastest {
+                                let current = preset.matches(&self.cache_config);
+                                if ui
+                                    .add_enabled(
+                                        !current,
+                                        egui::Button::new(tf(
+                                            Text::ApplyPreset,
+                                            &[&preset.mode.label()],
+                                        )),
+                                    )
+                                    .on_hover_text(t(Text::ApplyPresetHint))
+                                    .clicked()
+                                {
+                                    apply = Some(preset);
+                                }
+                            }
+                            restart = ui.button(t(Text::RunAgain)).clicked();
+                        });
+                    }
+                }
+            });
+
+        if let Some(source) = start {
+            if let Some(view) = &mut self.benchmark {
+                view.running = true;
+            }
+            let _ = self.worker_tx.send(WorkerMessage::Benchmark(source));
+        }
+        if let Some(preset) = apply {
+            self.cache_config.set_performance_mode(preset.mode);
+            self.persist_config();
+        }
+        if restart {
+            self.benchmark = Some(BenchmarkView::default());
+        }
+        if !open {
+            self.benchmark = None;
+        }
+    }
+
+    fn open_rename_dialog(&mut self) {
+        let candidates = self
+            .files
+            .iter()
+            .filter(|f| matches!(f.status, FileStatus::Completed) && !f.sha256.is_empty())
+            .map(|f| (f.path.clone(), f.sha256.clone(), true))
+            .collect();
+        self.rename = Some(RenameView {
+            candidates,
+            planning: false,
+            plan: None,
+            confirmed: false,
+            applying: false,
+        });
+    }
+
+    fn render_rename_window(&mut self, ctx: &egui::Context) {
+        let mut open = true;
+        // 窗口内的操作，在闭包外执行
+        let mut request_plan = false;
+        let mut apply = false;
+        let mut back = false;
+        let is_computing = self.is_computing;
+
+        egui::Window::new(t(Text::RenameByHash))
+            .open(&mut open)
+            .collapsible(false)
+            .default_width(640.0)
+            .show(ctx, |ui| {
+                let Some(view) = &mut self.rename else {
+                    return;
+                };
+
+                ui.label(egui::RichText::new(t(Text::RenameNote)).small().weak());
+                ui.separator();
+
+                if view.candidates.is_empty() {
+                    ui.label(t(Text::NothingToRename));
+                    return;
+                }
+
+                if view.planning || view.applying {
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        ui.label(if view.applying {
+                            t(Text::Renaming)
+                        } else {
+                            t(Text::PlanningRename)
+                        });
+                    });
+                    return;
+                }
+
+                let Some(plan) = &view.plan else {
+                    ui.horizontal(|ui| {
+                        if ui.button(t(Text::SelectAll)).clicked() {
+                            view.candidates.iter_mut().for_each(|c| c.2 = true);
+                        }
+                        if ui.button(t(Text::SelectNone)).clicked() {
+                            view.candidates.iter_mut().for_each(|c| c.2 = false);
+                        }
+                    });
+                    egui::ScrollArea::vertical()
+                        .max_height(320.0)
+                        .show(ui, |ui| {
+                            for (path, _, selected) in &mut view.candidates {
+                                ui.checkbox(
+                                    selected,
+                                    dunce::simplified(path).display().to_string(),
+                                );
+                            }
+                        });
+                    ui.add_space(4.0);
+                    let selected = view.candidates.iter().filter(|c| c.2).count();
+                    request_plan = ui
+                        .add_enabled(
+                            selected > 0,
+                            egui::Button::new(tf(Text::PreviewRename, &[&selected])),
+                        )
+                        .clicked();
+                    return;
+                };
+
+                egui::ScrollArea::vertical()
+                    .max_height(320.0)
+                    .show(ui, |ui| {
+                        egui::Grid::new("rename_plan_grid")
+                            .num_columns(3)
+                            .striped(true)
+                            .show(ui, |ui| {
+                                for entry in plan {
+                                    ui.label(
+                                        entry
+                                            .source
+                                            .file_name()
+                                            .unwrap_or_default()
+                                            .to_string_lossy(),
+                                    )
+                                    .on_hover_text(
+                                        dunce::simplified(&entry.source).display().to_string(),
+                                    );
+                                    ui.label(
+                                        entry
+                                            .target
+                                            .file_name()
+                                            .unwrap_or_default()
+                                            .to_string_lossy(),
+                                    );
+                                    let color = match entry.action {
+                                        RenameAction::Rename => ui.visuals().text_color(),
+                                        RenameAction::AlreadyNamed | RenameAction::SkipSameHash => {
+                                            ui.visuals().weak_text_color()
+                                        }
+                                        RenameAction::Conflict { .. }
+                                        | RenameAction::TargetUnreadable(_) => {
+                                            egui::Color32::from_rgb(255, 140, 0)
+                                        }
+                                    };
+                                    ui.label(
+                                        egui::RichText::new(entry.action.label()).color(color),
+                                    );
+                                    ui.end_row();
+                                }
+                            });
+                    });
+
+                let renames = plan.iter().filter(|e| e.action.is_rename()).count();
+                let conflicts = plan
+                    .iter()
+                    .filter(|e| {
+                        matches!(
+                            e.action,
+                            RenameAction::Conflict { .. } | RenameAction::TargetUnreadable(_)
+                        )
+                    })
+                    .count();
+                ui.separator();
+                ui.label(tf(
+                    Text::RenamePlanSummary,
+                    &[&renames, &(plan.len() - renames - conflicts), &conflicts],
+                ));
+                if conflicts > 0 {
+                    ui.label(
+                        egui::RichText::new(t(Text::RenameConflictNote))
+                            .color(egui::Color32::from_rgb(255, 140, 0)),
+                    );
+                }
+                if is_computing {
+                    ui.label(
+                        egui::RichText::new(t(Text::RenameWaitForBatch)).color(egui::Color32::RED),
+                    );
+                }
+
+                ui.add_enabled(
+                    renames > 0,
+                    egui::Checkbox::new(&mut view.confirmed, tf(Text::ConfirmRename, &[&renames])),
+                );
+                ui.horizontal(|ui| {
+                    apply = ui
+                        .add_enabled(
+                            view.confirmed && renames > 0 && !is_computing,
+                            egui::Button::new(t(Text::ApplyRename)),
+                        )
+                        .clicked();
+                    back = ui.button(t(Text::BackToSelection)).clicked();
+                });
+            });
+
+        if request_plan {
+            if let Some(view) = &mut self.rename {
+                let files: Vec<(PathBuf, String)> = view
+                    .candidates
+                    .iter()
+                    .filter(|c| c.2)
+                    .map(|(path, sha256, _)| (path.clone(), sha256.clone()))
+                    .collect();
+                view.planning = true;
+                let _ = self.worker_tx.send(WorkerMessage::PlanRename(files));
+            }
+        }
+        // 计算期间绝不改动文件，即使按钮状态尚未刷新
+        if apply && !self.is_computing {
+            if let Some(view) = &mut self.rename {
+                if let Some(plan) = view.plan.take() {
+                    view.applying = true;
+                    let _ = self.worker_tx.send(WorkerMessage::ApplyRename(plan));
+                }
+            }
+        }
+        if back {
+            if let Some(view) = &mut self.rename {
+                view.plan = None;
+                view.confirmed = false;
+            }
+        }
+        // 执行期间关闭窗口不影响结果，完成后仍会更新列表
+        if !open {
+            self.rename = None;
+        }
+    }
+
+    /// 工具栏中的哈希查找框：粘贴哈希值后高亮并跳转到匹配的行
+    fn render_hash_search_box(&mut self, ui: &mut egui::Ui) {
+        let response = ui
+            .add(
+                egui::TextEdit::singleline(&mut self.hash_search.query)
+                    .hint_text(t(Text::HashSearchHint))
+                    .desired_width(200.0),
+            )
+            .on_hover_text(t(Text::HashSearchTooltip));
+        let submitted = response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+        if response.changed() {
+            self.refresh_hash_search();
+            self.hash_search.list_open = true;
+            self.jump_to_first_match();
+        } else if self.hash_search.dirty {
+            self.refresh_hash_search();
+        }
+        if submitted {
+            self.hash_search.list_open = true;
+            self.jump_to_first_match();
+        }
+
+        let (text, color) = match &self.hash_search.result {
+            None => return,
+            Some(HashSearch::Invalid) => (t(Text::InvalidHash).to_string(), egui::Color32::RED),
+            Some(HashSearch::UnknownLength(len)) => {
+                (tf(Text::UnknownHashLength, &[&len]), egui::Color32::RED)
+            }
+            Some(HashSearch::Found { matches, .. }) if matches.is_empty() => {
+                (t(Text::NotFound).to_string(), egui::Color32::GRAY)
+            }
+            Some(HashSearch::Found {
+                algorithms,
+                matches,
+            }) => {
+                let labels: Vec<&str> = algorithms.iter().map(|a| a.label()).collect();
+                (
+                    tf(Text::FoundMatches, &[&matches.len(), &labels.join("/")]),
+                    egui::Color32::GREEN,
+                )
+            }
+        };
+        let response = ui.label(egui::RichText::new(text).color(color));
+        if self.hash_search_matches().len() > 1
+            && response
+                .on_hover_text(t(Text::ListMatchesHint))
+                .interact(egui::Sense::click())
+                .clicked()
+        {
+            self.hash_search.list_open = true;
+        }
+    }
+
+    fn refresh_hash_search(&mut self) {
+        self.hash_search.dirty = false;
+        self.hash_search.result = (!self.hash_search.query.trim().is_empty())
+            .then(|| search_by_hash(&self.hash_search.query, &self.files));
+    }
+
+    fn hash_search_matches(&self) -> &[(usize, HashAlgorithm)] {
+        self.hash_search
+            .result
+            .as_ref()
+            .map_or(&[], HashSearch::matches)
+    }
+
+    fn jump_to_first_match(&mut self) {
+        self.hash_search.scroll_to = self.hash_search_matches().first().map(|&(idx, _)| idx);
+    }
+
+    /// 匹配多个文件时列出全部，点击定位到对应的行
+    fn render_hash_search_window(&mut self, ctx: &egui::Context) {
+        let mut open = true;
+        let mut jump = None;
+
+        egui::Window::new(t(Text::HashSearchResults))
+            .open(&mut open)
+            .collapsible(false)
+            .default_width(560.0)
+            .show(ctx, |ui| {
+                let matches = self.hash_search_matches();
+                ui.label(tf(Text::SameHashFiles, &[&matches.len()]));
+                ui.separator();
+                ScrollArea::vertical().max_height(320.0).show(ui, |ui| {
+                    for &(idx, algorithm) in matches {
+                        let Some(file) = self.files.get(idx) else {
+                            continue;
+                        };
+                        ui.horizontal(|ui| {
+                            if ui.button(t(Text::Locate)).clicked() {
+                                jump = Some(idx);
+                            }
+                            ui.label(egui::RichText::new(algorithm.label()).weak());
+                            ui.label(file.filename())
+                                .on_hover_text(file.path.display().to_string());
+                        });
+                    }
+                });
+            });
+
+        if jump.is_some() {
+            self.hash_search.scroll_to = jump;
+        }
+        if !open {
+            self.hash_search.list_open = false;
+        }
+    }
+
+    /// 有问题的行（文件下标），包括哈希与缓存不同、校验清单未列出与命中哈希清单的文件；
+    /// 文件列表未变化时复用上次的结果
+    fn problem_rows(&mut self) -> &[usize] {
+        if self.problem_rows.0 != self.rows_generation {
+            let list = self.hash_list.list.as_ref();
+            let changed: HashSet<&Path> = self
+                .hash_changes
+                .iter()
+                .map(|change| change.path.as_path())
+                .collect();
+            let untracked = self.manifest.as_ref().map(|manifest| &manifest.untracked);
+            let rows = self
+                .files
+                .iter()
+                .enumerate()
+                .filter(|(_, file)| {
+                    file.has_problem()
+                        || changed.contains(file.path.as_path())
+                        || untracked.is_some_and(|untracked| untracked.contains(&file.path))
+                        || list.is_some_and(|list| list.find(file).is_some())
+                })
+                .map(|(idx, _)| idx)
+                .collect();
+            self.problem_rows = (self.rows_generation, rows);
+        }
+        &self.problem_rows.1
+    }
+
+    /// 筛选后表格显示的行（文件下标），未筛选时为 None
+    fn filtered_rows(&mut self) -> Option<Vec<usize>> {
+        if !self.only_problems && self.source_filter == SourceFilter::All {
+            return None;
+        }
+        let rows = if self.only_problems {
+            self.problem_rows().to_vec()
+        } else {
+            (0..self.files.len()).collect()
+        };
+        let filter = self.source_filter;
+        Some(
+            rows.into_iter()
+                .filter(|&idx| filter.matches(&self.files[idx]))
+                .collect(),
+        )
+    }
+
+    /// 按当前清单重新统计命中的文件数
+    fn refresh_hash_list_matches(&mut self) {
+        self.hash_list.dirty = false;
+        self.hash_list.matched = self.hash_list.list.as_ref().map_or(0, |list| {
+            self.files
+                .iter()
+                .filter(|file| list.find(file).is_some())
+                .count()
+        });
+    }
+
+    fn render_hash_list_window(&mut self, ctx: &egui::Context) {
+        let mut open = true;
+        let mut apply = false;
+        let mut clear = false;
+
+        egui::Window::new(t(Text::HashList))
+            .open(&mut open)
+            .collapsible(false)
+            .default_width(560.0)
+            .show(ctx, |ui| {
+                ui.label(egui::RichText::new(t(Text::HashListNote)).small().weak());
+                ui.separator();
+                ScrollArea::vertical().max_height(260.0).show(ui, |ui| {
+                    ui.add(
+                        egui::TextEdit::multiline(&mut self.hash_list.text)
+                            .hint_text(t(Text::HashListPlaceholder))
+                            .desired_rows(10)
+                            .desired_width(f32::INFINITY)
+                            .font(egui::TextStyle::Monospace),
+                    );
+                });
+                ui.horizontal(|ui| {
+                    apply = ui
+                        .add_enabled(
+                            !self.hash_list.text.trim().is_empty(),
+                            egui::Button::new(t(Text::Screen)),
+                        )
+                        .clicked();
+                    clear = ui
+                        .add_enabled(
+                            self.hash_list.list.is_some(),
+                            egui::Button::new(t(Text::ClearList)),
+                        )
+                        .clicked();
+                });
+
+                if let Some(list) = &self.hash_list.list {
+                    ui.separator();
+                    let mut summary = tf(Text::HashListSize, &[&list.len()]);
+                    if list.skipped > 0 {
+                        summary.push_str(&tf(Text::HashListSkipped, &[&list.skipped]));
+                    }
+                    ui.label(summary);
+                    let (text, color) = if self.hash_list.matched > 0 {
+                        (
+                            tf(Text::HashListHits, &[&self.hash_list.matched]),
+                            egui::Color32::RED,
+                        )
+                    } else {
+                        (t(Text::HashListNoHits).to_string(), egui::Color32::GREEN)
+                    };
+                    ui.label(egui::RichText::new(text).color(color).strong());
+                }
+            });
+
+        if apply {
+            let list = HashList::parse(&self.hash_list.text);
+            self.hash_list.list = Some(list);
+            self.rows_generation += 1;
+            self.refresh_hash_list_matches();
+            self.status_toast = Some((
+                tf(Text::HashListToast, &[&self.hash_list.matched]),
+                std::time::Instant::now(),
+            ));
+        }
+        if clear {
+            self.hash_list.list = None;
+            self.hash_list.matched = 0;
+            self.rows_generation += 1;
+        }
+        if !open {
+            self.hash_list.open = false;
+        }
+    }
+
+    fn render_remote_verify_window(&mut self, ctx: &egui::Context) {
+        let mut open = true;
+        let mut fetch = false;
+
+        egui::Window::new(t(Text::RemoteVerify))
+            .open(&mut open)
+            .collapsible(false)
+            .default_width(520.0)
+            .show(ctx, |ui| {
+                let Some(view) = &mut self.remote_verify else {
+                    return;
+                };
+
+                ui.label(
+                    egui::RichText::new(t(Text::RemoteVerifyNote))
+                        .small()
+                        .weak(),
+                );
+                ui.separator();
+
+                ui.add_enabled_ui(!view.fetching, |ui| {
+                    ui.horizontal(|ui| {
+                        let response = ui.add(
+                            egui::TextEdit::singleline(&mut view.url)
+                                .hint_text("https://example.com/SHA256SUMS")
+                                .desired_width(380.0),
+                        );
+                        let submitted =
+                            response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                        let clicked = ui
+                            .add_enabled(
+                                !view.url.trim().is_empty(),
+                                egui::Button::new(t(Text::Fetch)),
+                            )
+                            .clicked();
+                        fetch = (clicked || submitted) && !view.url.trim().is_empty();
+                    });
+                });
+
+                if view.fetching {
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        ui.label(t(Text::Downloading));
+                    });
+                } else if let Some(e) = &view.error {
+                    ui.label(egui::RichText::new(e).color(egui::Color32::RED));
+                }
+            });
+
+        if fetch {
+            if let Some(view) = &mut self.remote_verify {
+                view.fetching = true;
+                view.error = None;
+                let _ = self
+                    .worker_tx
+                    .send(WorkerMessage::FetchChecksums(view.url.trim().to_string()));
+            }
+        }
+        if !open {
+            self.remote_verify = None;
+        }
+    }
+
+    /// 按文件名把下载的校验清单应用到列表，已完成的文件立即显示比对结果
+    fn apply_remote_checksums(&mut self, list: &HashMap<String, ExpectedHash>) {
+        if list.is_empty() {
+            if let Some(view) = &mut self.remote_verify {
+                view.fetching = false;
+                view.error = Some(t(Text::NoChecksumsDownloaded).to_string());
+            }
+            return;
+        }
+
+        let (applied, matched, mismatched) = self.apply_checksum_list(list, None);
+        self.remote_verify = None;
+        self.status_toast = Some((
+            tf(
+                Text::ChecksumListApplied,
+                &[&list.len(), &applied, &matched, &mismatched],
+            ),
+            std::time::Instant::now(),
+        ));
+    }
+
+    /// 打开本地校验清单
+    fn open_manifest_dialog(&mut self) {
+        use rfd::FileDialog;
+        if let Some(manifest) = FileDialog::new()
+            .set_title(t(Text::PickManifest))
+            .pick_file()
+        {
+            let _ = self.worker_tx.send(WorkerMessage::VerifyManifest(manifest));
+        }
+    }
+
+    /// 应用本地校验清单：已在列表中的文件立即比对，再加入清单所在目录，
+    /// 扫描出的文件在加入时取得期望哈希
+    fn start_manifest_verify(
+        &mut self,
+        list: HashMap<String, ExpectedHash>,
+        report: ManifestReport,
+    ) {
+        if list.is_empty() {
+            self.status_toast = Some((
+                tf(Text::NoChecksumsIn, &[&report.manifest.display()]),
+                std::time::Instant::now(),
+            ));
+            return;
+        }
+        for path in &report.untracked {
+            log::warn!("校验清单未列出: {}", path.display());
+        }
+
+        let dir = report.manifest.parent().map(Path::to_path_buf);
+        self.apply_checksum_list(&list, dir.as_deref());
+        self.manifest = Some(ManifestView::new(list, report));
+        self.rows_generation += 1;
+        if let Some(dir) = dir {
+            self.add_files(vec![dir]);
+        }
+    }
+
+    /// 把校验清单应用到列表中的文件：`root` 为 None 时按文件名匹配，否则只应用到该目录下
+    /// 的文件并按相对路径匹配；返回 (匹配的文件数, 一致, 不一致)
+    fn apply_checksum_list(
+        &mut self,
+        list: &HashMap<String, ExpectedHash>,
+        root: Option<&Path>,
+    ) -> (usize, usize, usize) {
+        let (mut applied, mut matched, mut mismatched) = (0, 0, 0);
+        for file in &mut self.files {
+            let key = match root {
+                Some(root) => manifest_key(root, &file.path),
+                None => Some(file.filename()),
+            };
+            let Some(expected) = key.and_then(|key| list.get(&key)) else {
+                continue;
+            };
+            file.expected = Some(expected.clone());
+            applied += 1;
+            match expected.check(file) {
+                Some(true) => matched += 1,
+                Some(false) => {
+                    mismatched += 1;
+                    log::warn!(
+                        "与校验清单不一致: {} ({})",
+                        file.path.display(),
+                        expected.sidecar
+                    );
+                }
+                None => {}
+            }
+        }
+        self.rows_generation += 1;
+        (applied, matched, mismatched)
+    }
+
+    fn render_manifest_window(&mut self, ctx: &egui::Context) {
+        let mut open = true;
+
+        egui::Window::new(t(Text::ManifestReport))
+            .open(&mut open)
+            .default_width(560.0)
+            .show(ctx, |ui| {
+                let Some(manifest) = &self.manifest else {
+                    return;
+                };
+                let report = &manifest.report;
+                ui.label(tf(Text::ManifestLabel, &[&report.manifest.display()]));
+
+                let (mut matched, mut mismatched, mut pending) = (0, 0, 0);
+                for file in &self.files {
+                    let Some(expected) = &file.expected else {
+                        continue;
+                    };
+                    if !matches!(&expected.sidecar, ChecksumSource::File(path) if *path == report.manifest) {
+                        continue;
+                    }
+                    match expected.check(file) {
+                        Some(true) => matched += 1,
+                        Some(false) => mismatched += 1,
+                        None => pending += 1,
+                    }
+                }
+                ui.horizontal(|ui| {
+                    ui.label(
+                        egui::RichText::new(tf(Text::ManifestMatched, &[&matched]))
+                            .color(egui::Color32::GREEN),
+                    );
+                    ui.label(
+                        egui::RichText::new(tf(Text::ManifestMismatched, &[&mismatched]))
+                            .color(egui::Color32::RED),
+                    );
+                    ui.label(tf(Text::ManifestPending, &[&pending]));
+                });
+                ui.separator();
+
+                let warning = egui::Color32::from_rgb(230, 150, 30);
+                ui.label(
+                    egui::RichText::new(tf(
+                        Text::ManifestUntracked,
+                        &[&t(Text::Untracked), &report.untracked.len()],
+                    ))
+                    .color(warning)
+                    .strong(),
+                );
+                ScrollArea::vertical()
+                    .id_salt("manifest_untracked")
+                    .max_height(200.0)
+                    .show(ui, |ui| {
+                        for path in &report.untracked {
+                            ui.label(dunce::simplified(path).display().to_string());
+                        }
+                    });
+                ui.separator();
+
+                ui.label(
+                    egui::RichText::new(tf(Text::ManifestMissing, &[&report.missing.len()]))
+                        .strong(),
+                );
+                ScrollArea::vertical()
+                    .id_salt("manifest_missing")
+                    .max_height(200.0)
+                    .show(ui, |ui| {
+                        for name in &report.missing {
+                            ui.label(name);
+                        }
+                    });
+            });
+
+        // 关闭窗口即结束本次校验，之后扫描出的文件不再套用清单
+        if !open {
+            self.manifest = None;
+            self.rows_generation += 1;
+        }
+    }
+
+    /// 重命名完成后更新列表中的路径
+    fn finish_rename(&mut self, outcomes: Vec<RenameOutcome>) {
+        let mut renamed = 0;
+        let mut failed = 0;
+        for outcome in outcomes {
+            if outcome.result.is_err() {
+                failed += 1;
+                continue;
+            }
+            renamed += 1;
+            if let Some(idx) = self.file_index.remove(&outcome.source) {
+                self.files[idx].path = outcome.target.clone();
+                self.file_index.insert(outcome.target, idx);
+            }
+        }
+        self.rows_generation += 1;
+        self.rename = None;
+        let message = if failed == 0 {
+            tf(Text::Renamed, &[&renamed])
+        } else {
+            tf(Text::RenamedWithFailures, &[&renamed, &failed])
+        };
+        self.status_toast = Some((message, std::time::Instant::now()));
+    }
+
+    fn render_settings_window(&mut self, ctx: &egui::Context) {
+        // --- 点击外部关闭 (遮罩层) ---
+        egui::Area::new("settings_backdrop".into())
+            .fixed_pos(egui::pos2(0.0, 0.0))
+            .order(egui::Order::Middle) // 位于窗口之下
+            .show(ctx, |ui| {
+                let screen_rect = ctx.viewport_rect();
+                // 绘制半透明遮罩
+                ui.painter().rect_filled(
                     screen_rect,
                     egui::CornerRadius::ZERO,
                     egui::Color32::from_black_alpha(100),
                 );
 
-                // 捕获点击事件
-                let response = ui.allocate_rect(screen_rect, egui::Sense::click());
-                if response.clicked() {
-                    self.show_cache_settings = false;
-                    self.cache_operation_message = None;
-                }
-            });
+                // 捕获点击事件
+                let response = ui.allocate_rect(screen_rect, egui::Sense::click());
+                if response.clicked() {
+                    self.show_cache_settings = false;
+                    self.cache_operation_message = None;
+                }
+            });
+
+        let mut open = self.show_cache_settings;
+        let mut actions = SettingsActions::default();
+
+        egui::Window::new(t(Text::Settings))
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .default_width(420.0)
+            .pivot(egui::Align2::CENTER_CENTER)
+            .default_pos(ctx.viewport_rect().center())
+            .order(egui::Order::Foreground) // 位于遮罩之上
+            .show(ctx, |ui| {
+                ui.add_space(8.0);
+                let mut config_changed = self.render_performance_mode(ui, &mut actions);
+
+                ui.add_space(16.0);
+                ui.separator();
+                ui.add_space(16.0);
+
+                // 详细设置按分组排列，各组逐行加入同一个网格
+                egui::Grid::new("settings_grid")
+                    .num_columns(2)
+                    .spacing([24.0, 12.0])
+                    .striped(false)
+                    .show(ui, |ui| {
+                        config_changed |= self.render_performance_settings(ui);
+                        config_changed |= self.render_read_settings(ui);
+                        config_changed |= self.render_schedule_settings(ui);
+                        config_changed |= self.render_scan_settings(ui);
+                        config_changed |= self.render_scan_filter_settings(ui);
+                        config_changed |= self.render_cache_settings(ui);
+                        config_changed |= self.render_cache_validation_settings(ui);
+                        config_changed |= self.render_notification_settings(ui);
+                        config_changed |= self.render_export_settings(ui);
+                        config_changed |= self.render_language_settings(ui);
+                        config_changed |= self.render_progress_settings(ui);
+                        config_changed |= self.render_advanced_settings(ui, &mut actions);
+                    });
+
+                ui.add_space(16.0);
+                ui.separator();
+                ui.add_space(16.0);
+
+                self.render_maintenance_actions(ui, &mut actions);
+                ui.add_space(8.0);
+
+                // 立即保存
+                if config_changed {
+                    self.persist_config();
+                }
+            });
+        self.show_cache_settings = open;
+
+        if actions.audit {
+            self.open_cache_audit_dialog();
+        }
+        if actions.update_check {
+            self.check_for_update(true);
+        }
+        if let Some(kind) = actions.cleanup {
+            self.start_cache_cleanup(kind);
+        }
+        if actions.benchmark {
+            self.benchmark = Some(BenchmarkView::default());
+        }
+    }
+
+    /// 设置窗口顶部的性能模式切换与性能测试入口
+    fn render_performance_mode(
+        &mut self,
+        ui: &mut egui::Ui,
+        actions: &mut SettingsActions,
+    ) -> bool {
+        let mut config_changed = false;
+        ui.horizontal(|ui| {
+            ui.label(egui::RichText::new(t(Text::PerformanceMode)).strong());
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                ui.label(
+                    egui::RichText::new(t(Text::PerformanceModeHint))
+                        .weak()
+                        .small(),
+                );
+            });
+        });
+        ui.add_space(4.0);
+
+        let current_mode = self.cache_config.performance_mode;
+        let mut selected_mode = current_mode;
+        ui.horizontal(|ui| {
+            ui.style_mut().spacing.item_spacing.x = 0.0;
+            // 简单的分段按钮样式
+            for preset in &PERFORMANCE_PRESETS {
+                if ui
+                    .selectable_label(
+                        selected_mode == preset.mode,
+                        format!("{} {}", preset.icon, preset.mode.label()),
+                    )
+                    .clicked()
+                {
+                    selected_mode = preset.mode;
+                    config_changed = true;
+                }
+            }
+            if current_mode == PerformanceMode::Custom {
+                let _ =
+                    ui.selectable_label(true, format!("🔧 {}", PerformanceMode::Custom.label()));
+            }
+            ui.add_space(8.0);
+            if ui
+                .add_enabled(!self.is_computing, egui::Button::new(t(Text::Benchmark)))
+                .on_hover_text(t(Text::BenchmarkHint))
+                .clicked()
+            {
+                actions.benchmark = true;
+            }
+        });
+
+        if selected_mode != current_mode {
+            self.cache_config.set_performance_mode(selected_mode);
+        }
+        config_changed
+    }
+
+    /// 性能设置：读取缓冲与映射块
+    fn render_performance_settings(&mut self, ui: &mut egui::Ui) -> bool {
+        let sizes_before = (
+            self.cache_config.buffer_size,
+            self.cache_config.mmap_chunk_size,
+        );
+        let mut config_changed = false;
+
+        // Buffer Size
+        ui.label(t(Text::BufferSize));
+        egui::ComboBox::from_id_salt("buf_size")
+            .selected_text(format_size(
+                self.cache_config.buffer_size,
+                self.cache_config.size_unit,
+            ))
+            .show_ui(ui, |ui| {
+                for (size, label) in [
+                    (64 * 1024, "64 KB"),
+                    (256 * 1024, "256 KB"),
+                    (1024 * 1024, "1 MB"),
+                    (2 * 1024 * 1024, "2 MB"),
+                    (4 * 1024 * 1024, "4 MB"),
+                ] {
+                    if ui
+                        .selectable_value(&mut self.cache_config.buffer_size, size, label)
+                        .changed()
+                    {
+                        config_changed = true;
+                    }
+                }
+            });
+        ui.end_row();
+
+        // MMAP Chunk
+        ui.label(t(Text::MmapChunk));
+        egui::ComboBox::from_id_salt("mmap_size")
+            .selected_text(format_size(
+                self.cache_config.mmap_chunk_size,
+                self.cache_config.size_unit,
+            ))
+            .show_ui(ui, |ui| {
+                for (size, label) in [
+                    (1024 * 1024, "1 MB"),
+                    (4 * 1024 * 1024, "4 MB"),
+                    (16 * 1024 * 1024, "16 MB"),
+                    (64 * 1024 * 1024, "64 MB"),
+                ] {
+                    if ui
+                        .selectable_value(&mut self.cache_config.mmap_chunk_size, size, label)
+                        .changed()
+                    {
+                        config_changed = true;
+                    }
+                }
+            });
+        ui.end_row();
+
+        // 手动调整大小后，不再匹配任何预设即视为自定义
+        if (
+            self.cache_config.buffer_size,
+            self.cache_config.mmap_chunk_size,
+        ) != sizes_before
+        {
+            self.cache_config.performance_mode = PerformanceMode::from_sizes(
+                self.cache_config.buffer_size,
+                self.cache_config.mmap_chunk_size,
+            );
+        }
+        config_changed
+    }
+
+    /// 读取设置：网络路径、稀疏文件、块设备与数据流
+    fn render_read_settings(&mut self, ui: &mut egui::Ui) -> bool {
+        let mut config_changed = false;
+
+        // Network paths
+        ui.label(t(Text::NetworkPaths));
+        if ui
+            .checkbox(
+                &mut self.cache_config.network_mmap_enabled,
+                t(Text::AllowNetworkMmap),
+            )
+            .on_hover_text(t(Text::AllowNetworkMmapHint))
+            .changed()
+        {
+            config_changed = true;
+        }
+        ui.end_row();
+
+        // Sparse files
+        ui.label(t(Text::SparseFiles));
+        if ui
+            .checkbox(
+                &mut self.cache_config.sparse_files,
+                t(Text::SkipSparseHoles),
+            )
+            .on_hover_text(t(Text::SkipSparseHolesHint))
+            .changed()
+        {
+            config_changed = true;
+        }
+        ui.end_row();
+
+        // Block devices
+        ui.label(t(Text::BlockDevices));
+        if ui
+            .add_enabled(
+                cfg!(unix),
+                egui::Checkbox::new(
+                    &mut self.cache_config.hash_block_devices,
+                    t(Text::AllowBlockDevices),
+                ),
+            )
+            .on_hover_text(t(Text::AllowBlockDevicesHint))
+            .changed()
+        {
+            config_changed = true;
+        }
+        ui.end_row();
+
+        // NTFS alternate data streams
+        ui.label(t(Text::AlternateStreams));
+        if ui
+            .add_enabled(
+                cfg!(windows),
+                egui::Checkbox::new(
+                    &mut self.cache_config.hash_alternate_streams,
+                    t(Text::HashAlternateStreams),
+                ),
+            )
+            .on_hover_text(t(Text::HashAlternateStreamsHint))
+            .on_disabled_hover_text(t(Text::WindowsOnly))
+            .changed()
+        {
+            config_changed = true;
+        }
+        ui.end_row();
+        config_changed
+    }
+
+    /// 调度设置：计算顺序、磁盘并发与防止休眠
+    fn render_schedule_settings(&mut self, ui: &mut egui::Ui) -> bool {
+        let mut config_changed = false;
+
+        // Compute order
+        ui.label(t(Text::ComputeOrder));
+        egui::ComboBox::from_id_salt("compute_order")
+            .selected_text(self.cache_config.compute_order.label())
+            .show_ui(ui, |ui| {
+                for order in ComputeOrder::ALL {
+                    if ui
+                        .selectable_value(
+                            &mut self.cache_config.compute_order,
+                            order,
+                            order.label(),
+                        )
+                        .changed()
+                    {
+                        config_changed = true;
+                    }
+                }
+            })
+            .response
+            .on_hover_text(t(Text::ComputeOrderHint));
+        ui.end_row();
+
+        // Disk concurrency
+        ui.label(t(Text::DiskConcurrency));
+        egui::ComboBox::from_id_salt("disk_concurrency")
+            .selected_text(self.cache_config.disk_concurrency.label())
+            .show_ui(ui, |ui| {
+                for mode in DiskConcurrency::ALL {
+                    if ui
+                        .selectable_value(
+                            &mut self.cache_config.disk_concurrency,
+                            mode,
+                            mode.label(),
+                        )
+                        .changed()
+                    {
+                        config_changed = true;
+                    }
+                }
+            })
+            .response
+            .on_hover_text(
+                t(Text::DiskConcurrencyHint).replace("{}", &ROTATIONAL_THREADS.to_string()),
+            );
+        ui.end_row();
+
+        // Power
+        ui.label(t(Text::Power));
+        if ui
+            .checkbox(&mut self.cache_config.prevent_sleep, t(Text::PreventSleep))
+            .on_hover_text(t(Text::PreventSleepHint))
+            .changed()
+        {
+            config_changed = true;
+            if self.cache_config.prevent_sleep {
+                if self.is_computing && self.sleep_inhibitor.is_none() {
+                    self.sleep_inhibitor = SleepInhibitor::acquire();
+                }
+            } else {
+                self.sleep_inhibitor = None;
+            }
+        }
+        ui.end_row();
+        config_changed
+    }
+
+    /// 扫描设置：批次、并行度与跳过的文件
+    fn render_scan_settings(&mut self, ui: &mut egui::Ui) -> bool {
+        let mut config_changed = false;
+
+        // Scanner batching
+        ui.label(t(Text::ScanBatch));
+        ui.horizontal(|ui| {
+            if ui
+                .add(
+                    egui::DragValue::new(&mut self.cache_config.scan_batch_size)
+                        .range(10..=10_000)
+                        .suffix(t(Text::FilesSuffix)),
+                )
+                .on_hover_text(t(Text::ScanBatchSizeHint))
+                .changed()
+            {
+                config_changed = true;
+            }
+            if ui
+                .add(
+                    egui::DragValue::new(&mut self.cache_config.scan_flush_ms)
+                        .range(10..=5_000)
+                        .suffix(" ms"),
+                )
+                .on_hover_text(t(Text::ScanFlushHint))
+                .changed()
+            {
+                config_changed = true;
+            }
+        });
+        ui.end_row();
+
+        // Scanner parallelism
+        ui.label(t(Text::ScanThreads));
+        let threads_label = |n: usize| match n {
+            0 => String::from(t(Text::ThreadsAuto)),
+            1 => String::from(t(Text::ThreadsSerial)),
+            n => format!("{} {}", n, t(Text::Threads)),
+        };
+        egui::ComboBox::from_id_salt("scan_threads")
+            .selected_text(threads_label(self.cache_config.scan_threads))
+            .show_ui(ui, |ui| {
+                for n in [1, 0, 2, 4, 8, 16] {
+                    if ui
+                        .selectable_value(&mut self.cache_config.scan_threads, n, threads_label(n))
+                        .changed()
+                    {
+                        config_changed = true;
+                    }
+                }
+            })
+            .response
+            .on_hover_text(t(Text::ScanThreadsHint));
+        ui.end_row();
+
+        // Maximum file size
+        ui.label(t(Text::MaxFileSize));
+        egui::ComboBox::from_id_salt("max_file_size")
+            .selected_text(match self.cache_config.max_file_size {
+                0 => t(Text::Unlimited).to_string(),
+                size => format_size(size, self.cache_config.size_unit),
+            })
+            .show_ui(ui, |ui| {
+                for (size, label) in [
+                    (0, t(Text::Unlimited)),
+                    (1024 * 1024 * 1024, "1 GB"),
+                    (10 * 1024 * 1024 * 1024, "10 GB"),
+                    (100 * 1024 * 1024 * 1024, "100 GB"),
+                    (1024 * 1024 * 1024 * 1024, "1 TB"),
+                ] {
+                    if ui
+                        .selectable_value(&mut self.cache_config.max_file_size, size, label)
+                        .changed()
+                    {
+                        config_changed = true;
+                    }
+                }
+            })
+            .response
+            .on_hover_text(t(Text::MaxFileSizeHint));
+        ui.end_row();
+
+        // Symlinks
+        ui.label(t(Text::SymlinkPolicy));
+        egui::ComboBox::from_id_salt("symlink_policy")
+            .selected_text(self.cache_config.symlink_policy.label())
+            .show_ui(ui, |ui| {
+                for policy in SymlinkPolicy::ALL {
+                    if ui
+                        .selectable_value(
+                            &mut self.cache_config.symlink_policy,
+                            policy,
+                            policy.label(),
+                        )
+                        .changed()
+                    {
+                        config_changed = true;
+                    }
+                }
+            })
+            .response
+            .on_hover_text(t(Text::SymlinkPolicyHint));
+        ui.end_row();
+        config_changed
+    }
+
+    /// 扫描设置：排除目录、文件名规范化与校验文件
+    fn render_scan_filter_settings(&mut self, ui: &mut egui::Ui) -> bool {
+        let mut config_changed = false;
+
+        // Excluded directories
+        ui.label(t(Text::ExcludedDirs));
+        let response = ui
+            .add(
+                egui::TextEdit::multiline(&mut self.excluded_dirs_text)
+                    .desired_rows(3)
+                    .desired_width(220.0),
+            )
+            .on_hover_text(t(Text::ExcludedDirsHint));
+        if response.lost_focus() {
+            let dirs = parse_dir_names(&self.excluded_dirs_text);
+            self.excluded_dirs_text = dirs.join("\n");
+            if dirs != self.cache_config.excluded_dirs {
+                self.cache_config.excluded_dirs = dirs;
+                config_changed = true;
+            }
+        }
+        ui.end_row();
+
+        // Unicode filename normalization
+        ui.label(t(Text::FilenameNormalization));
+        if ui
+            .checkbox(
+                &mut self.cache_config.normalize_unicode_names,
+                t(Text::NormalizeNfc),
+            )
+            .on_hover_text(t(Text::NormalizeNfcHint))
+            .changed()
+        {
+            config_changed = true;
+        }
+        ui.end_row();
+
+        // Sidecar verification
+        ui.label(t(Text::Sidecars));
+        if ui
+            .checkbox(
+                &mut self.cache_config.verify_sidecars,
+                t(Text::VerifySidecars),
+            )
+            .on_hover_text(t(Text::VerifySidecarsHint))
+            .changed()
+        {
+            config_changed = true;
+        }
+        ui.end_row();
+        config_changed
+    }
+
+    /// 缓存设置：最小文件、保留期限与数据库维护
+    fn render_cache_settings(&mut self, ui: &mut egui::Ui) -> bool {
+        let mut config_changed = false;
+
+        // Min File Size
+        ui.label(t(Text::MinFileSize));
+        egui::ComboBox::from_id_salt("min_file_size")
+            .selected_text(format_size(
+                self.cache_config.min_file_size,
+                self.cache_config.size_unit,
+            ))
+            .show_ui(ui, |ui| {
+                for (size, label) in [
+                    (1024 * 1024, "1 MB"),
+                    (10 * 1024 * 1024, "10 MB"),
+                    (100 * 1024 * 1024, "100 MB"),
+                    (1024 * 1024 * 1024, "1 GB"),
+                ] {
+                    if ui
+                        .selectable_value(&mut self.cache_config.min_file_size, size, label)
+                        .changed()
+                    {
+                        config_changed = true;
+                    }
+                }
+            });
+        ui.end_row();
+
+        // Retention
+        ui.label(t(Text::Retention));
+        ui.horizontal(|ui| {
+            if ui
+                .add(
+                    egui::DragValue::new(&mut self.cache_config.retention_days)
+                        .speed(1)
+                        .suffix(t(Text::DaysSuffix)),
+                )
+                .changed()
+            {
+                config_changed = true;
+            }
+            if self.cache_config.retention_days == 0 {
+                ui.label(
+                    egui::RichText::new(t(Text::Forever))
+                        .color(egui::Color32::GOLD)
+                        .small(),
+                );
+            }
+        });
+        ui.end_row();
+
+        // Removable media
+        ui.label(t(Text::RemovableMedia));
+        if ui
+            .checkbox(
+                &mut self.cache_config.skip_removable_cache,
+                t(Text::SkipRemovableCache),
+            )
+            .on_hover_text(t(Text::SkipRemovableCacheHint))
+            .changed()
+        {
+            config_changed = true;
+        }
+        ui.end_row();
+
+        // Vacuum schedule
+        ui.label(t(Text::VacuumSchedule));
+        egui::ComboBox::from_id_salt("vacuum_schedule")
+            .selected_text(self.cache_config.vacuum_schedule.label())
+            .show_ui(ui, |ui| {
+                for schedule in VacuumSchedule::ALL {
+                    if ui
+                        .selectable_value(
+                            &mut self.cache_config.vacuum_schedule,
+                            schedule,
+                            schedule.label(),
+                        )
+                        .changed()
+                    {
+                        config_changed = true;
+                    }
+                }
+            })
+            .response
+            .on_hover_text(t(Text::VacuumScheduleHint));
+        ui.end_row();
+        config_changed
+    }
+
+    /// 缓存校验设置：修改时间容差与校验级别
+    fn render_cache_validation_settings(&mut self, ui: &mut egui::Ui) -> bool {
+        let mut config_changed = false;
+
+        // Modified time tolerance
+        ui.label(t(Text::MtimeTolerance));
+        if ui
+            .add(
+                egui::DragValue::new(&mut self.cache_config.mtime_tolerance_ms)
+                    .range(0..=10_000)
+                    .suffix(" ms"),
+            )
+            .on_hover_text(t(Text::MtimeToleranceHint))
+            .changed()
+        {
+            config_changed = true;
+        }
+        ui.end_row();
+
+        // Cache validation
+        ui.label(t(Text::CacheValidation));
+        egui::ComboBox::from_id_salt("cache_validation")
+            .selected_text(self.cache_config.cache_validation.label())
+            .show_ui(ui, |ui| {
+                for level in CacheValidation::ALL {
+                    if ui
+                        .selectable_value(
+                            &mut self.cache_config.cache_validation,
+                            level,
+                            level.label(),
+                        )
+                        .changed()
+                    {
+                        config_changed = true;
+                    }
+                }
+            })
+            .response
+            .on_hover_text(t(Text::CacheValidationHint));
+        ui.end_row();
+        config_changed
+    }
+
+    /// 通知与提示设置
+    fn render_notification_settings(&mut self, ui: &mut egui::Ui) -> bool {
+        let mut config_changed = false;
+
+        // System tray
+        ui.label(t(Text::SystemTray));
+        let tray_response = ui
+            .add_enabled(
+                TrayHandle::SUPPORTED,
+                egui::Checkbox::new(
+                    &mut self.cache_config.minimize_to_tray,
+                    t(Text::MinimizeToTray),
+                ),
+            )
+            .on_hover_text(t(Text::MinimizeToTrayHint))
+            .on_disabled_hover_text(t(Text::TrayUnavailable));
+        if tray_response.changed() {
+            self.tray = if self.cache_config.minimize_to_tray {
+                try_create_tray(ui.ctx())
+            } else {
+                None
+            };
+            self.tray_tooltip.clear();
+            config_changed = true;
+        }
+        ui.end_row();
+
+        // Notification
+        ui.label(t(Text::CompletionNotice));
+        ui.horizontal(|ui| {
+            if ui
+                .checkbox(&mut self.cache_config.notify_on_complete, t(Text::Enable))
+                .changed()
+            {
+                config_changed = true;
+            }
+            ui.add_enabled_ui(self.cache_config.notify_on_complete, |ui| {
+                ui.label(t(Text::LongerThan));
+                if ui
+                    .add(
+                        egui::DragValue::new(&mut self.cache_config.notify_threshold_secs)
+                            .range(0..=3600)
+                            .suffix(t(Text::SecondsSuffix)),
+                    )
+                    .changed()
+                {
+                    config_changed = true;
+                }
+            });
+        });
+        ui.end_row();
+
+        // Clear confirmation
+        ui.label(t(Text::ClearQueue));
+        if ui
+            .checkbox(
+                &mut self.cache_config.confirm_clear_queue,
+                t(Text::ConfirmClear),
+            )
+            .on_hover_text(t(Text::ConfirmClearHint))
+            .changed()
+        {
+            config_changed = true;
+        }
+        ui.end_row();
+
+        // Copy feedback
+        ui.label(t(Text::CopyFeedback));
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_id_salt("copy_feedback")
+                .selected_text(self.cache_config.copy_feedback.label())
+                .show_ui(ui, |ui| {
+                    for feedback in CopyFeedback::ALL {
+                        if ui
+                            .selectable_value(
+                                &mut self.cache_config.copy_feedback,
+                                feedback,
+                                feedback.label(),
+                            )
+                            .changed()
+                        {
+                            config_changed = true;
+                        }
+                    }
+                })
+                .response
+                .on_hover_text(t(Text::CopyFeedbackHint));
+            if ui
+                .add(
+                    egui::DragValue::new(&mut self.cache_config.copy_toast_ms)
+                        .range(500..=10_000)
+                        .speed(100)
+                        .suffix(" ms"),
+                )
+                .on_hover_text(t(Text::CopyToastDurationHint))
+                .changed()
+            {
+                config_changed = true;
+            }
+        });
+        ui.end_row();
+        config_changed
+    }
+
+    /// 导出设置：哈希算法、编码与扩展属性
+    fn render_export_settings(&mut self, ui: &mut egui::Ui) -> bool {
+        let mut config_changed = false;
+
+        // Hash algorithms
+        ui.label(t(Text::HashAlgorithms));
+        ui.vertical(|ui| {
+            ui.horizontal(|ui| {
+                for algorithm in HashAlgorithm::OPTIONAL {
+                    let mut enabled = self.cache_config.enabled_hashes.contains(algorithm);
+                    if ui.checkbox(&mut enabled, algorithm.label()).changed() {
+                        self.cache_config.enabled_hashes.set(algorithm, enabled);
+                        config_changed = true;
+                    }
+                }
+            });
+            let insecure = self.cache_config.enabled_hashes.insecure_without_sha256();
+            if !insecure.is_empty() {
+                let names: Vec<&str> = insecure.into_iter().map(HashAlgorithm::label).collect();
+                ui.label(
+                    egui::RichText::new(tf(Text::InsecureHashWarning, &[&names.join("/")]))
+                        .small()
+                        .color(egui::Color32::from_rgb(230, 160, 40)),
+                );
+            }
+        });
+        ui.end_row();
+
+        // Hash encoding
+        ui.label(t(Text::HashEncoding));
+        egui::ComboBox::from_id_salt("hash_encoding")
+            .selected_text(self.cache_config.hash_encoding.label())
+            .show_ui(ui, |ui| {
+                for encoding in HashEncoding::ALL {
+                    if ui
+                        .selectable_value(
+                            &mut self.cache_config.hash_encoding,
+                            encoding,
+                            encoding.label(),
+                        )
+                        .changed()
+                    {
+                        config_changed = true;
+                    }
+                }
+            })
+            .response
+            .on_hover_text(t(Text::HashEncodingHint));
+        ui.end_row();
+
+        // Extended attributes
+        ui.label(t(Text::Xattrs));
+        if ui
+            .add_enabled(
+                xattrs::SUPPORTED,
+                egui::Checkbox::new(
+                    &mut self.cache_config.write_xattrs,
+                    t(Text::WriteXattrsOnComplete),
+                ),
+            )
+            .on_hover_text(t(Text::WriteXattrsOnCompleteHint))
+            .on_disabled_hover_text(t(Text::XattrUnavailable))
+            .changed()
+        {
+            config_changed = true;
+        }
+        ui.end_row();
+        config_changed
+    }
+
+    /// 语言与显示设置
+    fn render_language_settings(&mut self, ui: &mut egui::Ui) -> bool {
+        let mut config_changed = false;
+
+        // UI language
+        ui.label("界面语言 (Language)");
+        egui::ComboBox::from_id_salt("ui_language")
+            .selected_text(self.cache_config.ui_language.label())
+            .show_ui(ui, |ui| {
+                for language in UiLanguage::ALL {
+                    if ui
+                        .selectable_value(
+                            &mut self.cache_config.ui_language,
+                            language,
+                            language.label(),
+                        )
+                        .changed()
+                    {
+                        i18n::set_language(
+                            language.resolve(self.chinese_font),
+                        );
+                        config_changed = true;
+                    }
+                }
+            })
+            .response
+            .on_hover_text(
+                "Auto uses English when no Chinese font is installed. 自动：未找到中文字体时使用英文界面。",
+            );
+        ui.end_row();
+
+        // Size unit
+        ui.label(t(Text::SizeUnit));
+        egui::ComboBox::from_id_salt("size_unit")
+            .selected_text(self.cache_config.size_unit.label())
+            .show_ui(ui, |ui| {
+                for unit in SizeUnit::ALL {
+                    if ui
+                        .selectable_value(&mut self.cache_config.size_unit, unit, unit.label())
+                        .changed()
+                    {
+                        config_changed = true;
+                    }
+                }
+            })
+            .response
+            .on_hover_text(t(Text::SizeUnitHint));
+        ui.end_row();
+
+        // Recent paths
+        ui.label(t(Text::RecentLimit));
+        if ui
+            .add(
+                egui::DragValue::new(&mut self.cache_config.recent_limit)
+                    .range(1..=50)
+                    .suffix(t(Text::EntriesSuffix)),
+            )
+            .changed()
+        {
+            config_changed = true;
+        }
+        ui.end_row();
+        config_changed
+    }
+
+    /// 进度与界面刷新设置
+    fn render_progress_settings(&mut self, ui: &mut egui::Ui) -> bool {
+        let mut config_changed = false;
+
+        // Progress cost model
+        ui.label(t(Text::ProgressModel));
+        ui.horizontal(|ui| {
+            if ui
+                .checkbox(
+                    &mut self.cache_config.progress_by_file_count,
+                    t(Text::ByFileCount),
+                )
+                .on_hover_text(t(Text::ByFileCountHint))
+                .changed()
+            {
+                config_changed = true;
+            }
+            if ui
+                .add_enabled(
+                    !self.cache_config.progress_by_file_count,
+                    egui::DragValue::new(&mut self.cache_config.progress_file_cost_kb)
+                        .range(0..=16_384)
+                        .prefix(t(Text::PerFilePrefix))
+                        .suffix(" KB"),
+                )
+                .on_hover_text(t(Text::PerFileCostHint))
+                .changed()
+            {
+                config_changed = true;
+            }
+        });
+        ui.end_row();
+
+        // Messages per frame
+        ui.label(t(Text::MessagesPerFrame));
+        let messages_label = |n: usize| match n {
+            0 => String::from(t(Text::MessagesPerFrameAuto)),
+            n => format!("{} {}", n, t(Text::MessagesPerFrameUnit)),
+        };
+        egui::ComboBox::from_id_salt("max_messages_per_frame")
+            .selected_text(messages_label(self.cache_config.max_messages_per_frame))
+            .show_ui(ui, |ui| {
+                for n in [0, 100, 500, 2000] {
+                    if ui
+                        .selectable_value(
+                            &mut self.cache_config.max_messages_per_frame,
+                            n,
+                            messages_label(n),
+                        )
+                        .changed()
+                    {
+                        config_changed = true;
+                    }
+                }
+            })
+            .response
+            .on_hover_text(t(Text::MessagesPerFrameHint));
+        ui.end_row();
+
+        // UI channel capacity
+        ui.label(t(Text::ChannelCapacity));
+        egui::ComboBox::from_id_salt("ui_channel_capacity")
+            .selected_text(format!(
+                "{} {}",
+                self.cache_config.ui_channel_capacity,
+                t(Text::MessagesPerFrameUnit)
+            ))
+            .show_ui(ui, |ui| {
+                for n in [MIN_UI_CHANNEL_CAPACITY, 256, 1024, 4096] {
+                    if ui
+                        .selectable_value(
+                            &mut self.cache_config.ui_channel_capacity,
+                            n,
+                            format!("{} {}", n, t(Text::MessagesPerFrameUnit)),
+                        )
+                        .changed()
+                    {
+                        config_changed = true;
+                    }
+                }
+            })
+            .response
+            .on_hover_text(t(Text::ChannelCapacityHint));
+        ui.end_row();
+        config_changed
+    }
+
+    /// 日志、更新检查与诊断设置
+    fn render_advanced_settings(
+        &mut self,
+        ui: &mut egui::Ui,
+        actions: &mut SettingsActions,
+    ) -> bool {
+        let mut config_changed = false;
+
+        // Logging
+        ui.label(t(Text::Logging));
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_id_salt("log_level")
+                .selected_text(crate::logging::level_label(self.cache_config.log_level))
+                .show_ui(ui, |ui| {
+                    for (level, label) in crate::logging::LEVELS {
+                        if ui
+                            .selectable_value(&mut self.cache_config.log_level, level, t(label))
+                            .changed()
+                        {
+                            crate::logging::set_level(level);
+                            config_changed = true;
+                        }
+                    }
+                });
+            if let Some(log_file) = crate::logging::log_file() {
+                if ui.button(t(Text::ViewLog)).clicked() {
+                    if let Err(e) = open_with_system(log_file) {
+                        self.cache_operation_message =
+                            Some(format!("{}: {}", t(Text::OpenLogFailed), e));
+                    }
+                }
+                if ui.button(t(Text::OpenLogDir)).clicked() {
+                    let dir = log_file.parent().unwrap_or(std::path::Path::new("."));
+                    if let Err(e) = open_with_system(dir) {
+                        self.cache_operation_message =
+                            Some(format!("{}: {}", t(Text::OpenDirFailed), e));
+                    }
+                }
+            }
+        });
+        ui.end_row();
+
+        // Update check
+        ui.label(t(Text::UpdateCheck));
+        ui.horizontal(|ui| {
+            ui.add_enabled_ui(update::SUPPORTED, |ui| {
+                if ui
+                    .checkbox(
+                        &mut self.cache_config.update_check,
+                        t(Text::CheckUpdateOnStartup),
+                    )
+                    .on_hover_text(t(Text::CheckUpdateHint))
+                    .on_disabled_hover_text(t(Text::UpdateUnsupportedHint))
+                    .changed()
+                {
+                    config_changed = true;
+                }
+                let response = ui.add(
+                    egui::TextEdit::singleline(&mut self.cache_config.update_manifest_url)
+                        .desired_width(260.0),
+                );
+                if response.lost_focus() {
+                    let url = self.cache_config.update_manifest_url.trim();
+                    self.cache_config.update_manifest_url = if url.is_empty() {
+                        update::DEFAULT_MANIFEST_URL.to_string()
+                    } else {
+                        url.to_string()
+                    };
+                    config_changed = true;
+                }
+                if ui.button(t(Text::CheckUpdateNow)).clicked() {
+                    actions.update_check = true;
+                }
+            });
+        });
+        ui.end_row();
+
+        // Diagnostics
+        ui.label(t(Text::Diagnostics));
+        ui.horizontal(|ui| {
+            if ui
+                .checkbox(&mut self.cache_config.hash_timing, t(Text::HashTiming))
+                .on_hover_text(t(Text::HashTimingHint))
+                .changed()
+            {
+                crate::hash_timing::set_enabled(self.cache_config.hash_timing);
+                config_changed = true;
+            }
+            if ui
+                .checkbox(
+                    &mut self.cache_config.serial_compute,
+                    t(Text::SerialCompute),
+                )
+                .on_hover_text(t(Text::SerialComputeHint))
+                .changed()
+            {
+                config_changed = true;
+            }
+        });
+        ui.end_row();
+        config_changed
+    }
+
+    /// 设置窗口底部的缓存维护操作
+    fn render_maintenance_actions(&mut self, ui: &mut egui::Ui, actions: &mut SettingsActions) {
+        ui.horizontal(|ui| {
+            let idle = self.cleanup.is_none();
+            if ui
+                .add_enabled(idle, egui::Button::new(t(Text::CleanExpired)))
+                .clicked()
+            {
+                actions.cleanup = Some(CleanupKind::Expired);
+            }
+            if ui
+                .button(t(Text::CheckpointWal))
+                .on_hover_text(t(Text::CheckpointWalHint))
+                .clicked()
+            {
+                let result = match self.cache.lock() {
+                    Ok(cache_guard) => cache_guard.checkpoint_wal().map_err(|e| e.to_string()),
+                    Err(e) => Err(e.to_string()),
+                };
+                self.cache_operation_message = Some(match result {
+                    Ok(true) => String::from(t(Text::WalCheckpointed)),
+                    Ok(false) => String::from(t(Text::WalPartial)),
+                    Err(e) => format!("{}: {}", t(Text::OperationFailed), e),
+                });
+            }
+            if ui
+                .button(t(Text::AuditCache))
+                .on_hover_text(t(Text::AuditCacheHint))
+                .clicked()
+            {
+                actions.audit = true;
+            }
+            if ui
+                .add_enabled(idle, egui::Button::new(t(Text::ClearAll)))
+                .clicked()
+            {
+                actions.cleanup = Some(CleanupKind::All);
+            }
+
+            if let Some(view) = &self.cleanup {
+                let status = match view.progress {
+                    Some(CleanupProgress::Deleting { deleted, total }) => format!(
+                        "{} {} / {}",
+                        t(Text::Cleaning),
+                        format_count(deleted),
+                        format_count(total)
+                    ),
+                    Some(CleanupProgress::Vacuuming) => String::from(t(Text::Vacuuming)),
+                    None => String::from(t(Text::Cleaning)),
+                };
+                ui.spinner();
+                ui.label(egui::RichText::new(status).small());
+                let cancellable = !matches!(view.progress, Some(CleanupProgress::Vacuuming));
+                if ui
+                    .add_enabled(
+                        cancellable && !view.cancel.load(Ordering::Relaxed),
+                        egui::Button::new(t(Text::Cancel)),
+                    )
+                    .on_disabled_hover_text(t(Text::VacuumNotCancellable))
+                    .clicked()
+                {
+                    view.cancel.store(true, Ordering::Relaxed);
+                }
+            } else if let Some(msg) = &self.cache_operation_message {
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    ui.label(
+                        egui::RichText::new(msg)
+                            .color(egui::Color32::LIGHT_BLUE)
+                            .small(),
+                    );
+                });
+            }
+        });
+    }
+
+    /// 工具栏：添加、清空与刷新文件列表
+    fn render_queue_actions(&mut self, ui: &mut egui::Ui) {
+        ui.heading("TurboHash");
+        ui.separator();
+
+        if ui.button(t(Text::AddFiles)).clicked() {
+            self.open_file_dialog();
+        }
+
+        if ui
+            .button(t(Text::AddFolder))
+            .on_hover_text(t(Text::AddFolderHint))
+            .clicked()
+        {
+            self.open_folder_dialog();
+        }
+
+        if ui
+            .button(t(Text::PastePaths))
+            .on_hover_text(t(Text::PastePathsHint))
+            .clicked()
+        {
+            self.paste_paths_from_clipboard();
+        }
+
+        let recent = ui.menu_button(t(Text::Recent), |ui| self.render_recent_menu(ui));
+        if recent.inner.is_none() {
+            self.recent_menu_open = false;
+        }
+
+        let clear_button_enabled = !self.is_computing;
+        if ui
+            .add_enabled(clear_button_enabled, egui::Button::new(t(Text::ClearQueue)))
+            .clicked()
+        {
+            if self.cache_config.confirm_clear_queue && !self.files.is_empty() {
+                self.clear_confirm_open = true;
+            } else {
+                self.clear_files_with_undo();
+            }
+        }
+
+        if ui
+            .add_enabled(
+                !self.is_computing && !self.files.is_empty(),
+                egui::Button::new(t(Text::RefreshMetadata)),
+            )
+            .on_hover_text(t(Text::RefreshMetadataHint))
+            .clicked()
+        {
+            self.refresh_metadata();
+        }
+
+        if ui
+            .add_enabled(
+                reveal_target(&self.files, &self.selected_rows).is_some(),
+                egui::Button::new(t(Text::OpenContainingFolder)),
+            )
+            .on_hover_text(t(Text::OpenContainingFolderHint))
+            .clicked()
+        {
+            self.reveal_selected_file();
+        }
+    }
+
+    /// 工具栏：复制、导出与校验结果
+    fn render_export_actions(&mut self, ui: &mut egui::Ui, ctx: &egui::Context) {
+        if ui
+            .add_enabled(!self.files.is_empty(), egui::Button::new(t(Text::CopyAll)))
+            .on_hover_text(t(Text::CopyAllHint))
+            .clicked()
+        {
+            self.copy_all_results(ctx);
+        }
+
+        if ui
+            .add_enabled(
+                !self.files.is_empty(),
+                egui::Button::new(t(Text::ExportChecksums)),
+            )
+            .on_hover_text(t(Text::ExportChecksumsHint))
+            .clicked()
+        {
+            self.export_dialog.open = true;
+        }
+
+        if ui
+            .add_enabled(
+                remote::SUPPORTED && !self.files.is_empty(),
+                egui::Button::new(t(Text::RemoteVerify)),
+            )
+            .on_hover_text(t(Text::RemoteVerifyHint))
+            .on_disabled_hover_text(t(Text::RemoteVerifyUnavailable))
+            .clicked()
+        {
+            self.remote_verify = Some(RemoteVerifyView::default());
+        }
+
+        if ui
+            .button(t(Text::VerifyManifest))
+            .on_hover_text(t(Text::VerifyManifestHint))
+            .clicked()
+        {
+            self.open_manifest_dialog();
+        }
+
+        if ui
+            .add_enabled(
+                xattrs::SUPPORTED
+                    && !self.xattr_writing
+                    && self
+                        .files
+                        .iter()
+                        .any(|f| matches!(f.status, FileStatus::Completed)),
+                egui::Button::new(t(Text::WriteXattrs)),
+            )
+            .on_hover_text(t(Text::WriteXattrsHint))
+            .on_disabled_hover_text(if xattrs::SUPPORTED {
+                t(Text::NoCompletedFiles)
+            } else {
+                t(Text::XattrUnavailable)
+            })
+            .clicked()
+        {
+            self.write_xattrs_for_completed();
+        }
+    }
+
+    /// 工具栏：哈希列表、重复检查与列表筛选
+    fn render_analysis_actions(&mut self, ui: &mut egui::Ui) {
+        if ui
+            .button(t(Text::HashList))
+            .on_hover_text(t(Text::HashListHint))
+            .clicked()
+        {
+            self.hash_list.open = true;
+        }
+        if self.hash_list.list.is_some() {
+            if self.hash_list.dirty {
+                self.refresh_hash_list_matches();
+            }
+            if self.hash_list.matched > 0
+                && ui
+                    .label(
+                        egui::RichText::new(format!(
+                            "{} {}",
+                            t(Text::HashListMatched),
+                            self.hash_list.matched
+                        ))
+                        .color(egui::Color32::RED)
+                        .strong(),
+                    )
+                    .on_hover_text(t(Text::HashListMatchedHint))
+                    .interact(egui::Sense::click())
+                    .clicked()
+            {
+                self.hash_list.open = true;
+            }
+        }
+
+        if ui
+            .add_enabled(
+                !self.files.is_empty(),
+                egui::Button::new(t(Text::Collisions)),
+            )
+            .on_hover_text(t(Text::CollisionsHint))
+            .clicked()
+        {
+            self.collision_report = Some(find_collisions(&self.files));
+        }
+
+        if ui
+            .add_enabled(
+                !self.files.is_empty(),
+                egui::Button::new(t(Text::NameConflicts)),
+            )
+            .on_hover_text(t(Text::NameConflictsHint))
+            .clicked()
+        {
+            self.name_conflicts = Some(find_name_conflicts(&self.files));
+        }
+
+        let problems = self.problem_rows().len();
+        ui.toggle_value(&mut self.only_problems, t(Text::OnlyProblems))
+            .on_hover_text(t(Text::OnlyProblemsHint));
+        if problems > 0 {
+            ui.label(
+                egui::RichText::new(format!(
+                    "⚠️ {} {}",
+                    format_count(problems),
+                    t(Text::Problems)
+                ))
+                .color(egui::Color32::from_rgb(230, 150, 30)),
+            );
+        }
+
+        egui::ComboBox::from_id_salt("source_filter")
+            .selected_text(self.source_filter.label())
+            .show_ui(ui, |ui| {
+                for filter in SourceFilter::ALL {
+                    ui.selectable_value(&mut self.source_filter, filter, filter.label());
+                }
+            })
+            .response
+            .on_hover_text(t(Text::SourceFilterHint));
+
+        ui.toggle_value(&mut self.show_type_summary, t(Text::TypeSummary))
+            .on_hover_text(t(Text::TypeSummaryHint));
+        ui.toggle_value(&mut self.group_by_folder, t(Text::GroupByFolder))
+            .on_hover_text(t(Text::GroupByFolderHint));
+    }
+
+    /// 工具栏：重命名、对比等工具窗口
+    fn render_tool_actions(&mut self, ui: &mut egui::Ui, ctx: &egui::Context) {
+        if ui
+            .add_enabled(
+                !self.is_computing && !self.files.is_empty(),
+                egui::Button::new(t(Text::RenameByHash)),
+            )
+            .on_hover_text(t(Text::RenameByHashHint))
+            .clicked()
+        {
+            self.open_rename_dialog();
+        }
+
+        if ui
+            .button(t(Text::CompareFiles))
+            .on_hover_text(t(Text::CompareFilesHint))
+            .clicked()
+        {
+            self.open_compare_dialog();
+        }
+
+        if ui
+            .button(t(Text::DirectoryHash))
+            .on_hover_text(t(Text::DirectoryHashHint))
+            .clicked()
+        {
+            self.open_directory_hash_dialog();
+        }
+
+        if self.tray.is_some() && ui.button(t(Text::MinimizeToTray)).clicked() {
+            ctx.send_viewport_cmd(egui::ViewportCommand::Visible(false));
+        }
+    }
+
+    /// 工具栏：设置、配置方案与计算选项
+    fn render_option_controls(&mut self, ui: &mut egui::Ui) {
+        if ui.button(t(Text::Settings)).clicked() {
+            self.show_cache_settings = true;
+        }
+
+        let profile = match &self.active_profile {
+            Some((name, config)) if *config != self.cache_config => format!("{name} *"),
+            Some((name, _)) => name.clone(),
+            None => t(Text::NoProfile).to_string(),
+        };
+        ui.menu_button(format!("{}: {}", t(Text::Profiles), profile), |ui| {
+            self.render_profiles_menu(ui);
+        })
+        .response
+        .on_hover_text(t(Text::ProfilesHint));
+
+        ui.separator();
+
+        if ui
+            .add_enabled(
+                self.cache_config.hash_encoding == HashEncoding::Hex,
+                egui::Checkbox::new(&mut self.uppercase_display, t(Text::Uppercase)),
+            )
+            .changed()
+        {
+            self.cache_config.uppercase_display = self.uppercase_display;
+            self.persist_config();
+        }
+
+        ui.menu_button(t(Text::Columns), |ui| self.render_columns_menu(ui));
+
+        if ui
+            .checkbox(
+                &mut self.cache_config.quick_prescreen,
+                t(Text::QuickPrescreen),
+            )
+            .on_hover_text(t(Text::QuickPrescreenHint))
+            .changed()
+        {
+            self.persist_config();
+        }
+
+        if ui
+            .checkbox(&mut self.cache_config.xxh3_only_mode, t(Text::Xxh3Only))
+            .on_hover_text(t(Text::Xxh3OnlyHint))
+            .changed()
+        {
+            self.persist_config();
+        }
+
+        if ui
+            .checkbox(&mut self.cache_config.sync_mode, t(Text::SyncMode))
+            .on_hover_text(t(Text::SyncModeHint))
+            .changed()
+        {
+            self.persist_config();
+        }
+
+        let previous_mode = self.auto_compute;
+        egui::ComboBox::from_id_salt("auto_compute_mode")
+            .selected_text(self.auto_compute.label())
+            .show_ui(ui, |ui| {
+                for mode in AutoComputeMode::ALL {
+                    ui.selectable_value(&mut self.auto_compute, mode, mode.label());
+                }
+            })
+            .response
+            .on_hover_text(t(Text::AutoComputeHint));
+        if self.auto_compute != previous_mode {
+            self.cache_config.auto_compute = self.auto_compute;
+            self.persist_config();
+            if self.auto_compute == AutoComputeMode::Manual {
+                self.last_file_add_time = None;
+                self.auto_compute_scheduled = false;
+            }
+        }
+    }
+
+    /// 工具栏：开始/停止计算与上次运行的结果
+    fn render_compute_controls(&mut self, ui: &mut egui::Ui) {
+        if self.is_computing {
+            if let Some(start_time) = self.batch_start_time {
+                let elapsed_ms = start_time.elapsed().as_millis() as u64;
+                ui.label(
+                    egui::RichText::new(format!(
+                        "{}: {}",
+                        t(Text::Elapsed),
+                        format_duration(elapsed_ms)
+                    ))
+                    .color(egui::Color32::GRAY),
+                );
+            }
+
+            if ui.button(t(Text::Stop)).clicked() {
+                self.stop_computing();
+            }
+        } else {
+            ui.add_enabled_ui(!self.files.is_empty(), |ui| {
+                if ui.button(t(Text::Start)).clicked() {
+                    self.start_computing();
+                    self.last_file_add_time = None;
+                    self.auto_compute_scheduled = false;
+                }
+            });
+            if !self.selected_rows.is_empty() {
+                let pending = self.selected_pending();
+                if ui
+                    .add_enabled(
+                        pending > 0,
+                        egui::Button::new(format!("{} ({})", t(Text::ComputeSelected), pending)),
+                    )
+                    .on_hover_text(t(Text::ComputeSelectedHint))
+                    .clicked()
+                {
+                    self.compute_selected();
+                }
+            }
+            if !self.hash_changes.is_empty()
+                && ui
+                    .link(
+                        egui::RichText::new(format!(
+                            "{} {}",
+                            t(Text::HashChanges),
+                            format_count(self.hash_changes.len())
+                        ))
+                        .color(egui::Color32::RED),
+                    )
+                    .on_hover_text(t(Text::HashChangesHint))
+                    .clicked()
+            {
+                self.show_hash_changes = true;
+            }
+            if self.batch_total_duration_ms > 0 {
+                let has_breakdown = self
+                    .last_timeline
+                    .as_ref()
+                    .is_some_and(|timeline| timeline.breakdown().is_some());
+                let text = format!(
+                    "{}: {}",
+                    t(Text::LastRun),
+                    format_duration(self.batch_total_duration_ms)
+                );
+                if has_breakdown {
+                    if ui
+                        .link(text)
+                        .on_hover_text(t(Text::TimeBreakdownHint))
+                        .clicked()
+                    {
+                        self.show_time_breakdown = true;
+                    }
+                } else {
+                    ui.label(text);
+                }
+            }
+        }
+    }
+
+    /// 文件表格，按文件夹分组时显示目录树
+    fn render_file_table(&mut self, ctx: &egui::Context) {
+        CentralPanel::default().show(ctx, |ui| {
+            ScrollArea::vertical()
+                .auto_shrink([false; 2])
+                .show(ui, |ui| {
+                    let mut visible_columns = self.cache_config.visible_columns;
+                    if self.cache_config.xxh3_only_mode {
+                        // 快速模式下 XXH3 是唯一的结果，始终显示
+                        visible_columns.set(TableColumn::Hash(HashAlgorithm::Xxh3), true);
+                    }
+                    let columns = visible_columns.visible(self.cache_config.enabled_hashes);
+                    // 状态与文件名列固定在左侧，其余列单独横向滚动
+                    let frozen_count = columns.iter().take_while(|c| c.is_frozen()).count();
+                    let (frozen, scrolling) = columns.split_at(frozen_count);
+                    // 筛选时表格行号与文件下标不同
+                    let rows = self.filtered_rows();
+                    // 定位到某一行时切换回表格
+                    if self.hash_search.scroll_to.is_some() {
+                        self.group_by_folder = false;
+                    }
+                    if self.group_by_folder {
+                        self.show_folder_tree(ui, ctx, &columns, rows.as_deref());
+                        ui.add_space(40.0);
+                        return;
+                    }
+                    self.handle_table_keys(ctx, rows.as_deref());
+                    let position = |idx: usize| match &rows {
+                        Some(rows) => rows.iter().position(|&r| r == idx),
+                        None => Some(idx),
+                    };
+                    let scroll_to_row = match self.hash_search.scroll_to.take() {
+                        Some(idx) => position(idx).map(|row| (row, Some(egui::Align::Center))),
+                        None => self
+                            .focus_scroll
+                            .take()
+                            .and_then(position)
+                            .map(|row| (row, None)),
+                    };
+                    let highlighted: HashSet<usize> = self
+                        .hash_search_matches()
+                        .iter()
+                        .map(|&(idx, _)| idx)
+                        .collect();
+                    let view = TableView {
+                        rows: rows.as_deref(),
+                        highlighted: &highlighted,
+                        scroll_to_row,
+                        scroll_y: self.table_scroll_y,
+                        hovered_row: self.table_hovered_row.take(),
+                        now: unix_now(),
+                    };
+                    ui.horizontal_top(|ui| {
+                        let mut offsets = vec![self.show_file_table(
+                            ui,
+                            ctx,
+                            &TablePart {
+                                id_salt: "frozen_columns",
+                                columns: frozen,
+                                leading: true,
+                                fill: scrolling.is_empty(),
+                            },
+                            &view,
+                        )];
+                        if !scrolling.is_empty() {
+                            let part = TablePart {
+                                id_salt: "scrolling_columns",
+                                columns: scrolling,
+                                leading: false,
+                                fill: true,
+                            };
+                            offsets.push(
+                                ScrollArea::horizontal()
+                                    .id_salt("scrolling_columns")
+                                    .auto_shrink([false; 2])
+                                    .show(ui, |ui| self.show_file_table(ui, ctx, &part, &view))
+                                    .inner,
+                            );
+                        }
+                        // 两侧的垂直滚动保持一致：采用本帧滚动过的一侧的偏移
+                        if let Some(&offset) =
+                            offsets.iter().find(|&&y| (y - view.scroll_y).abs() > 0.5)
+                        {
+                            self.table_scroll_y = offset;
+                            ctx.request_repaint();
+                        }
+                    });
+                    ui.add_space(40.0);
+                });
+        });
+    }
+
+    /// 底部状态栏：进度、缓存命中与撤销清空
+    fn render_status_bar(&mut self, ctx: &egui::Context) {
+        TopBottomPanel::bottom("status").show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.label(t(Text::GlobalProgress));
+                ui.add(egui::ProgressBar::new(self.global_progress as f32).show_percentage());
+                ui.separator();
+                match &self.progress_tracker {
+                    Some(tracker) if tracker.is_count_based() => {
+                        ui.label(format!(
+                            "{}: {} / {}",
+                            t(Text::FilesDone),
+                            tracker.completed_files(),
+                            tracker.total_files()
+                        ));
+                    }
+                    _ => {
+                        ui.label(format!(
+                            "{}: {} / {}",
+                            t(Text::ProcessedTotal),
+                            format_size(self.processed_size, self.cache_config.size_unit),
+                            format_size(self.total_size, self.cache_config.size_unit)
+                        ));
+                    }
+                }
+                let sources = self.sources;
+                if sources.total() > 0 {
+                    ui.separator();
+                    ui.label(format!(
+                        "{} {} / {} {}",
+                        t(Text::CacheHits),
+                        format_count(sources.cached),
+                        t(Text::FreshlyComputed),
+                        format_count(sources.computed)
+                    ))
+                    .on_hover_text(t(Text::SourceSummaryHint));
+                }
+                if self.is_computing {
+                    ui.separator();
+                    ui.label(format!(
+                        "{}: {}",
+                        t(Text::CacheValidation),
+                        self.batch_validation.label()
+                    ))
+                    .on_hover_text(t(Text::CacheValidationHint));
+                    if let Some(root) = &self.cache_contention {
+                        ui.separator();
+                        ui.colored_label(ui.visuals().warn_fg_color, t(Text::CacheContention))
+                            .on_hover_text(tf(
+                                Text::CacheContentionHint,
+                                &[&root.display(), &CONTENDED_SAVE_BATCH],
+                            ));
+                    }
+                }
+
+                if let Some((msg, _)) = &self.status_toast {
+                    ui.separator();
+                    ui.label(egui::RichText::new(msg).color(egui::Color32::GREEN));
+                }
+
+                if let Some(snapshot) = &self.cleared_queue {
+                    ui.separator();
+                    ui.label(format!(
+                        "{}: {}",
+                        t(Text::FilesCleared),
+                        format_count(snapshot.files.len())
+                    ));
+                    if ui
+                        .add_enabled(!self.is_computing, egui::Button::new(t(Text::Undo)))
+                        .on_disabled_hover_text(t(Text::UndoWhileComputing))
+                        .clicked()
+                    {
+                        self.undo_clear_files();
+                    }
+                }
+            });
+        });
+    }
+
+    /// 按时收起复制提示、状态提示与撤销按钮
+    fn expire_transient_state(&mut self, ctx: &egui::Context) {
+        if let Some((_, instant)) = &self.clipboard_toast {
+            let duration = std::time::Duration::from_millis(self.cache_config.copy_toast_ms);
+            match duration.checked_sub(instant.elapsed()) {
+                // 无输入时也要按时收起提示
+                Some(remaining) if !remaining.is_zero() => ctx.request_repaint_after(remaining),
+                _ => self.clipboard_toast = None,
+            }
+        }
+
+        if let Some((_, instant)) = &self.status_toast {
+            if instant.elapsed().as_secs() >= 2 {
+                self.status_toast = None;
+            }
+        }
+
+        if let Some(snapshot) = &self.cleared_queue {
+            if snapshot.cleared_at.elapsed().as_secs() >= UNDO_CLEAR_SECS {
+                self.cleared_queue = None;
+            } else {
+                // 无输入时也要按时收起撤销按钮
+                ctx.request_repaint_after(std::time::Duration::from_secs(1));
+            }
+        }
+
+        for file in &mut self.files {
+            if let Some((_, instant)) = &file.clipboard_verify {
+                if instant.elapsed().as_secs() >= 3 {
+                    file.clipboard_verify = None;
+                    self.rows_generation += 1;
+                }
+            }
+        }
+    }
+
+    /// 顶部工具栏
+    fn render_toolbar(&mut self, ctx: &egui::Context) {
+        TopBottomPanel::top("toolbar").show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                self.render_queue_actions(ui);
+                self.render_export_actions(ui, ctx);
+                self.render_analysis_actions(ui);
+                self.render_tool_actions(ui, ctx);
+
+                ui.separator();
+                self.render_option_controls(ui);
+
+                ui.separator();
+                self.render_compute_controls(ui);
+
+                ui.separator();
+                self.render_hash_search_box(ui);
+
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    ui.label(format!("{}: {}", t(Text::Files), self.files.len()));
+                });
+            });
+        });
+    }
+}
+
+impl eframe::App for TurboHashApp {
+    fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        self.flush_on_exit();
+    }
+
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        self.process_messages(ctx);
+
+        self.expire_transient_state(ctx);
+
+        self.handle_dropped_files(ctx);
+        self.handle_paste_shortcut(ctx);
+
+        self.check_and_execute_auto_compute();
+
+        if self.is_computing {
+            self.set_tray_tooltip(tf(
+                Text::TrayComputing,
+                &[&format!("{:.0}", self.global_progress * 100.0)],
+            ));
+        }
+
+        if self.is_computing || !self.ui_rx.is_empty() {
+            ctx.request_repaint();
+        } else if self.cleanup.is_some() {
+            // 后台清理期间定时刷新进度
+            ctx.request_repaint_after(std::time::Duration::from_millis(100));
+        }
+
+        self.render_toolbar(ctx);
+        self.show_update_banner(ctx);
+
+        if self.show_type_summary {
+            self.render_type_summary(ctx);
+        }
+
+        self.render_file_table(ctx);
+        self.render_status_bar(ctx);
+
+        if self.show_cache_settings {
+            self.render_settings_window(ctx);
+        }
+
+        if self.collision_report.is_some() {
+            self.render_collision_window(ctx);
+        }
+
+        if self.name_conflicts.is_some() {
+            self.render_name_conflict_window(ctx);
+        }
+
+        if self.directory_hash.is_some() {
+            self.render_directory_hash_window(ctx);
+        }
+
+        if self.comparison.is_some() {
+            self.render_comparison_window(ctx);
+        }
+
+        if self.rehash.is_some() {
+            self.render_rehash_window(ctx);
+        }
+
+        if self.chunk_hash.is_some() {
+            self.render_chunk_hash_window(ctx);
+        }
+
+        if self.show_time_breakdown {
+            self.render_time_breakdown_window(ctx);
+        }
+
+        if self.show_hash_changes {
+            self.render_hash_changes_window(ctx);
+        }
+
+        if self.audit.is_some() {
+            self.render_audit_window(ctx);
+        }
+
+        if self.benchmark.is_some() {
+            self.render_benchmark_window(ctx);
+        }
+
+        if self.rename.is_some() {
+            self.render_rename_window(ctx);
+        }
+
+        if self.remote_verify.is_some() {
+            self.render_remote_verify_window(ctx);
+        }
+
+        if self.manifest.is_some() {
+            self.render_manifest_window(ctx);
+        }
+
+        if self.hash_list.open {
+            self.render_hash_list_window(ctx);
+        }
+
+        if self.hash_search.list_open && self.hash_search_matches().len() > 1 {
+            self.render_hash_search_window(ctx);
+        }
+
+        if self.export_dialog.open {
+            self.render_export_window(ctx);
+        }
+
+        if self.clear_confirm_open {
+            self.render_clear_confirm(ctx);
+        }
+
+        if self.resume_batch.is_some() {
+            self.render_resume_prompt(ctx);
+        }
+
+        self.render_drop_hint(ctx);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    /// 使用临时缓存与给定的 Worker 通道构建应用，不阻止休眠
+    fn test_app(
+        cache_dir: &Path,
+        worker_tx: Sender<WorkerMessage>,
+        ui_rx: Receiver<UiMessage>,
+    ) -> TurboHashApp {
+        let config = CacheConfig {
+            prevent_sleep: false,
+            ..CacheConfig::default()
+        };
+        let cache = HashCache::new(&cache_dir.join("test.db"), config.clone()).unwrap();
+        TurboHashApp::with_worker(
+            Arc::new(Mutex::new(cache)),
+            config,
+            worker_tx,
+            ui_rx,
+            None,
+            true,
+        )
+    }
+
+    fn discovered(paths: &[&Path], size: u64) -> UiMessage {
+        UiMessage::FilesDiscovered(
+            paths
+                .iter()
+                .map(|path| DiscoveredFile {
+                    path: path.to_path_buf(),
+                    size,
+                    oversized: false,
+                    expected: None,
+                })
+                .collect(),
+        )
+    }
+
+    fn completed(batch_id: u64, path: &Path, file_size: u64) -> UiMessage {
+        UiMessage::FileCompleted {
+            batch_id,
+            path: path.to_path_buf(),
+            crc32: String::new(),
+            md5: String::new(),
+            sha1: String::new(),
+            sha256: String::new(),
+            crc64: String::new(),
+            adler32: String::new(),
+            xxhash3: String::new(),
+            duration_ms: 0,
+            modified_time: 0,
+            file_size,
+            from_cache: false,
+            unchanged: false,
+            computed_at: 0,
+        }
+    }
+
+    fn progress(batch_id: u64, path: &Path, processed: u64) -> UiMessage {
+        UiMessage::Progress {
+            batch_id,
+            path: path.to_path_buf(),
+            processed,
+            total: 100,
+        }
+    }
 
-        let mut open = self.show_cache_settings;
-        let mut config_changed = false;
+    /// 最近一次发给 Worker 的计算请求
+    fn last_compute(worker_rx: &Receiver<WorkerMessage>) -> Option<(u64, Vec<PathBuf>)> {
+        worker_rx.try_iter().fold(None, |last, msg| match msg {
+            WorkerMessage::Compute {
+                batch_id, files, ..
+            } => Some((batch_id, files.into_iter().map(|(path, _)| path).collect())),
+            _ => last,
+        })
+    }
 
-        egui::Window::new("缓存设置")
-            .open(&mut open)
-            .collapsible(false)
-            .resizable(false)
-            .default_width(420.0)
-            .pivot(egui::Align2::CENTER_CENTER)
-            .default_pos(ctx.viewport_rect().center())
-            .order(egui::Order::Foreground) // 位于遮罩之上
-            .show(ctx, |ui| {
-                if let Ok(cache_guard) = self.cache.lock() {
-                    ui.add_space(8.0);
+    fn status_of(app: &TurboHashApp, path: &Path) -> FileStatus {
+        app.files[app.file_index[path]].status.clone()
+    }
 
-                    // --- 1. 性能模式 (Segmented Control) ---
-                    ui.horizontal(|ui| {
-                        ui.label(egui::RichText::new("🚀 性能模式").strong());
-                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                            ui.label(egui::RichText::new("调整 I/O 策略").weak().small());
-                        });
-                    });
-                    ui.add_space(4.0);
+    #[test]
+    fn test_late_messages_after_cancel_are_stale() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let old: Vec<PathBuf> = (0u8..8)
+            .map(|i| {
+                let path = temp.path().join(format!("old{i}.bin"));
+                std::fs::write(&path, vec![i; 256 * 1024]).unwrap();
+                path
+            })
+            .collect();
+        let new = temp.path().join("new.bin");
+        std::fs::write(&new, vec![9u8; 4096]).unwrap();
 
-                    let current_preset = if self.cache_config.buffer_size == 64 * 1024
-                        && self.cache_config.mmap_chunk_size == 1024 * 1024
-                    {
-                        0 // 节能
-                    } else if self.cache_config.buffer_size == 256 * 1024
-                        && self.cache_config.mmap_chunk_size == 4 * 1024 * 1024
-                    {
-                        1 // 均衡
-                    } else if self.cache_config.buffer_size == 1024 * 1024
-                        && self.cache_config.mmap_chunk_size == 16 * 1024 * 1024
-                    {
-                        2 // 高性能
-                    } else {
-                        3 // 自定义
-                    };
+        let pool = HashCache::new(&temp.path().join("worker.db"), CacheConfig::default()).unwrap();
+        let (_worker, worker_tx, ui_rx) = WorkerThread::spawn(Arc::new(Mutex::new(pool)));
+        let mut app = test_app(temp.path(), worker_tx, ui_rx);
+        let ctx = egui::Context::default();
+        let pump = |app: &mut TurboHashApp, done: &dyn Fn(&TurboHashApp) -> bool| {
+            let deadline = Instant::now() + Duration::from_secs(10);
+            while !done(app) {
+                assert!(Instant::now() < deadline, "timed out waiting for worker");
+                app.process_messages(&ctx);
+                std::thread::sleep(Duration::from_millis(1));
+            }
+        };
 
-                    let mut selected_preset = current_preset;
-                    ui.horizontal(|ui| {
-                        ui.style_mut().spacing.item_spacing.x = 0.0;
-                        // 简单的分段按钮样式
-                        if ui
-                            .selectable_label(selected_preset == 0, "🍃 节能")
-                            .clicked()
-                        {
-                            selected_preset = 0;
-                            config_changed = true;
-                        }
-                        if ui
-                            .selectable_label(selected_preset == 1, "⚖️ 均衡")
-                            .clicked()
-                        {
-                            selected_preset = 1;
-                            config_changed = true;
-                        }
-                        if ui
-                            .selectable_label(selected_preset == 2, "⚡ 高性能")
-                            .clicked()
-                        {
-                            selected_preset = 2;
-                            config_changed = true;
-                        }
-                    });
+        // 与 UI 相同的流程：开始批次，立即停止，再加入新文件重新开始
+        app.add_files(old.clone());
+        pump(&mut app, &|app| app.files.len() == old.len());
+        app.start_computing();
+        app.stop_computing();
+        app.add_files(vec![new.clone()]);
+        pump(&mut app, &|app| app.files.len() == old.len() + 1);
+        app.start_computing();
+        pump(&mut app, &|app| !app.is_computing);
+        // 继续处理旧批次迟到的消息
+        let quiet = Instant::now() + Duration::from_millis(200);
+        pump(&mut app, &|_| Instant::now() >= quiet);
 
-                    if config_changed && selected_preset != current_preset {
-                        match selected_preset {
-                            0 => {
-                                self.cache_config.buffer_size = 64 * 1024;
-                                self.cache_config.mmap_chunk_size = 1024 * 1024;
-                            }
-                            1 => {
-                                self.cache_config.buffer_size = 256 * 1024;
-                                self.cache_config.mmap_chunk_size = 4 * 1024 * 1024;
-                            }
-                            2 => {
-                                self.cache_config.buffer_size = 1024 * 1024;
-                                self.cache_config.mmap_chunk_size = 16 * 1024 * 1024;
-                            }
-                            _ => {}
-                        }
-                    }
+        // 停止时计算中的文件为已取消，其余文件由新批次计算完成；旧批次迟到的结果全部被
+        // 丢弃，没有文件停留在计算中，已处理大小与进度只反映新批次
+        assert_eq!(status_of(&app, &new), FileStatus::Completed);
+        assert!(
+            app.files
+                .iter()
+                .all(|f| matches!(f.status, FileStatus::Completed | FileStatus::Cancelled))
+        );
+        let computed: u64 = app
+            .files
+            .iter()
+            .filter(|f| f.status == FileStatus::Completed)
+            .map(|f| f.size)
+            .sum();
+        assert_eq!(app.processed_size, computed);
+        assert_eq!(app.global_progress, 1.0);
+    }
 
-                    ui.add_space(16.0);
-                    ui.separator();
-                    ui.add_space(16.0);
+    #[test]
+    fn test_interleaved_batches_only_apply_current() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let (worker_tx, worker_rx) = crossbeam_channel::unbounded();
+        let (ui_tx, ui_rx) = crossbeam_channel::unbounded();
+        let mut app = test_app(temp.path(), worker_tx, ui_rx);
+        let ctx = egui::Context::default();
+        let old = PathBuf::from("/old.bin");
+        let new = PathBuf::from("/new.bin");
 
-                    // --- 2. 详细设置 (Grid Layout) ---
-                    egui::Grid::new("settings_grid")
-                        .num_columns(2)
-                        .spacing([24.0, 12.0])
-                        .striped(false)
-                        .show(ui, |ui| {
-                            // Row 1: Buffer Size
-                            ui.label("读取缓冲");
-                            egui::ComboBox::from_id_salt("buf_size")
-                                .selected_text(humansize::format_size(
-                                    self.cache_config.buffer_size,
-                                    humansize::BINARY,
-                                ))
-                                .show_ui(ui, |ui| {
-                                    if ui
-                                        .selectable_value(
-                                            &mut self.cache_config.buffer_size,
-                                            64 * 1024,
-                                            "64 KB",
-                                        )
-                                        .changed()
-                                    {
-                                        config_changed = true;
-                                    }
-                                    if ui
-                                        .selectable_value(
-                                            &mut self.cache_config.buffer_size,
-                                            256 * 1024,
-                                            "256 KB",
-                                        )
-                                        .changed()
-                                    {
-                                        config_changed = true;
-                                    }
-                                    if ui
-                                        .selectable_value(
-                                            &mut self.cache_config.buffer_size,
-                                            1024 * 1024,
-                                            "1 MB",
-                                        )
-                                        .changed()
-                                    {
-                                        config_changed = true;
-                                    }
-                                    if ui
-                                        .selectable_value(
-                                            &mut self.cache_config.buffer_size,
-                                            2 * 1024 * 1024,
-                                            "2 MB",
-                                        )
-                                        .changed()
-                                    {
-                                        config_changed = true;
-                                    }
-                                    if ui
-                                        .selectable_value(
-                                            &mut self.cache_config.buffer_size,
-                                            4 * 1024 * 1024,
-                                            "4 MB",
-                                        )
-                                        .changed()
-                                    {
-                                        config_changed = true;
-                                    }
-                                });
-                            ui.end_row();
+        ui_tx.send(discovered(&[&old, &new], 100)).unwrap();
+        app.process_messages(&ctx);
+        app.start_computing();
+        let (first, _) = last_compute(&worker_rx).unwrap();
+        ui_tx
+            .send(UiMessage::FileStarted {
+                batch_id: first,
+                path: old.clone(),
+            })
+            .unwrap();
+        app.process_messages(&ctx);
+        assert_eq!(status_of(&app, &old), FileStatus::Computing);
 
-                            // Row 2: MMAP Chunk
-                            ui.label("内存映射");
-                            egui::ComboBox::from_id_salt("mmap_size")
-                                .selected_text(humansize::format_size(
-                                    self.cache_config.mmap_chunk_size,
-                                    humansize::BINARY,
-                                ))
-                                .show_ui(ui, |ui| {
-                                    if ui
-                                        .selectable_value(
-                                            &mut self.cache_config.mmap_chunk_size,
-                                            1024 * 1024,
-                                            "1 MB",
-                                        )
-                                        .changed()
-                                    {
-                                        config_changed = true;
-                                    }
-                                    if ui
-                                        .selectable_value(
-                                            &mut self.cache_config.mmap_chunk_size,
-                                            4 * 1024 * 1024,
-                                            "4 MB",
-                                        )
-                                        .changed()
-                                    {
-                                        config_changed = true;
-                                    }
-                                    if ui
-                                        .selectable_value(
-                                            &mut self.cache_config.mmap_chunk_size,
-                                            16 * 1024 * 1024,
-                                            "16 MB",
-                                        )
-                                        .changed()
-                                    {
-                                        config_changed = true;
-                                    }
-                                    if ui
-                                        .selectable_value(
-                                            &mut self.cache_config.mmap_chunk_size,
-                                            64 * 1024 * 1024,
-                                            "64 MB",
-                                        )
-                                        .changed()
-                                    {
-                                        config_changed = true;
-                                    }
-                                });
-                            ui.end_row();
+        // 停止后立即重新开始：只有仍在等待的文件进入新批次
+        app.stop_computing();
+        assert_eq!(status_of(&app, &old), FileStatus::Cancelled);
+        app.start_computing();
+        let (current, files) = last_compute(&worker_rx).unwrap();
+        assert_eq!(files, std::slice::from_ref(&new));
 
-                            // Row 3: Min File Size
-                            ui.label("缓存阈值");
-                            egui::ComboBox::from_id_salt("min_file_size")
-                                .selected_text(humansize::format_size(
-                                    self.cache_config.min_file_size,
-                                    humansize::BINARY,
-                                ))
-                                .show_ui(ui, |ui| {
-                                    if ui
-                                        .selectable_value(
-                                            &mut self.cache_config.min_file_size,
-                                            1024 * 1024,
-                                            "1 MB",
-                                        )
-                                        .changed()
-                                    {
-                                        config_changed = true;
-                                    }
-                                    if ui
-                                        .selectable_value(
-                                            &mut self.cache_config.min_file_size,
-                                            10 * 1024 * 1024,
-                                            "10 MB",
-                                        )
-                                        .changed()
-                                    {
-                                        config_changed = true;
-                                    }
-                                    if ui
-                                        .selectable_value(
-                                            &mut self.cache_config.min_file_size,
-                                            100 * 1024 * 1024,
-                                            "100 MB",
-                                        )
-                                        .changed()
-                                    {
-                                        config_changed = true;
-                                    }
-                                    if ui
-                                        .selectable_value(
-                                            &mut self.cache_config.min_file_size,
-                                            1024 * 1024 * 1024,
-                                            "1 GB",
-                                        )
-                                        .changed()
-                                    {
-                                        config_changed = true;
-                                    }
-                                });
-                            ui.end_row();
+        // 旧批次的消息与新批次的消息交错到达
+        for msg in [
+            UiMessage::FileStarted {
+                batch_id: current,
+                path: new.clone(),
+            },
+            progress(first, &old, 80),
+            progress(current, &new, 40),
+            completed(first, &old, 100),
+            UiMessage::FileFailed {
+                batch_id: first,
+                path: new.clone(),
+                error: String::new(),
+            },
+            UiMessage::AllCompleted { batch_id: first },
+        ] {
+            ui_tx.send(msg).unwrap();
+        }
+        app.process_messages(&ctx);
+        assert!(app.is_computing);
+        assert_eq!(status_of(&app, &old), FileStatus::Cancelled);
+        assert_eq!(status_of(&app, &new), FileStatus::Computing);
+        assert!(app.global_progress <= 0.4);
+        assert_eq!(app.processed_size, 0);
 
-                            // Row 4: Retention
-                            ui.label("保留期限");
-                            ui.horizontal(|ui| {
-                                if ui
-                                    .add(
-                                        egui::DragValue::new(&mut self.cache_config.retention_days)
-                                            .speed(1)
-                                            .suffix(" 天"),
-                                    )
-                                    .changed()
-                                {
-                                    config_changed = true;
-                                }
-                                if self.cache_config.retention_days == 0 {
-                                    ui.label(
-                                        egui::RichText::new("(永久)")
-                                            .color(egui::Color32::GOLD)
-                                            .small(),
-                                    );
-                                }
-                            });
-                            ui.end_row();
-                        });
+        for msg in [
+            progress(current, &new, 100),
+            completed(current, &new, 100),
+            UiMessage::AllCompleted { batch_id: current },
+        ] {
+            ui_tx.send(msg).unwrap();
+        }
+        app.process_messages(&ctx);
+        assert!(!app.is_computing);
+        assert_eq!(status_of(&app, &old), FileStatus::Cancelled);
+        assert_eq!(status_of(&app, &new), FileStatus::Completed);
+        assert_eq!(app.processed_size, 100);
+        assert_eq!(app.global_progress, 1.0);
+    }
 
-                    ui.add_space(16.0);
-                    ui.separator();
-                    ui.add_space(16.0);
+    #[test]
+    fn test_completion_notice_with_tray() {
+        let config = CacheConfig {
+            notify_on_complete: false,
+            notify_threshold_secs: 60,
+            ..CacheConfig::default()
+        };
+        // 托盘模式下每个批次结束都通知，窗口聚焦时除外
+        assert!(should_notify_completion(&config, true, 500, false));
+        assert!(!should_notify_completion(&config, true, 500, true));
+        assert!(!should_notify_completion(&config, false, 120_000, false));
 
-                    // --- 3. 维护操作 ---
-                    ui.horizontal(|ui| {
-                        if ui.button("🧹 清理过期").clicked() {
-                            match cache_guard.cleanup_expired() {
-                                Ok(count) => {
-                                    self.cache_operation_message =
-                                        Some(format!("已清理 {} 条", count))
-                                }
-                                Err(e) => {
-                                    self.cache_operation_message = Some(format!("失败: {}", e))
-                                }
-                            }
-                        }
-                        if ui.button("🗑️ 清空所有").clicked() {
-                            match cache_guard.clear_all() {
-                                Ok(count) => {
-                                    self.cache_operation_message =
-                                        Some(format!("已清空 {} 条", count))
-                                }
-                                Err(e) => {
-                                    self.cache_operation_message = Some(format!("失败: {}", e))
-                                }
-                            }
-                        }
+        let config = CacheConfig {
+            notify_on_complete: true,
+            ..config
+        };
+        assert!(!should_notify_completion(&config, false, 500, false));
+        assert!(should_notify_completion(&config, false, 60_000, false));
+    }
 
-                        if let Some(msg) = &self.cache_operation_message {
-                            ui.with_layout(
-                                egui::Layout::right_to_left(egui::Align::Center),
-                                |ui| {
-                                    ui.label(
-                                        egui::RichText::new(msg)
-                                            .color(egui::Color32::LIGHT_BLUE)
-                                            .small(),
-                                    );
-                                },
-                            );
-                        }
-                    });
+    #[test]
+    fn test_dropped_roots_cleared_after_scan() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let (worker_tx, _worker_rx) = crossbeam_channel::unbounded();
+        let (ui_tx, ui_rx) = crossbeam_channel::unbounded();
+        let mut app = test_app(temp.path(), worker_tx, ui_rx);
+        app.auto_compute = AutoComputeMode::AutoOnDrop;
+        let ctx = egui::Context::default();
+        let root = PathBuf::from("/drop");
 
-                    ui.add_space(8.0);
+        // 与 handle_dropped_files 一致
+        app.dropped_roots.push(root.clone());
+        app.add_files(vec![root.clone()]);
+        ui_tx.send(discovered(&[&root.join("a.bin")], 100)).unwrap();
+        ui_tx.send(UiMessage::ScanFinished).unwrap();
+        app.process_messages(&ctx);
+        assert!(app.auto_compute_scheduled);
+        assert!(app.dropped_roots.is_empty());
 
-                    // 立即保存逻辑
-                    if config_changed {
-                        if let Err(e) = cache_guard.save_cache_config(&self.cache_config) {
-                            eprintln!("保存配置失败: {}", e);
-                        }
-                    }
-                }
-            });
-        self.show_cache_settings = open;
+        // 之后粘贴加入的同一目录下的文件不再视为拖放加入
+        app.auto_compute_scheduled = false;
+        app.add_files(vec![root.clone()]);
+        ui_tx.send(discovered(&[&root.join("b.bin")], 100)).unwrap();
+        ui_tx.send(UiMessage::ScanFinished).unwrap();
+        app.process_messages(&ctx);
+        assert!(!app.auto_compute_scheduled);
+        assert_eq!(app.files.len(), 2);
     }
-}
 
-impl eframe::App for TurboHashApp {
-    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-        self.process_messages(ctx);
+    #[test]
+    fn test_undo_clear_keeps_deleted_files_out_of_total_size() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let (worker_tx, _worker_rx) = crossbeam_channel::unbounded();
+        let (ui_tx, ui_rx) = crossbeam_channel::unbounded();
+        let mut app = test_app(temp.path(), worker_tx, ui_rx);
+        let ctx = egui::Context::default();
+        let kept = PathBuf::from("/kept.bin");
+        let deleted = PathBuf::from("/deleted.bin");
 
-        if let Some((_, instant)) = &self.clipboard_toast {
-            if instant.elapsed().as_secs() >= 2 {
-                self.clipboard_toast = None;
-            }
-        }
+        ui_tx.send(discovered(&[&kept, &deleted], 100)).unwrap();
+        ui_tx
+            .send(UiMessage::FileMissing {
+                batch_id: app.batch_id,
+                path: deleted,
+            })
+            .unwrap();
+        app.process_messages(&ctx);
+        assert_eq!(app.total_size, 100);
 
-        let dropped_files = ctx.input(|i| i.raw.dropped_files.clone());
-        if !dropped_files.is_empty() {
-            let mut paths = Vec::new();
-            for file in dropped_files {
-                if let Some(path) = file.path {
-                    paths.push(path);
-                }
-            }
-            if !paths.is_empty() {
-                self.add_files(paths);
-            }
+        app.clear_files_with_undo();
+        assert_eq!(app.total_size, 0);
+        app.undo_clear_files();
+        assert_eq!(app.files.len(), 2);
+        assert_eq!(app.total_size, 100);
+    }
+
+    #[test]
+    fn test_auto_compute_waits_for_running_batch() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let (worker_tx, worker_rx) = crossbeam_channel::unbounded();
+        let (ui_tx, ui_rx) = crossbeam_channel::unbounded();
+        let mut app = test_app(temp.path(), worker_tx, ui_rx);
+        app.debounce_duration_ms = 0;
+        let ctx = egui::Context::default();
+        let first = PathBuf::from("/first.bin");
+        let second = PathBuf::from("/second.bin");
+
+        ui_tx.send(discovered(&[&first], 100)).unwrap();
+        app.process_messages(&ctx);
+        app.check_and_execute_auto_compute();
+        let (batch_id, files) = last_compute(&worker_rx).unwrap();
+        assert_eq!(files, std::slice::from_ref(&first));
+
+        // 计算期间加入的文件不开始新批次，否则正在计算的文件会一直停留在计算中
+        ui_tx.send(discovered(&[&second], 100)).unwrap();
+        app.process_messages(&ctx);
+        app.check_and_execute_auto_compute();
+        assert!(last_compute(&worker_rx).is_none());
+        assert_eq!(app.batch_id, batch_id);
+
+        ui_tx.send(completed(batch_id, &first, 100)).unwrap();
+        ui_tx.send(UiMessage::AllCompleted { batch_id }).unwrap();
+        app.process_messages(&ctx);
+        assert_eq!(status_of(&app, &first), FileStatus::Completed);
+
+        // 当前批次结束后再计算
+        app.check_and_execute_auto_compute();
+        let (next, files) = last_compute(&worker_rx).unwrap();
+        assert!(next > batch_id);
+        assert_eq!(files, [second]);
+    }
+
+    #[test]
+    fn test_missing_recent_paths_are_removed() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let (worker_tx, _worker_rx) = crossbeam_channel::unbounded();
+        let (ui_tx, ui_rx) = crossbeam_channel::unbounded();
+        let mut app = test_app(temp.path(), worker_tx, ui_rx);
+        let ctx = egui::Context::default();
+        let kept = temp.path().to_path_buf();
+        let gone = temp.path().join("gone");
+        app.recent_paths = vec![gone.clone(), kept.clone()];
+
+        ui_tx
+            .send(UiMessage::RecentPathsMissing(vec![gone]))
+            .unwrap();
+        app.process_messages(&ctx);
+        assert_eq!(app.recent_paths, std::slice::from_ref(&kept));
+        // 已保存，重新打开后不再出现
+        let saved = app.cache.lock().unwrap().load_recent_paths().unwrap();
+        assert_eq!(saved, [kept]);
+    }
+
+    #[test]
+    fn test_source_counts_follow_messages() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let (worker_tx, _worker_rx) = crossbeam_channel::unbounded();
+        let (ui_tx, ui_rx) = crossbeam_channel::unbounded();
+        let mut app = test_app(temp.path(), worker_tx, ui_rx);
+        let ctx = egui::Context::default();
+        let paths: Vec<PathBuf> = (0..3).map(|i| PathBuf::from(format!("/f{i}"))).collect();
+        let batch_id = app.batch_id;
+
+        ui_tx
+            .send(discovered(
+                &paths.iter().map(PathBuf::as_path).collect::<Vec<_>>(),
+                10,
+            ))
+            .unwrap();
+        app.process_messages(&ctx);
+        let generation = app.rows_generation;
+        for path in &paths {
+            ui_tx.send(completed(batch_id, path, 10)).unwrap();
         }
+        ui_tx
+            .send(UiMessage::FileStarted {
+                batch_id,
+                path: paths[0].clone(),
+            })
+            .unwrap();
+        ui_tx
+            .send(UiMessage::FileFailed {
+                batch_id,
+                path: paths[1].clone(),
+                error: String::new(),
+            })
+            .unwrap();
+        app.process_messages(&ctx);
 
-        self.check_and_execute_auto_compute();
+        // 增量统计与重新统计一致，分组缓存随之失效
+        assert_eq!(app.sources, summarize_sources(&app.files));
+        assert_eq!(app.sources.total(), 1);
+        assert!(app.rows_generation > generation);
+    }
 
-        if self.is_computing || !self.ui_rx.is_empty() {
-            ctx.request_repaint();
+    #[test]
+    fn test_problem_rows_include_changed_and_unverifiable() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let (worker_tx, _worker_rx) = crossbeam_channel::unbounded();
+        let (ui_tx, ui_rx) = crossbeam_channel::unbounded();
+        let mut app = test_app(temp.path(), worker_tx, ui_rx);
+        let ctx = egui::Context::default();
+        let paths: Vec<PathBuf> = (0..4).map(|i| PathBuf::from(format!("/f{i}"))).collect();
+        let batch_id = app.batch_id;
+
+        ui_tx
+            .send(discovered(
+                &paths.iter().map(PathBuf::as_path).collect::<Vec<_>>(),
+                10,
+            ))
+            .unwrap();
+        for path in &paths {
+            ui_tx.send(completed(batch_id, path, 10)).unwrap();
         }
+        app.process_messages(&ctx);
+        assert!(app.problem_rows().is_empty());
 
-        TopBottomPanel::top("toolbar").show(ctx, |ui| {
-            ui.horizontal(|ui| {
-                ui.heading("TurboHash");
-                ui.separator();
+        // 校验文件使用的 SHA256 没有计算，无法校验
+        app.files[0].expected = Some(ExpectedHash {
+            algorithm: HashAlgorithm::Sha256,
+            hex: "0".repeat(64),
+            sidecar: ChecksumSource::File(PathBuf::from("/f0.sha256")),
+        });
+        // 文件列表未变化时复用缓存
+        assert!(app.problem_rows().is_empty());
+        app.rows_generation += 1;
+        assert_eq!(app.problem_rows(), [0]);
 
-                if ui.button("添加文件").clicked() {
-                    self.open_file_dialog();
-                }
+        ui_tx
+            .send(UiMessage::HashChanged {
+                batch_id,
+                path: paths[1].clone(),
+                old: FileHashes::default(),
+                new: FileHashes::default(),
+                metadata_changed: false,
+            })
+            .unwrap();
+        app.process_messages(&ctx);
+        app.manifest = Some(ManifestView::new(
+            HashMap::new(),
+            ManifestReport {
+                manifest: PathBuf::from("/SHA256SUMS"),
+                untracked: vec![paths[2].clone()],
+                missing: Vec::new(),
+            },
+        ));
+        app.rows_generation += 1;
+        assert_eq!(app.problem_rows(), [0, 1, 2]);
+    }
 
-                if ui.button("添加文件夹").clicked() {
-                    self.open_folder_dialog();
-                }
+    #[test]
+    fn test_hash_anyway_starts_batch_in_manual_mode() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let (worker_tx, worker_rx) = crossbeam_channel::unbounded();
+        let (ui_tx, ui_rx) = crossbeam_channel::unbounded();
+        let mut app = test_app(temp.path(), worker_tx, ui_rx);
+        app.auto_compute = AutoComputeMode::Manual;
+        let ctx = egui::Context::default();
+        let big = PathBuf::from("/big.img");
+        let other = PathBuf::from("/other.bin");
 
-                let clear_button_enabled = !self.is_computing;
-                if ui
-                    .add_enabled(clear_button_enabled, egui::Button::new("清空队列"))
-                    .clicked()
-                {
-                    self.clear_files();
-                }
+        ui_tx
+            .send(UiMessage::FilesDiscovered(vec![
+                DiscoveredFile {
+                    path: big.clone(),
+                    size: 4096,
+                    oversized: true,
+                    expected: None,
+                },
+                DiscoveredFile {
+                    path: other.clone(),
+                    size: 100,
+                    oversized: false,
+                    expected: None,
+                },
+            ]))
+            .unwrap();
+        app.process_messages(&ctx);
+        app.check_and_execute_auto_compute();
+        assert!(last_compute(&worker_rx).is_none());
+        assert_eq!(status_of(&app, &big), FileStatus::Oversized);
 
-                ui.separator();
+        // 只计算这一行，其他等待中的文件仍等手动开始
+        app.hash_anyway(app.file_index[&big]);
+        let (batch_id, files) = last_compute(&worker_rx).unwrap();
+        assert_eq!(files, std::slice::from_ref(&big));
+        assert!(app.is_computing);
+        assert_eq!(status_of(&app, &other), FileStatus::Pending);
 
-                if ui.button("缓存设置").clicked() {
-                    self.show_cache_settings = true;
-                }
+        ui_tx.send(completed(batch_id, &big, 4096)).unwrap();
+        ui_tx.send(UiMessage::AllCompleted { batch_id }).unwrap();
+        app.process_messages(&ctx);
+        assert_eq!(status_of(&app, &big), FileStatus::Completed);
+        app.check_and_execute_auto_compute();
+        assert!(last_compute(&worker_rx).is_none());
+    }
 
-                ui.separator();
+    #[test]
+    fn test_chunk_hash_restart_cancels_previous_request() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let (worker_tx, worker_rx) = crossbeam_channel::unbounded();
+        let (ui_tx, ui_rx) = crossbeam_channel::unbounded();
+        let mut app = test_app(temp.path(), worker_tx, ui_rx);
+        let ctx = egui::Context::default();
+        let path = PathBuf::from("/image.bin");
+        let cancel_flag = |message| match message {
+            WorkerMessage::ChunkHash { cancel, .. } => cancel,
+            _ => panic!("应发送分块哈希请求"),
+        };
 
-                if ui
-                    .checkbox(&mut self.uppercase_display, "大写显示")
-                    .changed()
-                {
-                    self.cache_config.uppercase_display = self.uppercase_display;
-                    if let Ok(guard) = self.cache.lock() {
-                        let _ = guard.save_cache_config(&self.cache_config);
-                    }
-                }
+        app.start_chunk_hash(path.clone(), DEFAULT_HASH_CHUNK);
+        let first = cancel_flag(worker_rx.try_recv().unwrap());
+        app.start_chunk_hash(path.clone(), 16 * 1024 * 1024);
+        let second = cancel_flag(worker_rx.try_recv().unwrap());
+        assert!(first.load(Ordering::Relaxed));
+        assert!(!second.load(Ordering::Relaxed));
 
-                if ui
-                    .checkbox(&mut self.auto_compute_enabled, "自动计算")
-                    .changed()
-                {
-                    self.cache_config.auto_compute_enabled = self.auto_compute_enabled;
-                    if let Ok(guard) = self.cache.lock() {
-                        let _ = guard.save_cache_config(&self.cache_config);
-                    }
-                    if !self.auto_compute_enabled {
-                        self.last_file_add_time = None;
-                        self.auto_compute_scheduled = false;
-                    }
-                }
+        // 只显示当前块大小的进度
+        for chunk_size in [DEFAULT_HASH_CHUNK, 16 * 1024 * 1024] {
+            ui_tx
+                .send(UiMessage::ChunkHashProgress {
+                    path: path.clone(),
+                    chunk_size,
+                    processed: chunk_size,
+                    total: 1 << 30,
+                })
+                .unwrap();
+        }
+        app.process_messages(&ctx);
+        let view = app.chunk_hash.as_ref().unwrap();
+        assert_eq!(view.progress, Some((16 * 1024 * 1024, 1 << 30)));
 
-                ui.separator();
+        app.cancel_chunk_hash();
+        assert!(second.load(Ordering::Relaxed));
+        assert!(app.chunk_hash.is_none());
+    }
 
-                if self.is_computing {
-                    if let Some(start_time) = self.batch_start_time {
-                        let elapsed_ms = start_time.elapsed().as_millis() as u64;
-                        ui.label(
-                            egui::RichText::new(format!("已用时: {}", format_duration(elapsed_ms)))
-                                .color(egui::Color32::GRAY),
-                        );
-                    }
+    #[test]
+    fn test_pending_batch_subset_leaves_others_waiting() {
+        let mut files: Vec<FileItem> = (0..5)
+            .map(|i| FileItem::new(PathBuf::from(format!("/data/{}.bin", i)), 100 * (i + 1)))
+            .collect();
+        files[3].status = FileStatus::Completed;
 
-                    if ui.button("停止").clicked() {
-                        self.stop_computing();
-                    }
-                } else {
-                    ui.add_enabled_ui(!self.files.is_empty(), |ui| {
-                        if ui.button("开始计算").clicked() {
-                            self.start_computing();
-                            self.last_file_add_time = None;
-                            self.auto_compute_scheduled = false;
-                        }
-                    });
-                    if self.batch_total_duration_ms > 0 {
-                        ui.label(format!(
-                            "上次耗时: {}",
-                            format_duration(self.batch_total_duration_ms)
-                        ));
-                    }
-                }
+        let (all, size) = pending_batch(&files, None);
+        assert_eq!(all.len(), 4);
+        assert_eq!(size, 100 + 200 + 300 + 500);
 
-                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                    ui.label(format!("文件: {}", self.files.len()));
-                });
-            });
-        });
+        // 已完成的选中行不重新计算
+        let rows: HashSet<usize> = [1, 3, 4].into_iter().collect();
+        let (subset, size) = pending_batch(&files, Some(&rows));
+        assert_eq!(
+            subset,
+            [
+                (files[1].path.clone(), files[1].size),
+                (files[4].path.clone(), files[4].size)
+            ]
+        );
+        assert_eq!(size, 200 + 500);
 
-        CentralPanel::default().show(ctx, |ui| {
-            ScrollArea::vertical()
-                .auto_shrink([false; 2])
-                .show(ui, |ui| {
-                    TableBuilder::new(ui)
-                        .striped(true)
-                        .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
-                        .column(Column::exact(60.0))
-                        .column(Column::initial(200.0).range(100.0..=400.0).clip(true))
-                        .column(Column::exact(100.0))
-                        .column(Column::exact(100.0))
-                        .column(Column::exact(150.0))
-                        .column(Column::initial(100.0).at_least(80.0).clip(true))
-                        .column(Column::initial(290.0).range(180.0..=300.0).clip(true))
-                        .column(Column::remainder().at_least(230.0).clip(true))
-                        .header(30.0, |mut header| {
-                            header.col(|ui| {
-                                ui.strong("状态");
-                            });
-                            header.col(|ui| {
-                                ui.strong("文件名");
-                            });
-                            header.col(|ui| {
-                                ui.strong("大小");
-                            });
-                            header.col(|ui| {
-                                ui.strong("耗时");
-                            });
-                            header.col(|ui| {
-                                ui.strong("进度");
-                            });
-                            header.col(|ui| {
-                                ui.strong("CRC32");
-                            });
-                            header.col(|ui| {
-                                ui.strong("MD5");
-                            });
-                            header.col(|ui| {
-                                ui.strong("SHA1");
-                            });
-                        })
-                        .body(|body| {
-                            body.rows(30.0, self.files.len(), |mut row| {
-                                let idx = row.index();
-                                if idx < self.files.len() {
-                                    // 解决借用冲突：提前克隆需要的数据
-                                    let (
-                                        status_icon,
-                                        filename,
-                                        size_str,
-                                        duration_str,
-                                        progress,
-                                        crc32,
-                                        md5,
-                                        sha1,
-                                        path_str,
-                                    ) = {
-                                        let file = &self.files[idx];
-                                        (
-                                            file.status_icon().to_string(),
-                                            file.filename(),
-                                            file.size_str.clone(),
-                                            file.duration_str(),
-                                            file.progress,
-                                            file.crc32.clone(),
-                                            file.md5.clone(),
-                                            file.sha1.clone(),
-                                            dunce::simplified(&file.path).display().to_string(),
-                                        )
-                                    };
+        assert!(pending_batch(&files, Some(&HashSet::new())).0.is_empty());
+    }
 
-                                    row.col(|ui| {
-                                        ui.label(status_icon);
-                                    });
-                                    row.col(|ui| {
-                                        ui.label(filename);
-                                    });
-                                    row.col(|ui| {
-                                        ui.label(size_str);
-                                    });
-                                    row.col(|ui| {
-                                        ui.label(duration_str);
-                                    });
-                                    row.col(|ui| {
-                                        egui::ProgressBar::new(progress as f32)
-                                            .show_percentage()
-                                            .ui(ui);
-                                    });
-                                    // 使用克隆的数据，不再持有 self.files 的借用
-                                    row.col(|ui| {
-                                        self.show_hash_cell(
-                                            ui,
-                                            ctx,
-                                            &crc32,
-                                            &format!("{}_crc32", path_str),
-                                        );
-                                    });
-                                    row.col(|ui| {
-                                        self.show_hash_cell(
-                                            ui,
-                                            ctx,
-                                            &md5,
-                                            &format!("{}_md5", path_str),
-                                        );
-                                    });
-                                    row.col(|ui| {
-                                        self.show_hash_cell(
-                                            ui,
-                                            ctx,
-                                            &sha1,
-                                            &format!("{}_sha1", path_str),
-                                        );
-                                    });
-                                }
-                            });
-                        });
-                    ui.add_space(40.0);
-                });
-        });
-        // ... (Status panel code same as before, simplified to save space, but keeping key elements)
-        TopBottomPanel::bottom("status").show(ctx, |ui| {
-            ui.horizontal(|ui| {
-                ui.label("全局进度:");
-                ui.add(egui::ProgressBar::new(self.global_progress as f32).show_percentage());
-                ui.separator();
-                ui.label(format!(
-                    "已处理: {} / 总计: {}",
-                    humansize::format_size(self.processed_size, humansize::BINARY),
-                    humansize::format_size(self.total_size, humansize::BINARY)
-                ));
+    #[test]
+    fn test_message_drain_consumes_burst() {
+        const BURST: usize = 5_000;
+        for limit in [0, 10] {
+            let (tx, rx) = crossbeam_channel::bounded(MIN_UI_CHANNEL_CAPACITY);
+            let producer = std::thread::spawn(move || {
+                for i in 0..BURST {
+                    tx.send(i).unwrap();
+                }
             });
-        });
 
-        if self.show_cache_settings {
-            self.render_settings_window(ctx);
+            let mut received = Vec::with_capacity(BURST);
+            let mut frames = 0;
+            while received.len() < BURST {
+                frames += 1;
+                assert!(frames < 1_000_000, "messages were not drained");
+                let mut drain = MessageDrain::new(limit);
+                let before = received.len();
+                while let Some(msg) = drain.next(&rx) {
+                    received.push(msg);
+                }
+                if limit > 0 {
+                    assert!(received.len() - before <= limit);
+                }
+                if received.len() == before {
+                    std::thread::yield_now();
+                }
+            }
+            producer.join().unwrap();
+
+            // 按顺序全部取到，超出预算时不丢消息
+            assert!(received.iter().copied().eq(0..BURST));
+            assert!(rx.is_empty());
         }
     }
+
+    #[test]
+    fn test_refresh_metadata_resets_changed_file() {
+        let stat = |size, modified_time| {
+            Some(FileStat {
+                size,
+                modified_time: Some(modified_time),
+            })
+        };
+        let mut file = FileItem::new(PathBuf::from("/data/data.bin"), 10);
+        file.status = FileStatus::Completed;
+        file.sha256 = "ab".repeat(32);
+        file.modified_time = Some(1_000);
+        file.note = "保留".to_string();
+        assert_eq!(
+            file.refresh_metadata(stat(10, 1_000), 0),
+            MetadataRefresh::Unchanged
+        );
+        assert!(matches!(file.status, FileStatus::Completed));
+
+        assert_eq!(
+            file.refresh_metadata(stat(25, 2_000), 0),
+            MetadataRefresh::Changed
+        );
+        assert!(matches!(file.status, FileStatus::Pending));
+        assert_eq!(file.size, 25);
+        assert_eq!(file.modified_time, Some(2_000));
+        assert!(file.sha256.is_empty());
+        assert_eq!(file.note, "保留");
+
+        assert_eq!(file.refresh_metadata(None, 0), MetadataRefresh::Missing);
+        assert!(matches!(file.status, FileStatus::Deleted));
+    }
+
+    #[test]
+    fn test_reveal_target_prefers_selection() {
+        let mut files: Vec<FileItem> = (0..4)
+            .map(|i| FileItem::new(PathBuf::from(format!("/data/{i}.bin")), 1))
+            .collect();
+        assert_eq!(reveal_target(&files, &HashSet::new()), None);
+
+        files[2].status = FileStatus::Completed;
+        files[3].status = FileStatus::Completed;
+        assert_eq!(reveal_target(&files, &HashSet::new()), Some(2));
+        // 选中行优先，不要求已完成；越界的下标忽略
+        assert_eq!(reveal_target(&files, &HashSet::from([9, 3, 1])), Some(1));
+        assert_eq!(reveal_target(&files, &HashSet::from([9])), Some(2));
+    }
+
+    #[test]
+    fn test_move_focus_follows_visible_rows() {
+        use FocusMove::*;
+        assert_eq!(move_focus(None, 0, None, Down), None);
+        assert_eq!(move_focus(None, 50, None, Down), Some(0));
+        assert_eq!(move_focus(None, 50, None, End), Some(49));
+        assert_eq!(move_focus(None, 50, Some(0), Up), Some(0));
+        assert_eq!(move_focus(None, 50, Some(45), PageDown), Some(49));
+        assert_eq!(move_focus(None, 50, Some(30), PageUp), Some(10));
+        // 清空部分队列后越界的焦点落到最后一行
+        assert_eq!(move_focus(None, 5, Some(30), Home), Some(0));
+        assert_eq!(focus_position(None, 5, 30), Some(4));
+
+        // 「只看问题」时按筛选后的顺序移动，返回文件下标
+        let rows = [2, 5, 9];
+        assert_eq!(move_focus(Some(&rows), 10, Some(5), Down), Some(9));
+        assert_eq!(move_focus(Some(&rows), 10, Some(9), Down), Some(9));
+        assert_eq!(move_focus(Some(&rows), 10, Some(2), End), Some(9));
+        // 焦点行被筛掉时落到其后最近的行，其后没有行时落到最后一行
+        assert_eq!(focus_position(Some(&rows), 10, 3), Some(1));
+        assert_eq!(focus_position(Some(&rows), 10, 7), Some(2));
+        assert_eq!(move_focus(Some(&rows), 10, Some(3), Up), Some(2));
+        assert_eq!(focus_position(Some(&[]), 10, 3), None);
+    }
 }
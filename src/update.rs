@@ -0,0 +1,162 @@
+// 检查更新（可选特性 update-check）
+//
+// 下载一个很小的版本清单 JSON（`{"version": "0.2.0", "url": "..."}`），与编译时嵌入的
+// `CARGO_PKG_VERSION` 比较，有新版本时在界面顶部显示提示。默认构建不联网；启用特性后
+// 还需在设置中开启，启动时的检查失败（离线、地址错误等）只记录日志，不打扰用户。
+
+use std::cmp::Ordering;
+
+use crate::cache::SizeUnit;
+use crate::error::HashResult;
+use crate::json::string_field;
+
+pub const SUPPORTED: bool = cfg!(feature = "update-check");
+
+/// 编译时嵌入的当前版本
+pub const CURRENT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// 默认的版本清单地址，即仓库根目录的 `version.json`
+pub const DEFAULT_MANIFEST_URL: &str =
+    "https://raw.githubusercontent.com/xihan123/TurboHash/main/version.json";
+
+/// 清单中没有下载地址时打开的发布页
+const RELEASES_URL: &str = "https://github.com/xihan123/TurboHash/releases";
+
+/// 可用的新版本
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UpdateInfo {
+    pub version: String,
+    /// 下载或发布说明页面
+    pub url: String,
+}
+
+/// 下载版本清单，比当前版本新时返回新版本信息
+pub fn check_for_update(manifest_url: &str, unit: SizeUnit) -> HashResult<Option<UpdateInfo>> {
+    let text = crate::remote::fetch_text(manifest_url, unit)?;
+    Ok(newer_release(&text, CURRENT_VERSION))
+}
+
+/// 解析版本清单，其中的版本比 `current` 新时返回；清单无法解析时返回 None
+pub fn newer_release(manifest: &str, current: &str) -> Option<UpdateInfo> {
+    let version = string_field(manifest, "version")?;
+    if compare_versions(&version, current)? != Ordering::Greater {
+        return None;
+    }
+    let url = string_field(manifest, "url")
+        .filter(|url| url.starts_with("https://") || url.starts_with("http://"))
+        .unwrap_or_else(|| RELEASES_URL.to_string());
+    Some(UpdateInfo { version, url })
+}
+
+/// 比较 `1.2.3`、`v1.2`、`1.3.0-beta.1` 形式的版本号，任一无法解析时返回 None
+///
+/// 缺少的部分按 0 处理；数字相同时正式版比预发布版新，两个预发布版按标识逐段比较。
+pub fn compare_versions(a: &str, b: &str) -> Option<Ordering> {
+    let (a_core, a_pre) = parse_version(a)?;
+    let (b_core, b_pre) = parse_version(b)?;
+    let len = a_core.len().max(b_core.len());
+    let part = |core: &[u64], i: usize| core.get(i).copied().unwrap_or(0);
+    let core = (0..len)
+        .map(|i| part(&a_core, i).cmp(&part(&b_core, i)))
+        .find(|ordering| ordering.is_ne())
+        .unwrap_or(Ordering::Equal);
+
+    Some(core.then_with(|| match (a_pre, b_pre) {
+        (None, None) => Ordering::Equal,
+        (None, Some(_)) => Ordering::Greater,
+        (Some(_), None) => Ordering::Less,
+        (Some(a), Some(b)) => compare_pre_release(a, b),
+    }))
+}
+
+/// 拆分为数字部分与预发布标识，忽略 `v` 前缀与 `+` 之后的构建信息
+fn parse_version(version: &str) -> Option<(Vec<u64>, Option<&str>)> {
+    let version = version.trim();
+    let version = version
+        .strip_prefix(['v', 'V'])
+        .unwrap_or(version)
+        .split('+')
+        .next()?;
+    let (core, pre) = match version.split_once('-') {
+        Some((core, pre)) => (core, Some(pre)),
+        None => (version, None),
+    };
+    let core = core
+        .split('.')
+        .map(|part| part.parse().ok())
+        .collect::<Option<Vec<u64>>>()?;
+    Some((core, pre))
+}
+
+/// 预发布标识逐段比较：纯数字的段按数值比较，其余按字符串比较，段数多的更新
+fn compare_pre_release(a: &str, b: &str) -> Ordering {
+    let mut a_parts = a.split('.');
+    let mut b_parts = b.split('.');
+    loop {
+        match (a_parts.next(), b_parts.next()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(a), Some(b)) => {
+                let ordering = match (a.parse::<u64>(), b.parse::<u64>()) {
+                    (Ok(a), Ok(b)) => a.cmp(&b),
+                    _ => a.cmp(b),
+                };
+                if ordering.is_ne() {
+                    return ordering;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_newer_release_from_mock_manifest() {
+        let manifest = r#"{
+            "version": "v0.3.0",
+            "url": "https:\/\/example.com\/TurboHash\/releases\/v0.3.0",
+            "notes": "修复若干问题"
+        }"#;
+        assert_eq!(
+            newer_release(manifest, "0.2.9"),
+            Some(UpdateInfo {
+                version: "v0.3.0".to_string(),
+                url: "https://example.com/TurboHash/releases/v0.3.0".to_string(),
+            })
+        );
+        // 相同或更旧的版本不提示
+        assert_eq!(newer_release(manifest, "0.3.0"), None);
+        assert_eq!(newer_release(manifest, "0.3.1"), None);
+
+        // 没有下载地址（或不是网页链接）时打开发布页
+        let update = newer_release(r#"{"version":"1.0.0","url":"file:///etc"}"#, "0.1.0").unwrap();
+        assert_eq!(update.url, RELEASES_URL);
+
+        // 无法解析的清单静默忽略
+        assert_eq!(newer_release("<html>404</html>", "0.1.0"), None);
+        assert_eq!(newer_release(r#"{"version":"latest"}"#, "0.1.0"), None);
+    }
+
+    #[test]
+    fn test_compare_versions() {
+        use Ordering::{Equal, Greater, Less};
+        assert_eq!(compare_versions("0.10.0", "0.9.9"), Some(Greater));
+        assert_eq!(compare_versions("1.2", "1.2.0"), Some(Equal));
+        assert_eq!(compare_versions("V1.2.1+build.5", "1.2.1"), Some(Equal));
+        assert_eq!(compare_versions("1.3.0-beta.1", "1.3.0"), Some(Less));
+        assert_eq!(
+            compare_versions("1.3.0-beta.2", "1.3.0-beta.10"),
+            Some(Less)
+        );
+        assert_eq!(
+            compare_versions("1.3.0-rc.1", "1.3.0-beta.3"),
+            Some(Greater)
+        );
+        assert_eq!(compare_versions("1.3.0-beta", "1.3.0-beta.1"), Some(Less));
+        assert_eq!(compare_versions("1.x", "1.0"), None);
+    }
+}
@@ -1,3 +1,105 @@
+use std::path::{Path, PathBuf};
+
+use crate::cache::{HashEncoding, SizeUnit, parse_modified_time};
+use crate::hash::HashAlgorithm;
+use crate::i18n::{Text, t};
+
+/// 按设置的单位格式化字节数，界面中的大小统一经由此处显示
+pub fn format_size(bytes: impl humansize::ToF64 + humansize::Unsigned, unit: SizeUnit) -> String {
+    match unit {
+        SizeUnit::Binary => humansize::format_size(bytes, humansize::BINARY),
+        SizeUnit::Decimal => humansize::format_size(bytes, humansize::DECIMAL),
+    }
+}
+
+/// 按设置的编码格式化十六进制摘要，界面中显示与复制的哈希统一经由此处转换
+pub fn format_digest(hex: &str, encoding: HashEncoding, uppercase: bool) -> String {
+    let bytes = match encoding {
+        HashEncoding::Hex if uppercase => return hex.to_uppercase(),
+        HashEncoding::Hex => return hex.to_string(),
+        _ => match hex::decode(hex) {
+            Ok(bytes) => bytes,
+            Err(_) => return hex.to_string(),
+        },
+    };
+    match encoding {
+        HashEncoding::Base64Url => encode_base64(&bytes, BASE64_URL_ALPHABET, false),
+        HashEncoding::ZBase32 => encode_zbase32(&bytes),
+        _ => encode_base64(&bytes, BASE64_ALPHABET, true),
+    }
+}
+
+/// Subresource Integrity 格式的摘要，如 `sha256-<base64>`；SRI 只接受 SHA-2 系列
+pub fn sri_digest(algorithm: HashAlgorithm, hex: &str) -> Option<String> {
+    let prefix = match algorithm {
+        HashAlgorithm::Sha256 => "sha256",
+        _ => return None,
+    };
+    let bytes = hex::decode(hex).ok().filter(|bytes| !bytes.is_empty())?;
+    Some(format!(
+        "{}-{}",
+        prefix,
+        encode_base64(&bytes, BASE64_ALPHABET, true)
+    ))
+}
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+const BASE64_URL_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+fn encode_base64(bytes: &[u8], alphabet: &[u8; 64], pad: bool) -> String {
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let group = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |acc, (i, &b)| acc | u32::from(b) << (16 - 8 * i));
+        // n 个字节产生 n + 1 个字符，不足 4 个时按需补 `=`
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(char::from(alphabet[(group >> (18 - 6 * i)) as usize & 63]));
+            } else if pad {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+/// z-base-32：按位从高到低每 5 位取一个字符，末尾不足 5 位时右侧补零，不带填充
+fn encode_zbase32(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 32] = b"ybndrfg8ejkmcpqxot1uwisza345h769";
+    let mut out = String::with_capacity((bytes.len() * 8).div_ceil(5));
+    let (mut buffer, mut bits) = (0u32, 0);
+    for &byte in bytes {
+        buffer = (buffer << 8) | u32::from(byte);
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            out.push(char::from(ALPHABET[(buffer >> bits) as usize & 31]));
+        }
+        buffer &= (1 << bits) - 1;
+    }
+    if bits > 0 {
+        out.push(char::from(ALPHABET[(buffer << (5 - bits)) as usize & 31]));
+    }
+    out
+}
+
+/// 按千位分隔格式化数量，例如 `1234` → `1,234`
+pub fn format_count(count: usize) -> String {
+    let digits = count.to_string();
+    let mut result = String::with_capacity(digits.len() + digits.len() / 3);
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            result.push(',');
+        }
+        result.push(c);
+    }
+    result
+}
+
 pub fn format_duration(ms: u64) -> String {
     if ms < 1000 {
         format!("{}ms", ms)
@@ -8,3 +110,231 @@ pub fn format_duration(ms: u64) -> String {
         format!("{}m{}s", seconds / 60, seconds % 60)
     }
 }
+
+/// 将缓存中的修改时间（秒 << 32 | 纳秒）格式化为本地时间
+pub fn format_modified_time(combined: u64) -> String {
+    let (secs, nanos) = parse_modified_time(combined);
+    format_local_time(secs, nanos)
+}
+
+/// 将 Unix 时间戳（秒）格式化为本地时间
+pub fn format_timestamp(secs: u64) -> String {
+    format_local_time(secs, 0)
+}
+
+/// 以「3天前」的形式显示已经过去的秒数
+pub fn format_age(secs: u64) -> String {
+    let (text, n) = match secs {
+        0..60 => return t(Text::JustNow).to_string(),
+        60..3600 => (Text::MinutesAgo, secs / 60),
+        3600..86400 => (Text::HoursAgo, secs / 3600),
+        _ => (Text::DaysAgo, secs / 86400),
+    };
+    t(text).replace("{}", &n.to_string())
+}
+
+fn format_local_time(secs: u64, nanos: u32) -> String {
+    i64::try_from(secs)
+        .ok()
+        .and_then(|secs| chrono::DateTime::from_timestamp(secs, nanos))
+        .map(|dt| {
+            dt.with_timezone(&chrono::Local)
+                .format("%Y-%m-%d %H:%M:%S")
+                .to_string()
+        })
+        .unwrap_or_else(|| "-".to_string())
+}
+
+/// 用系统默认程序打开文件或目录（目录在文件管理器中打开）
+pub fn open_with_system(path: &Path) -> std::io::Result<()> {
+    #[cfg(windows)]
+    let program = "explorer";
+    #[cfg(target_os = "macos")]
+    let program = "open";
+    #[cfg(not(any(windows, target_os = "macos")))]
+    let program = "xdg-open";
+
+    std::process::Command::new(program)
+        .arg(path)
+        .spawn()
+        .map(|_| ())
+}
+
+/// 在文件管理器中显示文件：Windows 与 macOS 打开所在文件夹并选中该文件，
+/// 其他平台没有统一的选中方式，只打开所在文件夹
+pub fn reveal_in_file_manager(path: &Path) -> std::io::Result<()> {
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        // explorer 自行解析参数，路径需放在 `/select,` 之后的引号中
+        std::process::Command::new("explorer")
+            .raw_arg(format!("/select,\"{}\"", path.display()))
+            .spawn()
+            .map(|_| ())
+    }
+    #[cfg(target_os = "macos")]
+    {
+        std::process::Command::new("open")
+            .arg("-R")
+            .arg(path)
+            .spawn()
+            .map(|_| ())
+    }
+    #[cfg(not(any(windows, target_os = "macos")))]
+    {
+        open_with_system(path.parent().unwrap_or(path))
+    }
+}
+
+/// 读取系统剪贴板文本
+pub fn read_clipboard_text() -> Option<String> {
+    match arboard::Clipboard::new().and_then(|mut c| c.get_text()) {
+        Ok(text) => Some(text),
+        Err(e) => {
+            log::warn!("读取剪贴板失败: {}", e);
+            None
+        }
+    }
+}
+
+/// 解析换行分隔的路径列表：去掉首尾空白与引号，仅保留存在的路径
+///
+/// 返回 (存在的路径, 跳过的行数)。
+pub fn parse_path_list(text: &str) -> (Vec<PathBuf>, usize) {
+    let mut paths = Vec::new();
+    let mut skipped = 0;
+
+    for line in text.lines() {
+        let trimmed = line.trim().trim_matches(|c| c == '"' || c == '\'').trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+
+        let path = PathBuf::from(trimmed);
+        if path.exists() {
+            paths.push(path);
+        } else {
+            skipped += 1;
+        }
+    }
+
+    (paths, skipped)
+}
+
+/// 读取响应文件（`@list.txt`）中的路径列表
+///
+/// 按 UTF-8 解码并去掉 BOM，非法字节以替换字符处理；每行的解析规则同 [`parse_path_list`]。
+pub fn read_path_list_file(path: &Path) -> std::io::Result<(Vec<PathBuf>, usize)> {
+    let bytes = std::fs::read(path)?;
+    let bytes = bytes.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(&bytes);
+    Ok(parse_path_list(&String::from_utf8_lossy(bytes)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_format_size_units() {
+        assert_eq!(format_size(1_500_000_u64, SizeUnit::Binary), "1.43 MiB");
+        assert_eq!(format_size(1_500_000_u64, SizeUnit::Decimal), "1.50 MB");
+        assert_eq!(format_size(512_u64, SizeUnit::Decimal), "512 B");
+    }
+
+    #[test]
+    fn test_format_digest_encodings() {
+        // SHA256("abc")
+        let hex = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+        assert_eq!(format_digest(hex, HashEncoding::Hex, false), hex);
+        assert_eq!(
+            format_digest(hex, HashEncoding::Hex, true),
+            hex.to_uppercase()
+        );
+        assert_eq!(
+            format_digest(hex, HashEncoding::Base64, true),
+            "ungWv48Bz+pBQUDeXa4iI7ADYaOWF3qctBD/YfIAFa0="
+        );
+        assert_eq!(
+            format_digest(hex, HashEncoding::Base64Url, false),
+            "ungWv48Bz-pBQUDeXa4iI7ADYaOWF3qctBD_YfIAFa0"
+        );
+        assert_eq!(
+            sri_digest(HashAlgorithm::Sha256, hex).as_deref(),
+            Some("sha256-ungWv48Bz+pBQUDeXa4iI7ADYaOWF3qctBD/YfIAFa0=")
+        );
+        assert_eq!(sri_digest(HashAlgorithm::Md5, hex), None);
+
+        // CRC32 长度不是 3 的倍数，需要填充
+        assert_eq!(
+            format_digest("352441c2", HashEncoding::Base64, false),
+            "NSRBwg=="
+        );
+        // z-base-32 规范中的示例
+        assert_eq!(
+            format_digest("f0bfc7", HashEncoding::ZBase32, false),
+            "6n9hq"
+        );
+        assert_eq!(
+            format_digest("d47a04", HashEncoding::ZBase32, false),
+            "4t7ye"
+        );
+        assert_eq!(
+            format_digest("352441c2", HashEncoding::ZBase32, false),
+            "gw1rdoo"
+        );
+    }
+
+    #[test]
+    fn test_format_count() {
+        assert_eq!(format_count(0), "0");
+        assert_eq!(format_count(999), "999");
+        assert_eq!(format_count(1234), "1,234");
+        assert_eq!(format_count(1_234_567), "1,234,567");
+    }
+
+    #[test]
+    fn test_format_age() {
+        assert_eq!(format_age(5), "刚刚");
+        assert_eq!(format_age(150), "2分钟前");
+        assert_eq!(format_age(3 * 3600 + 59), "3小时前");
+        assert_eq!(format_age(3 * 86400 + 7200), "3天前");
+        assert_eq!(format_age(400 * 86400), "400天前");
+    }
+
+    #[test]
+    fn test_parse_path_list() {
+        let temp = TempDir::new().unwrap();
+        let file_a = temp.path().join("a.txt");
+        let file_b = temp.path().join("b c.txt");
+        std::fs::write(&file_a, b"a").unwrap();
+        std::fs::write(&file_b, b"b").unwrap();
+
+        let text = format!(
+            "{}\r\n  \"{}\"  \n\n'{}'\n/definitely/missing/path\n",
+            file_a.display(),
+            file_b.display(),
+            temp.path().display()
+        );
+
+        let (paths, skipped) = parse_path_list(&text);
+        assert_eq!(paths, vec![file_a, file_b, temp.path().to_path_buf()]);
+        assert_eq!(skipped, 1);
+    }
+
+    #[test]
+    fn test_read_path_list_file_strips_bom() {
+        let temp = TempDir::new().unwrap();
+        let file = temp.path().join("数据.bin");
+        std::fs::write(&file, b"x").unwrap();
+
+        let list = temp.path().join("list.txt");
+        let mut content = b"\xEF\xBB\xBF".to_vec();
+        content.extend_from_slice(format!("  \"{}\"\r\n", file.display()).as_bytes());
+        std::fs::write(&list, content).unwrap();
+
+        let (paths, skipped) = read_path_list_file(&list).unwrap();
+        assert_eq!(paths, vec![file]);
+        assert_eq!(skipped, 0);
+    }
+}
@@ -0,0 +1,803 @@
+// 哈希校验模块
+
+use std::collections::{HashMap, HashSet};
+use std::ffi::OsString;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use walkdir::WalkDir;
+
+use crate::hash::HashAlgorithm;
+use crate::ui::{FileItem, FileStatus};
+
+/// 校验结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VerifyOutcome {
+    Match(HashAlgorithm),
+    Mismatch,
+    UnknownFormat,
+    NotComputed,
+}
+
+impl VerifyOutcome {
+    pub fn is_match(self) -> bool {
+        matches!(self, VerifyOutcome::Match(_))
+    }
+
+    pub fn message(self) -> String {
+        match self {
+            VerifyOutcome::Match(algorithm) => format!("✓ {} 匹配", algorithm.label()),
+            VerifyOutcome::Mismatch => "✗ 哈希不匹配".to_string(),
+            VerifyOutcome::UnknownFormat => "✗ 无法识别的哈希格式".to_string(),
+            VerifyOutcome::NotComputed => "文件尚未计算完成".to_string(),
+        }
+    }
+}
+
+/// 从任意文本中提取哈希值：取第一个词，去掉 `0x` 前缀并转为小写
+///
+/// 兼容直接复制 `<hash>  <filename>` 形式的校验文件行。
+pub fn normalize_hex(input: &str) -> Option<String> {
+    let token = input.split_whitespace().next()?;
+    let token = token
+        .strip_prefix("0x")
+        .or_else(|| token.strip_prefix("0X"))
+        .unwrap_or(token);
+
+    if token.is_empty() || !token.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    Some(token.to_ascii_lowercase())
+}
+
+/// 按十六进制长度推断可能的算法（32 位同时对应 MD5 与 XXH3）
+pub fn candidate_algorithms(hex_len: usize) -> Vec<HashAlgorithm> {
+    HashAlgorithm::ALL
+        .into_iter()
+        .filter(|a| a.hex_len() == hex_len)
+        .collect()
+}
+
+/// 将期望哈希与文件的计算结果比对
+pub fn verify_against(expected: &str, file: &FileItem) -> VerifyOutcome {
+    if !matches!(file.status, FileStatus::Completed) {
+        return VerifyOutcome::NotComputed;
+    }
+
+    let Some(expected) = normalize_hex(expected) else {
+        return VerifyOutcome::UnknownFormat;
+    };
+
+    let candidates = candidate_algorithms(expected.len());
+    if candidates.is_empty() {
+        return VerifyOutcome::UnknownFormat;
+    }
+
+    candidates
+        .into_iter()
+        .find(|&algorithm| file.hash_value(algorithm).eq_ignore_ascii_case(&expected))
+        .map_or(VerifyOutcome::Mismatch, VerifyOutcome::Match)
+}
+
+/// 按哈希值查找文件的结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HashSearch {
+    /// 不是十六进制哈希值
+    Invalid,
+    /// 长度不对应任何算法
+    UnknownLength(usize),
+    /// 按长度推断的算法，以及匹配的 (文件下标, 算法)，按列表顺序排列
+    Found {
+        algorithms: Vec<HashAlgorithm>,
+        matches: Vec<(usize, HashAlgorithm)>,
+    },
+}
+
+impl HashSearch {
+    pub fn matches(&self) -> &[(usize, HashAlgorithm)] {
+        match self {
+            HashSearch::Found { matches, .. } => matches,
+            _ => &[],
+        }
+    }
+}
+
+/// 在所有文件的哈希值中查找，按长度推断算法（32 位同时查找 MD5 与 XXH3）
+pub fn search_by_hash(query: &str, files: &[FileItem]) -> HashSearch {
+    let Some(hex) = normalize_hex(query) else {
+        return HashSearch::Invalid;
+    };
+    let algorithms = candidate_algorithms(hex.len());
+    if algorithms.is_empty() {
+        return HashSearch::UnknownLength(hex.len());
+    }
+
+    let matches = files
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, file)| {
+            algorithms
+                .iter()
+                .find(|&&algorithm| file.hash_value(algorithm).eq_ignore_ascii_case(&hex))
+                .map(|&algorithm| (idx, algorithm))
+        })
+        .collect();
+    HashSearch::Found {
+        algorithms,
+        matches,
+    }
+}
+
+/// 粘贴的已知哈希清单（每行一个，算法不限），用于批量筛查已加载的文件
+#[derive(Debug, Default)]
+pub struct HashList {
+    /// 小写十六进制
+    hashes: HashSet<String>,
+    /// 无法识别为哈希值的非空行数
+    pub skipped: usize,
+}
+
+impl HashList {
+    /// 每行取第一个词，兼容 `<hash>  <filename>` 形式；空行与 `#` 注释行忽略
+    pub fn parse(text: &str) -> Self {
+        let mut list = Self::default();
+        for line in text.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            match normalize_hex(line).filter(|hex| !candidate_algorithms(hex.len()).is_empty()) {
+                Some(hex) => {
+                    list.hashes.insert(hex);
+                }
+                None => list.skipped += 1,
+            }
+        }
+        list
+    }
+
+    pub fn len(&self) -> usize {
+        self.hashes.len()
+    }
+
+    /// 已完成文件任一算法的结果出现在清单中时返回该算法
+    pub fn find(&self, file: &FileItem) -> Option<HashAlgorithm> {
+        if !matches!(file.status, FileStatus::Completed) {
+            return None;
+        }
+        HashAlgorithm::ALL.into_iter().find(|&algorithm| {
+            let value = file.hash_value(algorithm);
+            !value.is_empty() && self.hashes.contains(&value.to_ascii_lowercase())
+        })
+    }
+}
+
+/// 同名校验文件（如 `movie.iso.sha256`）中记录的期望哈希
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExpectedHash {
+    pub algorithm: HashAlgorithm,
+    /// 小写十六进制
+    pub hex: String,
+    pub sidecar: ChecksumSource,
+}
+
+/// 期望哈希的来源：本地的校验文件或清单，或从 URL 下载的清单
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChecksumSource {
+    File(PathBuf),
+    Url(String),
+}
+
+impl ChecksumSource {
+    /// 状态列中显示的名称：文件名，或 URL 路径的最后一段
+    pub fn name(&self) -> String {
+        match self {
+            ChecksumSource::File(path) => path
+                .file_name()
+                .map_or_else(String::new, |n| n.to_string_lossy().into_owned()),
+            ChecksumSource::Url(url) => {
+                let path = url.split(['?', '#']).next().unwrap_or(url);
+                path.rsplit('/').next().unwrap_or(path).to_string()
+            }
+        }
+    }
+}
+
+impl fmt::Display for ChecksumSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChecksumSource::File(path) => write!(f, "{}", path.display()),
+            ChecksumSource::Url(url) => f.write_str(url),
+        }
+    }
+}
+
+impl ExpectedHash {
+    /// 与计算结果比对；对应算法未计算（未启用）时返回 None
+    pub fn check(&self, file: &FileItem) -> Option<bool> {
+        if !matches!(file.status, FileStatus::Completed) {
+            return None;
+        }
+        let actual = file.hash_value(self.algorithm);
+        (!actual.is_empty()).then(|| actual.eq_ignore_ascii_case(&self.hex))
+    }
+}
+
+/// 同一文件存在多个校验文件时优先采用的算法顺序
+const SIDECAR_PRIORITY: [HashAlgorithm; 7] = [
+    HashAlgorithm::Sha256,
+    HashAlgorithm::Sha1,
+    HashAlgorithm::Md5,
+    HashAlgorithm::Xxh3,
+    HashAlgorithm::Crc64,
+    HashAlgorithm::Crc32,
+    HashAlgorithm::Adler32,
+];
+
+/// 识别 `<name>.<algo>` 与 `<name>.<algo>sum` 形式的校验文件名，返回 (被校验的文件名, 算法)
+fn parse_sidecar_name(name: &str) -> Option<(&str, HashAlgorithm)> {
+    let lower = name.to_ascii_lowercase();
+    SIDECAR_PRIORITY.into_iter().find_map(|algorithm| {
+        let key = algorithm.key();
+        let suffix_len = [format!(".{key}sum"), format!(".{key}")]
+            .into_iter()
+            .find(|suffix| lower.ends_with(suffix.as_str()))?
+            .len();
+        let base = &name[..name.len() - suffix_len];
+        (!base.is_empty()).then_some((base, algorithm))
+    })
+}
+
+/// 从校验文件内容中取出 `file_name` 的期望哈希
+///
+/// 支持 `<hash>  <name>`（`sha256sum` 格式，含 `*` 二进制标记）、
+/// `SHA256 (<name>) = <hash>`（BSD 格式）与只有哈希值的文件。
+/// 多条记录时按文件名匹配；只有一条记录时不要求文件名一致（校验文件常随文件一起改名）。
+fn parse_sidecar(content: &str, file_name: &str, algorithm: HashAlgorithm) -> Option<String> {
+    let mut entries = Vec::new();
+    for (hash, name) in content.lines().filter_map(parse_checksum_line) {
+        let Some(hex) = normalize_hex(hash).filter(|h| h.len() == algorithm.hex_len()) else {
+            continue;
+        };
+        entries.push((hex, name));
+    }
+
+    let named = entries.iter().find(|(_, name)| {
+        name.and_then(|n| Path::new(n).file_name())
+            .is_some_and(|n| n == file_name)
+    });
+    match (named, entries.len()) {
+        (Some((hex, _)), _) => Some(hex.clone()),
+        (None, 1) => entries.pop().map(|(hex, _)| hex),
+        _ => None,
+    }
+}
+
+/// 拆分校验文件中的一行，返回 (哈希, 文件名)；空行与注释返回 None
+fn parse_checksum_line(line: &str) -> Option<(&str, Option<&str>)> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+        return None;
+    }
+    Some(match line.rsplit_once(" = ") {
+        Some((head, hash)) => (
+            hash,
+            head.split_once(" (")
+                .and_then(|(_, rest)| rest.strip_suffix(')')),
+        ),
+        None => match line.split_once(char::is_whitespace) {
+            Some((hash, rest)) => (hash, Some(rest.trim_start().trim_start_matches('*'))),
+            None => (line, None),
+        },
+    })
+}
+
+/// 解析包含多个文件的校验清单（如发布页的 `SHA256SUMS`），返回 文件名 → 期望哈希
+///
+/// 算法按哈希长度推断，32 位按 MD5 处理；记录中的目录部分被忽略，只按文件名匹配。
+/// `url` 记为期望哈希的来源，用于界面提示。
+pub fn parse_checksum_list(content: &str, url: &str) -> HashMap<String, ExpectedHash> {
+    let source = ChecksumSource::Url(url.to_string());
+    parse_checksum_entries(content, &source, |name| {
+        Path::new(name)
+            .file_name()
+            .and_then(|n| n.to_str())
+            .map(str::to_string)
+    })
+}
+
+/// 解析本地校验清单，返回 相对清单所在目录的路径（以 `/` 分隔）→ 期望哈希
+///
+/// 不同子目录中的同名文件各自对应自己的记录，见 [`manifest_key`]。
+pub fn parse_manifest(content: &str, source: &Path) -> HashMap<String, ExpectedHash> {
+    let source = ChecksumSource::File(source.to_path_buf());
+    parse_checksum_entries(content, &source, |name| {
+        let name = name.replace('\\', "/");
+        let mut name = name.as_str();
+        while let Some(rest) = name.strip_prefix("./") {
+            name = rest;
+        }
+        Some(name.to_string()).filter(|name| !name.is_empty())
+    })
+}
+
+/// 清单所在目录 `dir` 下的文件在清单中的键：相对路径，各层以 `/` 连接
+pub fn manifest_key(dir: &Path, path: &Path) -> Option<String> {
+    let parts = path
+        .strip_prefix(dir)
+        .ok()?
+        .components()
+        .map(|component| component.as_os_str().to_str())
+        .collect::<Option<Vec<&str>>>()?;
+    Some(parts.join("/"))
+}
+
+fn parse_checksum_entries(
+    content: &str,
+    source: &ChecksumSource,
+    key: impl Fn(&str) -> Option<String>,
+) -> HashMap<String, ExpectedHash> {
+    let mut entries = HashMap::new();
+    for (hash, name) in content.lines().filter_map(parse_checksum_line) {
+        let Some(file_name) = name.and_then(&key) else {
+            continue;
+        };
+        let Some(hex) = normalize_hex(hash) else {
+            continue;
+        };
+        let Some(algorithm) = SIDECAR_PRIORITY
+            .into_iter()
+            .find(|a| a.hex_len() == hex.len())
+        else {
+            continue;
+        };
+        entries.insert(
+            file_name,
+            ExpectedHash {
+                algorithm,
+                hex,
+                sidecar: source.clone(),
+            },
+        );
+    }
+    entries
+}
+
+/// 本地校验清单与其所在目录的比对结果（不涉及哈希计算）
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ManifestReport {
+    pub manifest: PathBuf,
+    /// 目录中存在、但清单中没有列出的文件（可能是后来加入或被篡改的文件）
+    pub untracked: Vec<PathBuf>,
+    /// 清单中列出、但目录中找不到的文件（相对清单所在目录的路径）
+    pub missing: Vec<String>,
+}
+
+/// 清单的签名文件，如 `SHA256SUMS.asc`，不算作未列出的文件
+const MANIFEST_SIGNATURE_EXTENSIONS: [&str; 3] = ["asc", "sig", "gpg"];
+
+/// 遍历清单所在目录（含子目录），找出未列出的文件与缺失的文件
+///
+/// `list` 由 [`parse_manifest`] 解析，按相对路径匹配。隐藏文件与符号链接同样列出，
+/// 被悄悄加入的文件往往正是这类条目。
+pub fn manifest_report(manifest: &Path, list: &HashMap<String, ExpectedHash>) -> ManifestReport {
+    let mut report = ManifestReport {
+        manifest: manifest.to_path_buf(),
+        ..ManifestReport::default()
+    };
+    let Some(dir) = manifest.parent() else {
+        return report;
+    };
+    let manifest_name = manifest.file_name().unwrap_or_default();
+    let is_manifest_or_signature = |path: &Path| {
+        path.parent() == Some(dir)
+            && (path.file_name() == Some(manifest_name)
+                || path.file_stem() == Some(manifest_name)
+                    && path
+                        .extension()
+                        .and_then(|ext| ext.to_str())
+                        .is_some_and(|ext| MANIFEST_SIGNATURE_EXTENSIONS.contains(&ext)))
+    };
+
+    let mut found = HashSet::new();
+    for entry in WalkDir::new(dir)
+        .follow_links(false)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter(|entry| !entry.file_type().is_dir())
+    {
+        let path = entry.path();
+        if is_manifest_or_signature(path) {
+            continue;
+        }
+        match manifest_key(dir, path) {
+            Some(key) if list.contains_key(&key) => {
+                found.insert(key);
+            }
+            _ => report.untracked.push(entry.into_path()),
+        }
+    }
+
+    report.missing = list
+        .keys()
+        .filter(|name| !found.contains(*name))
+        .cloned()
+        .collect();
+    report.missing.sort();
+    report
+}
+
+/// 目录中的校验文件：被校验的文件名 → (校验文件, 算法)
+type DirSidecars = HashMap<OsString, Vec<(PathBuf, HashAlgorithm)>>;
+
+/// 扫描期间查找同名校验文件
+///
+/// 每个目录只列举一次，只记录校验文件，避免为每个文件逐个探测多种后缀。
+#[derive(Default)]
+pub struct SidecarIndex {
+    dirs: Mutex<HashMap<PathBuf, Arc<DirSidecars>>>,
+}
+
+impl SidecarIndex {
+    /// 查找并解析 `path` 的校验文件，多个校验文件时按算法强度优先
+    pub fn lookup(&self, path: &Path) -> Option<ExpectedHash> {
+        let dir = path.parent()?;
+        let file_name = path.file_name()?;
+        let sidecars = self.sidecars_in(dir);
+        let mut candidates = sidecars.get(file_name)?.clone();
+        candidates
+            .sort_by_key(|(_, algorithm)| SIDECAR_PRIORITY.iter().position(|a| a == algorithm));
+
+        candidates.into_iter().find_map(|(sidecar, algorithm)| {
+            let content = match fs::read_to_string(&sidecar) {
+                Ok(content) => content,
+                Err(e) => {
+                    log::warn!("无法读取校验文件: {} - {}", sidecar.display(), e);
+                    return None;
+                }
+            };
+            let hex = parse_sidecar(&content, file_name.to_str()?, algorithm)?;
+            Some(ExpectedHash {
+                algorithm,
+                hex,
+                sidecar: ChecksumSource::File(sidecar),
+            })
+        })
+    }
+
+    fn sidecars_in(&self, dir: &Path) -> Arc<DirSidecars> {
+        if let Some(found) = self
+            .dirs
+            .lock()
+            .ok()
+            .and_then(|dirs| dirs.get(dir).cloned())
+        {
+            return found;
+        }
+
+        // 在锁外列举目录，并行扫描时不阻塞其他线程
+        let mut found = DirSidecars::new();
+        for entry in fs::read_dir(dir).into_iter().flatten().flatten() {
+            let name = entry.file_name();
+            let Some((base, algorithm)) = name.to_str().and_then(parse_sidecar_name) else {
+                continue;
+            };
+            found
+                .entry(OsString::from(base))
+                .or_default()
+                .push((entry.path(), algorithm));
+        }
+
+        let found = Arc::new(found);
+        if let Ok(mut dirs) = self.dirs.lock() {
+            dirs.insert(dir.to_path_buf(), Arc::clone(&found));
+        }
+        found
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn completed_item() -> FileItem {
+        FileItem::completed(
+            "/test/file.iso",
+            13,
+            &[
+                (HashAlgorithm::Crc32, "ec4ac3d0"),
+                (HashAlgorithm::Md5, "65a8e27d8879283831b664bd8b7f0ad4"),
+                (
+                    HashAlgorithm::Sha1,
+                    "0a0a9f2a6772942557ab5355d76af442f8f65e01",
+                ),
+                (HashAlgorithm::Xxh3, "531df2844447dd5077db03842cd75395"),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_normalize_hex() {
+        assert_eq!(
+            normalize_hex("  0xDEADBEEF \n"),
+            Some("deadbeef".to_string())
+        );
+        assert_eq!(
+            normalize_hex("ec4ac3d0  file.iso"),
+            Some("ec4ac3d0".to_string())
+        );
+        assert_eq!(normalize_hex("not-a-hash"), None);
+        assert_eq!(normalize_hex(""), None);
+    }
+
+    #[test]
+    fn test_search_by_hash_lists_all_duplicates() {
+        let mut other = completed_item();
+        other.path = PathBuf::from("/test/other.bin");
+        other.crc32 = "00000000".to_string();
+        other.md5 = "ffffffffffffffffffffffffffffffff".to_string();
+        let files = vec![
+            completed_item(),
+            other,
+            FileItem::new(PathBuf::from("/test/pending.bin"), 1),
+            completed_item(),
+        ];
+
+        assert_eq!(
+            search_by_hash("EC4AC3D0", &files).matches(),
+            [(0, HashAlgorithm::Crc32), (3, HashAlgorithm::Crc32)]
+        );
+        // 32 位同时对应 MD5 与 XXH3
+        assert_eq!(
+            search_by_hash("0x531DF2844447DD5077DB03842CD75395", &files),
+            HashSearch::Found {
+                algorithms: vec![HashAlgorithm::Md5, HashAlgorithm::Xxh3],
+                matches: vec![
+                    (0, HashAlgorithm::Xxh3),
+                    (1, HashAlgorithm::Xxh3),
+                    (3, HashAlgorithm::Xxh3)
+                ],
+            }
+        );
+        assert_eq!(
+            search_by_hash("ffffffffffffffffffffffffffffffff  other.bin", &files).matches(),
+            [(1, HashAlgorithm::Md5)]
+        );
+        assert!(
+            search_by_hash(&"ab".repeat(32), &files)
+                .matches()
+                .is_empty()
+        );
+        assert_eq!(search_by_hash("abc", &files), HashSearch::UnknownLength(3));
+        assert_eq!(search_by_hash("not a hash", &files), HashSearch::Invalid);
+    }
+
+    #[test]
+    fn test_hash_list_flags_known_files() {
+        let mut sha256_hit = completed_item();
+        sha256_hit.path = PathBuf::from("/test/payload.exe");
+        sha256_hit.crc32 = "11111111".to_string();
+        sha256_hit.md5 = "22222222222222222222222222222222".to_string();
+        sha256_hit.sha1 = "3".repeat(40);
+        sha256_hit.xxhash3 = "44444444444444444444444444444444".to_string();
+        sha256_hit.sha256 = "ab".repeat(32);
+        let mut clean = sha256_hit.clone();
+        clean.path = PathBuf::from("/test/clean.txt");
+        clean.sha256 = "cd".repeat(32);
+        let mut pending = completed_item();
+        pending.status = FileStatus::Pending;
+
+        let list = HashList::parse(&format!(
+            "# known bad\n\n{}  payload.exe\n0x65A8E27D8879283831B664BD8B7F0AD4\nnot-a-hash\n12345\n",
+            "AB".repeat(32)
+        ));
+        assert_eq!(list.len(), 2);
+        assert_eq!(list.skipped, 2);
+
+        assert_eq!(list.find(&sha256_hit), Some(HashAlgorithm::Sha256));
+        assert_eq!(list.find(&completed_item()), Some(HashAlgorithm::Md5));
+        assert_eq!(list.find(&clean), None);
+        // 未完成的文件不参与筛查
+        assert_eq!(list.find(&pending), None);
+    }
+
+    #[test]
+    fn test_detects_algorithm_by_length() {
+        let file = completed_item();
+        assert_eq!(
+            verify_against("EC4AC3D0", &file),
+            VerifyOutcome::Match(HashAlgorithm::Crc32)
+        );
+        assert_eq!(
+            verify_against("0a0a9f2a6772942557ab5355d76af442f8f65e01", &file),
+            VerifyOutcome::Match(HashAlgorithm::Sha1)
+        );
+    }
+
+    #[test]
+    fn test_32_char_checks_md5_and_xxh3() {
+        let file = completed_item();
+        assert_eq!(
+            verify_against("65a8e27d8879283831b664bd8b7f0ad4", &file),
+            VerifyOutcome::Match(HashAlgorithm::Md5)
+        );
+        assert_eq!(
+            verify_against("531df2844447dd5077db03842cd75395", &file),
+            VerifyOutcome::Match(HashAlgorithm::Xxh3)
+        );
+        assert_eq!(
+            verify_against("00000000000000000000000000000000", &file),
+            VerifyOutcome::Mismatch
+        );
+    }
+
+    #[test]
+    fn test_unknown_length_and_pending() {
+        let file = completed_item();
+        assert_eq!(verify_against("abc", &file), VerifyOutcome::UnknownFormat);
+
+        let pending = FileItem::new(PathBuf::from("/test/pending"), 1);
+        assert_eq!(
+            verify_against("ec4ac3d0", &pending),
+            VerifyOutcome::NotComputed
+        );
+    }
+
+    #[test]
+    fn test_sidecar_lookup() {
+        let temp = TempDir::new().unwrap();
+        let iso = temp.path().join("movie.iso");
+        fs::write(&iso, b"The quick brown fox jumps over the lazy dog").unwrap();
+        let sha256 = "d7a8fbb307d7809469ca9abcb0082e4f8d5651e46d3cdb762d02d0bf37c9e592";
+        fs::write(
+            temp.path().join("movie.iso.sha256"),
+            format!("{}  movie.iso\n", sha256.to_uppercase()),
+        )
+        .unwrap();
+        // 同时存在较弱算法的校验文件时采用 SHA256
+        fs::write(
+            temp.path().join("movie.iso.md5sum"),
+            "9e107d9d372bb6826bd81d3542a419d6 *movie.iso\n",
+        )
+        .unwrap();
+        let other = temp.path().join("other.bin");
+        fs::write(&other, b"x").unwrap();
+
+        let index = SidecarIndex::default();
+        let expected = index.lookup(&iso).unwrap();
+        assert_eq!(expected.algorithm, HashAlgorithm::Sha256);
+        assert_eq!(expected.hex, sha256);
+        assert_eq!(
+            expected.sidecar,
+            ChecksumSource::File(temp.path().join("movie.iso.sha256"))
+        );
+        assert!(index.lookup(&other).is_none());
+
+        let mut item = FileItem::new(iso, 43);
+        assert_eq!(expected.check(&item), None);
+        item.status = FileStatus::Completed;
+        item.sha256 = sha256.to_string();
+        assert_eq!(expected.check(&item), Some(true));
+        item.sha256 = "0".repeat(64);
+        assert_eq!(expected.check(&item), Some(false));
+    }
+
+    #[test]
+    fn test_parse_sidecar_formats() {
+        assert_eq!(
+            parse_sidecar_name("a.tar.gz.SHA256SUM"),
+            Some(("a.tar.gz", HashAlgorithm::Sha256))
+        );
+        assert_eq!(
+            parse_sidecar_name("disk.img.md5"),
+            Some(("disk.img", HashAlgorithm::Md5))
+        );
+        assert_eq!(parse_sidecar_name(".sha1"), None);
+        assert_eq!(parse_sidecar_name("notes.txt"), None);
+
+        let crc = HashAlgorithm::Crc32;
+        // BSD 格式与多条记录按文件名匹配
+        assert_eq!(
+            parse_sidecar(
+                "CRC32 (b.bin) = 11111111\nCRC32 (a.bin) = 2222AAAA",
+                "a.bin",
+                crc
+            ),
+            Some("2222aaaa".to_string())
+        );
+        assert_eq!(
+            parse_sidecar("11111111  b.bin\n22222222  c.bin", "a.bin", crc),
+            None
+        );
+        // 只有哈希值，或单条记录的文件名已改变
+        assert_eq!(
+            parse_sidecar("ec4ac3d0\n", "a.bin", crc),
+            Some("ec4ac3d0".to_string())
+        );
+        assert_eq!(
+            parse_sidecar("ec4ac3d0  old-name.bin", "a.bin", crc),
+            Some("ec4ac3d0".to_string())
+        );
+        // 长度与算法不符
+        assert_eq!(parse_sidecar("abcd  a.bin", "a.bin", crc), None);
+    }
+
+    #[test]
+    fn test_parse_checksum_list() {
+        let sha = "ab".repeat(32);
+        let content = format!(
+            "# release checksums\n{sha}  dist/app.tar.gz\nMD5 (app.zip) = {md5}\n{sha} *./app.msi\nnot-a-hash  readme.txt\n",
+            md5 = "cd".repeat(16),
+        );
+        let source = "https://example.com/SHA256SUMS?raw=1";
+        let list = parse_checksum_list(&content, source);
+
+        assert_eq!(list.len(), 3);
+        assert_eq!(list["app.tar.gz"].algorithm, HashAlgorithm::Sha256);
+        assert_eq!(
+            list["app.tar.gz"].sidecar,
+            ChecksumSource::Url(source.to_string())
+        );
+        assert_eq!(list["app.tar.gz"].sidecar.name(), "SHA256SUMS");
+        assert_eq!(list["app.tar.gz"].sidecar.to_string(), source);
+        assert_eq!(list["app.zip"].algorithm, HashAlgorithm::Md5);
+        assert_eq!(list["app.msi"].hex, sha);
+    }
+
+    #[test]
+    fn test_manifest_report_lists_untracked_files() {
+        let dir = TempDir::new().unwrap();
+        let release = dir.path().join("release");
+        fs::create_dir_all(release.join("docs")).unwrap();
+        fs::write(release.join("app.tar.gz"), b"app").unwrap();
+        fs::write(release.join("docs").join("guide.pdf"), b"guide").unwrap();
+        fs::write(release.join("extra.exe"), b"not in the manifest").unwrap();
+        fs::write(release.join("SHA256SUMS.asc"), b"signature").unwrap();
+
+        let sha = "ab".repeat(32);
+        let manifest = release.join("SHA256SUMS");
+        fs::write(
+            &manifest,
+            format!("{sha}  app.tar.gz\n{sha}  docs/guide.pdf\n{sha}  removed.zip\n"),
+        )
+        .unwrap();
+        let list = parse_manifest(&fs::read_to_string(&manifest).unwrap(), &manifest);
+
+        let report = manifest_report(&manifest, &list);
+        // 清单本身与其签名文件不算作未列出
+        assert_eq!(report.untracked, [release.join("extra.exe")]);
+        assert_eq!(report.missing, ["removed.zip"]);
+        assert_eq!(report.manifest, manifest);
+    }
+
+    #[test]
+    fn test_manifest_matches_same_name_in_subdirectories() {
+        let dir = TempDir::new().unwrap();
+        let release = dir.path().join("release");
+        for sub in ["linux", "windows", "mac"] {
+            fs::create_dir_all(release.join(sub)).unwrap();
+            fs::write(release.join(sub).join("app.bin"), sub).unwrap();
+        }
+
+        let (linux, windows) = ("ab".repeat(32), "cd".repeat(32));
+        let manifest = release.join("SHA256SUMS");
+        fs::write(
+            &manifest,
+            format!("{linux}  ./linux/app.bin\n{windows} *windows\\app.bin\n"),
+        )
+        .unwrap();
+        let list = parse_manifest(&fs::read_to_string(&manifest).unwrap(), &manifest);
+
+        // 同名文件各自对应所在子目录的记录，未列出的子目录中的同名文件不算作已列出
+        let key = |sub: &str| manifest_key(&release, &release.join(sub).join("app.bin")).unwrap();
+        assert_eq!(list[&key("linux")].hex, linux);
+        assert_eq!(list[&key("windows")].hex, windows);
+        assert!(!list.contains_key(&key("mac")));
+
+        let report = manifest_report(&manifest, &list);
+        assert_eq!(report.untracked, [release.join("mac").join("app.bin")]);
+        assert!(report.missing.is_empty());
+    }
+}
@@ -2,76 +2,358 @@
 
 #![allow(clippy::cast_possible_truncation)]
 
-use crossbeam_channel::{Receiver, Sender, bounded};
+use crossbeam_channel::{Receiver, Sender, bounded, unbounded};
 use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
-use std::thread;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
 
-use crate::cache::{CacheEntry, HashCache, get_file_modified_time};
-use crate::engine::{ProgressUpdate, compute_all_hashes_cached, compute_xxhash3_only};
-use crate::scanner::FileScanner;
+use crate::analysis::{AuditFinding, AuditReport, FileComparison};
+use crate::benchmark::{self, BenchmarkReport};
+use crate::cache::{
+    CacheConfig, CacheEntry, CacheValidation, CleanupKind, CleanupOutcome, CleanupProgress,
+    ComputeOrder, HashCache, SizeUnit, SymlinkPolicy, VacuumSchedule, get_file_modified_time,
+    metadata_modified_time, unix_now,
+};
+use crate::engine::{
+    ChunkedHashes, FileHashes, IoStrategy, ProgressUpdate, ROTATIONAL_THREADS, RemovableVolumes,
+    VolumeId, compute_all_hashes_cached, compute_chunk_hashes, compute_directory_hash,
+    compute_file_hash, compute_file_hash_exact, compute_link_hash, compute_sparse_hash,
+    compute_xxhash3_only, compute_xxhash3_sparse, content_len, ensure_not_special, is_block_device,
+    is_network_path, is_removable_path, is_rotational_path, is_special_file, is_symlink,
+    quick_hash, same_volume, volume_id, with_long_path,
+};
+use crate::error::{HashError, HashResult, IoErrorContext};
+use crate::hash::{EnabledHashes, HashAlgorithm};
+use crate::rename::{RenameOutcome, RenamePlanEntry, apply_plan, plan_renames};
+use crate::scanner::{DiscoveredFile, FileScanner, ScanOptions};
+use crate::update::UpdateInfo;
+use crate::verify::{
+    ExpectedHash, ManifestReport, manifest_report, parse_checksum_list, parse_manifest,
+};
+use crate::xattrs::{XattrReport, XattrTarget};
 
 /// UI发送给工作线程的消息
 #[cfg_attr(test, derive(Debug))]
 pub enum WorkerMessage {
-    Compute(Vec<PathBuf>),
+    // batch_id 为 UI 分配的批次编号，该批次的所有结果消息都携带同一编号；
+    // force 为强制重新计算：不采用缓存，结果与原有缓存不同时发送 HashChanged；
+    // 文件附带 UI 已知的大小，按大小排序时无需再逐个读取元数据
+    Compute {
+        batch_id: u64,
+        files: Vec<(PathBuf, u64)>,
+        force: bool,
+    },
     Scan(Vec<PathBuf>),
+    // 计算整个目录内容的合并哈希
+    DirectoryHash(PathBuf),
+    // 按固定大小分块计算单个文件的哈希列表，设置取消标志后停止读取
+    ChunkHash {
+        path: PathBuf,
+        chunk_size: u64,
+        cancel: Arc<AtomicBool>,
+    },
+    // 对比两个文件是否相同
+    CompareFiles([PathBuf; 2]),
+    // 重新核对目录下的缓存条目
+    AuditCache(PathBuf),
+    // 性能测试，None 表示使用生成的临时文件
+    Benchmark(Option<PathBuf>),
+    // 分批清理缓存，设置取消标志后在下一批之前停止
+    CleanupCache(CleanupKind, Arc<AtomicBool>),
     SaveCache(Vec<CacheEntry>),
+    // 批处理队列中已处理完的文件
+    MarkBatchDone(Vec<PathBuf>),
+    // 批处理全部完成或被放弃
+    ClearBatchQueue,
+    // 恢复上次未完成的批处理：读取文件大小后直接加入列表，无需重新扫描
+    ResumeBatch(Vec<PathBuf>),
+    // 找出最近记录中已不存在的路径；离线的网络路径可能要等待很久，不能在界面线程检查
+    CheckRecentPaths(Vec<PathBuf>),
+    // 读取新加入文件的备注
+    LoadNotes(Vec<PathBuf>),
+    // 保存单个文件的备注，空字符串表示删除
+    SaveNote(PathBuf, String),
+    // 刷新元数据：在后台重新读取文件大小与修改时间，避免阻塞界面
+    ReadMetadata(Vec<PathBuf>),
+    // 刷新元数据时发现已变更的文件，删除其缓存条目
+    InvalidateCache(Vec<PathBuf>),
+    // 生成按哈希重命名的试运行计划，(路径, SHA256)
+    PlanRename(Vec<(PathBuf, String)>),
+    // 执行用户确认过的重命名计划
+    ApplyRename(Vec<RenamePlanEntry>),
+    // 下载并解析 URL 上的校验清单
+    FetchChecksums(String),
+    // 下载版本清单检查更新；`manual` 为用户在设置中点击检查
+    CheckUpdate {
+        url: String,
+        manual: bool,
+    },
+    // 读取本地校验清单，并找出其所在目录中未列出与缺失的文件
+    VerifyManifest(PathBuf),
+    // 将哈希写入文件扩展属性，`overwrite` 为 false 时不覆盖已有的不同值
+    WriteXattrs {
+        targets: Vec<XattrTarget>,
+        overwrite: bool,
+    },
+    // 「高级重算」：用指定的缓冲区与映射块大小单独计算一个文件，不读写缓存
+    Rehash(RehashParams),
+    // 停止当前批次：尚未开始的文件不再计算，正在计算的文件结果由 UI 丢弃
     Cancel,
+    // 等待所有缓存保存完成后回复并退出，之后按 `vacuum` 在后台整理数据库
+    Shutdown {
+        ack: Sender<()>,
+        vacuum: VacuumSchedule,
+    },
 }
 
 /// 工作线程发送给UI的消息
 #[cfg_attr(test, derive(Debug))]
 pub enum UiMessage {
     FileStarted {
+        batch_id: u64,
         path: PathBuf,
     },
     // 仅用于更新UI显示的哈希值，不作为完成信号
     Xxhash3Computed {
+        batch_id: u64,
         path: PathBuf,
         xxhash3: String,
     },
     FileCompleted {
+        batch_id: u64,
         path: PathBuf,
         crc32: String,
         md5: String,
         sha1: String,
+        sha256: String,
+        crc64: String,
+        adler32: String,
         xxhash3: String, // 确保包含所有数据
         duration_ms: u64,
         modified_time: u64,
         file_size: u64,
         from_cache: bool, // 明确标记是否来自缓存
+        // 仅新增/变更模式下按元数据判定未变更，未读取文件内容
+        unchanged: bool,
+        // 计算时间（Unix 秒），命中缓存时为写入缓存的时间
+        computed_at: u64,
     },
     FileFailed {
+        batch_id: u64,
+        path: PathBuf,
+        // 失败原因，显示在状态列的悬停提示中
+        error: String,
+    },
+    // 强制重新计算的结果与原有缓存不同，随后仍发送 FileCompleted
+    HashChanged {
+        batch_id: u64,
         path: PathBuf,
+        old: FileHashes,
+        new: FileHashes,
+        // 大小或修改时间也已变化，多半是正常修改；未变化时可能是静默损坏
+        metadata_changed: bool,
     },
-    FilesDiscovered(Vec<(PathBuf, u64)>), // 批量文件发现 (路径, 大小)
+    // 扫描后、计算前文件已被删除或移走
+    FileMissing {
+        batch_id: u64,
+        path: PathBuf,
+    },
+    // 快速预筛中没有重复候选的文件，不再做完整计算
+    QuickHashed {
+        batch_id: u64,
+        path: PathBuf,
+        quick_hash: String,
+    },
+    FilesDiscovered(Vec<DiscoveredFile>), // 批量文件发现
+    // 恢复的批处理中仍然存在的文件，加入后立即开始计算
+    BatchResumed(Vec<DiscoveredFile>),
+    // 刷新元数据时读取到的文件状态，None 表示文件已不存在
+    MetadataRead(Vec<(PathBuf, Option<FileStat>)>),
+    // 最近记录中已不存在的路径
+    RecentPathsMissing(Vec<PathBuf>),
     Progress {
+        batch_id: u64,
+        path: PathBuf,
+        processed: u64,
+        total: u64,
+    },
+    // 缓存保存完成通知，附带本次写入的耗时
+    CacheSaved {
+        duration_ms: u64,
+    },
+    // 缓存数据库与本批次的文件位于同一块慢速磁盘，root 为批次的公共上级目录
+    CacheContention {
+        batch_id: u64,
+        root: PathBuf,
+    },
+    // 一次扫描请求中的所有路径已遍历完毕
+    ScanFinished,
+    AllCompleted {
+        batch_id: u64,
+    },
+    DirectoryHashed {
+        root: PathBuf,
+        result: Result<FileHashes, String>,
+    },
+    ChunkHashProgress {
         path: PathBuf,
+        chunk_size: u64,
         processed: u64,
         total: u64,
     },
-    CacheSaved, // 缓存保存完成通知
-    AllCompleted,
+    ChunksHashed {
+        path: PathBuf,
+        result: Result<ChunkedHashes, String>,
+    },
+    FilesCompared(Result<FileComparison, String>),
+    CacheAudited {
+        root: PathBuf,
+        result: Result<AuditReport, String>,
+    },
+    BenchmarkFinished(Result<BenchmarkReport, String>),
+    NotesLoaded(HashMap<PathBuf, String>),
+    CleanupProgress(CleanupProgress),
+    CleanupFinished(Result<CleanupOutcome, String>),
+    RenamePlanned(Vec<RenamePlanEntry>),
+    RenameApplied(Vec<RenameOutcome>),
+    // 文件名 → 期望哈希
+    ChecksumsFetched(Result<HashMap<String, ExpectedHash>, String>),
+    // 相对清单所在目录的路径 → 期望哈希
+    ManifestChecked(Result<(HashMap<String, ExpectedHash>, ManifestReport), String>),
+    XattrsWritten(XattrReport),
+    Rehashed(Result<RehashReport, String>),
+    UpdateChecked {
+        manual: bool,
+        result: Result<Option<UpdateInfo>, String>,
+    },
+}
+
+/// 刷新元数据时读取到的文件大小与修改时间
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FileStat {
+    pub size: u64,
+    pub modified_time: Option<u64>,
+}
+
+/// 「高级重算」使用的参数，覆盖设置中的性能参数
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RehashParams {
+    pub path: PathBuf,
+    pub buffer_size: usize,
+    pub mmap_chunk_size: usize,
+    /// 不使用内存映射，始终缓冲读取
+    pub force_buffered: bool,
+}
+
+/// 「高级重算」的结果
+#[derive(Debug, Clone)]
+pub struct RehashReport {
+    pub params: RehashParams,
+    /// 按文件大小选择的读取路径，小文件整体读取时不使用缓冲区与映射块大小
+    pub strategy: IoStrategy,
+    pub file_size: u64,
+    pub duration_ms: u64,
+    pub hashes: FileHashes,
+}
+
+impl UiMessage {
+    /// 计算批次中产生的消息所属的批次编号，其他消息返回 None
+    ///
+    /// 停止计算后仍在运行的任务会继续发送消息，UI 据此丢弃已取消批次的消息。
+    pub fn batch_id(&self) -> Option<u64> {
+        match self {
+            UiMessage::FileStarted { batch_id, .. }
+            | UiMessage::Xxhash3Computed { batch_id, .. }
+            | UiMessage::FileCompleted { batch_id, .. }
+            | UiMessage::FileFailed { batch_id, .. }
+            | UiMessage::FileMissing { batch_id, .. }
+            | UiMessage::HashChanged { batch_id, .. }
+            | UiMessage::QuickHashed { batch_id, .. }
+            | UiMessage::Progress { batch_id, .. }
+            | UiMessage::CacheContention { batch_id, .. }
+            | UiMessage::AllCompleted { batch_id } => Some(*batch_id),
+            _ => None,
+        }
+    }
+
+    /// 是否属于 `current` 之前的批次（已停止、已清空或已被新批次取代）
+    pub fn is_stale(&self, current: u64) -> bool {
+        self.batch_id().is_some_and(|batch_id| batch_id != current)
+    }
+}
+
+/// 按校验级别核对缓存条目的结果
+#[derive(Debug, PartialEq)]
+enum CacheLookup {
+    /// 缓存可信，附带采用的哈希
+    Hit(FileHashes),
+    /// 元数据不符或内容校验失败，需要重新计算
+    Miss,
+    /// 严格校验重新计算的哈希与缓存不同，附带本次计算值
+    Changed(FileHashes),
+}
+
+/// 缓存审计中单个条目的核对结果
+#[derive(Debug, PartialEq)]
+enum AuditOutcome {
+    /// 文件已不存在
+    Missing,
+    /// 与缓存一致
+    Consistent,
+    Finding(AuditFinding),
+}
+
+/// 一个计算批次中所有文件共用的状态
+struct BatchContext<'a> {
+    batch_id: u64,
+    /// 强制重新计算，不采用缓存
+    force: bool,
+    active_batch: &'a AtomicU64,
+    ui_tx: &'a Sender<UiMessage>,
+    multiplexor_tx: &'a Sender<MultiplexorMessage>,
+    cache: &'a Arc<Mutex<HashCache>>,
+    config: CacheConfig,
+    /// 批次开始时一次查出的缓存条目
+    cache_map: HashMap<PathBuf, Option<CacheEntry>>,
+    removable: RemovableVolumes,
 }
 
 enum MultiplexorMessage {
     Register {
+        batch_id: u64,
         path: PathBuf,
         progress_rx: Receiver<ProgressUpdate>,
     },
 }
 
+/// 发往 UI 的消息通道的最小容量（实际容量见 `CacheConfig::ui_channel_capacity`）
+///
+/// 通道满时扫描、计算线程会阻塞在发送上，worker 的吞吐被限制在「UI 每帧取走的条数 × 帧率」，
+/// 小文件批次每个文件约 3 条消息。进度与随后会被 `FileCompleted` 覆盖的 `Xxhash3Computed`
+/// 可以合并，通道满时直接丢弃，不阻塞计算。
+pub const MIN_UI_CHANNEL_CAPACITY: usize = 64;
+
+/// 退出时整理数据库的线程，窗口关闭后由 `main` 等待其结束
+static EXIT_VACUUM: Mutex<Option<JoinHandle<()>>> = Mutex::new(None);
+
 pub struct WorkerThread {}
 
 impl WorkerThread {
     pub fn spawn(
         cache: Arc<Mutex<HashCache>>,
     ) -> (Self, Sender<WorkerMessage>, Receiver<UiMessage>) {
+        let capacity = cache
+            .lock()
+            .map_or(CacheConfig::default().ui_channel_capacity, |cache_guard| {
+                cache_guard.config().ui_channel_capacity
+            })
+            .max(MIN_UI_CHANNEL_CAPACITY);
         let (worker_tx, worker_rx) = bounded(16);
-        let (ui_tx, ui_rx) = bounded(64);
+        let (ui_tx, ui_rx) = bounded(capacity);
         let (multiplexor_tx, multiplexor_rx) = bounded(128);
 
         let ui_tx_for_multiplexor = ui_tx.clone();
@@ -81,8 +363,14 @@ impl WorkerThread {
 
         let scanner = FileScanner::spawn(ui_tx.clone());
 
+        let (notes_tx, notes_rx) = unbounded();
+        if let Ok(pool) = cache.lock().map(|guard| guard.clone()) {
+            let ui_tx = ui_tx.clone();
+            thread::spawn(move || Self::run_notes_loader(&notes_rx, &pool, &ui_tx));
+        }
+
         thread::spawn(move || {
-            Self::run(worker_rx, ui_tx, multiplexor_tx, cache, scanner);
+            Self::run(worker_rx, ui_tx, multiplexor_tx, &notes_tx, cache, scanner);
         });
 
         (WorkerThread {}, worker_tx, ui_rx)
@@ -92,7 +380,8 @@ impl WorkerThread {
         multiplexor_rx: Receiver<MultiplexorMessage>,
         ui_tx: Sender<UiMessage>,
     ) {
-        let mut progress_channels: HashMap<PathBuf, Receiver<ProgressUpdate>> = HashMap::new();
+        let mut progress_channels: HashMap<PathBuf, (u64, Receiver<ProgressUpdate>)> =
+            HashMap::new();
         // 限制进度更新频率：每16ms（约60fps）才发送一次UI更新
         let mut last_ui_update = std::time::Instant::now();
 
@@ -100,16 +389,24 @@ impl WorkerThread {
             // 处理新注册
             while let Ok(msg) = multiplexor_rx.try_recv() {
                 match msg {
-                    MultiplexorMessage::Register { path, progress_rx } => {
-                        progress_channels.insert(path, progress_rx);
+                    MultiplexorMessage::Register {
+                        batch_id,
+                        path,
+                        progress_rx,
+                    } => {
+                        progress_channels.insert(path, (batch_id, progress_rx));
                     }
                 }
             }
 
             if progress_channels.is_empty() {
                 match multiplexor_rx.recv() {
-                    Ok(MultiplexorMessage::Register { path, progress_rx }) => {
-                        progress_channels.insert(path, progress_rx);
+                    Ok(MultiplexorMessage::Register {
+                        batch_id,
+                        path,
+                        progress_rx,
+                    }) => {
+                        progress_channels.insert(path, (batch_id, progress_rx));
                     }
                     Err(_) => return,
                 }
@@ -118,11 +415,13 @@ impl WorkerThread {
             let mut completed_paths = Vec::new();
             let should_send_update = last_ui_update.elapsed().as_millis() >= 32; // 降至30fps以减轻UI压力
 
-            for (path, progress_rx) in &progress_channels {
+            for (path, (batch_id, progress_rx)) in &progress_channels {
                 match progress_rx.try_recv() {
                     Ok(progress) => {
+                        // 通道已满时丢弃，下一次更新会覆盖这一次
                         if should_send_update {
-                            let _ = ui_tx.send(UiMessage::Progress {
+                            let _ = ui_tx.try_send(UiMessage::Progress {
+                                batch_id: *batch_id,
                                 path: path.clone(),
                                 processed: progress.processed,
                                 total: progress.total,
@@ -148,10 +447,32 @@ impl WorkerThread {
         }
     }
 
+    /// 读取新加入文件的备注，所有请求由这一个线程依次处理，积压的请求合并为一次查询
+    ///
+    /// 使用连接池句柄的克隆，规范化路径需要访问文件系统，期间不占用缓存锁。
+    fn run_notes_loader(
+        notes_rx: &Receiver<Vec<PathBuf>>,
+        pool: &HashCache,
+        ui_tx: &Sender<UiMessage>,
+    ) {
+        while let Ok(mut paths) = notes_rx.recv() {
+            paths.extend(notes_rx.try_iter().flatten());
+            let refs: Vec<&Path> = paths.iter().map(PathBuf::as_path).collect();
+            match pool.get_notes(&refs) {
+                Ok(notes) if !notes.is_empty() => {
+                    let _ = ui_tx.send(UiMessage::NotesLoaded(notes));
+                }
+                Ok(_) => {}
+                Err(e) => log::error!("读取文件备注失败: {}", e),
+            }
+        }
+    }
+
     fn run(
         worker_rx: Receiver<WorkerMessage>,
         ui_tx: Sender<UiMessage>,
         multiplexor_tx: Sender<MultiplexorMessage>,
+        notes_tx: &Sender<Vec<PathBuf>>,
         cache: Arc<Mutex<HashCache>>,
         scanner: FileScanner,
     ) {
@@ -162,175 +483,2629 @@ impl WorkerThread {
             .build_global()
             .ok();
 
+        let mut save_handles: Vec<JoinHandle<()>> = Vec::new();
+        // 正在进行的批次编号，0 表示已取消或空闲
+        let active_batch = Arc::new(AtomicU64::new(0));
+        // 通道已满时尚未报告的缓存写入耗时，随下一次 CacheSaved 一并送达
+        let unreported_save_ms = Arc::new(AtomicU64::new(0));
+
         while let Ok(msg) = worker_rx.recv() {
+            save_handles.retain(|handle| !handle.is_finished());
+
             match msg {
-                WorkerMessage::Compute(files) => {
-                    // 启动独立的计算线程，不阻塞 Worker 接收其他消息（如 Scan, SaveCache）
-                    let ui_tx = ui_tx.clone();
-                    let multiplexor_tx = multiplexor_tx.clone();
-                    let cache = cache.clone();
-
-                    thread::spawn(move || {
-                        Self::compute_batch(files, &ui_tx, &multiplexor_tx, &cache);
-                    });
+                WorkerMessage::Compute {
+                    batch_id,
+                    files,
+                    force,
+                } => {
+                    active_batch.store(batch_id, Ordering::Relaxed);
+                    Self::handle_compute(
+                        batch_id,
+                        files,
+                        force,
+                        &active_batch,
+                        &ui_tx,
+                        &multiplexor_tx,
+                        &cache,
+                    );
+                }
+                WorkerMessage::Scan(paths) => Self::handle_scan(paths, &scanner, &cache),
+                WorkerMessage::DirectoryHash(root) => {
+                    Self::handle_directory_hash(root, &ui_tx, &cache);
                 }
-                WorkerMessage::Scan(paths) => {
-                    scanner.scan(paths);
+                WorkerMessage::ChunkHash {
+                    path,
+                    chunk_size,
+                    cancel,
+                } => Self::handle_chunk_hash(path, chunk_size, cancel, &ui_tx),
+                WorkerMessage::AuditCache(root) => Self::handle_audit_cache(root, &ui_tx, &cache),
+                WorkerMessage::Benchmark(source) => {
+                    Self::handle_benchmark(source, &ui_tx, &cache);
+                }
+                WorkerMessage::CleanupCache(kind, cancel) => {
+                    Self::handle_cleanup_cache(kind, cancel, &ui_tx, &cache);
+                }
+                WorkerMessage::Rehash(params) => Self::handle_rehash(params, &ui_tx, &cache),
+                WorkerMessage::CompareFiles(paths) => {
+                    Self::handle_compare_files(paths, &ui_tx, &cache);
                 }
                 WorkerMessage::SaveCache(entries) => {
-                    let cache = cache.clone();
-                    let ui_tx = ui_tx.clone();
-                    // 在独立线程中保存，避免阻塞 Worker 循环或计算
-                    thread::spawn(move || {
-                        if let Ok(guard) = cache.lock() {
-                            if let Err(e) = guard.save_entries_batch(&entries) {
-                                eprintln!("[Worker] 保存缓存失败: {}", e);
-                            } else {
-                                let _ = ui_tx.send(UiMessage::CacheSaved);
-                            }
-                        }
-                    });
+                    save_handles.push(Self::handle_save_cache(
+                        entries,
+                        &ui_tx,
+                        &cache,
+                        &unreported_save_ms,
+                    ));
+                }
+                WorkerMessage::MarkBatchDone(paths) => Self::handle_mark_batch_done(&paths, &cache),
+                WorkerMessage::ClearBatchQueue => Self::handle_clear_batch_queue(&cache),
+                WorkerMessage::ResumeBatch(paths) => {
+                    Self::handle_resume_batch(paths, &ui_tx, &cache);
+                }
+                WorkerMessage::ReadMetadata(paths) => Self::handle_read_metadata(paths, &ui_tx),
+                WorkerMessage::CheckRecentPaths(paths) => {
+                    Self::handle_check_recent_paths(paths, &ui_tx);
+                }
+                WorkerMessage::LoadNotes(paths) => {
+                    let _ = notes_tx.send(paths);
+                }
+                WorkerMessage::SaveNote(path, note) => Self::handle_save_note(&path, &note, &cache),
+                WorkerMessage::InvalidateCache(paths) => {
+                    Self::handle_invalidate_cache(&paths, &cache);
+                }
+                WorkerMessage::PlanRename(files) => Self::handle_plan_rename(files, &ui_tx, &cache),
+                WorkerMessage::ApplyRename(plan) => Self::handle_apply_rename(plan, &ui_tx, &cache),
+                WorkerMessage::FetchChecksums(url) => {
+                    Self::handle_fetch_checksums(url, &ui_tx, &cache);
+                }
+                WorkerMessage::CheckUpdate { url, manual } => {
+                    Self::handle_check_update(url, manual, &ui_tx, &cache);
+                }
+                WorkerMessage::VerifyManifest(manifest) => {
+                    Self::handle_verify_manifest(manifest, &ui_tx);
+                }
+                WorkerMessage::WriteXattrs { targets, overwrite } => {
+                    Self::handle_write_xattrs(targets, overwrite, &ui_tx);
                 }
                 WorkerMessage::Cancel => {
-                    // No-op for API compatibility
+                    // 正在读取的文件无法中断，只阻止尚未开始的文件
+                    active_batch.store(0, Ordering::Relaxed);
+                }
+                WorkerMessage::Shutdown { ack, vacuum } => {
+                    Self::handle_shutdown(&mut save_handles, vacuum, &cache);
+                    let _ = ack.send(());
+                    return;
                 }
             }
         }
     }
 
-    fn compute_batch(
-        files: Vec<PathBuf>,
+    /// 启动独立的计算线程，不阻塞 Worker 接收其他消息（如 `Scan`, `SaveCache`）
+    fn handle_compute(
+        batch_id: u64,
+        files: Vec<(PathBuf, u64)>,
+        force: bool,
+        active_batch: &Arc<AtomicU64>,
         ui_tx: &Sender<UiMessage>,
         multiplexor_tx: &Sender<MultiplexorMessage>,
         cache: &Arc<Mutex<HashCache>>,
     ) {
-        use rayon::prelude::*;
+        let ui_tx = ui_tx.clone();
+        let multiplexor_tx = multiplexor_tx.clone();
+        let cache = cache.clone();
+        let active_batch = Arc::clone(active_batch);
 
-        let (buffer_size, mmap_chunk_size) = if let Ok(cache_guard) = cache.lock() {
-            (
-                cache_guard.get_buffer_size(),
-                cache_guard.get_mmap_chunk_size(),
-            )
+        thread::spawn(move || {
+            // 先持久化文件列表，崩溃后可以跳过扫描直接恢复；写完才开始计算，
+            // 之后的 MarkBatchDone 总能找到对应的记录
+            if active_batch.load(Ordering::Relaxed) != batch_id {
+                return;
+            }
+            let order = cache
+                .lock()
+                .map_or(ComputeOrder::Added, |guard| guard.config().compute_order);
+            let files = Self::order_files(files, order);
+            if let Ok(cache_guard) = cache.lock() {
+                if let Err(e) = cache_guard.enqueue_batch(&files) {
+                    log::error!("保存批处理队列失败: {}", e);
+                }
+            }
+            Self::compute_batch(
+                files,
+                batch_id,
+                force,
+                &active_batch,
+                &ui_tx,
+                &multiplexor_tx,
+                &cache,
+            );
+        });
+    }
+
+    fn handle_scan(paths: Vec<PathBuf>, scanner: &FileScanner, cache: &Arc<Mutex<HashCache>>) {
+        let options = if let Ok(cache_guard) = cache.lock() {
+            ScanOptions::from_config(cache_guard.config())
         } else {
-            (256 * 1024, 4 * 1024 * 1024)
+            ScanOptions::default()
         };
+        scanner.scan(paths, options);
+    }
 
-        let cache_map: HashMap<PathBuf, Option<CacheEntry>> = if let Ok(cache_guard) = cache.lock()
-        {
-            let paths: Vec<&PathBuf> = files.iter().collect();
-            let path_refs: Vec<&Path> = paths.iter().map(|p| p.as_path()).collect();
-            cache_guard
-                .get_by_paths_batch(&path_refs)
-                .unwrap_or_default()
+    fn handle_directory_hash(
+        root: PathBuf,
+        ui_tx: &Sender<UiMessage>,
+        cache: &Arc<Mutex<HashCache>>,
+    ) {
+        let (buffer_size, enabled) = if let Ok(cache_guard) = cache.lock() {
+            let config = cache_guard.config();
+            (config.buffer_size, config.enabled_hashes)
         } else {
-            HashMap::new()
+            let config = CacheConfig::default();
+            (config.buffer_size, config.enabled_hashes)
         };
+        let ui_tx = ui_tx.clone();
+        thread::spawn(move || {
+            let result =
+                compute_directory_hash(&root, buffer_size, enabled).map_err(|e| e.to_string());
+            let _ = ui_tx.send(UiMessage::DirectoryHashed { root, result });
+        });
+    }
 
-        files.par_iter().for_each(|path| {
-            let start = std::time::Instant::now();
-            let _ = ui_tx.send(UiMessage::FileStarted { path: path.clone() });
-
-            let (progress_tx, progress_rx) = bounded(32);
-            let _ = multiplexor_tx.send(MultiplexorMessage::Register {
-                path: path.clone(),
-                progress_rx,
+    fn handle_chunk_hash(
+        path: PathBuf,
+        chunk_size: u64,
+        cancel: Arc<AtomicBool>,
+        ui_tx: &Sender<UiMessage>,
+    ) {
+        let ui_tx = ui_tx.clone();
+        thread::spawn(move || {
+            let (progress_tx, progress_rx) = bounded::<ProgressUpdate>(16);
+            let result = thread::scope(|scope| {
+                scope.spawn(|| {
+                    for update in &progress_rx {
+                        let _ = ui_tx.try_send(UiMessage::ChunkHashProgress {
+                            path: path.clone(),
+                            chunk_size,
+                            processed: update.processed,
+                            total: update.total,
+                        });
+                    }
+                });
+                let result = compute_chunk_hashes(&path, chunk_size, Some(&progress_tx), &cancel);
+                drop(progress_tx);
+                result
             });
+            // 已取消的请求由 UI 换成了新的请求，不再发送结果
+            if !cancel.load(Ordering::Relaxed) {
+                let result = result.map_err(|e| e.to_string());
+                let _ = ui_tx.send(UiMessage::ChunksHashed { path, result });
+            }
+        });
+    }
 
-            let (file_size, modified_time, metadata_valid) =
-                if let Ok(metadata) = fs::metadata(path) {
-                    if let Ok(mtime) = get_file_modified_time(path) {
-                        (metadata.len(), mtime, true)
-                    } else {
-                        (metadata.len(), 0, false)
-                    }
-                } else {
-                    let _ = ui_tx.send(UiMessage::FileFailed { path: path.clone() });
-                    return;
-                };
+    fn handle_audit_cache(root: PathBuf, ui_tx: &Sender<UiMessage>, cache: &Arc<Mutex<HashCache>>) {
+        let ui_tx = ui_tx.clone();
+        let cache = cache.clone();
+        thread::spawn(move || {
+            let result = Self::audit_cache(&root, &cache).map_err(|e| e.to_string());
+            let _ = ui_tx.send(UiMessage::CacheAudited { root, result });
+        });
+    }
 
-            let cache_entry = cache_map.get(path).and_then(|entry| entry.as_ref());
+    fn handle_benchmark(
+        source: Option<PathBuf>,
+        ui_tx: &Sender<UiMessage>,
+        cache: &Arc<Mutex<HashCache>>,
+    ) {
+        let ui_tx = ui_tx.clone();
+        let config = if let Ok(cache_guard) = cache.lock() {
+            cache_guard.config().clone()
+        } else {
+            CacheConfig::default()
+        };
+        thread::spawn(move || {
+            let result = benchmark::run(source, &config).map_err(|e| e.to_string());
+            let _ = ui_tx.send(UiMessage::BenchmarkFinished(result));
+        });
+    }
 
-            if let Some(entry) = cache_entry {
-                if metadata_valid
-                    && HashCache::is_valid_with_metadata(entry, file_size, modified_time)
-                {
-                    match compute_xxhash3_only(
-                        path,
-                        Some(&progress_tx),
-                        buffer_size,
-                        mmap_chunk_size,
-                    ) {
-                        Ok((computed_xxhash3, _)) => {
-                            if HashCache::validate_cache_integrity(
-                                entry,
-                                &computed_xxhash3,
-                                file_size,
-                                modified_time,
-                            ) {
-                                if let Ok(cache_guard) = cache.lock() {
-                                    if let Ok(true) = cache_guard.verify_cached_hashes(entry) {
-                                        eprintln!("[Cache] ✓ 缓存命中: {}", path.display());
-                                        let _ = ui_tx.send(UiMessage::Xxhash3Computed {
-                                            path: path.clone(),
-                                            xxhash3: computed_xxhash3.clone(),
-                                        });
-                                        let _ = ui_tx.send(UiMessage::FileCompleted {
-                                            path: path.clone(),
-                                            crc32: entry.crc32.clone(),
-                                            md5: entry.md5.clone(),
-                                            sha1: entry.sha1.clone(),
-                                            xxhash3: computed_xxhash3,
-                                            duration_ms: start.elapsed().as_millis() as u64,
-                                            modified_time,
-                                            file_size,
-                                            from_cache: true,
-                                        });
-                                        return;
-                                    }
-                                }
-                            }
-
-                            eprintln!("[Cache] ✗ 缓存失效: {}", path.display());
-                            if let Ok(cache_guard) = cache.lock() {
-                                let _ = cache_guard.invalidate_entry(path);
-                            }
-                        }
-                        Err(_e) => {
-                            let _ = ui_tx.send(UiMessage::FileFailed { path: path.clone() });
-                            return;
-                        }
+    fn handle_cleanup_cache(
+        kind: CleanupKind,
+        cancel: Arc<AtomicBool>,
+        ui_tx: &Sender<UiMessage>,
+        cache: &Arc<Mutex<HashCache>>,
+    ) {
+        let ui_tx = ui_tx.clone();
+        // 克隆连接池句柄，清理期间不占用缓存锁，设置窗口等仍可正常读取
+        let Ok(pool) = cache.lock().map(|guard| guard.clone()) else {
+            return;
+        };
+        thread::spawn(move || {
+            let result = pool
+                .cleanup(kind, &cancel, |progress| {
+                    let _ = ui_tx.try_send(UiMessage::CleanupProgress(progress));
+                })
+                .map_err(|e| e.to_string());
+            let _ = ui_tx.send(UiMessage::CleanupFinished(result));
+        });
+    }
+
+    fn handle_rehash(
+        params: RehashParams,
+        ui_tx: &Sender<UiMessage>,
+        cache: &Arc<Mutex<HashCache>>,
+    ) {
+        let ui_tx = ui_tx.clone();
+        let cache = cache.clone();
+        thread::spawn(move || {
+            let result = Self::rehash(params, &cache).map_err(|e| e.to_string());
+            let _ = ui_tx.send(UiMessage::Rehashed(result));
+        });
+    }
+
+    fn handle_compare_files(
+        paths: [PathBuf; 2],
+        ui_tx: &Sender<UiMessage>,
+        cache: &Arc<Mutex<HashCache>>,
+    ) {
+        let ui_tx = ui_tx.clone();
+        let cache = cache.clone();
+        thread::spawn(move || {
+            let result = Self::compare_files(paths, &cache).map_err(|e| e.to_string());
+            let _ = ui_tx.send(UiMessage::FilesCompared(result));
+        });
+    }
+
+    /// 在独立线程中保存，避免阻塞 Worker 循环或计算
+    fn handle_save_cache(
+        mut entries: Vec<CacheEntry>,
+        ui_tx: &Sender<UiMessage>,
+        cache: &Arc<Mutex<HashCache>>,
+        unreported_save_ms: &Arc<AtomicU64>,
+    ) -> JoinHandle<()> {
+        let cache = cache.clone();
+        let ui_tx = ui_tx.clone();
+        let unreported_save_ms = Arc::clone(unreported_save_ms);
+        thread::spawn(move || {
+            let config = match cache.lock() {
+                Ok(guard) => guard.config().clone(),
+                Err(_) => return,
+            };
+            // 在取得写入用的锁之前筛选，按卷查询可移动介质
+            let removable = RemovableVolumes::default();
+            entries.retain(|entry| Self::cacheable(&entry.path, &config, &removable));
+            if let Ok(guard) = cache.lock() {
+                let start = std::time::Instant::now();
+                if let Err(e) = guard.save_entries_batch(&entries) {
+                    log::error!("保存缓存失败: {}", e);
+                } else {
+                    // 不阻塞在发送上：退出时 UI 等待保存完成，不再取走消息
+                    let duration_ms = unreported_save_ms.swap(0, Ordering::Relaxed)
+                        + start.elapsed().as_millis() as u64;
+                    if ui_tx
+                        .try_send(UiMessage::CacheSaved { duration_ms })
+                        .is_err()
+                    {
+                        unreported_save_ms.fetch_add(duration_ms, Ordering::Relaxed);
                     }
                 }
             }
+        })
+    }
 
-            match compute_all_hashes_cached(path, Some(&progress_tx), buffer_size, mmap_chunk_size)
-            {
-                Ok((crc32, md5, sha1, xxhash3, computed_file_size)) => {
-                    let duration = start.elapsed().as_millis() as u64;
+    fn handle_mark_batch_done(paths: &[PathBuf], cache: &Arc<Mutex<HashCache>>) {
+        if let Ok(cache_guard) = cache.lock() {
+            if let Err(e) = cache_guard.mark_batch_done(paths) {
+                log::error!("更新批处理队列失败: {}", e);
+            }
+        }
+    }
 
-                    let _ = ui_tx.send(UiMessage::Xxhash3Computed {
-                        path: path.clone(),
-                        xxhash3: xxhash3.clone(),
-                    });
+    fn handle_clear_batch_queue(cache: &Arc<Mutex<HashCache>>) {
+        if let Ok(cache_guard) = cache.lock() {
+            if let Err(e) = cache_guard.clear_batch_queue() {
+                log::error!("清空批处理队列失败: {}", e);
+            }
+        }
+    }
+
+    fn handle_resume_batch(
+        paths: Vec<PathBuf>,
+        ui_tx: &Sender<UiMessage>,
+        cache: &Arc<Mutex<HashCache>>,
+    ) {
+        let ui_tx = ui_tx.clone();
+        let block_devices = cache
+            .lock()
+            .is_ok_and(|guard| guard.config().hash_block_devices);
+        thread::spawn(move || {
+            let found = Self::resume_batch(paths, block_devices);
+            let _ = ui_tx.send(UiMessage::BatchResumed(found));
+        });
+    }
+
+    fn handle_read_metadata(paths: Vec<PathBuf>, ui_tx: &Sender<UiMessage>) {
+        let ui_tx = ui_tx.clone();
+        thread::spawn(move || {
+            let _ = ui_tx.send(UiMessage::MetadataRead(Self::read_metadata(paths)));
+        });
+    }
+
+    fn handle_check_recent_paths(paths: Vec<PathBuf>, ui_tx: &Sender<UiMessage>) {
+        let ui_tx = ui_tx.clone();
+        thread::spawn(move || {
+            let missing = paths.into_iter().filter(|p| !p.exists()).collect();
+            let _ = ui_tx.send(UiMessage::RecentPathsMissing(missing));
+        });
+    }
+
+    fn handle_save_note(path: &Path, note: &str, cache: &Arc<Mutex<HashCache>>) {
+        if let Ok(cache_guard) = cache.lock() {
+            if let Err(e) = cache_guard.save_note(path, note) {
+                log::error!("保存文件备注失败: {} ({})", path.display(), e);
+            }
+        }
+    }
+
+    fn handle_invalidate_cache(paths: &[PathBuf], cache: &Arc<Mutex<HashCache>>) {
+        if let Ok(cache_guard) = cache.lock() {
+            for path in paths {
+                if let Err(e) = cache_guard.invalidate_entry(path) {
+                    log::warn!("使缓存失效失败: {} ({})", path.display(), e);
+                }
+            }
+        }
+    }
+
+    fn handle_plan_rename(
+        files: Vec<(PathBuf, String)>,
+        ui_tx: &Sender<UiMessage>,
+        cache: &Arc<Mutex<HashCache>>,
+    ) {
+        let ui_tx = ui_tx.clone();
+        let cache = cache.clone();
+        thread::spawn(move || {
+            let plan = Self::plan_rename(&files, &cache);
+            let _ = ui_tx.send(UiMessage::RenamePlanned(plan));
+        });
+    }
+
+    fn handle_apply_rename(
+        plan: Vec<RenamePlanEntry>,
+        ui_tx: &Sender<UiMessage>,
+        cache: &Arc<Mutex<HashCache>>,
+    ) {
+        let ui_tx = ui_tx.clone();
+        let cache = cache.clone();
+        thread::spawn(move || {
+            let outcomes = Self::apply_rename(&plan, &cache);
+            let _ = ui_tx.send(UiMessage::RenameApplied(outcomes));
+        });
+    }
+
+    fn handle_fetch_checksums(
+        url: String,
+        ui_tx: &Sender<UiMessage>,
+        cache: &Arc<Mutex<HashCache>>,
+    ) {
+        let ui_tx = ui_tx.clone();
+        let unit = Self::size_unit(cache);
+        thread::spawn(move || {
+            let result = crate::remote::fetch_text(&url, unit)
+                .map(|text| parse_checksum_list(&text, url.trim()))
+                .map_err(|e| e.to_string());
+            let _ = ui_tx.send(UiMessage::ChecksumsFetched(result));
+        });
+    }
+
+    fn handle_check_update(
+        url: String,
+        manual: bool,
+        ui_tx: &Sender<UiMessage>,
+        cache: &Arc<Mutex<HashCache>>,
+    ) {
+        let ui_tx = ui_tx.clone();
+        let unit = Self::size_unit(cache);
+        thread::spawn(move || {
+            let result = crate::update::check_for_update(&url, unit).map_err(|e| e.to_string());
+            let _ = ui_tx.send(UiMessage::UpdateChecked { manual, result });
+        });
+    }
+
+    fn handle_verify_manifest(manifest: PathBuf, ui_tx: &Sender<UiMessage>) {
+        let ui_tx = ui_tx.clone();
+        thread::spawn(move || {
+            let result = fs::read_to_string(&manifest)
+                .map(|text| {
+                    let list = parse_manifest(&text, &manifest);
+                    let report = manifest_report(&manifest, &list);
+                    (list, report)
+                })
+                .map_err(|e| format!("{}: {}", manifest.display(), e));
+            let _ = ui_tx.send(UiMessage::ManifestChecked(result));
+        });
+    }
+
+    fn handle_write_xattrs(targets: Vec<XattrTarget>, overwrite: bool, ui_tx: &Sender<UiMessage>) {
+        let ui_tx = ui_tx.clone();
+        thread::spawn(move || {
+            let report = crate::xattrs::write_all(&targets, overwrite);
+            let _ = ui_tx.send(UiMessage::XattrsWritten(report));
+        });
+    }
+
+    /// 等待尚未完成的缓存保存，到了整理计划时在后台整理数据库
+    fn handle_shutdown(
+        save_handles: &mut Vec<JoinHandle<()>>,
+        vacuum: VacuumSchedule,
+        cache: &Arc<Mutex<HashCache>>,
+    ) {
+        for handle in save_handles.drain(..) {
+            let _ = handle.join();
+        }
+        if vacuum != VacuumSchedule::Off {
+            let cache = cache.clone();
+            let handle = thread::spawn(move || Self::vacuum_on_exit(&cache, vacuum));
+            if let Ok(mut slot) = EXIT_VACUUM.lock() {
+                *slot = Some(handle);
+            }
+        }
+    }
+
+    /// 通知工作线程完成剩余的缓存保存，超时返回 false
+    ///
+    /// 到了 `vacuum` 计划的整理时间时，保存完成后在后台整理数据库，不阻塞窗口关闭，
+    /// 由 [`WorkerThread::wait_for_exit_vacuum`] 等待。
+    pub fn shutdown(
+        worker_tx: &Sender<WorkerMessage>,
+        timeout: Duration,
+        vacuum: VacuumSchedule,
+    ) -> bool {
+        let (ack, ack_rx) = bounded(1);
+        if worker_tx
+            .send_timeout(WorkerMessage::Shutdown { ack, vacuum }, timeout)
+            .is_err()
+        {
+            return false;
+        }
+        ack_rx.recv_timeout(timeout).is_ok()
+    }
+
+    /// 等待退出时的数据库整理结束，没有进行整理时立即返回
+    pub fn wait_for_exit_vacuum() {
+        let handle = EXIT_VACUUM.lock().ok().and_then(|mut slot| slot.take());
+        if let Some(handle) = handle {
+            let _ = handle.join();
+        }
+    }
+
+    fn vacuum_on_exit(cache: &Arc<Mutex<HashCache>>, schedule: VacuumSchedule) {
+        let Ok(cache_guard) = cache.lock() else {
+            return;
+        };
+        match cache_guard.vacuum_if_due(schedule) {
+            Ok(true) => log::info!("退出时已按计划整理缓存数据库"),
+            Ok(false) => {}
+            Err(e) => log::warn!("退出时整理缓存数据库失败: {}", e),
+        }
+    }
+
+    /// 快速哈希预筛：快速哈希唯一的文件直接报告，其余文件返回继续完整计算
+    fn prescreen(files: Vec<PathBuf>, batch_id: u64, ui_tx: &Sender<UiMessage>) -> Vec<PathBuf> {
+        use rayon::prelude::*;
+
+        let quick_hashes: Vec<(PathBuf, Option<String>)> = files
+            .into_par_iter()
+            .map(|path| {
+                let hash = quick_hash(&path).ok();
+                (path, hash)
+            })
+            .collect();
+
+        let mut group_sizes: HashMap<&str, usize> = HashMap::new();
+        for hash in quick_hashes.iter().filter_map(|(_, h)| h.as_deref()) {
+            *group_sizes.entry(hash).or_default() += 1;
+        }
 
-                    let _ = ui_tx.send(UiMessage::FileCompleted {
+        let mut remaining = Vec::new();
+        for (path, hash) in &quick_hashes {
+            match hash {
+                Some(hash) if group_sizes.get(hash.as_str()) == Some(&1) => {
+                    let _ = ui_tx.send(UiMessage::QuickHashed {
+                        batch_id,
                         path: path.clone(),
-                        crc32,
-                        md5,
-                        sha1,
-                        xxhash3,
-                        duration_ms: duration,
-                        modified_time,
-                        file_size: computed_file_size,
-                        from_cache: false,
+                        quick_hash: hash.clone(),
                     });
                 }
-                Err(_e) => {
-                    let _ = ui_tx.send(UiMessage::FileFailed { path: path.clone() });
-                }
+                // 失败的文件交给完整计算流程统一报告错误
+                _ => remaining.push(path.clone()),
             }
-        });
+        }
 
-        let _ = ui_tx.send(UiMessage::AllCompleted);
+        remaining
+    }
+
+    fn compute_batch(
+        files: Vec<PathBuf>,
+        batch_id: u64,
+        force: bool,
+        active_batch: &AtomicU64,
+        ui_tx: &Sender<UiMessage>,
+        multiplexor_tx: &Sender<MultiplexorMessage>,
+        cache: &Arc<Mutex<HashCache>>,
+    ) {
+        use rayon::prelude::*;
+
+        let config = if let Ok(cache_guard) = cache.lock() {
+            cache_guard.config().clone()
+        } else {
+            CacheConfig::default()
+        };
+
+        let files = if config.quick_prescreen {
+            Self::prescreen(files, batch_id, ui_tx)
+        } else {
+            files
+        };
+
+        let cache_map: HashMap<PathBuf, Option<CacheEntry>> = if let Ok(cache_guard) = cache.lock()
+        {
+            let paths: Vec<&PathBuf> = files.iter().collect();
+            let path_refs: Vec<&Path> = paths.iter().map(|p| p.as_path()).collect();
+            cache_guard
+                .get_by_paths_batch(&path_refs)
+                .unwrap_or_default()
+        } else {
+            HashMap::new()
+        };
+
+        let roots = volume_roots(&files);
+        Self::warn_cache_contention(&roots, batch_id, ui_tx, cache, &config);
+        // 任一卷需要限制时整批限制，避免机械硬盘上的文件被多线程交错读取
+        let limited_roots: Vec<&PathBuf> = roots
+            .iter()
+            .filter(|root| config.disk_concurrency.limits(root))
+            .collect();
+        let limited = !limited_roots.is_empty();
+        for root in limited_roots {
+            log::info!(
+                "{} 位于机械硬盘（或已设置限制并发），计算并发限制为 {}",
+                root.display(),
+                ROTATIONAL_THREADS
+            );
+        }
+
+        let batch = BatchContext {
+            batch_id,
+            force,
+            active_batch,
+            ui_tx,
+            multiplexor_tx,
+            cache,
+            config,
+            cache_map,
+            removable: RemovableVolumes::default(),
+        };
+        let process = |path: &PathBuf| Self::compute_file(path, &batch);
+        let run = || {
+            if batch.config.serial_compute {
+                // 串行模式在当前线程按队列顺序逐个计算，耗时可复现
+                files.iter().for_each(process);
+            } else if batch.config.compute_order == ComputeOrder::Added {
+                files.par_iter().for_each(process);
+            } else {
+                // par_iter 会把列表切成连续的段分给各线程，无法保证顺序；
+                // par_bridge 让空闲线程依次从队列头部取文件
+                files.iter().par_bridge().for_each(process);
+            }
+        };
+
+        if limited && !batch.config.serial_compute {
+            match rayon::ThreadPoolBuilder::new()
+                .num_threads(ROTATIONAL_THREADS)
+                .thread_name(|index| format!("turbohash-hdd-{index}"))
+                .build()
+            {
+                Ok(pool) => pool.install(run),
+                Err(e) => {
+                    log::warn!("创建受限线程池失败，改用全局线程池: {}", e);
+                    run();
+                }
+            }
+        } else {
+            run();
+        }
+
+        let _ = ui_tx.send(UiMessage::AllCompleted { batch_id });
+    }
+
+    fn warn_cache_contention(
+        roots: &[PathBuf],
+        batch_id: u64,
+        ui_tx: &Sender<UiMessage>,
+        cache: &Arc<Mutex<HashCache>>,
+        config: &CacheConfig,
+    ) {
+        let Ok(db_path) = cache.lock().map(|guard| guard.db_path().to_path_buf()) else {
+            return;
+        };
+        // 数据库只在一个卷上，最多与一组文件争用
+        if let Some(root) = roots
+            .iter()
+            .find(|root| Self::contends_with_cache(root, &db_path, config))
+        {
+            log::warn!(
+                "缓存数据库与 {} 位于同一块慢速磁盘，读取文件与写入缓存会相互争用",
+                root.display()
+            );
+            let _ = ui_tx.send(UiMessage::CacheContention {
+                batch_id,
+                root: root.clone(),
+            });
+        }
+    }
+
+    /// 计算批次中的一个文件：先尝试采用缓存，否则读取文件计算
+    fn compute_file(path: &Path, batch: &BatchContext) {
+        // 批次已取消或被新批次取代
+        if batch.active_batch.load(Ordering::Relaxed) != batch.batch_id {
+            return;
+        }
+        let batch_id = batch.batch_id;
+        let started = std::time::Instant::now();
+        let _ = batch.ui_tx.send(UiMessage::FileStarted {
+            batch_id,
+            path: path.to_path_buf(),
+        });
+
+        let (progress_tx, progress_rx) = bounded(32);
+        let _ = batch.multiplexor_tx.send(MultiplexorMessage::Register {
+            batch_id,
+            path: path.to_path_buf(),
+            progress_rx,
+        });
+
+        let Some(stat) = Self::stat_batch_file(path, batch) else {
+            return;
+        };
+
+        let config = &batch.config;
+        let cacheable = Self::cacheable(path, config, &batch.removable);
+        let cache_entry = batch.cache_map.get(path).and_then(|entry| entry.as_ref());
+        let unchanged_hit = cache_entry
+            .filter(|_| cacheable && config.sync_mode && !batch.force)
+            .and_then(|entry| {
+                Self::unchanged_hit(
+                    path,
+                    entry,
+                    stat.size,
+                    stat.modified_time,
+                    config,
+                    batch.cache,
+                )
+            });
+        // 严格校验时已经重新计算过的哈希，与缓存不同时不再读取第二遍
+        let mut recomputed = None;
+        let cached = match (unchanged_hit, cache_entry) {
+            _ if batch.force => None,
+            (Some(hashes), _) => Some((hashes, true)),
+            (None, Some(entry)) if cacheable => match Self::lookup_cache(
+                path,
+                entry,
+                stat.size,
+                stat.modified_time,
+                config,
+                Some(&progress_tx),
+                batch.cache,
+            ) {
+                Ok(CacheLookup::Hit(hashes)) => Some((hashes, false)),
+                Ok(CacheLookup::Miss) => None,
+                Ok(CacheLookup::Changed(hashes)) => {
+                    recomputed = Some(hashes);
+                    None
+                }
+                Err(e) => {
+                    let _ = batch.ui_tx.send(UiMessage::FileFailed {
+                        batch_id,
+                        path: path.to_path_buf(),
+                        error: e.to_string(),
+                    });
+                    return;
+                }
+            },
+            (None, _) => None,
+        };
+
+        // Xxhash3Computed 之后的 FileCompleted 同样带有 xxhash3，通道已满时可以丢弃
+        if let Some((hashes, unchanged)) = cached {
+            let _ = batch.ui_tx.try_send(UiMessage::Xxhash3Computed {
+                batch_id,
+                path: path.to_path_buf(),
+                xxhash3: hashes.xxhash3.clone(),
+            });
+            let _ = batch.ui_tx.send(UiMessage::FileCompleted {
+                batch_id,
+                path: path.to_path_buf(),
+                crc32: hashes.crc32,
+                md5: hashes.md5,
+                sha1: hashes.sha1,
+                sha256: hashes.sha256,
+                crc64: hashes.crc64,
+                adler32: hashes.adler32,
+                xxhash3: hashes.xxhash3,
+                duration_ms: started.elapsed().as_millis() as u64,
+                modified_time: stat.modified_time.unwrap_or(0),
+                file_size: stat.size,
+                from_cache: true,
+                unchanged,
+                computed_at: cache_entry.map_or(0, |entry| entry.cached_at),
+            });
+            return;
+        }
+
+        Self::compute_uncached_file(
+            path,
+            batch,
+            stat,
+            cache_entry,
+            recomputed,
+            &progress_tx,
+            started,
+        );
+    }
+
+    /// 读取批次中文件的大小与修改时间，目录、特殊文件与读取失败直接报告给 UI
+    fn stat_batch_file(path: &Path, batch: &BatchContext) -> Option<FileStat> {
+        let batch_id = batch.batch_id;
+        // 计算链接本身时读取链接自身的元数据，悬空链接也能计算
+        let metadata = if Self::hashes_link_itself(path, &batch.config) {
+            with_long_path(path, |p| fs::symlink_metadata(p))
+        } else {
+            with_long_path(path, |p| fs::metadata(p))
+        };
+        match metadata {
+            // 加入队列后路径变成了目录：直接报告，不再尝试读取或写入缓存
+            Ok(metadata) if metadata.is_dir() => {
+                log::warn!("不是文件，跳过: {}", path.display());
+                let _ = batch.ui_tx.send(UiMessage::FileFailed {
+                    batch_id,
+                    path: path.to_path_buf(),
+                    error: "不是文件".to_string(),
+                });
+                None
+            }
+            // FIFO 等特殊文件读取时会一直阻塞，不能进入计算
+            Ok(metadata) if is_special_file(metadata.file_type()) => {
+                log::warn!("不支持的文件类型，跳过: {}", path.display());
+                let _ = batch.ui_tx.send(UiMessage::FileFailed {
+                    batch_id,
+                    path: path.to_path_buf(),
+                    error: "不支持的文件类型".to_string(),
+                });
+                None
+            }
+            Ok(metadata) => Some(FileStat {
+                size: metadata.len(),
+                modified_time: metadata_modified_time(&metadata, path).ok(),
+            }),
+            Err(e) => {
+                let _ = batch.ui_tx.send(Self::failure_message(path, batch_id, &e));
+                None
+            }
+        }
+    }
+
+    /// 读取文件计算哈希；严格校验已经重新计算过时直接采用 `recomputed`
+    fn compute_uncached_file(
+        path: &Path,
+        batch: &BatchContext,
+        stat: FileStat,
+        cache_entry: Option<&CacheEntry>,
+        recomputed: Option<FileHashes>,
+        progress_tx: &Sender<ProgressUpdate>,
+        started: std::time::Instant,
+    ) {
+        let batch_id = batch.batch_id;
+        let changed_from_cache = recomputed.is_some();
+        let computed = match recomputed {
+            Some(hashes) => Ok((hashes, stat.size)),
+            None => Self::compute_uncached(path, &batch.config, Some(progress_tx)),
+        };
+        match computed {
+            Ok((hashes, computed_file_size)) => {
+                let duration = started.elapsed().as_millis() as u64;
+
+                // 强制重新计算或严格校验的结果与原有缓存不同：可能是静默损坏，不能悄悄覆盖
+                if let Some(entry) = cache_entry.filter(|_| batch.force || changed_from_cache) {
+                    Self::report_hash_change(path, batch, entry, stat, &hashes);
+                }
+
+                let _ = batch.ui_tx.try_send(UiMessage::Xxhash3Computed {
+                    batch_id,
+                    path: path.to_path_buf(),
+                    xxhash3: hashes.xxhash3.clone(),
+                });
+
+                let _ = batch.ui_tx.send(UiMessage::FileCompleted {
+                    batch_id,
+                    path: path.to_path_buf(),
+                    crc32: hashes.crc32,
+                    md5: hashes.md5,
+                    sha1: hashes.sha1,
+                    sha256: hashes.sha256,
+                    crc64: hashes.crc64,
+                    adler32: hashes.adler32,
+                    xxhash3: hashes.xxhash3,
+                    duration_ms: duration,
+                    modified_time: stat.modified_time.unwrap_or(0),
+                    file_size: computed_file_size,
+                    from_cache: false,
+                    unchanged: false,
+                    computed_at: unix_now(),
+                });
+            }
+            // 元数据读取之后、打开之前被删除
+            Err(HashError::Io(e, _)) => {
+                let _ = batch.ui_tx.send(Self::failure_message(path, batch_id, &e));
+            }
+            Err(e) => {
+                let _ = batch.ui_tx.send(UiMessage::FileFailed {
+                    batch_id,
+                    path: path.to_path_buf(),
+                    error: e.to_string(),
+                });
+            }
+        }
+    }
+
+    fn report_hash_change(
+        path: &Path,
+        batch: &BatchContext,
+        entry: &CacheEntry,
+        stat: FileStat,
+        hashes: &FileHashes,
+    ) {
+        let old = Self::entry_hashes(entry);
+        if Self::changed_algorithms(&old, hashes).is_empty() {
+            return;
+        }
+        log::warn!("哈希已变更: {}", path.display());
+        let metadata_changed = stat.modified_time.is_none_or(|modified_time| {
+            !HashCache::is_valid_with_metadata(
+                entry,
+                stat.size,
+                modified_time,
+                batch.config.mtime_tolerance_ms,
+            )
+        });
+        let _ = batch.ui_tx.send(UiMessage::HashChanged {
+            batch_id: batch.batch_id,
+            path: path.to_path_buf(),
+            old,
+            new: hashes.clone(),
+            metadata_changed,
+        });
+    }
+
+    /// 文件不存在时报告为已删除，其余错误报告为失败
+    fn failure_message(path: &Path, batch_id: u64, error: &std::io::Error) -> UiMessage {
+        let path = path.to_path_buf();
+        if error.kind() == std::io::ErrorKind::NotFound {
+            log::info!("文件已不存在，跳过: {}", path.display());
+            UiMessage::FileMissing { batch_id, path }
+        } else {
+            UiMessage::FileFailed {
+                batch_id,
+                path,
+                error: error.to_string(),
+            }
+        }
+    }
+
+    /// 按 UI 传来的文件大小排序计算队列，大小相同时保持原有顺序
+    fn order_files(mut files: Vec<(PathBuf, u64)>, order: ComputeOrder) -> Vec<PathBuf> {
+        match order {
+            ComputeOrder::Added => {}
+            ComputeOrder::SmallestFirst => files.sort_by_key(|&(_, size)| size),
+            ComputeOrder::LargestFirst => files.sort_by_key(|&(_, size)| std::cmp::Reverse(size)),
+        }
+        files.into_iter().map(|(path, _)| path).collect()
+    }
+
+    /// 界面设置的大小单位，用于错误信息
+    fn size_unit(cache: &Arc<Mutex<HashCache>>) -> SizeUnit {
+        cache
+            .lock()
+            .map_or(SizeUnit::Binary, |guard| guard.config().size_unit)
+    }
+
+    /// 按指定参数直接调用引擎计算，不经过缓存与稀疏文件等处理，便于排查读取路径的问题
+    fn rehash(params: RehashParams, cache: &Arc<Mutex<HashCache>>) -> HashResult<RehashReport> {
+        let enabled = cache.lock().map_or_else(
+            |_| CacheConfig::default().enabled_hashes,
+            |guard| guard.config().enabled_hashes,
+        );
+        let path = params.path.as_path();
+        ensure_not_special(path)?;
+        let file_size = content_len(path).with_path(path)?;
+        let strategy = IoStrategy::for_file(path, file_size, params.force_buffered);
+        let start = std::time::Instant::now();
+        // 不按文件大小调整，窗口中显示的参数就是实际使用的参数
+        let hashes = compute_file_hash_exact(
+            path,
+            file_size,
+            strategy,
+            None,
+            params.buffer_size,
+            params.mmap_chunk_size,
+            enabled,
+        )?;
+        Ok(RehashReport {
+            params,
+            strategy,
+            file_size,
+            duration_ms: start.elapsed().as_millis() as u64,
+            hashes,
+        })
+    }
+
+    /// 对比两个文件：大小不同直接判定不同，否则优先使用缓存结果
+    fn compare_files(
+        paths: [PathBuf; 2],
+        cache: &Arc<Mutex<HashCache>>,
+    ) -> HashResult<FileComparison> {
+        let config = if let Ok(cache_guard) = cache.lock() {
+            cache_guard.config().clone()
+        } else {
+            CacheConfig::default()
+        };
+
+        for path in &paths {
+            ensure_not_special(path)?;
+        }
+        let sizes = [
+            with_long_path(&paths[0], |p| fs::metadata(p))
+                .with_path(&paths[0])?
+                .len(),
+            with_long_path(&paths[1], |p| fs::metadata(p))
+                .with_path(&paths[1])?
+                .len(),
+        ];
+        if sizes[0] != sizes[1] {
+            return Ok(FileComparison {
+                paths,
+                sizes,
+                hashes: None,
+            });
+        }
+
+        let cache_map = if let Ok(cache_guard) = cache.lock() {
+            cache_guard
+                .get_by_paths_batch(&[paths[0].as_path(), paths[1].as_path()])
+                .unwrap_or_default()
+        } else {
+            HashMap::new()
+        };
+
+        let removable = RemovableVolumes::default();
+        let hash_one = |path: &Path| -> HashResult<FileHashes> {
+            if let Some(entry) = cache_map
+                .get(path)
+                .and_then(Option::as_ref)
+                .filter(|_| Self::cacheable(path, &config, &removable))
+            {
+                let modified_time = get_file_modified_time(path).ok();
+                match Self::lookup_cache(
+                    path,
+                    entry,
+                    sizes[0],
+                    modified_time,
+                    &config,
+                    None,
+                    cache,
+                )? {
+                    CacheLookup::Hit(hashes) | CacheLookup::Changed(hashes) => return Ok(hashes),
+                    CacheLookup::Miss => {}
+                }
+            }
+            Ok(Self::compute_uncached(path, &config, None)?.0)
+        };
+
+        let (first, second) = rayon::join(|| hash_one(&paths[0]), || hash_one(&paths[1]));
+        Ok(FileComparison {
+            hashes: Some([first?, second?]),
+            paths,
+            sizes,
+        })
+    }
+
+    /// 生成重命名计划，已存在的目标只计算 SHA256 用于比较内容
+    fn plan_rename(
+        files: &[(PathBuf, String)],
+        cache: &Arc<Mutex<HashCache>>,
+    ) -> Vec<RenamePlanEntry> {
+        let config = if let Ok(cache_guard) = cache.lock() {
+            cache_guard.config().clone()
+        } else {
+            CacheConfig::default()
+        };
+        let sha256_only = EnabledHashes {
+            sha256: true,
+            ..EnabledHashes::NONE
+        };
+
+        plan_renames(files, |path| {
+            compute_file_hash(
+                path,
+                None,
+                config.buffer_size,
+                config.mmap_chunk_size,
+                None,
+                Self::force_buffered(path, &config),
+                sha256_only,
+            )
+            .map(|hashes| hashes.sha256)
+        })
+    }
+
+    /// 执行重命名并把缓存条目移到新路径
+    fn apply_rename(plan: &[RenamePlanEntry], cache: &Arc<Mutex<HashCache>>) -> Vec<RenameOutcome> {
+        // 重命名后原路径不再存在，必须先取得规范化路径
+        let normalized: HashMap<PathBuf, PathBuf> = if let Ok(cache_guard) = cache.lock() {
+            plan.iter()
+                .filter(|entry| entry.action.is_rename())
+                .filter_map(|entry| {
+                    let normalized = cache_guard.path_normalizer.normalize(&entry.source).ok()?;
+                    Some((entry.source.clone(), normalized))
+                })
+                .collect()
+        } else {
+            HashMap::new()
+        };
+
+        let outcomes = apply_plan(plan);
+
+        if let Ok(cache_guard) = cache.lock() {
+            for outcome in outcomes.iter().filter(|outcome| outcome.result.is_ok()) {
+                let Some(from) = normalized.get(&outcome.source) else {
+                    continue;
+                };
+                if let Err(e) = cache_guard.move_entry(from, &outcome.target) {
+                    log::error!(
+                        "迁移缓存条目失败: {} -> {} ({})",
+                        outcome.source.display(),
+                        outcome.target.display(),
+                        e
+                    );
+                }
+            }
+        }
+        for outcome in &outcomes {
+            match &outcome.result {
+                Ok(()) => log::info!(
+                    "已重命名: {} -> {}",
+                    outcome.source.display(),
+                    outcome.target.display()
+                ),
+                Err(e) => log::warn!("重命名失败: {} ({})", outcome.source.display(), e),
+            }
+        }
+        outcomes
+    }
+
+    /// 审计目录下的缓存条目：文件仍存在时重新计算 xxhash3 并与缓存比对
+    fn audit_cache(root: &Path, cache: &Arc<Mutex<HashCache>>) -> HashResult<AuditReport> {
+        use rayon::prelude::*;
+
+        // 查询后立即释放锁，重新计算期间不阻塞其他缓存操作
+        let (entries, config) = {
+            let cache_guard = cache
+                .lock()
+                .map_err(|e| HashError::SystemResource(format!("缓存锁已中毒: {}", e)))?;
+            (
+                cache_guard.get_entries_under(root)?,
+                cache_guard.config().clone(),
+            )
+        };
+
+        let outcomes: Vec<(PathBuf, AuditOutcome)> = entries
+            .par_iter()
+            .map(|entry| (entry.path.clone(), Self::audit_entry(entry, &config)))
+            .collect();
+
+        let mut report = AuditReport {
+            checked: 0,
+            missing: 0,
+            findings: Vec::new(),
+        };
+        for (path, outcome) in outcomes {
+            match outcome {
+                AuditOutcome::Missing => report.missing += 1,
+                AuditOutcome::Consistent => report.checked += 1,
+                AuditOutcome::Finding(finding) => {
+                    report.checked += 1;
+                    report.findings.push((path, finding));
+                }
+            }
+        }
+        Ok(report)
+    }
+
+    /// 核对单个缓存条目
+    fn audit_entry(entry: &CacheEntry, config: &CacheConfig) -> AuditOutcome {
+        let unreadable = |e: &dyn std::fmt::Display| {
+            AuditOutcome::Finding(AuditFinding::Unreadable(e.to_string()))
+        };
+        let path = entry.path.as_path();
+        let file_size = match with_long_path(path, |p| fs::metadata(p)) {
+            Ok(metadata) => metadata.len(),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return AuditOutcome::Missing,
+            Err(e) => return unreadable(&e),
+        };
+        let modified_time = match get_file_modified_time(path) {
+            Ok(time) => time,
+            Err(e) => return unreadable(&e),
+        };
+
+        if !HashCache::is_valid_with_metadata(
+            entry,
+            file_size,
+            modified_time,
+            config.mtime_tolerance_ms,
+        ) {
+            return AuditOutcome::Finding(AuditFinding::Changed);
+        }
+
+        let computed_xxhash3 = match Self::compute_xxhash3(path, config, None) {
+            Ok((hash, _)) => hash,
+            Err(e) => return unreadable(&e),
+        };
+
+        if HashCache::validate_cache_integrity(
+            entry,
+            &computed_xxhash3,
+            file_size,
+            modified_time,
+            config.mtime_tolerance_ms,
+        ) {
+            AuditOutcome::Consistent
+        } else {
+            AuditOutcome::Finding(AuditFinding::Corrupted {
+                cached: entry.xxhash3.clone(),
+                actual: computed_xxhash3,
+            })
+        }
+    }
+
+    /// 按校验级别核对缓存条目，通过时返回缓存的哈希
+    ///
+    /// 元数据不匹配时返回 `Miss`；快速级别只核对元数据，标准级别重新计算 xxhash3
+    /// （返回值中为本次计算值），严格级别重新计算所有启用的哈希。内容不匹配时使条目
+    /// 失效；严格级别已经算出了全部哈希，返回 `Changed` 与本次计算值，其余返回 `Miss`。
+    /// 路径能否使用缓存由调用方按 `cacheable` 判断。
+    fn lookup_cache(
+        path: &Path,
+        entry: &CacheEntry,
+        file_size: u64,
+        modified_time: Option<u64>,
+        config: &CacheConfig,
+        progress_tx: Option<&Sender<ProgressUpdate>>,
+        cache: &Arc<Mutex<HashCache>>,
+    ) -> HashResult<CacheLookup> {
+        let Some(modified_time) = modified_time else {
+            return Ok(CacheLookup::Miss);
+        };
+        if !HashCache::is_valid_with_metadata(
+            entry,
+            file_size,
+            modified_time,
+            config.mtime_tolerance_ms,
+        ) {
+            return Ok(CacheLookup::Miss);
+        }
+
+        let hashes = match config.cache_validation {
+            CacheValidation::Fast => Some(Self::entry_hashes(entry)),
+            CacheValidation::Normal => {
+                let (computed_xxhash3, _) = Self::compute_xxhash3(path, config, progress_tx)?;
+                HashCache::validate_cache_integrity(
+                    entry,
+                    &computed_xxhash3,
+                    file_size,
+                    modified_time,
+                    config.mtime_tolerance_ms,
+                )
+                .then(|| FileHashes {
+                    xxhash3: computed_xxhash3,
+                    ..Self::entry_hashes(entry)
+                })
+            }
+            CacheValidation::Paranoid => {
+                let (computed, _) = Self::compute_uncached(path, config, progress_tx)?;
+                if !Self::matches_computed(entry, &computed) {
+                    log::warn!("缓存内容与文件不符: {}", path.display());
+                    if let Ok(cache_guard) = cache.lock() {
+                        let _ = cache_guard.invalidate_entry(path);
+                    }
+                    return Ok(CacheLookup::Changed(computed));
+                }
+                Some(Self::entry_hashes(entry))
+            }
+        };
+
+        if let Some(hashes) = hashes {
+            if let Ok(cache_guard) = cache.lock() {
+                if let Ok(true) = cache_guard.verify_cached_hashes(entry) {
+                    log::debug!("✓ 缓存命中: {}", path.display());
+                    return Ok(CacheLookup::Hit(hashes));
+                }
+            }
+        }
+
+        log::debug!("✗ 缓存失效: {}", path.display());
+        if let Ok(cache_guard) = cache.lock() {
+            let _ = cache_guard.invalidate_entry(path);
+        }
+        Ok(CacheLookup::Miss)
+    }
+
+    /// 仅新增/变更模式：大小与修改时间与缓存一致即采用缓存结果，不读取文件内容
+    fn unchanged_hit(
+        path: &Path,
+        entry: &CacheEntry,
+        file_size: u64,
+        modified_time: Option<u64>,
+        config: &CacheConfig,
+        cache: &Arc<Mutex<HashCache>>,
+    ) -> Option<FileHashes> {
+        let modified_time = modified_time?;
+        if !HashCache::is_valid_with_metadata(
+            entry,
+            file_size,
+            modified_time,
+            config.mtime_tolerance_ms,
+        ) {
+            return None;
+        }
+        // 缓存中缺少当前启用的算法时仍需重新计算
+        if !cache.lock().ok()?.verify_cached_hashes(entry).ok()? {
+            return None;
+        }
+        log::debug!("未变更，跳过: {}", path.display());
+        Some(Self::entry_hashes(entry))
+    }
+
+    fn entry_hashes(entry: &CacheEntry) -> FileHashes {
+        FileHashes {
+            crc32: entry.crc32.clone(),
+            md5: entry.md5.clone(),
+            sha1: entry.sha1.clone(),
+            sha256: entry.sha256.clone(),
+            crc64: entry.crc64.clone(),
+            adler32: entry.adler32.clone(),
+            xxhash3: entry.xxhash3.clone(),
+        }
+    }
+
+    /// 两次结果都有值且不一致的算法（启用的算法变化导致的空值不算变更）
+    pub fn changed_algorithms(old: &FileHashes, new: &FileHashes) -> Vec<HashAlgorithm> {
+        HashAlgorithm::ALL
+            .into_iter()
+            .filter(|&algorithm| {
+                let (old, new) = (old.hash_value(algorithm), new.hash_value(algorithm));
+                !old.is_empty() && !new.is_empty() && !old.eq_ignore_ascii_case(new)
+            })
+            .collect()
+    }
+
+    /// 本次计算出的每个哈希都与缓存条目一致（快速模式下只有 XXH3）
+    fn matches_computed(entry: &CacheEntry, computed: &FileHashes) -> bool {
+        HashAlgorithm::ALL.into_iter().all(|algorithm| {
+            let value = computed.hash_value(algorithm);
+            if value.is_empty() || value.eq_ignore_ascii_case(entry.hash_value(algorithm)) {
+                return true;
+            }
+            log::debug!(
+                "验证失败: {} 不匹配 (缓存: {}, 计算: {})",
+                algorithm.label(),
+                entry.hash_value(algorithm),
+                value
+            );
+            false
+        })
+    }
+
+    /// 未命中缓存时的完整计算；快速模式只计算 XXH3，其余哈希留空
+    fn compute_uncached(
+        path: &Path,
+        config: &CacheConfig,
+        progress_tx: Option<&Sender<ProgressUpdate>>,
+    ) -> HashResult<(FileHashes, u64)> {
+        if Self::hashes_link_itself(path, config) {
+            return compute_link_hash(path, config.enabled_hashes).map(|(hashes, size)| {
+                if config.xxh3_only_mode {
+                    let xxhash3 = hashes.xxhash3;
+                    (
+                        FileHashes {
+                            xxhash3,
+                            ..FileHashes::default()
+                        },
+                        size,
+                    )
+                } else {
+                    (hashes, size)
+                }
+            });
+        }
+        if config.xxh3_only_mode {
+            return Self::compute_xxhash3(path, config, progress_tx).map(|(xxhash3, size)| {
+                (
+                    FileHashes {
+                        xxhash3,
+                        ..FileHashes::default()
+                    },
+                    size,
+                )
+            });
+        }
+        if config.sparse_files {
+            if let Some(result) =
+                compute_sparse_hash(path, progress_tx, config.buffer_size, config.enabled_hashes)?
+            {
+                return Ok(result);
+            }
+        }
+        compute_all_hashes_cached(
+            path,
+            progress_tx,
+            config.buffer_size,
+            config.mmap_chunk_size,
+            Self::force_buffered(path, config),
+            config.enabled_hashes,
+        )
+    }
+
+    /// 计算 XXH3（缓存校验与快速模式），开启稀疏文件处理时稀疏文件只读取已分配的区域
+    fn compute_xxhash3(
+        path: &Path,
+        config: &CacheConfig,
+        progress_tx: Option<&Sender<ProgressUpdate>>,
+    ) -> HashResult<(String, u64)> {
+        if config.sparse_files {
+            if let Some(result) = compute_xxhash3_sparse(path, progress_tx, config.buffer_size)? {
+                return Ok(result);
+            }
+        }
+        compute_xxhash3_only(
+            path,
+            progress_tx,
+            config.buffer_size,
+            config.mmap_chunk_size,
+            Self::force_buffered(path, config),
+        )
+    }
+
+    /// 网络路径上的 mmap 可能引发大量远程缺页，默认改用缓冲读取
+    fn force_buffered(path: &Path, config: &CacheConfig) -> bool {
+        !config.network_mmap_enabled && is_network_path(path)
+    }
+
+    /// 读取恢复的批处理中各文件的大小，已不存在的文件跳过；与扫描一致，未启用时跳过块设备
+    fn resume_batch(paths: Vec<PathBuf>, block_devices: bool) -> Vec<DiscoveredFile> {
+        paths
+            .into_iter()
+            .filter_map(|path| {
+                if !block_devices && is_block_device(&path) {
+                    log::warn!("跳过块设备（未在设置中启用）: {}", path.display());
+                    return None;
+                }
+                // 块设备的元数据长度为 0
+                let size = content_len(&path).ok()?;
+                Some(DiscoveredFile {
+                    path,
+                    size,
+                    oversized: false,
+                    expected: None,
+                })
+            })
+            .collect()
+    }
+
+    fn read_metadata(paths: Vec<PathBuf>) -> Vec<(PathBuf, Option<FileStat>)> {
+        paths
+            .into_iter()
+            .map(|path| {
+                let stat = content_len(&path).ok().map(|size| FileStat {
+                    size,
+                    modified_time: get_file_modified_time(&path).ok(),
+                });
+                (path, stat)
+            })
+            .collect()
+    }
+
+    /// 可移动介质上的文件默认不读写缓存，避免换盘后命中另一设备上同路径文件的结果；
+    /// 块设备的修改时间不反映内容变化，始终不缓存
+    /// 链接本身的哈希与目标文件无关，同样不缓存（缓存键会解析到目标路径）
+    pub fn cacheable(path: &Path, config: &CacheConfig, removable: &RemovableVolumes) -> bool {
+        (!config.skip_removable_cache || !removable.contains(path))
+            && !is_block_device(path)
+            && !Self::hashes_link_itself(path, config)
+    }
+
+    /// 缓存数据库与 `root` 位于同一块机械硬盘或可移动介质：计算读取文件的同时写入缓存，
+    /// 机械硬盘的磁头在两处之间来回寻道，U 盘的写入会拖慢读取。设置为不写入可移动介质
+    /// 的缓存时不算争用
+    fn contends_with_cache(root: &Path, db_path: &Path, config: &CacheConfig) -> bool {
+        if same_volume(root, db_path) != Some(true) {
+            return false;
+        }
+        let removable = is_removable_path(root);
+        (removable && !config.skip_removable_cache)
+            || (!removable && is_rotational_path(root) == Some(true))
+    }
+
+    /// 按设置对直接添加的符号链接计算链接本身，而非其指向的文件
+    fn hashes_link_itself(path: &Path, config: &CacheConfig) -> bool {
+        config.symlink_policy == SymlinkPolicy::Link && is_symlink(path)
+    }
+}
+
+/// 按所在卷分组，返回每个卷上文件的公共上级目录，用于判断所在磁盘
+///
+/// 不同挂载点上的文件不会合并成 `/` 之类与实际磁盘无关的目录；无法识别所在卷的文件归为
+/// 一组。每个目录只查询一次所在卷。
+fn volume_roots(files: &[PathBuf]) -> Vec<PathBuf> {
+    let mut dir_volumes: HashMap<&Path, Option<VolumeId>> = HashMap::new();
+    let mut roots: Vec<(Option<VolumeId>, PathBuf)> = Vec::new();
+    for file in files {
+        let Some(dir) = file.parent() else {
+            continue;
+        };
+        // Windows 上的卷标识为 String
+        #[allow(clippy::clone_on_copy)]
+        let volume = dir_volumes
+            .entry(dir)
+            .or_insert_with(|| volume_id(dir))
+            .clone();
+        match roots.iter_mut().find(|(id, _)| *id == volume) {
+            Some((_, root)) => while !dir.starts_with(&*root) && root.pop() {},
+            None => roots.push((volume, dir.to_path_buf())),
+        }
+    }
+    roots.into_iter().map(|(_, root)| root).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::HashCachePool;
+    use tempfile::TempDir;
+
+    /// 格式正确但与任何内容都不符的占位哈希
+    fn placeholder_hashes() -> FileHashes {
+        FileHashes {
+            crc32: "0".repeat(8),
+            crc64: "0".repeat(16),
+            adler32: "0".repeat(8),
+            md5: "0".repeat(32),
+            sha1: "0".repeat(40),
+            sha256: "0".repeat(64),
+            xxhash3: "0".repeat(32),
+        }
+    }
+
+    /// 计算请求附带的文件大小，无法读取时为 0
+    fn sized(paths: &[PathBuf]) -> Vec<(PathBuf, u64)> {
+        paths
+            .iter()
+            .map(|path| (path.clone(), content_len(path).unwrap_or(0)))
+            .collect()
+    }
+
+    #[test]
+    fn test_volume_roots() {
+        let temp = TempDir::new().unwrap();
+        let photos = temp.path().join("photos");
+        fs::create_dir_all(photos.join("2024")).unwrap();
+        fs::create_dir_all(photos.join("2023").join("c")).unwrap();
+        let files = [
+            photos.join("2024").join("a.jpg"),
+            photos.join("b.jpg"),
+            photos.join("2023").join("c").join("d.jpg"),
+        ];
+        assert_eq!(volume_roots(&files), std::slice::from_ref(&photos));
+        assert_eq!(volume_roots(&files[..1]), [photos.join("2024")]);
+        assert!(volume_roots(&[]).is_empty());
+
+        // 另一个挂载点上的文件单独成组，不合并为 `/`
+        #[cfg(target_os = "linux")]
+        {
+            let mut mixed = files.to_vec();
+            mixed.push(PathBuf::from("/proc/self/status"));
+            assert_eq!(volume_roots(&mixed), [photos, PathBuf::from("/proc/self")]);
+        }
+    }
+
+    #[test]
+    fn test_large_cleanup_reports_progress_asynchronously() {
+        const ENTRIES: usize = 25_000;
+
+        let temp = TempDir::new().unwrap();
+        let pool =
+            HashCachePool::new(&temp.path().join("test.db"), CacheConfig::default()).unwrap();
+        pool.insert_test_entries(ENTRIES);
+        let cache = Arc::new(Mutex::new(pool));
+        let (_worker, worker_tx, ui_rx) = WorkerThread::spawn(cache.clone());
+
+        worker_tx
+            .send(WorkerMessage::CleanupCache(
+                CleanupKind::All,
+                Arc::new(AtomicBool::new(false)),
+            ))
+            .unwrap();
+
+        let mut deleting = Vec::new();
+        let outcome = loop {
+            match ui_rx.recv_timeout(Duration::from_secs(30)).unwrap() {
+                UiMessage::CleanupProgress(CleanupProgress::Deleting { deleted, total }) => {
+                    // 清理进行中缓存锁保持可用
+                    assert!(cache.try_lock().is_ok());
+                    assert_eq!(total, ENTRIES);
+                    deleting.push(deleted);
+                }
+                UiMessage::CleanupFinished(result) => break result.unwrap(),
+                _ => {}
+            }
+        };
+
+        assert_eq!(outcome.deleted, ENTRIES);
+        assert!(!outcome.cancelled);
+        // 分批删除：0 → 10000 → 20000 → 25000
+        assert_eq!(deleting, [0, 10_000, 20_000, ENTRIES]);
+        let remaining = cache
+            .lock()
+            .unwrap()
+            .cleanup(CleanupKind::All, &AtomicBool::new(false), |_| {})
+            .unwrap();
+        assert_eq!(remaining.deleted, 0);
+    }
+
+    #[test]
+    fn test_cleanup_stops_when_cancelled() {
+        let temp = TempDir::new().unwrap();
+        let pool =
+            HashCachePool::new(&temp.path().join("test.db"), CacheConfig::default()).unwrap();
+        pool.insert_test_entries(15_000);
+
+        let cancel = AtomicBool::new(false);
+        let outcome = pool
+            .cleanup(CleanupKind::All, &cancel, |progress| {
+                // 第一批完成后取消
+                if matches!(progress, CleanupProgress::Deleting { deleted, .. } if deleted > 0) {
+                    cancel.store(true, std::sync::atomic::Ordering::Relaxed);
+                }
+            })
+            .unwrap();
+        assert!(outcome.cancelled);
+        assert_eq!(outcome.deleted, 10_000);
+    }
+
+    #[test]
+    fn test_read_metadata_reports_missing_files() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("data.bin");
+        std::fs::write(&path, [1u8; 10]).unwrap();
+        let missing = temp.path().join("missing.bin");
+
+        let stats = WorkerThread::read_metadata(vec![path.clone(), missing.clone()]);
+        assert_eq!(
+            stats,
+            vec![
+                (
+                    path.clone(),
+                    Some(FileStat {
+                        size: 10,
+                        modified_time: get_file_modified_time(&path).ok(),
+                    })
+                ),
+                (missing, None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_order_files_by_size() {
+        let files: Vec<(PathBuf, u64)> = [300, 10, 2000, 10, 0]
+            .into_iter()
+            .enumerate()
+            .map(|(i, size)| (PathBuf::from(format!("/data/file{}.bin", i)), size))
+            .collect();
+        let paths: Vec<PathBuf> = files.iter().map(|(path, _)| path.clone()).collect();
+
+        // 大小相同（file1 与 file3）时保持添加顺序
+        assert_eq!(
+            WorkerThread::order_files(files.clone(), ComputeOrder::SmallestFirst),
+            [
+                paths[4].clone(),
+                paths[1].clone(),
+                paths[3].clone(),
+                paths[0].clone(),
+                paths[2].clone(),
+            ]
+        );
+        assert_eq!(
+            WorkerThread::order_files(files.clone(), ComputeOrder::LargestFirst),
+            [
+                paths[2].clone(),
+                paths[0].clone(),
+                paths[1].clone(),
+                paths[3].clone(),
+                paths[4].clone(),
+            ]
+        );
+        assert_eq!(WorkerThread::order_files(files, ComputeOrder::Added), paths);
+    }
+
+    #[test]
+    fn test_shutdown_flushes_pending_saves() {
+        let temp = TempDir::new().unwrap();
+        let pool =
+            HashCachePool::new(&temp.path().join("test.db"), CacheConfig::default()).unwrap();
+        let cache = Arc::new(Mutex::new(pool));
+        let (_worker, worker_tx, _ui_rx) = WorkerThread::spawn(cache.clone());
+
+        let paths: Vec<PathBuf> = (0..5)
+            .map(|i| {
+                let path = temp.path().join(format!("file{}.bin", i));
+                fs::write(&path, format!("content {}", i)).unwrap();
+                path
+            })
+            .collect();
+        let entries: Vec<CacheEntry> = paths
+            .iter()
+            .map(|path| CacheEntry::new(path.clone(), 9, 1, 1, placeholder_hashes()))
+            .collect();
+
+        // 模拟退出：发送待保存条目后立即关闭
+        worker_tx.send(WorkerMessage::SaveCache(entries)).unwrap();
+        assert!(WorkerThread::shutdown(
+            &worker_tx,
+            Duration::from_secs(10),
+            VacuumSchedule::OnExit
+        ));
+        WorkerThread::wait_for_exit_vacuum();
+
+        let guard = cache.lock().unwrap();
+        let path_refs: Vec<&Path> = paths.iter().map(PathBuf::as_path).collect();
+        let saved = guard.get_by_paths_batch(&path_refs).unwrap();
+        for path in &paths {
+            assert!(
+                matches!(saved.get(path), Some(Some(_))),
+                "{}",
+                path.display()
+            );
+        }
+    }
+
+    #[test]
+    fn test_xxh3_only_mode_leaves_other_hashes_blank() {
+        let temp = TempDir::new().unwrap();
+        let config = CacheConfig {
+            xxh3_only_mode: true,
+            ..CacheConfig::default()
+        };
+        let pool = HashCachePool::new(&temp.path().join("test.db"), config).unwrap();
+        let cache = Arc::new(Mutex::new(pool));
+        let (_worker, worker_tx, ui_rx) = WorkerThread::spawn(cache);
+
+        let path = temp.path().join("data.bin");
+        fs::write(&path, vec![7u8; 200 * 1024]).unwrap();
+        worker_tx
+            .send(WorkerMessage::Compute {
+                batch_id: 1,
+                files: sized(std::slice::from_ref(&path)),
+                force: false,
+            })
+            .unwrap();
+
+        let mut completed = None;
+        while let Ok(msg) = ui_rx.recv_timeout(Duration::from_secs(10)) {
+            match msg {
+                UiMessage::FileCompleted {
+                    crc32,
+                    md5,
+                    sha1,
+                    xxhash3,
+                    ..
+                } => completed = Some((crc32, md5, sha1, xxhash3)),
+                UiMessage::AllCompleted { .. } => break,
+                _ => {}
+            }
+        }
+
+        let (crc32, md5, sha1, xxhash3) = completed.expect("缺少 FileCompleted");
+        assert!(crc32.is_empty() && md5.is_empty() && sha1.is_empty());
+        assert_eq!(xxhash3.len(), 32);
+    }
+
+    #[test]
+    fn test_serial_compute_matches_parallel() {
+        let temp = TempDir::new().unwrap();
+        let files: Vec<PathBuf> = (0..6u8)
+            .map(|i| {
+                let path = temp.path().join(format!("file{i}.bin"));
+                fs::write(&path, vec![i; 64 * 1024 + usize::from(i)]).unwrap();
+                path
+            })
+            .collect();
+
+        // 返回各文件的哈希与消息顺序；各模式使用独立的缓存，都从头计算
+        let run = |serial_compute: bool| {
+            let config = CacheConfig {
+                serial_compute,
+                ..CacheConfig::default()
+            };
+            let db = temp.path().join(format!("serial_{serial_compute}.db"));
+            let cache = Arc::new(Mutex::new(HashCachePool::new(&db, config).unwrap()));
+            let (_worker, worker_tx, ui_rx) = WorkerThread::spawn(cache);
+            worker_tx
+                .send(WorkerMessage::Compute {
+                    batch_id: 1,
+                    files: sized(&files),
+                    force: false,
+                })
+                .unwrap();
+
+            let mut hashes = HashMap::new();
+            let mut events = Vec::new();
+            while let Ok(msg) = ui_rx.recv_timeout(Duration::from_secs(10)) {
+                match msg {
+                    UiMessage::FileStarted { path, .. } => events.push(("started", path)),
+                    UiMessage::FileCompleted {
+                        path,
+                        md5,
+                        sha1,
+                        xxhash3,
+                        from_cache,
+                        ..
+                    } => {
+                        assert!(!from_cache);
+                        events.push(("completed", path.clone()));
+                        hashes.insert(path, (md5, sha1, xxhash3));
+                    }
+                    UiMessage::AllCompleted { .. } => break,
+                    _ => {}
+                }
+            }
+            (hashes, events)
+        };
+
+        let (serial_hashes, serial_events) = run(true);
+        let (parallel_hashes, parallel_events) = run(false);
+        assert_eq!(serial_hashes.len(), files.len());
+        assert_eq!(serial_hashes, parallel_hashes);
+        assert_eq!(serial_events.len(), parallel_events.len());
+
+        // 串行模式按队列顺序逐个开始并完成
+        let expected: Vec<(&str, PathBuf)> = files
+            .iter()
+            .flat_map(|path| [("started", path.clone()), ("completed", path.clone())])
+            .collect();
+        assert_eq!(serial_events, expected);
+    }
+
+    #[test]
+    fn test_rehash_with_overrides_matches_default() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("data.bin");
+        let data: Vec<u8> = (0..1024 * 1024).map(|i| (i % 251) as u8).collect();
+        fs::write(&path, &data).unwrap();
+        let pool =
+            HashCachePool::new(&temp.path().join("test.db"), CacheConfig::default()).unwrap();
+        let cache = Arc::new(Mutex::new(pool));
+        let enabled = CacheConfig::default().enabled_hashes;
+        let expected = compute_file_hash(
+            &path,
+            None,
+            256 * 1024,
+            4 * 1024 * 1024,
+            None,
+            false,
+            enabled,
+        )
+        .unwrap();
+
+        let params = RehashParams {
+            path: path.clone(),
+            buffer_size: 64 * 1024,
+            mmap_chunk_size: 1024 * 1024,
+            force_buffered: true,
+        };
+        let report = WorkerThread::rehash(params.clone(), &cache).unwrap();
+        assert_eq!(report.params, params);
+        assert_eq!(report.strategy, IoStrategy::Buffered);
+        assert_eq!(report.file_size, data.len() as u64);
+        assert_eq!(report.hashes, expected);
+    }
+
+    #[test]
+    fn test_directory_in_batch_fails_with_reason() {
+        let temp = TempDir::new().unwrap();
+        let dir = temp.path().join("became_dir");
+        fs::create_dir(&dir).unwrap();
+        let pool =
+            HashCachePool::new(&temp.path().join("test.db"), CacheConfig::default()).unwrap();
+        let (_worker, worker_tx, ui_rx) = WorkerThread::spawn(Arc::new(Mutex::new(pool)));
+        worker_tx
+            .send(WorkerMessage::Compute {
+                batch_id: 1,
+                files: sized(std::slice::from_ref(&dir)),
+                force: false,
+            })
+            .unwrap();
+
+        let mut failure = None;
+        while let Ok(msg) = ui_rx.recv_timeout(Duration::from_secs(10)) {
+            match msg {
+                UiMessage::FileFailed { path, error, .. } => failure = Some((path, error)),
+                UiMessage::FileCompleted { .. } => panic!("目录不应计算出哈希"),
+                UiMessage::AllCompleted { .. } => break,
+                _ => {}
+            }
+        }
+        assert_eq!(failure, Some((dir, "不是文件".to_string())));
+    }
+
+    #[test]
+    fn test_notes_load_without_cache_lock() {
+        let temp = TempDir::new().unwrap();
+        let paths: Vec<PathBuf> = ["a.txt", "b.txt", "c.txt"]
+            .iter()
+            .map(|name| temp.path().join(name))
+            .collect();
+        let pool =
+            HashCachePool::new(&temp.path().join("test.db"), CacheConfig::default()).unwrap();
+        for (path, note) in paths.iter().zip(["甲", "乙", ""]) {
+            fs::write(path, note).unwrap();
+            pool.save_note(path, note).unwrap();
+        }
+        let cache = Arc::new(Mutex::new(pool));
+        let (_worker, worker_tx, ui_rx) = WorkerThread::spawn(cache.clone());
+
+        // 计算线程长时间占用缓存锁时，备注仍能读取
+        let _guard = cache.lock().unwrap();
+        for path in &paths {
+            worker_tx
+                .send(WorkerMessage::LoadNotes(vec![path.clone()]))
+                .unwrap();
+        }
+        let mut notes = HashMap::new();
+        while notes.len() < 2 {
+            match ui_rx.recv_timeout(Duration::from_secs(10)) {
+                Ok(UiMessage::NotesLoaded(loaded)) => notes.extend(loaded),
+                Ok(_) => {}
+                Err(e) => panic!("未收到备注: {e}"),
+            }
+        }
+        assert_eq!(notes[&paths[0]], "甲");
+        assert_eq!(notes[&paths[1]], "乙");
+        assert!(!notes.contains_key(&paths[2]));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_resume_skips_block_devices_unless_enabled() {
+        let temp = TempDir::new().unwrap();
+        let file = temp.path().join("a.bin");
+        fs::write(&file, b"abc").unwrap();
+        // 环境中不一定有块设备，没有时只检查普通文件
+        let device = fs::read_dir("/dev")
+            .into_iter()
+            .flatten()
+            .flatten()
+            .map(|entry| entry.path())
+            .find(|path| is_block_device(path));
+        let mut paths = vec![file.clone(), temp.path().join("gone.bin")];
+        paths.extend(device.clone());
+
+        let found = WorkerThread::resume_batch(paths.clone(), false);
+        let found: Vec<&PathBuf> = found.iter().map(|found| &found.path).collect();
+        assert_eq!(found, [&file]);
+
+        if let Some(device) = device.filter(|device| content_len(device).is_ok()) {
+            let found = WorkerThread::resume_batch(paths, true);
+            assert!(found.iter().any(|found| found.path == device));
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_fifo_fails_without_blocking() {
+        use std::os::unix::ffi::OsStrExt;
+
+        let temp = TempDir::new().unwrap();
+        let fifo = temp.path().join("pipe");
+        let c_path = std::ffi::CString::new(fifo.as_os_str().as_bytes()).unwrap();
+        assert_eq!(unsafe { libc::mkfifo(c_path.as_ptr(), 0o600) }, 0);
+        let pool =
+            HashCachePool::new(&temp.path().join("test.db"), CacheConfig::default()).unwrap();
+        let (_worker, worker_tx, ui_rx) = WorkerThread::spawn(Arc::new(Mutex::new(pool)));
+        worker_tx
+            .send(WorkerMessage::Compute {
+                batch_id: 1,
+                files: sized(std::slice::from_ref(&fifo)),
+                force: false,
+            })
+            .unwrap();
+
+        let mut failure = None;
+        while let Ok(msg) = ui_rx.recv_timeout(Duration::from_secs(10)) {
+            match msg {
+                UiMessage::FileFailed { path, error, .. } => failure = Some((path, error)),
+                UiMessage::FileCompleted { .. } => panic!("FIFO 不应计算出哈希"),
+                UiMessage::AllCompleted { .. } => break,
+                _ => {}
+            }
+        }
+        assert_eq!(
+            failure,
+            Some((fifo.clone(), "不支持的文件类型".to_string()))
+        );
+
+        // 对比、高级重算与分块哈希同样不打开 FIFO
+        let file = temp.path().join("data.bin");
+        fs::write(&file, b"data").unwrap();
+        let pool =
+            HashCachePool::new(&temp.path().join("direct.db"), CacheConfig::default()).unwrap();
+        let cache = Arc::new(Mutex::new(pool));
+        assert!(WorkerThread::compare_files([file, fifo.clone()], &cache).is_err());
+        let params = RehashParams {
+            path: fifo.clone(),
+            buffer_size: 64 * 1024,
+            mmap_chunk_size: 1024 * 1024,
+            force_buffered: false,
+        };
+        assert!(WorkerThread::rehash(params, &cache).is_err());
+        assert!(compute_chunk_hashes(&fifo, 1024, None, &AtomicBool::new(false)).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_non_utf8_file_name_is_hashed_without_cache() {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+
+        let temp = TempDir::new().unwrap();
+        // Latin-1 编码的 café.bin
+        let legacy = temp.path().join(OsStr::from_bytes(b"caf\xe9.bin"));
+        let utf8 = temp.path().join("cafe.bin");
+        for path in [&legacy, &utf8] {
+            fs::write(path, b"same content").unwrap();
+        }
+        let pool =
+            HashCachePool::new(&temp.path().join("test.db"), CacheConfig::default()).unwrap();
+        let cache = Arc::new(Mutex::new(pool));
+        let (_worker, worker_tx, ui_rx) = WorkerThread::spawn(cache.clone());
+
+        let run_batch = |batch_id| {
+            worker_tx
+                .send(WorkerMessage::Compute {
+                    batch_id,
+                    files: sized(&[legacy.clone(), utf8.clone()]),
+                    force: false,
+                })
+                .unwrap();
+            let mut completed = HashMap::new();
+            while let Ok(msg) = ui_rx.recv_timeout(Duration::from_secs(10)) {
+                match msg {
+                    UiMessage::FileCompleted {
+                        path,
+                        xxhash3,
+                        crc32,
+                        md5,
+                        sha1,
+                        modified_time,
+                        file_size,
+                        from_cache,
+                        ..
+                    } => {
+                        let hashes = FileHashes {
+                            crc32,
+                            md5,
+                            sha1,
+                            xxhash3,
+                            ..FileHashes::default()
+                        };
+                        let entry =
+                            CacheEntry::new(path.clone(), file_size, modified_time, 1, hashes);
+                        completed.insert(path, (entry, from_cache));
+                    }
+                    UiMessage::FileFailed { path, error, .. } => {
+                        panic!("{} 计算失败: {}", path.display(), error)
+                    }
+                    UiMessage::AllCompleted { .. } => break,
+                    _ => {}
+                }
+            }
+            completed
+        };
+
+        let first = run_batch(1);
+        assert_eq!(first.len(), 2);
+        assert_eq!(first[&legacy].0.xxhash3, first[&utf8].0.xxhash3);
+        assert!(!first[&legacy].1 && !first[&utf8].1);
+
+        // 只有 UTF-8 路径写入缓存，同一批的保存不受影响
+        let entries: Vec<CacheEntry> = first.values().map(|(entry, _)| entry.clone()).collect();
+        {
+            let guard = cache.lock().unwrap();
+            assert_eq!(guard.save_entries_batch(&entries).unwrap(), 1);
+            guard.invalidate_entry(&legacy).unwrap();
+        }
+
+        let second = run_batch(2);
+        assert!(second[&utf8].1, "UTF-8 路径应命中缓存");
+        assert!(!second[&legacy].1);
+        assert_eq!(second[&legacy].0.xxhash3, first[&legacy].0.xxhash3);
+    }
+
+    #[test]
+    fn test_small_channel_does_not_stall_slow_ui() {
+        let temp = TempDir::new().unwrap();
+        let files: Vec<PathBuf> = (0..300)
+            .map(|i| {
+                let path = temp.path().join(format!("{}.txt", i));
+                fs::write(&path, i.to_string()).unwrap();
+                path
+            })
+            .collect();
+        let config = CacheConfig {
+            ui_channel_capacity: MIN_UI_CHANNEL_CAPACITY,
+            ..CacheConfig::default()
+        };
+        let pool = HashCachePool::new(&temp.path().join("test.db"), config).unwrap();
+        let (_worker, worker_tx, ui_rx) = WorkerThread::spawn(Arc::new(Mutex::new(pool)));
+        worker_tx
+            .send(WorkerMessage::Compute {
+                batch_id: 1,
+                files: sized(&files),
+                force: false,
+            })
+            .unwrap();
+
+        // 模拟处理缓慢的界面：通道频繁写满，进度消息可被丢弃，完成消息不能丢
+        let mut completed = 0;
+        loop {
+            match ui_rx
+                .recv_timeout(Duration::from_secs(30))
+                .expect("工作线程停滞")
+            {
+                UiMessage::FileCompleted { .. } => completed += 1,
+                UiMessage::AllCompleted { .. } => break,
+                _ => {}
+            }
+            thread::sleep(Duration::from_micros(200));
+        }
+        assert_eq!(completed, 300);
+    }
+
+    /// 10 万个小文件的批次在不同通道容量下的吞吐量：
+    /// `cargo test --release tiny_file_throughput -- --ignored --nocapture`
+    #[test]
+    #[ignore = "耗时一两分钟，需手动运行"]
+    fn test_tiny_file_throughput_by_channel_capacity() {
+        const FILES: u32 = 100_000;
+        let temp = TempDir::new().unwrap();
+        let files: Vec<PathBuf> = (0..FILES)
+            .map(|i| {
+                let path = temp.path().join(format!("{i}.txt"));
+                fs::write(&path, i.to_string()).unwrap();
+                path
+            })
+            .collect();
+
+        let run = |capacity: usize| {
+            let config = CacheConfig {
+                ui_channel_capacity: capacity,
+                ..CacheConfig::default()
+            };
+            let db = temp.path().join(format!("{capacity}.db"));
+            let pool = HashCachePool::new(&db, config).unwrap();
+            let (_worker, worker_tx, ui_rx) = WorkerThread::spawn(Arc::new(Mutex::new(pool)));
+            let start = std::time::Instant::now();
+            worker_tx
+                .send(WorkerMessage::Compute {
+                    batch_id: 1,
+                    files: sized(&files),
+                    force: false,
+                })
+                .unwrap();
+
+            // 模拟 60 帧/秒的界面：每帧取完通道中已有的消息，其余时间不取
+            let mut completed = 0;
+            'frames: loop {
+                while let Ok(msg) = ui_rx.try_recv() {
+                    match msg {
+                        UiMessage::FileCompleted { .. } => completed += 1,
+                        UiMessage::AllCompleted { .. } => break 'frames,
+                        _ => {}
+                    }
+                }
+                thread::sleep(Duration::from_millis(16));
+            }
+            assert_eq!(completed, FILES);
+            let elapsed = start.elapsed();
+            eprintln!(
+                "通道容量 {capacity}: {:.1}s，{:.0} 个文件/秒",
+                elapsed.as_secs_f64(),
+                f64::from(FILES) / elapsed.as_secs_f64()
+            );
+            elapsed
+        };
+
+        let smallest = run(MIN_UI_CHANNEL_CAPACITY);
+        let default = run(CacheConfig::default().ui_channel_capacity);
+        let largest = run(4096);
+        // 最小容量下每帧只能完成约 32 个文件，计算被限制在界面的速度
+        assert!(default < smallest);
+        assert!(largest < smallest);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlink_added_directly_follows_policy() {
+        use crate::cache::SymlinkPolicy;
+        use crate::engine::{format_hash_results, link_target_bytes};
+        use crate::hash::FileHasher;
+
+        let temp = TempDir::new().unwrap();
+        let target = temp.path().join("target.bin");
+        fs::write(&target, vec![3u8; 200 * 1024]).unwrap();
+        let link = temp.path().join("link.bin");
+        std::os::unix::fs::symlink(&target, &link).unwrap();
+        let dangling = temp.path().join("dangling.bin");
+        std::os::unix::fs::symlink(temp.path().join("missing.bin"), &dangling).unwrap();
+
+        // 返回每个文件的 (XXH3, 大小)，失败的文件为 None
+        let compute = |policy: SymlinkPolicy, files: Vec<PathBuf>| {
+            let config = CacheConfig {
+                symlink_policy: policy,
+                ..CacheConfig::default()
+            };
+            let db = temp.path().join(format!("{}.db", policy));
+            let pool = HashCachePool::new(&db, config).unwrap();
+            let (_worker, worker_tx, ui_rx) = WorkerThread::spawn(Arc::new(Mutex::new(pool)));
+            worker_tx
+                .send(WorkerMessage::Compute {
+                    batch_id: 1,
+                    files: sized(&files),
+                    force: false,
+                })
+                .unwrap();
+            let mut results = HashMap::new();
+            while let Ok(msg) = ui_rx.recv_timeout(Duration::from_secs(10)) {
+                match msg {
+                    UiMessage::FileCompleted {
+                        path,
+                        xxhash3,
+                        file_size,
+                        ..
+                    } => {
+                        results.insert(path, Some((xxhash3, file_size)));
+                    }
+                    UiMessage::FileFailed { path, .. } | UiMessage::FileMissing { path, .. } => {
+                        results.insert(path, None);
+                    }
+                    UiMessage::AllCompleted { .. } => break,
+                    _ => {}
+                }
+            }
+            results
+        };
+
+        // 跟随（默认）：链接与目标结果相同，悬空链接无法读取
+        let followed = compute(
+            SymlinkPolicy::Follow,
+            vec![target.clone(), link.clone(), dangling.clone()],
+        );
+        assert_eq!(followed[&link], followed[&target]);
+        assert_eq!(followed[&link].as_ref().unwrap().1, 200 * 1024);
+        assert_eq!(followed[&dangling], None);
+
+        // 链接本身：对链接中保存的目标路径计算，悬空链接同样可以计算
+        let linked = compute(SymlinkPolicy::Link, vec![link.clone(), dangling.clone()]);
+        for path in [&link, &dangling] {
+            let stored = link_target_bytes(path).unwrap();
+            let mut hasher = FileHasher::new();
+            hasher.update(&stored);
+            let expected = format_hash_results(&hasher.finalize().unwrap()).xxhash3;
+            assert_eq!(linked[path], Some((expected, stored.len() as u64)));
+        }
+        assert_ne!(linked[&link], followed[&target]);
+    }
+
+    #[test]
+    fn test_sync_mode_only_hashes_changed_files() {
+        let temp = TempDir::new().unwrap();
+        let config = CacheConfig {
+            sync_mode: true,
+            ..CacheConfig::default()
+        };
+        let pool = HashCachePool::new(&temp.path().join("test.db"), config).unwrap();
+        let cache = Arc::new(Mutex::new(pool));
+
+        let paths: Vec<PathBuf> = ["a.bin", "b.bin", "c.bin", "changed.bin"]
+            .iter()
+            .map(|name| {
+                let path = temp.path().join(name);
+                fs::write(&path, vec![5u8; 4096]).unwrap();
+                path
+            })
+            .collect();
+        // 缓存中是与内容不符的占位哈希：未变更的文件若被重新读取就会得到不同结果
+        let entries: Vec<CacheEntry> = paths
+            .iter()
+            .map(|path| {
+                let modified_time = get_file_modified_time(path).unwrap();
+                CacheEntry::new(path.clone(), 4096, modified_time, 1, placeholder_hashes())
+            })
+            .collect();
+        cache.lock().unwrap().save_entries_batch(&entries).unwrap();
+        fs::write(&paths[3], b"new content").unwrap();
+
+        let (_worker, worker_tx, ui_rx) = WorkerThread::spawn(cache);
+        worker_tx
+            .send(WorkerMessage::Compute {
+                batch_id: 1,
+                files: sized(&paths),
+                force: false,
+            })
+            .unwrap();
+
+        let mut completed = HashMap::new();
+        while let Ok(msg) = ui_rx.recv_timeout(Duration::from_secs(10)) {
+            match msg {
+                UiMessage::FileCompleted {
+                    path,
+                    xxhash3,
+                    from_cache,
+                    unchanged,
+                    computed_at,
+                    ..
+                } => {
+                    completed.insert(path, (xxhash3, from_cache, unchanged, computed_at));
+                }
+                UiMessage::AllCompleted { .. } => break,
+                _ => {}
+            }
+        }
+
+        assert_eq!(completed.len(), paths.len());
+        for path in &paths[..3] {
+            // 命中缓存时沿用条目写入缓存的时间
+            assert_eq!(completed[path], ("0".repeat(32), true, true, 1));
+        }
+        let (xxhash3, from_cache, unchanged, computed_at) = &completed[&paths[3]];
+        assert_ne!(xxhash3, &"0".repeat(32));
+        assert!(!from_cache && !unchanged);
+        assert!(*computed_at > 1);
+    }
+
+    #[test]
+    fn test_cache_validation_levels() {
+        let temp = TempDir::new().unwrap();
+        let pool =
+            HashCachePool::new(&temp.path().join("test.db"), CacheConfig::default()).unwrap();
+        let cache = Arc::new(Mutex::new(pool));
+        let path = temp.path().join("archive.bin");
+        fs::write(&path, vec![7u8; 8192]).unwrap();
+
+        let (hashes, file_size) =
+            WorkerThread::compute_uncached(&path, &CacheConfig::default(), None).unwrap();
+        let modified_time = get_file_modified_time(&path).unwrap();
+        let entry = CacheEntry::new(path.clone(), file_size, modified_time, 1, hashes.clone());
+        // 缓存中的 MD5 被篡改，XXH3 仍然一致
+        let forged = CacheEntry {
+            md5: "0".repeat(32),
+            ..entry.clone()
+        };
+        let lookup = |level, entry: &CacheEntry| {
+            let config = CacheConfig {
+                cache_validation: level,
+                ..CacheConfig::default()
+            };
+            WorkerThread::lookup_cache(
+                &path,
+                entry,
+                file_size,
+                Some(modified_time),
+                &config,
+                None,
+                &cache,
+            )
+            .unwrap()
+        };
+        let trusts =
+            |level, entry: &CacheEntry| matches!(lookup(level, entry), CacheLookup::Hit(_));
+
+        for level in CacheValidation::ALL {
+            assert!(trusts(level, &entry), "{level:?}");
+        }
+        assert!(trusts(CacheValidation::Fast, &forged));
+        assert!(trusts(CacheValidation::Normal, &forged));
+        // 严格级别返回本次计算的哈希，不必再读取一遍
+        assert_eq!(
+            lookup(CacheValidation::Paranoid, &forged),
+            CacheLookup::Changed(hashes.clone())
+        );
+
+        // 同样大小的内容被替换，并恢复原来的修改时间
+        let mtime = fs::metadata(&path).unwrap().modified().unwrap();
+        fs::write(&path, vec![8u8; 8192]).unwrap();
+        fs::File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(mtime)
+            .unwrap();
+        assert!(trusts(CacheValidation::Fast, &entry));
+        assert!(!trusts(CacheValidation::Normal, &entry));
+        assert!(!trusts(CacheValidation::Paranoid, &entry));
+    }
+
+    #[test]
+    fn test_forced_recompute_reports_changed_hash() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("archive.bin");
+        fs::write(&path, vec![7u8; 8192]).unwrap();
+
+        let (hashes, file_size) =
+            WorkerThread::compute_uncached(&path, &CacheConfig::default(), None).unwrap();
+        // 缓存中的 MD5 与 XXH3 与文件内容不符，大小与修改时间一致（模拟静默损坏）
+        let corrupted = FileHashes {
+            md5: "0".repeat(32),
+            xxhash3: "1".repeat(32),
+            ..hashes.clone()
+        };
+        // 强制重新计算，或严格校验级别下的普通计算
+        let (old, new) = forced_or_paranoid_change(&path, file_size, &corrupted, &hashes, true);
+        assert_eq!(
+            WorkerThread::changed_algorithms(&old, &new),
+            [HashAlgorithm::Md5, HashAlgorithm::Xxh3]
+        );
+        forced_or_paranoid_change(&path, file_size, &corrupted, &hashes, false);
+
+        // 新启用的算法在缓存中为空，不算变更
+        let without_sha256 = FileHashes {
+            sha256: String::new(),
+            ..hashes.clone()
+        };
+        let with_sha256 = FileHashes {
+            sha256: "2".repeat(64),
+            ..hashes
+        };
+        assert!(WorkerThread::changed_algorithms(&without_sha256, &with_sha256).is_empty());
+    }
+
+    /// 写入损坏的缓存条目后计算，返回 `HashChanged` 中的旧值与新值
+    fn forced_or_paranoid_change(
+        path: &Path,
+        file_size: u64,
+        corrupted: &FileHashes,
+        hashes: &FileHashes,
+        force: bool,
+    ) -> (FileHashes, FileHashes) {
+        let temp = TempDir::new().unwrap();
+        let config = CacheConfig {
+            cache_validation: if force {
+                CacheConfig::default().cache_validation
+            } else {
+                CacheValidation::Paranoid
+            },
+            ..CacheConfig::default()
+        };
+        let pool = HashCachePool::new(&temp.path().join("test.db"), config).unwrap();
+        let modified_time = get_file_modified_time(path).unwrap();
+        pool.save_entries_batch(&[CacheEntry::new(
+            path.to_path_buf(),
+            file_size,
+            modified_time,
+            1,
+            corrupted.clone(),
+        )])
+        .unwrap();
+        let (_worker, worker_tx, ui_rx) = WorkerThread::spawn(Arc::new(Mutex::new(pool)));
+        worker_tx
+            .send(WorkerMessage::Compute {
+                batch_id: 1,
+                files: sized(&[path.to_path_buf()]),
+                force,
+            })
+            .unwrap();
+
+        let mut changed = None;
+        let mut completed = None;
+        while let Ok(msg) = ui_rx.recv_timeout(Duration::from_secs(10)) {
+            match msg {
+                UiMessage::HashChanged {
+                    path,
+                    old,
+                    new,
+                    metadata_changed,
+                    ..
+                } => changed = Some((path, old, new, metadata_changed)),
+                UiMessage::FileCompleted {
+                    md5, from_cache, ..
+                } => completed = Some((md5, from_cache)),
+                UiMessage::AllCompleted { .. } => break,
+                _ => {}
+            }
+        }
+
+        let (changed_path, old, new, metadata_changed) = changed.expect("缺少 HashChanged");
+        assert_eq!(changed_path, path);
+        assert_eq!(&old, corrupted);
+        assert_eq!(&new, hashes);
+        assert!(!metadata_changed);
+        // 仍然报告新计算的结果
+        assert_eq!(completed, Some((hashes.md5.clone(), false)));
+        (old, new)
+    }
+
+    #[test]
+    fn test_deleted_file_reported_as_missing() {
+        use crate::progress::ProgressTracker;
+
+        let temp = TempDir::new().unwrap();
+        let pool =
+            HashCachePool::new(&temp.path().join("test.db"), CacheConfig::default()).unwrap();
+        let cache = Arc::new(Mutex::new(pool));
+
+        let kept = temp.path().join("kept.bin");
+        let gone = temp.path().join("gone.bin");
+        fs::write(&kept, vec![1u8; 4096]).unwrap();
+        fs::write(&gone, vec![2u8; 8192]).unwrap();
+        // 按扫描时记录的大小建立进度，随后在计算前删除其中一个文件
+        let tracker = ProgressTracker::new();
+        tracker.set_total(4096 + 8192);
+        tracker.set_total_files(2);
+        fs::remove_file(&gone).unwrap();
+
+        let (_worker, worker_tx, ui_rx) = WorkerThread::spawn(cache);
+        worker_tx
+            .send(WorkerMessage::Compute {
+                batch_id: 1,
+                files: sized(&[kept.clone(), gone.clone()]),
+                force: false,
+            })
+            .unwrap();
+
+        let (mut missing, mut failed) = (Vec::new(), Vec::new());
+        while let Ok(msg) = ui_rx.recv_timeout(Duration::from_secs(10)) {
+            match msg {
+                UiMessage::FileStarted { path, .. } => {
+                    let size = if path == kept { 4096 } else { 8192 };
+                    tracker.start_file(path, size);
+                }
+                UiMessage::FileCompleted { path, .. } => tracker.complete_file(&path),
+                UiMessage::FileMissing { path, .. } => {
+                    tracker.remove_file(&path, 8192);
+                    missing.push(path);
+                }
+                UiMessage::FileFailed { path, .. } => failed.push(path),
+                UiMessage::AllCompleted { .. } => break,
+                _ => {}
+            }
+        }
+
+        assert_eq!(missing, [gone]);
+        assert!(failed.is_empty());
+        assert_eq!(tracker.get_global_progress(), 1.0);
+    }
+
+    #[test]
+    fn test_compare_files() {
+        let temp = TempDir::new().unwrap();
+        let pool =
+            HashCachePool::new(&temp.path().join("test.db"), CacheConfig::default()).unwrap();
+        let cache = Arc::new(Mutex::new(pool));
+
+        let write = |name: &str, content: &[u8]| {
+            let path = temp.path().join(name);
+            fs::write(&path, content).unwrap();
+            path
+        };
+        let a = write("a.bin", b"same content");
+        let b = write("b.bin", b"same content");
+        let c = write("c.bin", b"SAME content");
+        let d = write("d.bin", b"short");
+
+        let same = WorkerThread::compare_files([a.clone(), b], &cache).unwrap();
+        assert!(same.identical());
+
+        let different = WorkerThread::compare_files([a.clone(), c], &cache).unwrap();
+        assert!(!different.identical());
+        assert!(different.hashes.is_some());
+
+        // 大小不同时不计算哈希
+        let size_mismatch = WorkerThread::compare_files([a, d], &cache).unwrap();
+        assert!(!size_mismatch.identical());
+        assert!(size_mismatch.hashes.is_none());
+    }
+
+    #[test]
+    fn test_audit_cache_flags_silent_corruption() {
+        let temp = TempDir::new().unwrap();
+        let pool =
+            HashCachePool::new(&temp.path().join("test.db"), CacheConfig::default()).unwrap();
+        let cache = Arc::new(Mutex::new(pool));
+
+        let root = temp.path().join("audit");
+        // 名称以 root 为前缀的同级目录不应被包含
+        let sibling = temp.path().join("audit_other");
+        fs::create_dir_all(root.join("sub")).unwrap();
+        fs::create_dir_all(&sibling).unwrap();
+
+        let paths: Vec<PathBuf> = [
+            root.join("ok.bin"),
+            root.join("sub").join("rot.bin"),
+            root.join("edited.bin"),
+            root.join("gone.bin"),
+            sibling.join("outside.bin"),
+        ]
+        .into_iter()
+        .map(|path| {
+            fs::write(&path, vec![3u8; 8 * 1024]).unwrap();
+            path
+        })
+        .collect();
+        let entries: Vec<CacheEntry> = paths
+            .iter()
+            .map(|path| {
+                let hashes = FileHashes {
+                    crc32: format!("{:08x}", 1),
+                    xxhash3: compute_xxhash3_only(path, None, 64 * 1024, 4 * 1024 * 1024, false)
+                        .unwrap()
+                        .0,
+                    ..FileHashes::default()
+                };
+                let modified_time = get_file_modified_time(path).unwrap();
+                CacheEntry::new(path.clone(), 8 * 1024, modified_time, 1, hashes)
+            })
+            .collect();
+        cache.lock().unwrap().save_entries_batch(&entries).unwrap();
+
+        // 静默损坏：内容变化但大小与修改时间不变
+        let rot = &paths[1];
+        let modified = fs::metadata(rot).unwrap().modified().unwrap();
+        fs::write(rot, vec![4u8; 8 * 1024]).unwrap();
+        fs::File::options()
+            .write(true)
+            .open(rot)
+            .unwrap()
+            .set_modified(modified)
+            .unwrap();
+        // 正常修改：大小变化
+        fs::write(&paths[2], b"edited").unwrap();
+        fs::remove_file(&paths[3]).unwrap();
+
+        let report = WorkerThread::audit_cache(&root, &cache).unwrap();
+        assert_eq!((report.checked, report.missing), (3, 1));
+        assert_eq!(report.findings.len(), 2);
+
+        let finding = |name: &str| {
+            report
+                .findings
+                .iter()
+                .find(|(path, _)| path.ends_with(name))
+                .map(|(_, finding)| finding.clone())
+        };
+        assert!(matches!(
+            finding("rot.bin"),
+            Some(AuditFinding::Corrupted { .. })
+        ));
+        assert_eq!(finding("edited.bin"), Some(AuditFinding::Changed));
+        assert_eq!(report.suspicious().count(), 1);
     }
 }
@@ -0,0 +1,234 @@
+// 将哈希写入文件扩展属性（可选特性 xattr-export，仅 Linux/macOS）
+//
+// 每种哈希写入一个 `user.<算法>` 属性（如 `user.sha256`），供归档流程中的
+// 其他工具直接读取。文件系统不支持扩展属性（FAT、部分网络共享等）时跳过并汇总报告。
+// 计算完成后自动写入的是刚算出的结果，直接覆盖；手动写入时已有的属性可能来自更早的
+// 计算或其他工具，值相同的不再重写，不同的保留原值并在报告中列出。
+
+use std::path::PathBuf;
+
+use crate::i18n::{Text, tf};
+
+/// 待写入的文件及其哈希：(算法名, 十六进制值)，空值不写入
+#[derive(Debug, Clone)]
+pub struct XattrTarget {
+    pub path: PathBuf,
+    // 未启用特性时只有占位实现，不读取哈希
+    #[cfg_attr(not(all(unix, feature = "xattr-export")), allow(dead_code))]
+    pub hashes: Vec<(&'static str, String)>,
+}
+
+/// 单个文件的写入结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+// 未启用特性时只有占位实现，不产生结果
+#[cfg_attr(not(all(unix, feature = "xattr-export")), allow(dead_code))]
+enum WriteOutcome {
+    Written,
+    /// 已有的属性与要写入的值相同
+    Unchanged,
+    /// 已有的属性与要写入的值不同，未覆盖
+    Conflict,
+    /// 所在文件系统不支持扩展属性
+    Unsupported,
+}
+
+/// 批量写入的结果汇总
+#[derive(Debug, Default)]
+pub struct XattrReport {
+    pub written: usize,
+    pub unchanged: usize,
+    /// 已有不同的值，未覆盖
+    pub conflicts: Vec<PathBuf>,
+    /// 所在文件系统不支持扩展属性
+    pub unsupported: Vec<PathBuf>,
+    pub failed: Vec<(PathBuf, String)>,
+}
+
+impl XattrReport {
+    pub fn summary(&self) -> String {
+        let mut text = tf(Text::XattrsWritten, &[&self.written]);
+        if self.unchanged > 0 {
+            text.push_str(&tf(Text::XattrsUnchanged, &[&self.unchanged]));
+        }
+        if !self.conflicts.is_empty() {
+            text.push_str(&tf(Text::XattrsConflicts, &[&self.conflicts.len()]));
+        }
+        if !self.unsupported.is_empty() {
+            text.push_str(&tf(Text::XattrsUnsupported, &[&self.unsupported.len()]));
+        }
+        if !self.failed.is_empty() {
+            text.push_str(&tf(Text::XattrsFailed, &[&self.failed.len()]));
+        }
+        text
+    }
+}
+
+/// 依次写入所有文件，单个文件失败不影响其余文件
+///
+/// `overwrite` 为 false 时不覆盖已有的不同值。
+pub fn write_all(targets: &[XattrTarget], overwrite: bool) -> XattrReport {
+    let mut report = XattrReport::default();
+    for target in targets {
+        match imp::write_hashes(target, overwrite) {
+            Ok(WriteOutcome::Written) => report.written += 1,
+            Ok(WriteOutcome::Unchanged) => report.unchanged += 1,
+            Ok(WriteOutcome::Conflict) => {
+                log::warn!("扩展属性已有不同的值，未覆盖: {}", target.path.display());
+                report.conflicts.push(target.path.clone());
+            }
+            Ok(WriteOutcome::Unsupported) => report.unsupported.push(target.path.clone()),
+            Err(e) => {
+                log::warn!("写入扩展属性失败 {}: {}", target.path.display(), e);
+                report.failed.push((target.path.clone(), e.to_string()));
+            }
+        }
+    }
+    report
+}
+
+#[cfg(all(unix, feature = "xattr-export"))]
+mod imp {
+    use std::io;
+
+    use super::{WriteOutcome, XattrTarget};
+    use crate::error::{HashError, HashResult};
+
+    pub const SUPPORTED: bool = true;
+
+    /// 写入一个文件的全部哈希
+    pub fn write_hashes(target: &XattrTarget, overwrite: bool) -> HashResult<WriteOutcome> {
+        let values: Vec<(String, String)> = target
+            .hashes
+            .iter()
+            .filter(|(_, value)| !value.is_empty())
+            .map(|(algorithm, value)| (attribute_name(algorithm), value.to_lowercase()))
+            .collect();
+
+        if !overwrite {
+            let mut unchanged = true;
+            for (name, value) in &values {
+                match xattr::get(&target.path, name) {
+                    Ok(Some(existing)) if existing == value.as_bytes() => {}
+                    Ok(Some(_)) => return Ok(WriteOutcome::Conflict),
+                    Ok(None) => unchanged = false,
+                    Err(e) if is_unsupported(&e) => return Ok(WriteOutcome::Unsupported),
+                    Err(e) => return Err(HashError::Io(e, target.path.clone())),
+                }
+            }
+            if unchanged {
+                return Ok(WriteOutcome::Unchanged);
+            }
+        }
+
+        for (name, value) in &values {
+            match xattr::set(&target.path, name, value.as_bytes()) {
+                Ok(()) => {}
+                Err(e) if is_unsupported(&e) => return Ok(WriteOutcome::Unsupported),
+                Err(e) => return Err(HashError::Io(e, target.path.clone())),
+            }
+        }
+        Ok(WriteOutcome::Written)
+    }
+
+    /// 扩展属性名，如 `user.sha256`
+    fn attribute_name(algorithm: &str) -> String {
+        format!("user.{}", algorithm)
+    }
+
+    fn is_unsupported(e: &io::Error) -> bool {
+        e.kind() == io::ErrorKind::Unsupported
+            || matches!(e.raw_os_error(), Some(code) if code == libc::ENOTSUP || code == libc::EOPNOTSUPP)
+    }
+}
+
+#[cfg(not(all(unix, feature = "xattr-export")))]
+mod imp {
+    use super::{WriteOutcome, XattrTarget};
+    use crate::error::{HashError, HashResult};
+
+    pub const SUPPORTED: bool = false;
+
+    /// 未启用 `xattr-export` 特性（或非 Unix 平台）时的占位实现
+    pub fn write_hashes(_target: &XattrTarget, _overwrite: bool) -> HashResult<WriteOutcome> {
+        Err(HashError::SystemResource(
+            "当前构建不支持写入扩展属性".to_string(),
+        ))
+    }
+}
+
+pub use imp::SUPPORTED;
+
+#[cfg(all(test, unix, feature = "xattr-export"))]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::path::Path;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_write_hashes_to_tmpfs() {
+        // /dev/shm 为 tmpfs，较新的内核支持 user.* 扩展属性
+        let dir = if Path::new("/dev/shm").is_dir() {
+            TempDir::new_in("/dev/shm").unwrap()
+        } else {
+            TempDir::new().unwrap()
+        };
+        let path = dir.path().join("archive.tar");
+        fs::write(&path, b"archive").unwrap();
+
+        let target = XattrTarget {
+            path: path.clone(),
+            hashes: vec![
+                ("sha256", "AB".repeat(32)),
+                ("md5", String::new()),
+                ("xxh3", "0123456789abcdef".to_string()),
+            ],
+        };
+        let report = write_all(std::slice::from_ref(&target), true);
+        assert!(report.failed.is_empty(), "{:?}", report.failed);
+
+        if report.unsupported.is_empty() {
+            assert_eq!(report.written, 1);
+            let sha256 = xattr::get(&path, "user.sha256").unwrap().unwrap();
+            assert_eq!(sha256, "ab".repeat(32).into_bytes());
+            assert_eq!(
+                xattr::get(&path, "user.xxh3").unwrap().unwrap(),
+                b"0123456789abcdef"
+            );
+            // 空值不写入
+            assert_eq!(xattr::get(&path, "user.md5").unwrap(), None);
+
+            // 手动写入：相同的值不重写，不同的值不覆盖
+            let report = write_all(std::slice::from_ref(&target), false);
+            assert_eq!((report.written, report.unchanged), (0, 1));
+            let mut changed = target.clone();
+            changed.hashes[0].1 = "cd".repeat(32);
+            let report = write_all(std::slice::from_ref(&changed), false);
+            assert_eq!(report.conflicts, std::slice::from_ref(&path));
+            assert_eq!(
+                xattr::get(&path, "user.sha256").unwrap().unwrap(),
+                "ab".repeat(32).into_bytes()
+            );
+            // 计算完成后自动写入时覆盖
+            let report = write_all(&[changed], true);
+            assert_eq!(report.written, 1);
+            assert_eq!(
+                xattr::get(&path, "user.sha256").unwrap().unwrap(),
+                "cd".repeat(32).into_bytes()
+            );
+        } else {
+            // 不支持的文件系统只计入报告，不视为失败
+            assert_eq!(report.written, 0);
+            assert_eq!(report.unsupported, [path]);
+        }
+
+        let missing = write_all(
+            &[XattrTarget {
+                path: dir.path().join("missing"),
+                hashes: vec![("sha256", "00".repeat(32))],
+            }],
+            false,
+        );
+        assert_eq!(missing.failed.len(), 1);
+    }
+}